@@ -0,0 +1,227 @@
+//! Aider session parsing for `whogitit import-aider`
+//!
+//! Aider applies its edits and commits them itself, so there is nothing to capture
+//! while it runs. Afterwards, `.aider.chat.history.md` tells which prompt led to
+//! which commit:
+//!
+//! ```text
+//! # aider chat started at 2026-03-02 14:05:11
+//!
+//! > Main model: gpt-4o with diff edit format
+//!
+//! #### Add retries to the client
+//!
+//! ...assistant reply and SEARCH/REPLACE blocks...
+//!
+//! > Applied edit to src/client.rs
+//! > Commit 3f9c2a1 feat: Add retries to the client
+//! ```
+//!
+//! User messages are the `#### ` lines; Aider's own output is quoted with `> `.
+//! Commits without a history entry are still recognized by the ` (aider)` suffix
+//! Aider adds to the author or committer name, or its `Co-authored-by: aider (<model>)`
+//! trailer.
+
+/// Default chat history file, at the repository root
+pub const CHAT_HISTORY_FILE: &str = ".aider.chat.history.md";
+
+/// Provider recorded for Aider edits
+pub const AIDER_PROVIDER: &str = "aider";
+
+/// One user message and the edits and commits Aider made for it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AiderTurn {
+    pub prompt: String,
+    /// Model active in the session, if the history names it
+    pub model: Option<String>,
+    /// Files Aider reported editing (`Applied edit to ...`)
+    pub files: Vec<String>,
+    /// Abbreviated hashes of the commits made after the edits
+    pub commits: Vec<String>,
+}
+
+/// Turns with edits from a chat history
+///
+/// Commands (`/add`, `/run`, ...) are not prompts; `/code` and `/architect`
+/// carry one after the command name. Commits Aider makes before applying any
+/// edit (its "dirty files" commits) are not attributed to the turn.
+pub fn parse_chat_history(history: &str) -> Vec<AiderTurn> {
+    let mut turns = Vec::new();
+    let mut model: Option<String> = None;
+    let mut current: Option<AiderTurn> = None;
+    let mut in_prompt = false;
+
+    for line in history.lines() {
+        if line.starts_with("# aider chat started at") {
+            turns.extend(current.take());
+            in_prompt = false;
+            continue;
+        }
+
+        if let Some(text) = line.strip_prefix("####") {
+            // Aider ends each line of a multi-line message with a markdown hard break
+            let text = text.strip_prefix(' ').unwrap_or(text).trim_end();
+            match current.as_mut() {
+                // Multi-line messages repeat the marker on every line
+                Some(turn) if in_prompt => {
+                    turn.prompt.push('\n');
+                    turn.prompt.push_str(text);
+                }
+                _ => {
+                    turns.extend(current.take());
+                    current = prompt_text(text).map(|prompt| AiderTurn {
+                        prompt,
+                        model: model.clone(),
+                        ..Default::default()
+                    });
+                    in_prompt = current.is_some();
+                }
+            }
+            continue;
+        }
+        in_prompt = false;
+
+        let Some(output) = line.strip_prefix('>') else {
+            continue;
+        };
+        let output = output.trim();
+        if let Some(rest) = output
+            .strip_prefix("Main model:")
+            .or_else(|| output.strip_prefix("Model:"))
+        {
+            model = rest.split_whitespace().next().map(str::to_string);
+            if let Some(turn) = current.as_mut() {
+                turn.model = model.clone();
+            }
+        } else if let Some(path) = output.strip_prefix("Applied edit to ") {
+            if let Some(turn) = current.as_mut() {
+                let path = path.trim().to_string();
+                if !turn.files.contains(&path) {
+                    turn.files.push(path);
+                }
+            }
+        } else if let Some(rest) = output.strip_prefix("Commit ") {
+            let hash = rest.split_whitespace().next().unwrap_or_default();
+            let is_hash = hash.len() >= 7 && hash.chars().all(|c| c.is_ascii_hexdigit());
+            if let Some(turn) = current.as_mut().filter(|turn| !turn.files.is_empty()) {
+                if is_hash {
+                    turn.commits.push(hash.to_string());
+                }
+            }
+        }
+    }
+    turns.extend(current);
+
+    turns.retain(|turn| !turn.files.is_empty());
+    turns
+}
+
+/// Prompt of a user message, or `None` for commands without one
+fn prompt_text(message: &str) -> Option<String> {
+    let message = message.trim();
+    if let Some(command) = message.strip_prefix('/') {
+        let (name, rest) = command.split_once(' ').unwrap_or((command, ""));
+        return match name {
+            "code" | "architect" if !rest.trim().is_empty() => Some(rest.trim().to_string()),
+            _ => None,
+        };
+    }
+    (!message.is_empty()).then(|| message.to_string())
+}
+
+/// Model of a commit Aider made, from its trailer or author marker
+///
+/// `Some(None)` when the commit is Aider's but names no model; `None` when it is
+/// not recognizably an Aider commit.
+pub fn commit_marker(message: &str, author: &str, committer: &str) -> Option<Option<String>> {
+    for line in message.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case("co-authored-by") {
+            continue;
+        }
+        if let Some(rest) = value.trim().strip_prefix("aider (") {
+            let model = rest.split(')').next().unwrap_or_default().trim();
+            return Some((!model.is_empty()).then(|| model.to_string()));
+        }
+    }
+    [author, committer]
+        .iter()
+        .any(|name| name.trim_end().ends_with("(aider)"))
+        .then_some(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HISTORY: &str = "
+# aider chat started at 2026-03-02 14:05:11
+
+> Aider v0.82.0
+> Main model: gpt-4o with diff edit format
+> Git repo: .git with 12 files
+
+#### /add src/client.rs
+> Added src/client.rs to the chat
+
+#### Add retries to the client
+#### with exponential backoff
+
+I'll wrap the request in a retry loop.
+
+src/client.rs
+```rust
+<<<<<<< SEARCH
+=======
+>>>>>>> REPLACE
+```
+
+> Applied edit to src/client.rs
+> Commit 3f9c2a1 feat: Add retries to the client
+
+#### What does backoff mean?
+
+It means waiting longer between attempts.
+
+# aider chat started at 2026-03-03 09:00:00
+
+> Commit 1234abc wip: dirty files
+
+#### /code rename fetch to get
+> Applied edit to src/client.rs
+> Applied edit to src/lib.rs
+> Commit 8e7d6c5 refactor: Rename fetch to get
+";
+
+    #[test]
+    fn test_parse_chat_history_turns() {
+        let turns = parse_chat_history(HISTORY);
+        assert_eq!(turns.len(), 2);
+
+        assert_eq!(
+            turns[0].prompt,
+            "Add retries to the client\nwith exponential backoff"
+        );
+        assert_eq!(turns[0].model.as_deref(), Some("gpt-4o"));
+        assert_eq!(turns[0].files, ["src/client.rs"]);
+        assert_eq!(turns[0].commits, ["3f9c2a1"]);
+
+        // The dirty-files commit precedes any edit and is not attributed
+        assert_eq!(turns[1].prompt, "rename fetch to get");
+        assert_eq!(turns[1].files, ["src/client.rs", "src/lib.rs"]);
+        assert_eq!(turns[1].commits, ["8e7d6c5"]);
+    }
+
+    #[test]
+    fn test_commit_marker() {
+        let message = "feat: Add retries\n\nCo-authored-by: aider (gpt-4o) <aider@aider.chat>\n";
+        assert_eq!(
+            commit_marker(message, "Dev", "Dev"),
+            Some(Some("gpt-4o".to_string()))
+        );
+        assert_eq!(commit_marker("fix: typo", "Dev (aider)", "Dev"), Some(None));
+        assert_eq!(commit_marker("fix: typo", "Dev", "Dev"), None);
+    }
+}
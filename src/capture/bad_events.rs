@@ -0,0 +1,160 @@
+//! Malformed hook payloads kept for diagnosis
+//!
+//! When a capture hook receives input that doesn't parse (a truncated pipe, a
+//! payload mangled on the way), the raw bytes are saved under
+//! `.git/whogitit/badevents/` next to a JSON record of what went wrong, so the
+//! failure shows up in `whogitit doctor` instead of vanishing with a serde
+//! error. Payloads are capped at [`MAX_PAYLOAD_BYTES`] and only the newest
+//! [`MAX_BAD_EVENTS`] are kept.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Directory under the git dir
+pub const BAD_EVENTS_DIR: &str = "whogitit/badevents";
+/// Payload bytes saved per event
+pub const MAX_PAYLOAD_BYTES: usize = 256 * 1024;
+/// Events kept; older ones are removed when a new one is recorded
+pub const MAX_BAD_EVENTS: usize = 20;
+
+const RECORD_EXTENSION: &str = "json";
+const PAYLOAD_EXTENSION: &str = "payload";
+
+/// Diagnostics for one malformed payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BadEvent {
+    /// Hook entry point that received the payload (`capture`, `raw-claude-event`)
+    pub source: String,
+    pub at: String,
+    /// Parse error
+    pub error: String,
+    /// Payload size as received, in bytes
+    pub size: usize,
+    /// Whether the saved payload was cut at [`MAX_PAYLOAD_BYTES`]
+    pub truncated: bool,
+    /// Fields recovered from the payload; the edit was captured when this is not empty
+    #[serde(default)]
+    pub salvaged: Vec<String>,
+    /// Saved payload, in the bad events directory
+    pub payload_file: String,
+}
+
+pub fn bad_events_dir(git_dir: &Path) -> PathBuf {
+    git_dir.join(BAD_EVENTS_DIR)
+}
+
+/// Save a payload that failed to parse, returning the path of its record
+pub fn record(
+    git_dir: &Path,
+    source: &str,
+    raw: &[u8],
+    error: &str,
+    salvaged: &[&str],
+) -> Result<PathBuf> {
+    let dir = bad_events_dir(git_dir);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&dir, fs::Permissions::from_mode(0o700));
+    }
+
+    let now = Utc::now();
+    let id = format!(
+        "{}-{}",
+        now.format("%Y%m%dT%H%M%S%.3fZ"),
+        std::process::id()
+    );
+    let payload_file = format!("{}.{}", id, PAYLOAD_EXTENSION);
+    let saved = &raw[..raw.len().min(MAX_PAYLOAD_BYTES)];
+    fs::write(dir.join(&payload_file), saved)
+        .with_context(|| format!("Failed to save payload in {}", dir.display()))?;
+
+    let event = BadEvent {
+        source: source.to_string(),
+        at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        error: error.to_string(),
+        size: raw.len(),
+        truncated: saved.len() < raw.len(),
+        salvaged: salvaged.iter().map(|field| field.to_string()).collect(),
+        payload_file,
+    };
+    let record_path = dir.join(format!("{}.{}", id, RECORD_EXTENSION));
+    fs::write(&record_path, serde_json::to_vec_pretty(&event)?)
+        .with_context(|| format!("Failed to write {}", record_path.display()))?;
+
+    prune(&dir);
+    Ok(record_path)
+}
+
+/// Recorded events, newest first
+pub fn list(git_dir: &Path) -> Vec<BadEvent> {
+    let mut events: Vec<BadEvent> = record_paths(&bad_events_dir(git_dir))
+        .iter()
+        .rev()
+        .filter_map(|path| serde_json::from_slice(&fs::read(path).ok()?).ok())
+        .collect();
+    events.sort_by(|a, b| b.at.cmp(&a.at));
+    events
+}
+
+/// Record files, oldest first (names start with their timestamp)
+fn record_paths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == RECORD_EXTENSION))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Keep the newest [`MAX_BAD_EVENTS`] events
+fn prune(dir: &Path) {
+    let paths = record_paths(dir);
+    let excess = paths.len().saturating_sub(MAX_BAD_EVENTS);
+    for path in &paths[..excess] {
+        let _ = fs::remove_file(path.with_extension(PAYLOAD_EXTENSION));
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_caps_payload_and_prunes_old_events() {
+        let dir = TempDir::new().unwrap();
+        let big = vec![b'x'; MAX_PAYLOAD_BYTES + 10];
+        let path = record(dir.path(), "capture", &big, "EOF while parsing", &[]).unwrap();
+
+        let events = list(dir.path());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].size, MAX_PAYLOAD_BYTES + 10);
+        assert!(events[0].truncated);
+        let payload = fs::read(path.with_extension(PAYLOAD_EXTENSION)).unwrap();
+        assert_eq!(payload.len(), MAX_PAYLOAD_BYTES);
+
+        for i in 0..MAX_BAD_EVENTS + 3 {
+            let raw = format!("{{\"file_path\": \"{}", i);
+            record(dir.path(), "capture", raw.as_bytes(), "EOF", &["file_path"]).unwrap();
+            // Distinct, ordered timestamps in the file names
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        let remaining = fs::read_dir(bad_events_dir(dir.path())).unwrap().count();
+        assert_eq!(remaining, MAX_BAD_EVENTS * 2);
+        let events = list(dir.path());
+        assert_eq!(events.len(), MAX_BAD_EVENTS);
+        assert!(events.iter().all(|e| !e.truncated));
+        assert_eq!(events[0].salvaged, vec!["file_path".to_string()]);
+    }
+}
@@ -0,0 +1,230 @@
+//! Cursor capture adapter (`--provider cursor`)
+//!
+//! Cursor runs the commands registered in `hooks.json` with one JSON event on
+//! stdin. `whogitit setup --agent cursor` registers
+//! `whogitit capture --stdin --provider cursor` for two events:
+//!
+//! - `beforeSubmitPrompt`: Cursor's edit events carry no prompt, so the prompt is
+//!   remembered per conversation in `.git/whogitit/cursor-prompts.json`
+//! - `afterFileEdit`: the file is already written; its content before the edit is
+//!   rebuilt by reverting the reported `old_string`/`new_string` replacements, and
+//!   the change enters the pending buffer like any other capture
+//!
+//! Cursor starts hooks outside the repository, so the repository is discovered
+//! from the edited file or the event's workspace roots, not the working directory.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::attribution::ModelInfo;
+
+/// Provider recorded for Cursor edits
+pub const CURSOR_PROVIDER: &str = "cursor";
+
+/// Model recorded when neither the event nor `WHOGITIT_MODEL_ID` names one
+const DEFAULT_CURSOR_MODEL: &str = "cursor-agent";
+
+/// Remembered prompts (inside the git dir)
+const PROMPTS_FILE: &str = "whogitit/cursor-prompts.json";
+
+/// Remembered prompts older than this are dropped
+const PROMPT_TTL_HOURS: i64 = 24;
+
+/// One search/replace reported by `afterFileEdit`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CursorEdit {
+    #[serde(default)]
+    pub old_string: String,
+    #[serde(default)]
+    pub new_string: String,
+}
+
+/// Hook event as Cursor sends it to hook commands
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CursorHookEvent {
+    /// `afterFileEdit`, `beforeSubmitPrompt`, ...
+    pub hook_event_name: String,
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    #[serde(default)]
+    pub generation_id: Option<String>,
+    #[serde(default)]
+    pub workspace_roots: Vec<String>,
+    /// Absolute path of the edited file (`afterFileEdit`)
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Replacements applied to the file (`afterFileEdit`)
+    #[serde(default)]
+    pub edits: Vec<CursorEdit>,
+    /// Prompt text (`beforeSubmitPrompt`)
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Model, when the Cursor version reports it
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl CursorHookEvent {
+    pub fn from_slice(raw: &[u8]) -> Result<Self> {
+        serde_json::from_slice(raw).context("Invalid Cursor hook event JSON")
+    }
+
+    /// Directory to discover the repository from
+    pub fn repo_hint(&self) -> Option<PathBuf> {
+        self.file_path
+            .as_deref()
+            .and_then(|path| Path::new(path).parent())
+            .map(Path::to_path_buf)
+            .or_else(|| self.workspace_roots.first().map(PathBuf::from))
+    }
+
+    /// The conversation id, used as the session when it is a UUID
+    pub fn session_id(&self) -> Option<&str> {
+        self.conversation_id
+            .as_deref()
+            .filter(|id| uuid::Uuid::parse_str(id).is_ok())
+    }
+
+    /// Model from the event, then `WHOGITIT_MODEL_ID`
+    pub fn model_info(&self) -> ModelInfo {
+        let id = self
+            .model
+            .clone()
+            .or_else(|| std::env::var("WHOGITIT_MODEL_ID").ok())
+            .unwrap_or_else(|| DEFAULT_CURSOR_MODEL.to_string());
+        ModelInfo::new(&id, CURSOR_PROVIDER)
+    }
+}
+
+/// Content before `edits`, reverting them last to first
+///
+/// Each `new_string` is replaced by its `old_string` at its first occurrence.
+/// `None` when an edit cannot be located (including pure deletions, whose empty
+/// `new_string` has no position); the caller then falls back to HEAD content.
+pub fn revert_edits(after: &str, edits: &[CursorEdit]) -> Option<String> {
+    let mut content = after.to_string();
+    for edit in edits.iter().rev() {
+        if edit.new_string.is_empty() {
+            return None;
+        }
+        let start = content.find(&edit.new_string)?;
+        content.replace_range(start..start + edit.new_string.len(), &edit.old_string);
+    }
+    Some(content)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredPrompt {
+    prompt: String,
+    at: String,
+}
+
+/// Latest prompt of each recent conversation
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PromptStore {
+    conversations: BTreeMap<String, StoredPrompt>,
+}
+
+impl PromptStore {
+    fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Remember the prompt just submitted in a conversation
+pub fn remember_prompt(git_dir: &Path, conversation: &str, prompt: &str) -> Result<()> {
+    let path = git_dir.join(PROMPTS_FILE);
+    let mut store = PromptStore::load(&path);
+    let cutoff = Utc::now() - Duration::hours(PROMPT_TTL_HOURS);
+    store
+        .conversations
+        .retain(|_, stored| DateTime::parse_from_rfc3339(&stored.at).is_ok_and(|at| at >= cutoff));
+    store.conversations.insert(
+        conversation.to_string(),
+        StoredPrompt {
+            prompt: prompt.to_string(),
+            at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        },
+    );
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create whogitit directory")?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string(&store)?).context("Failed to write Cursor prompt")?;
+    // Prompts are stored unredacted until the edit is captured
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))
+            .context("Failed to set permissions on Cursor prompts")?;
+    }
+    fs::rename(&tmp, &path).context("Failed to store Cursor prompt")
+}
+
+/// The last prompt submitted in a conversation
+pub fn recall_prompt(git_dir: &Path, conversation: &str) -> Option<String> {
+    PromptStore::load(&git_dir.join(PROMPTS_FILE))
+        .conversations
+        .remove(conversation)
+        .map(|stored| stored.prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_after_file_edit_and_revert() {
+        let json = r#"{
+            "hook_event_name": "afterFileEdit",
+            "conversation_id": "3f2c9a1e-7b4d-4c1a-9e8f-2d6b5a0c1e7f",
+            "generation_id": "gen-1",
+            "workspace_roots": ["/work/app"],
+            "file_path": "/work/app/src/lib.rs",
+            "edits": [
+                {"old_string": "fn a() {}", "new_string": "fn a() -> u8 { 1 }"},
+                {"old_string": "fn b() {}", "new_string": "fn b() -> u8 { 2 }"}
+            ]
+        }"#;
+        let event = CursorHookEvent::from_slice(json.as_bytes()).unwrap();
+        assert_eq!(event.repo_hint(), Some(PathBuf::from("/work/app/src")));
+        assert!(event.session_id().is_some());
+        assert_eq!(event.model_info().provider, CURSOR_PROVIDER);
+
+        let after = "fn a() -> u8 { 1 }\nfn b() -> u8 { 2 }\n";
+        assert_eq!(
+            revert_edits(after, &event.edits).as_deref(),
+            Some("fn a() {}\nfn b() {}\n")
+        );
+        // An edit that is no longer in the file cannot be reverted
+        assert!(revert_edits("fn c() {}\n", &event.edits).is_none());
+    }
+
+    #[test]
+    fn test_prompts_remembered_per_conversation() {
+        let dir = TempDir::new().unwrap();
+        remember_prompt(dir.path(), "conv-1", "Add retries").unwrap();
+        remember_prompt(dir.path(), "conv-2", "Fix the parser").unwrap();
+        remember_prompt(dir.path(), "conv-1", "Now add backoff").unwrap();
+
+        assert_eq!(
+            recall_prompt(dir.path(), "conv-1").as_deref(),
+            Some("Now add backoff")
+        );
+        assert_eq!(
+            recall_prompt(dir.path(), "conv-2").as_deref(),
+            Some("Fix the parser")
+        );
+        assert!(recall_prompt(dir.path(), "conv-3").is_none());
+    }
+}
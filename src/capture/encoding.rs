@@ -0,0 +1,156 @@
+//! Text encoding detection for captured and committed file content
+//!
+//! Attribution works on UTF-8 strings, but repositories also contain UTF-16 files
+//! (with a byte-order mark) and files with stray bytes that are not valid UTF-8.
+//! [`decode`] turns such content into a string and records the encoding it came
+//! from so [`encode`] can reproduce the original bytes exactly. Content with NUL
+//! bytes and no UTF-16 BOM is still treated as binary.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+/// Bytes inspected for NULs when deciding whether content is binary (same as git)
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Encoding of a file's bytes on disk or in a blob
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    /// UTF-8 with a leading byte-order mark
+    #[serde(rename = "utf-8-bom")]
+    Utf8Bom,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    /// Not valid UTF-8; each byte maps to one character so the bytes round-trip
+    #[serde(rename = "latin-1")]
+    Latin1,
+}
+
+impl TextEncoding {
+    pub fn is_utf8(&self) -> bool {
+        *self == TextEncoding::Utf8
+    }
+}
+
+/// Decoded text and the encoding it was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: TextEncoding,
+}
+
+/// Decode file content, returning None for binary content
+pub fn decode(bytes: &[u8]) -> Option<DecodedText> {
+    let decoded = |text: String, encoding| Some(DecodedText { text, encoding });
+
+    if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        return decode_utf16(rest, u16::from_le_bytes)
+            .and_then(|text| decoded(text, TextEncoding::Utf16Le));
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        return decode_utf16(rest, u16::from_be_bytes)
+            .and_then(|text| decoded(text, TextEncoding::Utf16Be));
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        if let Ok(text) = std::str::from_utf8(rest) {
+            return decoded(text.to_string(), TextEncoding::Utf8Bom);
+        }
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return decoded(text.to_string(), TextEncoding::Utf8);
+    }
+
+    if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+        return None;
+    }
+    decoded(
+        bytes.iter().map(|&b| b as char).collect(),
+        TextEncoding::Latin1,
+    )
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Option<String> {
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .ok()
+}
+
+/// Encode text back to the bytes it was decoded from
+pub fn encode(text: &str, encoding: TextEncoding) -> Vec<u8> {
+    match encoding {
+        TextEncoding::Utf8 => text.as_bytes().to_vec(),
+        TextEncoding::Utf8Bom => [UTF8_BOM, text.as_bytes()].concat(),
+        TextEncoding::Utf16Le => UTF16_LE_BOM
+            .iter()
+            .copied()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+        TextEncoding::Utf16Be => UTF16_BE_BOM
+            .iter()
+            .copied()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect(),
+        // Characters above U+00FF can only come from later edits, not from decoding
+        TextEncoding::Latin1 => text
+            .chars()
+            .map(|c| u8::try_from(c).unwrap_or(b'?'))
+            .collect(),
+    }
+}
+
+/// Read and decode a file, returning None if it is missing, unreadable, or binary
+pub fn read_text_file(path: &Path) -> Option<DecodedText> {
+    decode(&std::fs::read(path).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf16_round_trip() {
+        for encoding in [TextEncoding::Utf16Le, TextEncoding::Utf16Be] {
+            let bytes = encode("fn main() {\n    println!(\"héllo\");\n}\n", encoding);
+            let decoded = decode(&bytes).unwrap();
+            assert_eq!(decoded.encoding, encoding);
+            assert_eq!(decoded.text.lines().count(), 3);
+            assert_eq!(encode(&decoded.text, decoded.encoding), bytes);
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8_round_trips_losslessly() {
+        let bytes = b"caf\xe9 = 1\nname = \"\xff\"\n".to_vec();
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.encoding, TextEncoding::Latin1);
+        assert_eq!(decoded.text.lines().count(), 2);
+        assert_eq!(encode(&decoded.text, decoded.encoding), bytes);
+    }
+
+    #[test]
+    fn test_utf8_and_binary_detection() {
+        let plain = decode(b"hello\n").unwrap();
+        assert_eq!(plain.encoding, TextEncoding::Utf8);
+        assert_eq!(plain.text, "hello\n");
+
+        let bom = decode(b"\xef\xbb\xbfhello\n").unwrap();
+        assert_eq!(bom.encoding, TextEncoding::Utf8Bom);
+        assert_eq!(bom.text, "hello\n");
+
+        assert!(decode(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\xff").is_none());
+        // Odd-length UTF-16 payload is not text
+        assert!(decode(b"\xff\xfeh\x00i").is_none());
+    }
+}
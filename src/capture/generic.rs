@@ -0,0 +1,179 @@
+//! Generic provider capture input (`generic-v1`)
+//!
+//! A small, stable JSON schema that any AI agent can emit to
+//! `whogitit capture --stdin --provider generic-v1`, independent of
+//! Claude Code hook internals.
+
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::hook::HookInput;
+use crate::core::attribution::ModelInfo;
+
+/// Schema version accepted by the generic provider
+pub const GENERIC_SCHEMA_VERSION: u8 = 1;
+
+/// Provider name recorded when the input names a model but no provider
+const UNKNOWN_PROVIDER: &str = "unknown";
+
+fn default_version() -> u8 {
+    GENERIC_SCHEMA_VERSION
+}
+
+/// A single file change reported by a generic provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenericCaptureV1 {
+    /// Schema version (defaults to 1)
+    #[serde(default = "default_version")]
+    pub version: u8,
+    /// Tool or action that made the change (e.g. "Edit", "Write")
+    pub tool: String,
+    /// File path, absolute or relative to the repository root
+    pub file: String,
+    /// Content before the change (omit to use the content at HEAD)
+    #[serde(default)]
+    pub before: Option<String>,
+    /// Content after the change
+    pub after: String,
+    /// The user prompt that triggered the change
+    #[serde(default)]
+    pub prompt: String,
+    /// Model identifier (e.g. "gpt-5-codex")
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Model provider (e.g. "openai")
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Session identifier (UUID) grouping related changes
+    #[serde(default)]
+    pub session: Option<String>,
+}
+
+impl GenericCaptureV1 {
+    /// Parse and validate a generic-v1 document
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        let capture: Self =
+            serde_json::from_reader(reader).context("Invalid generic-v1 capture JSON")?;
+        capture.validate()?;
+        Ok(capture)
+    }
+
+    /// Validate schema version and required fields
+    pub fn validate(&self) -> Result<()> {
+        if self.version != GENERIC_SCHEMA_VERSION {
+            anyhow::bail!(
+                "Unsupported generic capture version {} (expected {})",
+                self.version,
+                GENERIC_SCHEMA_VERSION
+            );
+        }
+        if self.tool.trim().is_empty() {
+            anyhow::bail!("generic-v1 capture requires a non-empty 'tool'");
+        }
+        if self.file.trim().is_empty() {
+            anyhow::bail!("generic-v1 capture requires a non-empty 'file'");
+        }
+        if let Some(session) = &self.session {
+            if uuid::Uuid::parse_str(session).is_err() {
+                anyhow::bail!("generic-v1 'session' must be a UUID, got '{}'", session);
+            }
+        }
+        Ok(())
+    }
+
+    /// Model information, if the provider supplied a model
+    pub fn model_info(&self) -> Option<ModelInfo> {
+        self.model
+            .as_deref()
+            .map(|id| ModelInfo::new(id, self.provider.as_deref().unwrap_or(UNKNOWN_PROVIDER)))
+    }
+
+    /// Convert into the internal hook input
+    pub fn into_hook_input(self) -> HookInput {
+        HookInput {
+            tool: self.tool,
+            file_path: self.file,
+            prompt: self.prompt,
+            old_content_present: self.before.is_some(),
+            old_content: self.before,
+            new_content: self.after,
+            context: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_minimal() {
+        let json = r#"{"tool":"Edit","file":"src/lib.rs","after":"fn main() {}\n"}"#;
+        let capture = GenericCaptureV1::from_reader(json.as_bytes()).unwrap();
+
+        assert_eq!(capture.version, 1);
+        assert!(capture.before.is_none());
+        assert!(capture.model_info().is_none());
+        assert_eq!(capture.prompt, "");
+    }
+
+    #[test]
+    fn test_parse_full() {
+        let json = r#"{
+            "version": 1,
+            "tool": "Write",
+            "file": "src/new.rs",
+            "before": "",
+            "after": "pub fn x() {}\n",
+            "prompt": "Add x",
+            "model": "gpt-5-codex",
+            "provider": "openai",
+            "session": "3f2c9a1e-7b4d-4c1a-9e8f-2d6b5a0c1e7f"
+        }"#;
+        let capture = GenericCaptureV1::from_reader(json.as_bytes()).unwrap();
+        let model = capture.model_info().unwrap();
+        assert_eq!(model.id, "gpt-5-codex");
+        assert_eq!(model.provider, "openai");
+        assert_eq!(
+            capture.session.as_deref(),
+            Some("3f2c9a1e-7b4d-4c1a-9e8f-2d6b5a0c1e7f")
+        );
+
+        let input = capture.into_hook_input();
+        assert_eq!(input.file_path, "src/new.rs");
+        assert!(input.old_content_present);
+        assert_eq!(input.old_content.as_deref(), Some(""));
+        assert_eq!(input.prompt, "Add x");
+    }
+
+    #[test]
+    fn test_model_without_provider() {
+        let json = r#"{"tool":"Edit","file":"a.rs","after":"","model":"local-llm"}"#;
+        let capture = GenericCaptureV1::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(capture.model_info().unwrap().provider, "unknown");
+    }
+
+    #[test]
+    fn test_rejects_unsupported_version() {
+        let json = r#"{"version":2,"tool":"Edit","file":"a.rs","after":""}"#;
+        let err = GenericCaptureV1::from_reader(json.as_bytes()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported generic capture version"));
+    }
+
+    #[test]
+    fn test_rejects_non_uuid_session() {
+        let json = r#"{"tool":"Edit","file":"a.rs","after":"","session":"codex-1"}"#;
+        let err = GenericCaptureV1::from_reader(json.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("must be a UUID"));
+    }
+
+    #[test]
+    fn test_rejects_empty_file() {
+        let json = r#"{"tool":"Edit","file":" ","after":""}"#;
+        assert!(GenericCaptureV1::from_reader(json.as_bytes()).is_err());
+    }
+}
@@ -1,18 +1,46 @@
 use std::collections::HashSet;
 use std::env;
+use std::io::Read;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use git2::{Delta, DiffFindOptions, DiffOptions, Repository};
 use serde::{Deserialize, Serialize};
 
+use crate::capture::bad_events;
+use crate::capture::cursor::{self, CursorHookEvent};
+use crate::capture::encoding::{self, DecodedText, TextEncoding};
+use crate::capture::generic::GenericCaptureV1;
+use crate::capture::inline::{InlineCaptureV1, INLINE_COMPLETION_TOOL};
+use crate::capture::interrupt::{self, InterruptedAnalysis};
+use crate::capture::pause::CapturePause;
 use crate::capture::pending::{PendingBuffer, PendingStore, PromptRecord};
-use crate::capture::threeway::ThreeWayAnalyzer;
-use crate::core::attribution::{AIAttribution, PromptInfo, SessionMetadata};
-use crate::privacy::{Redactor, RetentionConfig, WhogititConfig};
+use crate::capture::progress::AnalysisProgress;
+use crate::capture::session::ActiveSession;
+use crate::capture::snapshot::{
+    compute_bytes_hash, compute_hash, ContentSnapshot, FileAttributionResult, FileEditHistory,
+    LineSource, SecretFinding,
+};
+use crate::capture::stash::{detect_desynced_files, warn_desynced_files};
+use crate::capture::threeway::{NormalizerRegistry, ThreeWayAnalyzer};
+use crate::capture::watch::is_capturable;
+use crate::core::attribution::{
+    prompt_hash, AIAttribution, ModelInfo, PromptInfo, SessionMetadata, ToolCall, SCHEMA_VERSION,
+};
+use crate::privacy::visibility;
+use crate::privacy::{
+    AnalysisGranularity, AuditForwardingConfig, Redactor, RetentionConfig, StaleAction,
+    StorageBackend, TierStorageConfig, WhogititConfig,
+};
 use crate::retention::apply_retention_policy;
 use crate::storage::audit::AuditLog;
+use crate::storage::index::AttributionIndex;
 use crate::storage::notes::NotesStore;
+use crate::storage::repo_lock::{LockHolderKind, RepoLock};
+use crate::storage::trailers::TrailerGenerator;
+use crate::utils::{repo_path, GlobSet};
 
 /// Environment variable for session ID
 const ENV_SESSION_ID: &str = "WHOGITIT_SESSION_ID";
@@ -36,6 +64,12 @@ pub struct HookContext {
     /// Subagent ID if applicable
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subagent_id: Option<String>,
+    /// `tool_use` id of the tool call that made the change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+    /// Transcript id of the assistant message that issued the tool call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
 }
 
 /// Input from Claude Code hook for file changes
@@ -65,14 +99,40 @@ pub struct CaptureHook {
     repo_root: std::path::PathBuf,
     /// Privacy redactor
     redactor: Redactor,
+    /// How prompt text is stored per privacy tier
+    prompt_storage: TierStorageConfig,
     /// Whether audit logging is enabled
     audit_enabled: bool,
+    /// Whether AI-written code is scanned for possible secrets
+    scan_generated_code: bool,
+    /// Syslog forwarding for audit events
+    audit_forwarding: AuditForwardingConfig,
     /// Similarity threshold for AI-modified detection
     similarity_threshold: f64,
     /// Maximum pending buffer age in hours
     max_pending_age_hours: i64,
+    /// What happens to a pending buffer older than `max_pending_age_hours`
+    stale_action: StaleAction,
     /// Retention configuration
     retention_config: RetentionConfig,
+    /// Globs for files attributed as a whole (lockfiles, assets)
+    binary_paths: GlobSet,
+    /// Whether notes store per-line attribution or only line ranges
+    granularity: AnalysisGranularity,
+    /// Line matching rules per file extension
+    normalizers: NormalizerRegistry,
+    /// Line count past which files get bounded-memory analysis (0 = no limit)
+    max_analysis_lines: usize,
+    /// Most consecutive lines joined when matching formatter reflows
+    max_block_lines: usize,
+    /// Whether writes that leave a file unchanged still add their prompt
+    count_noop_prompts: bool,
+    /// Where attribution is recorded (notes, commit trailers or both)
+    storage_backend: StorageBackend,
+    /// Whether post-commit analysis reports progress on stderr
+    show_progress: bool,
+    /// Set to stop the analysis at the next file (SIGINT also stops it)
+    cancel: Arc<AtomicBool>,
 }
 
 impl CaptureHook {
@@ -81,32 +141,129 @@ impl CaptureHook {
         let repo_root = repo_path.to_path_buf();
 
         // Load config and build redactor
-        let config = match WhogititConfig::load(&repo_root) {
-            Ok(config) => config,
-            Err(err) => {
-                eprintln!(
-                    "whogitit: Warning - failed to load config, using defaults: {}",
-                    err
-                );
-                WhogititConfig::default()
-            }
-        };
+        let config = WhogititConfig::load_or_warn(&repo_root);
         let redactor = config.privacy.build_redactor();
+        let prompt_storage = config.privacy.tiers;
         let audit_enabled = config.privacy.audit_log;
+        let scan_generated_code = config.privacy.scan_generated_code;
+        let audit_forwarding = config.audit.forwarding;
         let similarity_threshold = config.analysis.similarity_threshold;
         let max_pending_age_hours = config.analysis.max_pending_age_hours as i64;
+        let stale_action = config.analysis.stale_action;
         let retention_config = config.retention.unwrap_or_default();
+        let binary_paths = GlobSet::new(&config.analysis.binary_paths);
+        let granularity = config.analysis.granularity;
+        let normalizers = NormalizerRegistry::with_overrides(&config.analysis.normalizers)
+            .unwrap_or_else(|err| {
+                eprintln!(
+                    "whogitit: Warning - invalid analysis.normalizers, using built-ins: {:#}",
+                    err
+                );
+                NormalizerRegistry::default()
+            });
+        let storage_backend = config.storage.backend;
+        let max_analysis_lines = config.analysis.max_analysis_lines;
+        let max_block_lines = config.analysis.max_block_lines;
+        let count_noop_prompts = config.analysis.count_noop_prompts;
 
         Ok(Self {
             repo_root,
             redactor,
+            prompt_storage,
             audit_enabled,
+            scan_generated_code,
+            audit_forwarding,
             similarity_threshold,
             max_pending_age_hours,
+            stale_action,
             retention_config,
+            binary_paths,
+            granularity,
+            normalizers,
+            max_analysis_lines,
+            max_block_lines,
+            count_noop_prompts,
+            storage_backend,
+            show_progress: true,
+            cancel: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// Override `analysis.max_analysis_lines` (`post-commit --max-analysis-lines`)
+    pub fn with_max_analysis_lines(mut self, max_analysis_lines: usize) -> Self {
+        self.max_analysis_lines = max_analysis_lines;
+        self
+    }
+
+    /// Turn off the analysis progress on stderr (`post-commit --quiet`)
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.show_progress = !quiet;
+        self
+    }
+
+    /// Flag that interrupts the analysis like Ctrl-C does
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancel)
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst) || interrupt::sigint_received()
+    }
+
+    /// Whether a committed file is too large for full three-way analysis
+    fn exceeds_analysis_limit(&self, text: &str) -> bool {
+        self.max_analysis_lines > 0 && text.lines().nth(self.max_analysis_lines).is_some()
+    }
+
+    /// Load the pending buffer, applying `analysis.stale_action` if it is stale
+    ///
+    /// Returns `None` when there is no buffer or a stale one was archived or discarded.
+    fn load_pending(&self, store: &PendingStore) -> Result<Option<PendingBuffer>> {
+        if self.stale_action == StaleAction::Warn {
+            return store.load_with_max_age(self.max_pending_age_hours);
+        }
+        let Some(buffer) = store.load_ignoring_age()? else {
+            return Ok(None);
+        };
+        if !buffer.is_stale_hours(self.max_pending_age_hours) {
+            return Ok(Some(buffer));
+        }
+
+        let contents = format!(
+            "{} edit(s) in {} file(s) from {}",
+            buffer.total_edits(),
+            buffer.file_count(),
+            buffer.age_string()
+        );
+        let reason = if self.stale_action == StaleAction::Archive {
+            let repo = Repository::open(&self.repo_root).context("Failed to open repository")?;
+            let path = store.archive(repo.path())?;
+            eprintln!(
+                "whogitit: Archived stale pending buffer ({}) to {}",
+                contents,
+                path.display()
+            );
+            eprintln!("whogitit: Run 'whogitit import' to restore it.");
+            format!("archived {} to {}", contents, path.display())
+        } else {
+            store.delete()?;
+            eprintln!("whogitit: Discarded stale pending buffer ({})", contents);
+            format!("discarded {}", contents)
+        };
+
+        if self.audit_enabled {
+            if let Err(e) = self.audit_log().log_pending_expired(&reason) {
+                eprintln!("whogitit: Warning - failed to log pending expiry: {}", e);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Audit log for this repository, forwarding events if configured
+    fn audit_log(&self) -> AuditLog {
+        AuditLog::new(&self.repo_root).with_forwarding(&self.audit_forwarding)
+    }
+
     /// Get or create session ID
     fn get_session_id() -> String {
         env::var(ENV_SESSION_ID).unwrap_or_else(|_| uuid::Uuid::new_v4().to_string())
@@ -119,16 +276,42 @@ impl CaptureHook {
 
     /// Handle a file change from Claude Code
     pub fn on_file_change(&self, input: HookInput) -> Result<()> {
+        self.on_file_change_for_session(input, None, None)
+    }
+
+    /// Handle a file change with a provider-supplied session and model
+    ///
+    /// An active `whogitit session begin` session takes precedence over explicit
+    /// values, which take precedence over `WHOGITIT_SESSION_ID` / `WHOGITIT_MODEL_ID`.
+    pub fn on_file_change_for_session(
+        &self,
+        input: HookInput,
+        session_id: Option<&str>,
+        model: Option<ModelInfo>,
+    ) -> Result<()> {
+        if capture_paused(&self.repo_root) {
+            return Ok(());
+        }
+
         let store = PendingStore::new(&self.repo_root);
+        let active = ActiveSession::load_for_workdir(&self.repo_root);
+
+        let explicit_session = active
+            .as_ref()
+            .map(|a| a.session_id.clone())
+            .or_else(|| session_id.map(str::to_string))
+            .or_else(|| env::var(ENV_SESSION_ID).ok());
+        let model = active
+            .as_ref()
+            .and_then(ActiveSession::model_info)
+            .or(model)
+            .unwrap_or_else(|| ModelInfo::claude(&Self::get_model_id()));
 
         // Load or create pending buffer
-        let mut buffer = match store.load_with_max_age(self.max_pending_age_hours)? {
-            Some(b) => {
-                // Check if we should start a new session
-                // (different session ID in env means new session)
-                let current_session = Self::get_session_id();
-                if b.session.session_id != current_session && env::var(ENV_SESSION_ID).is_ok() {
-                    // New session ID explicitly set, start fresh
+        let mut buffer = match self.load_pending(&store)? {
+            Some(b) => match &explicit_session {
+                // New session ID explicitly set, start fresh
+                Some(current_session) if b.session.session_id != *current_session => {
                     // But first, warn about uncommitted changes
                     if b.has_changes() {
                         eprintln!(
@@ -136,19 +319,22 @@ impl CaptureHook {
                             b.total_edits()
                         );
                     }
-                    let mut buffer = PendingBuffer::new(&current_session, &Self::get_model_id());
+                    let mut buffer = PendingBuffer::new_with_model(current_session, model);
                     buffer.audit_logging_enabled = self.audit_enabled;
                     buffer
-                } else {
-                    b
                 }
-            }
+                _ => b,
+            },
             None => {
-                let mut buffer = PendingBuffer::new(&Self::get_session_id(), &Self::get_model_id());
+                let session = explicit_session.unwrap_or_else(Self::get_session_id);
+                let mut buffer = PendingBuffer::new_with_model(&session, model);
                 buffer.audit_logging_enabled = self.audit_enabled;
                 buffer
             }
         };
+        if let Some(active) = &active {
+            buffer.session.label = active.label.clone();
+        }
 
         // Make path relative to repo root
         let relative_path = self.make_relative_path(&input.file_path)?;
@@ -158,7 +344,7 @@ impl CaptureHook {
             anyhow::bail!("Empty file path");
         }
 
-        let rel_path = std::path::Path::new(&relative_path);
+        let rel_path = repo_path::to_path(&relative_path);
 
         // Reject absolute paths (including Windows prefixes)
         if rel_path.is_absolute()
@@ -188,6 +374,26 @@ impl CaptureHook {
             eprintln!("whogitit: Warning - empty new_content for non-delete operation");
         }
 
+        // The hook transports content as UTF-8 JSON, so UTF-16 and other non-UTF-8
+        // files arrive mangled; decode them from disk instead. Binary files can't be
+        // decoded at all, so only a hash of their bytes is kept for post-commit.
+        let disk_bytes = std::fs::read(self.repo_root.join(&rel_path)).ok();
+        let disk_decoded = disk_bytes.as_deref().and_then(encoding::decode);
+        let bytes_hash = disk_bytes
+            .as_deref()
+            .filter(|_| disk_decoded.is_none() || self.binary_paths.is_match(&relative_path))
+            .map(compute_bytes_hash);
+        let disk_text = disk_decoded.filter(|decoded| !decoded.encoding.is_utf8());
+        let file_encoding = disk_text
+            .as_ref()
+            .map_or(TextEncoding::Utf8, |decoded| decoded.encoding);
+        let mut input = input;
+        if let Some(decoded) = disk_text {
+            input.new_content = decoded.text;
+            input.old_content_present = false;
+            input.old_content = None;
+        }
+
         // Determine old content: use provided value, or fall back to git HEAD
         let old_content = if input.old_content_present {
             Some(input.old_content.unwrap_or_default())
@@ -196,8 +402,33 @@ impl CaptureHook {
         } else {
             // Try to get content from git HEAD for existing files
             self.get_content_from_git_head(&relative_path)
+                .map(|decoded| decoded.text)
         };
 
+        // A write that leaves the file as it was is not an edit
+        if let Some(old) = old_content.as_deref() {
+            if input.tool != "Delete" && is_noop_write(old, &input.new_content) {
+                buffer.record_noop(
+                    &relative_path,
+                    &input.prompt,
+                    Some(&self.redactor),
+                    self.count_noop_prompts,
+                );
+                buffer.apply_prompt_storage(&self.prompt_storage);
+                return store.save(&buffer);
+            }
+        }
+
+        // AI edits recorded for this file but gone from the worktree usually means a stash
+        if let Some(history) = buffer.get_file_history(&relative_path) {
+            let original = &history.original.content;
+            if history.latest_ai_content().content != *original
+                && old_content.as_deref().unwrap_or("") == original
+            {
+                warn_desynced_files(std::slice::from_ref(&relative_path));
+            }
+        }
+
         // Build edit context from hook input
         let edit_context =
             input
@@ -208,24 +439,50 @@ impl CaptureHook {
                     subagent_id: ctx.subagent_id.clone(),
                     agent_depth: ctx.agent_depth,
                     plan_step: None,
+                    tool_use_id: ctx.tool_use_id.clone(),
+                    message_id: ctx.message_id.clone(),
                 });
 
         // Record the edit with full content snapshots
-        buffer.record_edit_with_context(
-            &relative_path,
-            old_content.as_deref(),
-            &input.new_content,
-            &input.tool,
-            &input.prompt,
-            Some(&self.redactor),
-            edit_context,
-        );
+        let prompts_before = buffer.session.prompts.len();
+        if input.tool == INLINE_COMPLETION_TOOL {
+            let original = self
+                .get_content_from_git_head(&relative_path)
+                .map(|decoded| decoded.text);
+            buffer.record_micro_edit(
+                &relative_path,
+                original.as_deref(),
+                old_content.as_deref().unwrap_or(""),
+                &input.new_content,
+                &input.tool,
+                &input.prompt,
+                Some(&self.redactor),
+            );
+        } else {
+            buffer.record_edit_with_context(
+                &relative_path,
+                old_content.as_deref(),
+                &input.new_content,
+                &input.tool,
+                &input.prompt,
+                Some(&self.redactor),
+                edit_context,
+            );
+        }
+        buffer.apply_prompt_storage(&self.prompt_storage);
+        if !file_encoding.is_utf8() {
+            buffer.set_file_encoding(&relative_path, file_encoding);
+        }
+        if let Some(hash) = bytes_hash.filter(|_| input.tool != "Delete") {
+            buffer.set_latest_bytes_hash(&relative_path, hash);
+        }
 
-        // Log redaction audit events (if enabled)
-        if self.audit_enabled {
+        // Log redaction audit events (if enabled) for a newly recorded prompt; a prompt
+        // shared by several edits was logged with its first one
+        if self.audit_enabled && buffer.session.prompts.len() > prompts_before {
             if let Some(prompt) = buffer.session.prompts.last() {
                 if !prompt.redaction_events.is_empty() {
-                    let audit_log = AuditLog::new(&self.repo_root);
+                    let audit_log = self.audit_log();
                     let mut counts: std::collections::HashMap<String, u32> =
                         std::collections::HashMap::new();
                     for event in &prompt.redaction_events {
@@ -240,17 +497,84 @@ impl CaptureHook {
             }
         }
 
+        if self.scan_generated_code && input.tool != "Delete" {
+            self.report_secret_findings(&mut buffer, &relative_path);
+        }
+
         // Save buffer with atomic write
         store.save(&buffer)?;
 
         Ok(())
     }
 
+    /// Scan the latest edit of `path` for possible secrets, warning about new ones
+    ///
+    /// Findings are kept in the pending buffer and, with audit logging enabled,
+    /// recorded in the audit log (pattern and location only).
+    fn report_secret_findings(&self, buffer: &mut PendingBuffer, path: &str) {
+        let findings = buffer.scan_latest_edit(path, &self.redactor);
+        if findings.is_empty() {
+            return;
+        }
+        let audit_log = self.audit_enabled.then(|| self.audit_log());
+        for finding in &findings {
+            eprintln!(
+                "whogitit: Warning - possible secret ({}) written by AI in {}:{}",
+                finding.pattern_name, path, finding.line_number
+            );
+            if let Some(audit_log) = &audit_log {
+                if let Err(e) =
+                    audit_log.log_secret_detected(&finding.pattern_name, path, finding.line_number)
+                {
+                    eprintln!("whogitit: Warning - failed to log secret finding: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Capture-time secret findings still present in the committed file
+    ///
+    /// A finding is kept when a committed line attributed to its edit still matches
+    /// its pattern; line numbers come from the committed file.
+    fn confirm_secret_findings(
+        &self,
+        history: &FileEditHistory,
+        result: &FileAttributionResult,
+        committed: &str,
+    ) -> Vec<SecretFinding> {
+        let mut confirmed = Vec::new();
+        for (idx, line) in committed.lines().enumerate() {
+            let line_number = idx as u32 + 1;
+            let Some(attribution) = result.line(line_number) else {
+                continue;
+            };
+            let edit_id = match attribution.source {
+                LineSource::AI { edit_id } | LineSource::AIModified { edit_id, .. } => edit_id,
+                _ => continue,
+            };
+            for name in self.redactor.matching_pattern_names(line) {
+                if let Some(finding) = history
+                    .secret_findings
+                    .iter()
+                    .find(|f| f.pattern_name == name && f.edit_id == *edit_id)
+                {
+                    confirmed.push(SecretFinding {
+                        line_number,
+                        pattern_name: name.to_string(),
+                        edit_id: edit_id.clone(),
+                        prompt_index: attribution.prompt_index.unwrap_or(finding.prompt_index),
+                    });
+                }
+            }
+        }
+        confirmed
+    }
+
     /// Get file content from git HEAD (the last committed version)
     ///
     /// Returns None for new files or if git operations fail.
     /// Logs warnings for unexpected failures to aid debugging.
-    fn get_content_from_git_head(&self, path: &str) -> Option<String> {
+    fn get_content_from_git_head(&self, path: &str) -> Option<DecodedText> {
         let repo = match Repository::open(&self.repo_root) {
             Ok(r) => r,
             Err(e) => {
@@ -291,7 +615,7 @@ impl CaptureHook {
         };
 
         // File not existing in HEAD is normal for new files - don't warn
-        let entry = match tree.get_path(std::path::Path::new(path)) {
+        let entry = match tree.get_path(&repo_path::to_path(path)) {
             Ok(e) => e,
             Err(_) => return None, // New file, not in HEAD
         };
@@ -307,11 +631,8 @@ impl CaptureHook {
             }
         };
 
-        // Non-UTF8 content is valid for binary files - don't treat as error
-        match std::str::from_utf8(blob.content()) {
-            Ok(content) => Some(content.to_string()),
-            Err(_) => None, // Binary file
-        }
+        // Undecodable content is valid for binary files - don't treat as error
+        encoding::decode(blob.content())
     }
 
     /// Handle post-commit: perform three-way analysis, attach notes, and clean up
@@ -319,13 +640,21 @@ impl CaptureHook {
         let store = PendingStore::new(&self.repo_root);
 
         // Load pending buffer
-        let mut buffer = match store.load()? {
+        let mut buffer = match self.load_pending(&store)? {
             Some(b) if b.has_changes() => b,
             _ => return Ok(None),
         };
 
+        warn_desynced_files(&detect_desynced_files(&buffer, &self.repo_root));
+
         // Open repo and get HEAD commit
         let repo = Repository::open(&self.repo_root).context("Failed to open repository")?;
+
+        // Never fail a commit over the lock: after the wait, write the note without it
+        let _lock = RepoLock::acquire_default(repo.path(), LockHolderKind::PostCommit)
+            .map_err(|e| eprintln!("whogitit: Warning - {:#}; continuing without the lock", e))
+            .ok();
+
         let head = repo
             .head()
             .context("Failed to get HEAD")?
@@ -333,115 +662,75 @@ impl CaptureHook {
             .context("Failed to get HEAD commit")?;
 
         let tree = head.tree()?;
-
-        // Build rename map (old -> new) to preserve attribution across moves
-        let rename_map = build_rename_map(&repo, &head)?;
-        let changed_paths = build_changed_paths(&repo, &head)?;
-
-        // Preserve all prompt records before we split processed vs remaining histories.
-        let all_prompts = buffer.session.prompts.clone();
-
-        let mut file_results = Vec::new();
-        let mut remaining_histories = std::collections::HashMap::new();
-        let mut processed_prompt_indices = HashSet::new();
-        let mut remaining_prompt_indices = HashSet::new();
-        let mut used_plan_mode = false;
-        let mut subagent_count = 0u32;
-
-        for (path, history) in buffer.file_histories.drain() {
-            let Some(committed_path) = resolve_committed_path(&path, &changed_paths, &rename_map)
-            else {
-                for edit in &history.edits {
-                    remaining_prompt_indices.insert(edit.prompt_index);
-                }
-                remaining_histories.insert(path, history);
-                continue;
-            };
-
-            // Get the committed content for this file
-            let committed_content = match tree.get_path(std::path::Path::new(&committed_path)) {
-                Ok(entry) => {
-                    let blob = repo.find_blob(entry.id())?;
-                    String::from_utf8_lossy(blob.content()).to_string()
-                }
-                Err(_) => {
-                    // File was part of commit metadata but does not exist in final tree
-                    // (for example, deleted file). Consume it from pending state.
-                    continue;
-                }
-            };
-
-            // Perform three-way analysis
-            let mut result = ThreeWayAnalyzer::analyze_with_diff_with_threshold(
-                &history,
-                &committed_content,
-                self.similarity_threshold,
+        let parent_trees = head
+            .parents()
+            .map(|parent| parent.tree())
+            .collect::<Result<Vec<_>, _>>()?;
+        let parent_ids: Vec<git2::Oid> = head.parent_ids().collect();
+        let base_tree = merge_base_tree(&repo, &parent_ids)?;
+
+        let analysis =
+            self.analyze_commit(&repo, &buffer, &tree, &parent_trees, base_tree.as_ref())?;
+        if analysis.interrupted {
+            let head_id = head.id().to_string();
+            let marker = InterruptedAnalysis::new(
+                &head_id,
+                analysis.files_done,
+                analysis.files_total,
+                buffer.clone(),
             );
-            if committed_path != path {
-                result.path = committed_path;
-            }
-            file_results.push(result);
-
-            for edit in &history.edits {
-                processed_prompt_indices.insert(edit.prompt_index);
-                if edit.context.plan_mode {
-                    used_plan_mode = true;
-                }
-                if edit.context.agent_depth > 0 {
-                    subagent_count += 1;
-                }
+            marker.save(repo.path())?;
+            if analysis.remaining_histories.is_empty() {
+                store.delete()?;
+            } else {
+                keep_remaining(
+                    &mut buffer,
+                    analysis.remaining_histories,
+                    &analysis.remaining_prompt_indices,
+                );
+                store.save(&buffer)?;
             }
+            eprintln!(
+                "whogitit: Analysis interrupted after {}/{} files; run 'whogitit worker --resume' to attach attribution to {}",
+                analysis.files_done,
+                analysis.files_total,
+                &head_id[..7]
+            );
+            return Ok(None);
         }
-
-        // Nothing attributable for this commit; only update pending state.
-        if file_results.is_empty() {
-            if remaining_histories.is_empty() {
+        let Some(attribution) = analysis.attribution else {
+            // Nothing attributable for this commit; only update pending state.
+            if analysis.remaining_histories.is_empty() {
                 store.delete()?;
             } else {
-                buffer.file_histories = remaining_histories;
-                buffer.session.prompts =
-                    filter_prompt_records(&all_prompts, &remaining_prompt_indices);
-                buffer.session.prompt_count = buffer.session.prompts.len() as u32;
-                buffer.prompt_counter = next_prompt_index(&buffer.session.prompts);
-                buffer.total_redactions = buffer
-                    .session
-                    .prompts
-                    .iter()
-                    .map(|p| p.redaction_events.len() as u32)
-                    .sum();
+                keep_remaining(
+                    &mut buffer,
+                    analysis.remaining_histories,
+                    &analysis.remaining_prompt_indices,
+                );
                 store.save(&buffer)?;
             }
             return Ok(None);
-        }
-
-        let attribution_prompts = filter_prompt_records(&all_prompts, &processed_prompt_indices);
-
-        // Create attribution with full analysis
-        let attribution = AIAttribution {
-            version: 3,
-            session: SessionMetadata {
-                session_id: buffer.session.session_id.clone(),
-                model: buffer.session.model.clone(),
-                started_at: buffer.session.started_at.clone(),
-                prompt_count: attribution_prompts.len() as u32,
-                used_plan_mode,
-                subagent_count,
-            },
-            prompts: attribution_prompts
-                .iter()
-                .map(|p| PromptInfo {
-                    index: p.index,
-                    text: p.text.clone(),
-                    timestamp: p.timestamp.clone(),
-                    affected_files: p.affected_files.clone(),
-                })
-                .collect(),
-            files: file_results,
         };
 
-        // Store as git note
-        let notes_store = NotesStore::new(&repo)?;
-        notes_store.store_attribution(head.id(), &attribution)?;
+        // Store as git note (the trailers-only backend already wrote the
+        // summary into the commit message from the commit-msg hook)
+        if self.storage_backend.writes_notes() {
+            let notes_store = NotesStore::new(&repo)?;
+            notes_store.store_attribution(head.id(), &attribution)?;
+
+            // Keep an existing attribution index current (summary/export create it)
+            if AttributionIndex::exists(&repo) {
+                if let Err(e) = AttributionIndex::open(&repo)
+                    .and_then(|mut index| index.lookup(&repo, &notes_store, head.id()))
+                {
+                    eprintln!(
+                        "whogitit: Warning - failed to update attribution index: {}",
+                        e
+                    );
+                }
+            }
+        }
 
         if self.retention_config.auto_purge {
             if let Err(e) = apply_retention_policy(
@@ -449,26 +738,21 @@ impl CaptureHook {
                 &self.retention_config,
                 true,
                 "Auto purge (post-commit)",
-                self.audit_enabled,
+                self.audit_enabled.then(|| self.audit_log()).as_ref(),
             ) {
                 eprintln!("whogitit: Warning - auto purge failed: {}", e);
             }
         }
 
         // Persist any remaining pending edits only after attribution note is safely stored.
-        if remaining_histories.is_empty() {
+        if analysis.remaining_histories.is_empty() {
             store.delete()?;
         } else {
-            buffer.file_histories = remaining_histories;
-            buffer.session.prompts = filter_prompt_records(&all_prompts, &remaining_prompt_indices);
-            buffer.session.prompt_count = buffer.session.prompts.len() as u32;
-            buffer.prompt_counter = next_prompt_index(&buffer.session.prompts);
-            buffer.total_redactions = buffer
-                .session
-                .prompts
-                .iter()
-                .map(|p| p.redaction_events.len() as u32)
-                .sum();
+            keep_remaining(
+                &mut buffer,
+                analysis.remaining_histories,
+                &analysis.remaining_prompt_indices,
+            );
             store.save(&buffer)?;
         }
 
@@ -494,127 +778,654 @@ impl CaptureHook {
         Ok(Some(attribution))
     }
 
-    /// Make a path relative to the repo root
-    fn make_relative_path(&self, path: &str) -> Result<String> {
-        let input_path = Path::new(path);
-        if !input_path.is_absolute() {
-            return Ok(path.to_string());
-        }
-
-        // Fast path: exact prefix match against the repo root.
-        if let Ok(relative) = input_path.strip_prefix(&self.repo_root) {
-            return Ok(relative.to_string_lossy().to_string());
-        }
-
-        // Handle aliased absolute paths (e.g. /var vs /private/var on macOS)
-        // by canonicalizing both paths before prefix comparison.
-        let canonical_repo =
-            canonicalize_for_prefix(&self.repo_root).unwrap_or_else(|| self.repo_root.clone());
-        if let Some(canonical_input) = canonicalize_for_prefix(input_path) {
-            if let Ok(relative) = canonical_input.strip_prefix(&canonical_repo) {
-                return Ok(relative.to_string_lossy().to_string());
-            }
-        }
+    /// Finish an analysis interrupted during post-commit and attach its note
+    ///
+    /// Returns `None` when none of the saved pending files ended up in the commit.
+    pub fn resume_interrupted(
+        &self,
+        marker: &InterruptedAnalysis,
+    ) -> Result<Option<AIAttribution>> {
+        let commit = git2::Oid::from_str(&marker.commit)
+            .with_context(|| format!("Commit {} not found", marker.commit))?;
+        self.attach_to_commit(commit, &marker.buffer)
+    }
 
-        anyhow::bail!(
-            "Absolute path '{}' could not be mapped under repository root '{}'.",
-            path,
-            self.repo_root.display()
-        )
+    /// Attribute an existing commit from a buffer of the AI edits it contains
+    ///
+    /// For tools that commit their edits themselves (see `whogitit import-aider`).
+    /// Prompts are stored according to `privacy.tiers`, as for captured edits;
+    /// record them with [`Self::redactor`] so they are redacted too.
+    pub fn import_commit(
+        &self,
+        commit: git2::Oid,
+        mut buffer: PendingBuffer,
+    ) -> Result<Option<AIAttribution>> {
+        buffer.apply_prompt_storage(&self.prompt_storage);
+        self.attach_to_commit(commit, &buffer)
     }
 
-    /// Get current pending status
-    pub fn status(&self) -> Result<PendingStatus> {
-        let store = PendingStore::new(&self.repo_root);
+    /// Redactor built from the repository's privacy settings
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
 
-        // Use quiet load to avoid spurious warnings during status check
-        match store.load_quiet()? {
-            Some(buffer) => {
-                let session_id = buffer.session.session_id.clone();
-                let file_count = buffer.file_count();
-                let line_count = buffer.total_lines();
-                let edit_count = buffer.total_edits();
-                let prompt_count = buffer.session.prompt_count;
-                let has_pending = buffer.has_changes();
-                let is_stale = buffer.is_stale_hours(self.max_pending_age_hours);
-                let age = buffer.age_string();
-                Ok(PendingStatus {
-                    has_pending,
-                    session_id: Some(session_id),
-                    file_count,
-                    line_count,
-                    edit_count,
-                    prompt_count,
-                    is_stale,
-                    age,
-                    max_pending_age_hours: self.max_pending_age_hours,
-                })
+    /// Analyze `buffer` against a commit and store the resulting note
+    fn attach_to_commit(
+        &self,
+        commit: git2::Oid,
+        buffer: &PendingBuffer,
+    ) -> Result<Option<AIAttribution>> {
+        let repo = Repository::open(&self.repo_root).context("Failed to open repository")?;
+        let commit = repo
+            .find_commit(commit)
+            .with_context(|| format!("Commit {} not found", commit))?;
+
+        let tree = commit.tree()?;
+        let parent_trees = commit
+            .parents()
+            .map(|parent| parent.tree())
+            .collect::<Result<Vec<_>, _>>()?;
+        let parent_ids: Vec<git2::Oid> = commit.parent_ids().collect();
+        let base_tree = merge_base_tree(&repo, &parent_ids)?;
+
+        let analysis =
+            self.analyze_commit(&repo, buffer, &tree, &parent_trees, base_tree.as_ref())?;
+        if analysis.interrupted {
+            anyhow::bail!(
+                "Analysis interrupted after {}/{} files",
+                analysis.files_done,
+                analysis.files_total
+            );
+        }
+        if let Some(attribution) = &analysis.attribution {
+            if self.storage_backend.writes_notes() {
+                NotesStore::new(&repo)?.store_attribution(commit.id(), attribution)?;
             }
-            None => Ok(PendingStatus {
-                has_pending: false,
-                session_id: None,
-                file_count: 0,
-                line_count: 0,
-                edit_count: 0,
-                prompt_count: 0,
-                is_stale: false,
-                age: String::new(),
-                max_pending_age_hours: self.max_pending_age_hours,
-            }),
         }
+        Ok(analysis.attribution)
     }
 
-    /// Clear pending changes without committing
-    pub fn clear_pending(&self) -> Result<()> {
-        let store = PendingStore::new(&self.repo_root);
-        store.delete()
-    }
-}
-
-/// Canonicalize a path for prefix comparison.
-///
-/// If the full path doesn't exist yet, this resolves the deepest existing ancestor
-/// and re-appends the missing suffix so new files can still be matched reliably.
-fn canonicalize_for_prefix(path: &Path) -> Option<std::path::PathBuf> {
-    if let Ok(canonical) = std::fs::canonicalize(path) {
-        return Some(canonical);
-    }
+    /// Handle commit-msg: append attribution trailers to the message being committed
+    ///
+    /// Only active when `storage.backend` is `trailers` or `both`. The staged index
+    /// stands in for the commit tree, which does not exist yet. Pending state is left
+    /// untouched for post-commit. Returns whether trailers were added.
+    pub fn on_commit_msg(&self, message_path: &Path) -> Result<bool> {
+        if !self.storage_backend.writes_trailers() {
+            return Ok(false);
+        }
+        let buffer = match PendingStore::new(&self.repo_root).load()? {
+            Some(b) if b.has_changes() => b,
+            _ => return Ok(false),
+        };
 
-    let mut current = path;
-    let mut missing_components = Vec::new();
+        let repo = Repository::open(&self.repo_root).context("Failed to open repository")?;
+        let (tree, parent_ids) = staged_commit(&repo)?;
+        let parent_trees = parent_ids
+            .iter()
+            .map(|oid| repo.find_commit(*oid).and_then(|commit| commit.tree()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let base_tree = merge_base_tree(&repo, &parent_ids)?;
+
+        let Some(attribution) = self
+            .analyze_commit(&repo, &buffer, &tree, &parent_trees, base_tree.as_ref())?
+            .attribution
+        else {
+            return Ok(false);
+        };
 
-    while !current.exists() {
-        let file_name = current.file_name()?;
-        missing_components.push(file_name.to_os_string());
-        current = current.parent()?;
+        let message = std::fs::read_to_string(message_path)
+            .with_context(|| format!("Failed to read {}", message_path.display()))?;
+        std::fs::write(
+            message_path,
+            TrailerGenerator::apply_to_commit_message(&message, &attribution),
+        )
+        .with_context(|| format!("Failed to write {}", message_path.display()))?;
+        Ok(true)
     }
 
-    let mut canonical_base = std::fs::canonicalize(current).ok()?;
-    for component in missing_components.iter().rev() {
-        canonical_base.push(component);
+    /// Run the post-commit analysis against the staged index without committing
+    ///
+    /// Returns the attribution post-commit would attach if the index were committed
+    /// now. The pending buffer and notes are left untouched; only the staged tree is
+    /// written to the object database, as for commit-msg. `None` without pending edits.
+    pub fn preview(&self) -> Result<Option<CommitPreview>> {
+        let buffer = match PendingStore::new(&self.repo_root).load()? {
+            Some(b) if b.has_changes() => b,
+            _ => return Ok(None),
+        };
+        warn_desynced_files(&detect_desynced_files(&buffer, &self.repo_root));
+
+        let repo = Repository::open(&self.repo_root).context("Failed to open repository")?;
+        let (tree, parent_ids) = staged_commit(&repo)?;
+        let parent_trees = parent_ids
+            .iter()
+            .map(|oid| repo.find_commit(*oid).and_then(|commit| commit.tree()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let base_tree = merge_base_tree(&repo, &parent_ids)?;
+
+        let mut renamed: Vec<(String, String)> = build_rename_map(&repo, &tree, &parent_trees)?
+            .into_iter()
+            .filter(|(old, _)| buffer.file_histories.contains_key(old))
+            .collect();
+        renamed.sort();
+
+        let analysis =
+            self.analyze_commit(&repo, &buffer, &tree, &parent_trees, base_tree.as_ref())?;
+        if analysis.interrupted {
+            anyhow::bail!(
+                "Preview interrupted after {}/{} files",
+                analysis.files_done,
+                analysis.files_total
+            );
+        }
+        let mut still_pending: Vec<String> = analysis.remaining_histories.into_keys().collect();
+        still_pending.sort();
+
+        Ok(Some(CommitPreview {
+            attribution: analysis.attribution,
+            renamed,
+            still_pending,
+            parent_count: parent_ids.len(),
+            storage_backend: self.storage_backend,
+        }))
+    }
+
+    /// Three-way analysis of pending edits against a commit tree
+    ///
+    /// For merge commits (several parents, `base_tree` set to the merge base) only
+    /// lines that are in none of the parents or the base count as written for the
+    /// commit: files taken unchanged from one side are consumed without attribution,
+    /// and lines the resolution kept from either side are `Original`.
+    ///
+    /// A commit without parents (the initial import of a generated project) takes
+    /// a fast path: files the AI created in one edit and that are committed unchanged
+    /// are attributed as AI outright instead of being analyzed one by one.
+    fn analyze_commit(
+        &self,
+        repo: &Repository,
+        buffer: &PendingBuffer,
+        tree: &git2::Tree,
+        parent_trees: &[git2::Tree],
+        base_tree: Option<&git2::Tree>,
+    ) -> Result<CommitAnalysis> {
+        let is_merge = parent_trees.len() > 1;
+        let is_root = parent_trees.is_empty();
+        // Build rename map (old -> new) to preserve attribution across moves
+        let rename_map = build_rename_map(repo, tree, parent_trees)?;
+        let changed_paths = build_changed_paths(repo, tree, parent_trees)?;
+        let rename_sources: std::collections::HashMap<&str, &str> = rename_map
+            .iter()
+            .map(|(old, new)| (new.as_str(), old.as_str()))
+            .collect();
+
+        let mut file_results = Vec::new();
+        let mut remaining_histories = std::collections::HashMap::new();
+        let mut processed_prompt_indices = HashSet::new();
+        let mut remaining_prompt_indices = HashSet::new();
+        let mut used_plan_mode = false;
+        let mut subagent_count = 0u32;
+        let mut tool_calls: std::collections::HashMap<u32, Vec<ToolCall>> =
+            std::collections::HashMap::new();
+        let mut progress = AnalysisProgress::new(
+            buffer.file_histories.len(),
+            self.show_progress && atty::is(atty::Stream::Stderr),
+        );
+        let mut interrupted = false;
+        let mut files_done = 0;
+
+        for (path, history) in &buffer.file_histories {
+            let Some(committed_path) = resolve_committed_path(path, &changed_paths, &rename_map)
+            else {
+                for edit in &history.edits {
+                    remaining_prompt_indices.insert(edit.prompt_index);
+                }
+                remaining_histories.insert(path.clone(), history.clone());
+                progress.skip_file();
+                continue;
+            };
+
+            // After an interruption, only sort out which files stay pending
+            if !interrupted && self.cancelled() {
+                interrupted = true;
+                files_done = progress.done();
+            }
+            if interrupted {
+                continue;
+            }
+            progress.start_file();
+
+            // Get the committed content for this file
+            let blob = match tree.get_path(&repo_path::to_path(&committed_path)) {
+                Ok(entry) => repo.find_blob(entry.id())?,
+                Err(_) => {
+                    // File was part of commit metadata but does not exist in final tree
+                    // (for example, deleted file). Consume it from pending state.
+                    progress.skip_file();
+                    continue;
+                }
+            };
+
+            // Taken as-is from one side of a merge: nothing was written for this commit
+            if is_merge && matches_any_tree(parent_trees, &committed_path, blob.id()) {
+                progress.skip_file();
+                continue;
+            }
+
+            // A file edited under its new name after `git mv` has no HEAD content, so
+            // its original snapshot is empty; anchor it on the rename's pre-image instead
+            let inherited;
+            let history = match rename_sources
+                .get(committed_path.as_str())
+                .filter(|_| history.was_new_file)
+                .and_then(|old_path| rename_preimage(repo, parent_trees, old_path))
+            {
+                Some(preimage) => {
+                    inherited = inherit_rename_preimage(history, preimage);
+                    &inherited
+                }
+                None => history,
+            };
+
+            // Binary assets and lockfiles get whole-file attribution; everything else
+            // goes through three-way line analysis
+            let mut result = match encoding::decode(blob.content()) {
+                Some(committed) if !self.binary_paths.is_match(&committed_path) => {
+                    let normalizer = self.normalizers.for_path(&committed_path);
+                    let new_file = is_root
+                        .then(|| ThreeWayAnalyzer::analyze_new_file(history, &committed.text))
+                        .flatten();
+                    if let Some(result) = new_file {
+                        result
+                    } else if self.exceeds_analysis_limit(&committed.text) {
+                        let result = ThreeWayAnalyzer::analyze_bounded(
+                            history,
+                            &committed.text,
+                            self.max_analysis_lines,
+                            normalizer,
+                        );
+                        if let Some(warning) = &result.analysis_warning {
+                            eprintln!("whogitit: Warning - {}: {}", committed_path, warning);
+                        }
+                        result
+                    } else {
+                        ThreeWayAnalyzer::analyze_with_block_limit(
+                            history,
+                            &committed.text,
+                            self.similarity_threshold,
+                            normalizer,
+                            self.max_block_lines,
+                        )
+                    }
+                }
+                _ => ThreeWayAnalyzer::analyze_binary(history, blob.content()),
+            };
+            // Bounded results only carry line ranges; merge re-attribution needs lines
+            if is_merge && result.binary.is_none() && result.analysis_warning.is_none() {
+                let ancestors: Vec<String> = parent_trees
+                    .iter()
+                    .chain(base_tree)
+                    .filter_map(|tree| tree_text(repo, tree, &committed_path))
+                    .collect();
+                let ancestors: Vec<&str> = ancestors.iter().map(String::as_str).collect();
+                ThreeWayAnalyzer::mark_merge_inherited(&mut result, &ancestors);
+            }
+            if committed_path != *path {
+                result.path = committed_path;
+            }
+            match self.granularity {
+                _ if result.analysis_warning.is_some() => {}
+                AnalysisGranularity::Line => result.attach_blocks(),
+                AnalysisGranularity::Hunk => result.collapse_to_blocks(),
+            }
+            if !history.secret_findings.is_empty() && result.binary.is_none() {
+                if let Some(committed) = encoding::decode(blob.content()) {
+                    result.secret_findings =
+                        self.confirm_secret_findings(history, &result, &committed.text);
+                }
+            }
+            let file = result.path.clone();
+            progress.finish_file(&file);
+            file_results.push(result);
+
+            for edit in &history.edits {
+                processed_prompt_indices.insert(edit.prompt_index);
+                let context = &edit.context;
+                if context.tool_use_id.is_some() || context.message_id.is_some() {
+                    tool_calls
+                        .entry(edit.prompt_index)
+                        .or_default()
+                        .push(ToolCall {
+                            edit_id: edit.edit_id.clone(),
+                            file: file.clone(),
+                            tool: edit.tool.clone(),
+                            tool_use_id: context.tool_use_id.clone(),
+                            message_id: context.message_id.clone(),
+                        });
+                }
+                if edit.context.plan_mode {
+                    used_plan_mode = true;
+                }
+                if edit.context.agent_depth > 0 {
+                    subagent_count += 1;
+                }
+            }
+        }
+
+        progress.finish();
+        if interrupted {
+            return Ok(CommitAnalysis {
+                attribution: None,
+                remaining_histories,
+                remaining_prompt_indices,
+                interrupted,
+                files_done,
+                files_total: progress.total(),
+            });
+        }
+
+        let attribution = (!file_results.is_empty()).then(|| {
+            let attribution_prompts =
+                filter_prompt_records(&buffer.session.prompts, &processed_prompt_indices);
+
+            AIAttribution {
+                version: SCHEMA_VERSION,
+                session: SessionMetadata {
+                    session_id: buffer.session.session_id.clone(),
+                    model: buffer.session.model.clone(),
+                    started_at: buffer.session.started_at.clone(),
+                    prompt_count: attribution_prompts.len() as u32,
+                    used_plan_mode,
+                    subagent_count,
+                    label: buffer.session.label.clone(),
+                },
+                prompts: attribution_prompts
+                    .iter()
+                    .map(|p| PromptInfo {
+                        index: p.index,
+                        text: p.text.clone(),
+                        timestamp: p.timestamp.clone(),
+                        affected_files: p.affected_files.clone(),
+                        hash: Some(p.hash.clone().unwrap_or_else(|| prompt_hash(&p.text))),
+                        privacy: p.privacy,
+                        tool_calls: tool_calls.remove(&p.index).unwrap_or_default(),
+                    })
+                    .collect(),
+                files: file_results,
+            }
+        });
+
+        Ok(CommitAnalysis {
+            attribution,
+            remaining_histories,
+            remaining_prompt_indices,
+            interrupted,
+            files_done: progress.done(),
+            files_total: progress.total(),
+        })
+    }
+
+    /// Make a path relative to the repo root
+    fn make_relative_path(&self, path: &str) -> Result<String> {
+        let input_path = repo_path::to_path(path);
+        if !input_path.is_absolute() {
+            return Ok(path.to_string());
+        }
+        let input_path = input_path.as_path();
+
+        // Fast path: exact prefix match against the repo root.
+        if let Ok(relative) = input_path.strip_prefix(&self.repo_root) {
+            return Ok(repo_path::from_path(relative));
+        }
+
+        // Handle aliased absolute paths (e.g. /var vs /private/var on macOS)
+        // by canonicalizing both paths before prefix comparison.
+        let canonical_repo =
+            canonicalize_for_prefix(&self.repo_root).unwrap_or_else(|| self.repo_root.clone());
+        if let Some(canonical_input) = canonicalize_for_prefix(input_path) {
+            if let Ok(relative) = canonical_input.strip_prefix(&canonical_repo) {
+                return Ok(repo_path::from_path(relative));
+            }
+        }
+
+        anyhow::bail!(
+            "Absolute path '{}' could not be mapped under repository root '{}'.",
+            path,
+            self.repo_root.display()
+        )
+    }
+
+    /// Get current pending status
+    pub fn status(&self) -> Result<PendingStatus> {
+        let store = PendingStore::new(&self.repo_root);
+
+        // Use quiet load to avoid spurious warnings during status check
+        match store.load_quiet()? {
+            Some(buffer) => {
+                let session_id = buffer.session.session_id.clone();
+                let file_count = buffer.file_count();
+                let line_count = buffer.total_lines();
+                let edit_count = buffer.total_edits();
+                let prompt_count = buffer.session.prompt_count;
+                let mut skipped_noops: Vec<(String, u32)> = buffer
+                    .skipped_noops
+                    .iter()
+                    .map(|(path, count)| (path.clone(), *count))
+                    .collect();
+                skipped_noops.sort();
+                let has_pending = buffer.has_changes();
+                let is_stale = buffer.is_stale_hours(self.max_pending_age_hours);
+                let age = buffer.age_string();
+                let untracked_files = untracked_pending_files(&self.repo_root, &buffer);
+                let secret_findings = buffer
+                    .secret_findings()
+                    .into_iter()
+                    .map(|(path, finding)| (path.to_string(), finding.clone()))
+                    .collect();
+                Ok(PendingStatus {
+                    has_pending,
+                    session_id: Some(session_id),
+                    file_count,
+                    line_count,
+                    edit_count,
+                    prompt_count,
+                    is_stale,
+                    age,
+                    max_pending_age_hours: self.max_pending_age_hours,
+                    untracked_files,
+                    secret_findings,
+                    skipped_noops,
+                })
+            }
+            None => Ok(PendingStatus {
+                has_pending: false,
+                session_id: None,
+                file_count: 0,
+                line_count: 0,
+                edit_count: 0,
+                prompt_count: 0,
+                is_stale: false,
+                age: String::new(),
+                max_pending_age_hours: self.max_pending_age_hours,
+                untracked_files: Vec::new(),
+                secret_findings: Vec::new(),
+                skipped_noops: Vec::new(),
+            }),
+        }
+    }
+
+    /// Clear pending changes without committing
+    pub fn clear_pending(&self) -> Result<()> {
+        let store = PendingStore::new(&self.repo_root);
+        store.delete()
+    }
+
+    /// Remind about AI-created files missing from the index before a commit
+    ///
+    /// Their attribution stays pending and is dropped once the buffer goes
+    /// stale, so a forgotten `git add` silently loses it. Never blocks.
+    pub fn on_pre_commit(&self) -> Result<()> {
+        let store = PendingStore::new(&self.repo_root);
+        let Some(buffer) = store.load_quiet()? else {
+            return Ok(());
+        };
+
+        let untracked = untracked_pending_files(&self.repo_root, &buffer);
+        if untracked.is_empty() {
+            return Ok(());
+        }
+
+        eprintln!(
+            "whogitit: {} AI-edited file(s) not added to this commit:",
+            untracked.len()
+        );
+        for path in &untracked {
+            eprintln!("  {}", path);
+        }
+        eprintln!("Run 'git add' on them to keep their AI attribution.");
+
+        Ok(())
+    }
+}
+
+/// Pending files that exist in the working tree but are not in the index
+///
+/// These are typically files an AI created that were never `git add`-ed.
+/// Ignored files are skipped since they are not meant to be committed.
+fn untracked_pending_files(repo_root: &Path, buffer: &PendingBuffer) -> Vec<String> {
+    let Ok(repo) = Repository::open(repo_root) else {
+        return Vec::new();
+    };
+    let Ok(index) = repo.index() else {
+        return Vec::new();
+    };
+
+    let mut untracked: Vec<String> = buffer
+        .files()
+        .into_iter()
+        .filter(|path| repo_root.join(repo_path::to_path(path)).is_file())
+        .filter(|path| index.get_path(&repo_path::to_path(path), 0).is_none())
+        .filter(|path| {
+            !repo
+                .is_path_ignored(repo_path::to_path(path))
+                .unwrap_or(false)
+        })
+        .map(str::to_string)
+        .collect();
+    untracked.sort();
+    untracked
+}
+
+/// Canonicalize a path for prefix comparison.
+///
+/// If the full path doesn't exist yet, this resolves the deepest existing ancestor
+/// and re-appends the missing suffix so new files can still be matched reliably.
+fn canonicalize_for_prefix(path: &Path) -> Option<std::path::PathBuf> {
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        return Some(canonical);
+    }
+
+    let mut current = path;
+    let mut missing_components = Vec::new();
+
+    while !current.exists() {
+        let file_name = current.file_name()?;
+        missing_components.push(file_name.to_os_string());
+        current = current.parent()?;
+    }
+
+    let mut canonical_base = std::fs::canonicalize(current).ok()?;
+    for component in missing_components.iter().rev() {
+        canonical_base.push(component);
     }
 
     Some(canonical_base)
 }
 
+/// Result of [`CaptureHook::analyze_commit`]
+struct CommitAnalysis {
+    /// None when no pending file is part of the commit
+    attribution: Option<AIAttribution>,
+    /// Histories of files the commit did not touch
+    remaining_histories: std::collections::HashMap<String, FileEditHistory>,
+    /// Prompts referenced by the remaining histories
+    remaining_prompt_indices: HashSet<u32>,
+    /// Stopped by Ctrl-C (or the cancel flag) before every file was analyzed
+    interrupted: bool,
+    /// Pending files processed before the analysis finished or stopped
+    files_done: usize,
+    files_total: usize,
+}
+
+/// What committing the staged index would attribute (`whogitit preview`)
+#[derive(Debug)]
+pub struct CommitPreview {
+    /// None when no pending file is part of the staged changes
+    pub attribution: Option<AIAttribution>,
+    /// Pending files the commit renames (old path, new path); attribution follows them
+    pub renamed: Vec<(String, String)>,
+    /// Pending files the commit would leave pending
+    pub still_pending: Vec<String>,
+    /// Parents of the would-be commit (0 for the initial commit, 2+ during a merge)
+    pub parent_count: usize,
+    /// Where post-commit and commit-msg would record the attribution
+    pub storage_backend: StorageBackend,
+}
+
+/// Tree and parents of the commit the staged index would become
+///
+/// Writes the index tree to the object database. A merge in progress gets the
+/// merged commits as further parents.
+fn staged_commit(repo: &Repository) -> Result<(git2::Tree<'_>, Vec<git2::Oid>)> {
+    let tree_id = repo
+        .index()
+        .context("Failed to read index")?
+        .write_tree()
+        .context("Failed to write index tree")?;
+    let tree = repo.find_tree(tree_id)?;
+    let mut parent_ids = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit()?.id()],
+        Err(_) => Vec::new(), // Initial commit
+    };
+    if repo.state() == git2::RepositoryState::Merge {
+        let merge_head = std::fs::read_to_string(repo.path().join("MERGE_HEAD"))
+            .context("Failed to read MERGE_HEAD")?;
+        for line in merge_head.lines().filter(|l| !l.trim().is_empty()) {
+            parent_ids.push(git2::Oid::from_str(line.trim())?);
+        }
+    }
+    Ok((tree, parent_ids))
+}
+
+/// Reduce a pending buffer to the edits a commit did not consume
+fn keep_remaining(
+    buffer: &mut PendingBuffer,
+    remaining_histories: std::collections::HashMap<String, FileEditHistory>,
+    remaining_prompt_indices: &HashSet<u32>,
+) {
+    buffer.file_histories = remaining_histories;
+    buffer.session.prompts =
+        filter_prompt_records(&buffer.session.prompts, remaining_prompt_indices);
+    buffer.session.prompt_count = buffer.session.prompts.len() as u32;
+    buffer.prompt_counter = next_prompt_index(&buffer.session.prompts);
+    buffer.total_redactions = buffer
+        .session
+        .prompts
+        .iter()
+        .map(|p| p.redaction_events.len() as u32)
+        .sum();
+}
+
 fn build_rename_map(
     repo: &Repository,
-    head: &git2::Commit,
+    new_tree: &git2::Tree,
+    parent_trees: &[git2::Tree],
 ) -> Result<std::collections::HashMap<String, String>> {
     let mut map = std::collections::HashMap::new();
 
-    let new_tree = head.tree()?;
-
-    for parent_idx in 0..head.parent_count() {
-        let parent = match head.parent(parent_idx) {
-            Ok(p) => p,
-            Err(_) => continue,
-        };
-
-        let old_tree = parent.tree()?;
-
+    for old_tree in parent_trees {
         let mut opts = DiffOptions::new();
-        let mut diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut opts))?;
+        let mut diff = repo.diff_tree_to_tree(Some(old_tree), Some(new_tree), Some(&mut opts))?;
 
         let mut find_opts = DiffFindOptions::new();
         find_opts.renames_from_rewrites(true);
@@ -622,14 +1433,8 @@ fn build_rename_map(
 
         for delta in diff.deltas() {
             if delta.status() == Delta::Renamed {
-                let old_path = delta
-                    .old_file()
-                    .path()
-                    .map(|p| p.to_string_lossy().to_string());
-                let new_path = delta
-                    .new_file()
-                    .path()
-                    .map(|p| p.to_string_lossy().to_string());
+                let old_path = delta.old_file().path().map(repo_path::from_path);
+                let new_path = delta.new_file().path().map(repo_path::from_path);
                 if let (Some(old_path), Some(new_path)) = (old_path, new_path) {
                     map.entry(old_path).or_insert(new_path);
                 }
@@ -640,22 +1445,72 @@ fn build_rename_map(
     Ok(map)
 }
 
-fn build_changed_paths(repo: &Repository, head: &git2::Commit) -> Result<HashSet<String>> {
+/// Content of a renamed file's old path in the first parent tree that has it
+fn rename_preimage(
+    repo: &Repository,
+    parent_trees: &[git2::Tree],
+    old_path: &str,
+) -> Option<DecodedText> {
+    parent_trees.iter().find_map(|tree| {
+        let entry = tree.get_path(&repo_path::to_path(old_path)).ok()?;
+        let blob = repo.find_blob(entry.id()).ok()?;
+        encoding::decode(blob.content())
+    })
+}
+
+/// History whose original content is the pre-image of the rename it was recorded after
+fn inherit_rename_preimage(history: &FileEditHistory, preimage: DecodedText) -> FileEditHistory {
+    let mut history = history.clone();
+    history.original = ContentSnapshot::with_encoding(&preimage.text, preimage.encoding);
+    history.was_new_file = false;
+    history
+}
+
+/// Tree of the merge base of `parent_ids`, if there are several parents
+fn merge_base_tree<'r>(
+    repo: &'r Repository,
+    parent_ids: &[git2::Oid],
+) -> Result<Option<git2::Tree<'r>>> {
+    if parent_ids.len() < 2 {
+        return Ok(None);
+    }
+    match repo.merge_base_many(parent_ids) {
+        Ok(base) => Ok(Some(repo.find_commit(base)?.tree()?)),
+        // Unrelated histories have no merge base
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e).context("Failed to find merge base"),
+    }
+}
+
+/// Whether any of `trees` has exactly blob `id` at `path`
+fn matches_any_tree(trees: &[git2::Tree], path: &str, id: git2::Oid) -> bool {
+    trees.iter().any(|tree| {
+        tree.get_path(&repo_path::to_path(path))
+            .is_ok_and(|entry| entry.id() == id)
+    })
+}
+
+/// Text content of `path` in `tree`
+fn tree_text(repo: &Repository, tree: &git2::Tree, path: &str) -> Option<String> {
+    let entry = tree.get_path(&repo_path::to_path(path)).ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    encoding::decode(blob.content()).map(|decoded| decoded.text)
+}
+
+fn build_changed_paths(
+    repo: &Repository,
+    new_tree: &git2::Tree,
+    parent_trees: &[git2::Tree],
+) -> Result<HashSet<String>> {
     let mut changed = HashSet::new();
-    let new_tree = head.tree()?;
 
-    if head.parent_count() == 0 {
-        collect_changed_paths(repo, None, &new_tree, &mut changed)?;
+    if parent_trees.is_empty() {
+        collect_changed_paths(repo, None, new_tree, &mut changed)?;
         return Ok(changed);
     }
 
-    for parent_idx in 0..head.parent_count() {
-        let parent = match head.parent(parent_idx) {
-            Ok(p) => p,
-            Err(_) => continue,
-        };
-        let old_tree = parent.tree()?;
-        collect_changed_paths(repo, Some(&old_tree), &new_tree, &mut changed)?;
+    for old_tree in parent_trees {
+        collect_changed_paths(repo, Some(old_tree), new_tree, &mut changed)?;
     }
 
     Ok(changed)
@@ -672,10 +1527,10 @@ fn collect_changed_paths(
 
     for delta in diff.deltas() {
         if let Some(path) = delta.old_file().path() {
-            changed.insert(path.to_string_lossy().to_string());
+            changed.insert(repo_path::from_path(path));
         }
         if let Some(path) = delta.new_file().path() {
-            changed.insert(path.to_string_lossy().to_string());
+            changed.insert(repo_path::from_path(path));
         }
     }
 
@@ -700,6 +1555,21 @@ fn resolve_committed_path(
     None
 }
 
+/// Whether writing `new` over `old` leaves the file unchanged
+///
+/// Line endings and trailing newlines are ignored, as attribution compares lines.
+fn is_noop_write(old: &str, new: &str) -> bool {
+    if old == new {
+        return true;
+    }
+    let normalize = |text: &str| {
+        text.replace("\r\n", "\n")
+            .trim_end_matches('\n')
+            .to_string()
+    };
+    normalize(old) == normalize(new)
+}
+
 fn filter_prompt_records(
     prompts: &[PromptRecord],
     prompt_indices: &HashSet<u32>,
@@ -735,12 +1605,23 @@ pub struct PendingStatus {
     pub age: String,
     /// Configured maximum pending buffer age in hours
     pub max_pending_age_hours: i64,
+    /// Pending files in the working tree that are not in the index
+    pub untracked_files: Vec<String>,
+    /// Possible secrets in AI-written code (`privacy.scan_generated_code`), by path
+    pub secret_findings: Vec<(String, SecretFinding)>,
+    /// Writes skipped because they left the file unchanged, by path
+    pub skipped_noops: Vec<(String, u32)>,
 }
 
 /// Hook entry point for Claude Code integration
+///
+/// Input that doesn't parse is saved with [`bad_events::record`]; when the file
+/// path and new content can still be read from it, the edit is captured anyway.
 pub fn run_capture_hook() -> Result<()> {
     // Read input from stdin
-    let input: HookInput = serde_json::from_reader(std::io::stdin())
+    let mut raw = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut raw)
         .context("Failed to read hook input from stdin")?;
 
     // Find repo root
@@ -750,6 +1631,27 @@ pub fn run_capture_hook() -> Result<()> {
     if !is_repo_initialized(&repo_root) {
         return Ok(());
     }
+    // Checked before parsing so a paused session never saves a bad payload either
+    if capture_paused(&repo_root) {
+        return Ok(());
+    }
+
+    let input = match serde_json::from_slice::<HookInput>(&raw) {
+        Ok(input) => input,
+        Err(err) => {
+            let salvaged = salvage_hook_input(&String::from_utf8_lossy(&raw));
+            let fields = salvaged.as_ref().map_or(&[][..], |(_, fields)| fields);
+            let saved = record_bad_event(&repo_root, "capture", &raw, &err.to_string(), fields);
+            let Some((input, _)) = salvaged else {
+                anyhow::bail!("Failed to parse hook input: {}{}", err, saved);
+            };
+            eprintln!(
+                "whogitit: Warning - malformed hook input ({}){}; captured {} from salvaged fields",
+                err, saved, input.file_path
+            );
+            input
+        }
+    };
 
     // Process the change
     let hook = CaptureHook::new(&repo_root)?;
@@ -758,40 +1660,825 @@ pub fn run_capture_hook() -> Result<()> {
     Ok(())
 }
 
-/// Find the git repository root from current directory
-fn find_repo_root() -> Result<std::path::PathBuf> {
-    let current = env::current_dir()?;
-    let repo = Repository::discover(&current).context("Not in a git repository")?;
+/// Save a malformed hook payload; returns a note on where it went for messages
+fn record_bad_event(
+    repo_root: &Path,
+    source: &str,
+    raw: &[u8],
+    error: &str,
+    salvaged: &[&str],
+) -> String {
+    let Ok(repo) = Repository::open(repo_root) else {
+        return String::new();
+    };
+    match bad_events::record(repo.path(), source, raw, error, salvaged) {
+        Ok(path) => format!(" (payload saved to {})", path.display()),
+        Err(err) => {
+            eprintln!(
+                "whogitit: Warning - failed to save malformed hook payload: {:#}",
+                err
+            );
+            String::new()
+        }
+    }
+}
 
-    repo.workdir()
-        .map(|p| p.to_path_buf())
-        .context("Repository has no working directory")
+/// Recover a hook input from a payload that isn't valid JSON
+///
+/// Needs complete `file_path` and `new_content` strings; `tool`, `prompt` and
+/// `old_content` are used when present. Returns the input and the fields found.
+fn salvage_hook_input(raw: &str) -> Option<(HookInput, Vec<&'static str>)> {
+    let file_path = salvage_string_field(raw, "file_path").filter(|p| !p.is_empty())?;
+    let new_content = salvage_string_field(raw, "new_content")?;
+    let mut fields = vec!["file_path", "new_content"];
+    let mut optional = |name: &'static str| {
+        let value = salvage_string_field(raw, name);
+        if value.is_some() {
+            fields.push(name);
+        }
+        value
+    };
+    let tool = optional("tool").unwrap_or_else(|| "Edit".to_string());
+    let prompt = optional("prompt").unwrap_or_else(|| DEFAULT_PROMPT.to_string());
+    let old_content = optional("old_content");
+
+    let input = HookInput {
+        tool,
+        file_path,
+        prompt,
+        old_content_present: old_content.is_some(),
+        old_content,
+        new_content,
+        context: None,
+    };
+    Some((input, fields))
 }
 
-/// Check if the repository has been initialized with `whogitit init`
-/// by looking for the whogitit marker in the post-commit hook
-fn is_repo_initialized(repo_root: &std::path::Path) -> bool {
-    let post_commit = repo_root.join(".git/hooks/post-commit");
-    if let Ok(content) = std::fs::read_to_string(&post_commit) {
-        content.contains("whogitit")
-    } else {
-        false
+/// Value of the first complete `"key": "..."` string field in text that may not be valid JSON
+fn salvage_string_field(raw: &str, key: &str) -> Option<String> {
+    let name = format!("\"{}\"", key);
+    let mut search = 0;
+    while let Some(found) = raw[search..].find(&name) {
+        search += found + name.len();
+        let Some(value) = raw[search..].trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let Some(literal) = value.trim_start().strip_prefix('"') else {
+            continue;
+        };
+        // Find the closing quote, skipping escapes; a cut-off string is not salvaged
+        let mut escaped = false;
+        let end = literal.char_indices().find_map(|(i, c)| {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return Some(i),
+                _ => {}
+            }
+            None
+        })?;
+        return serde_json::from_str(&format!("\"{}\"", &literal[..end])).ok();
     }
+    None
 }
 
-/// Git post-commit hook entry point
-pub fn run_post_commit_hook() -> Result<()> {
+/// Generic provider (`generic-v1`) entry point
+pub fn run_generic_capture_hook() -> Result<()> {
+    let capture = GenericCaptureV1::from_reader(std::io::stdin())
+        .context("Failed to read generic-v1 capture input from stdin")?;
+
     let repo_root = find_repo_root()?;
-    let hook = CaptureHook::new(&repo_root)?;
 
+    // Only capture in repos that have been initialized with `whogitit init`
+    if !is_repo_initialized(&repo_root) {
+        return Ok(());
+    }
+
+    let session_id = capture.session.clone();
+    let model = capture.model_info();
+    let hook = CaptureHook::new(&repo_root)?;
+    hook.on_file_change_for_session(capture.into_hook_input(), session_id.as_deref(), model)?;
+
+    Ok(())
+}
+
+/// Inline completion provider (`inline-v1`) entry point
+pub fn run_inline_capture_hook() -> Result<()> {
+    let capture = InlineCaptureV1::from_reader(std::io::stdin())
+        .context("Failed to read inline-v1 capture input from stdin")?;
+
+    let repo_root = find_repo_root()?;
+
+    // Only capture in repos that have been initialized with `whogitit init`
+    if !is_repo_initialized(&repo_root) {
+        return Ok(());
+    }
+
+    let session_id = capture.session.clone();
+    let model = capture.model_info();
+    let hook = CaptureHook::new(&repo_root)?;
+    hook.on_file_change_for_session(
+        capture.into_hook_input()?,
+        session_id.as_deref(),
+        Some(model),
+    )?;
+
+    Ok(())
+}
+
+/// Cursor provider (`cursor`) entry point
+pub fn run_cursor_capture_hook() -> Result<()> {
+    let mut raw = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut raw)
+        .context("Failed to read Cursor hook event from stdin")?;
+    let event = CursorHookEvent::from_slice(&raw)?;
+
+    // Prompt hooks must answer, or Cursor holds the prompt back
+    if event.hook_event_name == "beforeSubmitPrompt" {
+        println!("{{\"continue\":true}}");
+    }
+
+    let Some(repo) = event
+        .repo_hint()
+        .and_then(|hint| Repository::discover(hint).ok())
+    else {
+        return Ok(());
+    };
+    let Some(repo_root) = repo.workdir().map(Path::to_path_buf) else {
+        return Ok(());
+    };
+
+    // Only capture in repos that have been initialized with `whogitit init`
+    if !is_repo_initialized(&repo_root) {
+        return Ok(());
+    }
+
+    match event.hook_event_name.as_str() {
+        "beforeSubmitPrompt" => {
+            if let (Some(conversation), Some(prompt)) = (&event.conversation_id, &event.prompt) {
+                cursor::remember_prompt(repo.path(), conversation, prompt)?;
+            }
+        }
+        "afterFileEdit" => {
+            let Some(file_path) = event.file_path.clone() else {
+                return Ok(());
+            };
+            let Some(current) = encoding::read_text_file(Path::new(&file_path)) else {
+                return Ok(());
+            };
+            let prompt = event
+                .conversation_id
+                .as_deref()
+                .and_then(|conversation| cursor::recall_prompt(repo.path(), conversation))
+                .unwrap_or_else(|| DEFAULT_PROMPT.to_string());
+            let old_content = cursor::revert_edits(&current.text, &event.edits);
+            let input = HookInput {
+                tool: "Edit".to_string(),
+                file_path,
+                prompt,
+                old_content_present: old_content.is_some(),
+                old_content,
+                new_content: current.text,
+                context: Some(HookContext {
+                    message_id: event.generation_id.clone(),
+                    ..Default::default()
+                }),
+            };
+            let hook = CaptureHook::new(&repo_root)?;
+            hook.on_file_change_for_session(input, event.session_id(), Some(event.model_info()))?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Environment variable selecting the Claude Code hook phase ("pre" or "post")
+const ENV_HOOK_PHASE: &str = "WHOGITIT_HOOK_PHASE";
+/// Prompt recorded when neither the transcript nor the tool input has one
+const DEFAULT_PROMPT: &str = "AI-assisted code change";
+/// Longest prompt taken from a transcript, in bytes
+const MAX_TRANSCRIPT_PROMPT_BYTES: usize = 2000;
+/// Longest Bash command quoted in a prompt, in characters
+const MAX_BASH_PREVIEW_CHARS: usize = 200;
+/// Pre-tool snapshots older than this are removed
+const STALE_STATE_SECS: u64 = 60 * 60;
+
+/// Raw PreToolUse/PostToolUse event as Claude Code sends it to hook commands
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClaudeHookEvent {
+    /// Tool that ran (Edit, Write, Bash, ...)
+    #[serde(default, alias = "tool")]
+    pub tool_name: String,
+    /// Arguments the tool was called with
+    #[serde(default)]
+    pub tool_input: ClaudeToolInput,
+    /// Top-level file path (older event shapes)
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Session transcript (JSON Lines)
+    #[serde(default)]
+    pub transcript_path: Option<String>,
+    /// Identifier pairing the pre and post event of one tool call
+    #[serde(default, alias = "id")]
+    pub tool_use_id: Option<String>,
+}
+
+/// Fields of `tool_input` used for attribution
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClaudeToolInput {
+    #[serde(default)]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Bash command
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ClaudeHookEvent {
+    /// File an Edit/Write event targets
+    fn target_file(&self) -> Option<&str> {
+        [
+            &self.tool_input.file_path,
+            &self.tool_input.path,
+            &self.file_path,
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .find(|path| !path.is_empty())
+    }
+
+    /// Prompt recorded for Bash changes: the command's description, else the command
+    fn bash_prompt(&self) -> String {
+        let non_empty = |s: &Option<String>| s.clone().filter(|s| !s.is_empty());
+        if let Some(description) = non_empty(&self.tool_input.description) {
+            return format!("[Bash] {}", description);
+        }
+        match non_empty(&self.tool_input.command) {
+            Some(command) if command.chars().count() > MAX_BASH_PREVIEW_CHARS => {
+                let preview: String = command.chars().take(MAX_BASH_PREVIEW_CHARS).collect();
+                format!("[Bash] {}...", preview)
+            }
+            Some(command) => format!("[Bash] {}", command),
+            None => "[Bash] AI-executed shell command".to_string(),
+        }
+    }
+}
+
+/// Claude Code hook phase, from `WHOGITIT_HOOK_PHASE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HookPhase {
+    Pre,
+    Post,
+}
+
+impl HookPhase {
+    fn from_env() -> Self {
+        match env::var(ENV_HOOK_PHASE).as_deref() {
+            Ok("pre") => HookPhase::Pre,
+            _ => HookPhase::Post,
+        }
+    }
+}
+
+/// Prompt and context read from a Claude Code transcript
+#[derive(Debug, Default)]
+struct TranscriptInfo {
+    /// Last user prompt (not tool results or compaction summaries)
+    prompt: Option<String>,
+    context: HookContext,
+}
+
+impl TranscriptInfo {
+    /// Read a transcript, skipping lines that are not JSON; missing files yield defaults
+    ///
+    /// `tool_use_id` is recorded in the context together with the id of the
+    /// assistant message that issued it, when the transcript has that message.
+    fn read(path: Option<&str>, tool_use_id: Option<&str>) -> Self {
+        let tool_use_id = tool_use_id.filter(|id| !id.is_empty());
+        let Some(content) = path
+            .filter(|p| !p.is_empty())
+            .and_then(|p| std::fs::read_to_string(p).ok())
+        else {
+            return Self {
+                prompt: None,
+                context: HookContext {
+                    tool_use_id: tool_use_id.map(str::to_string),
+                    ..HookContext::default()
+                },
+            };
+        };
+        let entries: Vec<serde_json::Value> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        let mut context = transcript_context(&entries);
+        if let Some(id) = tool_use_id {
+            context.tool_use_id = Some(id.to_string());
+            context.message_id = tool_use_message_id(&entries, id);
+        }
+        Self {
+            prompt: last_user_prompt(&entries),
+            context,
+        }
+    }
+}
+
+/// Id of the assistant message holding the `tool_use` block `tool_use_id`
+fn tool_use_message_id(entries: &[serde_json::Value], tool_use_id: &str) -> Option<String> {
+    let entry = entries.iter().rev().find(|e| {
+        e["type"] == "assistant"
+            && e["message"]["content"].as_array().is_some_and(|parts| {
+                parts
+                    .iter()
+                    .any(|part| part["type"] == "tool_use" && part["id"] == tool_use_id)
+            })
+    })?;
+    entry["message"]["id"]
+        .as_str()
+        .or_else(|| entry["uuid"].as_str())
+        .map(str::to_string)
+}
+
+fn last_user_prompt(entries: &[serde_json::Value]) -> Option<String> {
+    let entry = entries.iter().rev().find(|e| {
+        e["type"] == "user" && e["toolUseResult"].is_null() && e["isCompactSummary"] != true
+    })?;
+    let text = match &entry["message"]["content"] {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(parts) => parts
+            .iter()
+            .filter(|part| part["type"] == "text")
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => return None,
+    };
+    if text.trim().is_empty() {
+        return None;
+    }
+    let mut end = text.len().min(MAX_TRANSCRIPT_PROMPT_BYTES);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(text[..end].to_string())
+}
+
+fn transcript_context(entries: &[serde_json::Value]) -> HookContext {
+    let explicit_plan_mode = entries
+        .iter()
+        .rev()
+        .find_map(|e| e["planMode"].as_bool())
+        .filter(|plan_mode| *plan_mode);
+    let plan_mode = explicit_plan_mode.unwrap_or_else(|| {
+        entries
+            .iter()
+            .rev()
+            .find(|e| e["tool_name"] == "EnterPlanMode" || e["tool_name"] == "ExitPlanMode")
+            .is_some_and(|e| e["tool_name"] == "EnterPlanMode")
+    });
+    let has_task = entries.iter().any(|e| e["tool_name"] == "Task");
+    let has_agent_id = entries.iter().any(|e| !e["agentId"].is_null());
+
+    HookContext {
+        plan_mode,
+        is_subagent: has_agent_id || has_task,
+        agent_depth: u8::from(has_task),
+        subagent_id: None,
+        tool_use_id: None,
+        message_id: None,
+    }
+}
+
+/// Turns raw Claude Code events into hook inputs
+///
+/// Events carry no file content, so the pre phase snapshots what a tool may
+/// change into `.whogitit/state` and the post phase diffs against it.
+struct RawEventCapture {
+    repo_root: std::path::PathBuf,
+    state_dir: std::path::PathBuf,
+}
+
+impl RawEventCapture {
+    fn new(repo_root: &Path) -> Result<Self> {
+        let state_dir = repo_root.join(".whogitit").join("state");
+        std::fs::create_dir_all(state_dir.join("bash")).with_context(|| {
+            format!("Failed to create state directory: {}", state_dir.display())
+        })?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            for dir in [state_dir.clone(), state_dir.join("bash")] {
+                let _ = std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700));
+            }
+        }
+        remove_stale_state(&state_dir);
+
+        Ok(Self {
+            repo_root: repo_root.to_path_buf(),
+            state_dir,
+        })
+    }
+
+    /// Snapshot what the tool may change
+    fn pre_tool(&self, event: &ClaudeHookEvent) -> Result<()> {
+        match event.tool_name.as_str() {
+            "Edit" | "Write" => {
+                let Some(path) = event.target_file().map(absolute_path) else {
+                    return Ok(());
+                };
+                let state_file = self.edit_state_file(&path);
+                if path.is_file() {
+                    std::fs::copy(&path, &state_file).with_context(|| {
+                        format!("Failed to snapshot {} before edit", path.display())
+                    })?;
+                } else {
+                    let _ = std::fs::remove_file(&state_file);
+                }
+            }
+            "Bash" => {
+                let dir = self.bash_state_dir(event);
+                std::fs::create_dir_all(&dir)?;
+                let mut manifest = Vec::new();
+                for rel_path in dirty_text_files(&self.repo_root)? {
+                    let state_file = dir.join(compute_hash(&rel_path));
+                    if std::fs::copy(
+                        self.repo_root.join(repo_path::to_path(&rel_path)),
+                        state_file,
+                    )
+                    .is_ok()
+                    {
+                        manifest.push(rel_path);
+                    }
+                }
+                std::fs::write(dir.join("manifest.txt"), manifest.join("\n"))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Changes the tool made since its snapshot
+    fn post_tool(&self, event: &ClaudeHookEvent) -> Result<Vec<HookInput>> {
+        match event.tool_name.as_str() {
+            "Edit" | "Write" => self.post_edit(event),
+            "Bash" => self.post_bash(event),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn post_edit(&self, event: &ClaudeHookEvent) -> Result<Vec<HookInput>> {
+        let Some(path) = event.target_file().map(absolute_path) else {
+            return Ok(Vec::new());
+        };
+        let state_file = self.edit_state_file(&path);
+        let old_content = encoding::read_text_file(&state_file).map(|decoded| decoded.text);
+        let _ = std::fs::remove_file(&state_file);
+
+        let Some(new_content) = encoding::read_text_file(&path).map(|decoded| decoded.text) else {
+            return Ok(Vec::new());
+        };
+        if old_content.as_deref() == Some(new_content.as_str()) {
+            return Ok(Vec::new());
+        }
+
+        let transcript = TranscriptInfo::read(
+            event.transcript_path.as_deref(),
+            event.tool_use_id.as_deref(),
+        );
+        let prompt = transcript
+            .prompt
+            .or_else(|| event.tool_input.description.clone())
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| DEFAULT_PROMPT.to_string());
+
+        Ok(vec![HookInput {
+            tool: event.tool_name.clone(),
+            file_path: repo_path::from_path(&path),
+            prompt,
+            old_content_present: old_content.is_some(),
+            old_content,
+            new_content,
+            context: Some(transcript.context),
+        }])
+    }
+
+    fn post_bash(&self, event: &ClaudeHookEvent) -> Result<Vec<HookInput>> {
+        let dir = self.bash_state_dir(event);
+        let Ok(manifest) = std::fs::read_to_string(dir.join("manifest.txt")) else {
+            return Ok(Vec::new());
+        };
+        let snapshotted: HashSet<&str> = manifest.lines().filter(|l| !l.is_empty()).collect();
+
+        let prompt = event.bash_prompt();
+        let context = TranscriptInfo::read(
+            event.transcript_path.as_deref(),
+            event.tool_use_id.as_deref(),
+        )
+        .context;
+        let input = |rel_path: &str, old_content: Option<String>, new_content: String| HookInput {
+            tool: "Bash".to_string(),
+            file_path: rel_path.to_string(),
+            prompt: prompt.clone(),
+            old_content_present: old_content.is_some(),
+            old_content,
+            new_content,
+            context: Some(context.clone()),
+        };
+
+        let mut inputs = Vec::new();
+        // Snapshotted files the command changed (deletions are not tracked)
+        for rel_path in &snapshotted {
+            let Some(new) =
+                encoding::read_text_file(&self.repo_root.join(repo_path::to_path(rel_path)))
+            else {
+                continue;
+            };
+            let old = encoding::read_text_file(&dir.join(compute_hash(rel_path)));
+            if old.as_ref().map(|o| &o.text) != Some(&new.text) {
+                inputs.push(input(rel_path, old.map(|o| o.text), new.text));
+            }
+        }
+        // Files the command created
+        for rel_path in dirty_text_files(&self.repo_root)? {
+            if snapshotted.contains(rel_path.as_str()) {
+                continue;
+            }
+            if let Some(new) =
+                encoding::read_text_file(&self.repo_root.join(repo_path::to_path(&rel_path)))
+            {
+                if !new.text.is_empty() {
+                    inputs.push(input(&rel_path, None, new.text));
+                }
+            }
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+        Ok(inputs)
+    }
+
+    fn edit_state_file(&self, path: &Path) -> std::path::PathBuf {
+        self.state_dir
+            .join(compute_hash(&repo_path::from_path(path)))
+    }
+
+    /// Snapshot directory of one Bash call, keyed by its tool use id
+    fn bash_state_dir(&self, event: &ClaudeHookEvent) -> std::path::PathBuf {
+        let id = event
+            .tool_use_id
+            .clone()
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| {
+                format!(
+                    "bash_{}",
+                    chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+                )
+            });
+        self.state_dir.join("bash").join(compute_hash(&id))
+    }
+}
+
+/// Absolute, canonical form of a path from a tool event
+fn absolute_path(path: &str) -> std::path::PathBuf {
+    let path = Path::new(path);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    absolute.canonicalize().unwrap_or(absolute)
+}
+
+/// Modified, staged and untracked text files outside whogitit state, relative to the repository root
+fn dirty_text_files(repo_root: &Path) -> Result<Vec<String>> {
+    let repo = Repository::open(repo_root).context("Failed to open repository")?;
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let changed = git2::Status::WT_NEW
+        | git2::Status::WT_MODIFIED
+        | git2::Status::WT_RENAMED
+        | git2::Status::INDEX_NEW
+        | git2::Status::INDEX_MODIFIED
+        | git2::Status::INDEX_RENAMED;
+
+    let mut files: Vec<String> = repo
+        .statuses(Some(&mut options))?
+        .iter()
+        .filter(|entry| entry.status().intersects(changed))
+        .map(|entry| repo_path::encode(entry.path_bytes()))
+        .filter(|path| is_capturable(path))
+        .filter(|path| {
+            encoding::read_text_file(&repo_root.join(repo_path::to_path(path))).is_some()
+        })
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Remove snapshots left behind by tool calls that never got a post event
+fn remove_stale_state(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_stale_state(&path);
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age.as_secs() > STALE_STATE_SECS);
+        if stale {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+/// Raw Claude Code event entry point (`capture --stdin --raw-claude-event`)
+///
+/// The capture hook script pipes the event through unchanged, so parsing, file
+/// snapshots and transcript reading all happen here rather than in jq.
+pub fn run_raw_claude_event_hook() -> Result<()> {
+    let mut raw = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut raw)
+        .context("Failed to read Claude Code hook event from stdin")?;
+
+    let repo_root = find_repo_root()?;
+
+    // Only capture in repos that have been initialized with `whogitit init`
+    if !is_repo_initialized(&repo_root) {
+        return Ok(());
+    }
+    if capture_paused(&repo_root) {
+        return Ok(());
+    }
+
+    let event: ClaudeHookEvent = match serde_json::from_slice(&raw) {
+        Ok(event) => event,
+        Err(err) => {
+            let saved =
+                record_bad_event(&repo_root, "raw-claude-event", &raw, &err.to_string(), &[]);
+            anyhow::bail!("Failed to parse Claude Code hook event: {}{}", err, saved);
+        }
+    };
+
+    let capture = RawEventCapture::new(&repo_root)?;
+    match HookPhase::from_env() {
+        HookPhase::Pre => capture.pre_tool(&event),
+        HookPhase::Post => {
+            let inputs = capture.post_tool(&event)?;
+            if inputs.is_empty() {
+                return Ok(());
+            }
+            let hook = CaptureHook::new(&repo_root)?;
+            for input in inputs {
+                let path = input.file_path.clone();
+                if let Err(err) = hook.on_file_change(input) {
+                    eprintln!("whogitit: Warning - failed to capture {}: {}", path, err);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Find the git repository root from current directory
+pub(crate) fn find_repo_root() -> Result<std::path::PathBuf> {
+    let current = env::current_dir()?;
+    let repo = Repository::discover(&current).context("Not in a git repository")?;
+
+    repo.workdir()
+        .map(|p| p.to_path_buf())
+        .context("Repository has no working directory")
+}
+
+/// Whether `whogitit pause` is in effect, noting the skipped edit on stderr
+fn capture_paused(repo_root: &Path) -> bool {
+    match CapturePause::active_for_workdir(repo_root) {
+        Some(pause) => {
+            eprintln!(
+                "whogitit: Capture paused {}; edit not recorded",
+                pause.window()
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Check if the repository has been initialized with `whogitit init`
+/// by looking for the whogitit marker in the post-commit hook
+pub(crate) fn is_repo_initialized(repo_root: &std::path::Path) -> bool {
+    let post_commit = repo_root.join(".git/hooks/post-commit");
+    if let Ok(content) = std::fs::read_to_string(&post_commit) {
+        content.contains("whogitit")
+    } else {
+        false
+    }
+}
+
+/// Git post-commit hook entry point
+pub fn run_post_commit_hook(max_analysis_lines: Option<usize>, quiet: bool) -> Result<()> {
+    let repo_root = find_repo_root()?;
+    let mut hook = CaptureHook::new(&repo_root)?.with_quiet(quiet);
+    if let Some(max_analysis_lines) = max_analysis_lines {
+        hook = hook.with_max_analysis_lines(max_analysis_lines);
+    }
+
+    let _sigint = interrupt::SigintGuard::install();
     hook.on_post_commit()?;
 
+    if let Ok(repo) = Repository::open(&repo_root) {
+        let interrupted = InterruptedAnalysis::list(repo.path()).len();
+        if interrupted > 0 && !quiet {
+            eprintln!(
+                "whogitit: {} interrupted analysis(es) waiting; run 'whogitit worker --resume'",
+                interrupted
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Git pre-commit hook entry point
+pub fn run_pre_commit_hook() -> Result<()> {
+    let repo_root = find_repo_root()?;
+    let hook = CaptureHook::new(&repo_root)?;
+
+    hook.on_pre_commit()?;
+
+    Ok(())
+}
+
+/// Git pre-push hook entry point
+///
+/// Advisory only: warns when the notes about to be pushed carry prompts to a
+/// remote that doesn't match `privacy.prompt_visibility`, never blocks the push.
+pub fn run_pre_push_hook(remote: &str, url: &str) -> Result<()> {
+    let repo_root = find_repo_root()?;
+    let config = WhogititConfig::load_or_warn(&repo_root);
+
+    if let Some(advisory) = visibility::check_remote(&config, remote, url) {
+        eprintln!(
+            "whogitit: Warning - attribution notes with prompts will be pushed to {}",
+            advisory
+        );
+    }
+
+    Ok(())
+}
+
+/// Git commit-msg hook entry point
+pub fn run_commit_msg_hook(message_path: &Path) -> Result<()> {
+    let repo_root = find_repo_root()?;
+    let hook = CaptureHook::new(&repo_root)?;
+
+    hook.on_commit_msg(message_path)?;
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_salvage_hook_input_from_truncated_payload() {
+        let raw = r#"{"tool": "Write", "file_path": "src/a.rs", "new_content": "fn a() {\n    \"x\"\n}\n", "context": {"session_id": "abc"#;
+        assert!(serde_json::from_str::<HookInput>(raw).is_err());
+
+        let (input, fields) = salvage_hook_input(raw).unwrap();
+        assert_eq!(input.tool, "Write");
+        assert_eq!(input.file_path, "src/a.rs");
+        assert_eq!(input.new_content, "fn a() {\n    \"x\"\n}\n");
+        assert_eq!(input.prompt, DEFAULT_PROMPT);
+        assert!(!input.old_content_present);
+        assert_eq!(fields, vec!["file_path", "new_content", "tool"]);
+    }
+
+    #[test]
+    fn test_salvage_hook_input_needs_complete_content() {
+        let raw = r#"{"file_path": "src/a.rs", "new_content": "fn a() {\n  // cut off"#;
+        assert!(salvage_hook_input(raw).is_none());
+        assert!(salvage_hook_input(r#"{"new_content": "x"}"#).is_none());
+    }
     use git2::Signature;
     use tempfile::TempDir;
 
@@ -836,21 +2523,158 @@ mod tests {
     }
 
     #[test]
-    fn test_capture_hook_multiple_edits() {
+    fn test_capture_hook_skips_noop_writes() {
         let (dir, _repo) = create_test_repo();
+        std::fs::write(
+            dir.path().join(".whogitit.toml"),
+            "[analysis]\ncount_noop_prompts = true\n",
+        )
+        .unwrap();
         let hook = CaptureHook::new(dir.path()).unwrap();
-
-        // First edit
-        hook.on_file_change(HookInput {
+        let input = |prompt: &str, old: &str, new: &str| HookInput {
             tool: "Write".to_string(),
             file_path: "test.rs".to_string(),
-            prompt: "Create file".to_string(),
-            old_content: None,
-            old_content_present: false,
-            new_content: "line1\n".to_string(),
+            prompt: prompt.to_string(),
+            old_content: Some(old.to_string()),
+            old_content_present: true,
+            new_content: new.to_string(),
             context: None,
-        })
-        .unwrap();
+        };
+
+        hook.on_file_change(input("Create it", "", "fn test() {}\n"))
+            .unwrap();
+        hook.on_file_change(input("Rewrite it", "fn test() {}\n", "fn test() {}\r\n"))
+            .unwrap();
+
+        let status = hook.status().unwrap();
+        assert_eq!(status.edit_count, 1);
+        assert_eq!(status.prompt_count, 2);
+        assert_eq!(status.skipped_noops, vec![("test.rs".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_is_noop_write() {
+        assert!(is_noop_write("a\nb\n", "a\nb\n"));
+        assert!(is_noop_write("a\r\nb\r\n", "a\nb"));
+        assert!(is_noop_write("", ""));
+        assert!(!is_noop_write("a\nb\n", "a\nb \n"));
+        assert!(!is_noop_write("a\n", "\na\n"));
+    }
+
+    #[test]
+    fn test_capture_hook_skips_edits_while_paused() {
+        let (dir, repo) = create_test_repo();
+        let hook = CaptureHook::new(dir.path()).unwrap();
+        let input = |path: &str| HookInput {
+            tool: "Write".to_string(),
+            file_path: path.to_string(),
+            prompt: "Paste the API key".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: "KEY = 1\n".to_string(),
+            context: None,
+        };
+
+        CapturePause::new(Some(chrono::Duration::hours(1)), None)
+            .save(repo.path())
+            .unwrap();
+        hook.on_file_change(input("secret.rs")).unwrap();
+        assert!(!hook.status().unwrap().has_pending);
+
+        CapturePause::remove(repo.path()).unwrap();
+        hook.on_file_change(input("after.rs")).unwrap();
+        assert_eq!(hook.status().unwrap().file_count, 1);
+    }
+
+    #[test]
+    fn test_capture_hook_generic_provider_session_and_model() {
+        let (dir, _repo) = create_test_repo();
+        let hook = CaptureHook::new(dir.path()).unwrap();
+
+        let json = r#"{"tool":"Write","file":"gen.rs","after":"fn g() {}\n",
+            "prompt":"Add g","model":"gpt-5-codex","provider":"openai","session":"0b6f3c52-1d7e-4f7a-8a43-9c2e51d6b0aa"}"#;
+        let capture = GenericCaptureV1::from_reader(json.as_bytes()).unwrap();
+        let session_id = capture.session.clone();
+        let model = capture.model_info();
+        hook.on_file_change_for_session(capture.into_hook_input(), session_id.as_deref(), model)
+            .unwrap();
+
+        let buffer = PendingStore::new(dir.path()).load().unwrap().unwrap();
+        assert_eq!(
+            buffer.session.session_id,
+            "0b6f3c52-1d7e-4f7a-8a43-9c2e51d6b0aa"
+        );
+        assert_eq!(buffer.session.model.id, "gpt-5-codex");
+        assert_eq!(buffer.session.model.provider, "openai");
+        assert_eq!(buffer.file_count(), 1);
+    }
+
+    #[test]
+    fn test_inline_completion_attributes_only_completed_lines() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        let hook = CaptureHook::new(repo_root).unwrap();
+
+        // Human typed the function; the completion filled in one line of the body
+        let document = "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+        let json = serde_json::json!({
+            "file": "add.rs",
+            "range": {
+                "start": { "line": 1, "character": 0 },
+                "end": { "line": 1, "character": 9 },
+            },
+            "text": "    a + b",
+            "document": document,
+            "model": "copilot-gpt-4o",
+            "provider": "github",
+        })
+        .to_string();
+        let capture = InlineCaptureV1::from_reader(json.as_bytes()).unwrap();
+        let model = capture.model_info();
+        hook.on_file_change_for_session(capture.into_hook_input().unwrap(), None, Some(model))
+            .unwrap();
+        std::fs::write(repo_root.join("add.rs"), document).unwrap();
+
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("add.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Add add", &tree, &[&head])
+                .unwrap();
+        }
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        assert_eq!(attribution.session.model.id, "copilot-gpt-4o");
+        let file = &attribution.files[0];
+        let ai_lines: Vec<u32> = file
+            .lines
+            .iter()
+            .filter(|l| l.source.is_ai())
+            .map(|l| l.line_number)
+            .collect();
+        assert_eq!(ai_lines, vec![2]);
+        assert_eq!(file.summary.human_lines, 2);
+    }
+
+    #[test]
+    fn test_capture_hook_multiple_edits() {
+        let (dir, _repo) = create_test_repo();
+        let hook = CaptureHook::new(dir.path()).unwrap();
+
+        // First edit
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "test.rs".to_string(),
+            prompt: "Create file".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: "line1\n".to_string(),
+            context: None,
+        })
+        .unwrap();
 
         // Second edit to same file
         hook.on_file_change(HookInput {
@@ -871,193 +2695,850 @@ mod tests {
     }
 
     #[test]
-    fn test_capture_hook_status_empty() {
-        let (dir, _repo) = create_test_repo();
+    fn test_capture_hook_status_empty() {
+        let (dir, _repo) = create_test_repo();
+        let hook = CaptureHook::new(dir.path()).unwrap();
+
+        let status = hook.status().unwrap();
+        assert!(!status.has_pending);
+        assert_eq!(status.file_count, 0);
+    }
+
+    #[test]
+    fn test_capture_hook_clear() {
+        let (dir, _repo) = create_test_repo();
+        let hook = CaptureHook::new(dir.path()).unwrap();
+
+        // Add a change
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "test.rs".to_string(),
+            prompt: "test".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: "content\n".to_string(),
+            context: None,
+        })
+        .unwrap();
+
+        assert!(hook.status().unwrap().has_pending);
+
+        // Clear
+        hook.clear_pending().unwrap();
+        assert!(!hook.status().unwrap().has_pending);
+    }
+
+    #[test]
+    fn test_post_commit_rename_preserves_attribution_path() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+
+        // Create and commit initial file
+        let old_path = repo_root.join("old.rs");
+        std::fs::write(&old_path, "line1\n").unwrap();
+
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("old.rs")).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Add old.rs", &tree, &[&head])
+                .unwrap();
+        }
+
+        let hook = CaptureHook::new(repo_root).unwrap();
+        hook.on_file_change(HookInput {
+            tool: "Edit".to_string(),
+            file_path: "old.rs".to_string(),
+            prompt: "Add line".to_string(),
+            old_content: Some("line1\n".to_string()),
+            old_content_present: true,
+            new_content: "line1\nline2\n".to_string(),
+            context: None,
+        })
+        .unwrap();
+
+        // Rename file and commit
+        let new_path = repo_root.join("new.rs");
+        std::fs::rename(&old_path, &new_path).unwrap();
+
+        {
+            let mut index = repo.index().unwrap();
+            index.remove_path(std::path::Path::new("old.rs")).unwrap();
+            index.add_path(std::path::Path::new("new.rs")).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Rename old.rs to new.rs",
+                &tree,
+                &[&head],
+            )
+            .unwrap();
+        }
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        assert_eq!(attribution.files.len(), 1);
+        assert_eq!(attribution.files[0].path, "new.rs");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path_round_trips_through_notes_and_blame() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        let name = std::path::Path::new(std::ffi::OsStr::from_bytes(b"caf\xe9.rs"));
+        std::fs::write(repo_root.join(name), "fn ai() {}\n").unwrap();
+
+        // Absolute hook paths arrive in their encoded string form
+        let hook = CaptureHook::new(repo_root).unwrap();
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: repo_path::from_path(&repo_root.join(name)),
+            prompt: "Add ai".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: "fn ai() {}\n".to_string(),
+            context: None,
+        })
+        .unwrap();
+
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(name).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Add café", &tree, &[&head])
+                .unwrap();
+        }
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        assert_eq!(attribution.files[0].path, r#""caf\351.rs""#);
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let stored = NotesStore::new(&repo)
+            .unwrap()
+            .fetch_attribution(head)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            repo_path::to_path(&stored.files[0].path).as_os_str(),
+            name.as_os_str()
+        );
+
+        let blame = crate::core::AIBlamer::new(&repo)
+            .unwrap()
+            .blame(&stored.files[0].path, None)
+            .unwrap();
+        assert!(blame.lines[0].source.is_ai());
+    }
+
+    #[test]
+    fn test_post_commit_rename_and_edit_inherits_preimage() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+
+        let original = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\nfn e() {}\n";
+        std::fs::write(repo_root.join("old.rs"), original).unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("old.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Add old.rs", &tree, &[&head])
+                .unwrap();
+        }
+
+        // `git mv` first, then the edit is captured under the new path, which has
+        // no content in HEAD
+        std::fs::rename(repo_root.join("old.rs"), repo_root.join("new.rs")).unwrap();
+        let edited = format!("{}fn f() {{}}\nfn g() {{}}\n", original);
+        let hook = CaptureHook::new(repo_root).unwrap();
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "new.rs".to_string(),
+            prompt: "Add f and g".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: edited.clone(),
+            context: None,
+        })
+        .unwrap();
+        std::fs::write(repo_root.join("new.rs"), &edited).unwrap();
+
+        {
+            let mut index = repo.index().unwrap();
+            index.remove_path(std::path::Path::new("old.rs")).unwrap();
+            index.add_path(std::path::Path::new("new.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Move and extend", &tree, &[&head])
+                .unwrap();
+        }
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        let file = &attribution.files[0];
+        assert_eq!(file.path, "new.rs");
+        assert_eq!(file.summary.original_lines, 5);
+        assert_eq!(file.summary.ai_lines, 2);
+    }
+
+    #[test]
+    fn test_post_commit_preserves_pending_for_uncommitted_files() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+
+        // Add baseline files and commit them.
+        std::fs::write(repo_root.join("a.rs"), "a0\n").unwrap();
+        std::fs::write(repo_root.join("b.rs"), "b0\n").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("a.rs")).unwrap();
+            index.add_path(std::path::Path::new("b.rs")).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Add baseline files",
+                &tree,
+                &[&head],
+            )
+            .unwrap();
+        }
+
+        let hook = CaptureHook::new(repo_root).unwrap();
+
+        // Capture edits for both files.
+        hook.on_file_change(HookInput {
+            tool: "Edit".to_string(),
+            file_path: "a.rs".to_string(),
+            prompt: "Update a".to_string(),
+            old_content: Some("a0\n".to_string()),
+            old_content_present: true,
+            new_content: "a1\n".to_string(),
+            context: None,
+        })
+        .unwrap();
+
+        hook.on_file_change(HookInput {
+            tool: "Edit".to_string(),
+            file_path: "b.rs".to_string(),
+            prompt: "Update b".to_string(),
+            old_content: Some("b0\n".to_string()),
+            old_content_present: true,
+            new_content: "b1\n".to_string(),
+            context: None,
+        })
+        .unwrap();
+
+        std::fs::write(repo_root.join("a.rs"), "a1\n").unwrap();
+        std::fs::write(repo_root.join("b.rs"), "b1\n").unwrap();
+
+        // Commit only a.rs.
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("a.rs")).unwrap();
+            index.write().unwrap();
+            let tree_id = index.write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Commit only a.rs",
+                &tree,
+                &[&head],
+            )
+            .unwrap();
+        }
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        assert_eq!(attribution.files.len(), 1);
+        assert_eq!(attribution.files[0].path, "a.rs");
+
+        // b.rs should remain pending for a later commit.
+        let store = PendingStore::new(repo_root);
+        let remaining = store.load_quiet().unwrap().unwrap();
+        assert!(remaining.get_file_history("a.rs").is_none());
+        assert!(remaining.get_file_history("b.rs").is_some());
+
+        let status = hook.status().unwrap();
+        assert!(status.has_pending);
+        assert_eq!(status.file_count, 1);
+    }
+
+    #[test]
+    fn test_post_commit_merge_attributes_only_resolution_lines() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        let commit = |files: &[(&str, &str)], parents: &[git2::Oid], update_head: bool| {
+            let mut index = repo.index().unwrap();
+            for (path, content) in files {
+                std::fs::write(repo_root.join(path), content).unwrap();
+                index.add_path(std::path::Path::new(path)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = parents
+                .iter()
+                .map(|id| repo.find_commit(*id).unwrap())
+                .collect();
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            repo.commit(
+                update_head.then_some("HEAD"),
+                &sig,
+                &sig,
+                "commit",
+                &tree,
+                &parents,
+            )
+            .unwrap()
+        };
+
+        let initial = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let base = commit(&[("f.rs", "a\nb\nc\n"), ("g.rs", "g0\n")], &[initial], true);
+        let side = commit(
+            &[("f.rs", "a\nside\nc\n"), ("g.rs", "g-side\n")],
+            &[base],
+            false,
+        );
+        let main = commit(&[("f.rs", "a\nmain\nc\n"), ("g.rs", "g0\n")], &[base], true);
+
+        // Conflict resolved by the AI: keeps both sides plus a new line; g.rs takes theirs
+        let hook = CaptureHook::new(repo_root).unwrap();
+        let resolved = "a\nmain\nside\nresolved\nc\n";
+        for (path, old, new) in [
+            ("f.rs", "a\nmain\nc\n", resolved),
+            ("g.rs", "g0\n", "g-side\n"),
+        ] {
+            hook.on_file_change(HookInput {
+                tool: "Edit".to_string(),
+                file_path: path.to_string(),
+                prompt: "Resolve conflicts".to_string(),
+                old_content: Some(old.to_string()),
+                old_content_present: true,
+                new_content: new.to_string(),
+                context: None,
+            })
+            .unwrap();
+        }
+        commit(
+            &[("f.rs", resolved), ("g.rs", "g-side\n")],
+            &[main, side],
+            true,
+        );
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        assert_eq!(attribution.files.len(), 1);
+        let file = &attribution.files[0];
+        assert_eq!(file.path, "f.rs");
+        let sources: Vec<bool> = file
+            .lines
+            .iter()
+            .map(|l| matches!(l.source, crate::capture::snapshot::LineSource::AI { .. }))
+            .collect();
+        assert_eq!(sources, vec![false, false, false, true, false]);
+        assert_eq!(file.summary.original_lines, 4);
+
+        // g.rs was consumed, not left pending
+        assert!(PendingStore::new(repo_root).load_quiet().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_post_commit_initial_import_marks_generated_files_ai() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        // Small limit: analyzed files would fall back to bounded analysis
+        let hook = CaptureHook::new(dir.path())
+            .unwrap()
+            .with_max_analysis_lines(2);
+
+        let files = [
+            (
+                "Cargo.toml",
+                "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+            ),
+            ("src/main.rs", "fn main() {\n    app::run();\n}\n"),
+            ("src/lib.rs", "pub fn run() {\n    println!(\"hi\");\n}\n"),
+        ];
+        for (path, content) in files {
+            hook.on_file_change(HookInput {
+                tool: "Write".to_string(),
+                file_path: path.to_string(),
+                prompt: "Scaffold the project".to_string(),
+                old_content: None,
+                old_content_present: false,
+                new_content: content.to_string(),
+                context: None,
+            })
+            .unwrap();
+            std::fs::create_dir_all(dir.path().join(path).parent().unwrap()).unwrap();
+            std::fs::write(dir.path().join(path), content).unwrap();
+        }
+        // A human touches one generated file before the first commit
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub fn run() {\n    println!(\"hi\");\n}\n// TODO: config\n",
+        )
+        .unwrap();
+
+        {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Initial import", &tree, &[])
+                .unwrap();
+        }
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        assert_eq!(attribution.files.len(), 3);
+        let file = |path: &str| attribution.files.iter().find(|f| f.path == path).unwrap();
+        for path in ["Cargo.toml", "src/main.rs"] {
+            let generated = file(path);
+            assert!(generated.analysis_warning.is_none());
+            assert_eq!(generated.summary.ai_lines, 3);
+            assert!(generated.lines.iter().all(|l| l.prompt_index == Some(0)));
+        }
+        // Changed after generation: analyzed (bounded, given the limit)
+        assert!(file("src/lib.rs").analysis_warning.is_some());
+    }
+
+    #[test]
+    fn test_post_commit_records_tool_call_ids() {
+        let (dir, repo) = create_test_repo();
         let hook = CaptureHook::new(dir.path()).unwrap();
+        let content = "pub fn retry() {}\n";
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "retry.rs".to_string(),
+            prompt: "Add retries".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: content.to_string(),
+            context: Some(HookContext {
+                tool_use_id: Some("toolu_01".to_string()),
+                message_id: Some("msg_01".to_string()),
+                ..HookContext::default()
+            }),
+        })
+        .unwrap();
+        std::fs::write(dir.path().join("retry.rs"), content).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("retry.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Add retries", &tree, &[&parent])
+            .unwrap();
 
-        let status = hook.status().unwrap();
-        assert!(!status.has_pending);
-        assert_eq!(status.file_count, 0);
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        let calls = &attribution.prompts[0].tool_calls;
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].file, "retry.rs");
+        assert_eq!(calls[0].tool, "Write");
+        assert_eq!(calls[0].tool_use_id.as_deref(), Some("toolu_01"));
+        assert_eq!(calls[0].message_id.as_deref(), Some("msg_01"));
+        // Lines reference the same edit
+        let LineSource::AI { edit_id } = &attribution.files[0].lines[0].source else {
+            panic!("expected an AI line");
+        };
+        assert_eq!(*edit_id, calls[0].edit_id);
     }
 
     #[test]
-    fn test_capture_hook_clear() {
-        let (dir, _repo) = create_test_repo();
+    fn test_interrupted_post_commit_resumes_later() {
+        let (dir, repo) = create_test_repo();
         let hook = CaptureHook::new(dir.path()).unwrap();
-
-        // Add a change
+        let content = "pub fn retry() {}\n";
         hook.on_file_change(HookInput {
             tool: "Write".to_string(),
-            file_path: "test.rs".to_string(),
-            prompt: "test".to_string(),
+            file_path: "retry.rs".to_string(),
+            prompt: "Add retries".to_string(),
             old_content: None,
             old_content_present: false,
-            new_content: "content\n".to_string(),
+            new_content: content.to_string(),
             context: None,
         })
         .unwrap();
+        std::fs::write(dir.path().join("retry.rs"), content).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("retry.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let head = repo
+            .commit(Some("HEAD"), &sig, &sig, "Add retries", &tree, &[&parent])
+            .unwrap();
 
-        assert!(hook.status().unwrap().has_pending);
-
-        // Clear
-        hook.clear_pending().unwrap();
+        hook.cancel_flag().store(true, Ordering::SeqCst);
+        assert!(hook.on_post_commit().unwrap().is_none());
+        let notes = NotesStore::new(&repo).unwrap();
+        assert!(notes.fetch_attribution(head).unwrap().is_none());
         assert!(!hook.status().unwrap().has_pending);
+
+        let markers = InterruptedAnalysis::list(repo.path());
+        assert_eq!(markers.len(), 1);
+        let marker = InterruptedAnalysis::load(&markers[0]).unwrap();
+        assert_eq!(marker.commit, head.to_string());
+        assert_eq!((marker.files_done, marker.files_total), (0, 1));
+
+        let hook = CaptureHook::new(dir.path()).unwrap();
+        let attribution = hook.resume_interrupted(&marker).unwrap().unwrap();
+        assert_eq!(attribution.files[0].path, "retry.rs");
+        assert!(notes.fetch_attribution(head).unwrap().is_some());
     }
 
     #[test]
-    fn test_post_commit_rename_preserves_attribution_path() {
+    fn test_post_commit_lockfile_gets_binary_attribution() {
         let (dir, repo) = create_test_repo();
         let repo_root = dir.path();
+        let hook = CaptureHook::new(repo_root).unwrap();
 
-        // Create and commit initial file
-        let old_path = repo_root.join("old.rs");
-        std::fs::write(&old_path, "line1\n").unwrap();
+        let lock = "[[package]]\nname = \"serde\"\n";
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "Cargo.lock".to_string(),
+            prompt: "Add serde".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: lock.to_string(),
+            context: None,
+        })
+        .unwrap();
+        std::fs::write(repo_root.join("Cargo.lock"), lock).unwrap();
 
         {
             let mut index = repo.index().unwrap();
-            index.add_path(std::path::Path::new("old.rs")).unwrap();
+            index.add_path(std::path::Path::new("Cargo.lock")).unwrap();
             index.write().unwrap();
-            let tree_id = index.write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
             let sig = Signature::now("Test", "test@test.com").unwrap();
             let head = repo.head().unwrap().peel_to_commit().unwrap();
-            repo.commit(Some("HEAD"), &sig, &sig, "Add old.rs", &tree, &[&head])
+            repo.commit(Some("HEAD"), &sig, &sig, "Add lockfile", &tree, &[&head])
                 .unwrap();
         }
 
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        let file = &attribution.files[0];
+        assert!(file.lines.is_empty());
+        let binary = file.binary.as_ref().unwrap();
+        assert!(binary.ai_generated);
+        assert_eq!(binary.tool, "Write");
+    }
+
+    #[test]
+    fn test_post_commit_binary_file_matches_raw_bytes() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
         let hook = CaptureHook::new(repo_root).unwrap();
+
+        let png: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\xff\xd8\xc3";
+        std::fs::write(repo_root.join("logo.png"), png).unwrap();
         hook.on_file_change(HookInput {
-            tool: "Edit".to_string(),
-            file_path: "old.rs".to_string(),
-            prompt: "Add line".to_string(),
-            old_content: Some("line1\n".to_string()),
-            old_content_present: true,
-            new_content: "line1\nline2\n".to_string(),
+            tool: "Bash".to_string(),
+            file_path: "logo.png".to_string(),
+            prompt: "Add a logo".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: String::from_utf8_lossy(png).into_owned(),
             context: None,
         })
         .unwrap();
 
-        // Rename file and commit
-        let new_path = repo_root.join("new.rs");
-        std::fs::rename(&old_path, &new_path).unwrap();
-
         {
             let mut index = repo.index().unwrap();
-            index.remove_path(std::path::Path::new("old.rs")).unwrap();
-            index.add_path(std::path::Path::new("new.rs")).unwrap();
+            index.add_path(std::path::Path::new("logo.png")).unwrap();
             index.write().unwrap();
-            let tree_id = index.write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
             let sig = Signature::now("Test", "test@test.com").unwrap();
             let head = repo.head().unwrap().peel_to_commit().unwrap();
-            repo.commit(
-                Some("HEAD"),
-                &sig,
-                &sig,
-                "Rename old.rs to new.rs",
-                &tree,
-                &[&head],
-            )
-            .unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Add logo", &tree, &[&head])
+                .unwrap();
         }
 
         let attribution = hook.on_post_commit().unwrap().unwrap();
-        assert_eq!(attribution.files.len(), 1);
-        assert_eq!(attribution.files[0].path, "new.rs");
+        let binary = attribution.files[0].binary.as_ref().unwrap();
+        assert!(binary.ai_generated);
+        assert_eq!(binary.tool, "Bash");
     }
 
     #[test]
-    fn test_post_commit_preserves_pending_for_uncommitted_files() {
+    fn test_post_commit_large_file_uses_bounded_analysis() {
         let (dir, repo) = create_test_repo();
         let repo_root = dir.path();
+        let hook = CaptureHook::new(repo_root)
+            .unwrap()
+            .with_max_analysis_lines(3);
+
+        let ai_text = "fn one() {}\nfn two() {}\nfn three() {}\n";
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "gen.rs".to_string(),
+            prompt: "Generate".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: ai_text.to_string(),
+            context: None,
+        })
+        .unwrap();
+        let committed = format!("{}// added by hand\n", ai_text);
+        std::fs::write(repo_root.join("gen.rs"), &committed).unwrap();
 
-        // Add baseline files and commit them.
-        std::fs::write(repo_root.join("a.rs"), "a0\n").unwrap();
-        std::fs::write(repo_root.join("b.rs"), "b0\n").unwrap();
         {
             let mut index = repo.index().unwrap();
-            index.add_path(std::path::Path::new("a.rs")).unwrap();
-            index.add_path(std::path::Path::new("b.rs")).unwrap();
+            index.add_path(std::path::Path::new("gen.rs")).unwrap();
             index.write().unwrap();
-            let tree_id = index.write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
             let sig = Signature::now("Test", "test@test.com").unwrap();
             let head = repo.head().unwrap().peel_to_commit().unwrap();
             repo.commit(
                 Some("HEAD"),
                 &sig,
                 &sig,
-                "Add baseline files",
+                "Add generated file",
                 &tree,
                 &[&head],
             )
             .unwrap();
         }
 
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        let file = &attribution.files[0];
+        assert!(file.lines.is_empty());
+        assert!(file.analysis_warning.is_some());
+        assert_eq!(file.summary.ai_lines, 3);
+        assert_eq!(file.summary.human_lines, 1);
+        assert_eq!(file.blocks.as_ref().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_stale_buffer_is_archived_on_capture() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        std::fs::write(
+            repo_root.join(".whogitit.toml"),
+            "[privacy]\naudit_log = true\n\n[analysis]\nstale_action = \"archive\"\n",
+        )
+        .unwrap();
+        let store = PendingStore::new(repo_root);
+        let mut stale = PendingBuffer::new(
+            &uuid::Uuid::new_v4().to_string(),
+            "claude-opus-4-5-20251101",
+        );
+        stale.record_edit("old.rs", None, "old\n", "Write", "Old work", None);
+        stale.session.started_at = (chrono::Utc::now() - chrono::Duration::hours(48)).to_rfc3339();
+        store.save(&stale).unwrap();
+
         let hook = CaptureHook::new(repo_root).unwrap();
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "new.rs".to_string(),
+            prompt: "New work".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: "new\n".to_string(),
+            context: None,
+        })
+        .unwrap();
 
-        // Capture edits for both files.
+        let buffer = store.load_quiet().unwrap().unwrap();
+        assert_eq!(buffer.files(), vec!["new.rs"]);
+
+        let archives = PendingStore::archived_buffers(repo.path());
+        assert_eq!(archives.len(), 1);
+        let archived: PendingBuffer =
+            serde_json::from_str(&std::fs::read_to_string(&archives[0]).unwrap()).unwrap();
+        assert_eq!(archived.files(), vec!["old.rs"]);
+
+        let events = AuditLog::new(repo_root).read_all().unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.event == crate::storage::audit::AuditEventType::PendingExpired));
+    }
+
+    #[test]
+    fn test_secret_findings_recorded_and_confirmed_at_commit() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        std::fs::write(
+            repo_root.join(".whogitit.toml"),
+            "[privacy]\nscan_generated_code = true\n",
+        )
+        .unwrap();
+        let hook = CaptureHook::new(repo_root).unwrap();
+
+        let ai_text = "fn main() {}\nlet aws_secret_access_key = \"wJalrXUtnFEMI\";\n";
         hook.on_file_change(HookInput {
-            tool: "Edit".to_string(),
-            file_path: "a.rs".to_string(),
-            prompt: "Update a".to_string(),
-            old_content: Some("a0\n".to_string()),
-            old_content_present: true,
-            new_content: "a1\n".to_string(),
+            tool: "Write".to_string(),
+            file_path: "keys.rs".to_string(),
+            prompt: "Add keys".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: ai_text.to_string(),
             context: None,
         })
         .unwrap();
+        let status = hook.status().unwrap();
+        assert!(status
+            .secret_findings
+            .iter()
+            .any(|(path, f)| path == "keys.rs"
+                && f.line_number == 2
+                && f.pattern_name == "AWS_KEY"));
+
+        // The file is left as written; a human line above moves the finding down
+        let committed = format!("// keys\n{}", ai_text);
+        std::fs::write(repo_root.join("keys.rs"), &committed).unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("keys.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Add keys", &tree, &[&head])
+                .unwrap();
+        }
+
+        let attribution = hook.on_post_commit().unwrap().unwrap();
+        let findings = &attribution.files[0].secret_findings;
+        assert!(findings
+            .iter()
+            .any(|f| f.line_number == 3 && f.pattern_name == "AWS_KEY"));
+        assert!(findings.iter().all(|f| f.line_number == 3));
+    }
+
+    #[test]
+    fn test_utf16_file_is_decoded_from_disk_and_attributed() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        let hook = CaptureHook::new(repo_root).unwrap();
 
+        let text = "Name,Value\nalpha,1\nbeta,2\n";
+        let bytes = encoding::encode(text, TextEncoding::Utf16Le);
+        std::fs::write(repo_root.join("data.csv"), &bytes).unwrap();
+        // The shell hook drops the NULs, so the transported content is unusable
         hook.on_file_change(HookInput {
-            tool: "Edit".to_string(),
-            file_path: "b.rs".to_string(),
-            prompt: "Update b".to_string(),
-            old_content: Some("b0\n".to_string()),
-            old_content_present: true,
-            new_content: "b1\n".to_string(),
+            tool: "Write".to_string(),
+            file_path: "data.csv".to_string(),
+            prompt: "Add data".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: "\u{fffd}\u{fffd}Name,Value".to_string(),
             context: None,
         })
         .unwrap();
 
-        std::fs::write(repo_root.join("a.rs"), "a1\n").unwrap();
-        std::fs::write(repo_root.join("b.rs"), "b1\n").unwrap();
+        let buffer = PendingStore::new(repo_root).load().unwrap().unwrap();
+        let after = &buffer.get_file_history("data.csv").unwrap().edits[0].after;
+        assert_eq!(after.content, text);
+        assert_eq!(after.encoding, TextEncoding::Utf16Le);
+        assert_eq!(after.bytes(), bytes);
 
-        // Commit only a.rs.
         {
             let mut index = repo.index().unwrap();
-            index.add_path(std::path::Path::new("a.rs")).unwrap();
+            index.add_path(std::path::Path::new("data.csv")).unwrap();
             index.write().unwrap();
-            let tree_id = index.write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
             let sig = Signature::now("Test", "test@test.com").unwrap();
             let head = repo.head().unwrap().peel_to_commit().unwrap();
-            repo.commit(
-                Some("HEAD"),
-                &sig,
-                &sig,
-                "Commit only a.rs",
-                &tree,
-                &[&head],
-            )
-            .unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Add data", &tree, &[&head])
+                .unwrap();
         }
 
         let attribution = hook.on_post_commit().unwrap().unwrap();
-        assert_eq!(attribution.files.len(), 1);
-        assert_eq!(attribution.files[0].path, "a.rs");
+        let file = &attribution.files[0];
+        assert!(file.binary.is_none());
+        assert_eq!(file.summary.ai_lines, 3);
+    }
 
-        // b.rs should remain pending for a later commit.
-        let store = PendingStore::new(repo_root);
-        let remaining = store.load_quiet().unwrap().unwrap();
-        assert!(remaining.get_file_history("a.rs").is_none());
-        assert!(remaining.get_file_history("b.rs").is_some());
+    #[cfg(unix)]
+    #[test]
+    fn test_trailers_backend_writes_trailers_instead_of_notes() {
+        let (dir, repo) = create_test_repo();
+        let repo_root = dir.path();
+        std::fs::write(
+            repo_root.join(".whogitit.toml"),
+            "[storage]\nbackend = \"trailers\"\n",
+        )
+        .unwrap();
+        let hook = CaptureHook::new(repo_root).unwrap();
 
-        let status = hook.status().unwrap();
-        assert!(status.has_pending);
-        assert_eq!(status.file_count, 1);
+        let content = "fn main() {}\n";
+        hook.on_file_change(HookInput {
+            tool: "Write".to_string(),
+            file_path: "main.rs".to_string(),
+            prompt: "Add main".to_string(),
+            old_content: None,
+            old_content_present: false,
+            new_content: content.to_string(),
+            context: None,
+        })
+        .unwrap();
+        std::fs::write(repo_root.join("main.rs"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("main.rs")).unwrap();
+        index.write().unwrap();
+
+        // commit-msg runs against the staged index, before the commit exists
+        let message_path = repo_root.join(".git/COMMIT_EDITMSG");
+        std::fs::write(
+            &message_path,
+            "Add main\n# Please enter the commit message\n",
+        )
+        .unwrap();
+        assert!(hook.on_commit_msg(&message_path).unwrap());
+        let message = std::fs::read_to_string(&message_path).unwrap();
+        assert!(message.starts_with("Add main\n\nAI-Session: "));
+        assert!(message.contains("AI-Lines: 1\n"));
+        assert!(message.ends_with("# Please enter the commit message\n"));
+
+        {
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&head])
+                .unwrap();
+        }
+
+        assert!(hook.on_post_commit().unwrap().is_some());
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert!(!NotesStore::new(&repo).unwrap().has_attribution(head.id()));
+        assert!(!PendingStore::new(repo_root).exists());
     }
 
-    #[cfg(unix)]
     #[test]
     fn test_make_relative_path_accepts_symlinked_absolute_path() {
         let (dir, _repo) = create_test_repo();
@@ -1120,4 +3601,147 @@ mod tests {
         .unwrap();
         assert!(is_repo_initialized(dir.path()));
     }
+
+    #[test]
+    fn test_status_lists_pending_files_missing_from_index() {
+        let (dir, repo) = create_test_repo();
+        let hook = CaptureHook::new(dir.path()).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+
+        for path in ["staged.rs", "new.rs", "build/gen.rs", "deleted.rs"] {
+            let full = dir.path().join(path);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(&full, "fn f() {}\n").unwrap();
+            hook.on_file_change(HookInput {
+                tool: "Write".to_string(),
+                file_path: path.to_string(),
+                prompt: "Create it".to_string(),
+                old_content: None,
+                old_content_present: false,
+                new_content: "fn f() {}\n".to_string(),
+                context: None,
+            })
+            .unwrap();
+        }
+        std::fs::remove_file(dir.path().join("deleted.rs")).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.rs")).unwrap();
+        index.write().unwrap();
+
+        let status = hook.status().unwrap();
+        assert_eq!(status.untracked_files, vec!["new.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_active_session_sets_session_model_and_label() {
+        let (dir, repo) = create_test_repo();
+        let hook = CaptureHook::new(dir.path()).unwrap();
+        let session = ActiveSession::begin(
+            Some("gpt-5-codex".to_string()),
+            Some("openai".to_string()),
+            Some("ticket-123".to_string()),
+        );
+        session.save(repo.path()).unwrap();
+
+        for (path, provider_session) in [("a.rs", None), ("b.rs", Some("provider-session"))] {
+            std::fs::write(dir.path().join(path), "fn a() {}\n").unwrap();
+            hook.on_file_change_for_session(
+                HookInput {
+                    tool: "Write".to_string(),
+                    file_path: path.to_string(),
+                    prompt: "Create it".to_string(),
+                    old_content: None,
+                    old_content_present: false,
+                    new_content: "fn a() {}\n".to_string(),
+                    context: None,
+                },
+                provider_session,
+                None,
+            )
+            .unwrap();
+        }
+
+        let buffer = PendingStore::new(dir.path()).load().unwrap().unwrap();
+        assert_eq!(buffer.session.session_id, session.session_id);
+        assert_eq!(buffer.session.model.id, "gpt-5-codex");
+        assert_eq!(buffer.session.label.as_deref(), Some("ticket-123"));
+        assert_eq!(buffer.file_count(), 2);
+    }
+
+    #[test]
+    fn test_transcript_prompt_and_context() {
+        let dir = TempDir::new().unwrap();
+        let transcript = dir.path().join("transcript.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"content":"first prompt"}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"text","text":"Add"},{"type":"image"},{"type":"text","text":"retries"}]}}"#,
+            r#"{"type":"user","toolUseResult":{"ok":true},"message":{"content":"tool output"}}"#,
+            r#"{"type":"user","isCompactSummary":true,"message":{"content":"summary"}}"#,
+            "not json",
+            r#"{"tool_name":"EnterPlanMode"}"#,
+            r#"{"tool_name":"Task"}"#,
+            r#"{"type":"assistant","uuid":"u-1","message":{"id":"msg_01","content":[{"type":"text","text":"Editing"},{"type":"tool_use","id":"toolu_01","name":"Edit"}]}}"#,
+            r#"{"type":"assistant","uuid":"u-2","message":{"content":[{"type":"tool_use","id":"toolu_02","name":"Write"}]}}"#,
+        ];
+        std::fs::write(&transcript, lines.join("\n")).unwrap();
+
+        let info = TranscriptInfo::read(transcript.to_str(), None);
+        assert_eq!(info.prompt.as_deref(), Some("Add retries"));
+        assert!(info.context.plan_mode);
+        assert!(info.context.is_subagent);
+        assert_eq!(info.context.agent_depth, 1);
+        assert!(info.context.tool_use_id.is_none());
+
+        // The issuing message is found by its tool_use block; `uuid` stands in for a missing id
+        let info = TranscriptInfo::read(transcript.to_str(), Some("toolu_01"));
+        assert_eq!(info.context.tool_use_id.as_deref(), Some("toolu_01"));
+        assert_eq!(info.context.message_id.as_deref(), Some("msg_01"));
+        let info = TranscriptInfo::read(transcript.to_str(), Some("toolu_02"));
+        assert_eq!(info.context.message_id.as_deref(), Some("u-2"));
+
+        let missing = TranscriptInfo::read(Some("/nonexistent/transcript.jsonl"), Some("toolu_9"));
+        assert!(missing.prompt.is_none());
+        assert!(!missing.context.plan_mode);
+        assert_eq!(missing.context.tool_use_id.as_deref(), Some("toolu_9"));
+        assert!(missing.context.message_id.is_none());
+    }
+
+    #[test]
+    fn test_raw_claude_events_snapshot_and_diff() {
+        let (dir, _repo) = create_test_repo();
+        let root = dir.path().canonicalize().unwrap();
+        let file = root.join("lib.rs");
+        std::fs::write(&file, "fn a() {}\n").unwrap();
+        let capture = RawEventCapture::new(&root).unwrap();
+
+        let edit: ClaudeHookEvent = serde_json::from_value(serde_json::json!({
+            "tool_name": "Edit",
+            "tool_input": { "file_path": file, "description": "rename" },
+        }))
+        .unwrap();
+        capture.pre_tool(&edit).unwrap();
+        std::fs::write(&file, "fn b() {}\n").unwrap();
+        let inputs = capture.post_tool(&edit).unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].old_content.as_deref(), Some("fn a() {}\n"));
+        assert_eq!(inputs[0].new_content, "fn b() {}\n");
+        assert_eq!(inputs[0].prompt, "rename");
+        // The snapshot is consumed
+        assert!(capture.post_tool(&edit).unwrap()[0].old_content.is_none());
+
+        let bash: ClaudeHookEvent = serde_json::from_value(serde_json::json!({
+            "tool_name": "Bash",
+            "tool_use_id": "toolu_01",
+            "tool_input": { "command": "./gen.sh" },
+        }))
+        .unwrap();
+        capture.pre_tool(&bash).unwrap();
+        std::fs::write(root.join("generated.rs"), "pub const X: u8 = 1;\n").unwrap();
+        let inputs = capture.post_tool(&bash).unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].tool, "Bash");
+        assert_eq!(inputs[0].prompt, "[Bash] ./gen.sh");
+        assert!(inputs[0].file_path.ends_with("generated.rs"));
+        assert!(!inputs[0].old_content_present);
+    }
 }
@@ -0,0 +1,216 @@
+//! Inline completion capture input (`inline-v1`)
+//!
+//! Ghost-text completions accepted in an editor never pass through an agent's
+//! Edit/Write hooks. An editor extension reports each accepted completion to
+//! `whogitit capture --stdin --provider inline-v1`; it is recorded as a micro-edit
+//! whose `before` is the document without the completion, so only the lines the
+//! completion touched are attributed to AI, not everything typed around it.
+
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::hook::HookInput;
+pub use crate::capture::snapshot::INLINE_COMPLETION_TOOL;
+use crate::core::attribution::ModelInfo;
+
+/// Schema version accepted by the inline provider
+pub const INLINE_SCHEMA_VERSION: u8 = 1;
+
+/// Prompt recorded for inline completions (they have no user prompt)
+pub const INLINE_COMPLETION_PROMPT: &str = "[inline completion]";
+
+/// Provider name recorded when the event names no provider
+const UNKNOWN_PROVIDER: &str = "unknown";
+
+fn default_version() -> u8 {
+    INLINE_SCHEMA_VERSION
+}
+
+/// Zero-based position; `character` counts UTF-16 code units, as in LSP and VS Code
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// Range of the inserted text in the document after acceptance
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// An accepted inline completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InlineCaptureV1 {
+    /// Schema version (defaults to 1)
+    #[serde(default = "default_version")]
+    pub version: u8,
+    /// File path, absolute or relative to the repository root
+    pub file: String,
+    /// Where `text` sits in `document`
+    pub range: Range,
+    /// Text the completion inserted
+    pub text: String,
+    /// Text the completion replaced (e.g. the word prefix already typed)
+    #[serde(default)]
+    pub replaced: String,
+    /// Full document text after acceptance (unsaved editor buffer)
+    pub document: String,
+    /// Model identifier (e.g. "copilot-gpt-4o")
+    pub model: String,
+    /// Model provider (e.g. "github")
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Session identifier (UUID) grouping related changes
+    #[serde(default)]
+    pub session: Option<String>,
+}
+
+impl InlineCaptureV1 {
+    /// Parse and validate an inline-v1 document
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        let capture: Self =
+            serde_json::from_reader(reader).context("Invalid inline-v1 capture JSON")?;
+        capture.validate()?;
+        Ok(capture)
+    }
+
+    /// Validate schema version, required fields and that `range` holds `text`
+    pub fn validate(&self) -> Result<()> {
+        if self.version != INLINE_SCHEMA_VERSION {
+            anyhow::bail!(
+                "Unsupported inline capture version {} (expected {})",
+                self.version,
+                INLINE_SCHEMA_VERSION
+            );
+        }
+        if self.file.trim().is_empty() {
+            anyhow::bail!("inline-v1 capture requires a non-empty 'file'");
+        }
+        if self.model.trim().is_empty() {
+            anyhow::bail!("inline-v1 capture requires a non-empty 'model'");
+        }
+        if let Some(session) = &self.session {
+            if uuid::Uuid::parse_str(session).is_err() {
+                anyhow::bail!("inline-v1 'session' must be a UUID, got '{}'", session);
+            }
+        }
+        self.inserted_span()?;
+        Ok(())
+    }
+
+    /// Byte span of the inserted text in `document`
+    fn inserted_span(&self) -> Result<(usize, usize)> {
+        let start = byte_offset(&self.document, self.range.start)
+            .context("inline-v1 'range.start' is outside the document")?;
+        let end = byte_offset(&self.document, self.range.end)
+            .context("inline-v1 'range.end' is outside the document")?;
+        if end < start || self.document[start..end] != self.text {
+            anyhow::bail!("inline-v1 'range' does not cover 'text' in the document");
+        }
+        Ok((start, end))
+    }
+
+    /// Document content before the completion was accepted
+    pub fn document_before(&self) -> Result<String> {
+        let (start, end) = self.inserted_span()?;
+        Ok(format!(
+            "{}{}{}",
+            &self.document[..start],
+            self.replaced,
+            &self.document[end..]
+        ))
+    }
+
+    /// Model information
+    pub fn model_info(&self) -> ModelInfo {
+        ModelInfo::new(
+            &self.model,
+            self.provider.as_deref().unwrap_or(UNKNOWN_PROVIDER),
+        )
+    }
+
+    /// Convert into the internal hook input
+    pub fn into_hook_input(self) -> Result<HookInput> {
+        let before = self.document_before()?;
+        Ok(HookInput {
+            tool: INLINE_COMPLETION_TOOL.to_string(),
+            file_path: self.file,
+            prompt: INLINE_COMPLETION_PROMPT.to_string(),
+            old_content_present: true,
+            old_content: Some(before),
+            new_content: self.document,
+            context: None,
+        })
+    }
+}
+
+/// Byte offset of an LSP position in `text`
+fn byte_offset(text: &str, position: Position) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        line_start += text[line_start..].find('\n')? + 1;
+    }
+    let line_end = text[line_start..]
+        .find('\n')
+        .map_or(text.len(), |i| line_start + i);
+
+    let mut units = 0;
+    for (idx, ch) in text[line_start..line_end].char_indices() {
+        if units == position.character {
+            return Some(line_start + idx);
+        }
+        units += ch.len_utf16() as u32;
+    }
+    (units == position.character).then_some(line_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(document: &str, range: ((u32, u32), (u32, u32)), text: &str) -> String {
+        serde_json::json!({
+            "file": "src/lib.rs",
+            "range": {
+                "start": { "line": range.0 .0, "character": range.0 .1 },
+                "end": { "line": range.1 .0, "character": range.1 .1 },
+            },
+            "text": text,
+            "document": document,
+            "model": "copilot-gpt-4o",
+            "provider": "github",
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_multiline_completion_before_and_after() {
+        let document = "fn main() {\n    let é = 1;\n    println!(\"{}\", é);\n}\n";
+        // Completion inserted after `let é = 1;` (é is one UTF-16 unit)
+        let json = event(document, ((1, 14), (2, 22)), "\n    println!(\"{}\", é);");
+        let capture = InlineCaptureV1::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(capture.model_info().provider, "github");
+
+        let input = capture.into_hook_input().unwrap();
+        assert_eq!(input.tool, INLINE_COMPLETION_TOOL);
+        assert_eq!(
+            input.old_content.as_deref(),
+            Some("fn main() {\n    let é = 1;\n}\n")
+        );
+        assert_eq!(input.new_content, document);
+    }
+
+    #[test]
+    fn test_rejects_range_not_covering_text() {
+        let json = event("let x = 1;\n", ((0, 0), (0, 3)), "var");
+        let err = InlineCaptureV1::from_reader(json.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("does not cover"));
+
+        let json = event("let x = 1;\n", ((3, 0), (3, 3)), "let");
+        assert!(InlineCaptureV1::from_reader(json.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Interrupting post-commit analysis (Ctrl-C) without losing attribution
+//!
+//! While [`SigintGuard`] is held, SIGINT only raises a flag; the analysis loop
+//! checks it between files. An interrupted commit's pending edits are saved as
+//! an [`InterruptedAnalysis`] under `.git/whogitit/interrupted/<commit>.json`,
+//! and `whogitit worker --resume` analyzes them later and attaches the note.
+//! A second Ctrl-C falls back to the default handler and ends the process.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::pending::PendingBuffer;
+
+/// Interrupted analyses (inside the git dir)
+const INTERRUPTED_DIR: &str = "whogitit/interrupted";
+
+static SIGINT_RECEIVED: AtomicBool = AtomicBool::new(false);
+
+/// Whether SIGINT arrived while a [`SigintGuard`] was held
+pub fn sigint_received() -> bool {
+    SIGINT_RECEIVED.load(Ordering::SeqCst)
+}
+
+/// Turns SIGINT into a flag until dropped
+pub struct SigintGuard {
+    #[cfg(unix)]
+    previous: libc::sighandler_t,
+}
+
+#[cfg(unix)]
+extern "C" fn on_sigint(_: libc::c_int) {
+    SIGINT_RECEIVED.store(true, Ordering::SeqCst);
+    // Only storing the flag and resetting the handler: both are async-signal-safe
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+    }
+}
+
+impl SigintGuard {
+    pub fn install() -> Self {
+        SIGINT_RECEIVED.store(false, Ordering::SeqCst);
+        #[cfg(unix)]
+        {
+            let handler = on_sigint as extern "C" fn(libc::c_int);
+            let previous = unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) };
+            Self { previous }
+        }
+        #[cfg(not(unix))]
+        Self {}
+    }
+}
+
+impl Drop for SigintGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            libc::signal(libc::SIGINT, self.previous);
+        }
+        SIGINT_RECEIVED.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Pending edits of a commit whose analysis was interrupted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterruptedAnalysis {
+    /// Commit the attribution belongs to
+    pub commit: String,
+    /// When the analysis was interrupted (ISO 8601)
+    pub interrupted_at: String,
+    /// Files analyzed before the interruption
+    pub files_done: usize,
+    /// Pending files at the start of the analysis
+    pub files_total: usize,
+    /// Pending buffer as it was when the commit was made
+    pub buffer: PendingBuffer,
+}
+
+impl InterruptedAnalysis {
+    pub fn new(commit: &str, files_done: usize, files_total: usize, buffer: PendingBuffer) -> Self {
+        Self {
+            commit: commit.to_string(),
+            interrupted_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            files_done,
+            files_total,
+            buffer,
+        }
+    }
+
+    /// Marker path for a commit
+    pub fn path(git_dir: &Path, commit: &str) -> PathBuf {
+        git_dir
+            .join(INTERRUPTED_DIR)
+            .join(format!("{}.json", commit))
+    }
+
+    /// Write the marker (0600, like the pending buffer it contains)
+    pub fn save(&self, git_dir: &Path) -> Result<PathBuf> {
+        let path = Self::path(git_dir, &self.commit);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create whogitit directory")?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)
+            .context("Failed to write interrupted analysis")?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))
+                .context("Failed to set permissions on interrupted analysis")?;
+        }
+        fs::rename(&tmp, &path).context("Failed to store interrupted analysis")?;
+        Ok(path)
+    }
+
+    /// Marker files, oldest first
+    pub fn list(git_dir: &Path) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(git_dir.join(INTERRUPTED_DIR))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+        paths
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_interrupted_analysis_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(InterruptedAnalysis::list(dir.path()).is_empty());
+
+        let buffer = PendingBuffer::new("session", "claude-opus-4-5-20251101");
+        let marker = InterruptedAnalysis::new("abc123", 2, 5, buffer);
+        let path = marker.save(dir.path()).unwrap();
+        assert_eq!(path, InterruptedAnalysis::path(dir.path(), "abc123"));
+        assert_eq!(InterruptedAnalysis::list(dir.path()), vec![path.clone()]);
+
+        let loaded = InterruptedAnalysis::load(&path).unwrap();
+        assert_eq!(loaded.commit, "abc123");
+        assert_eq!((loaded.files_done, loaded.files_total), (2, 5));
+        assert_eq!(loaded.buffer.session.session_id, "session");
+    }
+}
@@ -1,10 +1,37 @@
+pub mod aider;
+pub mod bad_events;
+#[cfg(feature = "git")]
+pub mod cursor;
 pub mod diff;
+pub mod encoding;
+#[cfg(feature = "git")]
+pub mod generic;
+#[cfg(feature = "git")]
 pub mod hook;
+#[cfg(feature = "git")]
+pub mod inline;
+#[cfg(feature = "git")]
+pub mod interrupt;
+#[cfg(feature = "git")]
+pub mod pause;
 pub mod pending;
+pub mod progress;
+pub mod recovery;
+#[cfg(feature = "git")]
+pub mod session;
 pub mod snapshot;
+#[cfg(feature = "git")]
+pub mod stash;
 pub mod threeway;
+#[cfg(feature = "git")]
+pub mod watch;
 
+#[cfg(feature = "git")]
+pub use generic::GenericCaptureV1;
+#[cfg(feature = "git")]
 pub use hook::{CaptureHook, HookInput};
+#[cfg(feature = "git")]
+pub use inline::InlineCaptureV1;
 pub use pending::{PendingBuffer, PendingStore};
 pub use snapshot::{AIEdit, ContentSnapshot, FileEditHistory, LineAttribution, LineSource};
-pub use threeway::ThreeWayAnalyzer;
+pub use threeway::{LineNormalizer, NormalizerRegistry, ThreeWayAnalyzer};
@@ -0,0 +1,238 @@
+//! Capture pause switch (`whogitit pause` / `resume`)
+//!
+//! A pause is stored at `.git/whogitit/pause.json`. While it exists (and its window,
+//! if any, has not ended) the capture entry points record nothing: no prompts, no
+//! file content, no saved hook payloads. Edits made while paused are left for the
+//! post-commit analysis, which attributes them to the human.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::privacy::WhogititConfig;
+use crate::storage::audit::AuditLog;
+
+/// Pause file (inside the git dir)
+const PAUSE_FILE: &str = "whogitit/pause.json";
+
+/// A capture pause started with `whogitit pause`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CapturePause {
+    /// When capture was paused (ISO 8601)
+    pub paused_at: String,
+    /// When capture resumes on its own (ISO 8601); None pauses until `whogitit resume`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// Why capture was paused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl CapturePause {
+    /// Pause starting now, for `duration` or until resumed
+    pub fn new(duration: Option<Duration>, reason: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            paused_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+            until: duration.map(|d| (now + d).to_rfc3339_opts(SecondsFormat::Secs, true)),
+            reason,
+        }
+    }
+
+    /// Path of the pause file for a git dir
+    pub fn path(git_dir: &Path) -> PathBuf {
+        git_dir.join(PAUSE_FILE)
+    }
+
+    /// Load the pause marker, if any (expired or not)
+    pub fn load(git_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(git_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let pause = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(pause))
+    }
+
+    /// Write the pause file
+    pub fn save(&self, git_dir: &Path) -> Result<()> {
+        let path = Self::path(git_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create whogitit directory")?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .context("Failed to write pause file")?;
+        fs::rename(&tmp, &path).context("Failed to store pause file")?;
+        Ok(())
+    }
+
+    /// Remove the pause file, returning the pause if it could be read
+    ///
+    /// An unreadable file is removed as well, so `whogitit resume` always resumes.
+    pub fn remove(git_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(git_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let pause = Self::load(git_dir).ok().flatten();
+        fs::remove_file(&path).context("Failed to remove pause file")?;
+        Ok(pause)
+    }
+
+    /// End of the pause window, if it has one
+    pub fn until_time(&self) -> Option<DateTime<Utc>> {
+        self.until
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// Whether the pause window ended before `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.until_time().is_some_and(|until| until <= now)
+    }
+
+    /// Pause in effect for a working tree, without changing anything on disk
+    ///
+    /// Used by `status`, which must not write. An unreadable marker counts as paused.
+    pub fn peek_for_workdir(repo_root: &Path) -> Option<Self> {
+        let repo = Repository::open(repo_root).ok()?;
+        match Self::load(repo.path()) {
+            Ok(pause) => pause.filter(|p| !p.is_expired_at(Utc::now())),
+            Err(_) => Some(Self::unreadable()),
+        }
+    }
+
+    /// Pause in effect for a working tree, clearing an expired marker
+    ///
+    /// The capture hook fails closed: an unreadable marker keeps capture paused
+    /// until `whogitit resume` removes it.
+    pub fn active_for_workdir(repo_root: &Path) -> Option<Self> {
+        let repo = Repository::open(repo_root).ok()?;
+        match Self::load(repo.path()) {
+            Ok(Some(pause)) if pause.is_expired_at(Utc::now()) => {
+                if Self::remove(repo.path()).is_ok() {
+                    let reason = format!(
+                        "pause window ended at {}",
+                        pause.until.as_deref().unwrap_or("?")
+                    );
+                    audit_resume(repo_root, &pause, &reason);
+                }
+                None
+            }
+            Ok(pause) => pause,
+            Err(err) => {
+                eprintln!(
+                    "whogitit: Warning - treating unreadable pause marker as paused: {:#}",
+                    err
+                );
+                Some(Self::unreadable())
+            }
+        }
+    }
+
+    /// Stand-in for a marker that exists but cannot be parsed
+    fn unreadable() -> Self {
+        Self {
+            paused_at: String::new(),
+            until: None,
+            reason: Some("unreadable pause marker".to_string()),
+        }
+    }
+
+    /// "until <time>" or "until resumed", for messages
+    pub fn window(&self) -> String {
+        match &self.until {
+            Some(until) => format!("until {}", until),
+            None => "until resumed".to_string(),
+        }
+    }
+}
+
+/// Audit log for `repo_root` if `privacy.audit_log` is enabled
+fn audit_log(repo_root: &Path) -> Option<AuditLog> {
+    let config = WhogititConfig::load(repo_root).ok()?;
+    config
+        .privacy
+        .audit_log
+        .then(|| AuditLog::new(repo_root).with_forwarding(&config.audit.forwarding))
+}
+
+/// Record the start of a pause in the audit log (when enabled)
+pub fn audit_pause(repo_root: &Path, pause: &CapturePause) {
+    let Some(log) = audit_log(repo_root) else {
+        return;
+    };
+    if let Err(e) = log.log_capture_paused(
+        &pause.paused_at,
+        pause.until.as_deref(),
+        pause.reason.as_deref(),
+    ) {
+        eprintln!("whogitit: Warning - failed to write audit event: {}", e);
+    }
+}
+
+/// Record the end of a pause in the audit log (when enabled)
+pub fn audit_resume(repo_root: &Path, pause: &CapturePause, reason: &str) {
+    let Some(log) = audit_log(repo_root) else {
+        return;
+    };
+    if let Err(e) = log.log_capture_resumed(&pause.paused_at, reason) {
+        eprintln!("whogitit: Warning - failed to write audit event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pause_round_trip_and_expiry() {
+        let dir = TempDir::new().unwrap();
+        assert!(CapturePause::load(dir.path()).unwrap().is_none());
+
+        let pause = CapturePause::new(Some(Duration::hours(2)), Some("credentials".to_string()));
+        pause.save(dir.path()).unwrap();
+        let loaded = CapturePause::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded, pause);
+        assert!(!loaded.is_expired_at(Utc::now()));
+        assert!(loaded.is_expired_at(Utc::now() + Duration::hours(3)));
+        assert!(loaded.window().starts_with("until 20"));
+
+        let open_ended = CapturePause::new(None, None);
+        assert!(!open_ended.is_expired_at(Utc::now() + Duration::days(365)));
+        assert_eq!(open_ended.window(), "until resumed");
+
+        assert_eq!(CapturePause::remove(dir.path()).unwrap(), Some(pause));
+        assert!(CapturePause::remove(dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_active_pause_clears_expired_marker_and_fails_closed() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        let mut expired = CapturePause::new(Some(Duration::minutes(5)), None);
+        expired.until = Some((Utc::now() - Duration::minutes(1)).to_rfc3339());
+        expired.save(repo.path()).unwrap();
+        assert!(CapturePause::peek_for_workdir(dir.path()).is_none());
+        assert!(CapturePause::path(repo.path()).exists());
+        assert!(CapturePause::active_for_workdir(dir.path()).is_none());
+        assert!(!CapturePause::path(repo.path()).exists());
+
+        fs::write(CapturePause::path(repo.path()), "{ not json").unwrap();
+        assert!(CapturePause::active_for_workdir(dir.path()).is_some());
+        assert!(CapturePause::peek_for_workdir(dir.path()).is_some());
+        assert!(CapturePause::remove(repo.path()).unwrap().is_none());
+        assert!(CapturePause::active_for_workdir(dir.path()).is_none());
+    }
+}
@@ -0,0 +1,115 @@
+//! Progress reporting for post-commit analysis
+//!
+//! Small commits finish before anyone would notice, so nothing is printed until
+//! the analysis has run for [`PROGRESS_DELAY`]. From then on each analyzed file
+//! gets a line on stderr with its position and timing, so a large commit no
+//! longer looks like a hung hook. `post-commit --quiet` turns this off.
+
+use std::time::{Duration, Instant};
+
+/// How long analysis runs before progress is shown
+pub const PROGRESS_DELAY: Duration = Duration::from_secs(1);
+
+/// Files processed out of the pending files of a commit
+#[derive(Debug)]
+pub struct AnalysisProgress {
+    total: usize,
+    done: usize,
+    started: Instant,
+    file_started: Instant,
+    delay: Duration,
+    enabled: bool,
+    shown: bool,
+}
+
+impl AnalysisProgress {
+    pub fn new(total: usize, enabled: bool) -> Self {
+        let now = Instant::now();
+        Self {
+            total,
+            done: 0,
+            started: now,
+            file_started: now,
+            delay: PROGRESS_DELAY,
+            enabled,
+            shown: false,
+        }
+    }
+
+    /// Files processed so far
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Mark the start of the next file
+    pub fn start_file(&mut self) {
+        self.file_started = Instant::now();
+    }
+
+    /// Count a processed file, reporting it once the delay has passed
+    pub fn finish_file(&mut self, path: &str) {
+        self.done += 1;
+        if self.enabled && self.started.elapsed() >= self.delay {
+            self.shown = true;
+            eprintln!("{}", self.render(path, self.file_started.elapsed()));
+        }
+    }
+
+    /// Count a file skipped without analysis (not reported)
+    pub fn skip_file(&mut self) {
+        self.done += 1;
+    }
+
+    fn render(&self, path: &str, elapsed: Duration) -> String {
+        format!(
+            "whogitit: [{}/{}] {} ({})",
+            self.done,
+            self.total,
+            path,
+            format_duration(elapsed)
+        )
+    }
+
+    /// Closing line, if any progress was shown
+    pub fn finish(&self) {
+        if self.shown {
+            eprintln!(
+                "whogitit: Analyzed {} file(s) in {}",
+                self.done,
+                format_duration(self.started.elapsed())
+            );
+        }
+    }
+}
+
+fn format_duration(elapsed: Duration) -> String {
+    if elapsed < Duration::from_secs(1) {
+        format!("{} ms", elapsed.as_millis())
+    } else {
+        format!("{:.1} s", elapsed.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_counts_and_renders() {
+        let mut progress = AnalysisProgress::new(3, false);
+        progress.start_file();
+        progress.finish_file("src/a.rs");
+        progress.skip_file();
+        assert_eq!((progress.done(), progress.total()), (2, 3));
+        assert_eq!(
+            progress.render("src/b.rs", Duration::from_millis(42)),
+            "whogitit: [2/3] src/b.rs (42 ms)"
+        );
+        assert_eq!(format_duration(Duration::from_millis(2500)), "2.5 s");
+        assert!(!progress.shown);
+    }
+}
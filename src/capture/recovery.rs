@@ -0,0 +1,419 @@
+//! Salvage attribution from a corrupted pending buffer
+//!
+//! A pending buffer that fails to parse is usually truncated (interrupted write,
+//! full disk) or has a damaged region. Rather than discarding the whole session,
+//! [`salvage`] walks the raw JSON, keeps every file history and edit that is still
+//! intact, and rebuilds the session (ID, model, prompts) from whatever survives,
+//! falling back to the prompt text stored on each edit.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::capture::pending::{PendingBuffer, PromptRecord, SessionInfo};
+use crate::capture::snapshot::{AIEdit, ContentSnapshot, FileEditHistory};
+use crate::core::attribution::{prompt_hash, ModelInfo};
+
+/// What [`salvage`] recovered
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct RecoveryReport {
+    /// Whether the original session ID and model were recovered
+    pub session_recovered: bool,
+    /// Files whose full edit history was recovered
+    pub files_recovered: Vec<String>,
+    /// Files recovered up to the last intact edit
+    pub files_partial: Vec<String>,
+    /// Files found in the buffer but not recoverable
+    pub files_lost: Vec<String>,
+    /// Prompt records recovered intact
+    pub prompts_recovered: usize,
+    /// Prompt records rebuilt from the prompt text stored on edits
+    pub prompts_rebuilt: usize,
+}
+
+/// A repaired buffer and what went into it
+#[derive(Debug, Clone)]
+pub struct Salvage {
+    pub buffer: PendingBuffer,
+    pub report: RecoveryReport,
+}
+
+/// Recover what can be recovered from corrupted pending buffer JSON
+///
+/// Returns None when no file history could be salvaged.
+pub fn salvage(content: &str) -> Option<Salvage> {
+    let top = object_members(content);
+    let mut report = RecoveryReport::default();
+
+    let mut file_histories = HashMap::new();
+    if let Some(raw) = top.get("file_histories") {
+        for (path, raw_history) in object_members(raw.text) {
+            match salvage_history(&raw_history) {
+                Some((history, complete)) => {
+                    if complete {
+                        report.files_recovered.push(path.clone());
+                    } else {
+                        report.files_partial.push(path.clone());
+                    }
+                    file_histories.insert(path, history);
+                }
+                None => report.files_lost.push(path),
+            }
+        }
+    }
+    if file_histories.is_empty() {
+        return None;
+    }
+    report.files_recovered.sort();
+    report.files_partial.sort();
+    report.files_lost.sort();
+
+    let session_members = top
+        .get("session")
+        .map(|raw| object_members(raw.text))
+        .unwrap_or_default();
+    let session_id = session_members
+        .get("session_id")
+        .and_then(parse::<String>)
+        .filter(|id| Uuid::parse_str(id).is_ok());
+    let model = session_members.get("model").and_then(parse::<ModelInfo>);
+    report.session_recovered = session_id.is_some() && model.is_some();
+
+    let mut prompts: BTreeMap<u32, PromptRecord> = session_members
+        .get("prompts")
+        .map(|raw| array_elements(raw.text))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(parse::<PromptRecord>)
+        .map(|p| (p.index, p))
+        .collect();
+    report.prompts_recovered = prompts.len();
+
+    // Edits carry their prompt text, so missing prompt records can be rebuilt
+    let mut edits: Vec<(&String, &AIEdit)> = file_histories
+        .iter()
+        .flat_map(|(path, h)| h.edits.iter().map(move |e| (path, e)))
+        .collect();
+    edits.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp));
+    for (path, edit) in &edits {
+        let prompt = prompts.entry(edit.prompt_index).or_insert_with(|| {
+            report.prompts_rebuilt += 1;
+            PromptRecord {
+                index: edit.prompt_index,
+                text: edit.prompt.clone(),
+                timestamp: edit.timestamp.clone(),
+                affected_files: Vec::new(),
+                hash: Some(prompt_hash(&edit.prompt)),
+                redaction_events: Vec::new(),
+                privacy: None,
+            }
+        });
+        if !prompt.affected_files.contains(path) {
+            prompt.affected_files.push((*path).clone());
+        }
+    }
+
+    let started_at = session_members
+        .get("started_at")
+        .and_then(parse::<String>)
+        .or_else(|| edits.first().map(|(_, e)| e.timestamp.clone()))
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+    let prompts: Vec<PromptRecord> = prompts.into_values().collect();
+    let prompt_counter = prompts
+        .iter()
+        .map(|p| p.index.saturating_add(1))
+        .max()
+        .unwrap_or(0)
+        .max(
+            top.get("prompt_counter")
+                .and_then(parse::<u32>)
+                .unwrap_or(0),
+        );
+
+    let buffer = PendingBuffer {
+        version: 3,
+        session: SessionInfo {
+            session_id: session_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            model: model.unwrap_or_else(|| ModelInfo::new("unknown", "unknown")),
+            started_at,
+            prompt_count: prompts.len() as u32,
+            prompts,
+            label: session_members.get("label").and_then(parse::<String>),
+        },
+        file_histories,
+        prompt_counter,
+        audit_logging_enabled: top
+            .get("audit_logging_enabled")
+            .and_then(parse::<bool>)
+            .unwrap_or(false),
+        total_redactions: top
+            .get("total_redactions")
+            .and_then(parse::<u32>)
+            .unwrap_or(0),
+        skipped_noops: top
+            .get("skipped_noops")
+            .and_then(parse::<HashMap<String, u32>>)
+            .unwrap_or_default(),
+    };
+    Some(Salvage { buffer, report })
+}
+
+/// A file history, possibly cut short after its last intact edit
+///
+/// Returns the history and whether it was complete.
+fn salvage_history(raw: &RawValue) -> Option<(FileEditHistory, bool)> {
+    if let Some(history) = parse::<FileEditHistory>(raw) {
+        return (!history.edits.is_empty()).then_some((history, true));
+    }
+
+    let members = object_members(raw.text);
+    let path = members.get("path").and_then(parse::<String>)?;
+    let original = members.get("original").and_then(parse::<ContentSnapshot>)?;
+    let raw_edits = members.get("edits").map(|raw| array_elements(raw.text))?;
+    let edits: Vec<AIEdit> = raw_edits.iter().map_while(parse::<AIEdit>).collect();
+    if edits.is_empty() {
+        return None;
+    }
+    // Cut off before `was_new_file`: an empty original means the AI created the file
+    let was_new_file = members
+        .get("was_new_file")
+        .and_then(parse::<bool>)
+        .unwrap_or(original.content.is_empty());
+    Some((
+        FileEditHistory {
+            path,
+            original,
+            edits,
+            was_new_file,
+            secret_findings: Vec::new(),
+        },
+        false,
+    ))
+}
+
+/// Raw text of a JSON value and whether the input contained all of it
+#[derive(Debug, Clone, Copy)]
+struct RawValue<'a> {
+    text: &'a str,
+    complete: bool,
+}
+
+fn parse<T: DeserializeOwned>(raw: &RawValue) -> Option<T> {
+    if !raw.complete {
+        return None;
+    }
+    serde_json::from_str(raw.text).ok()
+}
+
+/// Members of the object at the start of `text`, up to where it becomes unreadable
+///
+/// The last member's value may be incomplete when the input is truncated.
+fn object_members(text: &str) -> HashMap<String, RawValue<'_>> {
+    let mut members = HashMap::new();
+    let bytes = text.as_bytes();
+    let mut i = skip_ws(bytes, 0);
+    if bytes.get(i) != Some(&b'{') {
+        return members;
+    }
+    i += 1;
+    loop {
+        i = skip_ws(bytes, i);
+        if bytes.get(i) != Some(&b'"') {
+            return members;
+        }
+        let Some(key_end) = value_end(bytes, i) else {
+            return members;
+        };
+        let Ok(key) = serde_json::from_str::<String>(&text[i..key_end]) else {
+            return members;
+        };
+        i = skip_ws(bytes, key_end);
+        if bytes.get(i) != Some(&b':') {
+            return members;
+        }
+        i = skip_ws(bytes, i + 1);
+        if i >= bytes.len() {
+            return members;
+        }
+        let Some(end) = value_end(bytes, i) else {
+            members.insert(
+                key,
+                RawValue {
+                    text: &text[i..],
+                    complete: false,
+                },
+            );
+            return members;
+        };
+        members.insert(
+            key,
+            RawValue {
+                text: &text[i..end],
+                complete: true,
+            },
+        );
+        i = skip_ws(bytes, end);
+        if bytes.get(i) != Some(&b',') {
+            return members;
+        }
+        i += 1;
+    }
+}
+
+/// Elements of the array at the start of `text`, up to where it becomes unreadable
+fn array_elements(text: &str) -> Vec<RawValue<'_>> {
+    let mut elements = Vec::new();
+    let bytes = text.as_bytes();
+    let mut i = skip_ws(bytes, 0);
+    if bytes.get(i) != Some(&b'[') {
+        return elements;
+    }
+    i += 1;
+    loop {
+        i = skip_ws(bytes, i);
+        if i >= bytes.len() || bytes[i] == b']' {
+            return elements;
+        }
+        let Some(end) = value_end(bytes, i) else {
+            elements.push(RawValue {
+                text: &text[i..],
+                complete: false,
+            });
+            return elements;
+        };
+        elements.push(RawValue {
+            text: &text[i..end],
+            complete: true,
+        });
+        i = skip_ws(bytes, end);
+        if bytes.get(i) != Some(&b',') {
+            return elements;
+        }
+        i += 1;
+    }
+}
+
+fn skip_ws(bytes: &[u8], mut i: usize) -> usize {
+    while bytes.get(i).is_some_and(|b| b.is_ascii_whitespace()) {
+        i += 1;
+    }
+    i
+}
+
+/// Offset just past the JSON value starting at `start`, or None if the input ends first
+fn value_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &b) in bytes.iter().enumerate().skip(start) {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return Some(i),
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            b',' if depth == 0 => return Some(i),
+            _ if depth == 0 && b.is_ascii_whitespace() => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_buffer() -> PendingBuffer {
+        let mut buffer = PendingBuffer::new(&Uuid::new_v4().to_string(), "claude-test");
+        buffer.record_edit("a.rs", None, "fn a() {}\n", "Write", "Create a", None);
+        buffer.record_edit(
+            "b.rs",
+            Some("old\n"),
+            "old\nnew\n",
+            "Edit",
+            "Extend b",
+            None,
+        );
+        buffer.record_edit(
+            "b.rs",
+            Some("old\nnew\n"),
+            "old\nnew\nnewer\n",
+            "Edit",
+            "Extend b again",
+            None,
+        );
+        buffer
+    }
+
+    #[test]
+    fn test_salvage_truncated_buffer_keeps_intact_histories() {
+        let buffer = sample_buffer();
+        let json = serde_json::to_string_pretty(&buffer).unwrap();
+
+        // Cut inside the second edit of b.rs; a.rs survives only if serialized first
+        let a_start = json.find("\"a.rs\": {").unwrap();
+        let b_start = json.find("\"b.rs\": {").unwrap();
+        let second_edit = json[b_start..]
+            .match_indices("\"edit_id\"")
+            .nth(1)
+            .unwrap()
+            .0;
+        let salvage = salvage(&json[..b_start + second_edit + 20]).unwrap();
+        let report = &salvage.report;
+
+        assert_eq!(report.files_partial, vec!["b.rs".to_string()]);
+        assert_eq!(report.files_recovered.len(), usize::from(a_start < b_start));
+        assert!(report.files_lost.is_empty());
+        assert!(salvage.buffer.validate().is_ok());
+        // Session metadata precedes the histories, so it survives
+        assert!(report.session_recovered);
+        assert_eq!(salvage.buffer.session.session_id, buffer.session.session_id);
+
+        let partial = &salvage.buffer.file_histories["b.rs"];
+        assert_eq!(partial.edits.len(), 1);
+        assert!(!partial.was_new_file);
+    }
+
+    #[test]
+    fn test_salvage_rebuilds_session_from_edits() {
+        let buffer = sample_buffer();
+        let mut value = serde_json::to_value(&buffer).unwrap();
+        value["session"] = serde_json::json!("\u{0}garbage");
+        let salvage = salvage(&value.to_string()).unwrap();
+
+        assert!(!salvage.report.session_recovered);
+        assert_eq!(salvage.report.prompts_rebuilt, 3);
+        assert_eq!(salvage.buffer.session.prompts.len(), 3);
+        assert_eq!(salvage.buffer.prompt_counter, 3);
+        assert_eq!(salvage.buffer.session.prompts[2].text, "Extend b again");
+        assert!(salvage.buffer.validate().is_ok());
+        assert_eq!(salvage.buffer.total_edits(), 3);
+    }
+
+    #[test]
+    fn test_salvage_returns_none_without_histories() {
+        assert!(salvage("").is_none());
+        assert!(salvage("{\"version\": 3, \"session\": {\"session_id\"").is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! Explicit attribution sessions (`whogitit session begin` / `end`)
+//!
+//! An active session is stored at `.git/whogitit/session.json`. While it exists the
+//! capture hook records every edit under its session ID, model and label, so
+//! several agent windows working in the same repository share one session instead
+//! of each inventing its own from `WHOGITIT_SESSION_ID` / `WHOGITIT_MODEL_ID`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::core::attribution::ModelInfo;
+
+/// Session file (inside the git dir)
+const SESSION_FILE: &str = "whogitit/session.json";
+
+/// A session started with `whogitit session begin`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActiveSession {
+    pub session_id: String,
+    /// Model ID recorded for edits in this session (None: keep the hook's model)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Provider of `model`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Free-form label (e.g. a ticket number) carried into notes and summaries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// When the session began (ISO 8601)
+    pub started_at: String,
+}
+
+impl ActiveSession {
+    /// Start a new session with a fresh ID
+    pub fn begin(model: Option<String>, provider: Option<String>, label: Option<String>) -> Self {
+        Self {
+            session_id: uuid::Uuid::new_v4().to_string(),
+            model,
+            provider,
+            label,
+            started_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Path of the session file for a git dir
+    pub fn path(git_dir: &Path) -> PathBuf {
+        git_dir.join(SESSION_FILE)
+    }
+
+    /// Load the active session, if any
+    pub fn load(git_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(git_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let session = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(session))
+    }
+
+    /// Load the active session for a working tree, warning instead of failing
+    ///
+    /// The capture hook must keep recording edits even if the file is unreadable.
+    pub fn load_for_workdir(repo_root: &Path) -> Option<Self> {
+        let repo = Repository::open(repo_root).ok()?;
+        match Self::load(repo.path()) {
+            Ok(session) => session,
+            Err(err) => {
+                eprintln!("whogitit: Warning - ignoring active session: {:#}", err);
+                None
+            }
+        }
+    }
+
+    /// Write the session file
+    pub fn save(&self, git_dir: &Path) -> Result<()> {
+        let path = Self::path(git_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create whogitit directory")?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .context("Failed to write session file")?;
+        fs::rename(&tmp, &path).context("Failed to store session file")?;
+        Ok(())
+    }
+
+    /// Remove the session file, returning the session that was active
+    pub fn end(git_dir: &Path) -> Result<Option<Self>> {
+        let session = Self::load(git_dir)?;
+        if session.is_some() {
+            fs::remove_file(Self::path(git_dir)).context("Failed to remove session file")?;
+        }
+        Ok(session)
+    }
+
+    /// Model to record for edits, if the session sets one
+    pub fn model_info(&self) -> Option<ModelInfo> {
+        self.model
+            .as_deref()
+            .map(|id| match self.provider.as_deref() {
+                Some(provider) => ModelInfo::new(id, provider),
+                None => ModelInfo::claude(id),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_session_round_trip_and_end() {
+        let dir = TempDir::new().unwrap();
+        assert!(ActiveSession::load(dir.path()).unwrap().is_none());
+
+        let session = ActiveSession::begin(
+            Some("gpt-5-codex".to_string()),
+            Some("openai".to_string()),
+            Some("ticket-123".to_string()),
+        );
+        session.save(dir.path()).unwrap();
+        assert_eq!(
+            ActiveSession::load(dir.path()).unwrap(),
+            Some(session.clone())
+        );
+        assert_eq!(session.model_info().unwrap().provider, "openai");
+
+        assert_eq!(ActiveSession::end(dir.path()).unwrap(), Some(session));
+        assert!(!ActiveSession::path(dir.path()).exists());
+        assert!(ActiveSession::end(dir.path()).unwrap().is_none());
+    }
+}
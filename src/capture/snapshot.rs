@@ -1,8 +1,14 @@
+use std::borrow::Cow;
+
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use crate::utils::{hex, CONTENT_HASH_BYTES};
+use crate::capture::encoding::{self, TextEncoding};
+use crate::utils::{hex, CONTENT_HASH_BYTES, LINE_HASH_BYTES};
+
+/// Tool name recorded on inline completion edits (see `capture::inline`)
+pub const INLINE_COMPLETION_TOOL: &str = "InlineCompletion";
 
 /// Context about the edit from Claude Code transcript
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -19,6 +25,12 @@ pub struct EditContext {
     /// Plan step index if in plan mode
     #[serde(skip_serializing_if = "Option::is_none")]
     pub plan_step: Option<u32>,
+    /// Claude Code `tool_use` id of the tool call that made the edit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+    /// Transcript id of the assistant message that issued the tool call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
 }
 
 /// A point-in-time snapshot of a file's content
@@ -32,6 +44,13 @@ pub struct ContentSnapshot {
     pub timestamp: String,
     /// Line count at this snapshot
     pub line_count: usize,
+    /// Encoding of the file's bytes (content is always stored decoded)
+    #[serde(default, skip_serializing_if = "TextEncoding::is_utf8")]
+    pub encoding: TextEncoding,
+    /// SHA-256 hash of the file's raw bytes on disk, for binary files whose
+    /// bytes `content` cannot reproduce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_hash: Option<String>,
 }
 
 impl ContentSnapshot {
@@ -41,6 +60,29 @@ impl ContentSnapshot {
             content_hash: compute_hash(content),
             timestamp: Utc::now().to_rfc3339(),
             line_count: content.lines().count(),
+            encoding: TextEncoding::Utf8,
+            bytes_hash: None,
+        }
+    }
+
+    /// Snapshot of content decoded from a non-UTF-8 encoding
+    pub fn with_encoding(content: &str, encoding: TextEncoding) -> Self {
+        Self {
+            encoding,
+            ..Self::new(content)
+        }
+    }
+
+    /// The file's bytes in their original encoding
+    pub fn bytes(&self) -> Vec<u8> {
+        encoding::encode(&self.content, self.encoding)
+    }
+
+    /// Whether committed bytes are exactly the file this snapshot was taken of
+    pub fn matches_bytes(&self, bytes: &[u8]) -> bool {
+        match &self.bytes_hash {
+            Some(hash) => *hash == compute_bytes_hash(bytes),
+            None => self.bytes() == bytes,
         }
     }
 
@@ -77,7 +119,12 @@ pub struct AIEdit {
 
 /// Helper for skip_serializing_if
 fn is_default_context(ctx: &EditContext) -> bool {
-    !ctx.plan_mode && ctx.subagent_id.is_none() && ctx.agent_depth == 0 && ctx.plan_step.is_none()
+    !ctx.plan_mode
+        && ctx.subagent_id.is_none()
+        && ctx.agent_depth == 0
+        && ctx.plan_step.is_none()
+        && ctx.tool_use_id.is_none()
+        && ctx.message_id.is_none()
 }
 
 impl AIEdit {
@@ -121,6 +168,22 @@ impl AIEdit {
     }
 }
 
+/// A line of AI-written code that matched a sensitive pattern
+/// (`privacy.scan_generated_code`)
+///
+/// Only the pattern name and location are kept; the matched text never is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecretFinding {
+    /// Line number (1-indexed) in the file content the finding refers to
+    pub line_number: u32,
+    /// Name of the redaction pattern that matched
+    pub pattern_name: String,
+    /// Edit that wrote the line
+    pub edit_id: String,
+    /// Prompt of that edit
+    pub prompt_index: u32,
+}
+
 /// Tracks the complete edit history for a single file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileEditHistory {
@@ -132,6 +195,9 @@ pub struct FileEditHistory {
     pub edits: Vec<AIEdit>,
     /// Whether file existed before tracking
     pub was_new_file: bool,
+    /// Possible secrets in lines written by the edits, against the latest AI content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_findings: Vec<SecretFinding>,
 }
 
 impl FileEditHistory {
@@ -146,6 +212,7 @@ impl FileEditHistory {
             original,
             edits: Vec::new(),
             was_new_file: was_new,
+            secret_findings: Vec::new(),
         }
     }
 
@@ -181,7 +248,14 @@ pub struct LineAttribution {
     /// Line number (1-indexed)
     pub line_number: u32,
     /// The actual line content
+    ///
+    /// Notes (schema v4+) store `content_hash` instead; the content is restored from
+    /// the commit's blob when the note is read.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub content: String,
+    /// Hash of the line content (see [`line_hash`]), set in notes instead of `content`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
     /// Attribution source
     pub source: LineSource,
     /// If AI-generated, which edit created it
@@ -190,6 +264,9 @@ pub struct LineAttribution {
     pub prompt_index: Option<u32>,
     /// Confidence in the attribution (0.0-1.0)
     pub confidence: f64,
+    /// If AI-generated, when the edit that produced it was captured (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
 }
 
 /// Source of a line
@@ -224,9 +301,72 @@ pub struct FileAttributionResult {
     pub path: String,
     pub lines: Vec<LineAttribution>,
     pub summary: AttributionSummary,
+    /// Whole-file attribution for content that is not analyzed line by line
+    /// (non-UTF8 content or paths matching `analysis.binary_paths`); `lines` is empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary: Option<BinaryAttribution>,
+    /// Run-length encoded copy of `lines` (without content) for fast blame lookups;
+    /// only stored for files of at least [`BLOCK_MIN_LINES`] lines, or for every file
+    /// with `analysis.granularity = "hunk"`, in which case `lines` is empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Vec<LineBlock>>,
+    /// Set when the file was too large for full analysis and was attributed with
+    /// the bounded-memory fallback (line ranges only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis_warning: Option<String>,
+    /// Possible secrets in AI-written lines that are still present in the commit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_findings: Vec<SecretFinding>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Files with at least this many lines get precomputed [`LineBlock`]s in their note
+pub const BLOCK_MIN_LINES: usize = 200;
+
+/// A run of consecutive lines sharing the same attribution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineBlock {
+    /// First line number (1-indexed)
+    pub start: u32,
+    /// Number of lines in the run
+    pub len: u32,
+    pub source: LineSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_index: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+impl LineBlock {
+    fn continues(&self, line: &LineAttribution) -> bool {
+        self.start + self.len == line.line_number
+            && self.source == line.source
+            && self.prompt_index == line.prompt_index
+            && self.timestamp == line.timestamp
+    }
+}
+
+/// Attribution of a single line, borrowed from lines or blocks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineAttributionRef<'a> {
+    pub source: &'a LineSource,
+    pub prompt_index: Option<u32>,
+    pub timestamp: Option<&'a str>,
+    /// Per-line confidence; blocks (`analysis.granularity = "hunk"`) don't keep one
+    pub confidence: Option<f64>,
+}
+
+/// Whole-file attribution for binary assets and lockfiles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinaryAttribution {
+    /// Whether the committed content is exactly what the AI last wrote
+    pub ai_generated: bool,
+    /// Tool used for the last AI edit
+    pub tool: String,
+    /// Prompt index of the last AI edit
+    pub prompt_index: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AttributionSummary {
     pub total_lines: usize,
     pub ai_lines: usize,
@@ -237,6 +377,78 @@ pub struct AttributionSummary {
 }
 
 impl FileAttributionResult {
+    /// Whether this file carries whole-file (binary) attribution
+    pub fn is_binary(&self) -> bool {
+        self.binary.is_some()
+    }
+
+    /// Run-length encode line attributions
+    pub fn compute_blocks(lines: &[LineAttribution]) -> Vec<LineBlock> {
+        let mut blocks: Vec<LineBlock> = Vec::new();
+        for line in lines {
+            match blocks.last_mut() {
+                Some(block) if block.continues(line) => block.len += 1,
+                _ => blocks.push(LineBlock {
+                    start: line.line_number,
+                    len: 1,
+                    source: line.source.clone(),
+                    prompt_index: line.prompt_index,
+                    timestamp: line.timestamp.clone(),
+                }),
+            }
+        }
+        blocks
+    }
+
+    /// Store precomputed blocks when the file is large enough to benefit
+    pub fn attach_blocks(&mut self) {
+        self.blocks =
+            (self.lines.len() >= BLOCK_MIN_LINES).then(|| Self::compute_blocks(&self.lines));
+    }
+
+    /// Keep only blocks, dropping per-line attribution (`analysis.granularity = "hunk"`)
+    pub fn collapse_to_blocks(&mut self) {
+        if !self.lines.is_empty() {
+            self.blocks = Some(Self::compute_blocks(&self.lines));
+            self.lines.clear();
+        }
+    }
+
+    /// Stored blocks, or blocks computed from `lines` for notes without them
+    pub fn line_blocks(&self) -> Cow<'_, [LineBlock]> {
+        match &self.blocks {
+            Some(blocks) => Cow::Borrowed(blocks),
+            None => Cow::Owned(Self::compute_blocks(&self.lines)),
+        }
+    }
+
+    /// Attribution for a line number, from blocks when present
+    pub fn line(&self, line_number: u32) -> Option<LineAttributionRef<'_>> {
+        if let Some(blocks) = &self.blocks {
+            let idx = blocks.partition_point(|b| b.start <= line_number);
+            let block = blocks[..idx].last()?;
+            return (line_number < block.start + block.len).then_some(LineAttributionRef {
+                source: &block.source,
+                prompt_index: block.prompt_index,
+                timestamp: block.timestamp.as_deref(),
+                confidence: None,
+            });
+        }
+
+        // Lines are stored in order, so the index usually matches the line number
+        let line = self
+            .lines
+            .get((line_number as usize).wrapping_sub(1))
+            .filter(|l| l.line_number == line_number)
+            .or_else(|| self.lines.iter().find(|l| l.line_number == line_number))?;
+        Some(LineAttributionRef {
+            source: &line.source,
+            prompt_index: line.prompt_index,
+            timestamp: line.timestamp.as_deref(),
+            confidence: Some(line.confidence),
+        })
+    }
+
     pub fn compute_summary(lines: &[LineAttribution]) -> AttributionSummary {
         let mut summary = AttributionSummary {
             total_lines: lines.len(),
@@ -259,16 +471,44 @@ impl FileAttributionResult {
 
         summary
     }
+
+    /// Summary of run-length encoded attribution
+    pub fn compute_block_summary(blocks: &[LineBlock]) -> AttributionSummary {
+        let mut summary = Self::compute_summary(&[]);
+        for block in blocks {
+            let len = block.len as usize;
+            summary.total_lines += len;
+            match block.source {
+                LineSource::Original => summary.original_lines += len,
+                LineSource::AI { .. } => summary.ai_lines += len,
+                LineSource::AIModified { .. } => summary.ai_modified_lines += len,
+                LineSource::Human => summary.human_lines += len,
+                LineSource::Unknown => summary.unknown_lines += len,
+            }
+        }
+        summary
+    }
 }
 
 /// Compute SHA-256 hash of content
 pub fn compute_hash(content: &str) -> String {
+    compute_bytes_hash(content.as_bytes())
+}
+
+/// Compute SHA-256 hash of raw file bytes
+pub fn compute_bytes_hash(bytes: &[u8]) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
+    hasher.update(bytes);
     let result = hasher.finalize();
     hex::encode(&result[..CONTENT_HASH_BYTES])
 }
 
+/// Short hash of a single line, stored in notes in place of its content
+pub fn line_hash(line: &str) -> String {
+    let result = Sha256::digest(line.as_bytes());
+    hex::encode(&result[..LINE_HASH_BYTES])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,4 +550,128 @@ mod tests {
         assert!(history.was_new_file);
         assert!(history.original.content.is_empty());
     }
+
+    fn line(line_number: u32, source: LineSource, prompt_index: Option<u32>) -> LineAttribution {
+        LineAttribution {
+            line_number,
+            content: format!("line {}", line_number),
+            content_hash: None,
+            source,
+            edit_id: None,
+            prompt_index,
+            confidence: 1.0,
+            timestamp: None,
+        }
+    }
+
+    fn file(lines: Vec<LineAttribution>) -> FileAttributionResult {
+        FileAttributionResult {
+            path: "src/lib.rs".to_string(),
+            summary: FileAttributionResult::compute_summary(&lines),
+            lines,
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compute_blocks_run_length_encodes() {
+        let ai = || LineSource::AI {
+            edit_id: "e1".to_string(),
+        };
+        let lines = vec![
+            line(1, LineSource::Original, None),
+            line(2, LineSource::Original, None),
+            line(3, ai(), Some(0)),
+            line(4, ai(), Some(0)),
+            line(5, ai(), Some(1)),
+            line(6, LineSource::Human, None),
+        ];
+
+        let blocks = FileAttributionResult::compute_blocks(&lines);
+        let runs: Vec<(u32, u32, Option<u32>)> = blocks
+            .iter()
+            .map(|b| (b.start, b.len, b.prompt_index))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![(1, 2, None), (3, 2, Some(0)), (5, 1, Some(1)), (6, 1, None)]
+        );
+    }
+
+    #[test]
+    fn test_line_lookup_matches_with_and_without_blocks() {
+        let mut lines: Vec<LineAttribution> = (1..=BLOCK_MIN_LINES as u32 + 10)
+            .map(|n| match n % 7 {
+                0 | 1 => line(
+                    n,
+                    LineSource::AI {
+                        edit_id: "e1".to_string(),
+                    },
+                    Some(n % 2),
+                ),
+                _ => line(n, LineSource::Original, None),
+            })
+            .collect();
+        // A gap in line numbers must not be covered by the neighbouring block
+        lines.retain(|l| l.line_number != 50);
+
+        let plain = file(lines);
+        let mut blocked = plain.clone();
+        blocked.attach_blocks();
+        assert!(blocked.blocks.is_some());
+
+        // Blocks keep no per-line confidence; everything else must match
+        fn without_confidence(line: LineAttributionRef<'_>) -> LineAttributionRef<'_> {
+            LineAttributionRef {
+                confidence: None,
+                ..line
+            }
+        }
+        for n in 0..=BLOCK_MIN_LINES as u32 + 11 {
+            assert_eq!(
+                plain.line(n).map(without_confidence),
+                blocked.line(n),
+                "line {}",
+                n
+            );
+        }
+        assert_eq!(plain.line(1).unwrap().confidence, Some(1.0));
+        assert!(blocked.line(50).is_none());
+        assert!(blocked.line(0).is_none());
+    }
+
+    #[test]
+    fn test_small_files_store_no_blocks() {
+        let mut small = file(vec![line(1, LineSource::Original, None)]);
+        small.attach_blocks();
+        assert!(small.blocks.is_none());
+
+        let json = serde_json::to_string(&small).unwrap();
+        assert!(!json.contains("blocks"));
+        let parsed: FileAttributionResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.line(1).unwrap().source, &LineSource::Original);
+    }
+
+    #[test]
+    fn test_collapse_to_blocks_keeps_ranges_and_summary() {
+        let ai = || LineSource::AI {
+            edit_id: "e1".to_string(),
+        };
+        let mut hunks = file(vec![
+            line(1, LineSource::Original, None),
+            line(2, ai(), Some(0)),
+            line(3, ai(), Some(0)),
+        ]);
+        let expected = hunks.line_blocks().into_owned();
+        hunks.collapse_to_blocks();
+
+        assert!(hunks.lines.is_empty());
+        assert_eq!(hunks.line_blocks().as_ref(), expected.as_slice());
+        assert_eq!(hunks.summary.ai_lines, 2);
+        assert_eq!(hunks.line(3).unwrap().prompt_index, Some(0));
+        assert!(hunks.line(4).is_none());
+    }
 }
@@ -0,0 +1,230 @@
+//! Pending-buffer aware `git stash` support
+//!
+//! Stashing AI-edited files removes the AI content from the worktree while the
+//! pending buffer still expects it, so the next commit would be analyzed against
+//! the wrong snapshots. This module detects that desync and lets the pending
+//! histories for stashed files be suspended alongside the stash entry and
+//! restored when it is popped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use git2::{Oid, Repository};
+
+use crate::capture::pending::PendingBuffer;
+use crate::core::sparse;
+use crate::utils::repo_path;
+
+/// Directory (inside the git dir) holding suspended pending histories, one file per stash commit
+const SUSPENDED_DIR: &str = "whogitit/stashed-pending";
+
+/// Files whose AI edits are no longer present in the worktree.
+///
+/// A file is considered desynced when its worktree content is back to the
+/// pre-AI original (or gone, for files the AI created) even though the pending
+/// history records AI changes - which is exactly what `git stash` leaves behind.
+/// Files outside a sparse-checkout cone are compared by their index content.
+pub fn detect_desynced_files(buffer: &PendingBuffer, repo_root: &Path) -> Vec<String> {
+    let repo = Repository::open(repo_root).ok();
+    let mut desynced: Vec<String> = buffer
+        .file_histories
+        .iter()
+        .filter(|(path, history)| {
+            let latest = &history.latest_ai_content().content;
+            if *latest == history.original.content {
+                return false;
+            }
+            let current = match &repo {
+                Some(repo) => sparse::read_current(repo, path).map(|decoded| decoded.text),
+                None => fs::read_to_string(repo_root.join(repo_path::to_path(path))).ok(),
+            };
+            match current {
+                Some(current) => current == history.original.content,
+                None => history.was_new_file,
+            }
+        })
+        .map(|(path, _)| path.clone())
+        .collect();
+    desynced.sort();
+    desynced
+}
+
+/// Print a warning for files whose AI edits appear to have been stashed
+pub fn warn_desynced_files(files: &[String]) {
+    if files.is_empty() {
+        return;
+    }
+    eprintln!(
+        "whogitit: Warning - AI edits to {} file(s) are missing from the worktree (stashed?): {}",
+        files.len(),
+        files.join(", ")
+    );
+    eprintln!(
+        "whogitit: Use 'whogitit stash push' / 'whogitit stash pop' to keep attribution in sync."
+    );
+}
+
+/// Suspended pending histories stored alongside stash entries
+pub struct SuspendedStore {
+    dir: PathBuf,
+}
+
+impl SuspendedStore {
+    /// Create a store for the repository's git dir
+    pub fn new(git_dir: &Path) -> Self {
+        Self {
+            dir: git_dir.join(SUSPENDED_DIR),
+        }
+    }
+
+    fn path_for(&self, stash: Oid) -> PathBuf {
+        self.dir.join(format!("{}.json", stash))
+    }
+
+    /// Save histories suspended with a stash commit
+    pub fn save(&self, stash: Oid, buffer: &PendingBuffer) -> Result<()> {
+        fs::create_dir_all(&self.dir).context("Failed to create stashed-pending directory")?;
+        let content =
+            serde_json::to_string_pretty(buffer).context("Failed to serialize pending buffer")?;
+        let path = self.path_for(stash);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, content).context("Failed to write suspended pending buffer")?;
+        fs::rename(&tmp, &path).context("Failed to store suspended pending buffer")?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+                .context("Failed to set permissions on suspended pending buffer")?;
+        }
+        Ok(())
+    }
+
+    /// Load histories suspended with a stash commit, if any
+    pub fn load(&self, stash: Oid) -> Result<Option<PendingBuffer>> {
+        let path = self.path_for(stash);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read_to_string(&path).context("Failed to read suspended pending buffer")?;
+        let buffer = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(buffer))
+    }
+
+    /// Remove the suspended histories for a stash commit
+    pub fn remove(&self, stash: Oid) -> Result<()> {
+        let path = self.path_for(stash);
+        if path.exists() {
+            fs::remove_file(&path).context("Failed to remove suspended pending buffer")?;
+        }
+        Ok(())
+    }
+
+    /// Stash commits that have suspended histories
+    pub fn list(&self) -> Vec<Oid> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.strip_suffix(".json")
+                    .and_then(|s| Oid::from_str(s).ok())
+            })
+            .collect()
+    }
+}
+
+/// Split off the histories of files that `git stash` removed from the worktree
+///
+/// Returns the suspended part (if any file was stashed); `buffer` keeps the rest.
+pub fn suspend_stashed_files(
+    buffer: &mut PendingBuffer,
+    repo_root: &Path,
+) -> Option<PendingBuffer> {
+    let stashed = detect_desynced_files(buffer, repo_root);
+    if stashed.is_empty() {
+        return None;
+    }
+    Some(buffer.split_off_files(&stashed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SESSION: &str = "0b6f7c1e-5a2d-4c8e-9f10-2d3e4f5a6b7c";
+
+    fn buffer_with_edits() -> PendingBuffer {
+        let mut buffer = PendingBuffer::new(SESSION, "test-model");
+        buffer.record_edit(
+            "a.rs",
+            Some("fn a() {}\n"),
+            "fn a() { ai(); }\n",
+            "Edit",
+            "Touch a",
+            None,
+        );
+        buffer.record_edit("b.rs", None, "fn b() {}\n", "Write", "Create b", None);
+        buffer
+    }
+
+    #[test]
+    fn test_detect_desynced_files() {
+        let dir = TempDir::new().unwrap();
+        let buffer = buffer_with_edits();
+
+        // Worktree still has the AI content: in sync
+        fs::write(dir.path().join("a.rs"), "fn a() { ai(); }\n").unwrap();
+        fs::write(dir.path().join("b.rs"), "fn b() {}\n").unwrap();
+        assert!(detect_desynced_files(&buffer, dir.path()).is_empty());
+
+        // Stash reverts a.rs to the original and removes the new b.rs
+        fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::remove_file(dir.path().join("b.rs")).unwrap();
+        assert_eq!(
+            detect_desynced_files(&buffer, dir.path()),
+            vec!["a.rs".to_string(), "b.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_human_edits_are_not_desync() {
+        let dir = TempDir::new().unwrap();
+        let buffer = buffer_with_edits();
+        fs::write(dir.path().join("a.rs"), "fn a() { ai(); human(); }\n").unwrap();
+        fs::write(dir.path().join("b.rs"), "fn b() {}\n").unwrap();
+        assert!(detect_desynced_files(&buffer, dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_suspend_and_restore_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let mut buffer = buffer_with_edits();
+        fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(dir.path().join("b.rs"), "fn b() {}\n").unwrap();
+
+        let suspended = suspend_stashed_files(&mut buffer, dir.path()).unwrap();
+        assert!(buffer.get_file_history("a.rs").is_none());
+        assert!(buffer.get_file_history("b.rs").is_some());
+        assert_eq!(buffer.session.prompts.len(), 1);
+        assert!(suspended.validate().is_ok());
+
+        let store = SuspendedStore::new(dir.path());
+        let oid = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+        store.save(oid, &suspended).unwrap();
+        assert_eq!(store.list(), vec![oid]);
+
+        buffer.absorb(store.load(oid).unwrap().unwrap());
+        store.remove(oid).unwrap();
+        assert!(store.list().is_empty());
+        assert!(buffer.get_file_history("a.rs").is_some());
+        assert_eq!(buffer.session.prompts.len(), 2);
+        assert!(buffer.validate().is_ok());
+    }
+}
@@ -1,9 +1,14 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
+use anyhow::{Context, Result};
 use similar::{ChangeTag, TextDiff};
 
 use crate::capture::snapshot::{
-    FileAttributionResult, FileEditHistory, LineAttribution, LineSource,
+    AIEdit, AttributionSummary, BinaryAttribution, FileAttributionResult, FileEditHistory,
+    LineAttribution, LineBlock, LineSource, INLINE_COMPLETION_TOOL,
 };
 
 /// Default similarity threshold for AIModified detection
@@ -13,6 +18,19 @@ const CONTEXT_CONFIDENCE: f64 = 0.85;
 const CONTEXT_SIMILARITY_FALLBACK: f64 = 0.5;
 const MAX_CONTEXT_ITERATIONS: usize = 5;
 
+/// Files with more lines than this get bounded-memory analysis
+/// This can be overridden via config (analysis.max_analysis_lines)
+pub const DEFAULT_MAX_ANALYSIS_LINES: usize = 50_000;
+/// Lines attributed per window by [`ThreeWayAnalyzer::analyze_bounded`]
+pub const BOUNDED_WINDOW_LINES: usize = 4_096;
+
+/// Most consecutive lines joined when matching formatter reflows
+/// This can be overridden via config (analysis.max_block_lines)
+pub const DEFAULT_MAX_BLOCK_LINES: usize = 16;
+/// Joined blocks stop growing once their normalized text reaches this many bytes,
+/// so files of short lines join many lines and files of long lines only a few
+const BLOCK_JOIN_BUDGET: usize = 480;
+
 /// Normalize a line for comparison purposes.
 /// - Trims trailing whitespace (but preserves leading indentation)
 /// - Normalizes line endings
@@ -27,6 +45,219 @@ fn normalize_for_key(line: &str) -> String {
     normalize_line(line)
 }
 
+/// Language-specific rules for matching lines during attribution
+///
+/// The analyzer compares lines three ways: exact matches (original vs. AI output vs.
+/// committed content), similarity scores for lines a human edited, and joined blocks
+/// for lines a formatter split or merged. Each step asks the normalizer for the text
+/// to compare, so a language can tune matching without changing the analyzer.
+/// Normalizers are picked per file extension through a [`NormalizerRegistry`].
+pub trait LineNormalizer: Send + Sync {
+    /// Key for exact line matching
+    fn line_key(&self, line: &str) -> String {
+        normalize_for_key(line)
+    }
+
+    /// Text scored for similarity (AIModified detection); empty skips the line
+    fn similarity_text(&self, line: &str) -> String {
+        line.trim().to_string()
+    }
+
+    /// Text compared when matching a run of consecutive lines as one block
+    fn block_text(&self, lines: &[&str]) -> String {
+        lines
+            .iter()
+            .map(|line| normalize_for_block_comparison(line))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether formatter reflows are detected by joining consecutive lines
+    fn block_matching(&self) -> bool {
+        true
+    }
+}
+
+/// Matching rules for languages without a dedicated normalizer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultNormalizer;
+
+impl LineNormalizer for DefaultNormalizer {}
+
+/// Python: indentation is significant and formatters add trailing commas
+///
+/// Leading tabs count as four spaces so a re-indented line still matches, while
+/// a line at a different depth does not. Block matching ignores the "magic
+/// trailing comma" black adds when it splits a call over several lines.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PythonNormalizer;
+
+impl LineNormalizer for PythonNormalizer {
+    fn line_key(&self, line: &str) -> String {
+        let content = line.trim_start_matches([' ', '\t']);
+        let indent: usize = line[..line.len() - content.len()]
+            .chars()
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        format!("{}{}", " ".repeat(indent), content.trim_end())
+    }
+
+    fn block_text(&self, lines: &[&str]) -> String {
+        let joined = lines
+            .iter()
+            .map(|line| line.trim_end().trim_end_matches('\\'))
+            .collect::<Vec<_>>()
+            .join(" ");
+        normalize_for_block_comparison(&joined)
+            .replace(",)", ")")
+            .replace(",]", "]")
+            .replace(",}", "}")
+    }
+}
+
+/// YAML: nesting is expressed only by indentation
+///
+/// Similarity keeps the indentation so a key moved to another level is not taken
+/// for a light edit, and block matching is off: joining lines would flatten the
+/// structure that decides what a value belongs to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YamlNormalizer;
+
+impl LineNormalizer for YamlNormalizer {
+    fn similarity_text(&self, line: &str) -> String {
+        line.trim_end().to_string()
+    }
+
+    fn block_matching(&self) -> bool {
+        false
+    }
+}
+
+/// Minified JavaScript/CSS: whole programs on one line
+///
+/// Lines are matched exactly (ignoring surrounding whitespace). Similarity and
+/// block matching are skipped; scoring multi-kilobyte lines character by character
+/// is slow and says nothing about who wrote what.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinifiedNormalizer;
+
+impl LineNormalizer for MinifiedNormalizer {
+    fn line_key(&self, line: &str) -> String {
+        line.trim().to_string()
+    }
+
+    fn similarity_text(&self, _line: &str) -> String {
+        String::new()
+    }
+
+    fn block_matching(&self) -> bool {
+        false
+    }
+}
+
+/// Normalizers keyed by file extension
+///
+/// Extensions are matched case-insensitively and the longest one wins, so
+/// `min.js` applies to `app.min.js` before `js` would. Files without a registered
+/// extension use [`DefaultNormalizer`].
+#[derive(Clone)]
+pub struct NormalizerRegistry {
+    by_extension: HashMap<String, Arc<dyn LineNormalizer>>,
+}
+
+impl NormalizerRegistry {
+    /// Names accepted by [`NormalizerRegistry::builtin`] (and `analysis.normalizers`)
+    pub const BUILTIN_NAMES: &'static [&'static str] = &["default", "python", "yaml", "minified"];
+
+    /// Registry without any extension mappings
+    pub fn empty() -> Self {
+        Self {
+            by_extension: HashMap::new(),
+        }
+    }
+
+    /// A built-in normalizer by name
+    pub fn builtin(name: &str) -> Option<Arc<dyn LineNormalizer>> {
+        let normalizer: Arc<dyn LineNormalizer> = match name {
+            "default" => Arc::new(DefaultNormalizer),
+            "python" => Arc::new(PythonNormalizer),
+            "yaml" => Arc::new(YamlNormalizer),
+            "minified" => Arc::new(MinifiedNormalizer),
+            _ => return None,
+        };
+        Some(normalizer)
+    }
+
+    /// Built-in mappings with `overrides` (extension -> built-in name) applied on top
+    pub fn with_overrides<'a>(
+        overrides: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> Result<Self> {
+        let mut registry = Self::default();
+        for (extension, name) in overrides {
+            let normalizer = Self::builtin(name).with_context(|| {
+                format!(
+                    "Unknown normalizer '{}' for extension '{}' (expected one of: {})",
+                    name,
+                    extension,
+                    Self::BUILTIN_NAMES.join(", ")
+                )
+            })?;
+            registry.register(extension, normalizer);
+        }
+        Ok(registry)
+    }
+
+    /// Use `normalizer` for files ending in `.<extension>`
+    pub fn register(&mut self, extension: &str, normalizer: Arc<dyn LineNormalizer>) {
+        let extension = extension.trim_start_matches('.').to_ascii_lowercase();
+        self.by_extension.insert(extension, normalizer);
+    }
+
+    /// Normalizer for a repository path
+    pub fn for_path(&self, path: &str) -> &dyn LineNormalizer {
+        let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let file_name = file_name.to_ascii_lowercase();
+        // Candidate extensions from longest ("min.js") to shortest ("js")
+        file_name
+            .match_indices('.')
+            .filter_map(|(idx, _)| self.by_extension.get(&file_name[idx + 1..]))
+            .next()
+            .map(|normalizer| normalizer.as_ref())
+            .unwrap_or(&DefaultNormalizer)
+    }
+}
+
+impl Default for NormalizerRegistry {
+    /// Python, YAML and minified asset mappings
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for (extension, name) in [
+            ("py", "python"),
+            ("pyi", "python"),
+            ("yaml", "yaml"),
+            ("yml", "yaml"),
+            ("min.js", "minified"),
+            ("min.mjs", "minified"),
+            ("min.css", "minified"),
+        ] {
+            if let Some(normalizer) = Self::builtin(name) {
+                registry.register(extension, normalizer);
+            }
+        }
+        registry
+    }
+}
+
+impl std::fmt::Debug for NormalizerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut extensions: Vec<&String> = self.by_extension.keys().collect();
+        extensions.sort();
+        f.debug_struct("NormalizerRegistry")
+            .field("extensions", &extensions)
+            .finish()
+    }
+}
+
 /// Performs three-way attribution analysis
 ///
 /// Given:
@@ -44,11 +275,13 @@ pub struct ThreeWayAnalyzer;
 impl ThreeWayAnalyzer {
     /// Analyze a file's final content against its edit history
     pub fn analyze(history: &FileEditHistory, final_content: &str) -> FileAttributionResult {
+        let registry = NormalizerRegistry::default();
+        let normalizer = registry.for_path(&history.path);
         let final_lines: Vec<&str> = final_content.lines().collect();
 
         // Build lookup tables for efficient matching
-        let original_lines = build_line_set(&history.original.content);
-        let ai_line_sources = build_ai_line_map(history);
+        let original_lines = build_line_set(&history.original.content, normalizer);
+        let ai_line_sources = build_ai_line_map(history, normalizer);
 
         // Analyze each line in the final content
         let mut attributions = Vec::with_capacity(final_lines.len());
@@ -60,14 +293,20 @@ impl ThreeWayAnalyzer {
                 line_number,
                 &original_lines,
                 &ai_line_sources,
-                history,
                 DEFAULT_SIMILARITY_THRESHOLD,
+                normalizer,
             );
             attributions.push(attribution);
         }
 
         // Post-process: improve attribution using context
-        improve_attributions_with_context(&mut attributions, history, final_content);
+        improve_attributions_with_context(
+            &mut attributions,
+            history,
+            normalizer,
+            DEFAULT_MAX_BLOCK_LINES,
+        );
+        stamp_edit_timestamps(&mut attributions, history);
 
         let summary = FileAttributionResult::compute_summary(&attributions);
 
@@ -75,9 +314,89 @@ impl ThreeWayAnalyzer {
             path: history.path.clone(),
             lines: attributions,
             summary,
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
         }
     }
 
+    /// Attribute a binary asset or lockfile as a whole
+    ///
+    /// The file counts as AI-generated when the committed bytes are exactly what
+    /// the last AI edit wrote; any other content means a human changed it afterwards.
+    pub fn analyze_binary(history: &FileEditHistory, committed: &[u8]) -> FileAttributionResult {
+        let last_edit = history.edits.last();
+        let ai_generated = last_edit.is_some_and(|edit| edit.after.matches_bytes(committed));
+
+        FileAttributionResult {
+            path: history.path.clone(),
+            lines: Vec::new(),
+            summary: AttributionSummary {
+                total_lines: 0,
+                ai_lines: 0,
+                ai_modified_lines: 0,
+                human_lines: 0,
+                original_lines: 0,
+                unknown_lines: 0,
+            },
+            binary: Some(BinaryAttribution {
+                ai_generated,
+                tool: last_edit.map(|e| e.tool.clone()).unwrap_or_default(),
+                prompt_index: last_edit.map(|e| e.prompt_index).unwrap_or(0),
+            }),
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        }
+    }
+
+    /// Attribute a file the AI created in one edit and that was committed unchanged
+    ///
+    /// Every line is then AI from that edit, so no diffing is needed. The initial
+    /// commit of a generated project is typically hundreds of such files. Returns
+    /// `None` when the file existed before, took several edits or was changed
+    /// afterwards; those need [`Self::analyze_with_normalizer`].
+    pub fn analyze_new_file(
+        history: &FileEditHistory,
+        final_content: &str,
+    ) -> Option<FileAttributionResult> {
+        let [edit] = history.edits.as_slice() else {
+            return None;
+        };
+        if !history.was_new_file || edit.after.content != final_content {
+            return None;
+        }
+
+        let lines: Vec<LineAttribution> = final_content
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| LineAttribution {
+                line_number: (idx + 1) as u32,
+                content: line.to_string(),
+                content_hash: None,
+                source: LineSource::AI {
+                    edit_id: edit.edit_id.clone(),
+                },
+                edit_id: Some(edit.edit_id.clone()),
+                prompt_index: Some(edit.prompt_index),
+                confidence: 1.0,
+                timestamp: Some(edit.timestamp.clone()),
+            })
+            .collect();
+        let summary = FileAttributionResult::compute_summary(&lines);
+
+        Some(FileAttributionResult {
+            path: history.path.clone(),
+            lines,
+            summary,
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        })
+    }
+
     /// Analyze with position-aware diff for better accuracy
     pub fn analyze_with_diff(
         history: &FileEditHistory,
@@ -87,10 +406,45 @@ impl ThreeWayAnalyzer {
     }
 
     /// Analyze with position-aware diff for better accuracy, using a custom similarity threshold
+    ///
+    /// Lines are matched with the built-in normalizer for the file's extension.
     pub fn analyze_with_diff_with_threshold(
         history: &FileEditHistory,
         final_content: &str,
         similarity_threshold: f64,
+    ) -> FileAttributionResult {
+        let registry = NormalizerRegistry::default();
+        Self::analyze_with_normalizer(
+            history,
+            final_content,
+            similarity_threshold,
+            registry.for_path(&history.path),
+        )
+    }
+
+    /// Position-aware analysis with explicit line matching rules
+    pub fn analyze_with_normalizer(
+        history: &FileEditHistory,
+        final_content: &str,
+        similarity_threshold: f64,
+        normalizer: &dyn LineNormalizer,
+    ) -> FileAttributionResult {
+        Self::analyze_with_block_limit(
+            history,
+            final_content,
+            similarity_threshold,
+            normalizer,
+            DEFAULT_MAX_BLOCK_LINES,
+        )
+    }
+
+    /// Position-aware analysis joining at most `max_block_lines` lines per reflow block
+    pub fn analyze_with_block_limit(
+        history: &FileEditHistory,
+        final_content: &str,
+        similarity_threshold: f64,
+        normalizer: &dyn LineNormalizer,
+        max_block_lines: usize,
     ) -> FileAttributionResult {
         let final_lines: Vec<&str> = final_content.lines().collect();
         let mut attributions = Vec::with_capacity(final_lines.len());
@@ -99,7 +453,7 @@ impl ThreeWayAnalyzer {
         if history.edits.is_empty() {
             for (idx, line) in final_lines.iter().enumerate() {
                 let line_number = (idx + 1) as u32;
-                let source = if line_in_content(line, &history.original.content) {
+                let source = if line_in_content(line, &history.original.content, normalizer) {
                     LineSource::Original
                 } else {
                     LineSource::Human
@@ -107,10 +461,12 @@ impl ThreeWayAnalyzer {
                 attributions.push(LineAttribution {
                     line_number,
                     content: line.to_string(),
+                    content_hash: None,
                     source,
                     edit_id: None,
                     prompt_index: None,
                     confidence: 1.0,
+                    timestamp: None,
                 });
             }
             let summary = FileAttributionResult::compute_summary(&attributions);
@@ -118,6 +474,10 @@ impl ThreeWayAnalyzer {
                 path: history.path.clone(),
                 lines: attributions,
                 summary,
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
             };
         }
 
@@ -126,8 +486,8 @@ impl ThreeWayAnalyzer {
         let latest_ai = history.latest_ai_content();
 
         // Build lookup sets
-        let original_lines = build_line_set(&history.original.content);
-        let ai_line_map = build_ai_line_map(history);
+        let original_lines = build_line_set(&history.original.content, normalizer);
+        let ai_line_map = build_ai_line_map(history, normalizer);
 
         // Track which final lines match AI content
         let ai_to_final_mapping = diff_map_lines(&latest_ai.content, final_content);
@@ -157,7 +517,7 @@ impl ThreeWayAnalyzer {
             }
 
             let ai_line = latest_ai.lines().get(*ai_idx).copied().unwrap_or("");
-            let normalized = normalize_for_key(ai_line);
+            let normalized = normalizer.line_key(ai_line);
 
             // This line was mapped from AI output and NOT from original position
             // So it's AI-generated (regardless of whether similar content exists in original)
@@ -181,7 +541,7 @@ impl ThreeWayAnalyzer {
                 continue;
             }
 
-            let normalized = normalize_for_key(line);
+            let normalized = normalizer.line_key(line);
 
             // Check if line exists in original first
             if original_lines.contains(&normalized) {
@@ -206,7 +566,7 @@ impl ThreeWayAnalyzer {
 
             // Check if this is similar to an AI line (modified)
             if let Some((edit_id, prompt_idx, similarity)) =
-                find_similar_ai_line(line, &ai_line_map, similarity_threshold)
+                find_similar_ai_line(line, &ai_line_map, similarity_threshold, normalizer)
             {
                 final_line_sources.insert(
                     idx,
@@ -245,15 +605,18 @@ impl ThreeWayAnalyzer {
             attributions.push(LineAttribution {
                 line_number,
                 content: line.to_string(),
+                content_hash: None,
                 source,
                 edit_id,
                 prompt_index,
                 confidence,
+                timestamp: None,
             });
         }
 
         // Post-process: improve attribution using context and block matching
-        improve_attributions_with_context(&mut attributions, history, final_content);
+        improve_attributions_with_context(&mut attributions, history, normalizer, max_block_lines);
+        stamp_edit_timestamps(&mut attributions, history);
 
         let summary = FileAttributionResult::compute_summary(&attributions);
 
@@ -261,13 +624,155 @@ impl ThreeWayAnalyzer {
             path: history.path.clone(),
             lines: attributions,
             summary,
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        }
+    }
+
+    /// Bounded-memory analysis for files past `analysis.max_analysis_lines`
+    ///
+    /// The position-aware diffs, similarity search and block matching of
+    /// [`Self::analyze_with_normalizer`] grow with the file, quadratically in places.
+    /// Here lines are matched by hashed content only, in windows of
+    /// [`BOUNDED_WINDOW_LINES`] that are processed independently, and only line
+    /// ranges are kept: the result has `blocks`, no `lines`, and an
+    /// `analysis_warning` explaining the fallback.
+    pub fn analyze_bounded(
+        history: &FileEditHistory,
+        final_content: &str,
+        max_lines: usize,
+        normalizer: &dyn LineNormalizer,
+    ) -> FileAttributionResult {
+        let original: HashSet<u64> = history
+            .original
+            .content
+            .lines()
+            .map(|line| line_hash(normalizer, line))
+            .collect();
+        // Later edits override earlier ones, as in `build_ai_line_map`
+        let mut ai: HashMap<u64, usize> = HashMap::new();
+        for (idx, edit) in history.edits.iter().enumerate() {
+            for line in ai_output_lines(edit) {
+                ai.insert(line_hash(normalizer, line), idx);
+            }
+        }
+
+        let mut blocks: Vec<LineBlock> = Vec::new();
+        let mut window = Vec::with_capacity(BOUNDED_WINDOW_LINES);
+        let mut lines = final_content.lines().enumerate();
+        loop {
+            window.clear();
+            for (idx, line) in lines.by_ref().take(BOUNDED_WINDOW_LINES) {
+                let hash = line_hash(normalizer, line);
+                let edit = ai.get(&hash).map(|&i| &history.edits[i]);
+                let (source, confidence) = if original.contains(&hash) {
+                    (LineSource::Original, 1.0)
+                } else if let Some(edit) = edit {
+                    let edit_id = edit.edit_id.clone();
+                    (LineSource::AI { edit_id }, 1.0)
+                } else {
+                    (LineSource::Human, 0.9)
+                };
+                let edit = edit.filter(|_| source.is_ai());
+                window.push(LineAttribution {
+                    line_number: (idx + 1) as u32,
+                    content: line.to_string(),
+                    content_hash: None,
+                    source,
+                    edit_id: edit.map(|e| e.edit_id.clone()),
+                    prompt_index: edit.map(|e| e.prompt_index),
+                    confidence,
+                    timestamp: None,
+                });
+            }
+            if window.is_empty() {
+                break;
+            }
+
+            improve_attributions_with_surrounding_context(&mut window);
+            stamp_edit_timestamps(&mut window, history);
+            for block in FileAttributionResult::compute_blocks(&window) {
+                match blocks.last_mut() {
+                    Some(last)
+                        if last.start + last.len == block.start
+                            && last.source == block.source
+                            && last.prompt_index == block.prompt_index
+                            && last.timestamp == block.timestamp =>
+                    {
+                        last.len += block.len
+                    }
+                    _ => blocks.push(block),
+                }
+            }
+        }
+
+        let summary = FileAttributionResult::compute_block_summary(&blocks);
+        let analysis_warning = format!(
+            "{} lines exceed the analysis limit of {}; attributed by line content in \
+             {}-line windows and stored as line ranges",
+            summary.total_lines, max_lines, BOUNDED_WINDOW_LINES
+        );
+        FileAttributionResult {
+            path: history.path.clone(),
+            lines: Vec::new(),
+            summary,
+            binary: None,
+            blocks: Some(blocks),
+            analysis_warning: Some(analysis_warning),
+            secret_findings: Vec::new(),
         }
     }
+
+    /// Re-attribute lines of a merge commit that came from one of its ancestors
+    ///
+    /// `ancestors` holds the file's content in each parent and in the merge base.
+    /// Lines the final content shares with any of them (by position-aware diff) were
+    /// brought in by the merge rather than written while resolving it, so they become
+    /// `Original`; only genuinely new resolution lines keep their AI/human source.
+    pub fn mark_merge_inherited(result: &mut FileAttributionResult, ancestors: &[&str]) {
+        if result.lines.is_empty() || ancestors.is_empty() {
+            return;
+        }
+        let final_content: String = result
+            .lines
+            .iter()
+            .map(|line| format!("{}\n", line.content))
+            .collect();
+
+        let mut inherited = HashSet::new();
+        for ancestor in ancestors {
+            inherited.extend(
+                diff_map_lines(ancestor, &final_content)
+                    .into_iter()
+                    .map(|(_, target)| target),
+            );
+        }
+
+        for (idx, line) in result.lines.iter_mut().enumerate() {
+            if inherited.contains(&idx) && line.source != LineSource::Original {
+                line.source = LineSource::Original;
+                line.edit_id = None;
+                line.prompt_index = None;
+                line.confidence = 1.0;
+                line.timestamp = None;
+            }
+        }
+        result.summary = FileAttributionResult::compute_summary(&result.lines);
+    }
 }
 
 /// Build a set of normalized lines from content for fast lookup
-fn build_line_set(content: &str) -> HashSet<String> {
-    content.lines().map(normalize_for_key).collect()
+/// Hash of a line's match key; bounded analysis keeps hashes instead of normalized text
+fn line_hash(normalizer: &dyn LineNormalizer, line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    normalizer.line_key(line).hash(&mut hasher);
+    hasher.finish()
+}
+
+fn build_line_set(content: &str, normalizer: &dyn LineNormalizer) -> HashSet<String> {
+    content.lines().map(|l| normalizer.line_key(l)).collect()
 }
 
 /// Build a map from normalized line content -> (edit_id, prompt_index) for all AI edits
@@ -278,16 +783,17 @@ fn build_line_set(content: &str) -> HashSet<String> {
 /// the AI chose to include them.
 ///
 /// Lines are normalized (trailing whitespace trimmed) to handle git/editor differences.
-fn build_ai_line_map(history: &FileEditHistory) -> HashMap<String, (String, u32)> {
+fn build_ai_line_map(
+    history: &FileEditHistory,
+    normalizer: &dyn LineNormalizer,
+) -> HashMap<String, (String, u32)> {
     let mut map = HashMap::new();
 
     // Process edits in order - later edits override earlier ones
     for edit in &history.edits {
-        // ALL lines in the AI's output are AI-generated
-        // This ensures complete file rewrites are properly attributed
-        for line in edit.after.content.lines() {
+        for line in ai_output_lines(edit) {
             map.insert(
-                normalize_for_key(line),
+                normalizer.line_key(line),
                 (edit.edit_id.clone(), edit.prompt_index),
             );
         }
@@ -296,10 +802,28 @@ fn build_ai_line_map(history: &FileEditHistory) -> HashMap<String, (String, u32)
     map
 }
 
+/// Lines an edit produced
+///
+/// ALL lines in the AI's output are AI-generated, which ensures complete file rewrites
+/// are properly attributed. Inline completions are the exception: they only contribute
+/// the lines they added, since the rest of the document was typed around them.
+fn ai_output_lines(edit: &AIEdit) -> Vec<&str> {
+    if edit.tool != INLINE_COMPLETION_TOOL {
+        return edit.after.content.lines().collect();
+    }
+    TextDiff::from_lines(&edit.before.content, &edit.after.content)
+        .iter_all_changes()
+        .filter(|change| change.tag() == ChangeTag::Insert)
+        .map(|change| change.value().trim_end_matches(['\r', '\n']))
+        .collect()
+}
+
 /// Check if a normalized line exists in content
-fn line_in_content(line: &str, content: &str) -> bool {
-    let normalized = normalize_for_key(line);
-    content.lines().any(|l| normalize_for_key(l) == normalized)
+fn line_in_content(line: &str, content: &str, normalizer: &dyn LineNormalizer) -> bool {
+    let normalized = normalizer.line_key(line);
+    content
+        .lines()
+        .any(|l| normalizer.line_key(l) == normalized)
 }
 
 /// Map line indices from source to target using diff
@@ -343,10 +867,10 @@ fn attribute_line(
     line_number: u32,
     original_lines: &HashSet<String>,
     ai_line_sources: &HashMap<String, (String, u32)>,
-    _history: &FileEditHistory,
     similarity_threshold: f64,
+    normalizer: &dyn LineNormalizer,
 ) -> LineAttribution {
-    let normalized = normalize_for_key(line);
+    let normalized = normalizer.line_key(line);
     let in_original = original_lines.contains(&normalized);
     let in_ai = ai_line_sources.get(&normalized);
 
@@ -356,10 +880,12 @@ fn attribute_line(
         return LineAttribution {
             line_number,
             content: line.to_string(),
+            content_hash: None,
             source: LineSource::Original,
             edit_id: None,
             prompt_index: None,
             confidence: 1.0,
+            timestamp: None,
         };
     }
 
@@ -369,10 +895,12 @@ fn attribute_line(
         return LineAttribution {
             line_number,
             content: line.to_string(),
+            content_hash: None,
             source: LineSource::Original,
             edit_id: None,
             prompt_index: None,
             confidence: 1.0,
+            timestamp: None,
         };
     }
 
@@ -381,22 +909,25 @@ fn attribute_line(
         return LineAttribution {
             line_number,
             content: line.to_string(),
+            content_hash: None,
             source: LineSource::AI {
                 edit_id: edit_id.clone(),
             },
             edit_id: Some(edit_id.clone()),
             prompt_index: Some(*prompt_idx),
             confidence: 1.0,
+            timestamp: None,
         };
     }
 
     // Check if line is similar to an AI line (human modified AI output)
     if let Some((edit_id, prompt_idx, similarity)) =
-        find_similar_ai_line(line, ai_line_sources, similarity_threshold)
+        find_similar_ai_line(line, ai_line_sources, similarity_threshold, normalizer)
     {
         return LineAttribution {
             line_number,
             content: line.to_string(),
+            content_hash: None,
             source: LineSource::AIModified {
                 edit_id: edit_id.clone(),
                 similarity,
@@ -404,6 +935,7 @@ fn attribute_line(
             edit_id: Some(edit_id),
             prompt_index: Some(prompt_idx),
             confidence: similarity,
+            timestamp: None,
         };
     }
 
@@ -411,10 +943,12 @@ fn attribute_line(
     LineAttribution {
         line_number,
         content: line.to_string(),
+        content_hash: None,
         source: LineSource::Human,
         edit_id: None,
         prompt_index: None,
         confidence: 0.9,
+        timestamp: None,
     }
 }
 
@@ -426,8 +960,9 @@ fn find_similar_ai_line(
     line: &str,
     ai_lines: &HashMap<String, (String, u32)>,
     threshold: f64,
+    normalizer: &dyn LineNormalizer,
 ) -> Option<(String, u32, f64)> {
-    let line_trimmed = line.trim();
+    let line_trimmed = normalizer.similarity_text(line);
 
     // Empty lines should be handled by exact matching, not similarity
     // (empty lines match other empty lines with 100% similarity via normalize_for_key)
@@ -438,14 +973,14 @@ fn find_similar_ai_line(
     let mut best_match: Option<(String, u32, f64)> = None;
 
     for (ai_line, (edit_id, prompt_idx)) in ai_lines {
-        let ai_trimmed = ai_line.trim();
+        let ai_trimmed = normalizer.similarity_text(ai_line);
 
         // Skip empty AI lines in similarity comparison
         if ai_trimmed.is_empty() {
             continue;
         }
 
-        let similarity = compute_similarity(line_trimmed, ai_trimmed);
+        let similarity = compute_similarity(&line_trimmed, &ai_trimmed);
         if similarity >= threshold
             && (best_match.is_none() || similarity > best_match.as_ref().unwrap().2)
         {
@@ -457,7 +992,7 @@ fn find_similar_ai_line(
 }
 
 /// Compute similarity between two strings (0.0 - 1.0)
-fn compute_similarity(a: &str, b: &str) -> f64 {
+pub(crate) fn compute_similarity(a: &str, b: &str) -> f64 {
     if a == b {
         return 1.0;
     }
@@ -500,11 +1035,32 @@ fn longest_common_subsequence(a: &str, b: &str) -> usize {
     dp[m][n]
 }
 
+/// Record when the edit behind each AI line was captured
+fn stamp_edit_timestamps(attributions: &mut [LineAttribution], history: &FileEditHistory) {
+    let timestamps: HashMap<&str, &str> = history
+        .edits
+        .iter()
+        .map(|e| (e.edit_id.as_str(), e.timestamp.as_str()))
+        .collect();
+
+    for attr in attributions.iter_mut() {
+        attr.timestamp = if attr.source.is_ai() {
+            attr.edit_id
+                .as_deref()
+                .and_then(|id| timestamps.get(id))
+                .map(|ts| ts.to_string())
+        } else {
+            None
+        };
+    }
+}
+
 /// Improve attributions using contextual information
 fn improve_attributions_with_context(
     attributions: &mut [LineAttribution],
     history: &FileEditHistory,
-    _final_content: &str,
+    normalizer: &dyn LineNormalizer,
+    max_block_lines: usize,
 ) {
     let len = attributions.len();
     if len < 2 {
@@ -532,7 +1088,10 @@ fn improve_attributions_with_context(
     // Second pass: block-level matching for reformatted code
     // This handles cases where formatters (rustfmt, prettier, etc.) split
     // a single AI-generated line into multiple lines
-    improve_attributions_with_block_matching(attributions, history);
+    if normalizer.block_matching() && max_block_lines > 0 {
+        let index = BlockIndex::build(history, normalizer, max_block_lines);
+        improve_attributions_with_block_matching(attributions, &index, normalizer);
+    }
 
     // Third pass: context-based attribution for remaining unmatched lines
     // If a Human/AIModified line is surrounded by AI lines from the same edit,
@@ -656,6 +1215,100 @@ fn normalize_for_block_comparison(s: &str) -> String {
         .replace("( ", "(")
 }
 
+/// Normalized AI output text that a block of committed lines may match
+struct BlockCandidate {
+    text: String,
+    edit: usize,
+}
+
+/// Single AI lines and joined runs of consecutive AI lines, built once per analysis
+///
+/// Windows start at every line of every edit and grow one line at a time until
+/// they reach `max_block_lines` lines or [`BLOCK_JOIN_BUDGET`] bytes of normalized
+/// text. Candidates are kept sorted by length, so a block is only scored against
+/// candidates whose length leaves the similarity threshold reachable.
+struct BlockIndex {
+    candidates: Vec<BlockCandidate>,
+    edits: Vec<(String, u32)>,
+    max_block_lines: usize,
+}
+
+impl BlockIndex {
+    fn build(
+        history: &FileEditHistory,
+        normalizer: &dyn LineNormalizer,
+        max_block_lines: usize,
+    ) -> Self {
+        let mut candidates = Vec::new();
+        let mut edits = Vec::with_capacity(history.edits.len());
+
+        for (edit_index, edit) in history.edits.iter().enumerate() {
+            edits.push((edit.edit_id.clone(), edit.prompt_index));
+            let lines = ai_output_lines(edit);
+            // Each line is normalized once; window extents are sized from these lengths
+            let single: Vec<String> = lines.iter().map(|l| normalizer.block_text(&[l])).collect();
+
+            for (start, text) in single.iter().enumerate() {
+                if !text.is_empty() {
+                    candidates.push(BlockCandidate {
+                        text: text.clone(),
+                        edit: edit_index,
+                    });
+                }
+                let mut joined_len = text.len();
+                for end in start + 2..=(start + max_block_lines).min(lines.len()) {
+                    joined_len += 1 + single[end - 1].len();
+                    if joined_len > BLOCK_JOIN_BUDGET {
+                        break;
+                    }
+                    let joined = normalizer.block_text(&lines[start..end]);
+                    if !joined.is_empty() {
+                        candidates.push(BlockCandidate {
+                            text: joined,
+                            edit: edit_index,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Stable: among equally long candidates, earlier edits and lines come first
+        candidates.sort_by_key(|candidate| candidate.text.len());
+        Self {
+            candidates,
+            edits,
+            max_block_lines,
+        }
+    }
+
+    /// Best candidate scoring at least `threshold` against `text`
+    fn best_match(&self, text: &str, threshold: f64) -> Option<(f64, &BlockCandidate)> {
+        // Similarity never exceeds shorter length / longer length
+        let len = text.len() as f64;
+        let lo = self
+            .candidates
+            .partition_point(|c| (c.text.len() as f64) < len * threshold);
+        let hi = self
+            .candidates
+            .partition_point(|c| (c.text.len() as f64) * threshold <= len);
+
+        let mut best: Option<(f64, &BlockCandidate)> = None;
+        for candidate in &self.candidates[lo..hi.max(lo)] {
+            let similarity = compute_similarity(text, &candidate.text);
+            let better = match best {
+                None => true,
+                Some((score, current)) => {
+                    similarity > score || (similarity == score && candidate.edit < current.edit)
+                }
+            };
+            if similarity >= threshold && better {
+                best = Some((similarity, candidate));
+            }
+        }
+        best
+    }
+}
+
 /// Improve attributions by matching blocks of consecutive Human lines
 /// against AI-generated content.
 ///
@@ -671,42 +1324,13 @@ fn normalize_for_block_comparison(s: &str) -> String {
 /// Each individual line doesn't match the AI output, but when joined they do.
 fn improve_attributions_with_block_matching(
     attributions: &mut [LineAttribution],
-    history: &FileEditHistory,
+    index: &BlockIndex,
+    normalizer: &dyn LineNormalizer,
 ) {
-    if attributions.is_empty() || history.edits.is_empty() {
+    if attributions.is_empty() || index.candidates.is_empty() {
         return;
     }
 
-    // Build normalized AI lines for comparison
-    // We normalize each AI line and also create joined versions of consecutive AI lines
-    let mut ai_normalized_lines: Vec<(String, String, u32)> = Vec::new(); // (normalized, edit_id, prompt_idx)
-
-    for edit in &history.edits {
-        for line in edit.after.content.lines() {
-            let normalized = normalize_for_block_comparison(line);
-            if !normalized.is_empty() {
-                ai_normalized_lines.push((normalized, edit.edit_id.clone(), edit.prompt_index));
-            }
-        }
-    }
-
-    // Also create joined versions of consecutive AI lines (2-8 lines joined)
-    for edit in &history.edits {
-        let lines: Vec<&str> = edit.after.content.lines().collect();
-        for window_size in 2..=8.min(lines.len()) {
-            for start in 0..=lines.len().saturating_sub(window_size) {
-                let joined: String = lines[start..start + window_size]
-                    .iter()
-                    .map(|l| normalize_for_block_comparison(l))
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                if !joined.is_empty() {
-                    ai_normalized_lines.push((joined, edit.edit_id.clone(), edit.prompt_index));
-                }
-            }
-        }
-    }
-
     // Find blocks of consecutive unmatched lines (Human or low-confidence AIModified)
     // Low-confidence AIModified lines are likely false positives from partial similarity matching
     let is_unmatched = |attr: &LineAttribution| -> bool {
@@ -736,46 +1360,34 @@ fn improve_attributions_with_block_matching(
 
         let block_len = block_end - block_start;
 
-        // Only process blocks of 1-8 lines
-        if (1..=8).contains(&block_len) {
+        // Only process blocks that a joined window could cover
+        if (1..=index.max_block_lines).contains(&block_len) {
             // Join the block content
-            let block_content: String = attributions[block_start..block_end]
+            let block_lines: Vec<&str> = attributions[block_start..block_end]
                 .iter()
-                .map(|a| normalize_for_block_comparison(&a.content))
-                .collect::<Vec<_>>()
-                .join(" ");
-
-            // Find best matching AI line/block
-            let mut best_match: Option<(f64, String, u32)> = None;
-
-            for (ai_normalized, edit_id, prompt_idx) in &ai_normalized_lines {
-                let similarity = compute_similarity(&block_content, ai_normalized);
-
-                // Require similarity threshold based on block size
-                // Lower thresholds because formatters can introduce small differences
-                // (e.g., extra spaces, line breaks in different positions)
-                let threshold = match block_len {
-                    1 => 0.75, // Single lines: might be partial match of split line
-                    2 => 0.70, // Common case: one line split into two
-                    3..=4 => 0.65,
-                    _ => 0.60,
-                };
-
-                if similarity >= threshold
-                    && (best_match.is_none() || similarity > best_match.as_ref().unwrap().0)
-                {
-                    best_match = Some((similarity, edit_id.clone(), *prompt_idx));
-                }
-            }
+                .map(|a| a.content.as_str())
+                .collect();
+            let block_content = normalizer.block_text(&block_lines);
+
+            // Require similarity threshold based on block size
+            // Lower thresholds because formatters can introduce small differences
+            // (e.g., extra spaces, line breaks in different positions)
+            let threshold = match block_len {
+                1 => 0.75, // Single lines: might be partial match of split line
+                2 => 0.70, // Common case: one line split into two
+                3..=4 => 0.65,
+                _ => 0.60,
+            };
 
             // If we found a match, re-attribute all lines in the block
-            if let Some((similarity, edit_id, prompt_idx)) = best_match {
+            if let Some((similarity, candidate)) = index.best_match(&block_content, threshold) {
+                let (edit_id, prompt_idx) = &index.edits[candidate.edit];
                 for attr in attributions.iter_mut().take(block_end).skip(block_start) {
                     attr.source = LineSource::AI {
                         edit_id: edit_id.clone(),
                     };
                     attr.edit_id = Some(edit_id.clone());
-                    attr.prompt_index = Some(prompt_idx);
+                    attr.prompt_index = Some(*prompt_idx);
                     attr.confidence = similarity;
                 }
             }
@@ -788,7 +1400,8 @@ fn improve_attributions_with_block_matching(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::capture::snapshot::AIEdit;
+    use crate::capture::snapshot::{compute_bytes_hash, AIEdit};
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_simple_ai_addition() {
@@ -811,6 +1424,126 @@ mod tests {
         assert_eq!(result.summary.human_lines, 0);
     }
 
+    #[test]
+    fn test_ai_lines_carry_edit_timestamp() {
+        let mut history = FileEditHistory::new("test.rs", Some("line1\n"));
+        let mut edit = AIEdit::new("Add line2", 0, "Edit", "line1\n", "line1\nline2\n");
+        edit.timestamp = "2026-03-01T12:00:00Z".to_string();
+        history.add_edit(edit);
+
+        let result = ThreeWayAnalyzer::analyze(&history, "line1\nline2\nhuman\n");
+
+        assert_eq!(result.lines[0].timestamp, None);
+        assert_eq!(
+            result.lines[1].timestamp.as_deref(),
+            Some("2026-03-01T12:00:00Z")
+        );
+        assert_eq!(result.lines[2].timestamp, None);
+    }
+
+    #[test]
+    fn test_mark_merge_inherited_keeps_only_resolution_lines() {
+        let ours = "fn a() {}\nfn ours() {}\n";
+        let theirs = "fn a() {}\nfn theirs() {}\n";
+        let resolved = "fn a() {}\nfn ours() {}\nfn theirs() {}\nfn glue() {}\nhuman\n";
+        let mut history = FileEditHistory::new("test.rs", Some(ours));
+        history.add_edit(AIEdit::new(
+            "Resolve conflict",
+            0,
+            "Edit",
+            ours,
+            "fn a() {}\nfn ours() {}\nfn theirs() {}\nfn glue() {}\n",
+        ));
+
+        let mut result = ThreeWayAnalyzer::analyze_with_diff(&history, resolved);
+        assert_eq!(result.summary.ai_lines, 2);
+
+        ThreeWayAnalyzer::mark_merge_inherited(&mut result, &[ours, theirs]);
+        assert_eq!(result.summary.original_lines, 3);
+        assert_eq!(result.summary.ai_lines, 1);
+        assert_eq!(result.summary.human_lines, 1);
+        assert_eq!(result.lines[2].source, LineSource::Original);
+        assert_eq!(result.lines[2].prompt_index, None);
+    }
+
+    #[test]
+    fn test_analyze_bounded_merges_blocks_across_windows() {
+        let ai_lines: Vec<String> = (0..BOUNDED_WINDOW_LINES + 10)
+            .map(|i| format!("let v{} = {};", i, i))
+            .collect();
+        let ai_content = format!("{}\n", ai_lines.join("\n"));
+        let mut history = FileEditHistory::new("big.rs", Some("fn main() {}\n"));
+        let ai_output = format!("fn main() {{}}\n{}", ai_content);
+        history.add_edit(AIEdit::new(
+            "Generate",
+            0,
+            "Edit",
+            "fn main() {}\n",
+            &ai_output,
+        ));
+        let final_content = format!("{}// tweak\n", ai_output);
+
+        let result =
+            ThreeWayAnalyzer::analyze_bounded(&history, &final_content, 1_000, &DefaultNormalizer);
+
+        assert!(result.lines.is_empty());
+        assert_eq!(result.summary.total_lines, BOUNDED_WINDOW_LINES + 12);
+        assert_eq!(result.summary.original_lines, 1);
+        assert_eq!(result.summary.ai_lines, BOUNDED_WINDOW_LINES + 10);
+        assert_eq!(result.summary.human_lines, 1);
+        let blocks = result.blocks.as_ref().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[1].len as usize, BOUNDED_WINDOW_LINES + 10);
+        assert!(result
+            .analysis_warning
+            .as_deref()
+            .unwrap()
+            .contains("analysis limit of 1000"));
+    }
+
+    #[test]
+    fn test_normalizer_registry_picks_longest_extension() {
+        let registry = NormalizerRegistry::default();
+        let key = |path: &str, line: &str| registry.for_path(path).line_key(line);
+
+        assert_eq!(key("src/app.py", "\treturn x"), "    return x");
+        assert_eq!(key("src/app.rs", "\treturn x"), "\treturn x");
+        assert_eq!(key("dist/App.MIN.js", "  a();b()  "), "a();b()");
+        assert!(registry
+            .for_path("config/ci.yml")
+            .similarity_text("  - run")
+            .starts_with(' '));
+
+        let overrides = BTreeMap::from([("py".to_string(), "default".to_string())]);
+        let registry = NormalizerRegistry::with_overrides(&overrides).unwrap();
+        assert_eq!(registry.for_path("a.py").line_key("\tx"), "\tx");
+
+        let overrides = BTreeMap::from([("js".to_string(), "nope".to_string())]);
+        assert!(NormalizerRegistry::with_overrides(&overrides).is_err());
+    }
+
+    #[test]
+    fn test_python_normalizer_matches_magic_trailing_comma() {
+        let ai = "def f():\n    return call(alpha_value, beta_value)\n";
+        let mut history = FileEditHistory::new("mod.py", Some(""));
+        history.add_edit(AIEdit::new("Add f", 0, "Write", "", ai));
+        let formatted =
+            "def f():\n    return call(\n        alpha_value,\n        beta_value,\n    )\n";
+
+        let result = ThreeWayAnalyzer::analyze_with_diff(&history, formatted);
+        assert_eq!(result.summary.ai_lines, 5);
+        assert!(result.lines.iter().all(|l| l.confidence == 1.0));
+
+        // The generic rules still match the block, but only approximately
+        let result = ThreeWayAnalyzer::analyze_with_normalizer(
+            &history,
+            formatted,
+            DEFAULT_SIMILARITY_THRESHOLD,
+            &DefaultNormalizer,
+        );
+        assert!(result.lines[2].confidence < 1.0);
+    }
+
     #[test]
     fn test_human_modification_after_ai() {
         let mut history = FileEditHistory::new("test.rs", Some("line1\n"));
@@ -1490,4 +2223,143 @@ fn test() {
         );
         assert_eq!(result.summary.ai_lines, 5, "All 5 lines should be AI");
     }
+
+    #[test]
+    fn test_block_matching_reflow_longer_than_eight_lines() {
+        let original = "";
+        let mut history = FileEditHistory::new("test.rs", Some(original));
+        let ai_output = "let config = Config::new(alpha, beta, gamma, delta, epsilon, \
+                         zeta, eta, theta, iota, kappa);\n";
+        history.add_edit(AIEdit::new(
+            "Generate code",
+            0,
+            "Write",
+            original,
+            ai_output,
+        ));
+
+        // One argument per line: 12 lines for a single AI statement
+        let final_content = "let config = Config::new(\n    alpha,\n    beta,\n    gamma,\n    \
+                             delta,\n    epsilon,\n    zeta,\n    eta,\n    theta,\n    iota,\n    \
+                             kappa,\n);\n";
+
+        let result = ThreeWayAnalyzer::analyze_with_diff(&history, final_content);
+        assert_eq!(result.summary.human_lines, 0);
+        assert_eq!(result.summary.ai_lines, 12);
+
+        // With the old fixed limit of 8 lines the block is left to humans
+        let limited = ThreeWayAnalyzer::analyze_with_block_limit(
+            &history,
+            final_content,
+            DEFAULT_SIMILARITY_THRESHOLD,
+            &DefaultNormalizer,
+            8,
+        );
+        assert!(limited.summary.human_lines > 0);
+    }
+
+    #[test]
+    fn test_block_index_windows_stop_at_join_budget() {
+        let long_line = format!("let value = \"{}\";", "x".repeat(200));
+        let short_lines: String = (0..100).map(|i| format!("a{};\n", i)).collect();
+        let long_lines = format!("{}\n", [long_line.as_str(); 6].join("\n"));
+
+        let mut history = FileEditHistory::new("test.rs", Some(""));
+        history.add_edit(AIEdit::new("Short", 0, "Write", "", &short_lines));
+        history.add_edit(AIEdit::new("Long", 1, "Write", "", &long_lines));
+        let index = BlockIndex::build(&history, &DefaultNormalizer, DEFAULT_MAX_BLOCK_LINES);
+
+        assert!(index
+            .candidates
+            .iter()
+            .all(|c| c.text.len() <= BLOCK_JOIN_BUDGET));
+        // Short lines join up to the line limit; long lines only in pairs
+        let short_windows = index.candidates.iter().filter(|c| c.edit == 0).count();
+        let long_windows = index.candidates.iter().filter(|c| c.edit == 1).count();
+        assert_eq!(
+            short_windows,
+            100 + (2..=16).map(|n| 101 - n).sum::<usize>()
+        );
+        assert_eq!(long_windows, 6 + 5);
+        // Sorted by length for the length-bounded search
+        assert!(index
+            .candidates
+            .windows(2)
+            .all(|pair| pair[0].text.len() <= pair[1].text.len()));
+    }
+
+    #[test]
+    fn test_analyze_binary() {
+        let mut history = FileEditHistory::new("logo.png", None);
+        history.add_edit(AIEdit::new("Add logo", 3, "Write", "", "PNGDATA"));
+
+        let result = ThreeWayAnalyzer::analyze_binary(&history, b"PNGDATA");
+        assert!(result.is_binary());
+        assert!(result.lines.is_empty());
+        assert_eq!(
+            result.binary,
+            Some(BinaryAttribution {
+                ai_generated: true,
+                tool: "Write".to_string(),
+                prompt_index: 3,
+            })
+        );
+
+        let changed = ThreeWayAnalyzer::analyze_binary(&history, &[0xff, 0xfe, 0x00]);
+        assert!(!changed.binary.unwrap().ai_generated);
+    }
+
+    #[test]
+    fn test_analyze_binary_compares_raw_bytes_hash() {
+        let png: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\xff\xd8";
+        let mut history = FileEditHistory::new("logo.png", None);
+        // The hook only sees the bytes as lossily decoded UTF-8
+        let lossy = String::from_utf8_lossy(png).into_owned();
+        history.add_edit(AIEdit::new("Add logo", 0, "Write", "", &lossy));
+        assert!(
+            !ThreeWayAnalyzer::analyze_binary(&history, png)
+                .binary
+                .unwrap()
+                .ai_generated
+        );
+
+        history.edits[0].after.bytes_hash = Some(compute_bytes_hash(png));
+        let result = ThreeWayAnalyzer::analyze_binary(&history, png);
+        assert!(result.binary.unwrap().ai_generated);
+
+        let mut changed = png.to_vec();
+        changed.push(0);
+        assert!(
+            !ThreeWayAnalyzer::analyze_binary(&history, &changed)
+                .binary
+                .unwrap()
+                .ai_generated
+        );
+    }
+
+    #[test]
+    fn test_analyze_new_file_matches_full_analysis() {
+        let content = "fn main() {\n    println!(\"hi\");\n}\n\n}\n";
+        let mut history = FileEditHistory::new("src/main.rs", None);
+        history.add_edit(AIEdit::new("Scaffold", 0, "Write", "", content));
+
+        let fast = ThreeWayAnalyzer::analyze_new_file(&history, content).unwrap();
+        let full = ThreeWayAnalyzer::analyze_with_normalizer(
+            &history,
+            content,
+            DEFAULT_SIMILARITY_THRESHOLD,
+            &DefaultNormalizer,
+        );
+        assert_eq!(
+            serde_json::to_value(&fast).unwrap(),
+            serde_json::to_value(&full).unwrap()
+        );
+
+        // Changed after the edit, or built from several edits: full analysis only
+        assert!(ThreeWayAnalyzer::analyze_new_file(&history, "fn main() {}\n").is_none());
+        history.add_edit(AIEdit::new("More", 1, "Edit", content, "fn main() {}\n"));
+        assert!(ThreeWayAnalyzer::analyze_new_file(&history, "fn main() {}\n").is_none());
+        let existing = FileEditHistory::new("src/lib.rs", Some(""));
+        assert!(ThreeWayAnalyzer::analyze_new_file(&existing, "").is_none());
+    }
 }
@@ -0,0 +1,364 @@
+//! Working-tree watch mode
+//!
+//! Captures edits from AI tools that have no hook system by watching the
+//! worktree for changes and recording before/after snapshots into the
+//! pending buffer.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use git2::{Repository, Status, StatusOptions};
+use notify::{RecursiveMode, Watcher};
+
+use crate::capture::encoding::{self, DecodedText};
+use crate::capture::hook::{CaptureHook, HookInput};
+use crate::core::attribution::ModelInfo;
+use crate::utils::repo_path;
+
+/// Tool name recorded for edits captured by the watcher
+pub const WATCH_TOOL: &str = "Watch";
+
+/// Prompt recorded when no prompt text is available
+const FALLBACK_PROMPT: &str = "(captured by whogitit watch-capture)";
+
+/// Where the prompt text for captured edits comes from
+#[derive(Debug, Clone)]
+pub enum PromptSource {
+    /// Fixed prompt text for the whole session
+    Text(String),
+    /// Sidecar file re-read on every change (falls back to the default prompt when empty)
+    File(PathBuf),
+}
+
+impl PromptSource {
+    /// Resolve the current prompt text
+    pub fn current(&self) -> String {
+        let text = match self {
+            PromptSource::Text(text) => text.trim().to_string(),
+            PromptSource::File(path) => std::fs::read_to_string(path)
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default(),
+        };
+        if text.is_empty() {
+            FALLBACK_PROMPT.to_string()
+        } else {
+            text
+        }
+    }
+}
+
+/// Watch session state: last known content of each file
+pub struct WatchSession {
+    repo_root: PathBuf,
+    hook: CaptureHook,
+    session_id: String,
+    model: ModelInfo,
+    prompt: PromptSource,
+    /// Last known content by repository-relative path
+    known: HashMap<String, String>,
+    /// Sidecar prompt file (relative), excluded from capture
+    prompt_file_rel: Option<String>,
+}
+
+impl WatchSession {
+    /// Create a watch session, snapshotting files that are already dirty
+    pub fn new(
+        repo_root: &Path,
+        session_id: &str,
+        model: ModelInfo,
+        prompt: PromptSource,
+    ) -> Result<Self> {
+        let repo_root = repo_root
+            .canonicalize()
+            .unwrap_or_else(|_| repo_root.to_path_buf());
+        let hook = CaptureHook::new(&repo_root)?;
+        let prompt_file_rel = match &prompt {
+            PromptSource::File(path) => path
+                .canonicalize()
+                .ok()
+                .and_then(|p| p.strip_prefix(&repo_root).ok().map(path_to_string)),
+            PromptSource::Text(_) => None,
+        };
+
+        let mut session = Self {
+            repo_root,
+            hook,
+            session_id: session_id.to_string(),
+            model,
+            prompt,
+            known: HashMap::new(),
+            prompt_file_rel,
+        };
+        session.snapshot_dirty_files()?;
+        Ok(session)
+    }
+
+    /// Record current content of modified/untracked files so pre-existing
+    /// human changes are not attributed to AI. Clean files fall back to HEAD.
+    fn snapshot_dirty_files(&mut self) -> Result<()> {
+        let repo = Repository::open(&self.repo_root).context("Failed to open repository")?;
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+        let statuses = repo
+            .statuses(Some(&mut opts))
+            .context("Failed to read repository status")?;
+
+        for entry in statuses.iter() {
+            if entry.status().contains(Status::WT_DELETED) {
+                continue;
+            }
+            let rel = repo_path::encode(entry.path_bytes());
+            if !is_capturable(&rel) {
+                continue;
+            }
+            if let Some(decoded) =
+                encoding::read_text_file(&self.repo_root.join(repo_path::to_path(&rel)))
+            {
+                self.known.insert(rel, decoded.text);
+            }
+        }
+        Ok(())
+    }
+
+    /// Map an absolute event path to a capturable repository-relative path
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        let rel = path.strip_prefix(&self.repo_root).ok()?;
+        let rel = path_to_string(rel);
+        if !is_capturable(&rel) || self.prompt_file_rel.as_deref() == Some(rel.as_str()) {
+            return None;
+        }
+        Some(rel)
+    }
+
+    /// Process a changed file; returns true if an edit was recorded
+    pub fn process_change(&mut self, repo: &Repository, rel: &str) -> Result<bool> {
+        let rel_path = repo_path::to_path(rel);
+        if repo.is_path_ignored(&rel_path).unwrap_or(false) {
+            return Ok(false);
+        }
+
+        let full_path = self.repo_root.join(rel_path);
+        if !full_path.is_file() {
+            return Ok(false);
+        }
+        // Skip binary or unreadable content
+        let Some(DecodedText {
+            text: new_content, ..
+        }) = encoding::read_text_file(&full_path)
+        else {
+            return Ok(false);
+        };
+
+        let old_content = self.known.get(rel).cloned();
+        if old_content.as_deref() == Some(new_content.as_str()) {
+            return Ok(false);
+        }
+
+        let input = HookInput {
+            tool: WATCH_TOOL.to_string(),
+            file_path: rel.to_string(),
+            prompt: self.prompt.current(),
+            old_content_present: old_content.is_some(),
+            old_content,
+            new_content: new_content.clone(),
+            context: None,
+        };
+        self.hook.on_file_change_for_session(
+            input,
+            Some(&self.session_id),
+            Some(self.model.clone()),
+        )?;
+        self.known.insert(rel.to_string(), new_content);
+
+        Ok(true)
+    }
+
+    /// Watch the worktree until the process is interrupted
+    pub fn run(&mut self, debounce: Duration) -> Result<()> {
+        let repo = Repository::open(&self.repo_root).context("Failed to open repository")?;
+        let (tx, rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(tx).context("Failed to start filesystem watcher")?;
+        watcher
+            .watch(&self.repo_root, RecursiveMode::Recursive)
+            .context("Failed to watch repository")?;
+
+        loop {
+            let first = rx.recv().context("Filesystem watcher stopped")?;
+            let mut changed: Vec<String> = Vec::new();
+            self.collect_event_paths(first, &mut changed);
+
+            // Debounce: gather the burst of events an editor emits on save
+            while let Ok(event) = rx.recv_timeout(debounce) {
+                self.collect_event_paths(event, &mut changed);
+            }
+
+            for rel in changed {
+                match self.process_change(&repo, &rel) {
+                    Ok(true) => println!("whogitit: Captured change to {}", rel),
+                    Ok(false) => {}
+                    Err(e) => eprintln!("whogitit: Warning - failed to capture {}: {}", rel, e),
+                }
+            }
+        }
+    }
+
+    fn collect_event_paths(&self, event: notify::Result<notify::Event>, out: &mut Vec<String>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("whogitit: Warning - watch error: {}", e);
+                return;
+            }
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        for path in event.paths {
+            if let Some(rel) = self.relative_path(&path) {
+                if !out.contains(&rel) {
+                    out.push(rel);
+                }
+            }
+        }
+    }
+}
+
+/// Whether a repository-relative path may be captured (excludes git and whogitit state)
+pub fn is_capturable(rel: &str) -> bool {
+    match repo_path::to_path(rel).components().next() {
+        Some(Component::Normal(first)) => {
+            let first = first.to_string_lossy();
+            first != ".git" && first != ".whogitit" && !first.starts_with(".whogitit-")
+        }
+        _ => false,
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    let path = repo_path::from_path(path);
+    if cfg!(windows) {
+        path.replace('\\', "/")
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::pending::PendingStore;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    const SESSION: &str = "6d1f0a2b-3c4d-4e5f-8a9b-0c1d2e3f4a5b";
+
+    fn create_test_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        {
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+                .unwrap();
+        }
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_is_capturable() {
+        assert!(is_capturable("src/main.rs"));
+        assert!(is_capturable(".whogitit.toml"));
+        assert!(!is_capturable(".git/index"));
+        assert!(!is_capturable(".whogitit-pending.json"));
+        assert!(!is_capturable(".whogitit/state/hook-debug.log"));
+        assert!(!is_capturable(""));
+    }
+
+    #[test]
+    fn test_prompt_source_text_and_fallback() {
+        assert_eq!(
+            PromptSource::Text("Add tests".into()).current(),
+            "Add tests"
+        );
+        assert_eq!(PromptSource::Text("  ".into()).current(), FALLBACK_PROMPT);
+    }
+
+    #[test]
+    fn test_prompt_source_file_is_reread() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("prompt.txt");
+        let source = PromptSource::File(path.clone());
+        assert_eq!(source.current(), FALLBACK_PROMPT);
+
+        std::fs::write(&path, "Refactor parser\n").unwrap();
+        assert_eq!(source.current(), "Refactor parser");
+    }
+
+    #[test]
+    fn test_process_change_records_edit_and_skips_unchanged() {
+        let (dir, repo) = create_test_repo();
+        let mut session = WatchSession::new(
+            dir.path(),
+            SESSION,
+            ModelInfo::new("gpt-5-codex", "openai"),
+            PromptSource::Text("Add hello".into()),
+        )
+        .unwrap();
+
+        std::fs::write(dir.path().join("hello.rs"), "fn hello() {}\n").unwrap();
+        assert!(session.process_change(&repo, "hello.rs").unwrap());
+        assert!(!session.process_change(&repo, "hello.rs").unwrap());
+
+        let buffer = PendingStore::new(dir.path()).load().unwrap().unwrap();
+        assert_eq!(buffer.session.session_id, SESSION);
+        assert_eq!(buffer.session.model.provider, "openai");
+        let history = buffer.get_file_history("hello.rs").unwrap();
+        assert_eq!(history.edits.len(), 1);
+        assert_eq!(history.edits[0].tool, WATCH_TOOL);
+    }
+
+    #[test]
+    fn test_dirty_files_snapshotted_at_start() {
+        let (dir, repo) = create_test_repo();
+        std::fs::write(dir.path().join("notes.txt"), "human draft\n").unwrap();
+
+        let mut session = WatchSession::new(
+            dir.path(),
+            SESSION,
+            ModelInfo::claude("test-model"),
+            PromptSource::Text("Expand notes".into()),
+        )
+        .unwrap();
+
+        std::fs::write(dir.path().join("notes.txt"), "human draft\nai line\n").unwrap();
+        assert!(session.process_change(&repo, "notes.txt").unwrap());
+
+        let buffer = PendingStore::new(dir.path()).load().unwrap().unwrap();
+        let history = buffer.get_file_history("notes.txt").unwrap();
+        assert_eq!(history.original.content, "human draft\n");
+    }
+
+    #[test]
+    fn test_ignored_files_are_skipped() {
+        let (dir, repo) = create_test_repo();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/out.txt"), "build\n").unwrap();
+
+        let mut session = WatchSession::new(
+            dir.path(),
+            SESSION,
+            ModelInfo::claude("test-model"),
+            PromptSource::Text("Build".into()),
+        )
+        .unwrap();
+        assert!(!session.process_change(&repo, "target/out.txt").unwrap());
+    }
+}
@@ -2,24 +2,36 @@
 //!
 //! This command outputs annotation data that can be consumed by GitHub's Checks API
 //! to display line-level AI attribution directly in the "Files changed" tab of PRs.
+//!
+//! Annotations are emitted at `notice` level unless the file matches a pattern in
+//! `[annotations.sensitive_paths]`, which raises them to `warning` or `failure`.
+//!
+//! `--format sarif` emits the same regions as a SARIF 2.1.0 log for code scanning
+//! tools, with one rule per kind of attributed region.
 
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use clap::{Args, ValueEnum};
 use colored::Colorize;
 use git2::Repository;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::capture::snapshot::LineSource;
 use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
-use crate::core::attribution::BlameLineResult;
+use crate::core::attribution::{prompt_hash, BlameLineResult};
 use crate::core::blame::AIBlamer;
+use crate::privacy::{SensitivePathLevel, WhogititConfig};
 use crate::storage::notes::NotesStore;
-use crate::utils::truncate_prompt;
+use crate::utils::{repo_path, truncate_prompt};
 
 const ANNOTATIONS_MACHINE_SCHEMA: &str = "whogitit.annotations.v1";
+const BASELINE_SCHEMA: &str = "whogitit.annotations-baseline.v1";
+const MERGE_QUEUE_MACHINE_SCHEMA: &str = "whogitit.merge-queue.v1";
 
 /// Output format for annotations
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -29,6 +41,8 @@ pub enum AnnotationsFormat {
     GithubChecks,
     /// Machine-readable JSON output
     Json,
+    /// SARIF 2.1.0 log (GitHub code scanning and other SARIF consumers)
+    Sarif,
 }
 
 /// Consolidation mode for annotations
@@ -56,7 +70,7 @@ pub enum SortMode {
 }
 
 /// Annotation level (maps to GitHub Checks API annotation_level)
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AnnotationLevel {
     Notice,
@@ -64,6 +78,78 @@ pub enum AnnotationLevel {
     Failure,
 }
 
+impl AnnotationLevel {
+    /// SARIF result level
+    fn sarif_level(self) -> &'static str {
+        match self {
+            AnnotationLevel::Notice => "note",
+            AnnotationLevel::Warning => "warning",
+            AnnotationLevel::Failure => "error",
+        }
+    }
+}
+
+impl From<SensitivePathLevel> for AnnotationLevel {
+    fn from(level: SensitivePathLevel) -> Self {
+        match level {
+            SensitivePathLevel::Notice => AnnotationLevel::Notice,
+            SensitivePathLevel::Warning => AnnotationLevel::Warning,
+            SensitivePathLevel::Failure => AnnotationLevel::Failure,
+        }
+    }
+}
+
+/// What an annotation covers, reported as the SARIF rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationKind {
+    /// Whole-file summary (consolidated annotation)
+    File,
+    /// Lines generated by AI and committed unchanged
+    Ai,
+    /// Lines generated by AI, then modified by a human
+    AiModified,
+    /// AI and AI-modified lines grouped together (`--group-ai-types`)
+    AiRelated,
+}
+
+impl AnnotationKind {
+    const ALL: [AnnotationKind; 4] = [
+        AnnotationKind::Ai,
+        AnnotationKind::AiModified,
+        AnnotationKind::AiRelated,
+        AnnotationKind::File,
+    ];
+
+    /// SARIF rule ID
+    fn rule_id(self) -> &'static str {
+        match self {
+            AnnotationKind::File => "whogitit/ai-file",
+            AnnotationKind::Ai => "whogitit/ai-generated",
+            AnnotationKind::AiModified => "whogitit/ai-modified",
+            AnnotationKind::AiRelated => "whogitit/ai-related",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            AnnotationKind::File => "File with a high share of AI-generated lines",
+            AnnotationKind::Ai => "Lines generated by AI and committed unchanged",
+            AnnotationKind::AiModified => "Lines generated by AI and then modified by a human",
+            AnnotationKind::AiRelated => "Lines generated or modified by AI",
+        }
+    }
+}
+
+impl From<GroupSourceType> for AnnotationKind {
+    fn from(source: GroupSourceType) -> Self {
+        match source {
+            GroupSourceType::AI => AnnotationKind::Ai,
+            GroupSourceType::AIModified => AnnotationKind::AiModified,
+            GroupSourceType::AIRelated => AnnotationKind::AiRelated,
+        }
+    }
+}
+
 /// A single annotation for the GitHub Checks API
 #[derive(Debug, Clone, Serialize)]
 pub struct CheckAnnotation {
@@ -82,6 +168,9 @@ pub struct CheckAnnotation {
     /// Optional raw details (not rendered as markdown)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_details: Option<String>,
+    /// Kind of region, for SARIF rules
+    #[serde(skip)]
+    pub kind: AnnotationKind,
 }
 
 /// Annotations command arguments
@@ -142,6 +231,27 @@ pub struct AnnotationsArgs {
     /// Maximum prompts for auto-consolidation (files with more prompts get granular annotations)
     #[arg(long, default_value = "3")]
     pub consolidate_prompt_limit: usize,
+
+    /// Suppress annotations whose fingerprints are listed in this baseline file
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    /// Write fingerprints of emitted and baselined annotations to this file
+    #[arg(long, value_name = "FILE")]
+    pub write_baseline: Option<PathBuf>,
+
+    /// Emit a compact pass/fail payload for merge queues instead of annotations
+    #[arg(long)]
+    pub merge_queue: bool,
+
+    /// Time budget for --merge-queue in milliseconds; files not blamed in time are reported as skipped
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = 10_000,
+        requires = "merge_queue"
+    )]
+    pub budget_ms: u64,
 }
 
 /// Summary of a prompt with line count
@@ -219,6 +329,53 @@ fn compute_annotation_score(stats: &FileStats, is_in_diff: bool) -> f64 {
     score
 }
 
+/// Fingerprint identifying an annotation across runs: path, line range, and prompt hash
+fn annotation_fingerprint(annotation: &CheckAnnotation) -> String {
+    let prompt = prompt_hash(annotation.raw_details.as_deref().unwrap_or(""));
+    format!(
+        "{}:{}-{}:{}",
+        annotation.path, annotation.start_line, annotation.end_line, prompt
+    )
+}
+
+/// Fingerprints of previously reported annotations
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AnnotationBaseline {
+    #[serde(default)]
+    schema_version: u8,
+    #[serde(default)]
+    schema: String,
+    fingerprints: BTreeSet<String>,
+}
+
+impl AnnotationBaseline {
+    /// Load a baseline; a missing file is an empty baseline (first CI run)
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse baseline {}", path.display()))
+    }
+
+    fn save(fingerprints: BTreeSet<String>, path: &Path) -> Result<()> {
+        let baseline = Self {
+            schema_version: MACHINE_OUTPUT_SCHEMA_VERSION,
+            schema: BASELINE_SCHEMA.to_string(),
+            fingerprints,
+        };
+        fs::write(path, serde_json::to_string_pretty(&baseline)? + "\n")
+            .with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    fn contains(&self, annotation: &CheckAnnotation) -> bool {
+        self.fingerprints
+            .contains(&annotation_fingerprint(annotation))
+    }
+}
+
 /// Check if repository is a shallow clone
 fn is_shallow_clone(repo: &Repository) -> bool {
     repo.is_shallow()
@@ -245,7 +402,7 @@ fn get_diff_ranges(
         None,
         Some(&mut |delta, hunk| {
             if let Some(path) = delta.new_file().path() {
-                let path_str = path.to_string_lossy().to_string();
+                let path_str = repo_path::from_path(path);
                 let start = hunk.new_start();
                 let end = start + hunk.new_lines().saturating_sub(1);
                 ranges.entry(path_str).or_default().push((start, end));
@@ -294,6 +451,22 @@ fn format_session_range(earliest: Option<&str>, latest: Option<&str>) -> Option<
 pub fn run(args: AnnotationsArgs) -> Result<()> {
     let repo = Repository::discover(".").context("Not in a git repository")?;
 
+    if args.merge_queue {
+        let config = repo
+            .workdir()
+            .map(WhogititConfig::load_or_warn)
+            .unwrap_or_default();
+        let output = merge_queue_check(
+            &repo,
+            args.base.as_deref(),
+            &args.head,
+            &config,
+            Duration::from_millis(args.budget_ms),
+        )?;
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
     // Determine effective consolidation mode for shallow clones
     let is_shallow = is_shallow_clone(&repo);
     let effective_consolidate = if is_shallow {
@@ -302,6 +475,12 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
             "Warning:".yellow()
         );
         ConsolidateMode::File
+    } else if matches!(
+        (args.format, args.consolidate),
+        (AnnotationsFormat::Sarif, ConsolidateMode::Auto)
+    ) {
+        // Code scanning shows each result inline, so report regions rather than files
+        ConsolidateMode::Lines
     } else {
         args.consolidate
     };
@@ -309,6 +488,12 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
     let notes_store = NotesStore::new(&repo)?;
     let mut blamer = AIBlamer::new(&repo)?;
 
+    let config = repo
+        .workdir()
+        .map(WhogititConfig::load_or_warn)
+        .unwrap_or_default();
+    let sensitive_paths = config.annotations.matcher();
+
     // Resolve head commit
     let head_obj = repo
         .revparse_single(&args.head)
@@ -394,11 +579,12 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
                     0.0
                 };
 
-                // Apply significance filters
-                if (ai_total as u32) < args.min_ai_lines {
+                // Apply significance filters (sensitive paths are always annotated)
+                let sensitive = sensitive_paths.level_for(&file.path).is_some();
+                if !sensitive && (ai_total as u32) < args.min_ai_lines {
                     continue;
                 }
-                if ai_percent < args.min_ai_percent {
+                if !sensitive && ai_percent < args.min_ai_percent {
                     continue;
                 }
 
@@ -456,12 +642,14 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
         };
 
         let score = compute_annotation_score(&file_stats, is_in_diff);
+        let sensitive_level = sensitive_paths.level_for(file_path);
 
         if should_consolidate {
             // Create a single file-level annotation
-            if let Some(annotation) =
+            if let Some(mut annotation) =
                 create_file_annotation(&file_stats, &models, session_range.as_deref())
             {
+                apply_sensitive_level(&mut annotation, sensitive_level);
                 // For file-level, check if the file itself is in diff
                 if diff_ranges.is_none() || is_in_diff {
                     candidates.push(AnnotationCandidate { annotation, score });
@@ -479,7 +667,8 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
                 session_range.as_deref(),
             );
 
-            for annotation in line_annotations {
+            for mut annotation in line_annotations {
+                apply_sensitive_level(&mut annotation, sensitive_level);
                 // Filter by diff ranges if --diff-only is enabled
                 if overlaps_diff(annotation.start_line, annotation.end_line, file_diff_ranges) {
                     candidates.push(AnnotationCandidate { annotation, score });
@@ -488,8 +677,32 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
         }
     }
 
-    // Sort candidates by score descending
-    candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    // Drop annotations already reported in the baseline
+    let baseline = args
+        .baseline
+        .as_deref()
+        .map(AnnotationBaseline::load)
+        .transpose()?;
+    let mut baselined = BTreeSet::new();
+    if let Some(baseline) = &baseline {
+        candidates.retain(|c| {
+            if baseline.contains(&c.annotation) {
+                baselined.insert(annotation_fingerprint(&c.annotation));
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    // Sort candidates by level, then score descending, so sensitive-path
+    // annotations survive truncation
+    candidates.sort_by(|a, b| {
+        b.annotation
+            .annotation_level
+            .cmp(&a.annotation.annotation_level)
+            .then_with(|| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal))
+    });
 
     // Truncate to max_annotations
     let annotations: Vec<CheckAnnotation> = candidates
@@ -498,10 +711,18 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
         .map(|c| c.annotation)
         .collect();
 
+    if let Some(path) = &args.write_baseline {
+        let mut fingerprints = baselined.clone();
+        fingerprints.extend(annotations.iter().map(annotation_fingerprint));
+        AnnotationBaseline::save(fingerprints, path)?;
+    }
+
     let summary = GithubChecksSummary {
         files_analyzed: files_to_annotate.len(),
         models,
         session_range,
+        conclusion: check_conclusion(&annotations),
+        baseline_suppressed: baseline.is_some().then_some(baselined.len()),
     };
 
     // Output based on format
@@ -528,11 +749,271 @@ pub fn run(args: AnnotationsArgs) -> Result<()> {
                 serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
             );
         }
+        AnnotationsFormat::Sarif => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&sarif_log(&annotations))
+                    .unwrap_or_else(|_| "{}".to_string())
+            );
+        }
     }
 
     Ok(())
 }
 
+/// SARIF 2.1.0 log with one result per annotation
+///
+/// Each kind of region is a rule, so code scanning can filter AI-generated,
+/// AI-modified and whole-file results separately. Fingerprints are the ones
+/// used for `--baseline`, so alerts keep their identity across runs.
+fn sarif_log(annotations: &[CheckAnnotation]) -> serde_json::Value {
+    let rules: Vec<serde_json::Value> = AnnotationKind::ALL
+        .iter()
+        .map(|kind| {
+            serde_json::json!({
+                "id": kind.rule_id(),
+                "shortDescription": { "text": kind.description() },
+                "defaultConfiguration": { "level": "note" },
+            })
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = annotations
+        .iter()
+        .map(|annotation| {
+            let rule_index = AnnotationKind::ALL
+                .iter()
+                .position(|kind| *kind == annotation.kind)
+                .unwrap_or_default();
+            let mut text = annotation.title.clone();
+            if let Some(prompt) = annotation
+                .raw_details
+                .as_deref()
+                .and_then(|details| details.lines().find(|line| !line.trim().is_empty()))
+            {
+                text.push_str(". Prompt: ");
+                text.push_str(&truncate_prompt(prompt, 200));
+            }
+            serde_json::json!({
+                "ruleId": annotation.kind.rule_id(),
+                "ruleIndex": rule_index,
+                "level": annotation.annotation_level.sarif_level(),
+                "message": { "text": text, "markdown": annotation.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": sarif_uri(&annotation.path),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": {
+                            "startLine": annotation.start_line,
+                            "endLine": annotation.end_line.max(annotation.start_line),
+                        },
+                    },
+                }],
+                "partialFingerprints": { "whogitit/v1": annotation_fingerprint(annotation) },
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "whogitit",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Relative URI of a repository path, percent-encoding everything but unreserved bytes
+fn sarif_uri(path: &str) -> String {
+    let mut uri = String::new();
+    for &byte in repo_path::decode(path).iter() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Compact check payload for merge queues (`--merge-queue`)
+#[derive(Debug, Serialize)]
+struct MergeQueueOutput {
+    schema_version: u8,
+    schema: &'static str,
+    /// `success`, `failure` (AI lines in a `failure` sensitive path) or `neutral`
+    /// (such a path could not be blamed within the budget)
+    conclusion: &'static str,
+    /// One-line summary for the check run title
+    title: String,
+    summary: MergeQueueSummary,
+    /// The budget ran out before every file was blamed
+    partial: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped_files: Vec<String>,
+    elapsed_ms: u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct MergeQueueSummary {
+    commits: usize,
+    changed_files: usize,
+    ai_files: usize,
+    ai_lines: usize,
+    models: Vec<String>,
+    /// AI-touched files under `failure` sensitive paths
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<String>,
+    /// AI-touched files under `warning` sensitive paths
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+}
+
+/// Build the merge-queue payload for `base..head`
+///
+/// Only files changed in the range that carry attribution in one of its commits
+/// are blamed, sensitive paths first. Once `budget` is spent, the remaining
+/// files are listed as skipped instead of blamed.
+fn merge_queue_check(
+    repo: &Repository,
+    base: Option<&str>,
+    head: &str,
+    config: &WhogititConfig,
+    budget: Duration,
+) -> Result<MergeQueueOutput> {
+    let start = Instant::now();
+    let head_commit = repo
+        .revparse_single(head)
+        .with_context(|| format!("Failed to resolve: {}", head))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", head))?;
+    // Without --base, check the head commit against its first parent
+    let base_commit = match base {
+        Some(base) => Some(
+            repo.revparse_single(base)
+                .with_context(|| format!("Failed to resolve base: {}", base))?
+                .peel_to_commit()
+                .with_context(|| format!("Not a valid commit: {}", base))?,
+        ),
+        None => head_commit.parent(0).ok(),
+    };
+
+    let base_tree = base_commit.as_ref().map(|c| c.tree()).transpose()?;
+    let diff = repo.diff_tree_to_tree(base_tree.as_ref(), Some(&head_commit.tree()?), None)?;
+    let changed: BTreeSet<String> = diff
+        .deltas()
+        .filter(|delta| delta.status() != git2::Delta::Deleted)
+        .filter_map(|delta| delta.new_file().path())
+        .map(repo_path::from_path)
+        .collect();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_commit.id())?;
+    if let Some(base_commit) = &base_commit {
+        revwalk.hide(base_commit.id())?;
+    }
+
+    // Attributions fetched here stay in the blamer's cache for the blames below
+    let mut blamer = AIBlamer::new(repo)?;
+    let mut summary = MergeQueueSummary {
+        changed_files: changed.len(),
+        ..Default::default()
+    };
+    let mut models = BTreeSet::new();
+    let mut attributed = BTreeSet::new();
+    for oid in revwalk {
+        let oid = oid?;
+        summary.commits += 1;
+        if let Some(attribution) = blamer.get_commit_attribution(&oid.to_string())? {
+            models.insert(attribution.session.model.id.clone());
+            attributed.extend(
+                attribution
+                    .files
+                    .iter()
+                    .filter(|f| changed.contains(&f.path))
+                    .map(|f| f.path.clone()),
+            );
+        }
+    }
+    summary.models = models.into_iter().collect();
+
+    let sensitive_paths = config.annotations.matcher();
+    let mut files: Vec<(String, Option<SensitivePathLevel>)> = attributed
+        .into_iter()
+        .map(|path| {
+            let level = sensitive_paths.level_for(&path);
+            (path, level)
+        })
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut skipped_files = Vec::new();
+    let mut skipped_failure = false;
+    for (path, level) in files {
+        if start.elapsed() >= budget {
+            skipped_failure |= level == Some(SensitivePathLevel::Failure);
+            skipped_files.push(path);
+            continue;
+        }
+        let Ok(blame) = blamer.blame(&path, Some(head)) else {
+            continue;
+        };
+        let ai_lines = blame.lines.iter().filter(|line| line.is_ai()).count();
+        if ai_lines == 0 {
+            continue;
+        }
+        summary.ai_files += 1;
+        summary.ai_lines += ai_lines;
+        match level {
+            Some(SensitivePathLevel::Failure) => summary.failures.push(path),
+            Some(SensitivePathLevel::Warning) => summary.warnings.push(path),
+            _ => {}
+        }
+    }
+
+    let conclusion = merge_queue_conclusion(!summary.failures.is_empty(), skipped_failure);
+    let mut title = format!(
+        "AI attribution: {} of {} changed files, {} AI lines",
+        summary.ai_files, summary.changed_files, summary.ai_lines
+    );
+    if !skipped_files.is_empty() {
+        title.push_str(&format!(" (partial: {} skipped)", skipped_files.len()));
+    }
+
+    Ok(MergeQueueOutput {
+        schema_version: MACHINE_OUTPUT_SCHEMA_VERSION,
+        schema: MERGE_QUEUE_MACHINE_SCHEMA,
+        conclusion,
+        title,
+        summary,
+        partial: !skipped_files.is_empty(),
+        skipped_files,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// `failure` beats an undecided skipped failure path (`neutral`), else `success`
+fn merge_queue_conclusion(has_failures: bool, skipped_failure: bool) -> &'static str {
+    if has_failures {
+        "failure"
+    } else if skipped_failure {
+        "neutral"
+    } else {
+        "success"
+    }
+}
+
 /// Compute statistics for a file to help with consolidation decisions
 fn compute_file_stats(path: &str, lines: &[BlameLineResult]) -> FileStats {
     let mut ai_lines = 0;
@@ -588,7 +1069,7 @@ fn compute_file_stats(path: &str, lines: &[BlameLineResult]) -> FileStats {
             }
         })
         .collect();
-    prompts.sort_by(|a, b| b.line_count.cmp(&a.line_count));
+    prompts.sort_by_key(|p| std::cmp::Reverse(p.line_count));
 
     FileStats {
         path: path.to_string(),
@@ -602,6 +1083,32 @@ fn compute_file_stats(path: &str, lines: &[BlameLineResult]) -> FileStats {
     }
 }
 
+/// Raise an annotation to the level configured for its sensitive path
+fn apply_sensitive_level(annotation: &mut CheckAnnotation, level: Option<SensitivePathLevel>) {
+    let Some(level) = level else {
+        return;
+    };
+    annotation.annotation_level = level.into();
+    if annotation.annotation_level != AnnotationLevel::Notice {
+        annotation.message = format!(
+            "**Sensitive path:** AI changes here require human review.\n\n{}",
+            annotation.message
+        );
+    }
+}
+
+/// Suggested check run conclusion: `failure` if any annotation fails, else `neutral`
+fn check_conclusion(annotations: &[CheckAnnotation]) -> &'static str {
+    if annotations
+        .iter()
+        .any(|a| a.annotation_level == AnnotationLevel::Failure)
+    {
+        "failure"
+    } else {
+        "neutral"
+    }
+}
+
 /// Create a single file-level annotation
 fn create_file_annotation(
     stats: &FileStats,
@@ -698,6 +1205,7 @@ fn create_file_annotation(
         title,
         message: message_lines.join("\n"),
         raw_details,
+        kind: AnnotationKind::File,
     })
 }
 
@@ -797,6 +1305,7 @@ fn create_line_annotations(
             title,
             message,
             raw_details: group.prompt_preview.clone(),
+            kind: group.source_type.into(),
         });
     }
 
@@ -818,6 +1327,11 @@ struct GithubChecksSummary {
     /// Session time range (e.g., "2024-01-15 to 2024-01-20")
     #[serde(skip_serializing_if = "Option::is_none")]
     session_range: Option<String>,
+    /// Suggested check run conclusion ("failure" when a sensitive path fails)
+    conclusion: &'static str,
+    /// Annotations suppressed because they were in the `--baseline` file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    baseline_suppressed: Option<usize>,
 }
 
 /// Stable machine output for `annotations --format json`.
@@ -941,6 +1455,8 @@ mod tests {
             source,
             prompt_index: Some(0),
             prompt_preview: Some("Test prompt".to_string()),
+            generated_at: None,
+            confidence: None,
         }
     }
 
@@ -1026,6 +1542,7 @@ mod tests {
             title: "AI Generated (5 lines)".to_string(),
             message: "Model: claude-opus-4-5-20251101".to_string(),
             raw_details: Some("Prompt: Add main function".to_string()),
+            kind: AnnotationKind::Ai,
         };
 
         let json = serde_json::to_string(&annotation).unwrap();
@@ -1311,6 +1828,8 @@ mod tests {
                 "claude-opus-4-5-20251101".to_string(),
             ],
             session_range: Some("2024-01-15 to 2024-01-20".to_string()),
+            conclusion: "neutral",
+            baseline_suppressed: None,
         };
 
         let json = serde_json::to_string(&summary).unwrap();
@@ -1326,6 +1845,8 @@ mod tests {
             files_analyzed: 3,
             models: vec!["claude-sonnet-4-20250514".to_string()],
             session_range: None,
+            conclusion: "neutral",
+            baseline_suppressed: None,
         };
 
         let json = serde_json::to_string(&summary).unwrap();
@@ -1347,11 +1868,14 @@ mod tests {
                 title: "AI Generated (1 line)".to_string(),
                 message: "Model: claude-opus-4-5-20251101".to_string(),
                 raw_details: None,
+                kind: AnnotationKind::Ai,
             }],
             summary: GithubChecksSummary {
                 files_analyzed: 1,
                 models: vec!["claude-opus-4-5-20251101".to_string()],
                 session_range: Some("2024-01-15".to_string()),
+                conclusion: "neutral",
+                baseline_suppressed: None,
             },
         };
 
@@ -1364,4 +1888,157 @@ mod tests {
         assert!(json["annotations"].is_array());
         assert!(json["summary"].is_object());
     }
+
+    fn notice(path: &str) -> CheckAnnotation {
+        CheckAnnotation {
+            path: path.to_string(),
+            start_line: 1,
+            end_line: 3,
+            annotation_level: AnnotationLevel::Notice,
+            title: "AI Generated (3 lines)".to_string(),
+            message: "Model: claude-opus-4-5-20251101".to_string(),
+            raw_details: None,
+            kind: AnnotationKind::Ai,
+        }
+    }
+
+    #[test]
+    fn test_apply_sensitive_level() {
+        let mut annotation = notice("src/auth/login.rs");
+        apply_sensitive_level(&mut annotation, None);
+        assert_eq!(annotation.annotation_level, AnnotationLevel::Notice);
+        assert!(!annotation.message.contains("Sensitive path"));
+
+        apply_sensitive_level(&mut annotation, Some(SensitivePathLevel::Failure));
+        assert_eq!(annotation.annotation_level, AnnotationLevel::Failure);
+        assert!(annotation.message.starts_with("**Sensitive path:**"));
+        let json = serde_json::to_string(&annotation).unwrap();
+        assert!(json.contains("\"annotation_level\":\"failure\""));
+    }
+
+    #[test]
+    fn test_check_conclusion() {
+        let mut annotations = vec![notice("src/main.rs")];
+        assert_eq!(check_conclusion(&annotations), "neutral");
+
+        annotations[0].annotation_level = AnnotationLevel::Warning;
+        assert_eq!(check_conclusion(&annotations), "neutral");
+
+        annotations.push(notice("crypto/aes.rs"));
+        annotations[1].annotation_level = AnnotationLevel::Failure;
+        assert_eq!(check_conclusion(&annotations), "failure");
+    }
+
+    #[test]
+    fn test_baseline_fingerprints_and_round_trip() {
+        let mut annotation = notice("src/main.rs");
+        annotation.raw_details = Some("Add   a parser".to_string());
+        let fingerprint = annotation_fingerprint(&annotation);
+        assert!(fingerprint.starts_with("src/main.rs:1-3:"));
+
+        // Whitespace-only prompt differences keep the fingerprint
+        let mut reformatted = annotation.clone();
+        reformatted.raw_details = Some("Add a parser".to_string());
+        assert_eq!(annotation_fingerprint(&reformatted), fingerprint);
+
+        // A moved range or different prompt is a new annotation
+        let mut moved = annotation.clone();
+        moved.end_line = 4;
+        assert_ne!(annotation_fingerprint(&moved), fingerprint);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("baseline.json");
+        assert!(AnnotationBaseline::load(&path)
+            .unwrap()
+            .fingerprints
+            .is_empty());
+
+        AnnotationBaseline::save(BTreeSet::from([fingerprint]), &path).unwrap();
+        let baseline = AnnotationBaseline::load(&path).unwrap();
+        assert_eq!(baseline.schema, BASELINE_SCHEMA);
+        assert!(baseline.contains(&reformatted));
+        assert!(!baseline.contains(&moved));
+    }
+
+    #[test]
+    fn test_merge_queue_conclusion() {
+        assert_eq!(merge_queue_conclusion(true, true), "failure");
+        assert_eq!(merge_queue_conclusion(false, true), "neutral");
+        assert_eq!(merge_queue_conclusion(false, false), "success");
+    }
+
+    #[test]
+    fn test_merge_queue_check_flags_sensitive_and_partial_results() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let config: WhogititConfig =
+            toml::from_str("[annotations.sensitive_paths]\n\"src/**\" = \"failure\"\n").unwrap();
+
+        let output = merge_queue_check(
+            &fixture.repo,
+            None,
+            "HEAD",
+            &config,
+            Duration::from_secs(60),
+        )
+        .unwrap();
+        assert_eq!(output.schema, MERGE_QUEUE_MACHINE_SCHEMA);
+        assert_eq!(output.conclusion, "failure");
+        assert_eq!(output.summary.commits, 1);
+        assert_eq!(output.summary.changed_files, 1);
+        assert_eq!(output.summary.failures, vec!["src/generated.rs"]);
+        assert_eq!(output.summary.models, vec!["fixture-model"]);
+        assert!(!output.partial);
+
+        let output =
+            merge_queue_check(&fixture.repo, None, "HEAD", &config, Duration::ZERO).unwrap();
+        assert!(output.partial);
+        assert_eq!(output.conclusion, "neutral");
+        assert_eq!(output.skipped_files, vec!["src/generated.rs"]);
+        assert_eq!(output.summary.ai_lines, 0);
+    }
+
+    #[test]
+    fn test_sarif_log_results() {
+        let mut modified = notice("src/auth.rs");
+        modified.kind = AnnotationKind::AiModified;
+        modified.annotation_level = AnnotationLevel::Failure;
+        modified.raw_details = Some("Add session expiry".to_string());
+        let log = sarif_log(&[notice("src/main.rs"), modified.clone()]);
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "whogitit");
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0]["ruleId"], "whogitit/ai-generated");
+        assert_eq!(results[0]["level"], "note");
+        assert_eq!(results[1]["ruleId"], "whogitit/ai-modified");
+        assert_eq!(results[1]["level"], "error");
+        let index = results[1]["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(rules[index]["id"], "whogitit/ai-modified");
+        assert_eq!(
+            results[1]["message"]["text"],
+            "AI Generated (3 lines). Prompt: Add session expiry"
+        );
+        let location = &results[1]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/auth.rs");
+        assert_eq!(location["region"]["startLine"], 1);
+        assert_eq!(location["region"]["endLine"], 3);
+        assert_eq!(
+            results[1]["partialFingerprints"]["whogitit/v1"],
+            annotation_fingerprint(&modified)
+        );
+    }
+
+    #[test]
+    fn test_sarif_uri_encoding() {
+        assert_eq!(sarif_uri("src/main.rs"), "src/main.rs");
+        assert_eq!(sarif_uri("docs/read me#1.md"), "docs/read%20me%231.md");
+        assert_eq!(sarif_uri("caf\u{e9}.rs"), "caf%C3%A9.rs");
+        assert_eq!(sarif_uri("\"caf\\351.rs\""), "caf%E9.rs");
+    }
 }
@@ -1,29 +1,83 @@
-//! Audit log viewing command
+//! Audit log viewing and export command
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use colored::Colorize;
 
-use crate::storage::audit::{AuditEventType, AuditLog};
+use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::privacy::{AuditForwardFormat, WhogititConfig};
+use crate::storage::audit::{
+    format_cef, format_syslog, hostname, syslog_facility, AuditEvent, AuditEventType, AuditLog,
+};
 
 /// Arguments for audit command
 #[derive(Debug, clap::Args)]
 pub struct AuditArgs {
+    /// Subcommand (omit to view recent events)
+    #[command(subcommand)]
+    pub command: Option<AuditCommand>,
+
     /// Only show events after this date (YYYY-MM-DD)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub since: Option<String>,
 
     /// Filter by event type
-    #[arg(long, value_parser = ["delete", "export", "retention_apply", "config_change", "redaction"])]
+    #[arg(long, global = true, value_parser = ["delete", "export", "retention_apply", "config_change", "redaction", "policy_hit", "prompt_access", "secret_detected", "pending_expired", "capture_paused", "capture_resumed"])]
     pub event_type: Option<String>,
 
+    /// Only show events for this redaction/secret pattern (e.g. API_KEY)
+    #[arg(long, global = true)]
+    pub pattern: Option<String>,
+
+    /// Only show events performed by this user
+    #[arg(long, global = true)]
+    pub actor: Option<String>,
+
     /// Output as JSON
     #[arg(long)]
     pub json: bool,
 
-    /// Show last N events
+    /// Events per page
     #[arg(long, default_value = "50")]
     pub limit: usize,
+
+    /// Page to show, newest first (1 = most recent events)
+    #[arg(long, default_value = "1")]
+    pub page: usize,
+
+    /// Summarize event counts per type per day instead of listing events
+    #[arg(long, conflicts_with = "page")]
+    pub stats: bool,
+}
+
+/// Audit subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum AuditCommand {
+    /// Export events for SIEM/compliance pipelines (oldest first, one per line)
+    Export {
+        /// Export format
+        #[arg(long, value_enum, default_value_t = AuditExportFormat::Json)]
+        format: AuditExportFormat,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Audit export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AuditExportFormat {
+    /// JSON Lines, as stored in the audit log
+    Json,
+    /// ArcSight Common Event Format
+    Cef,
+    /// RFC 5424 syslog with structured data
+    Syslog,
 }
 
 /// Run the audit command
@@ -35,8 +89,56 @@ pub fn run(args: AuditArgs) -> Result<()> {
 
     let audit_log = AuditLog::new(repo_root);
 
+    if let Some(AuditCommand::Export { format, output }) = &args.command {
+        let mut events = read_events(&audit_log, &args)?;
+        events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+        let config = WhogititConfig::load_or_warn(repo_root);
+        let facility = syslog_facility(&config.audit.forwarding.facility).with_context(|| {
+            format!(
+                "Unknown syslog facility '{}'",
+                config.audit.forwarding.facility
+            )
+        })?;
+        let hostname = hostname();
+        let lines = events
+            .iter()
+            .map(|event| export_line(event, *format, facility, &hostname))
+            .collect::<Result<Vec<_>>>()?;
+
+        return match output {
+            Some(path) => {
+                let mut content = lines.join("\n");
+                if !content.is_empty() {
+                    content.push('\n');
+                }
+                std::fs::write(path, content)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                eprintln!(
+                    "Exported {} audit events to {}",
+                    lines.len(),
+                    path.display()
+                );
+                Ok(())
+            }
+            None => {
+                let mut stdout = std::io::stdout().lock();
+                for line in &lines {
+                    writeln!(stdout, "{}", line)?;
+                }
+                Ok(())
+            }
+        };
+    }
+
+    if args.page == 0 {
+        anyhow::bail!("--page starts at 1");
+    }
+
     if !audit_log.exists() {
-        if args.json {
+        if args.json && args.stats {
+            println!("{}", serde_json::to_string_pretty(&stats_json(&[]))?);
+        } else if args.json {
             println!("[]");
         } else {
             println!("No audit log found.");
@@ -48,7 +150,144 @@ pub fn run(args: AuditArgs) -> Result<()> {
         return Ok(());
     }
 
-    // Read events
+    let mut events = read_events(&audit_log, &args)?;
+
+    // Sort by timestamp (newest first)
+    events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    if args.stats {
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&stats_json(&events))?);
+        } else {
+            print_stats(&events);
+        }
+        return Ok(());
+    }
+
+    let total = events.len();
+    let page = paginate(events, args.page, args.limit);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&page)?);
+    } else {
+        print_events(&page)?;
+        print_page_footer(args.page, args.limit, total);
+    }
+
+    Ok(())
+}
+
+/// Slice one page out of events already sorted newest first
+fn paginate(events: Vec<AuditEvent>, page: usize, limit: usize) -> Vec<AuditEvent> {
+    events
+        .into_iter()
+        .skip(page.saturating_sub(1).saturating_mul(limit))
+        .take(limit)
+        .collect()
+}
+
+fn page_count(total: usize, limit: usize) -> usize {
+    if limit == 0 {
+        return 0;
+    }
+    (total + limit - 1) / limit
+}
+
+fn print_page_footer(page: usize, limit: usize, total: usize) {
+    let pages = page_count(total, limit);
+    if pages <= 1 && page == 1 {
+        return;
+    }
+    println!();
+    let mut footer = format!("Page {} of {} ({} events)", page, pages, total);
+    if page < pages {
+        footer.push_str(&format!(" - use --page {} for older events", page + 1));
+    }
+    println!("{}", footer.dimmed());
+}
+
+/// Event counts keyed by day (YYYY-MM-DD, UTC), then by event type
+fn daily_counts(events: &[AuditEvent]) -> BTreeMap<String, BTreeMap<String, usize>> {
+    let mut days: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    for event in events {
+        *days
+            .entry(event_day(event))
+            .or_default()
+            .entry(event.event.to_string())
+            .or_default() += 1;
+    }
+    days
+}
+
+fn event_day(event: &AuditEvent) -> String {
+    DateTime::parse_from_rfc3339(&event.timestamp)
+        .map(|t| t.with_timezone(&Utc).format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| event.timestamp.chars().take(10).collect())
+}
+
+fn stats_json(events: &[AuditEvent]) -> serde_json::Value {
+    let days = daily_counts(events);
+    let mut by_type: BTreeMap<String, usize> = BTreeMap::new();
+    for counts in days.values() {
+        for (event_type, count) in counts {
+            *by_type.entry(event_type.clone()).or_default() += count;
+        }
+    }
+    let days: Vec<serde_json::Value> = days
+        .iter()
+        .rev()
+        .map(|(date, counts)| {
+            serde_json::json!({
+                "date": date,
+                "total": counts.values().sum::<usize>(),
+                "by_type": counts,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+        "schema": "whogitit.audit-stats.v1",
+        "total_events": events.len(),
+        "by_type": by_type,
+        "days": days,
+    })
+}
+
+fn print_stats(events: &[AuditEvent]) {
+    if events.is_empty() {
+        println!("No audit events found.");
+        return;
+    }
+
+    let days = daily_counts(events);
+
+    println!("{}", "Audit Stats".bold());
+    println!("{}", "=".repeat(60));
+    println!("{:<12} {:>7}  By type", "Date", "Events");
+    for (date, counts) in days.iter().rev() {
+        let breakdown: Vec<String> = counts
+            .iter()
+            .map(|(event_type, count)| format!("{}:{}", event_type, count))
+            .collect();
+        println!(
+            "{:<12} {:>7}  {}",
+            date,
+            counts.values().sum::<usize>(),
+            breakdown.join(" ").dimmed()
+        );
+    }
+    println!("{}", "-".repeat(60));
+    println!(
+        "{} events over {} day{}",
+        events.len(),
+        days.len(),
+        if days.len() == 1 { "" } else { "s" }
+    );
+}
+
+/// Read events matching --since, --event-type, --pattern and --actor
+fn read_events(audit_log: &AuditLog, args: &AuditArgs) -> Result<Vec<AuditEvent>> {
     let mut events = if let Some(since_str) = &args.since {
         let since_date = chrono::NaiveDate::parse_from_str(since_str, "%Y-%m-%d")
             .context("Invalid date format. Use YYYY-MM-DD.")?;
@@ -68,22 +307,39 @@ pub fn run(args: AuditArgs) -> Result<()> {
         events.retain(|e| e.event == event_type);
     }
 
-    // Sort by timestamp (newest first)
-    events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-    // Limit
-    events.truncate(args.limit);
+    if let Some(pattern) = &args.pattern {
+        events.retain(|e| {
+            e.details
+                .pattern_name
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(pattern))
+        });
+    }
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&events)?);
-    } else {
-        print_events(&events)?;
+    if let Some(actor) = &args.actor {
+        events.retain(|e| e.details.user.as_deref() == Some(actor.as_str()));
     }
 
-    Ok(())
+    Ok(events)
+}
+
+/// Render one event in an export format
+fn export_line(
+    event: &AuditEvent,
+    format: AuditExportFormat,
+    facility: u8,
+    hostname: &str,
+) -> Result<String> {
+    Ok(match format {
+        AuditExportFormat::Json => serde_json::to_string(event)?,
+        AuditExportFormat::Cef => format_cef(event),
+        AuditExportFormat::Syslog => {
+            format_syslog(event, facility, hostname, AuditForwardFormat::Syslog)
+        }
+    })
 }
 
-fn print_events(events: &[crate::storage::audit::AuditEvent]) -> Result<()> {
+fn print_events(events: &[AuditEvent]) -> Result<()> {
     if events.is_empty() {
         println!("No audit events found.");
         return Ok(());
@@ -107,6 +363,13 @@ fn print_events(events: &[crate::storage::audit::AuditEvent]) -> Result<()> {
             AuditEventType::RetentionApply => "retention".yellow(),
             AuditEventType::ConfigChange => "config".cyan(),
             AuditEventType::Redaction => "redaction".magenta(),
+            AuditEventType::PolicyHit => "policy_hit".red().bold(),
+            AuditEventType::NotesPushFailed => "push_failed".yellow(),
+            AuditEventType::PromptAccess => "prompt_access".cyan(),
+            AuditEventType::SecretDetected => "secret_detected".red().bold(),
+            AuditEventType::PendingExpired => "pending_expired".yellow(),
+            AuditEventType::CapturePaused => "capture_paused".yellow(),
+            AuditEventType::CaptureResumed => "capture_resumed".green(),
         };
 
         print!("{} {} ", timestamp.dimmed(), event_color);
@@ -133,6 +396,13 @@ fn print_events(events: &[crate::storage::audit::AuditEvent]) -> Result<()> {
         if let Some(field) = &details.field {
             detail_parts.push(format!("field:{}", field));
         }
+        if let Some(command) = &details.command {
+            detail_parts.push(format!("command:{}", command));
+        }
+        if let Some(prompts) = details.prompts.as_ref().filter(|p| !p.is_empty()) {
+            let indices: Vec<String> = prompts.iter().map(|i| format!("#{}", i)).collect();
+            detail_parts.push(format!("prompts:{}", indices.join(",")));
+        }
         if let Some(user) = &details.user {
             detail_parts.push(format!("user:{}", user));
         }
@@ -159,6 +429,13 @@ fn parse_event_type(s: &str) -> Option<AuditEventType> {
         "retention_apply" => Some(AuditEventType::RetentionApply),
         "config_change" => Some(AuditEventType::ConfigChange),
         "redaction" => Some(AuditEventType::Redaction),
+        "policy_hit" => Some(AuditEventType::PolicyHit),
+        "notes_push_failed" => Some(AuditEventType::NotesPushFailed),
+        "prompt_access" => Some(AuditEventType::PromptAccess),
+        "secret_detected" => Some(AuditEventType::SecretDetected),
+        "pending_expired" => Some(AuditEventType::PendingExpired),
+        "capture_paused" => Some(AuditEventType::CapturePaused),
+        "capture_resumed" => Some(AuditEventType::CaptureResumed),
         _ => None,
     }
 }
@@ -166,17 +443,22 @@ fn parse_event_type(s: &str) -> Option<AuditEventType> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::storage::audit::{AuditDetails, AuditEvent};
+    use crate::storage::audit::AuditDetails;
 
     // AuditArgs tests
 
     #[test]
     fn test_audit_args_defaults() {
         let args = AuditArgs {
+            command: None,
             since: None,
             event_type: None,
+            pattern: None,
+            actor: None,
             json: false,
             limit: 50,
+            page: 1,
+            stats: false,
         };
         assert!(args.since.is_none());
         assert!(args.event_type.is_none());
@@ -187,10 +469,15 @@ mod tests {
     #[test]
     fn test_audit_args_with_filters() {
         let args = AuditArgs {
+            command: None,
             since: Some("2024-01-01".to_string()),
             event_type: Some("delete".to_string()),
+            pattern: None,
+            actor: None,
             json: true,
             limit: 100,
+            page: 1,
+            stats: false,
         };
         assert_eq!(args.since, Some("2024-01-01".to_string()));
         assert_eq!(args.event_type, Some("delete".to_string()));
@@ -240,6 +527,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_event_type_policy_hit() {
+        assert!(matches!(
+            parse_event_type("policy_hit"),
+            Some(AuditEventType::PolicyHit)
+        ));
+    }
+
     #[test]
     fn test_parse_event_type_invalid() {
         assert!(parse_event_type("invalid").is_none());
@@ -292,6 +587,59 @@ mod tests {
         assert_eq!(events.len(), 50);
     }
 
+    #[test]
+    fn test_export_line_formats() {
+        let event = create_test_event(AuditEventType::Export);
+
+        let json = export_line(&event, AuditExportFormat::Json, 16, "host").unwrap();
+        let parsed: AuditEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.event, AuditEventType::Export);
+
+        let cef = export_line(&event, AuditExportFormat::Cef, 16, "host").unwrap();
+        assert!(cef.starts_with("CEF:0|"));
+
+        let syslog = export_line(&event, AuditExportFormat::Syslog, 16, "host").unwrap();
+        assert!(syslog.starts_with("<133>1 2024-01-15T12:00:00Z host whogitit "));
+    }
+
+    #[test]
+    fn test_paginate_newest_first() {
+        let events: Vec<AuditEvent> = (1..=5)
+            .rev()
+            .map(|day| create_test_event_with_time(&format!("2024-01-0{}T10:00:00Z", day)))
+            .collect();
+
+        let page = paginate(events.clone(), 2, 2);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].timestamp, "2024-01-03T10:00:00Z");
+        assert_eq!(page[1].timestamp, "2024-01-02T10:00:00Z");
+
+        assert_eq!(paginate(events.clone(), 3, 2).len(), 1);
+        assert!(paginate(events, 4, 2).is_empty());
+        assert_eq!(page_count(5, 2), 3);
+        assert_eq!(page_count(0, 50), 0);
+    }
+
+    #[test]
+    fn test_stats_json_counts_per_type_per_day() {
+        let mut events = vec![
+            create_test_event_with_time("2024-01-01T10:00:00Z"),
+            create_test_event_with_time("2024-01-01T11:00:00Z"),
+            create_test_event_with_time("2024-01-02T09:00:00Z"),
+        ];
+        events[1].event = AuditEventType::Redaction;
+
+        let stats = stats_json(&events);
+        assert_eq!(stats["schema"], "whogitit.audit-stats.v1");
+        assert_eq!(stats["total_events"], 3);
+        assert_eq!(stats["by_type"]["delete"], 2);
+        assert_eq!(stats["by_type"]["redaction"], 1);
+        assert_eq!(stats["days"][0]["date"], "2024-01-02");
+        assert_eq!(stats["days"][1]["date"], "2024-01-01");
+        assert_eq!(stats["days"][1]["total"], 2);
+        assert_eq!(stats["days"][1]["by_type"]["redaction"], 1);
+    }
+
     // Helper functions
 
     fn create_test_event(event_type: AuditEventType) -> AuditEvent {
@@ -0,0 +1,778 @@
+//! Auth command - integration tokens kept out of the environment and config
+//!
+//! `whogitit auth login <service>` stores the token used to post to GitHub or
+//! GitLab, or the `notify` signing secret, in the OS keychain: the login keychain
+//! through `security` on macOS, the Secret Service (GNOME Keyring, KWallet)
+//! through `secret-tool` elsewhere. Where no keychain is available, tokens go to
+//! `credentials.enc` in the whogitit data directory, encrypted with AES-256-GCM
+//! under a key derived from `WHOGITIT_CREDENTIALS_PASSPHRASE`.
+//!
+//! Lookups check the service's environment variables first, so CI secrets
+//! injected as variables keep working, then the keychain, then the file.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
+use openssl::hash::MessageDigest;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::environment::home_override;
+use crate::cli::setup::DoctorCheck;
+use crate::utils::hex;
+
+/// Environment variable holding the passphrase of the encrypted credentials file
+pub const ENV_CREDENTIALS_PASSPHRASE: &str = "WHOGITIT_CREDENTIALS_PASSPHRASE";
+
+/// Keychain service name the tokens are stored under
+const KEYCHAIN_SERVICE: &str = "whogitit";
+
+/// PBKDF2-HMAC-SHA256 iterations for new credentials files
+const KDF_ITERATIONS: u32 = 600_000;
+
+/// Additional authenticated data binding the ciphertext to its purpose
+const FILE_AAD: &[u8] = b"whogitit-credentials-v1";
+
+/// Auth command arguments
+#[derive(Debug, Args)]
+pub struct AuthArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub action: AuthAction,
+}
+
+/// Auth subcommands
+#[derive(Debug, Subcommand)]
+pub enum AuthAction {
+    /// Store a token (read from stdin, or prompted for without echo)
+    Login {
+        /// Service the token is for
+        #[arg(value_enum)]
+        service: Service,
+
+        /// Where to store the token
+        #[arg(long, value_enum, default_value_t = StoreKind::Auto)]
+        store: StoreKind,
+    },
+    /// Remove a stored token from the keychain and the encrypted file
+    Logout {
+        /// Service the token is for
+        #[arg(value_enum)]
+        service: Service,
+    },
+    /// Show where each service's token is found
+    Status,
+}
+
+/// Service a token authenticates to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Service {
+    /// GitHub API token for posting PR comments and check runs
+    Github,
+    /// GitLab API token for posting merge request notes
+    Gitlab,
+    /// HMAC secret that signs `notify` webhook requests
+    Notify,
+}
+
+impl Service {
+    pub const ALL: [Service; 3] = [Service::Github, Service::Gitlab, Service::Notify];
+
+    /// Keychain account and credentials file key
+    fn account(self) -> &'static str {
+        match self {
+            Service::Github => "github",
+            Service::Gitlab => "gitlab",
+            Service::Notify => "notify",
+        }
+    }
+
+    /// Human-readable name
+    pub fn label(self) -> &'static str {
+        match self {
+            Service::Github => "GitHub token",
+            Service::Gitlab => "GitLab token",
+            Service::Notify => "Notify secret",
+        }
+    }
+
+    /// Environment variables checked before stored tokens
+    ///
+    /// The notify secret's variable is configurable (`notify.secret_env`), so
+    /// `notify` checks it itself.
+    pub fn env_vars(self) -> &'static [&'static str] {
+        match self {
+            Service::Github => &["GITHUB_TOKEN", "GH_TOKEN"],
+            Service::Gitlab => &["GITLAB_TOKEN"],
+            Service::Notify => &[],
+        }
+    }
+}
+
+/// Storage backend for `auth login`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum StoreKind {
+    /// OS keychain, falling back to the encrypted file
+    #[default]
+    Auto,
+    /// OS keychain only
+    Keychain,
+    /// Encrypted file only (needs WHOGITIT_CREDENTIALS_PASSPHRASE)
+    File,
+}
+
+/// Where a token was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialSource {
+    Env(&'static str),
+    Keychain,
+    File,
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialSource::Env(var) => write!(f, "${}", var),
+            CredentialSource::Keychain => write!(f, "OS keychain"),
+            CredentialSource::File => write!(f, "encrypted credentials file"),
+        }
+    }
+}
+
+/// A token and where it came from
+#[derive(Debug, Clone)]
+pub struct Credential {
+    pub secret: String,
+    pub source: CredentialSource,
+}
+
+/// Token for a service: its environment variables, then stored tokens
+pub fn resolve(service: Service) -> Option<Credential> {
+    service
+        .env_vars()
+        .iter()
+        .find_map(|var| {
+            let secret = std::env::var(var).ok().filter(|v| !v.is_empty())?;
+            Some(Credential {
+                secret,
+                source: CredentialSource::Env(var),
+            })
+        })
+        .or_else(|| stored(service))
+}
+
+/// Token stored by `auth login`, from the keychain or the encrypted file
+///
+/// Never fails: an unreadable file is reported as a warning and treated as empty.
+pub fn stored(service: Service) -> Option<Credential> {
+    if let Some(secret) = keychain::get(service.account()) {
+        return Some(Credential {
+            secret,
+            source: CredentialSource::Keychain,
+        });
+    }
+    let (path, passphrase) = (credentials_path()?, passphrase()?);
+    match CredentialsFile::load(&path, &passphrase) {
+        Ok(tokens) => tokens.get(service.account()).map(|secret| Credential {
+            secret: secret.clone(),
+            source: CredentialSource::File,
+        }),
+        Err(e) => {
+            eprintln!("whogitit: Warning - {:#}", e);
+            None
+        }
+    }
+}
+
+/// Location of the encrypted credentials file
+pub fn credentials_path() -> Option<PathBuf> {
+    home_override()
+        .map(|home| home.join(".local").join("share"))
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("whogitit").join("credentials.enc"))
+}
+
+fn passphrase() -> Option<String> {
+    std::env::var(ENV_CREDENTIALS_PASSPHRASE)
+        .ok()
+        .filter(|v| !v.is_empty())
+}
+
+/// OS keychain access through the platform's command-line tool
+mod keychain {
+    use super::*;
+
+    /// Run a keychain tool, returning stdout on success
+    fn run(program: &str, args: &[&str], input: Option<&str>) -> Option<String> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(input.as_bytes()).ok()?;
+        }
+        let output = child.wait_with_output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn get(account: &str) -> Option<String> {
+        let args = [
+            "find-generic-password",
+            "-s",
+            KEYCHAIN_SERVICE,
+            "-a",
+            account,
+            "-w",
+        ];
+        run("security", &args, None)
+            .map(|out| out.trim_end_matches('\n').to_string())
+            .filter(|secret| !secret.is_empty())
+    }
+
+    /// `-w` comes last without a value, so `security` prompts for the password
+    /// (and its confirmation) and reads both from stdin; the secret never shows
+    /// up in the process list
+    #[cfg(target_os = "macos")]
+    pub fn set(account: &str, secret: &str) -> bool {
+        let label = format!("whogitit {}", account);
+        let args = [
+            "add-generic-password",
+            "-U",
+            "-s",
+            KEYCHAIN_SERVICE,
+            "-a",
+            account,
+            "-l",
+            &label,
+            "-w",
+        ];
+        let input = format!("{secret}\n{secret}\n");
+        run("security", &args, Some(&input)).is_some()
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn delete(account: &str) -> bool {
+        let args = [
+            "delete-generic-password",
+            "-s",
+            KEYCHAIN_SERVICE,
+            "-a",
+            account,
+        ];
+        run("security", &args, None).is_some()
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn get(account: &str) -> Option<String> {
+        let args = ["lookup", "service", KEYCHAIN_SERVICE, "account", account];
+        run("secret-tool", &args, None)
+            .map(|out| out.trim_end_matches('\n').to_string())
+            .filter(|secret| !secret.is_empty())
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn set(account: &str, secret: &str) -> bool {
+        let label = format!("--label=whogitit {}", account);
+        let args = [
+            "store",
+            &label,
+            "service",
+            KEYCHAIN_SERVICE,
+            "account",
+            account,
+        ];
+        run("secret-tool", &args, Some(secret)).is_some()
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn delete(account: &str) -> bool {
+        let args = ["clear", "service", KEYCHAIN_SERVICE, "account", account];
+        run("secret-tool", &args, None).is_some()
+    }
+
+    // Windows Credential Manager has no command-line reader; tokens use the file
+    #[cfg(not(unix))]
+    pub fn get(_account: &str) -> Option<String> {
+        None
+    }
+
+    #[cfg(not(unix))]
+    pub fn set(_account: &str, _secret: &str) -> bool {
+        false
+    }
+
+    #[cfg(not(unix))]
+    pub fn delete(_account: &str) -> bool {
+        false
+    }
+}
+
+/// Tokens encrypted with AES-256-GCM under a PBKDF2-HMAC-SHA256 key
+#[derive(Debug, Serialize, Deserialize)]
+struct CredentialsFile {
+    version: u8,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+    tag: String,
+}
+
+impl CredentialsFile {
+    fn key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<[u8; 32]> {
+        let mut key = [0u8; 32];
+        openssl::pkcs5::pbkdf2_hmac(
+            passphrase.as_bytes(),
+            salt,
+            iterations as usize,
+            MessageDigest::sha256(),
+            &mut key,
+        )?;
+        Ok(key)
+    }
+
+    /// Decrypted tokens; empty when the file doesn't exist
+    fn load(path: &Path, passphrase: &str) -> Result<BTreeMap<String, String>> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let file: Self = serde_json::from_slice(&content)
+            .with_context(|| format!("{} is not a whogitit credentials file", path.display()))?;
+        let field = |value: &str| {
+            hex::decode(value)
+                .with_context(|| format!("{} is not a whogitit credentials file", path.display()))
+        };
+        let key = Self::key(passphrase, &field(&file.salt)?, file.iterations)?;
+        let plaintext = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(&field(&file.nonce)?),
+            FILE_AAD,
+            &field(&file.ciphertext)?,
+            &field(&file.tag)?,
+        )
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Failed to decrypt {} (wrong {}?)",
+                path.display(),
+                ENV_CREDENTIALS_PASSPHRASE
+            )
+        })?;
+        serde_json::from_slice(&plaintext).context("Corrupted credentials file")
+    }
+
+    /// Encrypt the tokens with a fresh salt and nonce, readable by the owner only
+    fn save(path: &Path, passphrase: &str, tokens: &BTreeMap<String, String>) -> Result<()> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        openssl::rand::rand_bytes(&mut salt)?;
+        openssl::rand::rand_bytes(&mut nonce)?;
+        let key = Self::key(passphrase, &salt, KDF_ITERATIONS)?;
+        let mut tag = [0u8; 16];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(&nonce),
+            FILE_AAD,
+            &serde_json::to_vec(tokens)?,
+            &mut tag,
+        )?;
+        let file = Self {
+            version: 1,
+            iterations: KDF_ITERATIONS,
+            salt: hex::encode(&salt),
+            nonce: hex::encode(&nonce),
+            ciphertext: hex::encode(&ciphertext),
+            tag: hex::encode(&tag),
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create credentials directory")?;
+        }
+        let tmp = path.with_extension("enc.tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&tmp)
+            .and_then(|mut out| out.write_all(&serde_json::to_vec_pretty(&file)?))
+            .context("Failed to write credentials file")?;
+        fs::rename(&tmp, path).context("Failed to store credentials file")?;
+        Ok(())
+    }
+}
+
+/// Run the auth command
+pub fn run(args: AuthArgs) -> Result<()> {
+    match args.action {
+        AuthAction::Login { service, store } => login(service, store),
+        AuthAction::Logout { service } => logout(service),
+        AuthAction::Status => status(),
+    }
+}
+
+fn login(service: Service, store: StoreKind) -> Result<()> {
+    let secret = read_secret(&format!("{}: ", service.label()))?;
+    if secret.is_empty() {
+        anyhow::bail!("No token given");
+    }
+
+    if store != StoreKind::File && keychain::set(service.account(), &secret) {
+        println!(
+            "{} {} in the OS keychain",
+            "Stored".green().bold(),
+            service.label()
+        );
+    } else if store == StoreKind::Keychain {
+        anyhow::bail!(
+            "No OS keychain available (needs `security` on macOS or `secret-tool` with a running Secret Service); use --store file"
+        );
+    } else {
+        let Some(passphrase) = passphrase() else {
+            anyhow::bail!(
+                "No OS keychain available; set {} to store tokens in an encrypted file",
+                ENV_CREDENTIALS_PASSPHRASE
+            );
+        };
+        let path = credentials_path().context("No data directory for the credentials file")?;
+        let mut tokens = CredentialsFile::load(&path, &passphrase)?;
+        tokens.insert(service.account().to_string(), secret);
+        CredentialsFile::save(&path, &passphrase, &tokens)?;
+        println!(
+            "{} {} in {}",
+            "Stored".green().bold(),
+            service.label(),
+            path.display()
+        );
+    }
+    if service != Service::Notify {
+        println!("Run 'whogitit doctor' to check its scopes.");
+    }
+    Ok(())
+}
+
+fn logout(service: Service) -> Result<()> {
+    let mut removed = keychain::delete(service.account());
+    if let (Some(path), Some(passphrase)) = (credentials_path(), passphrase()) {
+        let mut tokens = CredentialsFile::load(&path, &passphrase)?;
+        if tokens.remove(service.account()).is_some() {
+            CredentialsFile::save(&path, &passphrase, &tokens)?;
+            removed = true;
+        }
+    }
+    if removed {
+        println!("Removed stored {}", service.label());
+    } else {
+        println!("No stored {}", service.label());
+    }
+    Ok(())
+}
+
+fn status() -> Result<()> {
+    for service in Service::ALL {
+        match resolve(service) {
+            Some(credential) => println!(
+                "{} {}: from {}",
+                "[OK]".green(),
+                service.label(),
+                credential.source
+            ),
+            None => println!("{} {}: not set", "[--]".dimmed(), service.label()),
+        }
+    }
+    if let Some(path) = credentials_path().filter(|p| p.exists()) {
+        if passphrase().is_none() {
+            println!(
+                "\n{} exists but {} is not set, so its tokens are not read.",
+                path.display(),
+                ENV_CREDENTIALS_PASSPHRASE
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Read a token from stdin, prompting without echo on a terminal
+fn read_secret(prompt: &str) -> Result<String> {
+    let mut input = String::new();
+    if atty::is(atty::Stream::Stdin) {
+        eprint!("{}", prompt);
+        let _echo_off = EchoGuard::disable();
+        std::io::stdin()
+            .read_line(&mut input)
+            .context("Failed to read token")?;
+        eprintln!();
+    } else {
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("Failed to read token from stdin")?;
+    }
+    Ok(input.trim().to_string())
+}
+
+/// Terminal echo disabled until dropped
+struct EchoGuard {
+    #[cfg(unix)]
+    saved: Option<libc::termios>,
+}
+
+impl EchoGuard {
+    #[cfg(unix)]
+    fn disable() -> Self {
+        let mut term = std::mem::MaybeUninit::<libc::termios>::uninit();
+        // SAFETY: tcgetattr initializes `term` when it returns 0
+        let saved = unsafe {
+            (libc::tcgetattr(libc::STDIN_FILENO, term.as_mut_ptr()) == 0)
+                .then(|| term.assume_init())
+        };
+        if let Some(saved) = saved {
+            let mut quiet = saved;
+            quiet.c_lflag &= !libc::ECHO;
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &quiet) };
+        }
+        Self { saved }
+    }
+
+    #[cfg(not(unix))]
+    fn disable() -> Self {
+        Self {}
+    }
+}
+
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(saved) = &self.saved {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved) };
+        }
+    }
+}
+
+/// Doctor checks for the tokens that are available: where each comes from and,
+/// for GitHub and GitLab, whether the API accepts it with the scopes to post
+pub(crate) fn doctor_checks() -> Vec<DoctorCheck> {
+    Service::ALL
+        .into_iter()
+        .filter_map(|service| Some((service, resolve(service)?)))
+        .map(|(service, credential)| {
+            let found = format!("from {}", credential.source);
+            let verdict = match service {
+                Service::Notify => Ok(String::new()),
+                _ => match query_api(service, &credential.secret) {
+                    Ok(response) => check_scopes(service, &response).map(|s| format!("; {}", s)),
+                    Err(e) => Ok(format!("; scopes not verified ({:#})", e)),
+                },
+            };
+            match verdict {
+                Ok(detail) => DoctorCheck {
+                    name: service.label(),
+                    passed: true,
+                    message: format!("{}{}", found, detail),
+                    fix_hint: None,
+                },
+                Err(problem) => DoctorCheck {
+                    name: service.label(),
+                    passed: false,
+                    message: format!("{}; {}", found, problem),
+                    fix_hint: Some(format!(
+                        "Create a token with the required scope, then run 'whogitit auth login {}'",
+                        service.account()
+                    )),
+                },
+            }
+        })
+        .collect()
+}
+
+/// API response (headers and body) for the token's own identity
+fn query_api(service: Service, secret: &str) -> Result<String> {
+    let api = |var: &str, default: &str| {
+        std::env::var(var)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| default.to_string())
+    };
+    let (url, header) = match service {
+        Service::Github => (
+            format!("{}/user", api("GITHUB_API_URL", "https://api.github.com")),
+            format!("Authorization: Bearer {}", secret),
+        ),
+        Service::Gitlab => (
+            format!(
+                "{}/personal_access_tokens/self",
+                api("CI_API_V4_URL", "https://gitlab.com/api/v4")
+            ),
+            format!("PRIVATE-TOKEN: {}", secret),
+        ),
+        Service::Notify => anyhow::bail!("The notify secret has no API"),
+    };
+
+    // The header is passed on stdin so the token never appears in argv
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--include", "--max-time", "10"])
+        .args(["--header", "@-", "--header", "User-Agent: whogitit"])
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("curl is not available")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(header.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether an API response shows a token that can post comments
+fn check_scopes(service: Service, response: &str) -> std::result::Result<String, String> {
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .or_else(|| response.split_once("\n\n"))
+        .unwrap_or((response, ""));
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .unwrap_or(0);
+    match status {
+        200..=299 => {}
+        401 => return Err("rejected by the API (expired or revoked)".to_string()),
+        _ => return Err(format!("API returned HTTP {}", status)),
+    }
+
+    match service {
+        Service::Github => {
+            let scopes = head.lines().find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim()
+                    .eq_ignore_ascii_case("x-oauth-scopes")
+                    .then(|| value.trim().to_string())
+            });
+            // Fine-grained and app tokens carry permissions instead of scopes
+            let Some(scopes) = scopes else {
+                return Ok("valid (fine-grained token, permissions not listed)".to_string());
+            };
+            if scopes
+                .split(',')
+                .any(|scope| matches!(scope.trim(), "repo" | "public_repo"))
+            {
+                Ok(format!("valid, scopes: {}", scopes))
+            } else {
+                Err(format!(
+                    "missing the repo or public_repo scope (has: {})",
+                    if scopes.is_empty() { "none" } else { &scopes }
+                ))
+            }
+        }
+        Service::Gitlab => {
+            let scopes: Vec<String> = serde_json::from_str::<serde_json::Value>(body)
+                .ok()
+                .and_then(|json| serde_json::from_value(json["scopes"].clone()).ok())
+                .unwrap_or_default();
+            if scopes.iter().any(|scope| scope == "api") {
+                Ok(format!("valid, scopes: {}", scopes.join(", ")))
+            } else {
+                Err(format!(
+                    "missing the api scope (has: {})",
+                    if scopes.is_empty() {
+                        "none".to_string()
+                    } else {
+                        scopes.join(", ")
+                    }
+                ))
+            }
+        }
+        Service::Notify => Ok(String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_credentials_file_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("whogitit/credentials.enc");
+        assert!(CredentialsFile::load(&path, "pass").unwrap().is_empty());
+
+        let mut tokens = BTreeMap::new();
+        tokens.insert("github".to_string(), "ghp_example".to_string());
+        CredentialsFile::save(&path, "pass", &tokens).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("ghp_example"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(CredentialsFile::load(&path, "pass").unwrap(), tokens);
+        let err = CredentialsFile::load(&path, "wrong").unwrap_err();
+        assert!(err.to_string().contains(ENV_CREDENTIALS_PASSPHRASE));
+    }
+
+    #[test]
+    fn test_check_scopes() {
+        let github = |scopes: &str| {
+            format!(
+                "HTTP/2 200\r\nx-oauth-scopes: {}\r\n\r\n{{\"login\":\"dev\"}}",
+                scopes
+            )
+        };
+        assert!(check_scopes(Service::Github, &github("repo, read:org")).is_ok());
+        assert!(check_scopes(Service::Github, &github("read:user"))
+            .unwrap_err()
+            .contains("public_repo"));
+        assert!(check_scopes(Service::Github, "HTTP/2 200\r\n\r\n{}")
+            .unwrap()
+            .contains("fine-grained"));
+        assert!(check_scopes(Service::Github, "HTTP/2 401\r\n\r\n{}")
+            .unwrap_err()
+            .contains("rejected"));
+
+        let gitlab = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\r\n";
+        assert!(check_scopes(
+            Service::Gitlab,
+            &format!("{}{{\"scopes\":[\"api\"],\"active\":true}}", gitlab)
+        )
+        .is_ok());
+        assert!(check_scopes(
+            Service::Gitlab,
+            &format!("{}{{\"scopes\":[\"read_api\"]}}", gitlab)
+        )
+        .unwrap_err()
+        .contains("read_api"));
+    }
+}
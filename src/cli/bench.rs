@@ -0,0 +1,524 @@
+//! Bench command - throughput of the matching algorithms on synthetic workloads
+//!
+//! Runs the three-way analyzer, the redactor and blame over generated content of
+//! a chosen size and reports the median time and throughput of each. The JSON
+//! output doubles as a baseline file: `--baseline` compares a run against it and
+//! fails when a workload got slower than `--max-regression` allows, so the same
+//! command guards performance locally and in CI.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::{Repository, Signature};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::hook::{CaptureHook, HookInput};
+use crate::capture::snapshot::{AIEdit, FileEditHistory};
+use crate::capture::threeway::ThreeWayAnalyzer;
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::blame::AIBlamer;
+use crate::privacy::WhogititConfig;
+
+const BENCH_MACHINE_SCHEMA: &str = "whogitit.bench.v1";
+const BENCH_FILE: &str = "src/bench.rs";
+
+/// Bench command arguments
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Lines in the synthetic file
+    #[arg(long, default_value_t = 500)]
+    pub lines: usize,
+
+    /// AI edits the file is built up from
+    #[arg(long, default_value_t = 2)]
+    pub edits: usize,
+
+    /// Timed runs per workload (the median is reported)
+    #[arg(long, default_value_t = 3)]
+    pub iterations: usize,
+
+    /// Compare against the JSON output of an earlier run
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    /// Throughput drop (percent) against the baseline that counts as a regression
+    #[arg(long, value_name = "PERCENT", default_value_t = 20.0)]
+    pub max_regression: f64,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+/// Timing of one workload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkloadResult {
+    pub name: String,
+    pub lines: usize,
+    pub edits: usize,
+    pub median_ms: f64,
+    /// Work per second, in `unit`
+    pub throughput: f64,
+    pub unit: String,
+}
+
+/// A workload compared against the baseline run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BaselineComparison {
+    pub name: String,
+    pub baseline_throughput: f64,
+    /// Throughput change in percent (negative is slower)
+    pub change_percent: f64,
+    pub regression: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaselineFile {
+    workloads: Vec<WorkloadResult>,
+}
+
+/// Run the bench command
+pub fn run(args: BenchArgs) -> Result<()> {
+    if args.lines < 2 || args.edits == 0 || args.iterations == 0 {
+        bail!("--lines must be at least 2, --edits and --iterations at least 1");
+    }
+    let edits = args.edits.min(args.lines / 2);
+
+    let baseline = args.baseline.as_deref().map(load_baseline).transpose()?;
+    let workloads = run_workloads(args.lines, edits, args.iterations)?;
+    let comparisons = baseline
+        .map(|baseline| compare_to_baseline(&workloads, &baseline, args.max_regression))
+        .unwrap_or_default();
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&workloads, &comparisons, args.iterations),
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": BENCH_MACHINE_SCHEMA,
+                "version": env!("CARGO_PKG_VERSION"),
+                "iterations": args.iterations,
+                "workloads": workloads,
+                "baseline": args.baseline.as_ref().map(|p| p.display().to_string()),
+                "comparisons": comparisons,
+                "redactor_speedup": redactor_speedup(&workloads),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    let regressions: Vec<&str> = comparisons
+        .iter()
+        .filter(|c| c.regression)
+        .map(|c| c.name.as_str())
+        .collect();
+    if !regressions.is_empty() {
+        bail!(
+            "Performance regression over {:.0}% in: {}",
+            args.max_regression,
+            regressions.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn load_baseline(path: &Path) -> Result<Vec<WorkloadResult>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+    let baseline: BaselineFile = serde_json::from_str(&content).with_context(|| {
+        format!(
+            "{} is not the JSON output of 'whogitit bench'",
+            path.display()
+        )
+    })?;
+    Ok(baseline.workloads)
+}
+
+/// Run every workload `iterations` times
+pub fn run_workloads(lines: usize, edits: usize, iterations: usize) -> Result<Vec<WorkloadResult>> {
+    let workload = Workload::generate(lines, edits);
+    let mut results = Vec::new();
+
+    // The position-aware analysis post-commit runs
+    let history = workload.history();
+    let median = time_median(iterations, || {
+        let result = ThreeWayAnalyzer::analyze_with_diff(&history, &workload.final_content);
+        std::hint::black_box(result);
+        Ok(())
+    })?;
+    results.push(result(
+        "analyzer",
+        &workload,
+        median,
+        lines as f64,
+        "lines/s",
+    ));
+
+    let redactor = WhogititConfig::default().privacy.build_redactor();
+    let prompts = workload.prompts();
+    let median = time_median(iterations, || {
+        std::hint::black_box(redactor.redact(&prompts));
+        Ok(())
+    })?;
+    let megabytes = prompts.len() as f64 / 1_000_000.0;
+    results.push(result("redactor", &workload, median, megabytes, "MB/s"));
+
+    // Same prompts, one pattern at a time: the reference for the pattern-set pass
+    let median = time_median(iterations, || {
+        std::hint::black_box(redactor.redact_sequential(&prompts));
+        Ok(())
+    })?;
+    results.push(result(
+        "redactor-sequential",
+        &workload,
+        median,
+        megabytes,
+        "MB/s",
+    ));
+
+    let dir = std::env::temp_dir().join(format!("whogitit-bench-{}", uuid::Uuid::new_v4()));
+    let blame = (|| {
+        let repo = workload.commit_with_note(&dir)?;
+        time_median(iterations, || {
+            let mut blamer = AIBlamer::new(&repo)?;
+            std::hint::black_box(blamer.blame(BENCH_FILE, None)?);
+            Ok(())
+        })
+    })();
+    let _ = std::fs::remove_dir_all(&dir);
+    results.push(result("blame", &workload, blame?, lines as f64, "lines/s"));
+
+    Ok(results)
+}
+
+/// Throughput of the pattern-set redactor over sequential pattern scanning
+pub fn redactor_speedup(workloads: &[WorkloadResult]) -> Option<f64> {
+    let throughput = |name: &str| {
+        workloads
+            .iter()
+            .find(|w| w.name == name)
+            .map(|w| w.throughput)
+    };
+    let sequential = throughput("redactor-sequential")?;
+    (sequential > 0.0).then(|| throughput("redactor").map(|set| set / sequential))?
+}
+
+fn result(
+    name: &str,
+    workload: &Workload,
+    median: Duration,
+    work: f64,
+    unit: &str,
+) -> WorkloadResult {
+    let seconds = median.as_secs_f64().max(1e-9);
+    WorkloadResult {
+        name: name.to_string(),
+        lines: workload.lines,
+        edits: workload.edits,
+        median_ms: seconds * 1_000.0,
+        throughput: work / seconds,
+        unit: unit.to_string(),
+    }
+}
+
+fn time_median(iterations: usize, mut run: impl FnMut() -> Result<()>) -> Result<Duration> {
+    let mut times = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        run()?;
+        times.push(start.elapsed());
+    }
+    times.sort_unstable();
+    Ok(times[times.len() / 2])
+}
+
+/// Compare workloads with the baseline entries of the same name and size
+pub fn compare_to_baseline(
+    workloads: &[WorkloadResult],
+    baseline: &[WorkloadResult],
+    max_regression: f64,
+) -> Vec<BaselineComparison> {
+    workloads
+        .iter()
+        .filter_map(|current| {
+            let base = baseline.iter().find(|b| {
+                b.name == current.name && b.lines == current.lines && b.edits == current.edits
+            })?;
+            if base.throughput <= 0.0 {
+                return None;
+            }
+            let change_percent = (current.throughput - base.throughput) / base.throughput * 100.0;
+            Some(BaselineComparison {
+                name: current.name.clone(),
+                baseline_throughput: base.throughput,
+                change_percent,
+                regression: change_percent < -max_regression,
+            })
+        })
+        .collect()
+}
+
+/// Deterministic synthetic file: every other line is original, the rest are added
+/// by `edits` AI edits, and a human touches every tenth line afterwards
+struct Workload {
+    lines: usize,
+    edits: usize,
+    original: String,
+    /// Content after each AI edit
+    snapshots: Vec<String>,
+    final_content: String,
+}
+
+impl Workload {
+    fn generate(lines: usize, edits: usize) -> Self {
+        let line = |i: usize| {
+            if i % 2 == 0 {
+                format!("fn original_{}() -> u32 {{ {} }}", i, i)
+            } else {
+                format!(
+                    "    let generated_{} = compute({}, \"value {}\");",
+                    i,
+                    i,
+                    i * 7
+                )
+            }
+        };
+        let render = |limit: usize| -> String {
+            (0..lines)
+                .filter(|i| i % 2 == 0 || *i < limit)
+                .map(|i| line(i) + "\n")
+                .collect()
+        };
+
+        let original = render(0);
+        let snapshots: Vec<String> = (1..=edits).map(|k| render(lines * k / edits)).collect();
+        let final_content = (0..lines)
+            .map(|i| {
+                if i % 10 == 9 {
+                    format!("{} // reviewed\n", line(i))
+                } else {
+                    line(i) + "\n"
+                }
+            })
+            .collect();
+
+        Self {
+            lines,
+            edits,
+            original,
+            snapshots,
+            final_content,
+        }
+    }
+
+    fn history(&self) -> FileEditHistory {
+        let mut history = FileEditHistory::new(BENCH_FILE, Some(&self.original));
+        let mut before = self.original.as_str();
+        for (index, after) in self.snapshots.iter().enumerate() {
+            history.add_edit(AIEdit::new(
+                "Generate the next block",
+                index as u32,
+                "Edit",
+                before,
+                after,
+            ));
+            before = after;
+        }
+        history
+    }
+
+    /// Prompt text of about the size of the file, with secrets for the redactor to find
+    fn prompts(&self) -> String {
+        (0..self.lines)
+            .map(|i| match i % 50 {
+                0 => format!("Use api_key=sk-bench{:020} for the staging call\n", i),
+                25 => format!("Send the report to dev{}@example.com when done\n", i),
+                _ => format!("Refactor step {} so the parser handles nested blocks\n", i),
+            })
+            .collect()
+    }
+
+    /// Repository in `dir` whose HEAD commit carries the note for the workload
+    fn commit_with_note(&self, dir: &Path) -> Result<Repository> {
+        let repo = Repository::init(dir).context("git init failed")?;
+        let file = dir.join(BENCH_FILE);
+        std::fs::create_dir_all(file.parent().unwrap_or(dir))?;
+        std::fs::write(
+            dir.join(".git/info/exclude"),
+            ".whogitit-pending.json\n.whogitit-pending.lock\n.whogitit-pending.tmp\n",
+        )?;
+        std::fs::write(&file, &self.original)?;
+        commit_all(&repo, "Original")?;
+
+        let hook = CaptureHook::new(dir)?;
+        let mut before = self.original.as_str();
+        for after in &self.snapshots {
+            hook.on_file_change(HookInput {
+                tool: "Edit".to_string(),
+                file_path: BENCH_FILE.to_string(),
+                prompt: "Generate the next block".to_string(),
+                old_content: Some(before.to_string()),
+                old_content_present: true,
+                new_content: after.clone(),
+                context: None,
+            })?;
+            before = after;
+        }
+        std::fs::write(&file, &self.final_content)?;
+        commit_all(&repo, "AI-assisted change")?;
+        hook.on_post_commit()?
+            .context("Post-commit analysis produced no attribution")?;
+        Ok(repo)
+    }
+}
+
+fn commit_all(repo: &Repository, message: &str) -> Result<git2::Oid> {
+    let mut index = repo.index()?;
+    index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let sig = Signature::now("whogitit bench", "bench@whogitit.invalid")?;
+    let parents: Vec<git2::Commit> = repo
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .into_iter()
+        .collect();
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    Ok(repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)?)
+}
+
+fn format_throughput(value: f64, unit: &str) -> String {
+    if value >= 1_000_000.0 {
+        format!("{:.2}M {}", value / 1_000_000.0, unit)
+    } else if value >= 1_000.0 {
+        format!("{:.1}k {}", value / 1_000.0, unit)
+    } else {
+        format!("{:.1} {}", value, unit)
+    }
+}
+
+fn print_pretty(
+    workloads: &[WorkloadResult],
+    comparisons: &[BaselineComparison],
+    iterations: usize,
+) {
+    let (lines, edits) = workloads
+        .first()
+        .map(|w| (w.lines, w.edits))
+        .unwrap_or_default();
+    println!(
+        "{} ({} lines, {} edits, median of {} run{})",
+        "Benchmark".bold(),
+        lines,
+        edits,
+        iterations,
+        if iterations == 1 { "" } else { "s" }
+    );
+    println!();
+
+    for workload in workloads {
+        let comparison = comparisons
+            .iter()
+            .find(|c| c.name == workload.name)
+            .map(|c| {
+                let text = format!(
+                    "  ({:+.1}% vs baseline {})",
+                    c.change_percent,
+                    format_throughput(c.baseline_throughput, &workload.unit)
+                );
+                if c.regression {
+                    text.red().to_string()
+                } else {
+                    text.dimmed().to_string()
+                }
+            })
+            .unwrap_or_default();
+        println!(
+            "  {:<20} {:>10.2} ms  {:>18}{}",
+            workload.name,
+            workload.median_ms,
+            format_throughput(workload.throughput, &workload.unit),
+            comparison
+        );
+    }
+
+    if let Some(speedup) = redactor_speedup(workloads) {
+        println!();
+        println!(
+            "  Redactor pattern set: {:.1}x the throughput of sequential scanning",
+            speedup
+        );
+    }
+
+    if !workloads.is_empty() && comparisons.len() < workloads.len() && !comparisons.is_empty() {
+        println!();
+        println!(
+            "{}",
+            "Workloads without a baseline entry of the same size were not compared.".dimmed()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workload(name: &str, throughput: f64) -> WorkloadResult {
+        WorkloadResult {
+            name: name.to_string(),
+            lines: 100,
+            edits: 2,
+            median_ms: 1.0,
+            throughput,
+            unit: "lines/s".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_to_baseline_flags_regressions() {
+        let baseline = vec![
+            workload("analyzer", 1_000.0),
+            workload("blame", 1_000.0),
+            WorkloadResult {
+                lines: 500,
+                ..workload("redactor", 1_000.0)
+            },
+        ];
+        let current = vec![
+            workload("analyzer", 700.0),
+            workload("blame", 900.0),
+            workload("redactor", 10.0),
+        ];
+
+        let comparisons = compare_to_baseline(&current, &baseline, 20.0);
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons[0].regression);
+        assert!((comparisons[0].change_percent + 30.0).abs() < 1e-9);
+        assert!(!comparisons[1].regression);
+    }
+
+    #[test]
+    fn test_run_workloads_small() {
+        let results = run_workloads(40, 3, 1).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["analyzer", "redactor", "redactor-sequential", "blame"]
+        );
+        assert!(redactor_speedup(&results).is_some_and(|x| x > 0.0));
+        assert!(results.iter().all(|r| r.throughput > 0.0 && r.lines == 40));
+
+        let workload = Workload::generate(40, 3);
+        let result =
+            ThreeWayAnalyzer::analyze_with_diff(&workload.history(), &workload.final_content);
+        assert_eq!(result.summary.original_lines, 20);
+        assert_eq!(
+            result.summary.ai_lines + result.summary.ai_modified_lines,
+            20
+        );
+    }
+}
@@ -0,0 +1,407 @@
+//! Bisect-ai command - find where AI-attributed changes to a path came from
+//!
+//! Instead of running tests like `git bisect`, walks the commits between a known
+//! good and a known bad revision and lists those whose attribution notes record
+//! AI-generated lines in the given path, oldest first, with the prompts behind
+//! them. These are the first places to look when a regression is suspected to
+//! come from AI code.
+
+use std::collections::BTreeSet;
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use clap::Args;
+use colored::Colorize;
+use git2::{Oid, Repository, Sort};
+use serde::Serialize;
+
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::attribution::AIAttribution;
+use crate::storage::notes::NotesStore;
+use crate::utils::{truncate_prompt, GlobSet, PROMPT_PREVIEW_LEN, SHORT_COMMIT_LEN};
+
+const BISECT_AI_MACHINE_SCHEMA: &str = "whogitit.bisect-ai.v1";
+
+/// Bisect-ai command arguments
+#[derive(Debug, Args)]
+pub struct BisectAiArgs {
+    /// Revision where the regression is present
+    #[arg(long, value_name = "REV")]
+    pub bad: String,
+
+    /// Revision known to be free of the regression (must be an ancestor of --bad)
+    #[arg(long, value_name = "REV")]
+    pub good: String,
+
+    /// File path (or glob) the regression is suspected in
+    #[arg(long, value_name = "FILE")]
+    pub path: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+/// A prompt that produced AI lines in a candidate commit
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CandidatePrompt {
+    pub index: u32,
+    pub text: String,
+}
+
+/// A commit that introduced AI-attributed changes to the path
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    pub commit: String,
+    pub subject: String,
+    pub author: String,
+    pub committed_at: String,
+    pub model: String,
+    /// Matching files (more than one when `--path` is a glob)
+    pub files: Vec<String>,
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    /// Whole-file AI changes to binary assets or lockfiles
+    pub binary_ai_files: usize,
+    pub prompts: Vec<CandidatePrompt>,
+}
+
+impl Candidate {
+    fn short_id(&self) -> &str {
+        &self.commit[..self.commit.len().min(SHORT_COMMIT_LEN)]
+    }
+}
+
+fn path_matches(pattern: &str, glob: &GlobSet, path: &str) -> bool {
+    pattern == path || glob.is_match(path)
+}
+
+/// AI changes recorded for matching files in one commit's attribution
+fn candidate_from(
+    commit: &git2::Commit,
+    attribution: &AIAttribution,
+    pattern: &str,
+    glob: &GlobSet,
+) -> Option<Candidate> {
+    let mut files = Vec::new();
+    let mut ai_lines = 0;
+    let mut ai_modified_lines = 0;
+    let mut binary_ai_files = 0;
+    let mut prompt_indexes = BTreeSet::new();
+
+    for file in attribution
+        .files
+        .iter()
+        .filter(|f| path_matches(pattern, glob, &f.path))
+    {
+        let binary_ai = file.binary.as_ref().filter(|b| b.ai_generated);
+        if file.summary.ai_lines + file.summary.ai_modified_lines == 0 && binary_ai.is_none() {
+            continue;
+        }
+        files.push(file.path.clone());
+        ai_lines += file.summary.ai_lines;
+        ai_modified_lines += file.summary.ai_modified_lines;
+        if let Some(binary) = binary_ai {
+            binary_ai_files += 1;
+            prompt_indexes.insert(binary.prompt_index);
+        }
+        prompt_indexes.extend(
+            file.line_blocks()
+                .iter()
+                .filter(|b| b.source.is_ai())
+                .filter_map(|b| b.prompt_index),
+        );
+    }
+
+    if files.is_empty() {
+        return None;
+    }
+
+    let prompts = prompt_indexes
+        .into_iter()
+        .filter_map(|index| attribution.get_prompt(index))
+        .map(|p| CandidatePrompt {
+            index: p.index,
+            text: p.text.clone(),
+        })
+        .collect();
+
+    Some(Candidate {
+        commit: commit.id().to_string(),
+        subject: commit.summary().unwrap_or("").to_string(),
+        author: commit.author().name().unwrap_or("unknown").to_string(),
+        committed_at: DateTime::from_timestamp(commit.time().seconds(), 0)
+            .unwrap_or(DateTime::UNIX_EPOCH)
+            .to_rfc3339(),
+        model: attribution.session.model.id.clone(),
+        files,
+        ai_lines,
+        ai_modified_lines,
+        binary_ai_files,
+        prompts,
+    })
+}
+
+/// Commits in `good..bad` with AI changes to matching paths, oldest first
+fn find_candidates(
+    repo: &Repository,
+    store: &NotesStore,
+    bad: Oid,
+    good: Oid,
+    pattern: &str,
+) -> Result<Vec<Candidate>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(bad)?;
+    revwalk.hide(good)?;
+
+    let glob = GlobSet::new(&[pattern]);
+    let mut candidates = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        let Some(attribution) = store.fetch_attribution(oid)? else {
+            continue;
+        };
+        let commit = repo.find_commit(oid)?;
+        if let Some(candidate) = candidate_from(&commit, &attribution, pattern, &glob) {
+            candidates.push(candidate);
+        }
+    }
+    Ok(candidates)
+}
+
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<Oid> {
+    Ok(repo
+        .revparse_single(rev)
+        .with_context(|| format!("Failed to resolve: {}", rev))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", rev))?
+        .id())
+}
+
+/// Run the bisect-ai command
+pub fn run(args: BisectAiArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let store = NotesStore::new(&repo)?;
+
+    let bad = resolve_commit(&repo, &args.bad)?;
+    let good = resolve_commit(&repo, &args.good)?;
+    if bad == good || !repo.graph_descendant_of(bad, good)? {
+        anyhow::bail!(
+            "--good ({}) must be an ancestor of --bad ({})",
+            args.good,
+            args.bad
+        );
+    }
+
+    let candidates = find_candidates(&repo, &store, bad, good, &args.path)?;
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&candidates, &args),
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": BISECT_AI_MACHINE_SCHEMA,
+                "bad": bad.to_string(),
+                "good": good.to_string(),
+                "path": args.path,
+                "candidates": candidates,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_pretty(candidates: &[Candidate], args: &BisectAiArgs) {
+    if candidates.is_empty() {
+        println!(
+            "No AI-attributed changes to {} between {} and {}.",
+            args.path, args.good, args.bad
+        );
+        println!("The regression is likely in human-authored changes; try 'git bisect'.");
+        return;
+    }
+
+    println!(
+        "{} to {} in {}..{} ({} candidate commit(s), oldest first)",
+        "AI-attributed changes".bold(),
+        args.path.cyan(),
+        args.good,
+        args.bad,
+        candidates.len()
+    );
+    println!();
+    for (i, candidate) in candidates.iter().enumerate() {
+        let date = candidate.committed_at.get(..10).unwrap_or("");
+        println!(
+            "{:>3}. {} {}  {}",
+            i + 1,
+            candidate.short_id().yellow(),
+            date,
+            candidate.subject
+        );
+        let mut counts = format!(
+            "{} AI, {} AI-modified lines",
+            candidate.ai_lines, candidate.ai_modified_lines
+        );
+        if candidate.binary_ai_files > 0 {
+            counts.push_str(&format!(
+                ", {} AI binary file(s)",
+                candidate.binary_ai_files
+            ));
+        }
+        println!(
+            "     {} by {} ({})",
+            counts,
+            candidate.author,
+            candidate.model.dimmed()
+        );
+        if candidate.files.len() > 1 || candidate.files[0] != args.path {
+            println!("     files: {}", candidate.files.join(", "));
+        }
+        for prompt in &candidate.prompts {
+            println!(
+                "     #{} {}",
+                prompt.index,
+                format!("\"{}\"", truncate_prompt(&prompt.text, PROMPT_PREVIEW_LEN)).dimmed()
+            );
+        }
+    }
+    println!();
+    println!(
+        "Inspect a candidate with 'whogitit show <commit>' or 'whogitit prompt {}:<line>'.",
+        args.path
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::{FileAttributionResult, LineAttribution, LineSource};
+    use crate::core::attribution::{ModelInfo, PromptInfo, SessionMetadata};
+    use git2::Signature;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str, message: &str) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        fs::write(workdir.join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    fn attribution(path: &str, ai_lines: u32, prompt: &str) -> AIAttribution {
+        let lines: Vec<LineAttribution> = (1..=ai_lines)
+            .map(|n| LineAttribution {
+                line_number: n,
+                content: format!("line {}", n),
+                content_hash: None,
+                source: LineSource::AI {
+                    edit_id: "e1".to_string(),
+                },
+                edit_id: Some("e1".to_string()),
+                prompt_index: Some(0),
+                confidence: 1.0,
+                timestamp: None,
+            })
+            .collect();
+        AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 1,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![PromptInfo {
+                index: 0,
+                text: prompt.to_string(),
+                timestamp: "2026-01-30T10:00:00Z".to_string(),
+                affected_files: vec![path.to_string()],
+                hash: None,
+                privacy: None,
+                tool_calls: Vec::new(),
+            }],
+            files: vec![FileAttributionResult {
+                path: path.to_string(),
+                summary: FileAttributionResult::compute_summary(&lines),
+                lines,
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_find_candidates_lists_ai_changes_oldest_first() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let store = NotesStore::new(&repo).unwrap();
+
+        let good = commit_file(&repo, "auth.rs", "fn a() {}\n", "initial");
+        let first = commit_file(&repo, "auth.rs", "fn a() {}\nfn b() {}\n", "add b");
+        store
+            .store_attribution(first, &attribution("auth.rs", 1, "Add b"))
+            .unwrap();
+        // Human-only commit and an AI change to another file are skipped
+        commit_file(&repo, "auth.rs", "fn a() {}\nfn b() {}\n// c\n", "comment");
+        let other = commit_file(&repo, "util.rs", "fn u() {}\n", "util");
+        store
+            .store_attribution(other, &attribution("util.rs", 1, "Add util"))
+            .unwrap();
+        let bad = commit_file(
+            &repo,
+            "auth.rs",
+            "fn a() {}\nfn b() {}\nfn d() {}\n",
+            "add d",
+        );
+        store
+            .store_attribution(bad, &attribution("auth.rs", 2, "Add d"))
+            .unwrap();
+
+        let candidates = find_candidates(&repo, &store, bad, good, "auth.rs").unwrap();
+        let found: Vec<(&str, usize, &str)> = candidates
+            .iter()
+            .map(|c| (c.subject.as_str(), c.ai_lines, c.prompts[0].text.as_str()))
+            .collect();
+        assert_eq!(found, vec![("add b", 1, "Add b"), ("add d", 2, "Add d")]);
+
+        let globbed = find_candidates(&repo, &store, bad, good, "*.rs").unwrap();
+        assert_eq!(globbed.len(), 3);
+
+        // The good commit itself is excluded
+        assert!(find_candidates(&repo, &store, first, first, "auth.rs")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_candidate_ignores_human_only_files() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let oid = commit_file(&repo, "a.rs", "x\n", "initial");
+        let commit = repo.find_commit(oid).unwrap();
+
+        let attr = attribution("a.rs", 0, "unused");
+        assert!(candidate_from(&commit, &attr, "a.rs", &GlobSet::new(&["a.rs"])).is_none());
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::Args;
 use colored::Colorize;
 use git2::Repository;
 
+use crate::cli::blame_compare;
 use crate::cli::output::{format_blame, OutputFormat};
+use crate::core::attribution::BlameLineResult;
 use crate::core::blame::AIBlamer;
 
 /// Blame command arguments
@@ -27,6 +30,50 @@ pub struct BlameArgs {
     /// Show only human-written lines
     #[arg(long)]
     pub human_only: bool,
+
+    /// Show when each AI line was generated
+    #[arg(long)]
+    pub show_time: bool,
+
+    /// Show only AI lines generated on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long, value_name = "DATE", conflicts_with = "human_only")]
+    pub ai_since: Option<String>,
+
+    /// Compare the file's AI/human composition between two revisions, side by side
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["REV_A", "REV_B"],
+        conflicts_with_all = ["revision", "ai_only", "human_only", "show_time", "ai_since"]
+    )]
+    pub compare: Option<Vec<String>>,
+}
+
+/// Parse an `--ai-since` value: a date (start of day, UTC) or an RFC 3339 timestamp
+fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .with_context(|| {
+            format!(
+                "Invalid date '{}'. Use YYYY-MM-DD or an RFC 3339 timestamp.",
+                value
+            )
+        })
+}
+
+/// Whether a line is AI-generated at or after `since`
+///
+/// Lines without a parseable generation time are excluded.
+fn generated_since(line: &BlameLineResult, since: DateTime<Utc>) -> bool {
+    line.source.is_ai()
+        && line
+            .generated_at
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .is_some_and(|ts| ts >= since)
 }
 
 /// Check if repository is a shallow clone
@@ -49,6 +96,10 @@ fn print_shallow_warning() {
 
 /// Run the blame command
 pub fn run(args: BlameArgs) -> Result<()> {
+    if let Some([rev_a, rev_b]) = args.compare.as_deref() {
+        return blame_compare::run(&args.file, rev_a, rev_b, args.format);
+    }
+
     // Open repository
     let repo = Repository::discover(".").context(
         "Not in a git repository. \
@@ -74,6 +125,12 @@ pub fn run(args: BlameArgs) -> Result<()> {
         "Failed to initialize blame engine. \
          Run 'whogitit doctor' to diagnose configuration issues.",
     )?;
+    run_with_blamer(&mut blamer, args)
+}
+
+/// Blame a file with an existing blame engine and print the result
+pub(crate) fn run_with_blamer(blamer: &mut AIBlamer, args: BlameArgs) -> Result<()> {
+    let ai_since = args.ai_since.as_deref().map(parse_since).transpose()?;
 
     // Run blame with improved error context
     let revision_display = args.revision.as_deref().unwrap_or("HEAD");
@@ -101,9 +158,12 @@ pub fn run(args: BlameArgs) -> Result<()> {
     } else if args.human_only {
         result.lines.retain(|l| l.source.is_human());
     }
+    if let Some(since) = ai_since {
+        result.lines.retain(|l| generated_since(l, since));
+    }
 
     // Format output
-    let output = format_blame(&result, args.format);
+    let output = format_blame(&result, args.format, args.show_time);
     print!("{}", output);
 
     Ok(())
@@ -113,7 +173,6 @@ pub fn run(args: BlameArgs) -> Result<()> {
 mod tests {
     use super::*;
     use crate::capture::snapshot::LineSource;
-    use crate::core::attribution::BlameLineResult;
 
     // BlameArgs tests
 
@@ -126,6 +185,9 @@ mod tests {
             format: OutputFormat::Pretty,
             ai_only: false,
             human_only: false,
+            show_time: false,
+            ai_since: None,
+            compare: None,
         };
         assert_eq!(args.file, "test.rs");
         assert!(args.revision.is_none());
@@ -142,6 +204,9 @@ mod tests {
             format: OutputFormat::Json,
             ai_only: true,
             human_only: false,
+            show_time: true,
+            ai_since: Some("2024-06-01".to_string()),
+            compare: None,
         };
         assert_eq!(args.revision, Some("abc1234".to_string()));
         assert!(matches!(args.format, OutputFormat::Json));
@@ -243,6 +308,44 @@ mod tests {
         assert!(lines.is_empty());
     }
 
+    #[test]
+    fn test_parse_since_accepts_date_and_rfc3339() {
+        assert_eq!(
+            parse_since("2024-06-01").unwrap().to_rfc3339(),
+            "2024-06-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_since("2024-06-01T12:00:00+02:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-06-01T10:00:00+00:00"
+        );
+        assert!(parse_since("June 1st").is_err());
+    }
+
+    #[test]
+    fn test_ai_since_filter() {
+        let since = parse_since("2024-06-01").unwrap();
+        let ai = |ts: Option<&str>| {
+            let mut line = create_test_blame_line(
+                1,
+                LineSource::AI {
+                    edit_id: "e1".to_string(),
+                },
+            );
+            line.generated_at = ts.map(str::to_string);
+            line
+        };
+
+        assert!(generated_since(&ai(Some("2024-06-01T00:00:00Z")), since));
+        assert!(!generated_since(&ai(Some("2024-05-31T23:59:59Z")), since));
+        assert!(!generated_since(&ai(None), since));
+
+        let mut human = create_test_blame_line(2, LineSource::Human);
+        human.generated_at = Some("2025-01-01T00:00:00Z".to_string());
+        assert!(!generated_since(&human, since));
+    }
+
     // Helper to create test BlameLineResult
     fn create_test_blame_line(line_num: u32, source: LineSource) -> BlameLineResult {
         BlameLineResult {
@@ -254,6 +357,8 @@ mod tests {
             content: format!("line {} content", line_num),
             prompt_index: None,
             prompt_preview: None,
+            generated_at: None,
+            confidence: None,
         }
     }
 
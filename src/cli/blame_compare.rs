@@ -0,0 +1,365 @@
+//! Side-by-side blame of a file at two revisions - `whogitit blame --compare`
+//!
+//! Answers questions like "did the human rewrite of this module actually remove
+//! the AI code?": the file's composition at each revision, the lines each source
+//! lost and gained between them, and a two-column view of the changed regions.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use git2::Repository;
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+use crate::cli::output::{source_marker, OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::attribution::{BlameLineResult, BlameResult};
+use crate::core::blame::AIBlamer;
+use crate::core::calibration::source_kind as kind;
+use crate::utils::truncate_or_pad;
+
+const BLAME_COMPARE_MACHINE_SCHEMA: &str = "whogitit.blame-compare.v1";
+
+/// Source kinds in display order
+const SOURCE_KINDS: &[&str] = &["ai", "ai_modified", "human", "original", "unknown"];
+
+/// Unchanged lines shown around each changed region
+const CONTEXT_LINES: usize = 2;
+
+/// Width of each code column in the side-by-side view
+const COLUMN_WIDTH: usize = 44;
+
+/// Line counts of one source kind at both revisions
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct SourceChange {
+    pub before: usize,
+    pub after: usize,
+    /// Lines of this source at the first revision that are gone at the second
+    pub lost: usize,
+    /// Lines of this source at the second revision that were not at the first
+    pub gained: usize,
+}
+
+/// One row of the side-by-side view
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    /// Line indices at (first, second) revision; None for an added or removed line
+    Lines(Option<usize>, Option<usize>),
+    /// Run of unchanged lines that is not shown
+    Skipped(usize),
+}
+
+/// Compare a file's attribution at two revisions
+pub fn run(file: &str, rev_a: &str, rev_b: &str, format: OutputFormat) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let mut blamer = AIBlamer::new(&repo)?;
+
+    let before = blame_at(&repo, &mut blamer, file, rev_a)?;
+    let after = blame_at(&repo, &mut blamer, file, rev_b)?;
+    if before.is_none() && after.is_none() {
+        anyhow::bail!("{} exists at neither {} nor {}", file, rev_a, rev_b);
+    }
+    let empty = |rev: &str| BlameResult {
+        path: file.to_string(),
+        revision: rev.to_string(),
+        lines: Vec::new(),
+    };
+    let (before_exists, after_exists) = (before.is_some(), after.is_some());
+    let before = before.unwrap_or_else(|| empty(rev_a));
+    let after = after.unwrap_or_else(|| empty(rev_b));
+
+    let (changes, rows) = compare(&before.lines, &after.lines);
+
+    match format {
+        OutputFormat::Pretty => print_pretty(file, &before, &after, &changes, &rows),
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": BLAME_COMPARE_MACHINE_SCHEMA,
+                "file": file,
+                "before": {
+                    "revision": rev_a,
+                    "exists": before_exists,
+                    "total_lines": before.lines.len(),
+                    "ai_percentage": before.ai_percentage(),
+                },
+                "after": {
+                    "revision": rev_b,
+                    "exists": after_exists,
+                    "total_lines": after.lines.len(),
+                    "ai_percentage": after.ai_percentage(),
+                },
+                "sources": changes,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+    Ok(())
+}
+
+/// Blame a file at a revision, or None if it doesn't exist there
+fn blame_at(
+    repo: &Repository,
+    blamer: &mut AIBlamer,
+    file: &str,
+    rev: &str,
+) -> Result<Option<BlameResult>> {
+    let tree = repo
+        .revparse_single(rev)
+        .with_context(|| format!("Failed to resolve: {}", rev))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", rev))?
+        .tree()?;
+    if tree.get_path(std::path::Path::new(file)).is_err() {
+        return Ok(None);
+    }
+    blamer.blame(file, Some(rev)).map(Some)
+}
+
+/// Per-source line changes and the rows of the side-by-side view
+fn compare(
+    before: &[BlameLineResult],
+    after: &[BlameLineResult],
+) -> (BTreeMap<&'static str, SourceChange>, Vec<Row>) {
+    let mut changes: BTreeMap<&'static str, SourceChange> = SOURCE_KINDS
+        .iter()
+        .map(|kind| (*kind, SourceChange::default()))
+        .collect();
+    for line in before {
+        changes.entry(kind(&line.source)).or_default().before += 1;
+    }
+    for line in after {
+        changes.entry(kind(&line.source)).or_default().after += 1;
+    }
+
+    let text = |lines: &[BlameLineResult]| {
+        lines
+            .iter()
+            .map(|l| format!("{}\n", l.content))
+            .collect::<String>()
+    };
+    let (old_text, new_text) = (text(before), text(after));
+    let diff = TextDiff::from_lines(&old_text, &new_text);
+
+    let mut all_rows = Vec::new();
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Equal => {
+                let (old, new) = (change.old_index(), change.new_index());
+                // Same text, different source (e.g. re-attributed) counts as lost and gained
+                if let (Some(o), Some(n)) = (old, new) {
+                    let (old_kind, new_kind) = (kind(&before[o].source), kind(&after[n].source));
+                    if old_kind != new_kind {
+                        changes.entry(old_kind).or_default().lost += 1;
+                        changes.entry(new_kind).or_default().gained += 1;
+                    }
+                }
+                all_rows.push((old, new, true));
+            }
+            ChangeTag::Delete => {
+                if let Some(o) = change.old_index() {
+                    changes.entry(kind(&before[o].source)).or_default().lost += 1;
+                }
+                all_rows.push((change.old_index(), None, false));
+            }
+            ChangeTag::Insert => {
+                if let Some(n) = change.new_index() {
+                    changes.entry(kind(&after[n].source)).or_default().gained += 1;
+                }
+                all_rows.push((None, change.new_index(), false));
+            }
+        }
+    }
+
+    (changes, collapse_unchanged(&all_rows))
+}
+
+/// Keep changed rows with some context, folding long unchanged runs
+fn collapse_unchanged(rows: &[(Option<usize>, Option<usize>, bool)]) -> Vec<Row> {
+    let mut shown = vec![false; rows.len()];
+    for (i, _) in rows.iter().enumerate().filter(|(_, r)| !r.2) {
+        let end = (i + CONTEXT_LINES + 1).min(rows.len());
+        shown[i.saturating_sub(CONTEXT_LINES)..end].fill(true);
+    }
+
+    let mut collapsed = Vec::new();
+    let mut skipped = 0;
+    for (i, (old, new, _)) in rows.iter().enumerate() {
+        if !shown[i] {
+            skipped += 1;
+            continue;
+        }
+        if skipped > 0 {
+            collapsed.push(Row::Skipped(skipped));
+            skipped = 0;
+        }
+        collapsed.push(Row::Lines(*old, *new));
+    }
+    if skipped > 0 {
+        collapsed.push(Row::Skipped(skipped));
+    }
+    collapsed
+}
+
+fn print_pretty(
+    file: &str,
+    before: &BlameResult,
+    after: &BlameResult,
+    changes: &BTreeMap<&'static str, SourceChange>,
+    rows: &[Row],
+) {
+    println!(
+        "{}: {} → {}",
+        file.bold(),
+        before.revision.cyan(),
+        after.revision.cyan()
+    );
+    println!();
+
+    let cell = |lines: &[BlameLineResult], idx: Option<usize>| match idx {
+        Some(i) => format!(
+            "{:>5} {} {}",
+            lines[i].line_number,
+            source_marker(&lines[i].source),
+            truncate_or_pad(&lines[i].content, COLUMN_WIDTH)
+        ),
+        None => " ".repeat(COLUMN_WIDTH + 8),
+    };
+    for row in rows {
+        match row {
+            Row::Lines(old, new) => {
+                let gutter = match (old, new) {
+                    (Some(_), Some(_)) => "│".dimmed(),
+                    (Some(_), None) => "<".red(),
+                    _ => ">".green(),
+                };
+                println!(
+                    "{} {} {}",
+                    cell(&before.lines, *old),
+                    gutter,
+                    cell(&after.lines, *new)
+                );
+            }
+            Row::Skipped(count) => {
+                println!("{}", format!("      ⋯ {} unchanged lines", count).dimmed());
+            }
+        }
+    }
+    if rows.is_empty() {
+        println!("{}", "(file is empty at both revisions)".dimmed());
+    }
+    println!();
+
+    println!(
+        "  {:<12} {:>8} {:>8} {:>7} {:>7}",
+        "Source", "Before", "After", "Lost", "Gained"
+    );
+    for kind in SOURCE_KINDS {
+        let change = &changes[kind];
+        if change.before == 0 && change.after == 0 {
+            continue;
+        }
+        println!(
+            "  {:<12} {:>8} {:>8} {:>7} {:>7}",
+            label(kind),
+            change.before,
+            change.after,
+            format!("-{}", change.lost).red(),
+            format!("+{}", change.gained).green()
+        );
+    }
+    let share = |result: &BlameResult| result.ai_percentage();
+    println!();
+    println!(
+        "AI involvement: {:.0}% → {:.0}%",
+        share(before),
+        share(after)
+    );
+}
+
+fn label(kind: &str) -> &'static str {
+    match kind {
+        "ai" => "AI",
+        "ai_modified" => "AI-modified",
+        "human" => "Human",
+        "original" => "Original",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::LineSource;
+
+    fn line(line_number: u32, content: &str, source: LineSource) -> BlameLineResult {
+        BlameLineResult {
+            line_number,
+            content: content.to_string(),
+            commit_id: "abc1234567".to_string(),
+            commit_short: "abc1234".to_string(),
+            author: "Test".to_string(),
+            source,
+            prompt_index: None,
+            prompt_preview: None,
+            generated_at: None,
+            confidence: None,
+        }
+    }
+
+    fn ai() -> LineSource {
+        LineSource::AI {
+            edit_id: "e1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_counts_lost_and_gained_per_source() {
+        // The human rewrite replaced two AI lines with one human line
+        let before = vec![
+            line(1, "fn handler() {", LineSource::Human),
+            line(2, "    let a = parse();", ai()),
+            line(3, "    let b = check(a);", ai()),
+            line(4, "}", LineSource::Human),
+        ];
+        let after = vec![
+            line(1, "fn handler() {", LineSource::Human),
+            line(2, "    validate(parse());", LineSource::Human),
+            line(3, "}", LineSource::Human),
+        ];
+
+        let (changes, rows) = compare(&before, &after);
+        assert_eq!(
+            changes["ai"],
+            SourceChange {
+                before: 2,
+                after: 0,
+                lost: 2,
+                gained: 0
+            }
+        );
+        assert_eq!(
+            changes["human"],
+            SourceChange {
+                before: 2,
+                after: 3,
+                lost: 0,
+                gained: 1
+            }
+        );
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[1], Row::Lines(Some(1), None));
+        assert_eq!(rows[3], Row::Lines(None, Some(1)));
+    }
+
+    #[test]
+    fn test_collapse_unchanged_folds_long_runs() {
+        let mut rows: Vec<(Option<usize>, Option<usize>, bool)> =
+            (0..10).map(|i| (Some(i), Some(i), true)).collect();
+        rows.push((Some(10), None, false));
+
+        let collapsed = collapse_unchanged(&rows);
+        assert_eq!(collapsed[0], Row::Skipped(8));
+        assert_eq!(collapsed.len(), 4);
+    }
+}
@@ -0,0 +1,170 @@
+//! Annotate-manual and calibrate commands - fit confidence to labeled lines
+//!
+//! `whogitit annotate-manual` records the true source of a line next to what
+//! attribution predicted; `whogitit calibrate` turns those labels into the
+//! `[calibration]` mapping in `.whogitit.toml`. See [`crate::core::calibration`].
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use git2::Repository;
+
+use crate::core::calibration::{self, LabeledOutcome};
+use crate::privacy::WhogititConfig;
+use crate::storage::notes::NotesStore;
+
+/// True source of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SourceLabel {
+    /// Unchanged from before the AI session
+    Original,
+    /// Written by AI and committed unchanged
+    Ai,
+    /// Written by AI, then edited by a human
+    AiModified,
+    /// Written by a human
+    Human,
+}
+
+impl SourceLabel {
+    /// Source kind as recorded in labeled outcomes
+    fn kind(self) -> &'static str {
+        match self {
+            Self::Original => "original",
+            Self::Ai => "ai",
+            Self::AiModified => "ai_modified",
+            Self::Human => "human",
+        }
+    }
+}
+
+/// Annotate-manual command arguments
+#[derive(Debug, Args)]
+pub struct AnnotateManualArgs {
+    /// Commit whose attribution is being labeled
+    pub commit: String,
+
+    /// File path (relative to the repository root)
+    pub file: String,
+
+    /// Line number (1-indexed)
+    pub line: u32,
+
+    /// Actual source of the line
+    #[arg(long, value_enum)]
+    pub source: SourceLabel,
+}
+
+/// Calibrate command arguments
+#[derive(Debug, Args)]
+pub struct CalibrateArgs {
+    /// Show the fitted mapping without writing .whogitit.toml
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Run the annotate-manual command
+pub fn run_annotate_manual(args: AnnotateManualArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let commit = repo
+        .revparse_single(&args.commit)
+        .with_context(|| format!("Failed to resolve: {}", args.commit))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", args.commit))?;
+
+    let store = NotesStore::new(&repo)?;
+    let Some(attribution) = store.fetch_attribution(commit.id())? else {
+        anyhow::bail!("No AI attribution for commit {}", args.commit);
+    };
+    let Some(file) = attribution.files.iter().find(|f| f.path == args.file) else {
+        anyhow::bail!("{} has no attribution in commit {}", args.file, args.commit);
+    };
+    let Some(line) = file.lines.iter().find(|l| l.line_number == args.line) else {
+        if file.blocks.is_some() && file.lines.is_empty() {
+            anyhow::bail!(
+                "{} was recorded with analysis.granularity = \"hunk\"; \
+                 per-line confidence is not available",
+                args.file
+            );
+        }
+        anyhow::bail!(
+            "{} has no line {} in commit {}",
+            args.file,
+            args.line,
+            args.commit
+        );
+    };
+
+    let outcome = LabeledOutcome {
+        commit: commit.id().to_string(),
+        path: args.file,
+        line: args.line,
+        predicted: calibration::source_kind(&line.source).to_string(),
+        confidence: line.confidence,
+        actual: args.source.kind().to_string(),
+        labeled_at: Utc::now().to_rfc3339(),
+    };
+    calibration::record_outcome(repo.path(), &outcome)?;
+
+    let verdict = if outcome.is_correct() {
+        "confirmed".green()
+    } else {
+        "corrected".yellow()
+    };
+    println!(
+        "{} {}:{} - predicted {} ({:.2}), labeled {}",
+        verdict, outcome.path, outcome.line, outcome.predicted, outcome.confidence, outcome.actual
+    );
+    let total = calibration::load_outcomes(repo.path())?.len();
+    println!(
+        "{} labeled line(s). Run 'whogitit calibrate' to update the confidence mapping.",
+        total
+    );
+    Ok(())
+}
+
+/// Run the calibrate command
+pub fn run_calibrate(args: CalibrateArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+
+    let outcomes = calibration::load_outcomes(repo.path())?;
+    if outcomes.is_empty() {
+        anyhow::bail!(
+            "No labeled lines. Label lines with 'whogitit annotate-manual <commit> <file> <line> --source <source>' first."
+        );
+    }
+    let bins = calibration::fit(&outcomes);
+
+    println!(
+        "{} ({} labeled lines)",
+        "Confidence calibration".bold(),
+        outcomes.len()
+    );
+    println!();
+    println!("  {:<12} {:>8} {:>10}", "Raw", "Samples", "Calibrated");
+    for bin in &bins {
+        println!(
+            "  {:<12} {:>8} {:>10.3}",
+            format!("{:.1}-{:.1}", bin.min, bin.max),
+            bin.samples,
+            bin.confidence
+        );
+    }
+    println!();
+
+    let config_path = WhogititConfig::repo_config_path(repo_root);
+    if args.dry_run {
+        println!(
+            "{}",
+            format!("Dry run - {} not written.", config_path.display()).dimmed()
+        );
+        return Ok(());
+    }
+    calibration::write_config_section(&config_path, &bins)?;
+    println!("Wrote [calibration] to {}", config_path.display());
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! CI doctor - `whogitit doctor --ci`
+//!
+//! CI runners never have the capture hook or Claude settings; what they need is
+//! a full clone with the notes ref fetched, a reachable base commit and a token for
+//! posting comments (from the environment or stored with `whogitit auth login`). Each failure class has its own exit code so a pipeline can tell
+//! why `summary` or `annotations` would come up empty before running them.
+
+use anyhow::Result;
+use git2::Repository;
+
+use crate::cli::auth::{self, Service};
+use crate::cli::setup::DoctorCheck;
+use crate::storage::notes::NotesStore;
+
+/// Not inside a git repository (or the repository has no commits)
+pub const EXIT_NO_REPOSITORY: i32 = 10;
+/// Shallow clone: history and merge bases are incomplete
+pub const EXIT_SHALLOW_CLONE: i32 = 11;
+/// No whogitit notes ref exists locally
+pub const EXIT_NOTES_NOT_FETCHED: i32 = 12;
+/// The base commit cannot be resolved or has no merge base with HEAD
+pub const EXIT_BASE_UNREACHABLE: i32 = 13;
+/// No token is available for posting PR comments
+pub const EXIT_TOKEN_MISSING: i32 = 14;
+
+/// A doctor check with the exit code reported when it fails
+#[derive(Debug)]
+pub struct CiCheck {
+    pub check: DoctorCheck,
+    pub exit_code: i32,
+}
+
+impl CiCheck {
+    fn pass(name: &'static str, message: String) -> Self {
+        Self {
+            check: DoctorCheck {
+                name,
+                passed: true,
+                message,
+                fix_hint: None,
+            },
+            exit_code: 0,
+        }
+    }
+
+    fn fail(name: &'static str, message: String, fix_hint: &str, exit_code: i32) -> Self {
+        Self {
+            check: DoctorCheck {
+                name,
+                passed: false,
+                message,
+                fix_hint: Some(fix_hint.to_string()),
+            },
+            exit_code,
+        }
+    }
+}
+
+/// Run the CI checks and exit with the code of the first failure
+pub fn run(base: Option<String>, check_token: bool) -> Result<()> {
+    println!("Checking whogitit CI environment...\n");
+
+    let base = base.or_else(base_from_env);
+    let token_source = check_token.then(|| {
+        auth::resolve(Service::Github)
+            .or_else(|| auth::resolve(Service::Gitlab))
+            .map(|credential| credential.source.to_string())
+    });
+
+    let checks = match Repository::discover(".") {
+        Ok(repo) => ci_checks(
+            &repo,
+            base.as_deref(),
+            token_source.as_ref().map(Option::as_deref),
+        ),
+        Err(_) => vec![CiCheck::fail(
+            "Repository",
+            "Not in a git repository".to_string(),
+            "Run whogitit after the checkout step, inside the repository",
+            EXIT_NO_REPOSITORY,
+        )],
+    };
+
+    println!("[OK] Required tools: the capture hook is not needed in CI");
+    for ci_check in &checks {
+        let check = &ci_check.check;
+        let status = if check.passed { "[OK]" } else { "[FAIL]" };
+        println!("{} {}: {}", status, check.name, check.message);
+        if let (false, Some(hint)) = (check.passed, &check.fix_hint) {
+            println!("   Fix: {}", hint);
+        }
+    }
+    println!();
+
+    match checks.iter().find(|c| !c.check.passed) {
+        Some(failed) => {
+            println!(
+                "CI environment is not ready for whogitit (exit code {}).",
+                failed.exit_code
+            );
+            std::process::exit(failed.exit_code);
+        }
+        None => println!("All CI checks passed."),
+    }
+    Ok(())
+}
+
+/// Base revision from the CI provider's pull/merge request variables
+fn base_from_env() -> Option<String> {
+    let non_empty = |var: &str| std::env::var(var).ok().filter(|v| !v.is_empty());
+    non_empty("GITHUB_BASE_REF")
+        .map(|branch| format!("origin/{}", branch))
+        .or_else(|| non_empty("CI_MERGE_REQUEST_DIFF_BASE_SHA"))
+}
+
+/// Checks in dependency order; `token_source` is None when the token check is skipped
+pub fn ci_checks(
+    repo: &Repository,
+    base: Option<&str>,
+    token_source: Option<Option<&str>>,
+) -> Vec<CiCheck> {
+    let mut checks = Vec::new();
+
+    let head = match repo.head().and_then(|h| h.peel_to_commit()) {
+        Ok(head) => head,
+        Err(_) => {
+            checks.push(CiCheck::fail(
+                "Repository",
+                "HEAD does not point to a commit".to_string(),
+                "Check out the commit under test before running whogitit",
+                EXIT_NO_REPOSITORY,
+            ));
+            return checks;
+        }
+    };
+    checks.push(CiCheck::pass(
+        "Repository",
+        format!("HEAD at {}", &head.id().to_string()[..7]),
+    ));
+
+    checks.push(if repo.is_shallow() {
+        CiCheck::fail(
+            "Clone depth",
+            "Shallow clone".to_string(),
+            "Use a full clone (actions/checkout: fetch-depth: 0) or run 'git fetch --unshallow'",
+            EXIT_SHALLOW_CLONE,
+        )
+    } else {
+        CiCheck::pass("Clone depth", "Full history".to_string())
+    });
+
+    checks.push(check_notes_fetched(repo));
+
+    if let Some(base) = base {
+        checks.push(check_base_reachable(repo, base, head.id()));
+    }
+
+    if let Some(token_source) = token_source {
+        checks.push(match token_source {
+            Some(source) => CiCheck::pass("Comment token", format!("From {}", source)),
+            None => CiCheck::fail(
+                "Comment token",
+                format!(
+                    "None of {} is set and no token is stored",
+                    [Service::Github, Service::Gitlab]
+                        .iter()
+                        .flat_map(|service| service.env_vars().iter().copied())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                "Pass the token to the step (env: GITHUB_TOKEN: ${{ github.token }}), run 'whogitit auth login github', or use --no-comment",
+                EXIT_TOKEN_MISSING,
+            ),
+        });
+    }
+
+    checks
+}
+
+fn check_notes_fetched(repo: &Repository) -> CiCheck {
+    let store = match NotesStore::new(repo) {
+        Ok(store) => store,
+        Err(e) => {
+            return CiCheck::fail(
+                "Attribution notes",
+                format!("Invalid storage config: {:#}", e),
+                "Fix [storage] in .whogitit.toml",
+                EXIT_NOTES_NOT_FETCHED,
+            )
+        }
+    };
+    let fetched: Vec<&String> = store
+        .read_refs()
+        .iter()
+        .filter(|r| repo.find_reference(r).is_ok())
+        .collect();
+
+    if fetched.is_empty() {
+        let notes_ref = store.write_ref();
+        CiCheck::fail(
+            "Attribution notes",
+            format!("{} not fetched", notes_ref),
+            &format!("git fetch origin {0}:{0}", notes_ref),
+            EXIT_NOTES_NOT_FETCHED,
+        )
+    } else {
+        let names: Vec<&str> = fetched.iter().map(|r| r.as_str()).collect();
+        CiCheck::pass("Attribution notes", format!("{} present", names.join(", ")))
+    }
+}
+
+fn check_base_reachable(repo: &Repository, base: &str, head: git2::Oid) -> CiCheck {
+    let hint = "Fetch the base branch (git fetch origin <base>) with full history";
+    let base_commit = match repo.revparse_single(base).and_then(|o| o.peel_to_commit()) {
+        Ok(commit) => commit,
+        Err(_) => {
+            return CiCheck::fail(
+                "Base commit",
+                format!("Cannot resolve {}", base),
+                hint,
+                EXIT_BASE_UNREACHABLE,
+            )
+        }
+    };
+    match repo.merge_base(base_commit.id(), head) {
+        Ok(_) => CiCheck::pass("Base commit", format!("{} shares history with HEAD", base)),
+        Err(_) => CiCheck::fail(
+            "Base commit",
+            format!("{} has no merge base with HEAD", base),
+            hint,
+            EXIT_BASE_UNREACHABLE,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn repo_with_commit() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        {
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+                .unwrap();
+        }
+        (dir, repo)
+    }
+
+    fn failed_codes(checks: &[CiCheck]) -> Vec<i32> {
+        checks
+            .iter()
+            .filter(|c| !c.check.passed)
+            .map(|c| c.exit_code)
+            .collect()
+    }
+
+    #[test]
+    fn test_ci_checks_report_distinct_failures() {
+        let (_dir, repo) = repo_with_commit();
+
+        let checks = ci_checks(&repo, Some("origin/main"), Some(None));
+        assert_eq!(
+            failed_codes(&checks),
+            vec![
+                EXIT_NOTES_NOT_FETCHED,
+                EXIT_BASE_UNREACHABLE,
+                EXIT_TOKEN_MISSING
+            ]
+        );
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        repo.note(
+            &sig,
+            &sig,
+            Some(crate::storage::notes::NOTES_REF),
+            head.id(),
+            "{}",
+            false,
+        )
+        .unwrap();
+        let checks = ci_checks(&repo, Some("HEAD"), Some(Some("$GITHUB_TOKEN")));
+        assert!(failed_codes(&checks).is_empty());
+        assert_eq!(checks.len(), 5);
+    }
+}
@@ -0,0 +1,369 @@
+//! Compare-branches command - how a branch changes the AI share of the files it touches
+//!
+//! For every file changed on the branch since its merge base with the base branch,
+//! compares the AI share of the file on the base branch with its share at the tip
+//! of the branch. Hunks that replace lines which were not AI-authored at the merge
+//! base with AI lines are counted as rewrites of human code, the changes most worth
+//! an architectural look when an agent reworks existing code.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::{Oid, Repository};
+use serde::Serialize;
+
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::blame::AIBlamer;
+use crate::utils::{repo_path, truncate, truncate_or_pad, SHORT_COMMIT_LEN};
+
+const COMPARE_BRANCHES_MACHINE_SCHEMA: &str = "whogitit.compare-branches.v1";
+
+/// Compare-branches command arguments
+#[derive(Debug, Args)]
+pub struct CompareBranchesArgs {
+    /// Base branch or revision (e.g., main)
+    pub base: String,
+
+    /// Branch or revision to compare against the base
+    pub branch: String,
+
+    /// Only list files where AI rewrote previously human-authored code
+    #[arg(long)]
+    pub rewrites_only: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+/// AI-attributed lines of a file at one revision
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct AiShare {
+    pub ai_lines: usize,
+    pub total_lines: usize,
+    pub ai_percent: f64,
+}
+
+impl AiShare {
+    fn new(ai_lines: usize, total_lines: usize) -> Self {
+        let ai_percent = if total_lines == 0 {
+            0.0
+        } else {
+            ai_lines as f64 / total_lines as f64 * 100.0
+        };
+        Self {
+            ai_lines,
+            total_lines,
+            ai_percent,
+        }
+    }
+}
+
+/// One changed file, compared between the base branch and the branch tip
+#[derive(Debug, Clone, Serialize)]
+pub struct FileComparison {
+    pub path: String,
+    /// Share on the base branch (None if the file doesn't exist there)
+    pub base: Option<AiShare>,
+    /// Share at the branch tip (None if the branch deletes the file)
+    pub branch: Option<AiShare>,
+    /// Change in AI percentage points
+    pub ai_percent_change: f64,
+    /// Lines that were not AI-authored at the merge base, replaced by AI lines on the branch
+    pub human_lines_rewritten: usize,
+}
+
+/// Removed (old side) and added (new side) line numbers of one diff hunk
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Hunk {
+    removed: Vec<u32>,
+    added: Vec<u32>,
+}
+
+/// A file changed between the merge base and the branch tip
+#[derive(Debug, Default)]
+struct ChangedFile {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+/// Run the compare-branches command
+pub fn run(args: CompareBranchesArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let base = resolve_commit(&repo, &args.base)?;
+    let branch = resolve_commit(&repo, &args.branch)?;
+    let merge_base = repo.merge_base(base, branch).with_context(|| {
+        format!(
+            "No common ancestor between {} and {}",
+            args.base, args.branch
+        )
+    })?;
+
+    let changed = changed_files(&repo, merge_base, branch)?;
+    let mut blamer = AIBlamer::new(&repo)?;
+    let (base_rev, branch_rev, merge_base_rev) =
+        (base.to_string(), branch.to_string(), merge_base.to_string());
+
+    let mut files = Vec::new();
+    for file in changed {
+        // A file renamed on the branch still has its old name on the base branch
+        let base_share = [&file.new_path, &file.old_path]
+            .into_iter()
+            .flatten()
+            .find_map(|p| ai_lines(&mut blamer, p, &base_rev));
+        let branch_share = file
+            .new_path
+            .as_deref()
+            .and_then(|p| ai_lines(&mut blamer, p, &branch_rev));
+
+        let human_lines_rewritten = match (&file.old_path, &branch_share) {
+            (Some(old_path), Some((branch_ai, _))) => {
+                let merge_base_ai = ai_lines(&mut blamer, old_path, &merge_base_rev)
+                    .map(|(ai, _)| ai)
+                    .unwrap_or_default();
+                count_human_rewrites(&file.hunks, &merge_base_ai, branch_ai)
+            }
+            _ => 0,
+        };
+
+        let base = base_share.map(|(ai, total)| AiShare::new(ai.len(), total));
+        let branch = branch_share.map(|(ai, total)| AiShare::new(ai.len(), total));
+        let percent = |share: Option<AiShare>| share.map_or(0.0, |s| s.ai_percent);
+        let comparison = FileComparison {
+            path: file.new_path.or(file.old_path).unwrap_or_default(),
+            base,
+            branch,
+            ai_percent_change: percent(branch) - percent(base),
+            human_lines_rewritten,
+        };
+        if !args.rewrites_only || comparison.human_lines_rewritten > 0 {
+            files.push(comparison);
+        }
+    }
+    sort_comparisons(&mut files);
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&args, &merge_base, &files),
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": COMPARE_BRANCHES_MACHINE_SCHEMA,
+                "base": args.base,
+                "branch": args.branch,
+                "merge_base": merge_base.to_string(),
+                "files": files,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<Oid> {
+    Ok(repo
+        .revparse_single(rev)
+        .with_context(|| format!("Failed to resolve: {}", rev))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", rev))?
+        .id())
+}
+
+/// Files changed from `from` to `to`, with line numbers of each hunk
+fn changed_files(repo: &Repository, from: Oid, to: Oid) -> Result<Vec<ChangedFile>> {
+    let from_tree = repo.find_commit(from)?.tree()?;
+    let to_tree = repo.find_commit(to)?.tree()?;
+    let mut diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+    diff.find_similar(None)?;
+
+    let path_of = |file: git2::DiffFile| {
+        (!file.id().is_zero())
+            .then(|| file.path().map(repo_path::from_path))
+            .flatten()
+    };
+
+    let mut files = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let mut file = ChangedFile {
+            old_path: path_of(delta.old_file()),
+            new_path: path_of(delta.new_file()),
+            hunks: Vec::new(),
+        };
+        // Binary files have no patch
+        if let Some(patch) = git2::Patch::from_diff(&diff, index)? {
+            for hunk_index in 0..patch.num_hunks() {
+                let mut hunk = Hunk::default();
+                for line_index in 0..patch.num_lines_in_hunk(hunk_index)? {
+                    let line = patch.line_in_hunk(hunk_index, line_index)?;
+                    match line.origin() {
+                        '+' => hunk.added.extend(line.new_lineno()),
+                        '-' => hunk.removed.extend(line.old_lineno()),
+                        _ => {}
+                    }
+                }
+                file.hunks.push(hunk);
+            }
+        }
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// AI line numbers and total line count of a file at a revision
+/// (None if the file is missing there or can't be blamed)
+fn ai_lines(blamer: &mut AIBlamer, path: &str, revision: &str) -> Option<(HashSet<u32>, usize)> {
+    let result = blamer.blame(path, Some(revision)).ok()?;
+    let ai = result
+        .lines
+        .iter()
+        .filter(|l| l.is_ai())
+        .map(|l| l.line_number)
+        .collect();
+    Some((ai, result.lines.len()))
+}
+
+/// Removed non-AI lines in hunks whose added lines include AI lines
+fn count_human_rewrites(
+    hunks: &[Hunk],
+    merge_base_ai: &HashSet<u32>,
+    branch_ai: &HashSet<u32>,
+) -> usize {
+    hunks
+        .iter()
+        .filter(|h| h.added.iter().any(|line| branch_ai.contains(line)))
+        .map(|h| {
+            h.removed
+                .iter()
+                .filter(|line| !merge_base_ai.contains(line))
+                .count()
+        })
+        .sum()
+}
+
+/// Rewrites of human code first, then by largest AI share increase
+fn sort_comparisons(files: &mut [FileComparison]) {
+    files.sort_by(|a, b| {
+        b.human_lines_rewritten
+            .cmp(&a.human_lines_rewritten)
+            .then(b.ai_percent_change.total_cmp(&a.ai_percent_change))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+}
+
+fn print_pretty(args: &CompareBranchesArgs, merge_base: &Oid, files: &[FileComparison]) {
+    let merge_base = merge_base.to_string();
+    println!(
+        "{} {} → {} ({} files, merge base {})",
+        "AI share".bold(),
+        args.base,
+        args.branch,
+        files.len(),
+        &merge_base[..SHORT_COMMIT_LEN]
+    );
+    if files.is_empty() {
+        println!("No changed files to compare.");
+        return;
+    }
+    println!();
+
+    let share = |s: Option<AiShare>| match s {
+        Some(s) => format!("{:>6.1}%", s.ai_percent),
+        None => format!("{:>7}", "-"),
+    };
+    println!(
+        "  {}  {:>7}  {:>7}  {:>7}",
+        truncate_or_pad("File", 40),
+        truncate(&args.base, 7),
+        truncate(&args.branch, 7),
+        "Change"
+    );
+    for file in files {
+        let change = format!("{:>+7.1}", file.ai_percent_change);
+        let change = if file.ai_percent_change > 0.0 {
+            change.yellow()
+        } else {
+            change.normal()
+        };
+        print!(
+            "  {}  {}  {}  {}",
+            truncate_or_pad(&file.path, 40),
+            share(file.base),
+            share(file.branch),
+            change
+        );
+        if file.human_lines_rewritten > 0 {
+            print!(
+                "  {}",
+                format!(
+                    "AI rewrote {} human-authored line{}",
+                    file.human_lines_rewritten,
+                    if file.human_lines_rewritten == 1 {
+                        ""
+                    } else {
+                        "s"
+                    }
+                )
+                .red()
+            );
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_human_rewrites_only_in_hunks_with_ai_additions() {
+        let hunks = vec![
+            // Two human lines replaced by AI lines 10-11
+            Hunk {
+                removed: vec![10, 11],
+                added: vec![10, 11],
+            },
+            // Old AI line and a human line replaced; only the human one counts
+            Hunk {
+                removed: vec![20, 21],
+                added: vec![20],
+            },
+            // Human rewrote human lines: not an AI rewrite
+            Hunk {
+                removed: vec![30],
+                added: vec![30],
+            },
+        ];
+        let merge_base_ai = HashSet::from([20]);
+        let branch_ai = HashSet::from([10, 11, 20]);
+
+        assert_eq!(count_human_rewrites(&hunks, &merge_base_ai, &branch_ai), 3);
+        assert_eq!(
+            count_human_rewrites(&hunks, &merge_base_ai, &HashSet::new()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_sort_puts_rewrites_first() {
+        let file = |path: &str, change: f64, rewritten: usize| FileComparison {
+            path: path.to_string(),
+            base: None,
+            branch: None,
+            ai_percent_change: change,
+            human_lines_rewritten: rewritten,
+        };
+        let mut files = vec![
+            file("a.rs", 80.0, 0),
+            file("b.rs", 10.0, 4),
+            file("c.rs", -5.0, 0),
+            file("d.rs", 30.0, 4),
+        ];
+        sort_comparisons(&mut files);
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["d.rs", "b.rs", "a.rs", "c.rs"]);
+    }
+}
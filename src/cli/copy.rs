@@ -5,6 +5,7 @@ use clap::Args;
 use git2::Repository;
 
 use crate::storage::notes::NotesStore;
+use crate::storage::{LockHolderKind, RepoLock};
 
 /// Copy AI attribution from one commit to another
 #[derive(Debug, Args)]
@@ -44,6 +45,8 @@ pub fn run(args: CopyNotesArgs) -> Result<()> {
         return Ok(());
     }
 
+    let _lock = RepoLock::acquire_default(repo.path(), LockHolderKind::CopyNotes)?;
+
     store.copy_attribution(source_oid, target_oid)?;
     println!("Copied attribution: {} -> {}", source_short, target_short);
     Ok(())
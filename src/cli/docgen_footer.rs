@@ -0,0 +1,273 @@
+//! Docgen-footer command - provenance blurbs for generated documentation
+//!
+//! Produces a one-line statement of how much of a file is AI-authored, which
+//! models wrote it and when the most recent AI session touched it. The blurb can
+//! be pasted into mdBook pages or rustdoc module headers by a pre-commit
+//! template step; `--all` writes the same data for every file as a JSON map.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use serde::Serialize;
+
+use crate::capture::encoding;
+use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::core::attribution::SessionMetadata;
+use crate::core::blame::AIBlamer;
+
+const DOCGEN_FOOTER_MACHINE_SCHEMA: &str = "whogitit.docgen-footer.v1";
+
+/// Docgen-footer command arguments
+#[derive(Debug, Args)]
+pub struct DocgenFooterArgs {
+    /// File to describe
+    #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+    pub path: Option<String>,
+
+    /// Describe every text file in the tree as a JSON map keyed by path
+    #[arg(long)]
+    pub all: bool,
+
+    /// Revision to describe
+    #[arg(long, default_value = "HEAD")]
+    pub rev: String,
+
+    /// Blurb format for a single file (--all always writes JSON)
+    #[arg(long, value_enum, default_value_t = FooterFormat::Markdown)]
+    pub format: FooterFormat,
+
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+/// Footer formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FooterFormat {
+    /// Plain sentence
+    Text,
+    /// Markdown blockquote for mdBook pages
+    Markdown,
+    /// `//!` inner doc comment for a Rust module header
+    Rustdoc,
+    /// Machine-readable record
+    Json,
+}
+
+/// Provenance of one file, as shown in a footer
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct FileFooter {
+    pub total_lines: usize,
+    /// AI and AI-modified lines
+    pub ai_lines: usize,
+    /// AI lines over all lines (0.0-1.0)
+    pub ai_share: f64,
+    /// Models that contributed AI lines still present in the file
+    pub models: Vec<String>,
+    /// Start date (YYYY-MM-DD) of the most recent session with lines still present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_ai_session: Option<String>,
+}
+
+/// Run the docgen-footer command
+pub fn run(args: DocgenFooterArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let commit = repo
+        .revparse_single(&args.rev)
+        .with_context(|| format!("Failed to resolve: {}", args.rev))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", args.rev))?;
+    let commit_id = commit.id().to_string();
+    let mut footers = FooterBuilder::new(&repo)?;
+
+    let content = if args.all {
+        let mut files = BTreeMap::new();
+        for path in text_files(&repo, &commit.tree()?)? {
+            let footer = footers.build(&path, &commit_id)?;
+            files.insert(path, footer);
+        }
+        let json = serde_json::json!({
+            "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+            "schema": DOCGEN_FOOTER_MACHINE_SCHEMA,
+            "commit": commit_id,
+            "files": files,
+        });
+        serde_json::to_string_pretty(&json)?
+    } else {
+        let path = args.path.as_deref().unwrap_or_default();
+        let footer = footers.build(path, &commit_id)?;
+        match args.format {
+            FooterFormat::Json => {
+                let json = serde_json::json!({
+                    "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                    "schema": DOCGEN_FOOTER_MACHINE_SCHEMA,
+                    "commit": commit_id,
+                    "path": path,
+                    "footer": footer,
+                });
+                serde_json::to_string_pretty(&json)?
+            }
+            format => render(&footer, format),
+        }
+    };
+
+    match &args.output {
+        Some(path) => std::fs::write(path, format!("{}\n", content))
+            .with_context(|| format!("Failed to write {}", path))?,
+        None => println!("{}", content),
+    }
+    Ok(())
+}
+
+/// Paths of all decodable text blobs in a tree
+fn text_files(repo: &Repository, tree: &git2::Tree) -> Result<Vec<String>> {
+    let mut blobs = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                blobs.push((format!("{}{}", dir, name), entry.id()));
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let mut paths = Vec::new();
+    for (path, blob_id) in blobs {
+        if encoding::decode(repo.find_blob(blob_id)?.content()).is_some() {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// Blames files and caches session metadata across them
+struct FooterBuilder<'a> {
+    blamer: AIBlamer<'a>,
+    commit_sessions: HashMap<String, Option<SessionMetadata>>,
+}
+
+impl<'a> FooterBuilder<'a> {
+    fn new(repo: &'a Repository) -> Result<Self> {
+        Ok(Self {
+            blamer: AIBlamer::new(repo)?,
+            commit_sessions: HashMap::new(),
+        })
+    }
+
+    fn build(&mut self, path: &str, commit_id: &str) -> Result<FileFooter> {
+        let blame = self.blamer.blame(path, Some(commit_id))?;
+        let mut footer = FileFooter {
+            total_lines: blame.lines.len(),
+            ..Default::default()
+        };
+        let mut models = BTreeSet::new();
+        let mut last_started: Option<String> = None;
+
+        for line in blame.lines.iter().filter(|line| line.is_ai()) {
+            footer.ai_lines += 1;
+            if !self.commit_sessions.contains_key(&line.commit_id) {
+                let session = self
+                    .blamer
+                    .get_commit_attribution(&line.commit_id)?
+                    .map(|a| a.session);
+                self.commit_sessions.insert(line.commit_id.clone(), session);
+            }
+            let Some(Some(session)) = self.commit_sessions.get(&line.commit_id) else {
+                continue;
+            };
+            models.insert(session.model.id.clone());
+            if last_started.as_deref() < Some(session.started_at.as_str()) {
+                last_started = Some(session.started_at.clone());
+            }
+        }
+
+        footer.ai_share = share(footer.ai_lines, footer.total_lines);
+        footer.models = models.into_iter().collect();
+        footer.last_ai_session = last_started.map(|started| session_date(&started));
+        Ok(footer)
+    }
+}
+
+/// Date part of an RFC 3339 timestamp, in UTC
+fn session_date(started_at: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(started_at)
+        .map(|t| t.with_timezone(&chrono::Utc).format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| started_at.chars().take(10).collect())
+}
+
+/// Fraction rounded to 3 decimals
+fn share(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (part as f64 / total as f64 * 1000.0).round() / 1000.0
+}
+
+/// The provenance sentence shared by all text formats
+fn sentence(footer: &FileFooter) -> String {
+    if footer.ai_lines == 0 {
+        return "No AI-assisted lines in this file.".to_string();
+    }
+    let mut text = format!(
+        "{:.1}% of this file ({} of {} lines) is AI-assisted",
+        footer.ai_share * 100.0,
+        footer.ai_lines,
+        footer.total_lines
+    );
+    if !footer.models.is_empty() {
+        text.push_str(&format!(" ({})", footer.models.join(", ")));
+    }
+    text.push('.');
+    if let Some(date) = &footer.last_ai_session {
+        text.push_str(&format!(" Last AI session: {}.", date));
+    }
+    text
+}
+
+fn render(footer: &FileFooter, format: FooterFormat) -> String {
+    let text = sentence(footer);
+    match format {
+        FooterFormat::Text | FooterFormat::Json => text,
+        FooterFormat::Markdown => format!("> **AI provenance:** {}", text),
+        FooterFormat::Rustdoc => format!("//! AI provenance: {}", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footer() -> FileFooter {
+        FileFooter {
+            total_lines: 200,
+            ai_lines: 84,
+            ai_share: 0.42,
+            models: vec!["claude-opus-4-5-20251101".to_string()],
+            last_ai_session: Some("2026-01-30".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let footer = footer();
+        assert_eq!(
+            render(&footer, FooterFormat::Text),
+            "42.0% of this file (84 of 200 lines) is AI-assisted (claude-opus-4-5-20251101). \
+             Last AI session: 2026-01-30."
+        );
+        assert!(render(&footer, FooterFormat::Markdown).starts_with("> **AI provenance:** 42.0%"));
+        assert!(render(&footer, FooterFormat::Rustdoc).starts_with("//! AI provenance: 42.0%"));
+        assert_eq!(
+            render(&FileFooter::default(), FooterFormat::Text),
+            "No AI-assisted lines in this file."
+        );
+    }
+
+    #[test]
+    fn test_session_date_uses_utc() {
+        assert_eq!(session_date("2026-01-30T23:30:00-02:00"), "2026-01-31");
+        assert_eq!(session_date("2026-01-30"), "2026-01-30");
+    }
+}
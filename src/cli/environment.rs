@@ -0,0 +1,132 @@
+//! Runtime environment detection for setup and doctor
+//!
+//! Capture normally runs on the same machine and home directory as the
+//! repository and the Claude Code config. Inside devcontainers, Codespaces or
+//! SSH sessions that is not guaranteed, so setup/doctor resolve the home
+//! directory through `WHOGITIT_HOME` first and report which environment they
+//! detected.
+
+use std::path::{Path, PathBuf};
+
+/// Overrides the home directory used to locate `.claude/` (settings and hooks)
+pub const ENV_WHOGITIT_HOME: &str = "WHOGITIT_HOME";
+
+/// Where whogitit is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeEnvironment {
+    /// Local machine
+    Host,
+    /// VS Code / devcontainer CLI container
+    Devcontainer,
+    /// GitHub Codespaces
+    Codespaces,
+    /// Other container (Docker, Podman, Kubernetes)
+    Container,
+    /// Remote shell over SSH
+    Ssh,
+}
+
+impl RuntimeEnvironment {
+    /// Detect the current environment
+    pub fn detect() -> Self {
+        Self::detect_with(
+            |key| std::env::var(key).ok().filter(|v| !v.is_empty()),
+            |path| Path::new(path).exists(),
+        )
+    }
+
+    fn detect_with(env: impl Fn(&str) -> Option<String>, exists: impl Fn(&str) -> bool) -> Self {
+        let is_true = |key: &str| env(key).is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+        if is_true("CODESPACES") {
+            RuntimeEnvironment::Codespaces
+        } else if is_true("REMOTE_CONTAINERS") || is_true("DEVCONTAINER") {
+            RuntimeEnvironment::Devcontainer
+        } else if exists("/.dockerenv")
+            || exists("/run/.containerenv")
+            || env("KUBERNETES_SERVICE_HOST").is_some()
+        {
+            RuntimeEnvironment::Container
+        } else if env("SSH_CONNECTION").is_some() || env("SSH_CLIENT").is_some() {
+            RuntimeEnvironment::Ssh
+        } else {
+            RuntimeEnvironment::Host
+        }
+    }
+
+    /// Whether the home directory may differ from the one the AI tool or host uses
+    pub fn is_remote(self) -> bool {
+        self != RuntimeEnvironment::Host
+    }
+
+    /// Human-readable name
+    pub fn label(self) -> &'static str {
+        match self {
+            RuntimeEnvironment::Host => "local host",
+            RuntimeEnvironment::Devcontainer => "devcontainer",
+            RuntimeEnvironment::Codespaces => "GitHub Codespaces",
+            RuntimeEnvironment::Container => "container",
+            RuntimeEnvironment::Ssh => "SSH session",
+        }
+    }
+}
+
+/// `WHOGITIT_HOME`, if set
+pub fn home_override() -> Option<PathBuf> {
+    std::env::var_os(ENV_WHOGITIT_HOME)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Home directory used for `.claude/`: `WHOGITIT_HOME`, falling back to `$HOME`
+pub fn home_dir() -> Option<PathBuf> {
+    home_override().or_else(dirs::home_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)], files: &[&str]) -> RuntimeEnvironment {
+        RuntimeEnvironment::detect_with(
+            |key| {
+                vars.iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            },
+            |path| files.contains(&path),
+        )
+    }
+
+    #[test]
+    fn test_detect_environment() {
+        assert_eq!(detect(&[], &[]), RuntimeEnvironment::Host);
+        assert_eq!(
+            detect(&[("CODESPACES", "true")], &["/.dockerenv"]),
+            RuntimeEnvironment::Codespaces
+        );
+        assert_eq!(
+            detect(&[("REMOTE_CONTAINERS", "true")], &["/.dockerenv"]),
+            RuntimeEnvironment::Devcontainer
+        );
+        assert_eq!(
+            detect(&[], &["/run/.containerenv"]),
+            RuntimeEnvironment::Container
+        );
+        assert_eq!(
+            detect(&[("SSH_CONNECTION", "10.0.0.1 5000 10.0.0.2 22")], &[]),
+            RuntimeEnvironment::Ssh
+        );
+        assert_eq!(
+            detect(&[("DEVCONTAINER", "false")], &[]),
+            RuntimeEnvironment::Host
+        );
+    }
+
+    #[test]
+    fn test_is_remote() {
+        assert!(!RuntimeEnvironment::Host.is_remote());
+        assert!(RuntimeEnvironment::Devcontainer.is_remote());
+        assert!(RuntimeEnvironment::Ssh.is_remote());
+    }
+}
@@ -2,12 +2,23 @@
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, NaiveTime, Utc};
+use git2::{Oid, Repository};
 use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
 use std::io::Write;
-
-use crate::core::attribution::AIAttribution;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use crate::capture::snapshot::FileAttributionResult;
+use crate::cli::output::{truncate_prompt_for_export, OutputSanitizer};
+use crate::core::attribution::{AIAttribution, PromptInfo, ToolCall};
+use crate::core::languages::LanguageMap;
+use crate::core::query::{FileRecord, Query};
+use crate::privacy::tiers::{self, PromptStorage, TierStorageConfig};
 use crate::privacy::WhogititConfig;
 use crate::storage::audit::AuditLog;
+use crate::storage::index::AttributionIndex;
 use crate::storage::notes::NotesStore;
 
 /// Arguments for export command
@@ -30,14 +41,37 @@ pub struct ExportArgs {
     pub output: Option<String>,
 
     /// Include full prompt text (default: truncated)
-    #[arg(long)]
+    #[arg(long, conflicts_with = "redact_level")]
     pub full_prompts: bool,
 
     /// Maximum prompt length when not using --full-prompts
     #[arg(long, default_value = "100")]
     pub prompt_max_len: usize,
+
+    /// Export prompts above this privacy tier as hash only (1-3)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=3))]
+    pub max_tier: Option<u8>,
+
+    /// Number of worker threads reading notes (default: available CPUs)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Disable the progress indicator
+    #[arg(long)]
+    pub no_progress: bool,
+
+    /// Sanitization preset: full, internal, external or one from `[redact_levels]`
+    #[arg(long)]
+    pub redact_level: Option<String>,
+
+    /// Only export files matching this query, e.g. 'ai_percent > 50 && path ~ "src/**"'
+    #[arg(long)]
+    pub query: Option<String>,
 }
 
+/// Commits handed to a worker at a time
+const EXPORT_CHUNK_SIZE: usize = 256;
+
 /// Export format for JSON output
 #[derive(Debug, Serialize)]
 pub struct ExportData {
@@ -47,7 +81,13 @@ pub struct ExportData {
     pub exported_at: String,
     /// Date range filter (if specified)
     pub date_range: Option<DateRange>,
-    /// Exported commits
+    /// Sanitization preset applied (`--redact-level`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redact_level: Option<String>,
+    /// File filter applied (`--query`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Exported commits (empty when the preset allows aggregated counts only)
     pub commits: Vec<CommitExport>,
     /// Summary statistics
     pub summary: ExportSummary,
@@ -89,14 +129,68 @@ pub struct CommitExport {
     pub files: Vec<String>,
     /// Prompts used
     pub prompts: Vec<PromptExport>,
+    /// Files attributed as a whole (binary assets, lockfiles)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub binary_files: Vec<BinaryFileExport>,
+    /// Line counts per language (detected from file extensions)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, LanguageExport>,
+}
+
+/// Exported line counts for one language
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct LanguageExport {
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    pub human_lines: usize,
+    /// Share of AI and AI-modified lines among the lines written (0.0-1.0)
+    pub ai_share: f64,
+}
+
+impl LanguageExport {
+    fn add(&mut self, other: &LanguageExport) {
+        self.ai_lines += other.ai_lines;
+        self.ai_modified_lines += other.ai_modified_lines;
+        self.human_lines += other.human_lines;
+        self.update_share();
+    }
+
+    fn update_share(&mut self) {
+        let ai = self.ai_lines + self.ai_modified_lines;
+        let written = ai + self.human_lines;
+        self.ai_share = if written == 0 {
+            0.0
+        } else {
+            ai as f64 / written as f64
+        };
+    }
+}
+
+/// Exported whole-file attribution for a binary asset or lockfile
+#[derive(Debug, Serialize)]
+pub struct BinaryFileExport {
+    pub path: String,
+    pub ai_generated: bool,
+    pub tool: String,
+    pub prompt_index: u32,
 }
 
 /// Exported prompt data
 #[derive(Debug, Serialize)]
 pub struct PromptExport {
     pub index: u32,
+    /// Prompt text, or a placeholder when the prompt is exported hash-only
     pub text: String,
+    pub hash: String,
+    /// Privacy tier (1-3)
+    pub tier: u8,
     pub affected_files: Vec<String>,
+    /// Tool calls behind the prompt's edits, for tracing lines to the session transcript
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// Whether `text` is a placeholder rather than prompt text
+    #[serde(skip)]
+    pub hash_only: bool,
 }
 
 /// Export summary statistics
@@ -109,6 +203,8 @@ pub struct ExportSummary {
     pub total_human_lines: usize,
     pub total_original_lines: usize,
     pub total_prompts: usize,
+    /// Line counts per language across all exported commits
+    pub languages: BTreeMap<String, LanguageExport>,
 }
 
 /// Run the export command
@@ -121,6 +217,21 @@ pub fn run(args: ExportArgs) -> Result<()> {
         .workdir()
         .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
     let notes_store = NotesStore::new(&repo)?;
+    let config = WhogititConfig::load(repo_root).context("Failed to load configuration")?;
+    let language_map = LanguageMap::new(&config.languages);
+    let sanitizer = match &args.redact_level {
+        Some(name) => Some(OutputSanitizer::new(name, config.redact_level(name)?)),
+        None => None,
+    };
+    if args.format == "csv" && sanitizer.as_ref().is_some_and(|s| s.aggregate_only()) {
+        anyhow::bail!(
+            "Redact level '{}' only allows aggregated counts, which CSV cannot carry; \
+             use --format json",
+            args.redact_level.as_deref().unwrap_or_default()
+        );
+    }
+
+    let query = args.query.as_deref().map(Query::parse).transpose()?;
 
     // Parse date filters
     let since = parse_date(&args.since, DateBoundary::StartOfDay)?;
@@ -138,40 +249,45 @@ pub fn run(args: ExportArgs) -> Result<()> {
     }
 
     // Get all commits with attribution
-    let attributed_commits = notes_store.list_attributed_commits()?;
-
-    // Collect export data
-    let mut commits: Vec<CommitExport> = Vec::new();
-
-    for commit_oid in attributed_commits {
-        let commit = repo.find_commit(commit_oid)?;
-        let commit_time =
-            DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or(DateTime::UNIX_EPOCH);
-
-        // Apply date filters
-        if let Some(ref since_date) = since {
-            if commit_time < *since_date {
-                continue;
-            }
-        }
-        if let Some(ref until_date) = until {
-            if commit_time > *until_date {
-                continue;
-            }
-        }
-
-        // Get attribution data
-        if let Some(attribution) = notes_store.fetch_attribution(commit_oid)? {
-            let export = build_commit_export(&commit, &attribution, &args)?;
-            commits.push(export);
+    let mut attributed_commits = notes_store.list_attributed_commits()?;
+    if let Some(query) = &query {
+        if let Some(skip) = indexed_non_matches(&repo, &notes_store, query, &language_map) {
+            attributed_commits.retain(|oid| !skip.contains(&oid.to_string()));
         }
     }
 
-    // Sort by commit time (newest first)
+    // Collect export data
+    let jobs = args.jobs.unwrap_or_else(default_jobs).max(1);
+    let mut progress = ExportProgress::new(
+        attributed_commits.len(),
+        !args.no_progress && atty::is(atty::Stream::Stderr),
+    );
+    let filter = DateFilter { since, until };
+    let mut commits = export_commits_parallel(
+        repo.path(),
+        &attributed_commits,
+        &filter,
+        &ExportOptions {
+            args: &args,
+            languages: &language_map,
+            prompt_storage: &config.privacy.tiers,
+            sanitizer: sanitizer.as_ref(),
+            query: query.as_ref(),
+        },
+        jobs,
+        EXPORT_CHUNK_SIZE,
+        &mut progress,
+    )?;
+    progress.finish();
+
+    // Sort by commit time (newest first); the sort is stable, so ties keep notes order
     commits.sort_by(|a, b| b.committed_at.cmp(&a.committed_at));
 
     // Build summary
     let summary = build_summary(&commits);
+    if sanitizer.as_ref().is_some_and(|s| s.aggregate_only()) {
+        commits.clear();
+    }
 
     // Write output
     let output_data = ExportData {
@@ -185,10 +301,28 @@ pub fn run(args: ExportArgs) -> Result<()> {
         } else {
             None
         },
+        redact_level: args.redact_level.clone(),
+        query: args.query.clone(),
         commits,
         summary,
     };
 
+    if let Some(audit_log) = AuditLog::for_prompt_access(repo_root, &config.audit) {
+        for commit in &output_data.commits {
+            let revealed: Vec<u32> = commit
+                .prompts
+                .iter()
+                .filter(|p| !p.hash_only)
+                .map(|p| p.index)
+                .collect();
+            if !revealed.is_empty() {
+                audit_log
+                    .log_prompt_access("export", &commit.commit_id, &revealed)
+                    .context("Failed to record prompt access in the audit log")?;
+            }
+        }
+    }
+
     match args.format.as_str() {
         "json" => write_json(&output_data, &args.output)?,
         "csv" => write_csv(&output_data, &args.output)?,
@@ -198,22 +332,216 @@ pub fn run(args: ExportArgs) -> Result<()> {
         ),
     }
 
-    let config = WhogititConfig::load(repo_root).context("Failed to load configuration")?;
     if config.privacy.audit_log {
-        let audit_log = AuditLog::new(repo_root);
+        let audit_log = AuditLog::new(repo_root).with_forwarding(&config.audit.forwarding);
         audit_log.log_export(&args.format, output_data.summary.total_commits as u32)?;
     }
 
     Ok(())
 }
 
+/// Commits without a file matching `query`, from the attribution index
+///
+/// Lets `--query` skip reading the notes of commits it would drop anyway. `None`
+/// (after a warning) when the index cannot be used; every commit is then read.
+fn indexed_non_matches(
+    repo: &Repository,
+    notes_store: &NotesStore,
+    query: &Query,
+    languages: &LanguageMap,
+) -> Option<HashSet<String>> {
+    let mut index = AttributionIndex::open_or_warn(repo)?;
+    let skip = index
+        .refresh(repo, notes_store)
+        .and_then(|_| index.commits_without_match(query, languages));
+    match skip {
+        Ok(skip) => Some(skip),
+        Err(err) => {
+            eprintln!(
+                "whogitit: Warning - attribution index unavailable, reading notes: {:#}",
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Settings shared by every commit export
+struct ExportOptions<'a> {
+    args: &'a ExportArgs,
+    languages: &'a LanguageMap,
+    prompt_storage: &'a TierStorageConfig,
+    /// `--redact-level` preset
+    sanitizer: Option<&'a OutputSanitizer>,
+    /// `--query` file filter
+    query: Option<&'a Query>,
+}
+
+/// Commit date filter from --since/--until
+#[derive(Debug, Clone, Copy, Default)]
+struct DateFilter {
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl DateFilter {
+    fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.since.map_or(true, |since| time >= since)
+            && self.until.map_or(true, |until| time <= until)
+    }
+}
+
+fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Progress indicator written to stderr while notes are processed
+struct ExportProgress {
+    total: usize,
+    done: usize,
+    enabled: bool,
+}
+
+impl ExportProgress {
+    fn new(total: usize, enabled: bool) -> Self {
+        Self {
+            total,
+            done: 0,
+            enabled: enabled && total > 0,
+        }
+    }
+
+    fn advance(&mut self, count: usize) {
+        self.done += count;
+        if self.enabled {
+            eprint!("\r{}", self.render());
+            let _ = std::io::stderr().flush();
+        }
+    }
+
+    fn render(&self) -> String {
+        const WIDTH: usize = 30;
+        let filled = (self.done * WIDTH)
+            .checked_div(self.total)
+            .unwrap_or(WIDTH)
+            .min(WIDTH);
+        format!(
+            "Exporting [{}{}] {}/{} commits",
+            "#".repeat(filled),
+            "-".repeat(WIDTH - filled),
+            self.done,
+            self.total
+        )
+    }
+
+    fn finish(&self) {
+        if self.enabled {
+            eprintln!();
+        }
+    }
+}
+
+/// Export commits using a pool of workers.
+///
+/// The commit list is split into chunks that workers claim in order; each worker
+/// opens its own repository handle (git2 handles are not shareable across threads)
+/// and sends back `(chunk index, exports)`. Results are reassembled in chunk order
+/// so the output matches a sequential export.
+fn export_commits_parallel(
+    git_dir: &Path,
+    oids: &[Oid],
+    filter: &DateFilter,
+    options: &ExportOptions,
+    jobs: usize,
+    chunk_size: usize,
+    progress: &mut ExportProgress,
+) -> Result<Vec<CommitExport>> {
+    let chunks: Vec<&[Oid]> = oids.chunks(chunk_size.max(1)).collect();
+    let next_chunk = AtomicUsize::new(0);
+    let mut results: Vec<Option<Vec<CommitExport>>> = Vec::new();
+    results.resize_with(chunks.len(), || None);
+
+    std::thread::scope(|scope| -> Result<()> {
+        let (tx, rx) = mpsc::channel::<(usize, usize, Result<Vec<CommitExport>>)>();
+
+        for _ in 0..jobs.min(chunks.len()) {
+            let tx = tx.clone();
+            let chunks = &chunks;
+            let next_chunk = &next_chunk;
+            scope.spawn(move || {
+                let repo = match Repository::open(git_dir) {
+                    Ok(repo) => repo,
+                    Err(e) => {
+                        let _ = tx.send((0, 0, Err(e).context("Failed to open repository")));
+                        return;
+                    }
+                };
+                loop {
+                    let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                    let Some(chunk) = chunks.get(index) else {
+                        break;
+                    };
+                    let result = export_chunk(&repo, chunk, filter, options);
+                    let failed = result.is_err();
+                    if tx.send((index, chunk.len(), result)).is_err() || failed {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        for (index, processed, result) in rx {
+            results[index] = Some(result?);
+            progress.advance(processed);
+        }
+        Ok(())
+    })?;
+
+    Ok(results.into_iter().flatten().flatten().collect())
+}
+
+/// Export one chunk of commits
+fn export_chunk(
+    repo: &Repository,
+    oids: &[Oid],
+    filter: &DateFilter,
+    options: &ExportOptions,
+) -> Result<Vec<CommitExport>> {
+    let notes_store = NotesStore::new(repo)?;
+    let mut exports = Vec::new();
+
+    for &commit_oid in oids {
+        let commit = repo.find_commit(commit_oid)?;
+        let commit_time =
+            DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or(DateTime::UNIX_EPOCH);
+
+        // Apply date filters
+        if !filter.contains(commit_time) {
+            continue;
+        }
+
+        // Get attribution data
+        if let Some(attribution) = notes_store.fetch_attribution(commit_oid)? {
+            exports.extend(build_commit_export(&commit, &attribution, options)?);
+        }
+    }
+
+    Ok(exports)
+}
+
 #[derive(Debug, Clone, Copy)]
-enum DateBoundary {
+pub(crate) enum DateBoundary {
     StartOfDay,
     EndOfDay,
 }
 
-fn parse_date(date_str: &Option<String>, boundary: DateBoundary) -> Result<Option<DateTime<Utc>>> {
+pub(crate) fn parse_date(
+    date_str: &Option<String>,
+    boundary: DateBoundary,
+) -> Result<Option<DateTime<Utc>>> {
     match date_str {
         Some(s) => {
             // Parse YYYY-MM-DD format
@@ -232,44 +560,86 @@ fn parse_date(date_str: &Option<String>, boundary: DateBoundary) -> Result<Optio
     }
 }
 
+/// Export a commit, restricted to the files matching `--query`
+///
+/// Returns `None` when a query is given and no file of the commit matches it.
 fn build_commit_export(
     commit: &git2::Commit,
     attribution: &AIAttribution,
-    args: &ExportArgs,
-) -> Result<CommitExport> {
+    options: &ExportOptions,
+) -> Result<Option<CommitExport>> {
+    let author_name = commit.author().name().unwrap_or("Unknown").to_string();
+    let selected: Vec<&FileAttributionResult> = match options.query {
+        Some(query) => {
+            let selected: Vec<_> = attribution
+                .files
+                .iter()
+                .filter(|f| {
+                    query.matches(&FileRecord {
+                        path: &f.path,
+                        model: &attribution.session.model.id,
+                        author: &author_name,
+                        language: options.languages.detect(&f.path),
+                        summary: &f.summary,
+                    })
+                })
+                .collect();
+            if selected.is_empty() {
+                return Ok(None);
+            }
+            selected
+        }
+        None => attribution.files.iter().collect(),
+    };
+
     let commit_id = commit.id().to_string();
     let commit_short = commit_id[..7].to_string();
-    let message = commit.summary().unwrap_or("(no message)").to_string();
-    let author = commit.author().name().unwrap_or("Unknown").to_string();
+    let content = |text: &str| match options.sanitizer {
+        Some(sanitizer) => sanitizer.content(text),
+        None => text.to_string(),
+    };
+    let message = content(commit.summary().unwrap_or("(no message)"));
+    let author = content(&author_name);
     let committed_at = DateTime::from_timestamp(commit.time().seconds(), 0)
         .unwrap_or(DateTime::UNIX_EPOCH)
         .to_rfc3339();
 
-    let ai_lines = attribution.total_ai_lines();
-    let ai_modified_lines = attribution.total_ai_modified_lines();
-    let human_lines = attribution.total_human_lines();
-    let original_lines = attribution.total_original_lines();
+    let ai_lines = selected.iter().map(|f| f.summary.ai_lines).sum();
+    let ai_modified_lines = selected.iter().map(|f| f.summary.ai_modified_lines).sum();
+    let human_lines = selected.iter().map(|f| f.summary.human_lines).sum();
+    let original_lines = selected.iter().map(|f| f.summary.original_lines).sum();
 
-    let files: Vec<String> = attribution.files.iter().map(|f| f.path.clone()).collect();
+    let files: Vec<String> = selected.iter().map(|f| content(&f.path)).collect();
+    let binary_files: Vec<BinaryFileExport> = selected
+        .iter()
+        .filter_map(|f| {
+            f.binary.as_ref().map(|b| BinaryFileExport {
+                path: content(&f.path),
+                ai_generated: b.ai_generated,
+                tool: b.tool.clone(),
+                prompt_index: b.prompt_index,
+            })
+        })
+        .collect();
+
+    let mut languages: BTreeMap<String, LanguageExport> = BTreeMap::new();
+    for file in &selected {
+        let language = languages
+            .entry(options.languages.detect(&file.path).to_string())
+            .or_default();
+        language.ai_lines += file.summary.ai_lines;
+        language.ai_modified_lines += file.summary.ai_modified_lines;
+        language.human_lines += file.summary.human_lines;
+        language.update_share();
+    }
 
     let prompts: Vec<PromptExport> = attribution
         .prompts
         .iter()
-        .map(|p| {
-            let text = if args.full_prompts {
-                p.text.clone()
-            } else {
-                truncate_prompt_for_export(&p.text, args.prompt_max_len)
-            };
-            PromptExport {
-                index: p.index,
-                text,
-                affected_files: p.affected_files.clone(),
-            }
-        })
+        .filter_map(|p| export_prompt(p, options))
         .collect();
 
-    Ok(CommitExport {
+    Ok(Some(CommitExport {
         commit_id,
         commit_short,
         message,
@@ -283,17 +653,57 @@ fn build_commit_export(
         original_lines,
         files,
         prompts,
-    })
+        binary_files,
+        languages,
+    }))
 }
 
-fn truncate_prompt_for_export(text: &str, max_len: usize) -> String {
-    let char_count = text.chars().count();
-    if char_count <= max_len {
-        return text.to_string();
+/// Export a prompt, hashing it if its tier is hash-only in config or above --max-tier
+///
+/// Prompts from notes written before classification are classified here. Returns
+/// `None` when the `--redact-level` preset omits prompts.
+fn export_prompt(prompt: &PromptInfo, options: &ExportOptions) -> Option<PromptExport> {
+    if options
+        .sanitizer
+        .is_some_and(OutputSanitizer::omits_prompts)
+    {
+        return None;
     }
-
-    let truncated: String = text.chars().take(max_len).collect();
-    format!("{truncated}...")
+    let args = options.args;
+    let privacy = prompt
+        .privacy
+        .unwrap_or_else(|| tiers::classify(&prompt.text));
+    let hash_only = options.prompt_storage.storage_for(privacy.tier) == PromptStorage::Hash
+        || args
+            .max_tier
+            .is_some_and(|max| u8::from(privacy.tier) > max);
+
+    let text = if hash_only {
+        tiers::hash_only_placeholder(privacy.tier)
+    } else if let Some(sanitizer) = options.sanitizer {
+        sanitizer.prompt(&prompt.text)?
+    } else if args.full_prompts {
+        prompt.text.clone()
+    } else {
+        truncate_prompt_for_export(&prompt.text, args.prompt_max_len)
+    };
+    let affected_files = match options.sanitizer {
+        Some(sanitizer) => prompt
+            .affected_files
+            .iter()
+            .map(|f| sanitizer.content(f))
+            .collect(),
+        None => prompt.affected_files.clone(),
+    };
+    Some(PromptExport {
+        index: prompt.index,
+        text,
+        hash: prompt.hash(),
+        tier: privacy.tier.into(),
+        affected_files,
+        tool_calls: prompt.tool_calls.clone(),
+        hash_only,
+    })
 }
 
 fn build_summary(commits: &[CommitExport]) -> ExportSummary {
@@ -303,6 +713,12 @@ fn build_summary(commits: &[CommitExport]) -> ExportSummary {
     let total_human_lines: usize = commits.iter().map(|c| c.human_lines).sum();
     let total_original_lines: usize = commits.iter().map(|c| c.original_lines).sum();
     let total_prompts: usize = commits.iter().map(|c| c.prompts.len()).sum();
+    let mut languages: BTreeMap<String, LanguageExport> = BTreeMap::new();
+    for commit in commits {
+        for (language, lines) in &commit.languages {
+            languages.entry(language.clone()).or_default().add(lines);
+        }
+    }
 
     ExportSummary {
         total_commits: commits.len(),
@@ -312,6 +728,7 @@ fn build_summary(commits: &[CommitExport]) -> ExportSummary {
         total_human_lines,
         total_original_lines,
         total_prompts,
+        languages,
     }
 }
 
@@ -387,7 +804,7 @@ fn write_csv(data: &ExportData, output: &Option<String>) -> Result<()> {
     Ok(())
 }
 
-fn csv_escape(value: &str) -> String {
+pub(crate) fn csv_escape(value: &str) -> String {
     let escaped_quotes = value.replace('"', "\"\"");
     let normalized_newlines = escaped_quotes.replace("\r\n", "\n").replace('\r', "\n");
     let escaped_single_line = normalized_newlines.replace('\n', " ");
@@ -397,6 +814,8 @@ fn csv_escape(value: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::attribution::prompt_hash;
+    use crate::privacy::RedactLevel;
 
     // parse_date tests
 
@@ -490,8 +909,14 @@ mod tests {
             prompts: vec![PromptExport {
                 index: 0,
                 text: "Test prompt".to_string(),
+                hash: String::new(),
+                tier: 1,
                 affected_files: vec!["src/main.rs".to_string()],
+                tool_calls: vec![],
+                hash_only: false,
             }],
+            binary_files: vec![],
+            languages: BTreeMap::new(),
         }];
         let summary = build_summary(&commits);
         assert_eq!(summary.total_commits, 1);
@@ -503,6 +928,17 @@ mod tests {
         assert_eq!(summary.total_prompts, 1);
     }
 
+    fn lang(ai_lines: usize, ai_modified_lines: usize, human_lines: usize) -> LanguageExport {
+        let mut lines = LanguageExport {
+            ai_lines,
+            ai_modified_lines,
+            human_lines,
+            ai_share: 0.0,
+        };
+        lines.update_share();
+        lines
+    }
+
     #[test]
     fn test_build_summary_multiple_commits() {
         let commits = vec![
@@ -523,14 +959,24 @@ mod tests {
                     PromptExport {
                         index: 0,
                         text: "Prompt 1".to_string(),
+                        hash: String::new(),
+                        tier: 1,
                         affected_files: vec![],
+                        tool_calls: vec![],
+                        hash_only: false,
                     },
                     PromptExport {
                         index: 1,
                         text: "Prompt 2".to_string(),
+                        hash: String::new(),
+                        tier: 1,
                         affected_files: vec![],
+                        tool_calls: vec![],
+                        hash_only: false,
                     },
                 ],
+                binary_files: vec![],
+                languages: BTreeMap::from([("Rust".to_string(), lang(50, 10, 5))]),
             },
             CommitExport {
                 commit_id: "def456".to_string(),
@@ -548,8 +994,17 @@ mod tests {
                 prompts: vec![PromptExport {
                     index: 0,
                     text: "Prompt 3".to_string(),
+                    hash: String::new(),
+                    tier: 1,
                     affected_files: vec![],
+                    tool_calls: vec![],
+                    hash_only: false,
                 }],
+                binary_files: vec![],
+                languages: BTreeMap::from([
+                    ("Rust".to_string(), lang(0, 0, 10)),
+                    ("Terraform".to_string(), lang(30, 5, 0)),
+                ]),
             },
         ];
         let summary = build_summary(&commits);
@@ -560,6 +1015,9 @@ mod tests {
         assert_eq!(summary.total_human_lines, 15); // 5 + 10
         assert_eq!(summary.total_original_lines, 150); // 100 + 50
         assert_eq!(summary.total_prompts, 3); // 2 + 1
+        assert_eq!(summary.languages["Rust"], lang(50, 10, 15));
+        assert_eq!(summary.languages["Rust"].ai_share, 0.8);
+        assert_eq!(summary.languages["Terraform"].ai_share, 1.0);
     }
 
     #[test]
@@ -578,6 +1036,8 @@ mod tests {
             original_lines: 200,
             files: vec!["file.rs".to_string()],
             prompts: vec![],
+            binary_files: vec![],
+            languages: BTreeMap::new(),
         }];
         let summary = build_summary(&commits);
         assert_eq!(summary.total_commits, 1);
@@ -628,6 +1088,8 @@ mod tests {
         let data = ExportData {
             export_version: 1,
             exported_at: "2024-01-15T12:00:00Z".to_string(),
+            redact_level: None,
+            query: None,
             date_range: Some(DateRange {
                 since: Some("2024-01-01".to_string()),
                 until: None,
@@ -641,6 +1103,7 @@ mod tests {
                 total_human_lines: 0,
                 total_original_lines: 0,
                 total_prompts: 0,
+                languages: BTreeMap::new(),
             },
         };
 
@@ -655,6 +1118,8 @@ mod tests {
         let data = ExportData {
             export_version: 1,
             exported_at: "2024-01-15T12:00:00Z".to_string(),
+            redact_level: None,
+            query: None,
             date_range: None,
             commits: vec![],
             summary: ExportSummary {
@@ -665,6 +1130,7 @@ mod tests {
                 total_human_lines: 0,
                 total_original_lines: 0,
                 total_prompts: 0,
+                languages: BTreeMap::new(),
             },
         };
 
@@ -688,6 +1154,8 @@ mod tests {
             original_lines: 100,
             files: vec!["src/main.rs".to_string(), "src/lib.rs".to_string()],
             prompts: vec![],
+            binary_files: vec![],
+            languages: BTreeMap::new(),
         };
 
         let json = serde_json::to_string(&commit).unwrap();
@@ -695,4 +1163,238 @@ mod tests {
         assert!(json.contains("\"ai_lines\":42"));
         assert!(json.contains("\"model\":\"claude-opus-4-5-20251101\""));
     }
+
+    fn test_export_args() -> ExportArgs {
+        ExportArgs {
+            format: "json".to_string(),
+            since: None,
+            until: None,
+            output: None,
+            full_prompts: false,
+            prompt_max_len: 100,
+            max_tier: None,
+            jobs: None,
+            no_progress: true,
+            redact_level: None,
+            query: None,
+        }
+    }
+
+    fn commit_with_note(repo: &Repository, message: &str, time: i64) -> Oid {
+        use crate::core::attribution::{ModelInfo, SessionMetadata};
+
+        let sig = git2::Signature::new("Test", "test@test.com", &git2::Time::new(time, 0)).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap();
+
+        let attribution = AIAttribution {
+            version: crate::core::attribution::SCHEMA_VERSION,
+            session: SessionMetadata {
+                session_id: format!("session-{}", message),
+                model: ModelInfo::claude("test-model"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 0,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files: vec![],
+        };
+        NotesStore::new(repo)
+            .unwrap()
+            .store_attribution(oid, &attribution)
+            .unwrap();
+        oid
+    }
+
+    #[test]
+    fn test_parallel_export_matches_sequential() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        for i in 0..7 {
+            commit_with_note(&repo, &format!("c{}", i), 1_700_000_000 + i);
+        }
+        let oids = NotesStore::new(&repo)
+            .unwrap()
+            .list_attributed_commits()
+            .unwrap();
+        let args = test_export_args();
+        let filter = DateFilter::default();
+
+        let run = |jobs: usize| {
+            let mut progress = ExportProgress::new(oids.len(), false);
+            let exports = export_commits_parallel(
+                repo.path(),
+                &oids,
+                &filter,
+                &ExportOptions {
+                    args: &args,
+                    languages: &LanguageMap::default(),
+                    prompt_storage: &TierStorageConfig::default(),
+                    sanitizer: None,
+                    query: None,
+                },
+                jobs,
+                2,
+                &mut progress,
+            )
+            .unwrap();
+            assert_eq!(progress.done, oids.len());
+            exports.into_iter().map(|c| c.commit_id).collect::<Vec<_>>()
+        };
+
+        let sequential = run(1);
+        assert_eq!(sequential.len(), 7);
+        assert_eq!(run(4), sequential);
+    }
+
+    #[test]
+    fn test_export_prompt_respects_tiers() {
+        let prompt = |text: &str, privacy| PromptInfo {
+            index: 0,
+            text: text.to_string(),
+            timestamp: "2026-01-30T10:00:00Z".to_string(),
+            affected_files: vec![],
+            hash: None,
+            privacy,
+            tool_calls: Vec::new(),
+        };
+        let args = ExportArgs {
+            max_tier: Some(2),
+            ..test_export_args()
+        };
+        let languages = LanguageMap::default();
+        let storage = TierStorageConfig::default();
+        let options = ExportOptions {
+            args: &args,
+            languages: &languages,
+            prompt_storage: &storage,
+            sanitizer: None,
+            query: None,
+        };
+
+        // Legacy prompt without a stored classification is classified on export
+        let pii = export_prompt(&prompt("Email ops@example.com", None), &options).unwrap();
+        assert_eq!(pii.tier, 3);
+        assert_eq!(pii.text, "[hash-only: tier 3]");
+        assert!(pii.hash_only);
+        assert_eq!(pii.hash, prompt_hash("Email ops@example.com"));
+
+        let plain = prompt("Add retries", Some(tiers::classify("Add retries")));
+        assert_eq!(export_prompt(&plain, &options).unwrap().text, "Add retries");
+
+        // Config marks tier 1 hash-only
+        let storage = TierStorageConfig {
+            tier1: PromptStorage::Hash,
+            ..Default::default()
+        };
+        let options = ExportOptions {
+            prompt_storage: &storage,
+            ..options
+        };
+        assert_eq!(
+            export_prompt(&plain, &options).unwrap().text,
+            "[hash-only: tier 1]"
+        );
+
+        // Redact levels decide the text of prompts that are not hash-only
+        let external = OutputSanitizer::new("external", RedactLevel::builtin("external").unwrap());
+        let internal = OutputSanitizer::new("internal", RedactLevel::builtin("internal").unwrap());
+        let storage = TierStorageConfig::default();
+        let options = ExportOptions {
+            prompt_storage: &storage,
+            sanitizer: Some(&external),
+            ..options
+        };
+        assert!(export_prompt(&plain, &options).is_none());
+        let options = ExportOptions {
+            sanitizer: Some(&internal),
+            ..options
+        };
+        assert_eq!(export_prompt(&plain, &options).unwrap().text, "Add retries");
+    }
+
+    #[test]
+    fn test_commit_export_restricted_to_query_matches() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let commit = fixture.repo.find_commit(fixture.head()).unwrap();
+        let mut attribution = fixture.attribution(fixture.head()).unwrap().unwrap();
+        let generated = attribution.files[0].clone();
+        let mut notes = generated.clone();
+        notes.path = "docs/notes.md".to_string();
+        notes.summary.human_lines = notes.summary.ai_lines;
+        notes.summary.ai_lines = 0;
+        attribution.files.push(notes);
+
+        let args = test_export_args();
+        let languages = LanguageMap::default();
+        let storage = TierStorageConfig::default();
+        let query = Query::parse(r#"ai_percent > 50 && path ~ "src/**""#).unwrap();
+        let options = ExportOptions {
+            args: &args,
+            languages: &languages,
+            prompt_storage: &storage,
+            sanitizer: None,
+            query: Some(&query),
+        };
+
+        let export = build_commit_export(&commit, &attribution, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(export.files, vec![generated.path.clone()]);
+        assert_eq!(export.ai_lines, generated.summary.ai_lines);
+        assert_eq!(export.human_lines, generated.summary.human_lines);
+
+        let query = Query::parse(r#"path ~ "tests/**""#).unwrap();
+        let options = ExportOptions {
+            query: Some(&query),
+            ..options
+        };
+        assert!(build_commit_export(&commit, &attribution, &options)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_date_filter_contains() {
+        let filter = DateFilter {
+            since: parse_date(&Some("2024-01-01".to_string()), DateBoundary::StartOfDay).unwrap(),
+            until: parse_date(&Some("2024-01-31".to_string()), DateBoundary::EndOfDay).unwrap(),
+        };
+        let inside = DateTime::from_timestamp(1_705_000_000, 0).unwrap(); // 2024-01-11
+        let outside = DateTime::from_timestamp(1_710_000_000, 0).unwrap(); // 2024-03-09
+        assert!(filter.contains(inside));
+        assert!(!filter.contains(outside));
+        assert!(DateFilter::default().contains(outside));
+    }
+
+    #[test]
+    fn test_export_progress_render() {
+        let mut progress = ExportProgress::new(4, false);
+        progress.advance(1);
+        assert_eq!(
+            progress.render(),
+            format!(
+                "Exporting [{}{}] 1/4 commits",
+                "#".repeat(7),
+                "-".repeat(23)
+            )
+        );
+        progress.advance(3);
+        assert!(progress.render().ends_with("4/4 commits"));
+    }
 }
@@ -0,0 +1,77 @@
+//! Fixtures command - scratch repositories for attribution scenarios
+//!
+//! Only built with the `dev-tools` feature. See [`crate::testing`].
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+
+use crate::testing::{self, Scenario};
+use crate::utils::SHORT_COMMIT_LEN;
+
+/// Fixtures command arguments
+#[derive(Debug, Args)]
+pub struct FixturesArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub action: FixturesAction,
+}
+
+/// Fixtures subcommands
+#[derive(Debug, Subcommand)]
+pub enum FixturesAction {
+    /// Create a repository for a scenario with real capture and post-commit analysis
+    Generate {
+        /// Scenario to build
+        #[arg(value_enum)]
+        scenario: Scenario,
+
+        /// Directory to create the repository in (must be missing or empty)
+        /// [default: ./whogitit-fixture-<scenario>]
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// List available scenarios
+    List,
+}
+
+/// Run the fixtures command
+pub fn run(args: FixturesArgs) -> Result<()> {
+    match args.action {
+        FixturesAction::Generate { scenario, dir } => {
+            let dir =
+                dir.unwrap_or_else(|| PathBuf::from(format!("whogitit-fixture-{}", scenario)));
+            let fixture = testing::generate(scenario, &dir)?;
+
+            println!(
+                "{} {} in {}",
+                "Generated".green(),
+                scenario.to_string().bold(),
+                fixture.dir.display()
+            );
+            println!("  {}", scenario.description().dimmed());
+            for oid in &fixture.commits {
+                let commit = fixture.repo.find_commit(*oid)?;
+                let noted = fixture.attribution(*oid)?.is_some();
+                println!(
+                    "  {} {}{}",
+                    oid.to_string()[..SHORT_COMMIT_LEN].yellow(),
+                    commit.summary().unwrap_or(""),
+                    if noted { "" } else { " (no note)" }.dimmed()
+                );
+            }
+        }
+        FixturesAction::List => {
+            for scenario in Scenario::ALL {
+                println!(
+                    "{:<18} {}",
+                    scenario.to_string().bold(),
+                    scenario.description()
+                );
+            }
+        }
+    }
+    Ok(())
+}
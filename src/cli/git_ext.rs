@@ -0,0 +1,272 @@
+//! Git-ext command - run whogitit as a `git` subcommand
+//!
+//! git runs any `git-<name>` executable on PATH for `git <name>`. The whogitit
+//! binary checks the name it was started as: `git-whogitit` behaves like
+//! `whogitit`, and `git-aiblame` like `whogitit blame`. `whogitit git-ext install`
+//! creates those links next to the whogitit executable (or in `--dir`); with
+//! `--alias` it adds equivalent aliases to the global git config instead.
+//! Either way `git -c whogitit.<key>=<value>` overrides reach the configuration
+//! (see [`crate::privacy::git_config`]).
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+
+/// Executable names recognized as shims, and the subcommand each one runs
+pub const SHIMS: &[(&str, Option<&str>)] =
+    &[("git-whogitit", None), ("git-aiblame", Some("blame"))];
+
+/// Git aliases written by `install --alias`
+///
+/// Shell aliases run from the top of the worktree; `GIT_PREFIX` brings them back
+/// to the directory git was started in, so relative paths keep working.
+const ALIASES: &[(&str, &str)] = &[
+    ("whogitit", "!cd \"${GIT_PREFIX:-.}\" && whogitit"),
+    ("aiblame", "!cd \"${GIT_PREFIX:-.}\" && whogitit blame"),
+];
+
+/// Git-ext command arguments
+#[derive(Debug, Args)]
+pub struct GitExtArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub action: GitExtAction,
+}
+
+/// Git-ext subcommands
+#[derive(Debug, Subcommand)]
+pub enum GitExtAction {
+    /// Make `git whogitit ...` and `git aiblame ...` available
+    Install {
+        /// Directory for the git-whogitit/git-aiblame links (default: next to this executable)
+        #[arg(long, conflicts_with = "alias")]
+        dir: Option<PathBuf>,
+
+        /// Add aliases to the global git config instead of creating links
+        #[arg(long)]
+        alias: bool,
+
+        /// Replace existing links or aliases with the same names
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove the links or aliases created by `install`
+    Uninstall {
+        /// Directory the links were created in (default: next to this executable)
+        #[arg(long, conflicts_with = "alias")]
+        dir: Option<PathBuf>,
+
+        /// Remove the global git aliases instead of links
+        #[arg(long)]
+        alias: bool,
+    },
+}
+
+/// Rewrite the arguments of a shim invocation into regular whogitit arguments
+///
+/// `git-aiblame src/main.rs` becomes `whogitit blame src/main.rs`; other names
+/// are returned unchanged.
+pub fn shim_args(mut args: Vec<OsString>) -> Vec<OsString> {
+    let Some(program) = args.first() else {
+        return args;
+    };
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("");
+    let subcommand = SHIMS
+        .iter()
+        .find(|(shim, _)| *shim == name)
+        .and_then(|(_, subcommand)| *subcommand);
+    if let Some(subcommand) = subcommand {
+        args.insert(1, OsString::from(subcommand));
+    }
+    args
+}
+
+/// Run the git-ext command
+pub fn run(args: GitExtArgs) -> Result<()> {
+    match args.action {
+        GitExtAction::Install {
+            alias: true, force, ..
+        } => install_aliases(force),
+        GitExtAction::Install { dir, force, .. } => {
+            let dir = link_dir(dir)?;
+            let exe =
+                std::env::current_exe().context("Failed to locate the whogitit executable")?;
+            for path in install_links(&exe, &dir, force)? {
+                println!("Linked {}", path.display());
+            }
+            if !dir_on_path(&dir) {
+                println!(
+                    "Note: {} is not on PATH; git will not find the links until it is.",
+                    dir.display()
+                );
+            }
+            println!("Run 'git whogitit --help' or 'git aiblame <file>' to try it.");
+            Ok(())
+        }
+        GitExtAction::Uninstall { alias: true, .. } => uninstall_aliases(),
+        GitExtAction::Uninstall { dir, .. } => {
+            let dir = link_dir(dir)?;
+            for (shim, _) in SHIMS {
+                let path = dir.join(shim_file_name(shim));
+                match fs::remove_file(&path) {
+                    Ok(()) => println!("Removed {}", path.display()),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => {
+                        return Err(e)
+                            .with_context(|| format!("Failed to remove {}", path.display()))
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn link_dir(dir: Option<PathBuf>) -> Result<PathBuf> {
+    match dir {
+        Some(dir) => Ok(dir),
+        None => std::env::current_exe()
+            .context("Failed to locate the whogitit executable")?
+            .parent()
+            .map(Path::to_path_buf)
+            .context("The whogitit executable has no parent directory"),
+    }
+}
+
+fn shim_file_name(shim: &str) -> String {
+    format!("{}{}", shim, std::env::consts::EXE_SUFFIX)
+}
+
+fn dir_on_path(dir: &Path) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|entry| entry == dir))
+        .unwrap_or(false)
+}
+
+/// Create a link to `exe` in `dir` for each shim; returns the created paths
+fn install_links(exe: &Path, dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut created = Vec::new();
+    for (shim, _) in SHIMS {
+        let path = dir.join(shim_file_name(shim));
+        if path.symlink_metadata().is_ok() {
+            if !force {
+                anyhow::bail!(
+                    "{} already exists (use --force to replace it)",
+                    path.display()
+                );
+            }
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        link(exe, &path).with_context(|| format!("Failed to create {}", path.display()))?;
+        created.push(path);
+    }
+    Ok(created)
+}
+
+#[cfg(unix)]
+fn link(exe: &Path, path: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(exe, path)
+}
+
+// Symlinks need extra privileges on Windows; a copy of the binary works the same
+#[cfg(not(unix))]
+fn link(exe: &Path, path: &Path) -> std::io::Result<()> {
+    fs::copy(exe, path).map(|_| ())
+}
+
+/// The global git config file, created on first write if it doesn't exist yet
+fn global_git_config() -> Result<git2::Config> {
+    let path = match git2::Config::find_global() {
+        Ok(path) => path,
+        Err(_) => dirs::home_dir()
+            .context("Could not determine the home directory")?
+            .join(".gitconfig"),
+    };
+    git2::Config::open(&path)
+        .with_context(|| format!("Failed to open the global git config {}", path.display()))
+}
+
+fn install_aliases(force: bool) -> Result<()> {
+    let mut config = global_git_config()?;
+    for (name, command) in ALIASES {
+        let key = format!("alias.{}", name);
+        if let Ok(existing) = config.get_string(&key) {
+            if existing != *command && !force {
+                anyhow::bail!(
+                    "git alias '{}' is already set to '{}' (use --force to replace it)",
+                    name,
+                    existing
+                );
+            }
+        }
+        config
+            .set_str(&key, command)
+            .with_context(|| format!("Failed to set {}", key))?;
+        println!("Added git alias '{}'", name);
+    }
+    Ok(())
+}
+
+fn uninstall_aliases() -> Result<()> {
+    let mut config = global_git_config()?;
+    for (name, command) in ALIASES {
+        let key = format!("alias.{}", name);
+        // Leave aliases the user has since pointed elsewhere
+        if config.get_string(&key).ok().as_deref() == Some(*command) {
+            config
+                .remove(&key)
+                .with_context(|| format!("Failed to remove {}", key))?;
+            println!("Removed git alias '{}'", name);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_shim_args() {
+        assert_eq!(
+            shim_args(args(&["/usr/local/bin/git-aiblame", "src/main.rs"])),
+            args(&["/usr/local/bin/git-aiblame", "blame", "src/main.rs"])
+        );
+        assert_eq!(
+            shim_args(args(&["git-whogitit", "show", "HEAD"])),
+            args(&["git-whogitit", "show", "HEAD"])
+        );
+        assert_eq!(
+            shim_args(args(&["whogitit", "summary"])),
+            args(&["whogitit", "summary"])
+        );
+    }
+
+    #[test]
+    fn test_install_links_refuses_to_overwrite_without_force() {
+        let dir = TempDir::new().unwrap();
+        let exe = dir.path().join("whogitit");
+        fs::write(&exe, "binary").unwrap();
+        let bin = dir.path().join("bin");
+
+        let created = install_links(&exe, &bin, false).unwrap();
+        assert_eq!(created.len(), SHIMS.len());
+        assert_eq!(fs::read(&created[1]).unwrap(), b"binary");
+
+        assert!(install_links(&exe, &bin, false).is_err());
+        assert_eq!(install_links(&exe, &bin, true).unwrap(), created);
+    }
+}
@@ -0,0 +1,296 @@
+//! Gutter command - per-file attribution sidecars for editor plugins
+//!
+//! Writes `.whogitit/<path>.attr.json` with one entry per line of the working
+//! copy: source, confidence and prompt index. Lines unchanged since HEAD come
+//! from blame; lines changed since HEAD come from the pending buffer, analyzed
+//! against the file on disk. Editor plugins read the sidecar directly instead of
+//! running blame, and `--watch` keeps it current while the AI edits.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use clap::Args;
+use git2::Repository;
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+use crate::capture::encoding;
+use crate::capture::pending::PendingStore;
+use crate::capture::snapshot::compute_hash;
+use crate::capture::threeway::ThreeWayAnalyzer;
+use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::core::blame::AIBlamer;
+use crate::core::calibration::source_kind;
+
+const GUTTER_MACHINE_SCHEMA: &str = "whogitit.gutter.v1";
+
+/// Directory (under the repo root) holding the sidecars
+const GUTTER_DIR: &str = ".whogitit";
+
+/// Wait for related filesystem events before regenerating
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Gutter command arguments
+#[derive(Debug, Args)]
+pub struct GutterArgs {
+    /// File to describe (repository-relative path)
+    #[arg(long)]
+    pub file: String,
+
+    /// Keep running and rewrite the sidecar when the file or the pending buffer changes
+    #[arg(long)]
+    pub watch: bool,
+}
+
+/// Sidecar contents for one file
+#[derive(Debug, Serialize)]
+pub struct GutterFile {
+    pub schema_version: u8,
+    pub schema: &'static str,
+    pub path: String,
+    /// HEAD commit the committed lines were blamed at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// Hash of the working-copy content described, to detect a stale sidecar
+    pub content_hash: String,
+    pub generated_at: String,
+    pub lines: Vec<GutterLine>,
+}
+
+/// Attribution of one working-copy line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GutterLine {
+    pub line: u32,
+    /// `ai`, `ai_modified`, `human`, `original` or `unknown`
+    pub source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Prompt index in the line's commit note, or in the pending buffer when uncommitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_index: Option<u32>,
+    /// Commit that last changed the line (committed lines only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Changed since HEAD
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub uncommitted: bool,
+}
+
+/// Run the gutter command
+pub fn run(args: GutterArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?
+        .to_path_buf();
+
+    let sidecar = write_sidecar(&repo, &repo_root, &args.file)?;
+    println!("Wrote {}", sidecar.display());
+
+    if args.watch {
+        watch(&repo, &repo_root, &args.file)?;
+    }
+    Ok(())
+}
+
+/// Sidecar path for a repository-relative file path
+pub fn sidecar_path(repo_root: &Path, path: &str) -> PathBuf {
+    repo_root
+        .join(GUTTER_DIR)
+        .join(format!("{}.attr.json", path))
+}
+
+fn write_sidecar(repo: &Repository, repo_root: &Path, path: &str) -> Result<PathBuf> {
+    let gutter = gutter_file(repo, repo_root, path)?;
+    let sidecar = sidecar_path(repo_root, path);
+    if let Some(parent) = sidecar.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    // Replace atomically so plugins never read a half-written file
+    let tmp = sidecar.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(&gutter)? + "\n")
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &sidecar)
+        .with_context(|| format!("Failed to write {}", sidecar.display()))?;
+    Ok(sidecar)
+}
+
+/// Attribute every line of the working copy of `path`
+pub fn gutter_file(repo: &Repository, repo_root: &Path, path: &str) -> Result<GutterFile> {
+    let bytes =
+        std::fs::read(repo_root.join(path)).with_context(|| format!("File not found: {}", path))?;
+    let content = encoding::decode(&bytes)
+        .with_context(|| format!("File appears to be binary: {}", path))?
+        .text;
+    let working: Vec<&str> = content.lines().collect();
+
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .map(|c| c.id().to_string());
+    let in_head = head.is_some()
+        && repo
+            .head()?
+            .peel_to_tree()?
+            .get_path(Path::new(path))
+            .is_ok();
+    let committed = if in_head {
+        AIBlamer::new(repo)?.blame(path, Some("HEAD"))?.lines
+    } else {
+        Vec::new()
+    };
+
+    let pending = PendingStore::new(repo_root)
+        .load_quiet()?
+        .and_then(|buffer| {
+            buffer
+                .get_file_history(path)
+                .map(|history| ThreeWayAnalyzer::analyze(history, &content))
+        });
+
+    let committed_text: Vec<&str> = committed.iter().map(|l| l.content.as_str()).collect();
+    let diff = TextDiff::from_slices(&committed_text, &working);
+    let mut lines = Vec::with_capacity(working.len());
+    for change in diff.iter_all_changes() {
+        let Some(new_index) = change.new_index() else {
+            continue;
+        };
+        let line = (new_index + 1) as u32;
+        let entry = match (change.tag(), change.old_index()) {
+            (ChangeTag::Equal, Some(old_index)) => {
+                let blamed = &committed[old_index];
+                GutterLine {
+                    line,
+                    source: source_kind(&blamed.source),
+                    confidence: blamed.confidence,
+                    prompt_index: blamed.prompt_index,
+                    commit: Some(blamed.commit_id.clone()),
+                    uncommitted: false,
+                }
+            }
+            _ => match pending.as_ref().and_then(|result| result.line(line)) {
+                Some(attribution) => GutterLine {
+                    line,
+                    source: source_kind(attribution.source),
+                    confidence: attribution.confidence,
+                    prompt_index: attribution.prompt_index,
+                    commit: None,
+                    uncommitted: true,
+                },
+                // Changed outside any captured AI edit
+                None => GutterLine {
+                    line,
+                    source: "human",
+                    confidence: None,
+                    prompt_index: None,
+                    commit: None,
+                    uncommitted: true,
+                },
+            },
+        };
+        lines.push(entry);
+    }
+
+    Ok(GutterFile {
+        schema_version: MACHINE_OUTPUT_SCHEMA_VERSION,
+        schema: GUTTER_MACHINE_SCHEMA,
+        path: path.to_string(),
+        head,
+        content_hash: compute_hash(&content),
+        generated_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        lines,
+    })
+}
+
+/// Rewrite the sidecar whenever the file or the pending buffer changes
+fn watch(repo: &Repository, repo_root: &Path, path: &str) -> Result<()> {
+    let file = repo_root.join(path);
+    let pending = PendingStore::new(repo_root).path().to_path_buf();
+    let watched: Vec<PathBuf> = [file.parent(), pending.parent()]
+        .into_iter()
+        .flatten()
+        .map(Path::to_path_buf)
+        .collect();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).context("Failed to start filesystem watcher")?;
+    for dir in &watched {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+    }
+    println!("Watching {} (Ctrl-C to stop)", path);
+
+    let relevant = |event: notify::Result<notify::Event>| match event {
+        Ok(event) if !event.kind.is_access() => {
+            event.paths.iter().any(|p| p == &file || p == &pending)
+        }
+        Ok(_) => false,
+        Err(e) => {
+            eprintln!("whogitit: Warning - watch error: {}", e);
+            false
+        }
+    };
+
+    loop {
+        let mut changed = relevant(rx.recv().context("Filesystem watcher stopped")?);
+        // Debounce: an editor save or a hook run emits a burst of events
+        while let Ok(event) = rx.recv_timeout(WATCH_DEBOUNCE) {
+            changed |= relevant(event);
+        }
+        if !changed {
+            continue;
+        }
+        match write_sidecar(repo, repo_root, path) {
+            Ok(sidecar) => println!("Updated {}", sidecar.display()),
+            Err(e) => eprintln!("whogitit: Warning - failed to update gutter data: {:#}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{generate, Scenario};
+
+    #[test]
+    fn test_gutter_marks_committed_and_uncommitted_lines() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let fixture = generate(Scenario::AiNewFile, dir.path()).unwrap();
+        let path = "src/generated.rs";
+        let file = dir.path().join(path);
+        let committed = std::fs::read_to_string(&file).unwrap();
+        std::fs::write(&file, format!("// header\n{}", committed)).unwrap();
+
+        let gutter = gutter_file(&fixture.repo, dir.path(), path).unwrap();
+        assert_eq!(gutter.schema, GUTTER_MACHINE_SCHEMA);
+        assert_eq!(gutter.head, Some(fixture.head().to_string()));
+        assert_eq!(gutter.lines.len(), committed.lines().count() + 1);
+
+        let header = &gutter.lines[0];
+        assert_eq!((header.source, header.uncommitted), ("human", true));
+        assert!(header.commit.is_none());
+
+        let first_ai = &gutter.lines[1];
+        assert_eq!(first_ai.line, 2);
+        assert_eq!(first_ai.source, "ai");
+        assert!(!first_ai.uncommitted);
+        assert_eq!(first_ai.commit, Some(fixture.head().to_string()));
+        assert!(first_ai.confidence.is_some());
+    }
+
+    #[test]
+    fn test_sidecar_path_mirrors_repo_path() {
+        assert_eq!(
+            sidecar_path(Path::new("/repo"), "src/lib.rs"),
+            Path::new("/repo/.whogitit/src/lib.rs.attr.json")
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Localized strings and number formatting for `show` and `summary`
+//!
+//! `--locale` (or `WHOGITIT_LOCALE`) selects the language of headings and labels in
+//! pretty and Markdown output, and how counts and percentages are written. Without
+//! either, output is the untranslated, ungrouped `C` form. JSON output never changes.
+
+use std::fmt::Display;
+
+use anyhow::{bail, Result};
+
+/// Environment variable selecting the output locale
+pub const ENV_LOCALE: &str = "WHOGITIT_LOCALE";
+
+/// Output locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// English strings, no digit grouping (`1234`, `12.5%`)
+    #[default]
+    C,
+    /// English (`1,234`, `12.5%`)
+    En,
+    /// German (`1.234`, `12,5 %`)
+    De,
+    /// French (`1 234`, `12,5 %`)
+    Fr,
+    /// Spanish (`1.234`, `12,5 %`)
+    Es,
+}
+
+impl Locale {
+    /// Parse a locale tag: `de`, `de-DE`, `de_DE.UTF-8`, `C` or `POSIX`
+    pub fn parse(tag: &str) -> Result<Self> {
+        let language = tag
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Ok(match language.as_str() {
+            "c" | "posix" => Self::C,
+            "en" => Self::En,
+            "de" => Self::De,
+            "fr" => Self::Fr,
+            "es" => Self::Es,
+            _ => bail!(
+                "Unsupported locale '{}'. Supported locales: en, de, fr, es (or C).",
+                tag
+            ),
+        })
+    }
+
+    /// `--locale` if given, else `WHOGITIT_LOCALE`, else `C`
+    pub fn resolve(flag: Option<&str>) -> Result<Self> {
+        match flag {
+            Some(tag) => Self::parse(tag),
+            None => match std::env::var(ENV_LOCALE) {
+                Ok(tag) if !tag.trim().is_empty() => Self::parse(tag.trim()),
+                _ => Ok(Self::C),
+            },
+        }
+    }
+
+    /// Thousands separator and decimal mark
+    fn separators(self) -> (Option<&'static str>, char) {
+        match self {
+            Self::C => (None, '.'),
+            Self::En => (Some(","), '.'),
+            Self::De | Self::Es => (Some("."), ','),
+            Self::Fr => (Some("\u{202f}"), ','),
+        }
+    }
+
+    /// A count with digit grouping, e.g. `12,345`
+    pub fn number(self, n: usize) -> String {
+        let digits = n.to_string();
+        let Some(separator) = self.separators().0 else {
+            return digits;
+        };
+        let mut out = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                out.push_str(separator);
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    /// A percentage with `decimals` places, e.g. `12.5%` or `12,5 %`
+    pub fn percent(self, value: f64, decimals: usize) -> String {
+        let (_, decimal_mark) = self.separators();
+        let rounded = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = match rounded.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (rounded.as_str(), None),
+        };
+        let mut out = String::new();
+        if value < 0.0 && rounded.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            out.push('-');
+        }
+        out.push_str(&self.number(whole.parse().unwrap_or_default()));
+        if let Some(fraction) = fraction {
+            out.push(decimal_mark);
+            out.push_str(fraction);
+        }
+        match self {
+            Self::C | Self::En => out.push('%'),
+            Self::De | Self::Fr | Self::Es => out.push_str("\u{a0}%"),
+        }
+        out
+    }
+
+    /// Translated text of a message
+    pub fn text(self, msg: Msg) -> &'static str {
+        let [en, de, fr, es] = msg.catalog();
+        match self {
+            Self::C | Self::En => en,
+            Self::De => de,
+            Self::Fr => fr,
+            Self::Es => es,
+        }
+    }
+
+    /// Translated message with each `{}` replaced by the next argument
+    pub fn fill(self, msg: Msg, args: &[&dyn Display]) -> String {
+        let mut args = args.iter();
+        let mut parts = self.text(msg).split("{}");
+        let mut out = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+            out.push_str(part);
+        }
+        out
+    }
+}
+
+/// User-facing strings of `show` and `summary`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    Commit,
+    Session,
+    Label,
+    Model,
+    Started,
+    PromptsUsed,
+    FilesWithAiChanges,
+    FileAi,
+    FileModified,
+    FileHuman,
+    FileOriginal,
+    TotalLines,
+    Summary,
+    AiGeneratedLines,
+    AiModifiedLines,
+    HumanAddedLines,
+    OriginalLines,
+    SummaryTitle,
+    CommitsAnalyzed,
+    FromTrailers,
+    GeneratedExcluded,
+    NoAiData,
+    LinesAdded,
+    AiGenerated,
+    AiModifiedByHuman,
+    HumanWritten,
+    TotalAdditions,
+    AiInvolvement,
+    AdditionsAreAi,
+    FilesChanged,
+    NewFile,
+    ModelsUsed,
+    ModelsUsedHeading,
+    Sessions,
+    PrAdds,
+    TrailerOnly,
+    AdditionsBreakdown,
+    Metric,
+    Lines,
+    PercentOfAdditions,
+    MarkdownAiInvolvement,
+    File,
+    Added,
+    Ai,
+    Human,
+    AiPercent,
+    Status,
+    New,
+    Modified,
+    Binary,
+    BinaryCell,
+}
+
+impl Msg {
+    /// English, German, French and Spanish text
+    fn catalog(self) -> [&'static str; 4] {
+        match self {
+            Self::Commit => ["Commit", "Commit", "Commit", "Commit"],
+            Self::Session => ["Session", "Sitzung", "Session", "Sesión"],
+            Self::Label => ["Label", "Label", "Libellé", "Etiqueta"],
+            Self::Model => ["Model", "Modell", "Modèle", "Modelo"],
+            Self::Started => ["Started", "Gestartet", "Démarrée", "Iniciada"],
+            Self::PromptsUsed => [
+                "Prompts used:",
+                "Verwendete Prompts:",
+                "Prompts utilisés:",
+                "Prompts usados:",
+            ],
+            Self::FilesWithAiChanges => [
+                "Files with AI changes:",
+                "Dateien mit KI-Änderungen:",
+                "Fichiers modifiés par l'IA:",
+                "Archivos con cambios de IA:",
+            ],
+            Self::FileAi => ["{} AI", "{} KI", "{} IA", "{} IA"],
+            Self::FileModified => ["{} modified", "{} geändert", "{} modifiées", "{} modificadas"],
+            Self::FileHuman => ["{} human", "{} menschlich", "{} humaines", "{} humanas"],
+            Self::FileOriginal => ["{} original", "{} original", "{} d'origine", "{} originales"],
+            Self::TotalLines => [
+                "{} total lines",
+                "{} Zeilen insgesamt",
+                "{} lignes au total",
+                "{} líneas en total",
+            ],
+            Self::Summary => ["Summary:", "Zusammenfassung:", "Résumé:", "Resumen:"],
+            Self::AiGeneratedLines => [
+                "{} AI-generated lines",
+                "{} KI-generierte Zeilen",
+                "{} lignes générées par l'IA",
+                "{} líneas generadas por IA",
+            ],
+            Self::AiModifiedLines => [
+                "{} AI lines modified by human",
+                "{} KI-Zeilen von Menschen geändert",
+                "{} lignes IA modifiées par un humain",
+                "{} líneas de IA modificadas por una persona",
+            ],
+            Self::HumanAddedLines => [
+                "{} human-added lines",
+                "{} von Menschen hinzugefügte Zeilen",
+                "{} lignes ajoutées par un humain",
+                "{} líneas añadidas por una persona",
+            ],
+            Self::OriginalLines => [
+                "{} original/unchanged lines",
+                "{} ursprüngliche/unveränderte Zeilen",
+                "{} lignes d'origine/inchangées",
+                "{} líneas originales/sin cambios",
+            ],
+            Self::SummaryTitle => [
+                "AI Attribution Summary",
+                "KI-Attributionsübersicht",
+                "Synthèse de l'attribution IA",
+                "Resumen de atribución de IA",
+            ],
+            Self::CommitsAnalyzed => [
+                "Commits analyzed: {} ({} with AI attribution)",
+                "Analysierte Commits: {} ({} mit KI-Attribution)",
+                "Commits analysés: {} ({} avec attribution IA)",
+                "Commits analizados: {} ({} con atribución de IA)",
+            ],
+            Self::FromTrailers => [
+                "{} from commit trailers (line counts only, not in the file breakdown)",
+                "{} aus Commit-Trailern (nur Zeilenzahlen, nicht in der Dateiübersicht)",
+                "{} issus des trailers de commit (nombres de lignes seulement, absents du détail par fichier)",
+                "{} de trailers de commit (solo recuentos de líneas, no en el desglose por archivo)",
+            ],
+            Self::GeneratedExcluded => [
+                "{} line(s) in {} vendored or generated file(s) not counted (--include-generated counts them)",
+                "{} Zeile(n) in {} vendorisierten oder generierten Datei(en) nicht gezählt (--include-generated zählt sie mit)",
+                "{} ligne(s) dans {} fichier(s) vendorisé(s) ou généré(s) non comptée(s) (--include-generated les compte)",
+                "{} línea(s) en {} archivo(s) vendorizado(s) o generado(s) sin contar (--include-generated las cuenta)",
+            ],
+            Self::NoAiData => [
+                "No AI attribution data found in the specified commit range.",
+                "Keine KI-Attributionsdaten im angegebenen Commit-Bereich gefunden.",
+                "Aucune donnée d'attribution IA dans la plage de commits indiquée.",
+                "No se encontraron datos de atribución de IA en el rango de commits indicado.",
+            ],
+            Self::LinesAdded => [
+                "Lines Added:",
+                "Hinzugefügte Zeilen:",
+                "Lignes ajoutées:",
+                "Líneas añadidas:",
+            ],
+            Self::AiGenerated => [
+                "AI-generated",
+                "KI-generiert",
+                "Générées par l'IA",
+                "Generadas por IA",
+            ],
+            Self::AiModifiedByHuman => [
+                "AI-modified by human",
+                "KI, von Menschen geändert",
+                "IA modifiées par un humain",
+                "IA modificadas por una persona",
+            ],
+            Self::HumanWritten => [
+                "Human-written",
+                "Von Menschen geschrieben",
+                "Écrites par un humain",
+                "Escritas por una persona",
+            ],
+            Self::TotalAdditions => [
+                "Total additions",
+                "Hinzufügungen gesamt",
+                "Total des ajouts",
+                "Total de adiciones",
+            ],
+            Self::AiInvolvement => [
+                "AI involvement",
+                "KI-Anteil",
+                "Part de l'IA",
+                "Participación de IA",
+            ],
+            Self::AdditionsAreAi => [
+                "{} of additions are AI-generated",
+                "{} der Hinzufügungen sind KI-generiert",
+                "{} des ajouts sont générés par l'IA",
+                "{} de las adiciones son generadas por IA",
+            ],
+            Self::FilesChanged => [
+                "Files Changed",
+                "Geänderte Dateien",
+                "Fichiers modifiés",
+                "Archivos modificados",
+            ],
+            Self::NewFile => ["new", "neu", "nouveau", "nuevo"],
+            Self::ModelsUsed => [
+                "Models used:",
+                "Verwendete Modelle:",
+                "Modèles utilisés:",
+                "Modelos usados:",
+            ],
+            Self::ModelsUsedHeading => [
+                "Models Used",
+                "Verwendete Modelle",
+                "Modèles utilisés",
+                "Modelos usados",
+            ],
+            Self::Sessions => ["Sessions", "Sitzungen", "Sessions", "Sesiones"],
+            Self::PrAdds => [
+                "This PR adds **+{}** lines with AI attribution across **{}** files.",
+                "Dieser PR fügt **+{}** Zeilen mit KI-Attribution in **{}** Dateien hinzu.",
+                "Cette PR ajoute **+{}** lignes avec attribution IA dans **{}** fichiers.",
+                "Este PR añade **+{}** líneas con atribución de IA en **{}** archivos.",
+            ],
+            Self::TrailerOnly => [
+                "_{} commit(s) only carry attribution trailers; their lines are counted in the totals but not in the file breakdown._",
+                "_{} Commit(s) haben nur Attributions-Trailer; ihre Zeilen zählen zu den Summen, aber nicht zur Dateiübersicht._",
+                "_{} commit(s) ne portent que des trailers d'attribution; leurs lignes comptent dans les totaux mais pas dans le détail par fichier._",
+                "_{} commit(s) solo llevan trailers de atribución; sus líneas cuentan en los totales pero no en el desglose por archivo._",
+            ],
+            Self::AdditionsBreakdown => [
+                "Additions Breakdown",
+                "Aufschlüsselung der Hinzufügungen",
+                "Détail des ajouts",
+                "Desglose de adiciones",
+            ],
+            Self::Metric => ["Metric", "Kennzahl", "Mesure", "Métrica"],
+            Self::Lines => ["Lines", "Zeilen", "Lignes", "Líneas"],
+            Self::PercentOfAdditions => [
+                "% of Additions",
+                "% der Hinzufügungen",
+                "% des ajouts",
+                "% de adiciones",
+            ],
+            Self::MarkdownAiInvolvement => [
+                "**AI involvement: {}** of additions are AI-generated",
+                "**KI-Anteil: {}** der Hinzufügungen sind KI-generiert",
+                "**Part de l'IA: {}** des ajouts sont générés par l'IA",
+                "**Participación de IA: {}** de las adiciones son generadas por IA",
+            ],
+            Self::File => ["File", "Datei", "Fichier", "Archivo"],
+            Self::Added => ["+Added", "+Hinzugefügt", "+Ajoutées", "+Añadidas"],
+            Self::Ai => ["AI", "KI", "IA", "IA"],
+            Self::Human => ["Human", "Mensch", "Humain", "Humano"],
+            Self::AiPercent => ["AI %", "KI %", "IA %", "IA %"],
+            Self::Status => ["Status", "Status", "Statut", "Estado"],
+            Self::New => ["New", "Neu", "Nouveau", "Nuevo"],
+            Self::Modified => ["Modified", "Geändert", "Modifié", "Modificado"],
+            Self::Binary => ["Binary", "Binär", "Binaire", "Binario"],
+            Self::BinaryCell => ["binary", "binär", "binaire", "binario"],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parse_and_resolve() {
+        assert_eq!(Locale::parse("de_DE.UTF-8").unwrap(), Locale::De);
+        assert_eq!(Locale::parse("fr-CA").unwrap(), Locale::Fr);
+        assert_eq!(Locale::parse("EN").unwrap(), Locale::En);
+        assert_eq!(Locale::parse("POSIX").unwrap(), Locale::C);
+        assert!(Locale::parse("tlh").is_err());
+        assert_eq!(Locale::resolve(Some("es")).unwrap(), Locale::Es);
+    }
+
+    #[test]
+    fn test_number_and_percent_formatting() {
+        assert_eq!(Locale::C.number(1234567), "1234567");
+        assert_eq!(Locale::En.number(1234567), "1,234,567");
+        assert_eq!(Locale::De.number(1234), "1.234");
+        assert_eq!(Locale::Fr.number(1234), "1\u{202f}234");
+        assert_eq!(Locale::En.number(999), "999");
+
+        assert_eq!(Locale::C.percent(12.34, 1), "12.3%");
+        assert_eq!(Locale::De.percent(12.34, 1), "12,3\u{a0}%");
+        assert_eq!(Locale::En.percent(1234.5, 0), "1,234%");
+        assert_eq!(Locale::Es.percent(100.0, 0), "100\u{a0}%");
+    }
+
+    #[test]
+    fn test_fill_translates_and_substitutes() {
+        assert_eq!(
+            Locale::C.fill(Msg::CommitsAnalyzed, &[&3, &2]),
+            "Commits analyzed: 3 (2 with AI attribution)"
+        );
+        assert_eq!(
+            Locale::De.fill(Msg::TotalLines, &[&Locale::De.number(1200)]),
+            "1.200 Zeilen insgesamt"
+        );
+        assert_eq!(Locale::Fr.text(Msg::Ai), "IA");
+    }
+}
@@ -0,0 +1,112 @@
+//! Import command - restore a pending buffer archived as stale
+//!
+//! With `analysis.stale_action = "archive"`, capture and post-commit move a stale
+//! pending buffer to `.git/whogitit/archive/<timestamp>.json` instead of using it.
+//! This command merges such a buffer back into the pending buffer, so its edits
+//! are attributed by the next commit.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+
+use crate::capture::pending::{PendingBuffer, PendingStore};
+use crate::storage::{LockHolderKind, RepoLock};
+
+/// Import command arguments
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// Archived buffer to restore (default: the newest archive)
+    #[arg(value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
+    /// List archived buffers instead of importing
+    #[arg(long, conflicts_with = "file")]
+    pub list: bool,
+
+    /// Keep the archive file after importing it
+    #[arg(long)]
+    pub keep: bool,
+}
+
+/// Run the import command
+pub fn run(args: ImportArgs) -> Result<()> {
+    let repo = git2::Repository::discover(".").context("Not in a git repository")?;
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+    let store = PendingStore::new(repo_root);
+    let archives = PendingStore::archived_buffers(repo.path());
+
+    if args.list {
+        if archives.is_empty() {
+            println!("No archived pending buffers.");
+        }
+        for path in &archives {
+            match read_archive(path) {
+                Ok(buffer) => println!(
+                    "{}  {} edit(s) in {} file(s), session {}",
+                    path.display(),
+                    buffer.total_edits(),
+                    buffer.file_count(),
+                    buffer.session.session_id
+                ),
+                Err(e) => println!(
+                    "{}  {}",
+                    path.display(),
+                    format!("({})", e.root_cause()).red()
+                ),
+            }
+        }
+        return Ok(());
+    }
+
+    let _lock = RepoLock::acquire_default(repo.path(), LockHolderKind::Import)?;
+    let source = match args.file {
+        Some(path) => path,
+        None => archives
+            .last()
+            .cloned()
+            .context("No archived pending buffers to import")?,
+    };
+    let archived = read_archive(&source)?;
+    let file_count = archived.file_count();
+
+    let buffer = match store.load_quiet()? {
+        // Edits captured since the archive was made are newer
+        Some(mut current) => {
+            current.absorb(archived);
+            current
+        }
+        None if store.exists() => {
+            anyhow::bail!(
+                "The pending buffer is corrupted; run 'whogitit recover' before importing"
+            )
+        }
+        None => archived,
+    };
+    store.save(&buffer)?;
+
+    if !args.keep {
+        fs::remove_file(&source)
+            .with_context(|| format!("Failed to remove {}", source.display()))?;
+    }
+
+    println!(
+        "{} {} file(s) from {}; {} file(s) now pending. Run 'git commit' to finalize attribution.",
+        "Imported".green().bold(),
+        file_count,
+        source.display(),
+        buffer.file_count()
+    );
+    Ok(())
+}
+
+fn read_archive(path: &std::path::Path) -> Result<PendingBuffer> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a pending buffer", path.display()))
+}
@@ -0,0 +1,330 @@
+//! Import-aider command - attribute commits made by Aider
+//!
+//! Aider edits files and commits them itself, so its changes never pass through
+//! the capture hooks. This command recovers the edits afterwards: every commit the
+//! chat history (`.aider.chat.history.md`) ties to a prompt, and every commit
+//! carrying Aider's author marker or `Co-authored-by` trailer, gets a note built
+//! the same way as for captured edits. Commits that already have a note are left
+//! alone, so the command can be re-run after each Aider session.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::{Commit, Oid, Repository};
+
+use crate::capture::aider::{self, AIDER_PROVIDER, CHAT_HISTORY_FILE};
+use crate::capture::encoding;
+use crate::capture::hook::CaptureHook;
+use crate::capture::pending::PendingBuffer;
+use crate::core::attribution::ModelInfo;
+use crate::storage::notes::NotesStore;
+use crate::utils::{repo_path, truncate_prompt, PROMPT_PREVIEW_LEN, SHORT_COMMIT_LEN};
+
+/// Tool name recorded for imported edits
+const AIDER_TOOL: &str = "AiderEdit";
+
+/// Import-aider command arguments
+#[derive(Debug, Args)]
+pub struct ImportAiderArgs {
+    /// Aider chat history (default: .aider.chat.history.md at the repository root)
+    #[arg(long, value_name = "FILE")]
+    pub history: Option<PathBuf>,
+
+    /// Number of commits from HEAD to scan for Aider trailers
+    #[arg(long, default_value = "500")]
+    pub max_commits: usize,
+
+    /// Show which commits would be attributed without writing notes
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// A commit to attribute to Aider
+#[derive(Debug)]
+struct AiderCommit {
+    oid: Oid,
+    prompt: String,
+    model: Option<String>,
+    /// Files Aider reported editing; `None` attributes every changed file
+    files: Option<Vec<String>>,
+}
+
+/// Run the import-aider command
+pub fn run(args: ImportAiderArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?
+        .to_path_buf();
+
+    let history = match &args.history {
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+        ),
+        None => std::fs::read_to_string(repo_root.join(CHAT_HISTORY_FILE)).ok(),
+    };
+    let commits = find_aider_commits(&repo, history.as_deref(), args.max_commits)?;
+
+    let store = NotesStore::new(&repo)?;
+    let hook = CaptureHook::new(&repo_root)?;
+    let mut imported = 0;
+    let mut skipped = 0;
+    for commit in commits {
+        let short = &commit.oid.to_string()[..SHORT_COMMIT_LEN];
+        if store.has_attribution(commit.oid) {
+            skipped += 1;
+            continue;
+        }
+
+        let buffer = build_buffer(&repo, &commit, &hook)?;
+        if buffer.file_count() == 0 {
+            continue;
+        }
+        if args.dry_run {
+            println!(
+                "Would attribute {} ({} file(s)): {}",
+                short,
+                buffer.file_count(),
+                truncate_prompt(&commit.prompt, PROMPT_PREVIEW_LEN)
+            );
+            continue;
+        }
+
+        match hook.import_commit(commit.oid, buffer)? {
+            Some(attribution) => {
+                imported += 1;
+                println!(
+                    "{} {} ({} file(s))",
+                    "Attributed".green().bold(),
+                    short,
+                    attribution.files.len()
+                );
+            }
+            None => println!("No AI lines left in {}", short),
+        }
+    }
+
+    if !args.dry_run {
+        println!(
+            "Imported {} Aider commit(s); {} already attributed.",
+            imported, skipped
+        );
+    }
+    Ok(())
+}
+
+/// Commits tied to a prompt by the chat history, then those recognized by their markers
+fn find_aider_commits(
+    repo: &Repository,
+    history: Option<&str>,
+    max_commits: usize,
+) -> Result<Vec<AiderCommit>> {
+    let mut commits = Vec::new();
+    let mut seen = HashSet::new();
+
+    for turn in history.map(aider::parse_chat_history).unwrap_or_default() {
+        for hash in &turn.commits {
+            // Rewritten or garbage-collected commits no longer resolve
+            let Some(oid) = repo
+                .revparse_single(hash)
+                .and_then(|object| object.peel_to_commit())
+                .ok()
+                .map(|commit| commit.id())
+            else {
+                continue;
+            };
+            if seen.insert(oid) {
+                commits.push(AiderCommit {
+                    oid,
+                    prompt: turn.prompt.clone(),
+                    model: turn.model.clone(),
+                    files: Some(turn.files.clone()),
+                });
+            }
+        }
+    }
+
+    let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) else {
+        return Ok(commits);
+    };
+    let mut walk = repo.revwalk()?;
+    walk.push(head.id())?;
+    for oid in walk.take(max_commits) {
+        let oid = oid?;
+        if seen.contains(&oid) {
+            continue;
+        }
+        let commit = repo.find_commit(oid)?;
+        let marker = aider::commit_marker(
+            &String::from_utf8_lossy(commit.message_bytes()),
+            &String::from_utf8_lossy(commit.author().name_bytes()),
+            &String::from_utf8_lossy(commit.committer().name_bytes()),
+        );
+        if let Some(model) = marker {
+            seen.insert(oid);
+            commits.push(AiderCommit {
+                oid,
+                prompt: format!("Aider commit: {}", subject(&commit)),
+                model,
+                files: None,
+            });
+        }
+    }
+    Ok(commits)
+}
+
+/// Pending buffer holding a commit's changes as Aider edits
+fn build_buffer(
+    repo: &Repository,
+    commit: &AiderCommit,
+    hook: &CaptureHook,
+) -> Result<PendingBuffer> {
+    let model = commit.model.as_deref().unwrap_or(AIDER_PROVIDER);
+    let mut buffer = PendingBuffer::new_with_model(
+        &uuid::Uuid::new_v4().to_string(),
+        ModelInfo::new(model, AIDER_PROVIDER),
+    );
+
+    let git_commit = repo.find_commit(commit.oid)?;
+    let tree = git_commit.tree()?;
+    let parent_tree = git_commit.parents().next().map(|p| p.tree()).transpose()?;
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+    for delta in diff.deltas() {
+        // Deletions leave no lines to attribute
+        let Some(path_bytes) = delta.new_file().path_bytes() else {
+            continue;
+        };
+        if delta.new_file().id().is_zero() {
+            continue;
+        }
+        let path = repo_path::encode(path_bytes);
+        if let Some(files) = &commit.files {
+            if !files.contains(&path) {
+                continue;
+            }
+        }
+
+        let Some(new_content) = blob_text(repo, delta.new_file().id()) else {
+            continue;
+        };
+        let old_content = if delta.old_file().id().is_zero() {
+            None
+        } else {
+            match blob_text(repo, delta.old_file().id()) {
+                Some(text) => Some(text),
+                None => continue,
+            }
+        };
+        buffer.record_edit(
+            &path,
+            old_content.as_deref(),
+            &new_content,
+            AIDER_TOOL,
+            &commit.prompt,
+            Some(hook.redactor()),
+        );
+    }
+    Ok(buffer)
+}
+
+/// Text of a blob, or `None` if it is binary
+fn blob_text(repo: &Repository, oid: Oid) -> Option<String> {
+    let blob = repo.find_blob(oid).ok()?;
+    encoding::decode(blob.content()).map(|decoded| decoded.text)
+}
+
+/// Subject line of a commit, for prompts of commits without a history entry
+fn subject(commit: &Commit) -> String {
+    String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default()).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn commit_file(
+        repo: &Repository,
+        path: &str,
+        content: &str,
+        author: &str,
+        message: &str,
+    ) -> Oid {
+        let root = repo.workdir().unwrap();
+        std::fs::write(root.join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now(author, "dev@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_aider_commits_from_history_and_trailers() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.rs", "fn a() {}\n", "Dev", "Initial");
+        let from_history = commit_file(&repo, "a.rs", "fn a() { b() }\n", "Dev", "feat: call b");
+        let from_trailer = commit_file(
+            &repo,
+            "b.rs",
+            "fn b() {}\n",
+            "Dev",
+            "feat: add b\n\nCo-authored-by: aider (gpt-4o) <noreply@aider.chat>\n",
+        );
+
+        let history = format!(
+            "# aider chat started at 2026-03-02 14:05:11\n\n> Main model: gpt-4o with diff edit format\n\n#### Call b from a\n\n> Applied edit to a.rs\n> Commit {} feat: call b\n",
+            &from_history.to_string()[..7]
+        );
+        let commits = find_aider_commits(&repo, Some(&history), 100).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].oid, from_history);
+        assert_eq!(commits[0].prompt, "Call b from a");
+        assert_eq!(commits[0].files.as_deref(), Some(&["a.rs".to_string()][..]));
+        assert_eq!(commits[1].oid, from_trailer);
+        assert_eq!(commits[1].prompt, "Aider commit: feat: add b");
+        assert_eq!(commits[1].model.as_deref(), Some("gpt-4o"));
+        assert!(commits[1].files.is_none());
+    }
+
+    #[test]
+    fn test_import_commit_attaches_aider_note() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "a.rs", "fn a() {}\n", "Dev", "Initial");
+        let oid = commit_file(
+            &repo,
+            "a.rs",
+            "fn a() {}\n\nfn b() {}\n",
+            "Dev (aider)",
+            "feat: add b",
+        );
+
+        let commits = find_aider_commits(&repo, None, 100).unwrap();
+        assert_eq!(commits.len(), 1);
+        let hook = CaptureHook::new(dir.path()).unwrap();
+        let buffer = build_buffer(&repo, &commits[0], &hook).unwrap();
+        hook.import_commit(oid, buffer).unwrap().unwrap();
+
+        let attribution = NotesStore::new(&repo)
+            .unwrap()
+            .fetch_attribution(oid)
+            .unwrap()
+            .unwrap();
+        assert_eq!(attribution.session.model.provider, AIDER_PROVIDER);
+        assert_eq!(attribution.prompts[0].text, "Aider commit: feat: add b");
+        assert_eq!(attribution.total_ai_lines(), 2);
+    }
+}
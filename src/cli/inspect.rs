@@ -0,0 +1,340 @@
+//! Inspect command - audit a pull request's AI attribution without setting up whogitit
+//!
+//! Fetches the PR head, the base it is compared against and the remote's notes into
+//! temporary refs, prints the summary (and blame for `--blame` files) from them, and
+//! deletes the refs again. Nothing is checked out, no hooks or configuration are
+//! needed, and the local notes ref is never touched, so any clone will do.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::Repository;
+
+use crate::cli::blame::{self, BlameArgs};
+use crate::cli::output::OutputFormat;
+use crate::cli::summary::{self, SummaryArgs, SummaryFormat};
+use crate::core::blame::AIBlamer;
+use crate::privacy::StorageConfig;
+use crate::storage::notes::{NotesStore, NOTES_NAMESPACE_PREFIX, NOTES_REF};
+use crate::storage::notes_sync::{self, git};
+use crate::utils::SHORT_COMMIT_LEN;
+
+/// Prefix of the refs holding fetched commits during an inspection
+const INSPECT_REF_PREFIX: &str = "refs/whogitit/inspect/";
+
+/// Inspect command arguments
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    /// Remote to fetch from (default: origin, or the only remote)
+    #[arg(long)]
+    pub remote: Option<String>,
+
+    /// Pull request number (fetches refs/pull/<N>/head)
+    #[arg(long, required_unless_present = "git_ref", conflicts_with = "git_ref")]
+    pub pr: Option<u64>,
+
+    /// Remote ref to inspect, e.g. refs/merge-requests/7/head
+    #[arg(long = "ref", value_name = "REF")]
+    pub git_ref: Option<String>,
+
+    /// Remote ref the changes are compared against (default: the remote's HEAD)
+    #[arg(long, default_value = "HEAD")]
+    pub base: String,
+
+    /// Commits of history to fetch from each ref (0 fetches the full history)
+    #[arg(long, default_value = "50")]
+    pub depth: u32,
+
+    /// Also show AI-aware blame of this file at the inspected head (repeatable)
+    #[arg(long, value_name = "FILE")]
+    pub blame: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = SummaryFormat::Pretty)]
+    pub format: SummaryFormat,
+}
+
+impl InspectArgs {
+    /// Remote ref holding the changes to inspect
+    fn head_ref(&self) -> String {
+        match (&self.git_ref, self.pr) {
+            (Some(git_ref), _) => git_ref.clone(),
+            (None, Some(pr)) => format!("refs/pull/{}/head", pr),
+            (None, None) => "HEAD".to_string(),
+        }
+    }
+}
+
+/// Temporary refs of one inspection, removed on drop
+///
+/// A `--depth` fetch records its boundary commits in `.git/shallow`, which would
+/// make a full clone shallow; the file is put back as it was.
+struct Inspection {
+    git_dir: PathBuf,
+    id: String,
+    shallow: Option<Vec<u8>>,
+}
+
+impl Inspection {
+    fn begin(git_dir: &Path) -> Self {
+        Self {
+            git_dir: git_dir.to_path_buf(),
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            shallow: fs::read(git_dir.join("shallow")).ok(),
+        }
+    }
+
+    fn head_ref(&self) -> String {
+        format!("{}{}/head", INSPECT_REF_PREFIX, self.id)
+    }
+
+    fn base_ref(&self) -> String {
+        format!("{}{}/base", INSPECT_REF_PREFIX, self.id)
+    }
+
+    /// Notes namespace the remote's notes are fetched into
+    fn namespace(&self) -> String {
+        format!("inspect-{}", self.id)
+    }
+
+    fn notes_ref(&self) -> String {
+        format!("{}{}", NOTES_NAMESPACE_PREFIX, self.namespace())
+    }
+
+    /// Fetch refspecs into the inspection's refs
+    ///
+    /// `--refmap=` keeps git from also updating remote-tracking branches.
+    fn fetch(&self, remote: &str, refspecs: &[String], depth: u32) -> Result<()> {
+        let mut cmd = git(&self.git_dir);
+        cmd.args([
+            "fetch",
+            "--quiet",
+            "--no-tags",
+            "--no-write-fetch-head",
+            "--refmap=",
+        ]);
+        if depth > 0 {
+            cmd.arg(format!("--depth={}", depth));
+        }
+        let output = cmd
+            .arg(remote)
+            .args(refspecs)
+            .output()
+            .context("Failed to run git fetch")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git fetch {} {} failed: {}",
+                remote,
+                refspecs.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Inspection {
+    fn drop(&mut self) {
+        if let Ok(repo) = Repository::open(&self.git_dir) {
+            for name in [self.head_ref(), self.base_ref(), self.notes_ref()] {
+                if let Ok(mut reference) = repo.find_reference(&name) {
+                    let _ = reference.delete();
+                }
+            }
+        }
+        let shallow = self.git_dir.join("shallow");
+        let _ = match &self.shallow {
+            Some(content) => fs::write(shallow, content),
+            None => fs::remove_file(shallow),
+        };
+    }
+}
+
+/// Run the inspect command
+pub fn run(args: InspectArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let remote = match &args.remote {
+        Some(remote) => remote.clone(),
+        None => {
+            notes_sync::default_remote(&repo).context("No 'origin' remote; pass --remote <name>")?
+        }
+    };
+    let head = args.head_ref();
+    let inspection = Inspection::begin(repo.path());
+
+    inspection.fetch(
+        &remote,
+        &[
+            format!("+{}:{}", head, inspection.head_ref()),
+            format!("+{}:{}", args.base, inspection.base_ref()),
+        ],
+        args.depth,
+    )?;
+    // Only the newest notes tree is read
+    let notes_fetched = inspection
+        .fetch(
+            &remote,
+            &[format!("+{}:{}", NOTES_REF, inspection.notes_ref())],
+            1,
+        )
+        .is_ok();
+
+    // Reopen so the fetched refs and shallow boundary are seen
+    let repo = Repository::open(repo.path()).context("Failed to reopen repository")?;
+    let head_id = repo.refname_to_id(&inspection.head_ref())?;
+    eprintln!(
+        "whogitit: Inspecting {} from {} at {} against {}{}",
+        head,
+        remote,
+        &head_id.to_string()[..SHORT_COMMIT_LEN],
+        args.base,
+        if notes_fetched {
+            ""
+        } else {
+            " (the remote has no attribution notes)"
+        }
+    );
+
+    let storage = StorageConfig {
+        namespace: Some(inspection.namespace()),
+        read_order: vec![inspection.namespace()],
+        ..Default::default()
+    };
+    summary::run_with_store(
+        &repo,
+        &NotesStore::with_storage(&repo, &storage)?,
+        SummaryArgs {
+            base: Some(inspection.base_ref()),
+            head: inspection.head_ref(),
+            format: args.format,
+            fail_on_missing_attribution: false,
+            by_package: false,
+            redact_level: None,
+            reconcile: false,
+            locale: None,
+            include_generated: false,
+        },
+    )?;
+
+    let format = match args.format {
+        SummaryFormat::Json => OutputFormat::Json,
+        SummaryFormat::Pretty | SummaryFormat::Markdown => OutputFormat::Pretty,
+    };
+    for file in args.blame {
+        let mut blamer =
+            AIBlamer::with_notes_store(&repo, NotesStore::with_storage(&repo, &storage)?);
+        blame::run_with_blamer(
+            &mut blamer,
+            BlameArgs {
+                file,
+                revision: Some(inspection.head_ref()),
+                format,
+                ai_only: false,
+                human_only: false,
+                show_time: false,
+                ai_since: None,
+                compare: None,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attribution::{AIAttribution, ModelInfo, SessionMetadata};
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, update_ref: &str, content: &str) -> git2::Oid {
+        let root = repo.workdir().unwrap();
+        fs::write(root.join("lib.rs"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Dev", "dev@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some(update_ref), &sig, &sig, "change", &tree, &parents)
+            .unwrap()
+    }
+
+    fn attribution() -> AIAttribution {
+        AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 0,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files: vec![],
+        }
+    }
+
+    #[test]
+    fn test_inspection_fetches_and_cleans_up() {
+        let upstream_dir = TempDir::new().unwrap();
+        let upstream = Repository::init(upstream_dir.path()).unwrap();
+        commit(&upstream, "HEAD", "fn a() {}\n");
+        let pr_head = commit(&upstream, "refs/pull/7/head", "fn a() {}\nfn b() {}\n");
+        NotesStore::new(&upstream)
+            .unwrap()
+            .store_attribution(pr_head, &attribution())
+            .unwrap();
+
+        let clone_dir = TempDir::new().unwrap();
+        let clone =
+            Repository::clone(upstream_dir.path().to_str().unwrap(), clone_dir.path()).unwrap();
+        assert!(!clone.is_shallow());
+
+        let url = format!("file://{}", upstream_dir.path().display());
+        {
+            let inspection = Inspection::begin(clone.path());
+            inspection
+                .fetch(
+                    &url,
+                    &[format!("+refs/pull/7/head:{}", inspection.head_ref())],
+                    1,
+                )
+                .unwrap();
+            inspection
+                .fetch(
+                    &url,
+                    &[format!("+{}:{}", NOTES_REF, inspection.notes_ref())],
+                    1,
+                )
+                .unwrap();
+
+            let repo = Repository::open(clone.path()).unwrap();
+            assert_eq!(repo.refname_to_id(&inspection.head_ref()).unwrap(), pr_head);
+            let storage = StorageConfig {
+                namespace: Some(inspection.namespace()),
+                read_order: vec![inspection.namespace()],
+                ..Default::default()
+            };
+            assert!(NotesStore::with_storage(&repo, &storage)
+                .unwrap()
+                .has_attribution(pr_head));
+        }
+
+        // Temporary refs are gone, the clone is not left shallow and local notes stay empty
+        let refs: Vec<String> = clone
+            .references()
+            .unwrap()
+            .flatten()
+            .filter_map(|r| r.name().map(str::to_string))
+            .collect();
+        assert!(refs.iter().all(|name| !name.starts_with(INSPECT_REF_PREFIX)
+            && !name.starts_with(NOTES_NAMESPACE_PREFIX)));
+        assert!(clone.find_reference(NOTES_REF).is_err());
+        assert!(!clone_dir.path().join(".git/shallow").exists());
+    }
+}
@@ -0,0 +1,410 @@
+//! Manifest command - release provenance manifest
+//!
+//! Walks the tree at a revision (typically a release tag) and records, for every
+//! file, how many of its lines are AI-authored and which sessions and models
+//! contributed them. The JSON manifest can ship alongside release artifacts for
+//! customers asking about AI involvement.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Args;
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use serde::Serialize;
+
+use crate::capture::encoding;
+use crate::capture::snapshot::LineSource;
+use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::core::attribution::SessionMetadata;
+use crate::core::blame::AIBlamer;
+
+const MANIFEST_MACHINE_SCHEMA: &str = "whogitit.manifest.v1";
+
+/// Manifest command arguments
+#[derive(Debug, Args)]
+pub struct ManifestArgs {
+    /// Revision whose tree is described (e.g. a release tag)
+    #[arg(long, default_value = "HEAD")]
+    pub rev: String,
+
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+/// Release provenance manifest
+#[derive(Debug, Serialize)]
+pub struct ProvenanceManifest {
+    pub schema_version: u8,
+    pub schema: &'static str,
+    pub generated_at: String,
+    /// Revision as given on the command line
+    pub revision: String,
+    /// Commit the revision resolves to
+    pub commit: String,
+    pub summary: ManifestSummary,
+    pub models: Vec<ModelContribution>,
+    pub sessions: Vec<SessionContribution>,
+    pub files: Vec<FileProvenance>,
+}
+
+/// Totals across all files in the tree
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ManifestSummary {
+    pub files: usize,
+    pub files_with_ai: usize,
+    pub binary_files: usize,
+    pub total_lines: usize,
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    pub human_lines: usize,
+    /// Lines last changed by commits without attribution
+    pub unattributed_lines: usize,
+    /// AI and AI-modified lines over all lines (0.0-1.0)
+    pub ai_share: f64,
+}
+
+/// Provenance of one file
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct FileProvenance {
+    pub path: String,
+    /// Not line-attributable (no line counts)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+    pub total_lines: usize,
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    pub human_lines: usize,
+    pub unattributed_lines: usize,
+    pub ai_share: f64,
+    /// Sessions that contributed AI lines still present in the file
+    pub sessions: Vec<String>,
+    /// Models that contributed AI lines still present in the file
+    pub models: Vec<String>,
+}
+
+/// AI lines contributed by a model
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ModelContribution {
+    pub model: String,
+    pub provider: String,
+    pub ai_lines: usize,
+    pub sessions: usize,
+}
+
+/// AI lines contributed by a session
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SessionContribution {
+    pub session_id: String,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub ai_lines: usize,
+    pub files: usize,
+}
+
+/// Run the manifest command
+pub fn run(args: ManifestArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let manifest = build_manifest(&repo, &args.rev)?;
+    let json = serde_json::to_string_pretty(&manifest)?;
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, format!("{}\n", json))
+                .with_context(|| format!("Failed to write {}", path))?;
+            eprintln!(
+                "Wrote provenance manifest for {} files at {} ({:.1}% AI) to {}",
+                manifest.summary.files,
+                &manifest.commit[..7],
+                manifest.summary.ai_share * 100.0,
+                path
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Build the provenance manifest for the tree at `rev`
+pub fn build_manifest(repo: &Repository, rev: &str) -> Result<ProvenanceManifest> {
+    let commit = repo
+        .revparse_single(rev)
+        .with_context(|| format!("Failed to resolve: {}", rev))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", rev))?;
+    let commit_id = commit.id().to_string();
+    let tree = commit.tree()?;
+
+    let mut blobs = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                blobs.push((format!("{}{}", dir, name), entry.id()));
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let mut blamer = AIBlamer::new(repo)?;
+    let mut commit_sessions: HashMap<String, Option<SessionMetadata>> = HashMap::new();
+    let mut files = Vec::new();
+    let mut sessions: BTreeMap<String, SessionContribution> = BTreeMap::new();
+    let mut session_files: BTreeMap<String, usize> = BTreeMap::new();
+    let mut models: BTreeMap<String, (String, usize, BTreeSet<String>)> = BTreeMap::new();
+
+    for (path, blob_id) in blobs {
+        let blob = repo.find_blob(blob_id)?;
+        if encoding::decode(blob.content()).is_none() {
+            files.push(FileProvenance {
+                path,
+                binary: true,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let blame = blamer.blame(&path, Some(&commit_id))?;
+        let mut file = FileProvenance {
+            path,
+            total_lines: blame.lines.len(),
+            ..Default::default()
+        };
+        let mut file_sessions = BTreeSet::new();
+        let mut file_models = BTreeSet::new();
+
+        for line in &blame.lines {
+            match line.source {
+                LineSource::AI { .. } => file.ai_lines += 1,
+                LineSource::AIModified { .. } => file.ai_modified_lines += 1,
+                LineSource::Human | LineSource::Original => file.human_lines += 1,
+                LineSource::Unknown => file.unattributed_lines += 1,
+            }
+            if !line.is_ai() {
+                continue;
+            }
+            if !commit_sessions.contains_key(&line.commit_id) {
+                let session = blamer
+                    .get_commit_attribution(&line.commit_id)?
+                    .map(|a| a.session);
+                commit_sessions.insert(line.commit_id.clone(), session);
+            }
+            let Some(Some(session)) = commit_sessions.get(&line.commit_id) else {
+                continue;
+            };
+            sessions
+                .entry(session.session_id.clone())
+                .or_insert_with(|| SessionContribution {
+                    session_id: session.session_id.clone(),
+                    model: session.model.id.clone(),
+                    label: session.label.clone(),
+                    ai_lines: 0,
+                    files: 0,
+                })
+                .ai_lines += 1;
+            let model = models
+                .entry(session.model.id.clone())
+                .or_insert_with(|| (session.model.provider.clone(), 0, BTreeSet::new()));
+            model.1 += 1;
+            model.2.insert(session.session_id.clone());
+            file_sessions.insert(session.session_id.clone());
+            file_models.insert(session.model.id.clone());
+        }
+
+        for session_id in &file_sessions {
+            *session_files.entry(session_id.clone()).or_default() += 1;
+        }
+        file.ai_share = share(file.ai_lines + file.ai_modified_lines, file.total_lines);
+        file.sessions = file_sessions.into_iter().collect();
+        file.models = file_models.into_iter().collect();
+        files.push(file);
+    }
+
+    for (session_id, count) in session_files {
+        if let Some(session) = sessions.get_mut(&session_id) {
+            session.files = count;
+        }
+    }
+
+    let mut sessions: Vec<SessionContribution> = sessions.into_values().collect();
+    sessions.sort_by_key(|s| Reverse(s.ai_lines));
+    let mut models: Vec<ModelContribution> = models
+        .into_iter()
+        .map(
+            |(model, (provider, ai_lines, sessions))| ModelContribution {
+                model,
+                provider,
+                ai_lines,
+                sessions: sessions.len(),
+            },
+        )
+        .collect();
+    models.sort_by_key(|m| Reverse(m.ai_lines));
+
+    Ok(ProvenanceManifest {
+        schema_version: MACHINE_OUTPUT_SCHEMA_VERSION,
+        schema: MANIFEST_MACHINE_SCHEMA,
+        generated_at: Utc::now().to_rfc3339(),
+        revision: rev.to_string(),
+        commit: commit_id,
+        summary: summarize(&files),
+        models,
+        sessions,
+        files,
+    })
+}
+
+fn summarize(files: &[FileProvenance]) -> ManifestSummary {
+    let mut summary = ManifestSummary {
+        files: files.len(),
+        ..Default::default()
+    };
+    for file in files {
+        summary.binary_files += usize::from(file.binary);
+        summary.files_with_ai += usize::from(file.ai_lines + file.ai_modified_lines > 0);
+        summary.total_lines += file.total_lines;
+        summary.ai_lines += file.ai_lines;
+        summary.ai_modified_lines += file.ai_modified_lines;
+        summary.human_lines += file.human_lines;
+        summary.unattributed_lines += file.unattributed_lines;
+    }
+    summary.ai_share = share(
+        summary.ai_lines + summary.ai_modified_lines,
+        summary.total_lines,
+    );
+    summary
+}
+
+/// Fraction rounded to 3 decimals
+pub(crate) fn share(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (part as f64 / total as f64 * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::{AttributionSummary, FileAttributionResult, LineAttribution};
+    use crate::core::attribution::{AIAttribution, ModelInfo, SCHEMA_VERSION};
+    use crate::storage::notes::NotesStore;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn commit_files(repo: &Repository, files: &[(&str, &[u8])], message: &str) -> git2::Oid {
+        let root = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            let full = root.join(path);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(&full, content).unwrap();
+            index.add_path(std::path::Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_manifest_counts_ai_lines_sessions_and_models() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_files(
+            &repo,
+            &[("README.md", b"# Demo\n"), ("logo.png", b"\x89PNG\x00\x01")],
+            "Initial",
+        );
+        let ai_commit = commit_files(&repo, &[("src/lib.rs", b"fn a() {}\nfn b() {}\n")], "AI");
+
+        let line = |n: u32, source: LineSource| LineAttribution {
+            line_number: n,
+            content: String::new(),
+            content_hash: None,
+            source,
+            edit_id: Some("e1".to_string()),
+            prompt_index: Some(0),
+            confidence: 1.0,
+            timestamp: None,
+        };
+        let attribution = AIAttribution {
+            version: SCHEMA_VERSION,
+            session: SessionMetadata {
+                session_id: "session-1".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 1,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: Some("release-prep".to_string()),
+            },
+            prompts: vec![],
+            files: vec![FileAttributionResult {
+                path: "src/lib.rs".to_string(),
+                lines: vec![
+                    line(
+                        1,
+                        LineSource::AI {
+                            edit_id: "e1".to_string(),
+                        },
+                    ),
+                    line(2, LineSource::Human),
+                ],
+                summary: AttributionSummary {
+                    total_lines: 2,
+                    ai_lines: 1,
+                    ai_modified_lines: 0,
+                    human_lines: 1,
+                    original_lines: 0,
+                    unknown_lines: 0,
+                },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
+            }],
+        };
+        NotesStore::new(&repo)
+            .unwrap()
+            .store_attribution(ai_commit, &attribution)
+            .unwrap();
+        repo.tag_lightweight("v1.0.0", &repo.find_object(ai_commit, None).unwrap(), false)
+            .unwrap();
+
+        let manifest = build_manifest(&repo, "v1.0.0").unwrap();
+        assert_eq!(manifest.commit, ai_commit.to_string());
+        assert_eq!(manifest.summary.files, 3);
+        assert_eq!(manifest.summary.binary_files, 1);
+        assert_eq!(manifest.summary.files_with_ai, 1);
+        assert_eq!(manifest.summary.unattributed_lines, 1);
+        assert_eq!(manifest.summary.ai_share, 0.333);
+
+        let lib = manifest
+            .files
+            .iter()
+            .find(|f| f.path == "src/lib.rs")
+            .unwrap();
+        assert_eq!((lib.ai_lines, lib.human_lines, lib.ai_share), (1, 1, 0.5));
+        assert_eq!(lib.sessions, vec!["session-1"]);
+        assert_eq!(lib.models, vec!["claude-opus-4-5-20251101"]);
+
+        assert_eq!(manifest.sessions.len(), 1);
+        assert_eq!(manifest.sessions[0].label.as_deref(), Some("release-prep"));
+        assert_eq!(
+            (manifest.sessions[0].ai_lines, manifest.sessions[0].files),
+            (1, 1)
+        );
+        assert_eq!(manifest.models[0].provider, "anthropic");
+    }
+}
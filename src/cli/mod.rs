@@ -1,28 +1,71 @@
 pub mod annotations;
 pub mod audit;
+pub mod auth;
+pub mod bench;
+pub mod bisect;
 pub mod blame;
+pub mod blame_compare;
+pub mod calibrate;
+pub mod check_prompt;
+pub mod ci_doctor;
+pub mod compare_branches;
 pub mod copy;
+pub mod docgen_footer;
+pub mod environment;
 pub mod export;
+#[cfg(feature = "dev-tools")]
+pub mod fixtures;
+pub mod git_ext;
+pub mod gutter;
+pub mod i18n;
+pub mod import;
+pub mod import_aider;
+pub mod inspect;
+pub mod manifest;
+pub mod notify;
 pub mod output;
 pub mod pager;
+pub mod pause;
+pub mod policy;
+pub mod prepush_scan;
+pub mod preview;
 pub mod prompt;
+pub mod prompts;
+pub mod push_notes;
+pub mod recover;
 pub mod redact;
+pub mod reindex;
 pub mod retention;
+pub mod review_plan;
+pub mod selftest;
+pub mod serve;
+pub mod session;
 pub mod setup;
 pub mod show;
+pub mod split_plan;
+pub mod stash;
+pub mod stats;
+pub mod status;
 pub mod summary;
+pub mod top;
+pub mod transitions;
+pub mod usage;
+pub mod watch;
+pub mod worker;
 
 use std::fs;
 
 use anyhow::{Context, Result};
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
 use crate::capture::hook;
-use crate::privacy::WhogititConfig;
+use crate::privacy::{self, WhogititConfig};
 use crate::storage::audit::AuditLog;
+use crate::storage::notes_sync::{self, NotesAvailability};
+use crate::storage::{LockHolderKind, RepoLock};
 
 /// AI-aware git blame tool for tracking AI-generated code
 #[derive(Debug, Parser)]
@@ -31,6 +74,10 @@ use crate::storage::audit::AuditLog;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Don't fetch attribution notes from the remote when they are missing locally
+    #[arg(long, global = true)]
+    pub no_fetch: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -41,55 +88,198 @@ pub enum Commands {
     /// View the prompt that generated specific lines
     Prompt(prompt::PromptArgs),
 
+    /// List prompts by provenance hash (use --duplicates to find reused prompts)
+    Prompts(prompts::PromptsArgs),
+
     /// Show AI attribution summary for a commit
     Show(show::ShowArgs),
 
     /// Generate summary for a range of commits (useful for PRs)
     Summary(summary::SummaryArgs),
 
+    /// Audit a pull request's attribution from any clone by fetching it into temporary refs
+    Inspect(inspect::InspectArgs),
+
+    /// Rank files in a commit range by AI review risk (ordered checklist)
+    ReviewPlan(review_plan::ReviewPlanArgs),
+
+    /// Propose commits separating AI-generated and human changes in the working tree
+    SplitPlan(split_plan::SplitPlanArgs),
+
+    /// Compare AI share of the files a branch changes against the base branch
+    CompareBranches(compare_branches::CompareBranchesArgs),
+
+    /// AI adoption metrics over a time window (per author with --by-author)
+    Stats(stats::StatsArgs),
+
+    /// Rank files and directories by AI lines or share (hotspots with --churn)
+    Top(top::TopArgs),
+
+    /// How committed lines change source later: AI lines modified, replaced or surviving
+    Transitions(transitions::TransitionsArgs),
+
     /// Generate annotations for GitHub Checks API
     Annotations(annotations::AnnotationsArgs),
 
+    /// Write per-line attribution for a file to .whogitit/<path>.attr.json for editor plugins
+    Gutter(gutter::GutterArgs),
+
+    /// List commits that introduced AI-attributed changes to a path between two revisions
+    BisectAi(bisect::BisectAiArgs),
+
     /// Annotate git diff output with AI attribution (for use as git pager)
     Pager(pager::PagerArgs),
 
     /// Test redaction patterns against text or files
     RedactTest(redact::RedactArgs),
 
+    /// Screen a prompt or hook payload for prohibited content (exit 2 blocks the tool call)
+    CheckPrompt(check_prompt::CheckPromptArgs),
+
     /// Export attribution data for multiple commits
     Export(export::ExportArgs),
 
+    /// Write a release provenance manifest (AI share per file) for the tree at a revision
+    Manifest(manifest::ManifestArgs),
+
+    /// Print a provenance blurb (AI share, models, last AI session) for generated docs
+    DocgenFooter(docgen_footer::DocgenFooterArgs),
+
+    /// Evaluate attribution policy rules over a commit range (structured violations);
+    /// exits non-zero on error-severity violations to gate CI
+    #[command(visible_alias = "check")]
+    Policy(policy::PolicyArgs),
+
+    /// Update the SQLite attribution index used by summary and export --query
+    Reindex(reindex::ReindexArgs),
+
     /// Manage data retention policies
     Retention(retention::RetentionArgs),
 
     /// View the audit log
     Audit(audit::AuditArgs),
 
+    /// Label the true source of a line (collects data for `calibrate`)
+    AnnotateManual(calibrate::AnnotateManualArgs),
+
+    /// Fit the confidence calibration in .whogitit.toml to labeled lines
+    Calibrate(calibrate::CalibrateArgs),
+
     /// Capture a file change (called by Claude Code hook)
     #[command(hide = true)]
     Capture(CaptureArgs),
 
     /// Finalize attribution after a commit (post-commit hook)
     #[command(hide = true)]
-    PostCommit,
+    PostCommit {
+        /// Attribute files with more lines than this in bounded memory, as line ranges
+        /// (overrides analysis.max_analysis_lines; 0 disables the limit)
+        #[arg(long)]
+        max_analysis_lines: Option<usize>,
+
+        /// Don't report analysis progress on stderr
+        #[arg(long)]
+        quiet: bool,
+    },
+
+    /// List AI-edited files missing from the index (pre-commit hook)
+    #[command(hide = true)]
+    PreCommit,
+
+    /// Warn when prompts would be pushed against privacy.prompt_visibility (pre-push hook)
+    #[command(hide = true)]
+    PrePush {
+        /// Name of the remote being pushed to
+        remote: String,
+        /// URL of the remote
+        url: String,
+    },
+
+    /// Append attribution trailers to a commit message (commit-msg hook)
+    #[command(hide = true)]
+    CommitMsg {
+        /// Commit message file passed by git
+        file: std::path::PathBuf,
+    },
+
+    /// Capture edits by watching the worktree (for AI tools without hooks)
+    WatchCapture(watch::WatchCaptureArgs),
 
     /// Show pending changes status
-    Status,
+    Status(status::StatusArgs),
+
+    /// Dry-run the post-commit analysis on the staged index (nothing is written)
+    Preview(preview::PreviewArgs),
 
     /// Clear pending changes without committing
     Clear,
 
+    /// Salvage attribution from a corrupted pending buffer
+    Recover(recover::RecoverArgs),
+
+    /// Restore a stale pending buffer archived by `analysis.stale_action = "archive"`
+    Import(import::ImportArgs),
+
+    /// Attribute commits made by Aider from its chat history and commit trailers
+    ImportAider(import_aider::ImportAiderArgs),
+
+    /// Stash changes while keeping pending AI attribution in sync
+    Stash(stash::StashArgs),
+
+    /// Begin or end an explicit attribution session with a stable ID and label
+    Session(session::SessionArgs),
+
+    /// Pause capture of prompts and file content (e.g. while editing credentials)
+    Pause(pause::PauseArgs),
+
+    /// Resume capture after `whogitit pause`
+    Resume,
+
     /// Initialize whogitit in a git repository (installs post-commit hook)
     Init(InitArgs),
 
     /// Set up whogitit globally (install capture hook and configure Claude Code)
-    Setup,
+    Setup(setup::SetupArgs),
 
     /// Check whogitit configuration and diagnose issues
-    Doctor,
+    Doctor(setup::DoctorArgs),
+
+    /// Run an end-to-end capture/commit/blame check in a temporary repository
+    Selftest(selftest::SelftestArgs),
+
+    /// Measure analyzer, redactor and blame throughput on synthetic workloads
+    Bench(bench::BenchArgs),
+
+    /// Generate scratch repositories for attribution scenarios (dev-tools feature)
+    #[cfg(feature = "dev-tools")]
+    Fixtures(fixtures::FixturesArgs),
 
     /// Copy AI attribution from one commit to another
     CopyNotes(copy::CopyNotesArgs),
+
+    /// Push attribution notes to a remote (used by the pre-push hook)
+    PushNotes(push_notes::PushNotesArgs),
+
+    /// Scan outgoing attribution notes for secrets and oversized prompts (used by the pre-push hook)
+    PrepushScan(prepush_scan::PrepushScanArgs),
+
+    /// POST a summary of pushed attribution to a dashboard endpoint (used by the pre-push hook)
+    Notify(notify::NotifyArgs),
+
+    /// Serve a read-only HTTP API for blame, commit and summary queries
+    Serve(serve::ServeArgs),
+
+    /// Store GitHub/GitLab tokens and the notify secret in the OS keychain
+    Auth(auth::AuthArgs),
+
+    /// Opt-in, local-only command usage counts and a cross-machine report
+    Usage(usage::UsageArgs),
+
+    /// Retry attaching attribution notes whose write previously failed
+    Worker(worker::WorkerArgs),
+
+    /// Install `git whogitit` / `git aiblame` links or aliases
+    GitExt(git_ext::GitExtArgs),
 }
 
 /// Init command arguments
@@ -98,6 +288,14 @@ pub struct InitArgs {
     /// Skip global setup check
     #[arg(long)]
     pub force: bool,
+
+    /// Write a commented starter .whogitit.toml to the repository root
+    #[arg(long)]
+    pub with_config: bool,
+
+    /// Write .whogitit.toml from a template file or http(s) URL instead of the starter
+    #[arg(long, value_name = "URL_OR_PATH")]
+    pub from: Option<String>,
 }
 
 /// Capture command arguments
@@ -118,47 +316,202 @@ pub struct CaptureArgs {
     /// Prompt text
     #[arg(long)]
     pub prompt: Option<String>,
+
+    /// Input schema of the stdin payload
+    #[arg(long, value_enum, default_value_t = CaptureProvider::ClaudeCode)]
+    pub provider: CaptureProvider,
+
+    /// Stdin is the unmodified Claude Code hook event (phase from WHOGITIT_HOOK_PHASE)
+    #[arg(long, requires = "stdin", conflicts_with = "provider")]
+    pub raw_claude_event: bool,
+}
+
+/// Stdin input schema for the capture command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CaptureProvider {
+    /// Payload built by the Claude Code capture hook script
+    #[default]
+    ClaudeCode,
+    /// Versioned, provider-neutral schema (see `whogitit setup --provider generic`)
+    #[value(name = "generic-v1")]
+    GenericV1,
+    /// Accepted inline (ghost-text) completion reported by an editor extension
+    #[value(name = "inline-v1")]
+    InlineV1,
+    /// Cursor `hooks.json` event (see `whogitit setup --agent cursor`)
+    #[value(name = "cursor")]
+    Cursor,
 }
 
 /// Run the CLI
 pub fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let command = Cli::command();
+    // Started as `git-whogitit` or `git-aiblame` (see `git-ext install`)
+    let matches = command
+        .clone()
+        .get_matches_from(git_ext::shim_args(std::env::args_os().collect()));
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // Hook entry points are hidden and run on every edit/commit; only count commands
+    // people type
+    if let Some(name) = matches.subcommand_name() {
+        if command
+            .find_subcommand(name)
+            .is_some_and(|c| !c.is_hide_set())
+        {
+            usage::record_command(name);
+        }
+    }
+
+    if reads_notes(&cli.command) {
+        ensure_local_notes(!cli.no_fetch);
+    }
 
     match cli.command {
         Commands::Blame(args) => blame::run(args),
         Commands::Prompt(args) => prompt::run(args),
+        Commands::Prompts(args) => prompts::run(args),
         Commands::Show(args) => show::run(args),
         Commands::Summary(args) => summary::run(args),
+        Commands::Inspect(args) => inspect::run(args),
+        Commands::ReviewPlan(args) => review_plan::run(args),
+        Commands::SplitPlan(args) => split_plan::run(args),
+        Commands::CompareBranches(args) => compare_branches::run(args),
+        Commands::Stats(args) => stats::run(args),
+        Commands::Top(args) => top::run(args),
+        Commands::Transitions(args) => transitions::run(args),
         Commands::Annotations(args) => annotations::run(args),
+        Commands::Gutter(args) => gutter::run(args),
+        Commands::BisectAi(args) => bisect::run(args),
         Commands::Pager(args) => pager::run(args),
         Commands::RedactTest(args) => redact::run(args),
+        Commands::CheckPrompt(args) => check_prompt::run(args),
         Commands::Export(args) => export::run(args),
+        Commands::Manifest(args) => manifest::run(args),
+        Commands::DocgenFooter(args) => docgen_footer::run(args),
+        Commands::Policy(args) => policy::run(args),
+        Commands::Reindex(args) => reindex::run(args),
         Commands::Retention(args) => retention::run(args),
         Commands::Audit(args) => audit::run(args),
+        Commands::AnnotateManual(args) => calibrate::run_annotate_manual(args),
+        Commands::Calibrate(args) => calibrate::run_calibrate(args),
         Commands::Capture(args) => run_capture(args),
-        Commands::PostCommit => run_post_commit(),
-        Commands::Status => run_status(),
+        Commands::PostCommit {
+            max_analysis_lines,
+            quiet,
+        } => run_post_commit(max_analysis_lines, quiet),
+        Commands::PreCommit => hook::run_pre_commit_hook(),
+        Commands::PrePush { remote, url } => hook::run_pre_push_hook(&remote, &url),
+        Commands::CommitMsg { file } => hook::run_commit_msg_hook(&file),
+        Commands::WatchCapture(args) => watch::run(args),
+        Commands::Status(args) if args.porcelain => status::run_porcelain(args.timeout),
+        Commands::Status(args) => run_status(args.verbose),
+        Commands::Preview(args) => preview::run(args),
         Commands::Clear => run_clear(),
+        Commands::Recover(args) => recover::run(args),
+        Commands::Import(args) => import::run(args),
+        Commands::ImportAider(args) => import_aider::run(args),
+        Commands::Stash(args) => stash::run(args),
+        Commands::Session(args) => session::run(args),
+        Commands::Pause(args) => pause::run_pause(args),
+        Commands::Resume => pause::run_resume(),
         Commands::Init(args) => run_init(args),
-        Commands::Setup => setup::run_setup(),
-        Commands::Doctor => setup::run_doctor(),
+        Commands::Setup(args) => setup::run_setup(args),
+        Commands::Doctor(args) => setup::run_doctor(args),
+        Commands::Selftest(args) => selftest::run(args),
+        Commands::Bench(args) => bench::run(args),
+        #[cfg(feature = "dev-tools")]
+        Commands::Fixtures(args) => fixtures::run(args),
         Commands::CopyNotes(args) => copy::run(args),
+        Commands::PushNotes(args) => push_notes::run(args),
+        Commands::PrepushScan(args) => prepush_scan::run(args),
+        Commands::Notify(args) => notify::run(args),
+        Commands::Serve(args) => serve::run(args),
+        Commands::Auth(args) => auth::run(args),
+        Commands::Usage(args) => usage::run(args),
+        Commands::Worker(args) => worker::run(args),
+        Commands::GitExt(args) => git_ext::run(args),
+    }
+}
+
+/// Commands that read attribution notes
+fn reads_notes(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Blame(_)
+            | Commands::Prompt(_)
+            | Commands::Prompts(_)
+            | Commands::Show(_)
+            | Commands::Summary(_)
+            | Commands::ReviewPlan(_)
+            | Commands::CompareBranches(_)
+            | Commands::Stats(_)
+            | Commands::Transitions(_)
+            | Commands::Annotations(_)
+            | Commands::Gutter(_)
+            | Commands::BisectAi(_)
+            | Commands::Export(_)
+            | Commands::Manifest(_)
+            | Commands::DocgenFooter(_)
+            | Commands::Policy(_)
+            | Commands::Reindex(_)
+            | Commands::AnnotateManual(_)
+            | Commands::Serve(_)
+    )
+}
+
+/// Fetch notes that exist on the remote but not locally, explaining what happened on stderr
+///
+/// Never fails the command: without notes, output simply shows no AI attribution.
+fn ensure_local_notes(fetch: bool) {
+    let Ok(repo) = git2::Repository::discover(".") else {
+        return;
+    };
+    match notes_sync::ensure_local_notes(&repo, fetch) {
+        Ok(NotesAvailability::Fetched { remote }) => {
+            eprintln!(
+                "whogitit: Fetched attribution notes from '{}' (they were missing locally).",
+                remote
+            );
+        }
+        Ok(NotesAvailability::FetchDisabled { remote }) => {
+            eprintln!(
+                "whogitit: No local attribution notes; all lines will show as non-AI.\n\
+                 whogitit: If the team pushes notes, fetch them with: git fetch {} {}",
+                remote,
+                notes_sync::fetch_refspec()
+            );
+        }
+        Ok(NotesAvailability::Local | NotesAvailability::Missing) => {}
+        Err(e) => {
+            eprintln!(
+                "whogitit: Warning - could not check remote for attribution notes: {:#}",
+                e
+            );
+        }
     }
 }
 
 fn run_capture(args: CaptureArgs) -> Result<()> {
-    if args.stdin {
-        hook::run_capture_hook()
+    if args.stdin && args.raw_claude_event {
+        hook::run_raw_claude_event_hook()
+    } else if args.stdin {
+        match args.provider {
+            CaptureProvider::ClaudeCode => hook::run_capture_hook(),
+            CaptureProvider::GenericV1 => hook::run_generic_capture_hook(),
+            CaptureProvider::InlineV1 => hook::run_inline_capture_hook(),
+            CaptureProvider::Cursor => hook::run_cursor_capture_hook(),
+        }
     } else {
         anyhow::bail!("Capture requires --stdin flag for hook input")
     }
 }
 
-fn run_post_commit() -> Result<()> {
-    hook::run_post_commit_hook()
+fn run_post_commit(max_analysis_lines: Option<usize>, quiet: bool) -> Result<()> {
+    hook::run_post_commit_hook(max_analysis_lines, quiet)
 }
 
-fn run_status() -> Result<()> {
+fn run_status(verbose: bool) -> Result<()> {
     let repo = git2::Repository::discover(".")?;
     let repo_root = repo
         .workdir()
@@ -176,10 +529,32 @@ fn run_status() -> Result<()> {
         println!("  Files: {}", status.file_count);
         println!("  Edits: {}", status.edit_count);
         println!("  Lines: {}", status.line_count);
+        if verbose {
+            println!("  Prompts: {}", status.prompt_count);
+        }
         if !status.age.is_empty() {
             println!("  Age: {}", status.age);
         }
 
+        if !status.secret_findings.is_empty() {
+            println!("\n🚨 Possible secrets in AI-written code:");
+            for (path, finding) in &status.secret_findings {
+                println!(
+                    "  {}:{}  {} (prompt #{})",
+                    path, finding.line_number, finding.pattern_name, finding.prompt_index
+                );
+            }
+            println!("   Review these lines before committing; whogitit does not change them.");
+        }
+
+        if !status.untracked_files.is_empty() {
+            println!("\n⚠️  AI-edited files not added to git:");
+            for path in &status.untracked_files {
+                println!("  {}", path);
+            }
+            println!("   Run 'git add' on them, or their attribution stays pending.");
+        }
+
         if status.is_stale {
             println!(
                 "\n⚠️  Warning: This pending buffer is stale (> {} hours old).",
@@ -193,6 +568,51 @@ fn run_status() -> Result<()> {
         println!("No pending AI attribution.");
     }
 
+    if verbose && !status.skipped_noops.is_empty() {
+        let total: u32 = status.skipped_noops.iter().map(|(_, count)| count).sum();
+        println!("\nSkipped {} write(s) that left files unchanged:", total);
+        for (path, count) in &status.skipped_noops {
+            println!("  {}  ({})", path, count);
+        }
+    }
+
+    if let Some(session) = crate::capture::session::ActiveSession::load_for_workdir(repo_root) {
+        println!("\nActive session: {}", session::describe(&session));
+    }
+
+    if let Some(pause) = crate::capture::pause::CapturePause::peek_for_workdir(repo_root) {
+        println!("\n⏸️  Capture paused {}", pause.window());
+        if let Some(reason) = &pause.reason {
+            println!("   Reason: {}", reason);
+        }
+        println!("   Run 'whogitit resume' to capture again.");
+    }
+
+    if let Some(holder) = RepoLock::holder(repo.path()) {
+        println!("\n🔒 Repository lock held by {}", holder);
+    }
+
+    if let Some(failure) = crate::storage::notes_push::last_failure(&repo) {
+        println!(
+            "\n⚠️  Pushing attribution notes to {} failed ({}):",
+            failure.remote, failure.at
+        );
+        println!("   {}", failure.error);
+        println!(
+            "   Run 'whogitit push-notes --remote {}' to retry.",
+            failure.remote
+        );
+    }
+
+    let store = crate::capture::PendingStore::new(repo_root);
+    if store.exists() && store.load_quiet()?.is_none() {
+        println!("\n⚠️  The pending buffer is corrupted. Run 'whogitit recover' to salvage it.");
+    } else if !store.corrupted_backups().is_empty() {
+        println!(
+            "\nA corrupted pending buffer was backed up. Run 'whogitit recover' to salvage it."
+        );
+    }
+
     Ok(())
 }
 
@@ -202,6 +622,7 @@ fn run_clear() -> Result<()> {
         .workdir()
         .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
 
+    let _lock = RepoLock::acquire_default(repo.path(), LockHolderKind::Clear)?;
     let hook_handler = crate::capture::CaptureHook::new(repo_root)?;
     hook_handler.clear_pending()?;
 
@@ -211,9 +632,16 @@ fn run_clear() -> Result<()> {
 }
 
 fn run_init(args: InitArgs) -> Result<()> {
-    // Check global setup status first (unless --force is used)
+    // Check global setup status first (unless --force is used). Cursor users
+    // set up with `setup --agent cursor` instead of the Claude Code hooks.
     let status = setup::check_setup_status();
-    if !status.is_complete() && !args.force {
+    let cursor_configured = setup::cursor_hooks_configured(
+        git2::Repository::discover(".")
+            .ok()
+            .and_then(|repo| repo.workdir().map(std::path::Path::to_path_buf))
+            .as_deref(),
+    );
+    if !status.is_complete() && !cursor_configured && !args.force {
         println!("Global setup incomplete:");
         if !status.hook_script_installed {
             println!("  - Capture hook not installed");
@@ -225,14 +653,15 @@ fn run_init(args: InitArgs) -> Result<()> {
             println!("  - Claude Code hooks not configured");
         }
         println!();
-        println!("Run 'whogitit setup' first to configure Claude Code integration.");
+        println!("Run 'whogitit setup' first to configure Claude Code integration");
+        println!("(or 'whogitit setup --agent cursor' for Cursor).");
         println!("Then run 'whogitit init' again to initialize this repository.");
         println!();
         println!("Or run 'whogitit init --force' to skip this check and proceed anyway.");
         return Ok(());
     }
 
-    if !status.is_complete() && args.force {
+    if !status.is_complete() && !cursor_configured && args.force {
         println!("Warning: Global setup is incomplete. Proceeding with --force.\n");
     }
 
@@ -241,6 +670,13 @@ fn run_init(args: InitArgs) -> Result<()> {
         .workdir()
         .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
 
+    // Write the config first so the hooks installed below follow it
+    let config_written = if args.with_config || args.from.is_some() {
+        write_repo_config(repo_root, args.from.as_deref())?
+    } else {
+        false
+    };
+
     let hooks_dir = repo_root.join(".git/hooks");
     fs::create_dir_all(&hooks_dir).context("Failed to create hooks directory")?;
 
@@ -253,13 +689,31 @@ fn run_init(args: InitArgs) -> Result<()> {
     // Install post-rewrite hook (preserves notes during rebase/amend)
     install_post_rewrite_hook(&hooks_dir)?;
 
+    let config = WhogititConfig::load(repo_root);
+
+    // Install commit-msg hook (writes attribution trailers) when configured
+    if config
+        .as_ref()
+        .is_ok_and(|c| c.storage.backend.writes_trailers())
+    {
+        install_commit_msg_hook(&hooks_dir)?;
+    }
+
+    // Install pre-commit hook (reminds about AI-edited files not staged) when configured
+    if config
+        .as_ref()
+        .is_ok_and(|c| c.analysis.pre_commit_reminder)
+    {
+        install_pre_commit_hook(&hooks_dir)?;
+    }
+
     // Configure git to auto-fetch notes
     let fetch_updated = configure_git_fetch(&repo)?;
     let exclude_updated = add_git_exclude(&repo)?;
 
-    if let Ok(config) = WhogititConfig::load(repo_root) {
+    if let Ok(config) = config {
         if config.privacy.audit_log {
-            let audit_log = AuditLog::new(repo_root);
+            let audit_log = AuditLog::new(repo_root).with_forwarding(&config.audit.forwarding);
             if fetch_updated {
                 if let Err(e) = audit_log.log_config_change(
                     "git.remote.origin.fetch",
@@ -268,6 +722,17 @@ fn run_init(args: InitArgs) -> Result<()> {
                     eprintln!("whogitit: Warning - failed to write audit event: {}", e);
                 }
             }
+            if config_written {
+                if let Err(e) = audit_log.log_config_change(
+                    ".whogitit.toml",
+                    &format!(
+                        "Wrote repository config from {}",
+                        args.from.as_deref().unwrap_or("the starter template")
+                    ),
+                ) {
+                    eprintln!("whogitit: Warning - failed to write audit event: {}", e);
+                }
+            }
             if exclude_updated {
                 if let Err(e) = audit_log.log_config_change(
                     "git.info.exclude",
@@ -282,13 +747,68 @@ fn run_init(args: InitArgs) -> Result<()> {
     println!("\nRepository initialized! AI attribution will be tracked for commits in this repo.");
     println!("Notes will be automatically pushed with 'git push' and fetched with 'git fetch'.");
 
-    if !status.is_complete() {
+    if !status.is_complete() && !cursor_configured {
         println!("\nReminder: Run 'whogitit setup' to complete Claude Code integration.");
     }
 
     Ok(())
 }
 
+/// Write `.whogitit.toml` from the starter or a template, returning whether it was written
+///
+/// An existing config is left alone. Templates are validated before writing.
+fn write_repo_config(repo_root: &std::path::Path, from: Option<&str>) -> Result<bool> {
+    let config_path = WhogititConfig::repo_config_path(repo_root);
+    if config_path.exists() {
+        println!(
+            "✓ {} already exists; leaving it unchanged (remove it to regenerate).",
+            config_path.display()
+        );
+        return Ok(false);
+    }
+
+    let content = match from {
+        Some(source) => {
+            let content = read_config_template(source)?;
+            toml::from_str::<WhogititConfig>(&content)
+                .with_context(|| format!("Template {} is not a valid whogitit config", source))?;
+            content
+        }
+        None => privacy::STARTER_CONFIG.to_string(),
+    };
+    fs::write(&config_path, content)
+        .with_context(|| format!("Failed to write {}", config_path.display()))?;
+    println!(
+        "✓ Wrote {} from {}. Commit it to share the settings.",
+        config_path.display(),
+        from.unwrap_or("the starter template")
+    );
+    Ok(true)
+}
+
+/// Read a config template from a local path or an http(s) URL (fetched with curl)
+fn read_config_template(source: &str) -> Result<String> {
+    if !(source.starts_with("https://") || source.starts_with("http://")) {
+        return fs::read_to_string(source)
+            .with_context(|| format!("Failed to read config template {}", source));
+    }
+
+    let output = std::process::Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--max-time", "30", source])
+        .output()
+        .context("Failed to run curl (required to fetch config templates from URLs)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to fetch config template {}: {}",
+            source,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("Config template {} is not UTF-8", source))
+}
+
 /// Marker comment to identify whogitit hook sections
 const WHOGITIT_MARKER_START: &str = "# >>> whogitit hook start >>>";
 const WHOGITIT_MARKER_END: &str = "# <<< whogitit hook end <<<";
@@ -307,7 +827,7 @@ fn install_post_commit_hook(hooks_dir: &std::path::Path) -> Result<()> {
 
         // Append to existing hook with markers for idempotency
         let whogitit_section = format!(
-            "\n\n{}\n# whogitit post-commit hook - Attaches AI attribution notes\nif command -v whogitit &> /dev/null; then\n    whogitit post-commit 2>/dev/null || true\nfi\n{}\n",
+            "\n\n{}\n# whogitit post-commit hook - Attaches AI attribution notes\nif command -v whogitit &> /dev/null; then\n    whogitit post-commit || true\nfi\n{}\n",
             WHOGITIT_MARKER_START,
             WHOGITIT_MARKER_END
         );
@@ -322,9 +842,9 @@ fn install_post_commit_hook(hooks_dir: &std::path::Path) -> Result<()> {
 # Attaches AI attribution notes to the commit
 
 if command -v whogitit &> /dev/null; then
-    whogitit post-commit 2>/dev/null || true
+    whogitit post-commit || true
 elif [[ -x "$HOME/.cargo/bin/whogitit" ]]; then
-    "$HOME/.cargo/bin/whogitit" post-commit 2>/dev/null || true
+    "$HOME/.cargo/bin/whogitit" post-commit || true
 fi
 {}
 "#,
@@ -352,7 +872,7 @@ fn install_pre_push_hook(hooks_dir: &std::path::Path) -> Result<()> {
 
         // Append to existing hook with markers for idempotency
         let whogitit_section = format!(
-            "\n\n{}\n# whogitit pre-push hook - automatically push notes\n# Skip if already pushing notes (prevent recursion)\n[[ \"$WHOGITIT_PUSHING_NOTES\" == \"1\" ]] && exit 0\nremote=\"$1\"\nif git notes --ref=whogitit list &>/dev/null; then\n    WHOGITIT_PUSHING_NOTES=1 git push \"$remote\" refs/notes/whogitit 2>/dev/null || true\nfi\n{}\n",
+            "\n\n{}\n# whogitit pre-push hook - automatically push notes\n# Skip if already pushing notes (prevent recursion)\n[[ \"$WHOGITIT_PUSHING_NOTES\" == \"1\" ]] && exit 0\nremote=\"$1\"\nnotes_refs=\"$(git for-each-ref --format='%(refname)' refs/notes/whogitit refs/notes/whogitit-ns)\"\nif [[ -n \"$notes_refs\" ]]; then\n    if command -v whogitit &> /dev/null; then\n        updates=\"$(cat)\"\n        printf '%s\\n' \"$updates\" | whogitit prepush-scan --remote \"$remote\" --stdin || exit 1\n        whogitit pre-push \"$remote\" \"$2\" || true\n        whogitit push-notes --remote \"$remote\" || true\n        printf '%s\\n' \"$updates\" | whogitit notify --remote \"$remote\" --stdin || true\n    else\n        WHOGITIT_PUSHING_NOTES=1 git push \"$remote\" $notes_refs 2>/dev/null || true\n    fi\nfi\n{}\n",
             WHOGITIT_MARKER_START,
             WHOGITIT_MARKER_END
         );
@@ -371,9 +891,23 @@ fn install_pre_push_hook(hooks_dir: &std::path::Path) -> Result<()> {
 
 remote="$1"
 
-# Only push notes if they exist
-if git notes --ref=whogitit list &>/dev/null; then
-    WHOGITIT_PUSHING_NOTES=1 git push "$remote" refs/notes/whogitit 2>/dev/null || true
+# Only push notes if they exist, warning first if they would disclose prompts.
+# prepush-scan blocks the push if outgoing notes hold secrets or oversized prompts
+# (WHOGITIT_PREPUSH_FORCE=1 overrides). push-notes never blocks the push; failures
+# are printed and shown by 'whogitit status'. notify POSTs a summary of the pushed
+# attribution when notify.url is configured. Namespaced refs
+# (refs/notes/whogitit-ns/*) count as well as the main ref.
+notes_refs="$(git for-each-ref --format='%(refname)' refs/notes/whogitit refs/notes/whogitit-ns)"
+if [[ -n "$notes_refs" ]]; then
+    if command -v whogitit &> /dev/null; then
+        updates="$(cat)"
+        printf '%s\n' "$updates" | whogitit prepush-scan --remote "$remote" --stdin || exit 1
+        whogitit pre-push "$remote" "$2" || true
+        whogitit push-notes --remote "$remote" || true
+        printf '%s\n' "$updates" | whogitit notify --remote "$remote" --stdin || true
+    else
+        WHOGITIT_PUSHING_NOTES=1 git push "$remote" $notes_refs 2>/dev/null || true
+    fi
 fi
 {}
 "#,
@@ -436,6 +970,95 @@ done
     Ok(())
 }
 
+fn install_commit_msg_hook(hooks_dir: &std::path::Path) -> Result<()> {
+    let hook_path = hooks_dir.join("commit-msg");
+
+    if hook_path.exists() {
+        let content = fs::read_to_string(&hook_path)?;
+
+        if content.contains(WHOGITIT_MARKER_START) || content.contains("whogitit commit-msg") {
+            println!("✓ whogitit commit-msg hook already installed.");
+            return Ok(());
+        }
+
+        // Append to existing hook with markers for idempotency
+        let whogitit_section = format!(
+            "\n\n{}\n# whogitit commit-msg hook - Appends AI attribution trailers\nif command -v whogitit &> /dev/null; then\n    whogitit commit-msg \"$1\" 2>/dev/null || true\nfi\n{}\n",
+            WHOGITIT_MARKER_START,
+            WHOGITIT_MARKER_END
+        );
+        let new_content = format!("{}{}", content.trim_end(), whogitit_section);
+        fs::write(&hook_path, new_content)?;
+        println!("✓ Added whogitit to existing commit-msg hook.");
+    } else {
+        let hook_content = format!(
+            r#"#!/bin/bash
+{}
+# whogitit commit-msg hook
+# Appends AI attribution trailers (storage.backend = "trailers" or "both")
+
+if command -v whogitit &> /dev/null; then
+    whogitit commit-msg "$1" 2>/dev/null || true
+elif [[ -x "$HOME/.cargo/bin/whogitit" ]]; then
+    "$HOME/.cargo/bin/whogitit" commit-msg "$1" 2>/dev/null || true
+fi
+{}
+"#,
+            WHOGITIT_MARKER_START, WHOGITIT_MARKER_END
+        );
+        fs::write(&hook_path, hook_content)?;
+        make_executable(&hook_path)?;
+        println!("✓ Installed whogitit commit-msg hook.");
+    }
+
+    Ok(())
+}
+
+fn install_pre_commit_hook(hooks_dir: &std::path::Path) -> Result<()> {
+    let hook_path = hooks_dir.join("pre-commit");
+
+    if hook_path.exists() {
+        let content = fs::read_to_string(&hook_path)?;
+
+        if content.contains(WHOGITIT_MARKER_START) || content.contains("whogitit pre-commit") {
+            println!("✓ whogitit pre-commit hook already installed.");
+            return Ok(());
+        }
+
+        // Append to existing hook with markers for idempotency
+        let whogitit_section = format!(
+            "\n\n{}\n# whogitit pre-commit hook - Lists AI-edited files missing from the index\nif command -v whogitit &> /dev/null; then\n    whogitit pre-commit || true\nfi\n{}\n",
+            WHOGITIT_MARKER_START,
+            WHOGITIT_MARKER_END
+        );
+        let new_content = format!("{}{}", content.trim_end(), whogitit_section);
+        fs::write(&hook_path, new_content)?;
+        println!("✓ Added whogitit to existing pre-commit hook.");
+    } else {
+        let hook_content = format!(
+            r#"#!/bin/bash
+{}
+# whogitit pre-commit hook
+# Lists AI-edited files missing from the index (analysis.pre_commit_reminder = true)
+# Never blocks the commit
+
+if command -v whogitit &> /dev/null; then
+    whogitit pre-commit || true
+elif [[ -x "$HOME/.cargo/bin/whogitit" ]]; then
+    "$HOME/.cargo/bin/whogitit" pre-commit || true
+fi
+{}
+"#,
+            WHOGITIT_MARKER_START, WHOGITIT_MARKER_END
+        );
+        fs::write(&hook_path, hook_content)?;
+        make_executable(&hook_path)?;
+        println!("✓ Installed whogitit pre-commit hook.");
+    }
+
+    Ok(())
+}
+
 /// Make a file executable (Unix only - no-op on Windows)
 #[cfg(unix)]
 fn make_executable(path: &std::path::Path) -> Result<()> {
@@ -456,7 +1079,6 @@ fn make_executable(_path: &std::path::Path) -> Result<()> {
 fn configure_git_fetch(repo: &git2::Repository) -> Result<bool> {
     let mut config = repo.config().context("Failed to open git config")?;
 
-    let fetch_refspec = "+refs/notes/whogitit:refs/notes/whogitit";
     let mut existing_fetch = Vec::new();
     if let Ok(entries) = config.entries(Some("remote.origin.fetch")) {
         entries.for_each(|entry| {
@@ -465,15 +1087,28 @@ fn configure_git_fetch(repo: &git2::Repository) -> Result<bool> {
             }
         })?;
     }
-    let fetch_configured = existing_fetch.iter().any(|v| v.contains("whogitit"));
+    // The main ref and every `[storage] namespace` ref
+    let missing: Vec<String> = [
+        notes_sync::fetch_refspec(),
+        notes_sync::namespace_fetch_refspec(),
+    ]
+    .into_iter()
+    .filter(|refspec| !existing_fetch.contains(refspec))
+    .collect();
 
-    if !fetch_configured {
+    if missing.is_empty() {
+        println!("✓ Git already configured to fetch whogitit notes.");
+        return Ok(false);
+    }
+
+    for fetch_refspec in &missing {
         let result = config.set_multivar("remote.origin.fetch", "^$", fetch_refspec);
         if result.is_err() {
             if existing_fetch.is_empty() {
                 config
                     .set_str("remote.origin.fetch", fetch_refspec)
                     .context("Failed to configure fetch refspec")?;
+                existing_fetch.push(fetch_refspec.clone());
             } else {
                 eprintln!(
                     "whogitit: Warning - unable to add fetch refspec without overwriting existing settings."
@@ -482,13 +1117,9 @@ fn configure_git_fetch(repo: &git2::Repository) -> Result<bool> {
                 return Ok(false);
             }
         }
-        println!("✓ Configured git to fetch whogitit notes automatically.");
-        return Ok(true);
-    } else {
-        println!("✓ Git already configured to fetch whogitit notes.");
     }
-
-    Ok(false)
+    println!("✓ Configured git to fetch whogitit notes automatically.");
+    Ok(true)
 }
 
 /// Add whogitit artifacts to git exclude list to avoid accidental commits
@@ -601,7 +1232,34 @@ mod tests {
         let content = fs::read_to_string(&hook_path).unwrap();
         assert!(content.contains(WHOGITIT_MARKER_START));
         assert!(content.contains("WHOGITIT_PUSHING_NOTES"));
-        assert!(content.contains("refs/notes/whogitit"));
+        assert!(content.contains("refs/notes/whogitit refs/notes/whogitit-ns"));
+        assert!(!content.contains("git notes --ref=whogitit list"));
+        assert!(content.contains("whogitit prepush-scan --remote \"$remote\" --stdin || exit 1"));
+        assert!(content.contains("whogitit notify --remote \"$remote\" --stdin || true"));
+        assert!(content.contains("whogitit pre-push \"$remote\" \"$2\""));
+        assert!(content.contains("whogitit push-notes --remote \"$remote\""));
+    }
+
+    #[test]
+    fn test_configure_git_fetch_includes_namespaced_refs() {
+        let dir = TempDir::new().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+
+        assert!(configure_git_fetch(&repo).unwrap());
+        assert!(!configure_git_fetch(&repo).unwrap());
+
+        let config = repo.config().unwrap();
+        let mut fetch = Vec::new();
+        config
+            .entries(Some("remote.origin.fetch"))
+            .unwrap()
+            .for_each(|entry| fetch.push(entry.value().unwrap().to_string()))
+            .unwrap();
+        assert!(fetch.contains(&"+refs/notes/whogitit:refs/notes/whogitit".to_string()));
+        assert!(fetch.contains(&"+refs/notes/whogitit-ns/*:refs/notes/whogitit-ns/*".to_string()));
+        assert!(fetch.contains(&"+refs/heads/*:refs/remotes/origin/*".to_string()));
     }
 
     #[test]
@@ -662,15 +1320,48 @@ mod tests {
         assert!(content.contains("git notes --ref=whogitit copy"));
     }
 
+    #[test]
+    fn test_write_repo_config_starter_and_template() {
+        let dir = TempDir::new().unwrap();
+        assert!(write_repo_config(dir.path(), None).unwrap());
+        let config = WhogititConfig::load_from_file(&dir.path().join(".whogitit.toml")).unwrap();
+        assert!(config.privacy.enabled);
+        assert_eq!(config.analysis.similarity_threshold, 0.6);
+        // An existing config is never overwritten
+        assert!(!write_repo_config(dir.path(), None).unwrap());
+
+        let repo = TempDir::new().unwrap();
+        let template = dir.path().join("org.toml");
+        fs::write(&template, "[privacy]\naudit_log = true\n").unwrap();
+        let template = template.to_str().unwrap();
+        assert!(write_repo_config(repo.path(), Some(template)).unwrap());
+        let config = WhogititConfig::load_from_file(&repo.path().join(".whogitit.toml")).unwrap();
+        assert!(config.privacy.audit_log);
+
+        let invalid = dir.path().join("bad.toml");
+        fs::write(&invalid, "[privacy]\nenabled = \"yes\"\n").unwrap();
+        let other = TempDir::new().unwrap();
+        assert!(write_repo_config(other.path(), Some(invalid.to_str().unwrap())).is_err());
+        assert!(!other.path().join(".whogitit.toml").exists());
+    }
+
     #[test]
     fn test_init_args_default() {
-        let args = InitArgs { force: false };
+        let args = InitArgs {
+            force: false,
+            with_config: false,
+            from: None,
+        };
         assert!(!args.force);
     }
 
     #[test]
     fn test_init_args_force() {
-        let args = InitArgs { force: true };
+        let args = InitArgs {
+            force: true,
+            with_config: false,
+            from: None,
+        };
         assert!(args.force);
     }
 
@@ -681,6 +1372,8 @@ mod tests {
             file: None,
             tool: None,
             prompt: None,
+            provider: CaptureProvider::ClaudeCode,
+            raw_claude_event: false,
         };
         assert!(args.stdin);
         assert!(args.file.is_none());
@@ -693,10 +1386,34 @@ mod tests {
             file: Some("test.rs".to_string()),
             tool: Some("Edit".to_string()),
             prompt: Some("Fix bug".to_string()),
+            provider: CaptureProvider::ClaudeCode,
+            raw_claude_event: false,
         };
         assert!(!args.stdin);
         assert_eq!(args.file.as_deref(), Some("test.rs"));
         assert_eq!(args.tool.as_deref(), Some("Edit"));
         assert_eq!(args.prompt.as_deref(), Some("Fix bug"));
     }
+
+    #[test]
+    fn test_capture_args_generic_provider() {
+        let cli =
+            Cli::try_parse_from(["whogitit", "capture", "--stdin", "--provider", "generic-v1"])
+                .unwrap();
+        match cli.command {
+            Commands::Capture(args) => assert_eq!(args.provider, CaptureProvider::GenericV1),
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_capture_args_raw_claude_event_requires_stdin() {
+        let cli =
+            Cli::try_parse_from(["whogitit", "capture", "--stdin", "--raw-claude-event"]).unwrap();
+        match cli.command {
+            Commands::Capture(args) => assert!(args.raw_claude_event),
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["whogitit", "capture", "--raw-claude-event"]).is_err());
+    }
 }
@@ -0,0 +1,333 @@
+//! Notify command - POST a summary of pushed attribution to a dashboard
+//!
+//! The pre-push hook pipes git's ref lines into `whogitit notify --stdin`. For the
+//! commits being pushed that carry an attribution note, a compact JSON summary
+//! (line totals and model per commit; no prompts or content) is POSTed with curl
+//! to `--remote-url` or `notify.url`. When the environment variable named by
+//! `notify.secret_env` holds a key (or one was stored with `whogitit auth login
+//! notify`), the body is signed with HMAC-SHA256 in the [`SIGNATURE_HEADER`]
+//! header as `sha256=<hex>`, so the receiver can verify it.
+
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use clap::Args;
+use git2::{Oid, Repository};
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::cli::auth::{self, Service};
+use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::cli::prepush_scan::{outgoing_commits, parse_updates};
+use crate::core::attribution::AIAttribution;
+use crate::privacy::visibility::normalize_remote_url;
+use crate::privacy::WhogititConfig;
+use crate::storage::notes::NotesStore;
+use crate::storage::notes_sync::default_remote;
+use crate::utils::hex;
+
+const NOTIFY_MACHINE_SCHEMA: &str = "whogitit.notify.v1";
+
+/// Request header carrying the body's HMAC-SHA256 signature
+pub const SIGNATURE_HEADER: &str = "X-Whogitit-Signature";
+
+/// Notify command arguments
+#[derive(Debug, Args)]
+pub struct NotifyArgs {
+    /// HTTPS endpoint to POST the summary to (default: notify.url)
+    #[arg(long, value_name = "URL")]
+    pub remote_url: Option<String>,
+
+    /// Remote being pushed to (default: origin, or the only remote)
+    #[arg(long)]
+    pub remote: Option<String>,
+
+    /// Read git's pre-push ref lines from stdin instead of using unpushed HEAD commits
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Print the request instead of sending it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Line totals of one pushed commit
+#[derive(Debug, Serialize)]
+struct CommitSummary {
+    commit: String,
+    model: String,
+    ai_lines: usize,
+    ai_modified_lines: usize,
+    human_lines: usize,
+    original_lines: usize,
+    files: usize,
+}
+
+impl CommitSummary {
+    fn new(oid: Oid, attribution: &AIAttribution) -> Self {
+        Self {
+            commit: oid.to_string(),
+            model: attribution.session.model.id.clone(),
+            ai_lines: attribution.total_ai_lines(),
+            ai_modified_lines: attribution.total_ai_modified_lines(),
+            human_lines: attribution.total_human_lines(),
+            original_lines: attribution.total_original_lines(),
+            files: attribution.files.len(),
+        }
+    }
+}
+
+/// Compact summary of the attributed commits in a push
+fn build_payload(
+    repository: &str,
+    remote: &str,
+    commits: &[(Oid, AIAttribution)],
+    sent_at: &str,
+) -> serde_json::Value {
+    let summaries: Vec<CommitSummary> = commits
+        .iter()
+        .map(|(oid, attribution)| CommitSummary::new(*oid, attribution))
+        .collect();
+    let models: BTreeSet<&str> = summaries.iter().map(|s| s.model.as_str()).collect();
+    json!({
+        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+        "schema": NOTIFY_MACHINE_SCHEMA,
+        "repository": repository,
+        "remote": remote,
+        "sent_at": sent_at,
+        "totals": {
+            "commits": summaries.len(),
+            "ai_lines": summaries.iter().map(|s| s.ai_lines).sum::<usize>(),
+            "ai_modified_lines": summaries.iter().map(|s| s.ai_modified_lines).sum::<usize>(),
+            "human_lines": summaries.iter().map(|s| s.human_lines).sum::<usize>(),
+            "original_lines": summaries.iter().map(|s| s.original_lines).sum::<usize>(),
+        },
+        "models": models,
+        "commits": summaries,
+    })
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+    let key = PKey::hmac(key).context("Failed to create HMAC key")?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(message)?;
+    signer.sign_to_vec().context("Failed to sign notification")
+}
+
+/// Signature header value for a request body
+pub fn sign(secret: &str, body: &[u8]) -> Result<String> {
+    Ok(format!(
+        "sha256={}",
+        hex::encode(&hmac_sha256(secret.as_bytes(), body)?)
+    ))
+}
+
+/// Only HTTPS endpoints, plus plain HTTP on the loopback host for local receivers
+fn validate_endpoint(url: &str) -> Result<()> {
+    if url.starts_with("https://") {
+        return Ok(());
+    }
+    let loopback = url
+        .strip_prefix("http://")
+        .and_then(|rest| rest.split(['/', ':']).next())
+        .is_some_and(|host| host == "localhost" || host == "127.0.0.1");
+    if !loopback {
+        anyhow::bail!("Notify endpoint must be an https:// URL: {}", url);
+    }
+    Ok(())
+}
+
+/// POST the body with curl (no redirects), failing on HTTP errors
+fn post(url: &str, body: &[u8], signature: Option<&str>, timeout_secs: u64) -> Result<()> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--fail", "--silent", "--show-error", "--request", "POST"])
+        .args(["--max-time", &timeout_secs.to_string()])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-"]);
+    if let Some(signature) = signature {
+        command.args(["--header", &format!("{}: {}", SIGNATURE_HEADER, signature)]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl (required to send notifications)")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(body)
+            .context("Failed to send notification body to curl")?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to notify {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Run the notify command
+pub fn run(args: NotifyArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let config = repo
+        .workdir()
+        .map(WhogititConfig::load_or_warn)
+        .unwrap_or_default();
+
+    let Some(endpoint) = args.remote_url.or_else(|| config.notify.url.clone()) else {
+        // The pre-push hook always calls notify; without an endpoint it has nothing to do
+        if args.stdin {
+            return Ok(());
+        }
+        anyhow::bail!("No endpoint; pass --remote-url or set notify.url");
+    };
+    validate_endpoint(&endpoint)?;
+    let Some(remote) = args.remote.or_else(|| default_remote(&repo)) else {
+        anyhow::bail!("No default remote; pass --remote");
+    };
+
+    let updates = if args.stdin {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("Failed to read pre-push input")?;
+        Some(parse_updates(&input))
+    } else {
+        None
+    };
+    let store = NotesStore::new(&repo)?;
+    let mut commits = Vec::new();
+    for oid in outgoing_commits(&repo, &remote, updates.as_deref())? {
+        if let Some(attribution) = store.fetch_attribution(oid)? {
+            commits.push((oid, attribution));
+        }
+    }
+    if commits.is_empty() {
+        if args.dry_run {
+            println!("No attributed commits to report.");
+        }
+        return Ok(());
+    }
+
+    let url = repo
+        .find_remote(&remote)
+        .ok()
+        .and_then(|r| r.url().map(str::to_string))
+        .unwrap_or_else(|| remote.clone());
+    let sent_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    let payload = build_payload(&normalize_remote_url(&url), &remote, &commits, &sent_at);
+    let body = serde_json::to_vec(&payload)?;
+
+    let secret = std::env::var(&config.notify.secret_env)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| auth::stored(Service::Notify).map(|credential| credential.secret));
+    let signature = secret
+        .as_deref()
+        .map(|secret| sign(secret, &body))
+        .transpose()?;
+    if signature.is_none() {
+        eprintln!(
+            "whogitit: Warning - {} is not set and no notify secret is stored; the notification is unsigned",
+            config.notify.secret_env
+        );
+    }
+
+    if args.dry_run {
+        println!("POST {}", endpoint);
+        if let Some(signature) = &signature {
+            println!("{}: {}", SIGNATURE_HEADER, signature);
+        }
+        println!("{}", String::from_utf8_lossy(&body));
+        return Ok(());
+    }
+
+    post(
+        &endpoint,
+        &body,
+        signature.as_deref(),
+        config.notify.timeout_secs,
+    )?;
+    eprintln!(
+        "whogitit: Notified {} of {} attributed commit(s)",
+        endpoint,
+        commits.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_vectors() {
+        assert_eq!(
+            hex::encode(&hmac_sha256(b"Jefe", b"what do ya want for nothing?").unwrap()),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than the block size are hashed first
+        assert_eq!(
+            hex::encode(
+                &hmac_sha256(
+                    &[0xaa; 131],
+                    b"Test Using Larger Than Block-Size Key - Hash Key First"
+                )
+                .unwrap()
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+        assert!(sign("Jefe", b"what do ya want for nothing?")
+            .unwrap()
+            .starts_with("sha256=5bdcc146"));
+    }
+
+    #[test]
+    fn test_payload_summarizes_attributed_commits() {
+        let dir = TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let head = fixture.head();
+        let attribution = NotesStore::new(&fixture.repo)
+            .unwrap()
+            .fetch_attribution(head)
+            .unwrap()
+            .unwrap();
+
+        let payload = build_payload(
+            "github.com/acme/app",
+            "origin",
+            &[(head, attribution)],
+            "2026-01-01T00:00:00Z",
+        );
+        assert_eq!(payload["schema"], NOTIFY_MACHINE_SCHEMA);
+        assert_eq!(payload["totals"]["commits"], 1);
+        assert_eq!(payload["totals"]["ai_lines"], 3);
+        assert_eq!(payload["commits"][0]["commit"], head.to_string());
+        assert_eq!(payload["models"].as_array().unwrap().len(), 1);
+        // Summaries only: no prompt text leaves the machine
+        assert!(!payload.to_string().contains("Add a generated module"));
+    }
+
+    #[test]
+    fn test_endpoint_must_be_https_except_loopback() {
+        assert!(validate_endpoint("https://dash.example.com/hooks/whogitit").is_ok());
+        assert!(validate_endpoint("http://127.0.0.1:8080/hook").is_ok());
+        assert!(validate_endpoint("http://localhost/hook").is_ok());
+        assert!(validate_endpoint("http://dash.example.com/hook").is_err());
+        assert!(validate_endpoint("http://localhost.evil.com/hook").is_err());
+    }
+}
@@ -2,8 +2,9 @@ use clap::ValueEnum;
 use colored::Colorize;
 use serde::Serialize;
 
-use crate::capture::snapshot::LineSource;
-use crate::core::attribution::BlameResult;
+use crate::capture::snapshot::{compute_hash, LineSource};
+use crate::core::attribution::{prompt_hash, BlameResult};
+use crate::privacy::{ContentExposure, PromptExposure, RedactLevel};
 use crate::utils::{truncate, truncate_or_pad};
 
 /// Schema version for machine-readable CLI outputs.
@@ -50,28 +51,140 @@ impl From<&LineSource> for LineSourceOutput {
     }
 }
 
+/// Applies a `--redact-level` preset to command output
+///
+/// Commands route sensitive fields through the sanitizer instead of writing them
+/// directly, so the same preset means the same thing in `export` and `summary`.
+#[derive(Debug, Clone)]
+pub struct OutputSanitizer {
+    name: String,
+    level: RedactLevel,
+}
+
+impl OutputSanitizer {
+    pub fn new(name: &str, level: RedactLevel) -> Self {
+        Self {
+            name: name.to_string(),
+            level,
+        }
+    }
+
+    /// Preset name, recorded in machine output
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Prompt text to write, or `None` when prompts are omitted
+    pub fn prompt(&self, text: &str) -> Option<String> {
+        match self.level.prompts {
+            PromptExposure::Full => Some(text.to_string()),
+            PromptExposure::Truncate => {
+                Some(truncate_prompt_for_export(text, self.level.prompt_max_len))
+            }
+            PromptExposure::Hash => Some(format!("[prompt {}]", prompt_hash(text))),
+            PromptExposure::Omit => None,
+        }
+    }
+
+    /// Whether prompts are left out entirely
+    pub fn omits_prompts(&self) -> bool {
+        self.level.prompts == PromptExposure::Omit
+    }
+
+    /// Commit message, author, path or label to write
+    ///
+    /// Hashing is stable, so hashed values can still be grouped and compared.
+    pub fn content(&self, text: &str) -> String {
+        match self.level.content {
+            ContentExposure::Plain => text.to_string(),
+            ContentExposure::Hash => compute_hash(text),
+        }
+    }
+
+    /// Whether only aggregated counts may be written
+    pub fn aggregate_only(&self) -> bool {
+        self.level.aggregate_only
+    }
+}
+
+/// Truncate a prompt to `max_len` characters, marking the cut with "..."
+pub fn truncate_prompt_for_export(text: &str, max_len: usize) -> String {
+    let char_count = text.chars().count();
+    if char_count <= max_len {
+        return text.to_string();
+    }
+
+    let truncated: String = text.chars().take(max_len).collect();
+    format!("{truncated}...")
+}
+
+/// Width of the `--show-time` column ("YYYY-MM-DD HH:MM")
+const BLAME_TIME_WIDTH: usize = 16;
+
 /// Format blame results for display
-pub fn format_blame(result: &BlameResult, format: OutputFormat) -> String {
+///
+/// `show_time` adds a column with the time AI lines were generated (pretty output only;
+/// JSON always includes `generated_at`).
+pub fn format_blame(result: &BlameResult, format: OutputFormat, show_time: bool) -> String {
     match format {
-        OutputFormat::Pretty => format_blame_pretty(result),
+        OutputFormat::Pretty => format_blame_pretty(result, show_time),
         OutputFormat::Json => format_blame_json(result),
     }
 }
 
-fn format_blame_pretty(result: &BlameResult) -> String {
+/// Render a generation timestamp for the blame time column
+fn format_generated_at(generated_at: Option<&str>) -> String {
+    let formatted = generated_at
+        .map(|ts| match chrono::DateTime::parse_from_rfc3339(ts) {
+            Ok(dt) => dt
+                .with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            Err(_) => truncate(ts, BLAME_TIME_WIDTH),
+        })
+        .unwrap_or_default();
+    format!("{:<width$}", formatted, width = BLAME_TIME_WIDTH)
+}
+
+/// Colored source marker used in blame views
+pub fn source_marker(source: &LineSource) -> String {
+    match source {
+        LineSource::AI { .. } => "●".green().bold().to_string(),
+        LineSource::AIModified { .. } => "◐".yellow().to_string(),
+        LineSource::Human => "+".blue().to_string(),
+        LineSource::Original => "─".dimmed().to_string(),
+        LineSource::Unknown => "?".dimmed().to_string(),
+    }
+}
+
+fn format_blame_pretty(result: &BlameResult, show_time: bool) -> String {
     let mut output = String::new();
 
     // Header
+    let time_header = if show_time {
+        format!(
+            "{} │ ",
+            format!("{:<width$}", "GENERATED", width = BLAME_TIME_WIDTH).dimmed()
+        )
+    } else {
+        String::new()
+    };
     output.push_str(&format!(
-        "\n {} {} │ {} │ {} │ {} │ {}\n",
+        "\n {} {} │ {} │ {} │ {}{} │ {}\n",
         "LINE".dimmed(),
         " ".repeat(2),
         "COMMIT ".dimmed(),
         "AUTHOR     ".dimmed(),
+        time_header,
         "SRC".dimmed(),
         "CODE".dimmed()
     ));
-    output.push_str(&format!("{}\n", "─".repeat(85).dimmed()));
+    let rule_width = if show_time {
+        85 + BLAME_TIME_WIDTH + 3
+    } else {
+        85
+    };
+    output.push_str(&format!("{}\n", "─".repeat(rule_width).dimmed()));
 
     // Lines
     for line in &result.lines {
@@ -79,23 +192,26 @@ fn format_blame_pretty(result: &BlameResult) -> String {
         let commit = &line.commit_short;
         let author = truncate_or_pad(&line.author, 10);
 
-        // Source marker with different symbols for different sources
-        let source_marker = match &line.source {
-            LineSource::AI { .. } => "●".green().bold().to_string(),
-            LineSource::AIModified { .. } => "◐".yellow().to_string(),
-            LineSource::Human => "+".blue().to_string(),
-            LineSource::Original => "─".dimmed().to_string(),
-            LineSource::Unknown => "?".dimmed().to_string(),
-        };
+        let source_marker = source_marker(&line.source);
 
         // Truncate long lines
         let code = truncate(&line.content, 50);
 
+        let time = if show_time {
+            format!(
+                "{} │ ",
+                format_generated_at(line.generated_at.as_deref()).dimmed()
+            )
+        } else {
+            String::new()
+        };
+
         let formatted_line = format!(
-            "{} │ {} │ {} │  {} │ {}\n",
+            "{} │ {} │ {} │ {} {} │ {}\n",
             line_num.dimmed(),
             commit.yellow(),
             author,
+            time,
             source_marker,
             code
         );
@@ -163,6 +279,7 @@ fn format_blame_json(result: &BlameResult) -> String {
                     "index": line.prompt_index,
                     "preview": line.prompt_preview,
                 },
+                "generated_at": line.generated_at,
                 "content": line.content,
             })
         })
@@ -229,6 +346,8 @@ mod tests {
                 },
                 prompt_index: Some(0),
                 prompt_preview: Some("prompt".to_string()),
+                generated_at: Some("2026-03-01T12:00:00Z".to_string()),
+                confidence: None,
             }],
         };
 
@@ -241,5 +360,36 @@ mod tests {
         assert_eq!(parsed["schema"], "whogitit.blame.v1");
         assert_eq!(parsed["lines"][0]["source"]["type"], "ai");
         assert_eq!(parsed["lines"][0]["source"]["edit_id"], "edit-1");
+        assert_eq!(parsed["lines"][0]["generated_at"], "2026-03-01T12:00:00Z");
+    }
+
+    #[test]
+    fn test_sanitizer_builtin_levels() {
+        let level = |name| OutputSanitizer::new(name, RedactLevel::builtin(name).unwrap());
+        let prompt = "Refactor the session store to use a write-ahead log ".repeat(4);
+
+        let full = level("full");
+        assert_eq!(full.prompt(&prompt).as_deref(), Some(prompt.as_str()));
+        assert_eq!(full.content("src/main.rs"), "src/main.rs");
+        assert!(!full.aggregate_only());
+
+        let internal = level("internal");
+        assert!(internal.prompt(&prompt).unwrap().ends_with("..."));
+        let hashed = internal.content("src/main.rs");
+        assert_ne!(hashed, "src/main.rs");
+        assert_eq!(hashed, internal.content("src/main.rs"));
+
+        let external = level("external");
+        assert_eq!(external.prompt(&prompt), None);
+        assert!(external.aggregate_only());
+    }
+
+    #[test]
+    fn test_format_generated_at() {
+        assert_eq!(
+            format_generated_at(Some("2026-03-01T14:30:00+02:00")),
+            "2026-03-01 12:30"
+        );
+        assert_eq!(format_generated_at(None), " ".repeat(BLAME_TIME_WIDTH));
     }
 }
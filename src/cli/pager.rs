@@ -2,24 +2,44 @@
 //!
 //! This command reads git diff output from stdin and annotates it with
 //! AI attribution markers, then passes it through to the default pager.
+//! Input is streamed, so paging starts immediately even on very large diffs.
+//!
+//! External annotators (`--annotator`) add columns next to the attribution:
+//! each is run once per file with the file's shown line ranges on stdin and
+//! answers with labels (see [`ANNOTATOR_PROTOCOL`]). Output is then buffered one
+//! file at a time.
 //!
 //! Usage:
 //!   git config --global core.pager "whogitit pager"
 //!   # or as an alias:
 //!   git config --global alias.ai-diff '!whogitit pager'
 
-use std::collections::HashMap;
-use std::io::{self, BufRead, Write};
-use std::process::{Command, Stdio};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, LineWriter, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
 
 use anyhow::{Context, Result};
 use clap::Args;
 use colored::Colorize;
 use git2::Repository;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 use crate::capture::snapshot::LineSource;
 use crate::core::blame::AIBlamer;
+use crate::utils::truncate;
+
+/// Protocol spoken with `--annotator` commands
+///
+/// The command gets one JSON request on stdin:
+/// `{"protocol": "whogitit.annotator.v1", "path": "src/lib.rs", "ranges": [{"start": 10, "end": 14}]}`
+/// (new-side line numbers, inclusive) and prints one JSON object per label:
+/// `{"line": 12, "label": "uncovered"}`. Lines outside the ranges are ignored.
+pub const ANNOTATOR_PROTOCOL: &str = "whogitit.annotator.v1";
+
+/// Longest label shown, in characters
+const MAX_LABEL_CHARS: usize = 40;
 
 /// Pager command arguments
 #[derive(Debug, Args)]
@@ -35,8 +55,15 @@ pub struct PagerArgs {
     /// Bypass the pager and output directly to stdout
     #[arg(long)]
     pub no_pager: bool,
+
+    /// Command adding a label column (run per file; see `whogitit.annotator.v1`), repeatable
+    #[arg(long = "annotator", value_name = "CMD")]
+    pub annotators: Vec<String>,
 }
 
+/// Files whose attribution is kept in memory while streaming
+const BLAME_CACHE_SIZE: usize = 8;
+
 /// Attribution info for a line
 #[derive(Debug, Clone)]
 struct LineAttribution {
@@ -45,179 +72,421 @@ struct LineAttribution {
     prompt_preview: Option<String>,
 }
 
-/// Run the pager command
-pub fn run(args: PagerArgs) -> Result<()> {
-    // Read diff from stdin
-    let stdin = io::stdin();
-    let lines: Vec<String> = stdin.lock().lines().map_while(Result::ok).collect();
+/// AI-attributed lines of a file, by line number
+type FileAttribution = HashMap<u32, LineAttribution>;
+
+/// Least-recently-used cache of per-file attribution
+///
+/// Diffs touch each file once, in order, so a handful of entries is enough while
+/// keeping memory flat on huge diffs.
+#[derive(Debug)]
+struct BlameCache {
+    capacity: usize,
+    /// Most recently used last
+    entries: VecDeque<(String, Option<FileAttribution>)>,
+}
 
-    // If stdin is empty, just return
-    if lines.is_empty() {
-        return Ok(());
+impl BlameCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
     }
 
-    // Try to open repository for attribution lookup
-    let repo = Repository::discover(".").ok();
-    let mut blamer = repo.as_ref().and_then(|r| AIBlamer::new(r).ok());
-
-    // Parse diff and build attribution map
-    let attribution_map = if let Some(ref mut b) = blamer {
-        build_attribution_map(&lines, b)
-    } else {
-        HashMap::new()
-    };
+    /// Cached attribution for a file, loading it on a miss (None if it can't be blamed)
+    fn get_or_load(
+        &mut self,
+        path: &str,
+        load: impl FnOnce() -> Option<FileAttribution>,
+    ) -> Option<&FileAttribution> {
+        match self.entries.iter().position(|(p, _)| p == path) {
+            Some(index) => {
+                let entry = self.entries.remove(index).expect("index in range");
+                self.entries.push_back(entry);
+            }
+            None => {
+                if self.entries.len() == self.capacity {
+                    self.entries.pop_front();
+                }
+                self.entries.push_back((path.to_string(), load()));
+            }
+        }
+        self.entries.back().and_then(|(_, attrs)| attrs.as_ref())
+    }
+}
 
-    // Annotate the diff output
-    let annotated = annotate_diff(&lines, &attribution_map, &args);
+/// Streaming diff annotator: feed diff lines in order, get annotated lines back
+///
+/// Blame runs lazily, the first time an added line of a file is seen, so files
+/// without additions (and output before them) cost nothing.
+struct DiffAnnotator<'a, 'r> {
+    blamer: Option<&'a mut AIBlamer<'r>>,
+    cache: BlameCache,
+    file_header_re: Regex,
+    hunk_re: Regex,
+    current_file: Option<String>,
+    current_line: u32,
+}
 
-    // Output through pager or directly
-    if args.no_pager || !atty::is(atty::Stream::Stdout) {
-        // Direct output
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        for line in annotated {
-            writeln!(handle, "{}", line)?;
+impl<'a, 'r> DiffAnnotator<'a, 'r> {
+    fn new(blamer: Option<&'a mut AIBlamer<'r>>) -> Self {
+        Self {
+            blamer,
+            cache: BlameCache::new(BLAME_CACHE_SIZE),
+            file_header_re: Regex::new(r"^\+\+\+ b/(.+)$").unwrap(),
+            hunk_re: Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@").unwrap(),
+            current_file: None,
+            current_line: 0,
         }
-    } else {
-        // Use pager
-        output_through_pager(&annotated)?;
     }
 
-    Ok(())
-}
+    /// Annotate one diff line, updating file and line tracking
+    fn annotate(&mut self, line: &str, args: &PagerArgs) -> String {
+        self.annotate_numbered(line, args).0
+    }
 
-/// Build a map of file:line -> attribution by parsing diff hunks
-fn build_attribution_map(
-    diff_lines: &[String],
-    blamer: &mut AIBlamer,
-) -> HashMap<(String, u32), LineAttribution> {
-    let mut map = HashMap::new();
-
-    // Regex to match diff file headers
-    let file_header_re = Regex::new(r"^\+\+\+ b/(.+)$").unwrap();
-    // Regex to match hunk headers: @@ -start,count +start,count @@
-    let hunk_re = Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@").unwrap();
-
-    let mut current_file: Option<String> = None;
-    let mut current_line: u32 = 0;
-
-    // Cache of blame results per file
-    let mut blame_cache: HashMap<String, Vec<(u32, LineAttribution)>> = HashMap::new();
-
-    for line in diff_lines {
-        // Check for new file
-        if let Some(caps) = file_header_re.captures(line) {
-            let file_path = caps.get(1).map(|m| m.as_str().to_string());
-            current_file = file_path.clone();
-
-            // Prefetch blame for this file
-            if let Some(ref path) = current_file {
-                if !blame_cache.contains_key(path) {
-                    if let Ok(blame_result) = blamer.blame(path, None) {
-                        let attrs: Vec<(u32, LineAttribution)> = blame_result
-                            .lines
-                            .into_iter()
-                            .map(|l| {
-                                (
-                                    l.line_number,
-                                    LineAttribution {
-                                        source: l.source,
-                                        prompt_preview: l.prompt_preview,
-                                    },
-                                )
-                            })
-                            .collect();
-                        blame_cache.insert(path.clone(), attrs);
-                    }
-                }
-            }
-            continue;
+    /// Annotate one diff line, with its new-side line number for added and context lines
+    fn annotate_numbered(&mut self, line: &str, args: &PagerArgs) -> (String, Option<u32>) {
+        if let Some(caps) = self.file_header_re.captures(line) {
+            self.current_file = caps.get(1).map(|m| m.as_str().to_string());
+            return (line.to_string(), None);
         }
 
-        // Check for hunk header
-        if let Some(caps) = hunk_re.captures(line) {
+        if let Some(caps) = self.hunk_re.captures(line) {
             if let Some(start) = caps.get(1) {
-                current_line = start.as_str().parse().unwrap_or(1);
+                self.current_line = start.as_str().parse().unwrap_or(1);
             }
-            continue;
+            return (line.to_string(), None);
         }
 
-        // Track line numbers for added lines
-        if let Some(ref file) = current_file {
-            if line.starts_with('+') && !line.starts_with("+++") {
-                // This is an added line
-                if let Some(file_attrs) = blame_cache.get(file) {
-                    if let Some((_, attr)) = file_attrs.iter().find(|(ln, _)| *ln == current_line) {
-                        map.insert((file.clone(), current_line), attr.clone());
-                    }
-                }
-                current_line += 1;
-            } else if line.starts_with('-') && !line.starts_with("---") {
-                // Deleted line - don't increment current_line
-            } else if !line.starts_with('\\') {
-                // Context line
-                current_line += 1;
+        if line.starts_with('+') && !line.starts_with("+++") {
+            let line_number = self.current_line;
+            self.current_line += 1;
+            if let Some(attr) = self.lookup(line_number) {
+                return (annotate_added_line(line, &attr, args), Some(line_number));
             }
+            return (line.to_string(), Some(line_number));
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            // Deleted line - no increment
+        } else if line.starts_with(' ') {
+            // Context line
+            self.current_line += 1;
+            return (line.to_string(), Some(self.current_line - 1));
+        } else if !line.starts_with('\\') {
+            self.current_line += 1;
         }
+
+        (line.to_string(), None)
     }
 
-    map
+    fn lookup(&mut self, line_number: u32) -> Option<LineAttribution> {
+        let path = self.current_file.as_deref()?;
+        let blamer = &mut self.blamer;
+        self.cache
+            .get_or_load(path, || load_file_attribution(blamer.as_deref_mut()?, path))?
+            .get(&line_number)
+            .cloned()
+    }
 }
 
-/// Annotate diff lines with AI attribution markers
-fn annotate_diff(
-    diff_lines: &[String],
-    attribution_map: &HashMap<(String, u32), LineAttribution>,
-    args: &PagerArgs,
-) -> Vec<String> {
-    let mut result = Vec::with_capacity(diff_lines.len());
-
-    // Regex patterns
-    let file_header_re = Regex::new(r"^\+\+\+ b/(.+)$").unwrap();
-    let hunk_re = Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@").unwrap();
-
-    let mut current_file: Option<String> = None;
-    let mut current_line: u32 = 0;
-
-    for line in diff_lines {
-        // Check for file header
-        if let Some(caps) = file_header_re.captures(line) {
-            current_file = caps.get(1).map(|m| m.as_str().to_string());
-            result.push(line.clone());
-            continue;
+/// Inclusive range of new-side line numbers sent to annotators
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+struct LineRange {
+    start: u32,
+    end: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct AnnotatorRequest<'a> {
+    protocol: &'static str,
+    path: &'a str,
+    ranges: &'a [LineRange],
+}
+
+#[derive(Debug, Deserialize)]
+struct AnnotatorLabel {
+    line: u32,
+    label: String,
+}
+
+/// Runs of consecutive line numbers
+fn line_ranges(numbers: impl IntoIterator<Item = u32>) -> Vec<LineRange> {
+    let mut numbers: Vec<u32> = numbers.into_iter().collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    let mut ranges: Vec<LineRange> = Vec::new();
+    for n in numbers {
+        match ranges.last_mut() {
+            Some(range) if range.end + 1 == n => range.end = n,
+            _ => ranges.push(LineRange { start: n, end: n }),
         }
+    }
+    ranges
+}
 
-        // Check for hunk header
-        if let Some(caps) = hunk_re.captures(line) {
-            if let Some(start) = caps.get(1) {
-                current_line = start.as_str().parse().unwrap_or(1);
+/// An `--annotator` command
+///
+/// A command that fails or answers with something other than labels is reported
+/// once and then skipped, so a broken annotator never breaks the diff.
+struct ExternalAnnotator {
+    command: String,
+    dir: Option<PathBuf>,
+    disabled: bool,
+}
+
+impl ExternalAnnotator {
+    fn new(command: &str, dir: Option<PathBuf>) -> Self {
+        Self {
+            command: command.to_string(),
+            dir,
+            disabled: false,
+        }
+    }
+
+    /// Labels for lines of `path` within `ranges`
+    fn labels(&mut self, path: &str, ranges: &[LineRange]) -> HashMap<u32, String> {
+        if self.disabled || ranges.is_empty() {
+            return HashMap::new();
+        }
+        match self.run(path, ranges) {
+            Ok(labels) => labels,
+            Err(err) => {
+                eprintln!(
+                    "whogitit: Warning - annotator '{}' skipped: {:#}",
+                    self.command, err
+                );
+                self.disabled = true;
+                HashMap::new()
             }
-            result.push(line.clone());
-            continue;
         }
+    }
 
-        // Check for added lines that might have attribution
-        if line.starts_with('+') && !line.starts_with("+++") {
-            if let Some(ref file) = current_file {
-                if let Some(attr) = attribution_map.get(&(file.clone(), current_line)) {
-                    let annotated = annotate_added_line(line, attr, args);
-                    result.push(annotated);
-                    current_line += 1;
-                    continue;
-                }
+    fn run(&self, path: &str, ranges: &[LineRange]) -> Result<HashMap<u32, String>> {
+        let request = serde_json::to_string(&AnnotatorRequest {
+            protocol: ANNOTATOR_PROTOCOL,
+            path,
+            ranges,
+        })?;
+        let mut command = shell_command(&self.command);
+        if let Some(dir) = &self.dir {
+            command.current_dir(dir);
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .context("Failed to start")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // The command may not read its input; that is not an error
+            let _ = writeln!(stdin, "{}", request);
+        }
+        let output = child.wait_with_output().context("Failed to run")?;
+        if !output.status.success() {
+            anyhow::bail!("exited with {}", output.status);
+        }
+
+        let mut labels = HashMap::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let label: AnnotatorLabel = serde_json::from_str(line)
+                .with_context(|| format!("Invalid label line: {}", truncate(line, 60)))?;
+            let shown = ranges
+                .iter()
+                .any(|r| (r.start..=r.end).contains(&label.line));
+            if shown {
+                // Labels come from another program; keep terminal control out
+                let text: String = label.label.chars().filter(|c| !c.is_control()).collect();
+                labels.insert(label.line, truncate(&text, MAX_LABEL_CHARS));
             }
-            current_line += 1;
-        } else if line.starts_with('-') && !line.starts_with("---") {
-            // Deleted line - no increment
-        } else if !line.starts_with('\\') {
-            // Context line
-            current_line += 1;
         }
+        Ok(labels)
+    }
+}
 
-        result.push(line.clone());
+/// Run a command line through the platform shell
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
     }
+}
 
-    result
+/// Append annotator labels to a rendered line
+fn with_labels(line: String, labels: &[Option<&String>], args: &PagerArgs) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .flatten()
+        .map(|label| format!("[{}]", label))
+        .collect();
+    if labels.is_empty() {
+        return line;
+    }
+    let column = labels.join(" ");
+    if args.no_color {
+        format!("{}  {}", line, column)
+    } else {
+        format!("{}  {}", line, column.cyan())
+    }
+}
+
+/// AI and AI-modified lines of a file at HEAD
+fn load_file_attribution(blamer: &mut AIBlamer, path: &str) -> Option<FileAttribution> {
+    let blame_result = blamer.blame(path, None).ok()?;
+    Some(
+        blame_result
+            .lines
+            .into_iter()
+            .filter(|l| {
+                matches!(
+                    l.source,
+                    LineSource::AI { .. } | LineSource::AIModified { .. }
+                )
+            })
+            .map(|l| {
+                (
+                    l.line_number,
+                    LineAttribution {
+                        source: l.source,
+                        prompt_preview: l.prompt_preview,
+                    },
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Run the pager command
+///
+/// Output starts as soon as the first line arrives: stdin is annotated line by
+/// line and written straight to the pager (or stdout).
+pub fn run(args: PagerArgs) -> Result<()> {
+    let repo = Repository::discover(".").ok();
+    let mut blamer = repo.as_ref().and_then(|r| AIBlamer::new(r).ok());
+    let mut annotator = DiffAnnotator::new(blamer.as_mut());
+    let workdir = repo
+        .as_ref()
+        .and_then(|r| r.workdir())
+        .map(|d| d.to_path_buf());
+    let mut externals: Vec<ExternalAnnotator> = args
+        .annotators
+        .iter()
+        .map(|command| ExternalAnnotator::new(command, workdir.clone()))
+        .collect();
+
+    let mut pager = None;
+    let sink: Box<dyn Write> = if args.no_pager || !atty::is(atty::Stream::Stdout) {
+        Box::new(io::stdout().lock())
+    } else {
+        let mut child = spawn_pager()?;
+        let stdin = child.stdin.take().context("Failed to open pager input")?;
+        pager = Some(child);
+        Box::new(stdin)
+    };
+    let mut out = LineWriter::new(sink);
+
+    let result = if externals.is_empty() {
+        stream_diff(io::stdin().lock(), &mut out, &mut annotator, &args)
+    } else {
+        stream_diff_by_file(
+            io::stdin().lock(),
+            &mut out,
+            &mut annotator,
+            &mut externals,
+            &args,
+        )
+    };
+    drop(out);
+    if let Some(mut child) = pager {
+        child.wait().context("Pager failed")?;
+    }
+
+    match result {
+        // The reader quit the pager before the end of the diff
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other.context("Failed to write diff output"),
+    }
+}
+
+/// Annotate a diff from `input` into `out`, one line at a time
+fn stream_diff(
+    mut input: impl BufRead,
+    out: &mut impl Write,
+    annotator: &mut DiffAnnotator,
+    args: &PagerArgs,
+) -> io::Result<()> {
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if input.read_until(b'\n', &mut buf)? == 0 {
+            return Ok(());
+        }
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches('\n').trim_end_matches('\r');
+        writeln!(out, "{}", annotator.annotate(line, args))?;
+    }
+}
+
+/// Annotate a diff with external annotator columns, one file section at a time
+///
+/// Each section (from one `diff --git` line to the next) is held back until its
+/// annotators have answered.
+fn stream_diff_by_file(
+    mut input: impl BufRead,
+    out: &mut impl Write,
+    annotator: &mut DiffAnnotator,
+    externals: &mut [ExternalAnnotator],
+    args: &PagerArgs,
+) -> io::Result<()> {
+    let mut section: Vec<(String, Option<u32>)> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let done = input.read_until(b'\n', &mut buf)? == 0;
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches('\n').trim_end_matches('\r');
+        if done || line.starts_with("diff --git ") {
+            let path = annotator.current_file.clone();
+            write_section(out, &mut section, path.as_deref(), externals, args)?;
+        }
+        if done {
+            return Ok(());
+        }
+        section.push(annotator.annotate_numbered(line, args));
+    }
+}
+
+/// Write a buffered file section with its annotator labels
+fn write_section(
+    out: &mut impl Write,
+    section: &mut Vec<(String, Option<u32>)>,
+    path: Option<&str>,
+    externals: &mut [ExternalAnnotator],
+    args: &PagerArgs,
+) -> io::Result<()> {
+    let ranges = line_ranges(section.iter().filter_map(|(_, n)| *n));
+    let labels: Vec<HashMap<u32, String>> = match path {
+        Some(path) => externals
+            .iter_mut()
+            .map(|e| e.labels(path, &ranges))
+            .collect(),
+        None => Vec::new(),
+    };
+    for (text, number) in section.drain(..) {
+        let line_labels: Vec<Option<&String>> = match number {
+            Some(n) => labels.iter().map(|l| l.get(&n)).collect(),
+            None => Vec::new(),
+        };
+        writeln!(out, "{}", with_labels(text, &line_labels, args))?;
+    }
+    Ok(())
 }
 
 /// Annotate a single added line with AI attribution
@@ -274,9 +543,9 @@ fn annotate_added_line(line: &str, attr: &LineAttribution, args: &PagerArgs) ->
     }
 }
 
-/// Output through the system pager (less, more, etc.)
-fn output_through_pager(lines: &[String]) -> Result<()> {
-    // Try to use the user's preferred pager, falling back to less, then more
+/// Spawn the system pager (less, more, etc.) reading from a pipe
+fn spawn_pager() -> Result<Child> {
+    // Try to use the user's preferred pager, falling back to less
     let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
 
     // For less, add -R flag to handle colors
@@ -286,21 +555,11 @@ fn output_through_pager(lines: &[String]) -> Result<()> {
         (pager.as_str(), vec![])
     };
 
-    let mut child = Command::new(cmd)
+    Command::new(cmd)
         .args(&args)
         .stdin(Stdio::piped())
         .spawn()
-        .context("Failed to spawn pager")?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        for line in lines {
-            writeln!(stdin, "{}", line)?;
-        }
-    }
-
-    child.wait().context("Pager failed")?;
-
-    Ok(())
+        .context("Failed to spawn pager")
 }
 
 #[cfg(test)]
@@ -339,6 +598,7 @@ mod tests {
             no_color: true,
             verbose: false,
             no_pager: true,
+            annotators: vec![],
         };
 
         let result = annotate_added_line("+    let x = 42;", &attr, &args);
@@ -359,10 +619,128 @@ mod tests {
             no_color: true,
             verbose: true,
             no_pager: true,
+            annotators: vec![],
         };
 
         let result = annotate_added_line("+    let y = 99;", &attr, &args);
         assert!(result.contains("◐"));
         assert!(result.contains("AI-mod(85%)"));
     }
+
+    fn ai_line(edit_id: &str) -> LineAttribution {
+        LineAttribution {
+            source: LineSource::AI {
+                edit_id: edit_id.to_string(),
+            },
+            prompt_preview: None,
+        }
+    }
+
+    #[test]
+    fn test_stream_diff_annotates_added_lines_in_order() {
+        let args = PagerArgs {
+            no_color: true,
+            verbose: false,
+            no_pager: true,
+            annotators: vec![],
+        };
+        let mut annotator = DiffAnnotator::new(None);
+        annotator
+            .cache
+            .get_or_load("src/lib.rs", || Some(HashMap::from([(11, ai_line("e1"))])));
+
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n\
+                    --- a/src/lib.rs\n\
+                    +++ b/src/lib.rs\n\
+                    @@ -10,2 +10,3 @@\n \
+                    context\n\
+                    +ai line\n\
+                    +human line\n\
+                    -removed\n";
+        let mut out = Vec::new();
+        stream_diff(diff.as_bytes(), &mut out, &mut annotator, &args).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[5], "● +ai line  # AI");
+        assert_eq!(lines[6], "+human line");
+        assert_eq!(lines[7], "-removed");
+    }
+
+    #[test]
+    fn test_blame_cache_evicts_least_recently_used() {
+        let mut cache = BlameCache::new(2);
+        let mut loads = 0;
+        let mut load = |cache: &mut BlameCache, path: &str| {
+            cache.get_or_load(path, || {
+                loads += 1;
+                Some(HashMap::new())
+            });
+        };
+
+        load(&mut cache, "a");
+        load(&mut cache, "b");
+        load(&mut cache, "a"); // hit, "b" becomes least recent
+        load(&mut cache, "c"); // evicts "b"
+        load(&mut cache, "a"); // hit
+        load(&mut cache, "b"); // reloaded
+        assert_eq!(loads, 4);
+    }
+
+    #[test]
+    fn test_line_ranges_merges_consecutive_lines() {
+        assert_eq!(
+            line_ranges([12, 10, 11, 20, 11]),
+            vec![
+                LineRange { start: 10, end: 12 },
+                LineRange { start: 20, end: 20 }
+            ]
+        );
+        assert!(line_ranges([]).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_annotator_labels_shown_lines() {
+        let args = PagerArgs {
+            no_color: true,
+            verbose: false,
+            no_pager: true,
+            annotators: vec![],
+        };
+        let mut annotator = DiffAnnotator::new(None);
+        // Echo the request's path back on line 11, plus a label outside the diff
+        let command = r#"path=$(sed 's/.*"path":"\([^"]*\)".*/\1/'); printf '{"line":11,"label":"owner: %s"}\n{"line":99,"label":"hidden"}\n' "$path""#;
+        let mut externals = vec![ExternalAnnotator::new(command, None)];
+
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n\
+                    --- a/src/lib.rs\n\
+                    +++ b/src/lib.rs\n\
+                    @@ -10,2 +10,2 @@\n \
+                    context\n\
+                    +added\n\
+                    -removed\n";
+        let mut out = Vec::new();
+        stream_diff_by_file(
+            diff.as_bytes(),
+            &mut out,
+            &mut annotator,
+            &mut externals,
+            &args,
+        )
+        .unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[4], " context");
+        assert_eq!(lines[5], "+added  [owner: src/lib.rs]");
+        assert!(!out.contains("hidden"));
+
+        let mut failing = ExternalAnnotator::new("exit 3", None);
+        let ranges = [LineRange { start: 1, end: 1 }];
+        assert!(failing.labels("a.rs", &ranges).is_empty());
+        assert!(failing.disabled);
+    }
 }
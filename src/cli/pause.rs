@@ -0,0 +1,129 @@
+//! Pause and resume commands - suspend capture during sensitive work
+//!
+//! `whogitit pause` writes `.git/whogitit/pause.json`, which every capture entry
+//! point checks before recording anything; `whogitit resume` removes it. See
+//! [`crate::capture::pause`].
+
+use anyhow::{Context, Result};
+use chrono::Duration;
+use clap::Args;
+use colored::Colorize;
+use git2::Repository;
+
+use crate::capture::pause::{self, CapturePause};
+
+/// Pause command arguments
+#[derive(Debug, Args)]
+pub struct PauseArgs {
+    /// Resume automatically after this long (e.g. 30m, 2h, 1d, 1h30m); default: until resumed
+    #[arg(long = "for", value_name = "DURATION", value_parser = parse_duration)]
+    pub duration: Option<Duration>,
+
+    /// Why capture is paused, recorded in the audit log
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
+/// Parse a duration such as `90s`, `30m`, `2h`, `1d` or `1h30m`
+fn parse_duration(value: &str) -> Result<Duration> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid duration '{}'. Use a number with s, m, h or d, e.g. 30m, 2h or 1h30m.",
+            value
+        )
+    };
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for c in value.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: i64 = digits.parse().map_err(|_| invalid())?;
+        digits.clear();
+        total += match c {
+            's' => Duration::seconds(n),
+            'm' => Duration::minutes(n),
+            'h' => Duration::hours(n),
+            'd' => Duration::days(n),
+            _ => return Err(invalid()),
+        };
+    }
+    if !digits.is_empty() || total <= Duration::zero() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// Run the pause command
+pub fn run_pause(args: PauseArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+
+    let replaced = CapturePause::path(repo.path()).exists();
+    let pause = CapturePause::new(args.duration, args.reason);
+    pause.save(repo.path())?;
+    pause::audit_pause(workdir, &pause);
+
+    println!("{} capture {}", "Paused".yellow().bold(), pause.window());
+    if replaced {
+        println!("Replaced the previous pause.");
+    }
+    println!("Prompts and file content are not recorded; edits made meanwhile count as human.");
+    if pause.until.is_none() {
+        println!("Run 'whogitit resume' when done.");
+    }
+    Ok(())
+}
+
+/// Run the resume command
+pub fn run_resume() -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+
+    let path = CapturePause::path(repo.path());
+    if !path.exists() {
+        println!("Capture is not paused.");
+        return Ok(());
+    }
+    match CapturePause::remove(repo.path())? {
+        Some(pause) => {
+            pause::audit_resume(workdir, &pause, "whogitit resume");
+            println!(
+                "{} capture (paused since {})",
+                "Resumed".green().bold(),
+                pause.paused_at
+            );
+        }
+        None => println!(
+            "{} capture (removed an unreadable pause marker)",
+            "Resumed".green().bold()
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h").unwrap(), Duration::hours(2));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::days(1));
+        assert_eq!(
+            parse_duration("1h30m").unwrap(),
+            Duration::hours(1) + Duration::minutes(30)
+        );
+        assert_eq!(parse_duration("90s").unwrap(), Duration::seconds(90));
+
+        for bad in ["", "2", "h", "2x", "0m", "-1h", "1.5h"] {
+            assert!(parse_duration(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Policy command - evaluate attribution policy rules over a commit range
+//!
+//! Thin wrapper around [`crate::core::policy::evaluate`]; `--format json` prints
+//! the `whogitit.policy.v1` report as-is for bots and merge queues. Also runs as
+//! `whogitit check`, the CI gate for the `[policy]` section.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::Repository;
+
+use crate::cli::output::OutputFormat;
+use crate::core::policy::{self, PolicyOptions, PolicyReport, Severity};
+use crate::privacy::WhogititConfig;
+use crate::utils::SHORT_COMMIT_LEN;
+
+/// Policy command arguments
+#[derive(Debug, Args)]
+pub struct PolicyArgs {
+    /// Base commit (exclusive) - defaults to first commit if not specified
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// Head commit (inclusive) - defaults to HEAD
+    #[arg(long, default_value = "HEAD")]
+    pub head: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+
+    /// Report commits that change source files without attribution as errors
+    #[arg(long)]
+    pub require_attribution: bool,
+}
+
+/// Run the policy command; fails when any violation has error severity
+pub fn run(args: PolicyArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let config = repo
+        .workdir()
+        .map(WhogititConfig::load_or_warn)
+        .unwrap_or_default();
+
+    let options = PolicyOptions {
+        require_attribution: args.require_attribution,
+    };
+    let report = policy::evaluate(&repo, &config, args.base.as_deref(), &args.head, options)?;
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&report),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    if !report.passed {
+        anyhow::bail!(
+            "{} policy violation(s) with error severity",
+            report.count(Severity::Error)
+        );
+    }
+    Ok(())
+}
+
+fn print_pretty(report: &PolicyReport) {
+    println!(
+        "Policy check: {} commit(s) up to {}",
+        report.commits_evaluated,
+        &report.head[..report.head.len().min(SHORT_COMMIT_LEN)]
+    );
+    println!();
+
+    if report.violations.is_empty() {
+        println!("{}", "No violations.".green());
+        return;
+    }
+
+    for violation in &report.violations {
+        let severity = match violation.severity {
+            Severity::Error => "error".red().bold(),
+            Severity::Warning => "warning".yellow().bold(),
+            Severity::Info => "info".cyan().bold(),
+        };
+        let location = match (&violation.path, &violation.commit) {
+            (Some(path), _) => {
+                let lines: Vec<String> = violation
+                    .lines
+                    .iter()
+                    .map(|r| {
+                        if r.start == r.end {
+                            r.start.to_string()
+                        } else {
+                            format!("{}-{}", r.start, r.end)
+                        }
+                    })
+                    .collect();
+                if lines.is_empty() {
+                    path.clone()
+                } else {
+                    format!("{}:{}", path, lines.join(","))
+                }
+            }
+            (None, Some(commit)) => commit[..commit.len().min(SHORT_COMMIT_LEN)].to_string(),
+            (None, None) => String::new(),
+        };
+        println!("{} [{}] {}", severity, violation.rule_id, location.bold());
+        println!("    {}", violation.evidence.dimmed());
+    }
+
+    println!();
+    println!(
+        "{} error(s), {} warning(s), {} info",
+        report.count(Severity::Error),
+        report.count(Severity::Warning),
+        report.count(Severity::Info)
+    );
+}
@@ -0,0 +1,381 @@
+//! Prepush-scan command - block pushes whose attribution notes leak secrets or huge prompts
+//!
+//! The pre-push hook runs `whogitit prepush-scan` before `push-notes`. Since
+//! `push-notes` pushes whole notes refs, every note in every local notes ref is
+//! checked as stored, including notes rewritten on commits pushed long ago,
+//! against the current redaction patterns (which may have gained patterns since
+//! the notes were written) and `privacy.max_push_prompt_bytes`. Any finding fails
+//! the command, and with it the push, unless `--force` or
+//! `WHOGITIT_PREPUSH_FORCE=1` is given.
+
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::{Oid, Repository};
+
+use crate::core::attribution::AIAttribution;
+use crate::privacy::{Redactor, WhogititConfig};
+use crate::storage::note_format;
+use crate::storage::notes::NOTES_REF;
+use crate::storage::notes_push::local_notes_refs;
+use crate::storage::notes_sync::default_remote;
+
+/// Environment variable that turns findings into warnings (`--force` for the hook)
+pub const FORCE_ENV: &str = "WHOGITIT_PREPUSH_FORCE";
+
+/// Prepush-scan command arguments
+#[derive(Debug, Args)]
+pub struct PrepushScanArgs {
+    /// Remote being pushed to (default: origin, or the only remote)
+    #[arg(long)]
+    pub remote: Option<String>,
+
+    /// Consume git's pre-push ref lines from stdin (the scan covers every note either way)
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Report findings without blocking the push (also WHOGITIT_PREPUSH_FORCE=1)
+    #[arg(long)]
+    pub force: bool,
+
+    /// Largest prompt allowed in a note, in bytes (default: privacy.max_push_prompt_bytes)
+    #[arg(long, value_name = "BYTES")]
+    pub max_prompt_bytes: Option<usize>,
+}
+
+/// A problem found in an outgoing note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// Text matching a redaction pattern
+    Secret {
+        /// Where in the note: "prompt #N", "session label" or "path:line"
+        location: String,
+        pattern: String,
+    },
+    /// Prompt text over the size limit
+    OversizedPrompt { index: u32, bytes: usize },
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::Secret { location, pattern } => write!(f, "{} matches {}", location, pattern),
+            Finding::OversizedPrompt { index, bytes } => {
+                write!(f, "prompt #{} is {} bytes", index, bytes)
+            }
+        }
+    }
+}
+
+/// Check one note for secrets and, when prompts are pushed, oversized prompts
+pub fn scan_note(
+    attribution: &AIAttribution,
+    redactor: &Redactor,
+    check_prompts: bool,
+    max_prompt_bytes: usize,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut secrets = |location: String, text: &str| {
+        for pattern in redactor.matching_pattern_names(text) {
+            findings.push(Finding::Secret {
+                location: location.clone(),
+                pattern: pattern.to_string(),
+            });
+        }
+    };
+
+    if let Some(label) = &attribution.session.label {
+        secrets("session label".to_string(), label);
+    }
+    if check_prompts {
+        for prompt in &attribution.prompts {
+            secrets(format!("prompt #{}", prompt.index), &prompt.text);
+        }
+    }
+    for file in &attribution.files {
+        for line in file.lines.iter().filter(|l| !l.content.is_empty()) {
+            secrets(format!("{}:{}", file.path, line.line_number), &line.content);
+        }
+    }
+
+    if check_prompts {
+        findings.extend(
+            attribution
+                .prompts
+                .iter()
+                .filter(|p| p.text.len() > max_prompt_bytes)
+                .map(|p| Finding::OversizedPrompt {
+                    index: p.index,
+                    bytes: p.text.len(),
+                }),
+        );
+    }
+    findings
+}
+
+/// Local commits of a pre-push update, with the remote commit they replace
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RefUpdate {
+    local: Oid,
+    remote: Option<Oid>,
+}
+
+/// Parse git's pre-push lines: `<local ref> <local sha> <remote ref> <remote sha>`
+///
+/// Deletions (zero local sha) and pushes of notes refs carry no commits to scan.
+pub(crate) fn parse_updates(input: &str) -> Vec<RefUpdate> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [local_ref, local, _remote_ref, remote] = fields[..] else {
+                return None;
+            };
+            let local = Oid::from_str(local).ok().filter(|o| !o.is_zero())?;
+            if local_ref.starts_with("refs/notes/") {
+                return None;
+            }
+            let remote = Oid::from_str(remote).ok().filter(|o| !o.is_zero());
+            Some(RefUpdate { local, remote })
+        })
+        .collect()
+}
+
+/// Commits being pushed: reachable from the updates (or HEAD) but not from the remote
+pub(crate) fn outgoing_commits(
+    repo: &Repository,
+    remote: &str,
+    updates: Option<&[RefUpdate]>,
+) -> Result<Vec<Oid>> {
+    let mut revwalk = repo.revwalk()?;
+    match updates {
+        Some(updates) => {
+            for update in updates {
+                revwalk.push(update.local)?;
+                if let Some(oid) = update.remote.filter(|o| repo.find_commit(*o).is_ok()) {
+                    revwalk.hide(oid)?;
+                }
+            }
+            if updates.is_empty() {
+                return Ok(Vec::new());
+            }
+        }
+        None => revwalk.push_head().context("HEAD has no commits")?,
+    }
+    // Remote URLs have no tracking refs; the glob then matches nothing.
+    revwalk.hide_glob(&format!("refs/remotes/{}/*", remote))?;
+    revwalk
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to walk outgoing commits")
+}
+
+/// Findings in one note of one notes ref
+#[derive(Debug)]
+struct FlaggedNote {
+    notes_ref: String,
+    commit: Oid,
+    findings: Vec<Finding>,
+}
+
+/// Findings for every note `push-notes` would push, in each local notes ref
+fn scan_notes(
+    repo: &Repository,
+    redactor: &Redactor,
+    check_prompts: bool,
+    max_prompt_bytes: usize,
+) -> Result<Vec<FlaggedNote>> {
+    let mut flagged = Vec::new();
+    for notes_ref in local_notes_refs(repo)? {
+        let notes = repo
+            .notes(Some(&notes_ref))
+            .with_context(|| format!("Failed to read {}", notes_ref))?;
+        for (note_oid, commit) in notes.flatten() {
+            let blob = repo.find_blob(note_oid)?;
+            // Notes that aren't attribution JSON are none of our business here.
+            let Some(attribution) = std::str::from_utf8(blob.content())
+                .ok()
+                .and_then(|payload| note_format::parse_note(payload).ok())
+            else {
+                continue;
+            };
+            let findings = scan_note(&attribution, redactor, check_prompts, max_prompt_bytes);
+            if !findings.is_empty() {
+                flagged.push(FlaggedNote {
+                    notes_ref: notes_ref.clone(),
+                    commit,
+                    findings,
+                });
+            }
+        }
+    }
+    Ok(flagged)
+}
+
+/// Run the prepush-scan command
+pub fn run(args: PrepushScanArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let Some(remote) = args.remote.or_else(|| default_remote(&repo)) else {
+        anyhow::bail!("No default remote; pass --remote");
+    };
+    let force = args.force || std::env::var(FORCE_ENV).is_ok_and(|v| v == "1");
+
+    let config = repo
+        .workdir()
+        .map(WhogititConfig::load_or_warn)
+        .unwrap_or_default();
+    let url = repo
+        .find_remote(&remote)
+        .ok()
+        .and_then(|r| r.url().map(str::to_string))
+        .unwrap_or_else(|| remote.clone());
+    let check_prompts = config.remote_policy(&remote, &url).push_prompts;
+    let max_prompt_bytes = args
+        .max_prompt_bytes
+        .unwrap_or(config.privacy.max_push_prompt_bytes);
+
+    if args.stdin {
+        // The hook pipes the ref lines in; drain them so it never sees a broken pipe
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("Failed to read pre-push input")?;
+    }
+    let redactor = config.privacy.build_redactor();
+    let flagged = scan_notes(&repo, &redactor, check_prompts, max_prompt_bytes)?;
+
+    if flagged.is_empty() {
+        return Ok(());
+    }
+
+    let total: usize = flagged.iter().map(|f| f.findings.len()).sum();
+    eprintln!(
+        "whogitit: {} problem(s) in attribution notes about to be pushed to {}:",
+        total, remote
+    );
+    for note in &flagged {
+        let mut label = note.commit.to_string()[..7].to_string();
+        if note.notes_ref != NOTES_REF {
+            label.push_str(&format!(" ({})", note.notes_ref));
+        }
+        for finding in &note.findings {
+            match finding {
+                Finding::OversizedPrompt { .. } => {
+                    eprintln!("  {}  {} (limit {})", label, finding, max_prompt_bytes)
+                }
+                Finding::Secret { .. } => eprintln!("  {}  {}", label, finding),
+            }
+        }
+    }
+    eprintln!("Remove a note with 'git notes --ref=whogitit remove <commit>' (or the ref shown");
+    eprintln!("in parentheses), or keep prompts off this remote with push_prompts = false in");
+    eprintln!("its [remotes] entry.");
+
+    if force {
+        eprintln!("whogitit: Pushing anyway (forced).");
+        return Ok(());
+    }
+    anyhow::bail!(
+        "Push blocked by whogitit prepush-scan ({} note(s)); set {}=1 to push anyway",
+        flagged.len(),
+        FORCE_ENV
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_updates_skips_deletes_and_notes() {
+        let a = "1111111111111111111111111111111111111111";
+        let b = "2222222222222222222222222222222222222222";
+        let zero = "0000000000000000000000000000000000000000";
+        let input = format!(
+            "refs/heads/main {a} refs/heads/main {b}\n\
+             refs/heads/new {b} refs/heads/new {zero}\n\
+             (delete) {zero} refs/heads/old {a}\n\
+             refs/notes/whogitit {a} refs/notes/whogitit {zero}\n"
+        );
+        let updates = parse_updates(&input);
+        assert_eq!(
+            updates,
+            vec![
+                RefUpdate {
+                    local: Oid::from_str(a).unwrap(),
+                    remote: Some(Oid::from_str(b).unwrap()),
+                },
+                RefUpdate {
+                    local: Oid::from_str(b).unwrap(),
+                    remote: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_flags_secrets_and_oversized_prompts() {
+        let dir = TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+
+        let clean = scan_notes(&fixture.repo, &Redactor::new(&[]), true, 16).unwrap();
+        assert_eq!(clean.len(), 1);
+        assert!(matches!(
+            clean[0].findings[..],
+            [Finding::OversizedPrompt { index: 0, .. }]
+        ));
+
+        let redactor = Redactor::new(&["generated module"]);
+        let flagged = scan_notes(&fixture.repo, &redactor, true, 1024).unwrap();
+        assert_eq!(flagged[0].commit, fixture.head());
+        assert_eq!(flagged[0].notes_ref, NOTES_REF);
+        assert_eq!(
+            flagged[0].findings,
+            vec![Finding::Secret {
+                location: "prompt #0".to_string(),
+                pattern: "CUSTOM_0".to_string(),
+            }]
+        );
+
+        // Prompts withheld from the remote are not checked.
+        assert!(scan_notes(&fixture.repo, &redactor, false, 16)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_scan_covers_notes_on_pushed_commits_and_namespaces() {
+        let dir = TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let repo = &fixture.repo;
+        let head = fixture.head();
+        // The commit is already on the remote; only its note would change
+        repo.reference("refs/remotes/origin/main", head, true, "test")
+            .unwrap();
+        assert!(outgoing_commits(repo, "origin", None).unwrap().is_empty());
+
+        let payload = String::from_utf8(
+            repo.find_blob(repo.find_note(Some(NOTES_REF), head).unwrap().id())
+                .unwrap()
+                .content()
+                .to_vec(),
+        )
+        .unwrap();
+        let sig = git2::Signature::now("Bot", "bot@example.com").unwrap();
+        let namespaced = "refs/notes/whogitit-ns/ci-bot";
+        repo.note(&sig, &sig, Some(namespaced), head, &payload, false)
+            .unwrap();
+
+        let redactor = Redactor::new(&["generated module"]);
+        let flagged = scan_notes(repo, &redactor, true, 1024).unwrap();
+        let refs: Vec<&str> = flagged
+            .iter()
+            .inspect(|f| assert_eq!(f.commit, head))
+            .map(|f| f.notes_ref.as_str())
+            .collect();
+        assert_eq!(refs, vec![NOTES_REF, namespaced]);
+    }
+}
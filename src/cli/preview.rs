@@ -0,0 +1,244 @@
+//! Preview command - dry-run of the post-commit analysis on the staged index
+//!
+//! Runs the same pipeline as the post-commit hook (rename detection, three-way
+//! analysis, note payload) against what `git commit` would commit right now, and
+//! prints the attribution the commit would get. Nothing is committed and no
+//! note, pending buffer or audit entry is written.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use git2::Repository;
+
+use crate::capture::hook::CommitPreview;
+use crate::capture::CaptureHook;
+use crate::cli::i18n::Locale;
+use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::cli::show;
+use crate::storage::notes::{note_payload, NotesStore};
+use crate::storage::trailers::TrailerGenerator;
+
+/// Output format for the preview command
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum PreviewFormat {
+    /// Human-readable summary, as `show` prints it after the commit
+    #[default]
+    Pretty,
+    /// Attribution (with line data), renames and files left pending
+    Json,
+    /// The exact note payload post-commit would write
+    Note,
+}
+
+/// Preview command arguments
+#[derive(Debug, Args)]
+pub struct PreviewArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = PreviewFormat::Pretty)]
+    pub format: PreviewFormat,
+
+    /// Attribute files with more lines than this in bounded memory, as line ranges
+    /// (overrides analysis.max_analysis_lines, like `post-commit --max-analysis-lines`)
+    #[arg(long)]
+    pub max_analysis_lines: Option<usize>,
+
+    /// Language and number format of pretty output: en, de, fr, es (default: $WHOGITIT_LOCALE)
+    #[arg(long, value_name = "LOCALE")]
+    pub locale: Option<String>,
+}
+
+/// Run the preview command
+pub fn run(args: PreviewArgs) -> Result<()> {
+    let locale = Locale::resolve(args.locale.as_deref())?;
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+
+    let mut hook = CaptureHook::new(repo_root)?;
+    if let Some(max_analysis_lines) = args.max_analysis_lines {
+        hook = hook.with_max_analysis_lines(max_analysis_lines);
+    }
+    let preview = hook.preview()?;
+
+    match args.format {
+        PreviewFormat::Pretty => print_pretty(&repo, preview.as_ref(), locale),
+        PreviewFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&preview_json(preview.as_ref())?)?
+            );
+            Ok(())
+        }
+        PreviewFormat::Note => {
+            let attribution = preview
+                .as_ref()
+                .and_then(|p| p.attribution.as_ref())
+                .context("No staged AI edits; the commit would get no note")?;
+            println!("{}", note_payload(attribution)?.0);
+            Ok(())
+        }
+    }
+}
+
+fn preview_json(preview: Option<&CommitPreview>) -> Result<serde_json::Value> {
+    let Some(preview) = preview else {
+        return Ok(serde_json::json!({
+            "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+            "pending": false,
+            "attribution": null,
+        }));
+    };
+    let payload = preview.attribution.as_ref().map(note_payload).transpose();
+    let (note_bytes, note_error) = match &payload {
+        Ok(payload) => (payload.as_ref().map(|(json, _)| json.len()), None),
+        Err(err) => (None, Some(format!("{:#}", err))),
+    };
+    let trailers: Vec<serde_json::Value> = match &preview.attribution {
+        Some(attribution) if preview.storage_backend.writes_trailers() => {
+            TrailerGenerator::generate(attribution)
+                .into_iter()
+                .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(serde_json::json!({
+        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+        "pending": true,
+        "parents": preview.parent_count,
+        "attribution": preview.attribution,
+        "renamed": preview
+            .renamed
+            .iter()
+            .map(|(from, to)| serde_json::json!({ "from": from, "to": to }))
+            .collect::<Vec<_>>(),
+        "still_pending": preview.still_pending,
+        "writes_note": preview.storage_backend.writes_notes(),
+        "note_bytes": note_bytes,
+        "note_error": note_error,
+        "trailers": trailers,
+    }))
+}
+
+fn print_pretty(repo: &Repository, preview: Option<&CommitPreview>, locale: Locale) -> Result<()> {
+    let Some(preview) = preview else {
+        println!("No pending AI attribution; the commit would get no attribution.");
+        return Ok(());
+    };
+    println!(
+        "{}",
+        "Preview of the staged commit (nothing is committed or written)".dimmed()
+    );
+    println!();
+
+    match &preview.attribution {
+        Some(attribution) => show::print_summary("(staged)", attribution, false, locale),
+        None => println!("No pending AI edits are staged; the commit would get no attribution."),
+    }
+
+    if !preview.renamed.is_empty() {
+        println!();
+        println!("{}", "Renamed (attribution follows the file):".bold());
+        for (from, to) in &preview.renamed {
+            println!("  {} -> {}", from, to);
+        }
+    }
+
+    if !preview.still_pending.is_empty() {
+        println!();
+        println!("{}", "Still pending after this commit (not staged):".bold());
+        for path in &preview.still_pending {
+            println!("  {}", path);
+        }
+    }
+
+    let Some(attribution) = &preview.attribution else {
+        return Ok(());
+    };
+    println!();
+    if preview.storage_backend.writes_notes() {
+        match note_payload(attribution) {
+            Ok((json, warning)) => {
+                println!(
+                    "{}: {} bytes in {}",
+                    "Note".bold(),
+                    json.len(),
+                    NotesStore::new(repo)?.write_ref()
+                );
+                if let Some(warning) = warning {
+                    println!("  {}", format!("Warning: {}", warning).yellow());
+                }
+            }
+            Err(err) => println!(
+                "{}: {}",
+                "Note".bold(),
+                format!("post-commit would fail: {:#}", err).red()
+            ),
+        }
+    }
+    if preview.storage_backend.writes_trailers() {
+        println!("{}", "Trailers:".bold());
+        for (key, value) in TrailerGenerator::generate(attribution) {
+            println!("  {}: {}", key, value);
+        }
+    }
+    if preview.parent_count > 1 {
+        println!(
+            "{}",
+            "Merge in progress: only lines in none of the parents count as written.".dimmed()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::HookInput;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_preview_matches_staged_changes_only() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial", &tree, &[])
+            .unwrap();
+
+        let hook = CaptureHook::new(dir.path()).unwrap();
+        for (path, content) in [("staged.rs", "fn a() {}\n"), ("later.rs", "fn b() {}\n")] {
+            std::fs::write(dir.path().join(path), content).unwrap();
+            hook.on_file_change(HookInput {
+                tool: "Write".to_string(),
+                file_path: path.to_string(),
+                prompt: "Add functions".to_string(),
+                old_content: None,
+                old_content_present: false,
+                new_content: content.to_string(),
+                context: None,
+            })
+            .unwrap();
+        }
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("staged.rs")).unwrap();
+        index.write().unwrap();
+
+        let preview = hook.preview().unwrap().unwrap();
+        let attribution = preview.attribution.as_ref().unwrap();
+        assert_eq!(attribution.files.len(), 1);
+        assert_eq!(attribution.files[0].path, "staged.rs");
+        assert_eq!(attribution.files[0].summary.ai_lines, 1);
+        assert_eq!(preview.still_pending, vec!["later.rs"]);
+
+        // Nothing was consumed: both files are still pending
+        assert_eq!(hook.status().unwrap().file_count, 2);
+        let json = preview_json(Some(&preview)).unwrap();
+        assert_eq!(json["still_pending"][0], "later.rs");
+        assert!(json["note_bytes"].as_u64().unwrap() > 0);
+    }
+}
@@ -3,8 +3,12 @@ use clap::Args;
 use colored::Colorize;
 use git2::Repository;
 
+use crate::capture::snapshot::LineSource;
 use crate::cli::output::{LineSourceOutput, OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::attribution::ToolCall;
 use crate::core::blame::AIBlamer;
+use crate::privacy::WhogititConfig;
+use crate::storage::audit::AuditLog;
 use crate::utils::{pad_right, truncate, word_wrap};
 
 /// Prompt command arguments
@@ -24,6 +28,10 @@ pub struct PromptArgs {
     /// Output as JSON (deprecated: use --format json)
     #[arg(long)]
     pub json: bool,
+
+    /// Also show the edit and tool-call ids that trace the line to the session transcript
+    #[arg(short, long)]
+    pub verbose: bool,
 }
 
 /// Parsed file:line reference
@@ -108,6 +116,19 @@ pub fn run(args: PromptArgs) -> Result<()> {
     let prompt_info = line
         .prompt_index
         .and_then(|idx| attribution.get_prompt(idx));
+    let edit_id = match &line.source {
+        LineSource::AI { edit_id } | LineSource::AIModified { edit_id, .. } => Some(edit_id),
+        _ => None,
+    };
+
+    if let (Some(prompt), Some(root)) = (prompt_info, repo.workdir()) {
+        let config = WhogititConfig::load_or_warn(root);
+        if let Some(audit_log) = AuditLog::for_prompt_access(root, &config.audit) {
+            audit_log
+                .log_prompt_access("prompt", &line.commit_id, &[prompt.index])
+                .context("Failed to record prompt access in the audit log")?;
+        }
+    }
 
     if output_format == OutputFormat::Json {
         let output = serde_json::json!({
@@ -124,6 +145,7 @@ pub fn run(args: PromptArgs) -> Result<()> {
                 "content": line.content,
                 "source": LineSourceOutput::from(&line.source),
                 "prompt_index": line.prompt_index,
+                "edit_id": edit_id,
             },
             "commit": {
                 "id": line.commit_id,
@@ -135,6 +157,7 @@ pub fn run(args: PromptArgs) -> Result<()> {
                 "text": p.text,
                 "timestamp": p.timestamp,
                 "affected_files": p.affected_files,
+                "tool_calls": p.tool_calls,
             })),
             "session": {
                 "id": attribution.session.session_id,
@@ -165,11 +188,43 @@ pub fn run(args: PromptArgs) -> Result<()> {
         println!("File: {}:{}", file_ref.file, line.line_number);
         println!("Commit: {}", line.commit_short);
         println!("Source: {:?}", line.source);
+        if args.verbose {
+            print_tool_calls(prompt_info.map_or(&[], |p| &p.tool_calls), edit_id);
+        }
     }
 
     Ok(())
 }
 
+/// Tool calls of the prompt, marking the one that wrote the line
+fn print_tool_calls(tool_calls: &[ToolCall], edit_id: Option<&String>) {
+    println!();
+    if tool_calls.is_empty() {
+        println!(
+            "{}",
+            "No tool-call ids recorded (captured before ids were stored, or not reported by the hook)"
+                .dimmed()
+        );
+        return;
+    }
+    println!("{}", "Tool calls for this prompt:".dimmed());
+    for call in tool_calls {
+        let marker = if Some(&call.edit_id) == edit_id {
+            " (this line)".green().to_string()
+        } else {
+            String::new()
+        };
+        println!("  - {} {}{}", call.tool, call.file, marker);
+        println!("    edit:     {}", call.edit_id);
+        if let Some(id) = &call.tool_use_id {
+            println!("    tool use: {}", id);
+        }
+        if let Some(id) = &call.message_id {
+            println!("    message:  {}", id);
+        }
+    }
+}
+
 fn print_prompt_box(
     prompt: &crate::core::attribution::PromptInfo,
     session_id: &str,
@@ -321,6 +376,7 @@ mod tests {
             revision: None,
             format: None,
             json: false,
+            verbose: false,
         };
         assert_eq!(args.reference, "src/main.rs:42");
         assert!(args.revision.is_none());
@@ -335,6 +391,7 @@ mod tests {
             revision: Some("HEAD~1".to_string()),
             format: Some(OutputFormat::Json),
             json: true,
+            verbose: false,
         };
         assert_eq!(args.revision.as_deref(), Some("HEAD~1"));
         assert!(matches!(args.format, Some(OutputFormat::Json)));
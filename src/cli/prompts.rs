@@ -0,0 +1,170 @@
+//! Prompts command - list prompts by provenance hash and find reused prompts
+//!
+//! Every prompt stored in a note carries a stable hash (whitespace-normalized
+//! SHA-256). `--duplicates` surfaces prompts reused across many commits or
+//! repositories, such as copy-pasted jailbreaks or unsafe prompt templates.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::Repository;
+
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::storage::prompt_index::{PromptGroup, PromptIndex};
+
+const PROMPTS_MACHINE_SCHEMA: &str = "whogitit.prompts.v1";
+
+/// Prompts command arguments
+#[derive(Debug, Args)]
+pub struct PromptsArgs {
+    /// Only show prompts used at least --min-count times
+    #[arg(long)]
+    pub duplicates: bool,
+
+    /// Minimum occurrences for --duplicates
+    #[arg(long, default_value_t = 2)]
+    pub min_count: usize,
+
+    /// Additional repositories to include (repeatable)
+    #[arg(long = "repo", value_name = "PATH")]
+    pub repos: Vec<PathBuf>,
+
+    /// Maximum number of prompts to list
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+/// Run the prompts command
+pub fn run(args: PromptsArgs) -> Result<()> {
+    let multi_repo = !args.repos.is_empty();
+    let mut indexes = Vec::new();
+
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let label = multi_repo.then(|| repo_label(&repo));
+    indexes.push((label, PromptIndex::refresh(&repo)?));
+
+    for path in &args.repos {
+        let repo = Repository::discover(path)
+            .with_context(|| format!("Not a git repository: {}", path.display()))?;
+        indexes.push((Some(repo_label(&repo)), PromptIndex::refresh(&repo)?));
+    }
+
+    let commits: usize = indexes.iter().map(|(_, i)| i.commit_count()).sum();
+    let groups = select_groups(
+        PromptIndex::groups(&indexes),
+        args.duplicates.then_some(args.min_count),
+        args.limit,
+    );
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&groups, commits, args.duplicates),
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": PROMPTS_MACHINE_SCHEMA,
+                "commits_indexed": commits,
+                "prompts": groups,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn repo_label(repo: &Repository) -> String {
+    repo.workdir()
+        .unwrap_or_else(|| repo.path())
+        .display()
+        .to_string()
+}
+
+/// Apply the duplicate threshold and limit to groups sorted by frequency
+fn select_groups(
+    groups: Vec<PromptGroup>,
+    min_count: Option<usize>,
+    limit: Option<usize>,
+) -> Vec<PromptGroup> {
+    groups
+        .into_iter()
+        .filter(|g| min_count.map_or(true, |min| g.count >= min))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+fn print_pretty(groups: &[PromptGroup], commits: usize, duplicates: bool) {
+    if groups.is_empty() {
+        if duplicates {
+            println!(
+                "No duplicate prompts across {} attributed commits.",
+                commits
+            );
+        } else {
+            println!("No prompts found in {} attributed commits.", commits);
+        }
+        return;
+    }
+
+    let title = if duplicates {
+        "Duplicate prompts"
+    } else {
+        "Prompts"
+    };
+    println!(
+        "{} ({} across {} attributed commits)",
+        title.bold(),
+        groups.len(),
+        commits
+    );
+    println!();
+    for group in groups {
+        println!(
+            "{} {}x in {} commit(s)  {}",
+            group.hash[..12].yellow(),
+            group.count,
+            group.commits,
+            group.preview.dimmed()
+        );
+        for occurrence in &group.occurrences {
+            let commit = &occurrence.commit[..crate::utils::SHORT_COMMIT_LEN];
+            match &occurrence.repo {
+                Some(repo) => println!("    {} #{} ({})", commit, occurrence.prompt_index, repo),
+                None => println!("    {} #{}", commit, occurrence.prompt_index),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(hash: &str, count: usize) -> PromptGroup {
+        PromptGroup {
+            hash: hash.to_string(),
+            preview: "prompt".to_string(),
+            count,
+            commits: count,
+            occurrences: vec![],
+        }
+    }
+
+    #[test]
+    fn test_select_groups_filters_duplicates_and_limits() {
+        let groups = vec![group("a", 5), group("b", 2), group("c", 1)];
+
+        assert_eq!(select_groups(groups.clone(), None, None).len(), 3);
+        let dupes = select_groups(groups.clone(), Some(2), None);
+        assert_eq!(
+            dupes.iter().map(|g| g.hash.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(select_groups(groups, Some(2), Some(1)).len(), 1);
+    }
+}
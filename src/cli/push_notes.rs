@@ -0,0 +1,97 @@
+//! Push-notes command - push attribution notes with git2 (called by the pre-push hook)
+//!
+//! Failures are printed, recorded for `whogitit status` and written to the audit
+//! log (when enabled) instead of being discarded. See [`crate::storage::notes_push`].
+//!
+//! Remotes configured with `push_prompts = false` receive a copy of the notes
+//! with prompt text withheld.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::Repository;
+
+use crate::privacy::WhogititConfig;
+use crate::storage::audit::AuditLog;
+use crate::storage::notes::NoteTransform;
+use crate::storage::notes_push;
+use crate::storage::notes_sync::default_remote;
+
+/// Push-notes command arguments
+#[derive(Debug, Args)]
+pub struct PushNotesArgs {
+    /// Remote name or URL (default: origin, or the only remote)
+    #[arg(long)]
+    pub remote: Option<String>,
+}
+
+/// Run the push-notes command
+pub fn run(args: PushNotesArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let Some(remote) = args.remote.or_else(|| default_remote(&repo)) else {
+        anyhow::bail!("No default remote; pass --remote");
+    };
+
+    let config = repo
+        .workdir()
+        .map(WhogititConfig::load_or_warn)
+        .unwrap_or_default();
+    let transforms = remote_transforms(&repo, &config, &remote);
+
+    match notes_push::push_notes(&repo, &remote, &transforms) {
+        Ok(refs) if refs.is_empty() => println!("whogitit: No attribution notes to push"),
+        Ok(refs) => {
+            notes_push::clear_failure(&repo);
+            let withheld = if transforms.contains(&NoteTransform::StripPrompts) {
+                " (prompts withheld)"
+            } else {
+                ""
+            };
+            println!(
+                "whogitit: Pushed {} notes ref(s) to {}{}",
+                refs.len(),
+                remote,
+                withheld
+            );
+        }
+        Err(err) => {
+            let message = format!("{:#}", err);
+            if let Err(e) = notes_push::record_failure(&repo, &remote, &message) {
+                eprintln!("whogitit: Warning - failed to record push failure: {:#}", e);
+            }
+            audit_failure(&repo, &config, &remote, &message);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Transforms for notes pushed to `remote`, from its `[remotes]` policy
+fn remote_transforms(
+    repo: &Repository,
+    config: &WhogititConfig,
+    remote: &str,
+) -> Vec<NoteTransform> {
+    let url = repo
+        .find_remote(remote)
+        .ok()
+        .and_then(|r| r.url().map(str::to_string))
+        .unwrap_or_else(|| remote.to_string());
+    if config.remote_policy(remote, &url).push_prompts {
+        Vec::new()
+    } else {
+        vec![NoteTransform::StripPrompts]
+    }
+}
+
+fn audit_failure(repo: &Repository, config: &WhogititConfig, remote: &str, message: &str) {
+    let Some(workdir) = repo.workdir() else {
+        return;
+    };
+    if !config.privacy.audit_log {
+        return;
+    }
+    let audit_log = AuditLog::new(workdir).with_forwarding(&config.audit.forwarding);
+    if let Err(e) = audit_log.log_notes_push_failure(remote, message) {
+        eprintln!("whogitit: Warning - failed to write audit log: {:#}", e);
+    }
+}
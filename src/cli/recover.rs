@@ -0,0 +1,143 @@
+//! Recover command - salvage attribution from a corrupted pending buffer
+//!
+//! When the pending buffer fails to parse, the capture hook backs it up as
+//! `.whogitit-pending.corrupted.<timestamp>` and starts a fresh session. This
+//! command salvages the intact parts of that backup (or of a still-corrupted
+//! pending buffer) and merges them back into the pending buffer.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+
+use crate::capture::pending::PendingStore;
+use crate::capture::recovery::{self, RecoveryReport};
+use crate::storage::{LockHolderKind, RepoLock};
+
+/// Recover command arguments
+#[derive(Debug, Args)]
+pub struct RecoverArgs {
+    /// Corrupted buffer to recover from
+    /// (default: the pending buffer if corrupted, else the newest corrupted backup)
+    #[arg(long, value_name = "FILE")]
+    pub from: Option<PathBuf>,
+
+    /// Show what can be recovered without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Run the recover command
+pub fn run(args: RecoverArgs) -> Result<()> {
+    let repo = git2::Repository::discover(".").context("Not in a git repository")?;
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+    let _lock = (!args.dry_run)
+        .then(|| RepoLock::acquire_default(repo.path(), LockHolderKind::Recover))
+        .transpose()?;
+    let store = PendingStore::new(repo_root);
+    let current = store.load_quiet()?;
+
+    let source = match args.from {
+        Some(path) => path,
+        None if store.exists() && current.is_none() => store.path().to_path_buf(),
+        None => match store.corrupted_backups().pop() {
+            Some(path) => path,
+            None => {
+                println!("Nothing to recover: the pending buffer is intact and there are no corrupted backups.");
+                return Ok(());
+            }
+        },
+    };
+
+    let content =
+        fs::read(&source).with_context(|| format!("Failed to read {}", source.display()))?;
+    let content = String::from_utf8_lossy(&content);
+    let Some(salvage) = recovery::salvage(&content) else {
+        anyhow::bail!(
+            "No attribution could be recovered from {}",
+            source.display()
+        );
+    };
+
+    println!("Recovering from {}", source.display());
+    print_report(&salvage.report);
+
+    if args.dry_run {
+        println!("\n{}", "Dry run - nothing written.".dimmed());
+        return Ok(());
+    }
+
+    let recovering_pending_file = source == store.path();
+    let buffer = match current {
+        // A fresh session was started after the corruption; the recovered edits are older
+        Some(mut current) if !recovering_pending_file => {
+            current.absorb(salvage.buffer);
+            current
+        }
+        _ => salvage.buffer,
+    };
+
+    if let Some(backup) = store.backup()? {
+        println!("Previous pending buffer backed up to {}", backup.display());
+    }
+    store.save(&buffer)?;
+
+    if !recovering_pending_file {
+        mark_recovered(&source);
+    }
+
+    println!(
+        "\n{} {} files now pending. Run 'git commit' to finalize attribution.",
+        "Recovered.".green().bold(),
+        buffer.file_count()
+    );
+    Ok(())
+}
+
+fn print_report(report: &RecoveryReport) {
+    let session = if report.session_recovered {
+        "recovered".green()
+    } else {
+        "rebuilt (new session ID)".yellow()
+    };
+    println!("  Session: {}", session);
+    println!(
+        "  Prompts: {} recovered, {} rebuilt from edits",
+        report.prompts_recovered, report.prompts_rebuilt
+    );
+    for path in &report.files_recovered {
+        println!("  {} {}", "✓".green(), path);
+    }
+    for path in &report.files_partial {
+        println!(
+            "  {} {} {}",
+            "~".yellow(),
+            path,
+            "(up to the last intact edit)".dimmed()
+        );
+    }
+    for path in &report.files_lost {
+        println!("  {} {} {}", "✗".red(), path, "(unrecoverable)".dimmed());
+    }
+}
+
+/// Rename a recovered backup so it is not picked up again
+fn mark_recovered(source: &Path) {
+    let Some(name) = source.file_name().and_then(|n| n.to_str()) else {
+        return;
+    };
+    if let Some(suffix) = name.strip_prefix(".whogitit-pending.corrupted.") {
+        let target = source.with_file_name(format!(".whogitit-pending.recovered.{}", suffix));
+        if let Err(e) = fs::rename(source, &target) {
+            eprintln!(
+                "whogitit: Warning - failed to rename {}: {}",
+                source.display(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Reindex command - bring the SQLite attribution index up to date
+//!
+//! `summary` and `export --query` maintain the index as they go, and the
+//! post-commit hook adds each new commit; `reindex` indexes every note up front
+//! (e.g. after fetching notes) or, with `--full`, rebuilds it from scratch.
+//! See [`crate::storage::index`].
+
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::Repository;
+
+use crate::storage::index::AttributionIndex;
+use crate::storage::notes::NotesStore;
+
+/// Reindex command arguments
+#[derive(Debug, Args)]
+pub struct ReindexArgs {
+    /// Drop the index and read every note again
+    #[arg(long)]
+    pub full: bool,
+}
+
+/// Run the reindex command
+pub fn run(args: ReindexArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let notes_store = NotesStore::new(&repo)?;
+    let started = Instant::now();
+
+    let mut index = AttributionIndex::open(&repo)?;
+    if args.full {
+        index.clear()?;
+    }
+    let stats = index.refresh(&repo, &notes_store)?;
+
+    println!(
+        "Indexed {} commit(s), {} unchanged, {} removed in {:.1}s",
+        stats.indexed,
+        stats.reused,
+        stats.removed,
+        started.elapsed().as_secs_f64()
+    );
+    println!(
+        "{}: {} commit(s), {} file entries",
+        AttributionIndex::path(repo.path()).display(),
+        index.commit_count()?,
+        index.file_count()?
+    );
+    if stats.failed > 0 {
+        eprintln!(
+            "whogitit: Warning - {} note(s) could not be read and were left out",
+            stats.failed
+        );
+    }
+    Ok(())
+}
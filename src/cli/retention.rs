@@ -7,6 +7,8 @@ use git2::{Oid, Repository};
 
 use crate::privacy::WhogititConfig;
 use crate::retention::{apply_retention_policy_with_sets, compute_retention_sets};
+use crate::storage::audit::AuditLog;
+use crate::storage::{LockHolderKind, RepoLock};
 
 const DEFAULT_PREVIEW_SHOW_LIMIT: usize = 25;
 
@@ -182,6 +184,9 @@ fn run_apply(execute: bool, reason: Option<String>) -> Result<()> {
     let config = WhogititConfig::load(repo_root).context("Failed to load configuration")?;
     let retention = config.retention.unwrap_or_default();
 
+    let _lock = execute
+        .then(|| RepoLock::acquire_default(repo.path(), LockHolderKind::Retention))
+        .transpose()?;
     let sets = compute_retention_sets(&repo, &retention)?;
     if sets.to_delete.is_empty() && sets.to_keep.is_empty() {
         println!("No attribution data found.");
@@ -203,8 +208,12 @@ fn run_apply(execute: bool, reason: Option<String>) -> Result<()> {
     }
 
     let reason_str = reason.unwrap_or_else(|| "Retention policy".to_string());
+    let audit_log = config
+        .privacy
+        .audit_log
+        .then(|| AuditLog::new(repo_root).with_forwarding(&config.audit.forwarding));
     let result =
-        apply_retention_policy_with_sets(&repo, sets, true, &reason_str, config.privacy.audit_log)?;
+        apply_retention_policy_with_sets(&repo, sets, true, &reason_str, audit_log.as_ref())?;
 
     println!(
         "{} Deleted attribution for {} commits",
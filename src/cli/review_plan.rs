@@ -0,0 +1,574 @@
+//! Review-plan command - direct human review where AI involvement is highest
+//!
+//! Ranks files in a commit range by a risk score combining AI share, attribution
+//! confidence (calibrated, see [`crate::core::calibration`]), critical-path globs from config (`[review] critical_paths`) and
+//! AI lines no human has touched, and emits an ordered checklist. With a decay
+//! half-life (`--half-life` or `[decay]`), the AI components use decay-weighted
+//! line counts (see [`crate::core::decay`]) so old AI code ranks lower.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::{Oid, Repository};
+use serde::Serialize;
+
+use crate::capture::snapshot::{FileAttributionResult, LineSource};
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::blame::AIBlamer;
+use crate::core::calibration;
+use crate::core::decay::{self, DecayModel, DecayedLines};
+use crate::privacy::WhogititConfig;
+use crate::storage::notes::NotesStore;
+use crate::utils::GlobSet;
+
+const REVIEW_PLAN_MACHINE_SCHEMA: &str = "whogitit.review-plan.v1";
+
+/// Weight of the AI share of additions in the risk score
+const WEIGHT_AI_SHARE: f64 = 40.0;
+/// Weight of unreviewed (unchanged) AI lines, saturating at `UNREVIEWED_SATURATION`
+const WEIGHT_UNREVIEWED: f64 = 20.0;
+/// Weight of low attribution confidence
+const WEIGHT_LOW_CONFIDENCE: f64 = 15.0;
+/// Bonus for files matching a critical path glob
+const WEIGHT_CRITICAL: f64 = 25.0;
+/// Unreviewed AI line count at which that component is maxed out
+const UNREVIEWED_SATURATION: f64 = 200.0;
+
+/// Review-plan command arguments
+#[derive(Debug, Args)]
+pub struct ReviewPlanArgs {
+    /// Base commit (exclusive) - defaults to first commit if not specified
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// Head commit (inclusive) - defaults to HEAD
+    #[arg(long, default_value = "HEAD")]
+    pub head: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+
+    /// Maximum number of files to list
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Weight AI lines by age with this half-life in days (overrides `[decay]`)
+    #[arg(long, value_name = "DAYS")]
+    pub half_life: Option<f64>,
+}
+
+/// Aggregated per-file review statistics
+#[derive(Debug, Clone, Default)]
+struct FileReviewStats {
+    ai_lines: usize,
+    ai_modified_lines: usize,
+    human_lines: usize,
+    confidence_sum: f64,
+    confidence_count: usize,
+    /// AI lines from the range still unchanged at head (from blame)
+    unreviewed: usize,
+    /// Decay-weighted AI lines (only with a decay model)
+    decayed: Option<DecayedLines>,
+}
+
+impl FileReviewStats {
+    fn add(&mut self, file: &FileAttributionResult) {
+        self.ai_lines += file.summary.ai_lines;
+        self.ai_modified_lines += file.summary.ai_modified_lines;
+        self.human_lines += file.summary.human_lines;
+        for line in &file.lines {
+            if matches!(
+                line.source,
+                LineSource::AI { .. } | LineSource::AIModified { .. }
+            ) {
+                self.confidence_sum += line.confidence;
+                self.confidence_count += 1;
+            }
+        }
+    }
+
+    fn add_decayed(&mut self, lines: DecayedLines) {
+        self.decayed.get_or_insert_with(Default::default).add(lines);
+    }
+
+    fn additions(&self) -> usize {
+        self.ai_lines + self.ai_modified_lines + self.human_lines
+    }
+
+    fn ai_share(&self) -> f64 {
+        let adds = self.additions();
+        if adds == 0 {
+            0.0
+        } else {
+            (self.ai_lines + self.ai_modified_lines) as f64 / adds as f64
+        }
+    }
+
+    /// AI share and unmodified AI lines used for scoring (decay-weighted if enabled)
+    fn scored_ai(&self) -> (f64, f64) {
+        match &self.decayed {
+            Some(decayed) if self.additions() > 0 => {
+                (decayed.total() / self.additions() as f64, decayed.ai_lines)
+            }
+            Some(_) => (0.0, 0.0),
+            None => (self.ai_share(), self.unreviewed as f64),
+        }
+    }
+
+    /// Average confidence across AI lines (1.0 when there are none)
+    fn avg_confidence(&self) -> f64 {
+        if self.confidence_count == 0 {
+            1.0
+        } else {
+            self.confidence_sum / self.confidence_count as f64
+        }
+    }
+}
+
+/// A ranked entry in the review plan
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewItem {
+    pub rank: usize,
+    pub path: String,
+    pub risk_score: f64,
+    pub ai_percent: f64,
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    pub human_lines: usize,
+    /// AI lines committed without any human modification
+    pub unreviewed_ai_lines: usize,
+    pub avg_confidence: f64,
+    /// Decay-weighted AI and AI-modified lines (with a decay half-life)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decayed_ai_lines: Option<f64>,
+    pub critical: bool,
+    pub reasons: Vec<String>,
+}
+
+/// Compute the risk score (0-100) for a file
+fn risk_score(stats: &FileReviewStats, critical: bool) -> f64 {
+    let (ai_share, unreviewed) = stats.scored_ai();
+    let unreviewed = (unreviewed / UNREVIEWED_SATURATION).min(1.0);
+    let mut score = ai_share * WEIGHT_AI_SHARE
+        + unreviewed * WEIGHT_UNREVIEWED
+        + (1.0 - stats.avg_confidence()).clamp(0.0, 1.0) * WEIGHT_LOW_CONFIDENCE;
+    if critical {
+        score += WEIGHT_CRITICAL;
+    }
+    (score.min(100.0) * 10.0).round() / 10.0
+}
+
+fn build_reasons(stats: &FileReviewStats, critical: bool) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if critical {
+        reasons.push("critical path".to_string());
+    }
+    reasons.push(format!("{:.0}% AI", stats.ai_share() * 100.0));
+    if stats.decayed.is_some() {
+        reasons.push(format!(
+            "{:.0}% AI after decay",
+            stats.scored_ai().0 * 100.0
+        ));
+    }
+    if stats.unreviewed > 0 {
+        reasons.push(format!("{} unreviewed AI lines", stats.unreviewed));
+    }
+    if stats.confidence_count > 0 && stats.avg_confidence() < 0.9 {
+        reasons.push(format!("confidence {:.2}", stats.avg_confidence()));
+    }
+    reasons
+}
+
+/// Count, per file, the AI lines committed in `range` that are still unchanged
+/// at `head`
+///
+/// Blame attributes each line to the last commit that touched it, so a line
+/// rewritten later in the range counts once and lines since deleted or edited
+/// by a human don't count. Files that no longer exist at head have none.
+fn count_unreviewed(
+    repo: &Repository,
+    head: &str,
+    range: &HashSet<Oid>,
+    stats: &mut HashMap<String, FileReviewStats>,
+) -> Result<()> {
+    let mut blamer = AIBlamer::new(repo)?;
+    for (path, entry) in stats.iter_mut() {
+        if entry.ai_lines + entry.ai_modified_lines == 0 {
+            continue;
+        }
+        let Ok(blame) = blamer.blame(path, Some(head)) else {
+            continue;
+        };
+        entry.unreviewed = blame
+            .lines
+            .iter()
+            .filter(|line| matches!(line.source, LineSource::AI { .. }))
+            .filter(|line| Oid::from_str(&line.commit_id).is_ok_and(|oid| range.contains(&oid)))
+            .count();
+    }
+    Ok(())
+}
+
+/// Rank files by descending risk (ties broken by path)
+fn build_plan(
+    stats: HashMap<String, FileReviewStats>,
+    critical_paths: &[String],
+) -> Vec<ReviewItem> {
+    let critical_paths = GlobSet::new(critical_paths);
+    let mut items: Vec<ReviewItem> = stats
+        .into_iter()
+        .filter(|(_, s)| s.ai_lines + s.ai_modified_lines > 0)
+        .map(|(path, s)| {
+            let critical = critical_paths.is_match(&path);
+            ReviewItem {
+                rank: 0,
+                risk_score: risk_score(&s, critical),
+                ai_percent: s.ai_share() * 100.0,
+                ai_lines: s.ai_lines,
+                ai_modified_lines: s.ai_modified_lines,
+                human_lines: s.human_lines,
+                unreviewed_ai_lines: s.unreviewed,
+                avg_confidence: s.avg_confidence(),
+                decayed_ai_lines: s.decayed.map(|d| (d.total() * 10.0).round() / 10.0),
+                critical,
+                reasons: build_reasons(&s, critical),
+                path,
+            }
+        })
+        .collect();
+
+    items.sort_by(|a, b| {
+        b.risk_score
+            .partial_cmp(&a.risk_score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    for (i, item) in items.iter_mut().enumerate() {
+        item.rank = i + 1;
+    }
+    items
+}
+
+/// Run the review-plan command
+pub fn run(args: ReviewPlanArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let notes_store = NotesStore::new(&repo)?;
+
+    let config = repo
+        .workdir()
+        .map(WhogititConfig::load_or_warn)
+        .unwrap_or_default();
+    let decay = DecayModel::from_config(&config.decay, args.half_life)?;
+    let now = chrono::Utc::now();
+
+    let head_commit = repo
+        .revparse_single(&args.head)
+        .with_context(|| format!("Failed to resolve: {}", args.head))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", args.head))?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_commit.id())?;
+    if let Some(base_ref) = &args.base {
+        let base_commit = repo
+            .revparse_single(base_ref)
+            .with_context(|| format!("Failed to resolve base: {}", base_ref))?
+            .peel_to_commit()
+            .with_context(|| format!("Not a valid commit: {}", base_ref))?;
+        revwalk.hide(base_commit.id())?;
+    }
+
+    let mut stats: HashMap<String, FileReviewStats> = HashMap::new();
+    let mut range: HashSet<Oid> = HashSet::new();
+    for oid in revwalk {
+        let oid = oid?;
+        range.insert(oid);
+        if let Ok(Some(mut attr)) = notes_store.fetch_attribution(oid) {
+            calibration::apply_to_attribution(&config.calibration, &mut attr);
+            let age_days = match &decay {
+                Some(_) => decay::age_days(repo.find_commit(oid)?.time().seconds(), now),
+                None => 0.0,
+            };
+            for file in &attr.files {
+                let entry = stats.entry(file.path.clone()).or_default();
+                entry.add(file);
+                if let Some(model) = &decay {
+                    entry.add_decayed(model.weigh(&file.summary, age_days));
+                }
+            }
+        }
+    }
+
+    count_unreviewed(&repo, &head_commit.id().to_string(), &range, &mut stats)?;
+    let mut plan = build_plan(stats, &config.review.critical_paths);
+    if let Some(limit) = args.limit {
+        plan.truncate(limit);
+    }
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&plan, decay.as_ref()),
+        OutputFormat::Json => {
+            let mut output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": REVIEW_PLAN_MACHINE_SCHEMA,
+                "files": plan,
+            });
+            if let Some(model) = &decay {
+                output["decay_half_life_days"] = model.half_life_days().into();
+            }
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_pretty(plan: &[ReviewItem], decay: Option<&DecayModel>) {
+    if plan.is_empty() {
+        println!("No AI-attributed files in the specified commit range.");
+        return;
+    }
+
+    println!(
+        "{} ({} files, highest risk first)",
+        "Review plan".bold(),
+        plan.len()
+    );
+    if let Some(model) = decay {
+        println!(
+            "{}",
+            format!(
+                "AI lines weighted by age ({}-day half-life)",
+                model.half_life_days()
+            )
+            .dimmed()
+        );
+    }
+    println!();
+    for item in plan {
+        let score = format!("{:>5.1}", item.risk_score);
+        let score = if item.risk_score >= 60.0 {
+            score.red()
+        } else if item.risk_score >= 30.0 {
+            score.yellow()
+        } else {
+            score.green()
+        };
+        println!(
+            "  [ ] {:>2}. {} {}  {}",
+            item.rank,
+            score,
+            item.path,
+            format!("({})", item.reasons.join(", ")).dimmed()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(ai: usize, ai_mod: usize, human: usize, confidence: f64) -> FileReviewStats {
+        FileReviewStats {
+            ai_lines: ai,
+            ai_modified_lines: ai_mod,
+            human_lines: human,
+            confidence_sum: confidence * (ai + ai_mod) as f64,
+            confidence_count: ai + ai_mod,
+            unreviewed: ai,
+            decayed: None,
+        }
+    }
+
+    #[test]
+    fn test_risk_score_components() {
+        // Fully AI, 200+ unreviewed lines, full confidence
+        assert_eq!(risk_score(&stats(200, 0, 0, 1.0), false), 60.0);
+        // Critical bonus
+        assert_eq!(risk_score(&stats(200, 0, 0, 1.0), true), 85.0);
+        // Low confidence raises the score
+        assert!(
+            risk_score(&stats(10, 10, 0, 0.5), false) > risk_score(&stats(10, 10, 0, 1.0), false)
+        );
+        // Capped at 100
+        assert!(risk_score(&stats(1000, 0, 0, 0.0), true) <= 100.0);
+    }
+
+    #[test]
+    fn test_build_plan_orders_by_risk_and_skips_human_only() {
+        let mut map = HashMap::new();
+        map.insert("src/util.rs".to_string(), stats(5, 5, 90, 1.0));
+        map.insert("src/auth/login.rs".to_string(), stats(5, 5, 90, 1.0));
+        map.insert("src/gen.rs".to_string(), stats(100, 0, 0, 1.0));
+        map.insert("README.md".to_string(), stats(0, 0, 10, 1.0));
+
+        let plan = build_plan(map, &["src/auth/**".to_string()]);
+        let paths: Vec<&str> = plan.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["src/gen.rs", "src/auth/login.rs", "src/util.rs"]
+        );
+        assert_eq!(plan[0].rank, 1);
+        assert!(plan[1].critical);
+        assert_eq!(plan[1].reasons[0], "critical path");
+    }
+
+    #[test]
+    fn test_decay_lowers_risk_of_old_ai_lines() {
+        let model = DecayModel::new(30.0, 0.5).unwrap();
+        let summary = crate::capture::snapshot::AttributionSummary {
+            total_lines: 100,
+            ai_lines: 100,
+            ai_modified_lines: 0,
+            human_lines: 0,
+            original_lines: 0,
+            unknown_lines: 0,
+        };
+        let mut fresh = stats(100, 0, 0, 1.0);
+        fresh.add_decayed(model.weigh(&summary, 0.0));
+        let mut old = stats(100, 0, 0, 1.0);
+        old.add_decayed(model.weigh(&summary, 60.0));
+
+        assert_eq!(
+            risk_score(&fresh, false),
+            risk_score(&stats(100, 0, 0, 1.0), false)
+        );
+        assert!(risk_score(&old, false) < risk_score(&fresh, false));
+
+        let plan = build_plan(HashMap::from([("old.rs".to_string(), old)]), &[]);
+        assert_eq!(plan[0].ai_lines, 100);
+        assert_eq!(plan[0].decayed_ai_lines, Some(25.0));
+        assert!(plan[0].reasons.contains(&"25% AI after decay".to_string()));
+    }
+
+    #[test]
+    fn test_unreviewed_lines_come_from_blame_at_head() {
+        use crate::capture::snapshot::{AttributionSummary, LineAttribution};
+        use crate::core::attribution::{AIAttribution, ModelInfo, SessionMetadata, SCHEMA_VERSION};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        let commit = |content: &str, message: &str| {
+            std::fs::write(dir.path().join("gen.rs"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("gen.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = repo
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .into_iter()
+                .collect();
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+        let note = |sources: Vec<LineSource>| {
+            let ai_lines = sources.iter().filter(|s| s.is_ai()).count();
+            AIAttribution {
+                version: SCHEMA_VERSION,
+                session: SessionMetadata {
+                    session_id: "s".to_string(),
+                    model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                    started_at: "2026-01-30T10:00:00Z".to_string(),
+                    prompt_count: 1,
+                    used_plan_mode: false,
+                    subagent_count: 0,
+                    label: None,
+                },
+                prompts: vec![],
+                files: vec![FileAttributionResult {
+                    path: "gen.rs".to_string(),
+                    summary: AttributionSummary {
+                        total_lines: sources.len(),
+                        ai_lines,
+                        ai_modified_lines: 0,
+                        human_lines: 0,
+                        original_lines: sources.len() - ai_lines,
+                        unknown_lines: 0,
+                    },
+                    lines: sources
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, source)| LineAttribution {
+                            line_number: i as u32 + 1,
+                            content: String::new(),
+                            content_hash: None,
+                            source,
+                            edit_id: None,
+                            prompt_index: Some(0),
+                            confidence: 1.0,
+                            timestamp: None,
+                        })
+                        .collect(),
+                    binary: None,
+                    blocks: None,
+                    analysis_warning: None,
+                    secret_findings: Vec::new(),
+                }],
+            }
+        };
+        let ai = || LineSource::AI {
+            edit_id: "e".to_string(),
+        };
+        let notes = NotesStore::new(&repo).unwrap();
+
+        commit("// base\n", "Initial");
+        // AI writes lines 2-3, then rewrites line 2; a human then rewrites line 3
+        let first = commit("// base\nfn a() {}\nfn b() {}\n", "Add a and b");
+        notes
+            .store_attribution(first, &note(vec![LineSource::Original, ai(), ai()]))
+            .unwrap();
+        let second = commit("// base\nfn a2() {}\nfn b() {}\n", "Rework a");
+        notes
+            .store_attribution(
+                second,
+                &note(vec![LineSource::Original, ai(), LineSource::Original]),
+            )
+            .unwrap();
+        let third = commit("// base\nfn a2() {}\nfn b2() {}\n", "Fix b by hand");
+
+        let mut stats = HashMap::new();
+        for oid in [first, second] {
+            let attribution = notes.fetch_attribution(oid).unwrap().unwrap();
+            stats
+                .entry("gen.rs".to_string())
+                .or_insert_with(FileReviewStats::default)
+                .add(&attribution.files[0]);
+        }
+        assert_eq!(stats["gen.rs"].ai_lines, 3);
+
+        let range = HashSet::from([first, second, third]);
+        count_unreviewed(&repo, "HEAD", &range, &mut stats).unwrap();
+        let plan = build_plan(stats, &[]);
+        // Only the rewritten line 2 is still AI-written and untouched
+        assert_eq!(plan[0].unreviewed_ai_lines, 1);
+        assert!(plan[0]
+            .reasons
+            .contains(&"1 unreviewed AI lines".to_string()));
+    }
+
+    #[test]
+    fn test_avg_confidence_defaults_to_one() {
+        assert_eq!(FileReviewStats::default().avg_confidence(), 1.0);
+    }
+
+    #[test]
+    fn test_review_plan_args_structure() {
+        let args = ReviewPlanArgs {
+            base: Some("main".to_string()),
+            head: "HEAD".to_string(),
+            format: OutputFormat::Json,
+            limit: Some(10),
+            half_life: None,
+        };
+        assert_eq!(args.base.as_deref(), Some("main"));
+        assert_eq!(args.limit, Some(10));
+    }
+}
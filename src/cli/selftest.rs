@@ -0,0 +1,386 @@
+//! Selftest command - end-to-end check of the capture → commit → query pipeline
+//!
+//! Creates a throwaway repository, feeds it simulated capture input, commits,
+//! runs the post-commit analysis and verifies what show/blame/summary would
+//! report. Environment checks (hook script, permissions, hook binary) are included so
+//! the JSON output can be attached to support requests as-is.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::{Repository, Signature};
+use serde::Serialize;
+
+use crate::capture::hook::{CaptureHook, HookInput};
+use crate::capture::pending::PendingStore;
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::cli::setup::{
+    check_environment, check_hook_executable, check_hook_installed, check_required_tools,
+};
+use crate::core::attribution::{AIAttribution, ModelInfo};
+use crate::core::blame::AIBlamer;
+use crate::storage::notes::NotesStore;
+
+const SELFTEST_MACHINE_SCHEMA: &str = "whogitit.selftest.v1";
+
+const ORIGINAL_LIB: &str = "fn original() {}\n";
+const AI_LIB: &str = "fn original() {}\nfn ai_added() {\n    ai();\n}\n";
+const HUMAN_LIB: &str = "fn original() {}\nfn ai_added() {\n    ai();\n}\n// Reviewed by a human\n";
+const AI_NEW_FILE: &str = "pub fn generated() {}\n";
+
+/// Selftest command arguments
+#[derive(Debug, Args)]
+pub struct SelftestArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+
+    /// Keep the temporary repository for inspection
+    #[arg(long)]
+    pub keep: bool,
+}
+
+/// Outcome of a single selftest step
+#[derive(Debug, Clone, Serialize)]
+pub struct SelftestStep {
+    pub name: &'static str,
+    /// "environment" or "pipeline"
+    pub category: &'static str,
+    pub passed: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_hint: Option<String>,
+    pub duration_ms: u128,
+}
+
+/// Run the selftest command
+pub fn run(args: SelftestArgs) -> Result<()> {
+    let mut steps = environment_steps();
+
+    let dir = std::env::temp_dir().join(format!("whogitit-selftest-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).context("Failed to create temporary directory")?;
+    steps.extend(run_pipeline(&dir));
+    if !args.keep {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    let passed = steps.iter().all(|s| s.passed);
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&steps, args.keep.then_some(dir.as_path())),
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": SELFTEST_MACHINE_SCHEMA,
+                "passed": passed,
+                "version": env!("CARGO_PKG_VERSION"),
+                "os": std::env::consts::OS,
+                "temp_repo": args.keep.then(|| dir.display().to_string()),
+                "steps": steps,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if !passed {
+        let failed = steps.iter().filter(|s| !s.passed).count();
+        anyhow::bail!(
+            "Self-test failed: {} of {} checks failed",
+            failed,
+            steps.len()
+        );
+    }
+    Ok(())
+}
+
+/// Installation checks shared with `whogitit doctor`
+fn environment_steps() -> Vec<SelftestStep> {
+    [
+        check_environment as fn() -> _,
+        check_hook_installed,
+        check_hook_executable,
+        check_required_tools,
+    ]
+    .iter()
+    .map(|check| {
+        let start = Instant::now();
+        let result = check();
+        SelftestStep {
+            name: result.name,
+            category: "environment",
+            passed: result.passed,
+            message: result.message,
+            fix_hint: result.fix_hint,
+            duration_ms: start.elapsed().as_millis(),
+        }
+    })
+    .collect()
+}
+
+/// Run the end-to-end pipeline in `dir`, stopping at the first failed step
+fn run_pipeline(dir: &Path) -> Vec<SelftestStep> {
+    let mut steps = Vec::new();
+    let mut ctx = PipelineContext {
+        dir: dir.to_path_buf(),
+        repo: None,
+        hook: None,
+    };
+
+    let pipeline: [(&'static str, PipelineStep); 7] = [
+        ("Create repository", create_repository),
+        ("Capture edits", capture_edits),
+        ("Commit", commit_changes),
+        ("Post-commit analysis", post_commit),
+        ("show", verify_show),
+        ("summary", verify_summary),
+        ("blame", verify_blame),
+    ];
+
+    for (name, step) in pipeline {
+        let start = Instant::now();
+        let result = step(&mut ctx);
+        let passed = result.is_ok();
+        steps.push(SelftestStep {
+            name,
+            category: "pipeline",
+            passed,
+            message: match result {
+                Ok(message) => message,
+                Err(e) => format!("{:#}", e),
+            },
+            fix_hint: None,
+            duration_ms: start.elapsed().as_millis(),
+        });
+        if !passed {
+            break;
+        }
+    }
+    steps
+}
+
+type PipelineStep = fn(&mut PipelineContext) -> Result<String>;
+
+struct PipelineContext {
+    dir: PathBuf,
+    repo: Option<Repository>,
+    hook: Option<CaptureHook>,
+}
+
+impl PipelineContext {
+    fn repo(&self) -> Result<&Repository> {
+        self.repo
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Repository not created"))
+    }
+
+    fn hook(&self) -> Result<&CaptureHook> {
+        self.hook
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Capture hook not initialized"))
+    }
+}
+
+fn commit_all(repo: &Repository, message: &str) -> Result<git2::Oid> {
+    let mut index = repo.index()?;
+    index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let sig = Signature::now("whogitit selftest", "selftest@whogitit.invalid")?;
+    let parents: Vec<git2::Commit> = repo
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .into_iter()
+        .collect();
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    Ok(repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)?)
+}
+
+fn create_repository(ctx: &mut PipelineContext) -> Result<String> {
+    let repo = Repository::init(&ctx.dir).context("git init failed")?;
+    std::fs::create_dir_all(ctx.dir.join("src"))?;
+    std::fs::write(ctx.dir.join("src/lib.rs"), ORIGINAL_LIB)?;
+    std::fs::write(
+        ctx.dir.join(".git/info/exclude"),
+        ".whogitit-pending.json\n.whogitit-pending.lock\n.whogitit-pending.tmp\n",
+    )?;
+    commit_all(&repo, "Initial commit")?;
+    ctx.repo = Some(repo);
+    Ok(format!("Initialized {}", ctx.dir.display()))
+}
+
+fn capture_edits(ctx: &mut PipelineContext) -> Result<String> {
+    let hook = CaptureHook::new(&ctx.dir)?;
+    let session = uuid::Uuid::new_v4().to_string();
+    let model = ModelInfo::new("selftest-model", "whogitit");
+
+    let edits = [
+        ("Edit", "src/lib.rs", Some(ORIGINAL_LIB), AI_LIB),
+        ("Write", "src/generated.rs", None, AI_NEW_FILE),
+    ];
+    for (tool, path, before, after) in edits {
+        hook.on_file_change_for_session(
+            HookInput {
+                tool: tool.to_string(),
+                file_path: path.to_string(),
+                prompt: "whogitit selftest".to_string(),
+                old_content: before.map(str::to_string),
+                old_content_present: before.is_some(),
+                new_content: after.to_string(),
+                context: None,
+            },
+            Some(&session),
+            Some(model.clone()),
+        )?;
+        std::fs::write(ctx.dir.join(path), after)?;
+    }
+    // A human tweak on top of the AI edit
+    std::fs::write(ctx.dir.join("src/lib.rs"), HUMAN_LIB)?;
+
+    let store = PendingStore::new(&ctx.dir);
+    let buffer = store
+        .load_quiet()?
+        .ok_or_else(|| anyhow::anyhow!("Pending buffer was not written"))?;
+    if buffer.file_count() != 2 {
+        anyhow::bail!("Expected 2 pending files, found {}", buffer.file_count());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(store.path())?.permissions().mode() & 0o777;
+        if mode != 0o600 {
+            anyhow::bail!("Pending buffer has mode {:o}, expected 600", mode);
+        }
+    }
+
+    ctx.hook = Some(hook);
+    Ok(format!("Recorded {} edits", buffer.total_edits()))
+}
+
+fn commit_changes(ctx: &mut PipelineContext) -> Result<String> {
+    let oid = commit_all(ctx.repo()?, "AI-assisted change")?;
+    Ok(format!("Created commit {}", &oid.to_string()[..7]))
+}
+
+fn post_commit(ctx: &mut PipelineContext) -> Result<String> {
+    let attribution = ctx
+        .hook()?
+        .on_post_commit()?
+        .ok_or_else(|| anyhow::anyhow!("No attribution was produced"))?;
+    if PendingStore::new(&ctx.dir).exists() {
+        anyhow::bail!("Pending buffer was not cleared after commit");
+    }
+    Ok(format!("Attributed {} files", attribution.files.len()))
+}
+
+fn head_attribution(repo: &Repository) -> Result<AIAttribution> {
+    let head = repo.head()?.peel_to_commit()?;
+    NotesStore::new(repo)?
+        .fetch_attribution(head.id())?
+        .ok_or_else(|| anyhow::anyhow!("No git note found on HEAD"))
+}
+
+fn verify_show(ctx: &mut PipelineContext) -> Result<String> {
+    let attribution = head_attribution(ctx.repo()?)?;
+    let mut paths: Vec<&str> = attribution.files.iter().map(|f| f.path.as_str()).collect();
+    paths.sort_unstable();
+    if paths != ["src/generated.rs", "src/lib.rs"] {
+        anyhow::bail!("Unexpected files in note: {}", paths.join(", "));
+    }
+    if attribution.session.model.id != "selftest-model" {
+        anyhow::bail!("Unexpected model in note: {}", attribution.session.model.id);
+    }
+    Ok(format!("Note on HEAD covers {} files", paths.len()))
+}
+
+fn verify_summary(ctx: &mut PipelineContext) -> Result<String> {
+    let attribution = head_attribution(ctx.repo()?)?;
+    let counts = (
+        attribution.total_ai_lines(),
+        attribution.total_human_lines(),
+        attribution.total_original_lines(),
+    );
+    if counts != (4, 1, 1) {
+        anyhow::bail!(
+            "Expected 4 AI / 1 human / 1 original lines, got {} / {} / {}",
+            counts.0,
+            counts.1,
+            counts.2
+        );
+    }
+    Ok("4 AI, 1 human, 1 original line(s)".to_string())
+}
+
+fn verify_blame(ctx: &mut PipelineContext) -> Result<String> {
+    let repo = ctx.repo()?;
+    let mut blamer = AIBlamer::new(repo)?;
+    let result = blamer.blame("src/lib.rs", None)?;
+    let ai = result.pure_ai_line_count();
+    let total = result.lines.len();
+    if ai != 3 || total != 5 {
+        anyhow::bail!(
+            "Expected 3 of 5 lines attributed to AI, got {} of {}",
+            ai,
+            total
+        );
+    }
+    Ok("3 of 5 lines in src/lib.rs attributed to AI".to_string())
+}
+
+fn print_pretty(steps: &[SelftestStep], kept: Option<&Path>) {
+    println!("{}", "whogitit self-test".bold());
+    println!();
+    for step in steps {
+        let status = if step.passed {
+            "[OK]".green()
+        } else {
+            "[FAIL]".red()
+        };
+        println!(
+            "{} {}: {} {}",
+            status,
+            step.name,
+            step.message,
+            format!("({}ms)", step.duration_ms).dimmed()
+        );
+        if let Some(hint) = &step.fix_hint {
+            if !step.passed {
+                println!("   Fix: {}", hint);
+            }
+        }
+    }
+    if let Some(dir) = kept {
+        println!();
+        println!("Test repository kept at {}", dir.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_passes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let steps = run_pipeline(dir.path());
+        for step in &steps {
+            assert!(step.passed, "{}: {}", step.name, step.message);
+        }
+        assert_eq!(steps.len(), 7);
+    }
+
+    #[test]
+    fn test_pipeline_stops_at_first_failure() {
+        let dir = tempfile::TempDir::new().unwrap();
+        // A file where the repository should go makes `git init` fail
+        let blocked = dir.path().join("repo");
+        std::fs::write(&blocked, "not a directory").unwrap();
+
+        let steps = run_pipeline(&blocked);
+        assert_eq!(steps.len(), 1);
+        assert!(!steps[0].passed);
+    }
+}
@@ -0,0 +1,573 @@
+//! Serve command - read-only HTTP API for attribution queries
+//!
+//! Dashboards query attribution over HTTP instead of running whogitit per
+//! request. Responses are the JSON that the matching commands print:
+//!
+//! - `GET /blame/<path>[?rev=<rev>]` - `blame --format json`
+//! - `GET /commit/<rev>` - `show --format json`
+//! - `GET /summary[?base=<rev>&head=<rev>]` - `summary --format json`
+//!
+//! The repository is reopened for every request, so new commits and fetched
+//! notes are served without a restart. Prompt text is served like `show`
+//! prints it, and recorded in the audit log the same way.
+//!
+//! Requests must name the server by IP address, `localhost` or a host given with
+//! `--allow-host`, so a web page can't read the API through DNS rebinding.
+
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::Repository;
+use serde_json::json;
+
+use crate::cli::output::{format_blame, OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::cli::show;
+use crate::cli::summary::{self, SummaryArgs, SummaryFormat};
+use crate::core::blame::AIBlamer;
+use crate::storage::notes::NotesStore;
+
+const SERVE_MACHINE_SCHEMA: &str = "whogitit.serve.v1";
+
+/// Longest request line and header block accepted
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+
+/// Time a client has to send the whole request line and headers
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections handled at once; further connections wait in the listen backlog
+const MAX_CONCURRENT_CONNECTIONS: usize = 16;
+
+/// Serve command arguments
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:7374")]
+    pub bind: SocketAddr,
+
+    /// Value of Access-Control-Allow-Origin, for dashboards querying from a browser
+    #[arg(long, value_name = "ORIGIN")]
+    pub allow_origin: Option<String>,
+
+    /// Host name clients may use besides IP addresses and localhost (repeatable)
+    #[arg(long, value_name = "HOST")]
+    pub allow_host: Vec<String>,
+}
+
+/// Status and JSON body of a response
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Response {
+    fn ok(body: serde_json::Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            431 => "Request Header Fields Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+/// Run the serve command
+pub fn run(args: ServeArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let git_dir = repo.path().to_path_buf();
+    let listener = TcpListener::bind(args.bind)
+        .with_context(|| format!("Failed to listen on {}", args.bind))?;
+
+    if !args.bind.ip().is_loopback() {
+        eprintln!(
+            "whogitit: Warning - serving on {}; anyone who can reach it can read prompts \
+             stored in notes",
+            args.bind
+        );
+    }
+    eprintln!(
+        "whogitit: Serving attribution for {} on http://{}",
+        repo.workdir().unwrap_or(repo.path()).display(),
+        listener.local_addr()?
+    );
+
+    let allow_origin: Arc<Option<String>> = Arc::new(args.allow_origin);
+    let allow_host: Arc<Vec<String>> = Arc::new(args.allow_host);
+    let slots = Arc::new((Mutex::new(0usize), Condvar::new()));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let slot = ConnectionSlot::acquire(&slots);
+        let git_dir = git_dir.clone();
+        let allow_origin = Arc::clone(&allow_origin);
+        let allow_host = Arc::clone(&allow_host);
+        std::thread::spawn(move || {
+            let _slot = slot;
+            if let Err(e) =
+                handle_connection(stream, &git_dir, allow_origin.as_deref(), &allow_host)
+            {
+                eprintln!("whogitit: Request failed: {:#}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// One of `MAX_CONCURRENT_CONNECTIONS` handler slots, released on drop
+struct ConnectionSlot(Arc<(Mutex<usize>, Condvar)>);
+
+impl ConnectionSlot {
+    /// Wait until fewer than `MAX_CONCURRENT_CONNECTIONS` handlers run and take a slot
+    fn acquire(slots: &Arc<(Mutex<usize>, Condvar)>) -> Self {
+        let (count, freed) = &**slots;
+        let mut count = count.lock().unwrap_or_else(|e| e.into_inner());
+        while *count >= MAX_CONCURRENT_CONNECTIONS {
+            count = freed.wait(count).unwrap_or_else(|e| e.into_inner());
+        }
+        *count += 1;
+        Self(Arc::clone(slots))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        let (count, freed) = &*self.0;
+        *count.lock().unwrap_or_else(|e| e.into_inner()) -= 1;
+        freed.notify_one();
+    }
+}
+
+/// Reads a connection against one deadline for the whole request
+///
+/// A socket read timeout alone restarts with every byte, so a client trickling
+/// bytes could hold a handler slot indefinitely.
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+/// Method, target and Host header of a request
+#[derive(Debug)]
+struct RequestHead {
+    method: String,
+    target: String,
+    host: Option<String>,
+}
+
+/// Read one request, answer it and close the connection
+fn handle_connection(
+    stream: TcpStream,
+    git_dir: &Path,
+    allow_origin: Option<&str>,
+    allow_host: &[String],
+) -> Result<()> {
+    let reader = DeadlineReader {
+        stream: stream.try_clone()?,
+        deadline: Instant::now() + REQUEST_TIMEOUT,
+    };
+    // One byte over the limit tells a full request from an oversized one
+    let mut reader = BufReader::new(reader.take(MAX_REQUEST_BYTES as u64 + 1));
+
+    let (response, head_only) = match read_head(&mut reader) {
+        Err(response) => (response, false),
+        Ok(head) if !host_allowed(head.host.as_deref(), allow_host) => (
+            Response::error(
+                403,
+                "Host not allowed; use an IP address, localhost or a host given with --allow-host",
+            ),
+            head.method == "HEAD",
+        ),
+        Ok(head) => match head.method.as_str() {
+            "GET" | "HEAD" => (route(git_dir, &head.target), head.method == "HEAD"),
+            _ => (
+                Response::error(405, "Only GET and HEAD are supported"),
+                false,
+            ),
+        },
+    };
+    write_response(stream, &response, head_only, allow_origin)
+}
+
+/// Read the request line and headers, or the error response for a request that is
+/// too large, too slow or unreadable
+fn read_head(reader: &mut impl BufRead) -> Result<RequestHead, Response> {
+    let mut request_line = None;
+    // Only Host matters: requests carry everything else in the target
+    let mut host = None;
+    let mut bytes = 0;
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line).map_err(|e| match e.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => {
+                Response::error(408, "Request not received in time")
+            }
+            _ => Response::error(400, "Malformed request"),
+        })?;
+        bytes += read;
+        if bytes > MAX_REQUEST_BYTES {
+            return Err(Response::error(431, "Request too large"));
+        }
+        if request_line.is_none() {
+            request_line = Some(line);
+            if read == 0 {
+                break;
+            }
+            continue;
+        }
+        if read == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let request_line = request_line.unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    Ok(RequestHead {
+        method: parts.next().unwrap_or("").to_string(),
+        target: parts.next().unwrap_or("").to_string(),
+        host,
+    })
+}
+
+/// Whether a Host header names the server by IP address, localhost or an allowed name
+///
+/// DNS rebinding needs a host name the attacker controls, so IP literals are safe.
+fn host_allowed(host: Option<&str>, allow_host: &[String]) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    // Strip the port: "[::1]:7374", "127.0.0.1:7374", "localhost:7374"
+    let name = match host.strip_prefix('[') {
+        Some(rest) => match rest.split_once(']') {
+            Some((ip, _)) => ip,
+            None => return false,
+        },
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    name.parse::<IpAddr>().is_ok()
+        || name.eq_ignore_ascii_case("localhost")
+        || allow_host.iter().any(|h| h.eq_ignore_ascii_case(name))
+}
+
+fn write_response(
+    mut stream: TcpStream,
+    response: &Response,
+    head_only: bool,
+    allow_origin: Option<&str>,
+) -> Result<()> {
+    let body = serde_json::to_vec_pretty(&response.body)?;
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.reason(),
+        body.len()
+    );
+    if response.status == 405 {
+        head.push_str("Allow: GET, HEAD\r\n");
+    }
+    if let Some(origin) = allow_origin {
+        head.push_str(&format!("Access-Control-Allow-Origin: {}\r\n", origin));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    if !head_only {
+        stream.write_all(&body)?;
+    }
+    stream.flush()?;
+    Ok(())
+}
+
+/// Answer a request target
+fn route(git_dir: &Path, target: &str) -> Response {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            // Form encoding: `+` is a space in query values only
+            .and_then(|(_, value)| percent_decode(&value.replace('+', " ")))
+    };
+    let Some(path) = percent_decode(path) else {
+        return Response::error(400, "Malformed percent-encoding in path");
+    };
+
+    let result = match path.as_str() {
+        "/" => Ok(index()),
+        "/summary" => summary(git_dir, param("base"), param("head")),
+        _ => {
+            if let Some(file) = path.strip_prefix("/blame/").filter(|f| !f.is_empty()) {
+                blame(git_dir, file, param("rev"))
+            } else if let Some(rev) = path.strip_prefix("/commit/").filter(|r| !r.is_empty()) {
+                commit(git_dir, rev)
+            } else {
+                return Response::error(404, format!("No endpoint {}", path));
+            }
+        }
+    };
+    match result {
+        Ok(body) => Response::ok(body),
+        Err(e) => Response::error(400, format!("{:#}", e)),
+    }
+}
+
+fn index() -> serde_json::Value {
+    json!({
+        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+        "schema": SERVE_MACHINE_SCHEMA,
+        "version": env!("CARGO_PKG_VERSION"),
+        "endpoints": [
+            "/blame/<path>?rev=<rev>",
+            "/commit/<rev>",
+            "/summary?base=<rev>&head=<rev>",
+        ],
+    })
+}
+
+fn open(git_dir: &Path) -> Result<Repository> {
+    Repository::open(git_dir).context("Failed to open repository")
+}
+
+fn blame(git_dir: &Path, file: &str, rev: Option<String>) -> Result<serde_json::Value> {
+    let repo = open(git_dir)?;
+    let mut blamer = AIBlamer::new(&repo)?;
+    let result = blamer
+        .blame(file, rev.as_deref())
+        .with_context(|| format!("Failed to blame '{}'", file))?;
+    Ok(serde_json::from_str(&format_blame(
+        &result,
+        OutputFormat::Json,
+        false,
+    ))?)
+}
+
+fn commit(git_dir: &Path, rev: &str) -> Result<serde_json::Value> {
+    let repo = open(git_dir)?;
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Failed to resolve '{}'", rev))?;
+    show::commit_json(&repo, &NotesStore::new(&repo)?, &commit, "serve")
+}
+
+fn summary(
+    git_dir: &Path,
+    base: Option<String>,
+    head: Option<String>,
+) -> Result<serde_json::Value> {
+    let repo = open(git_dir)?;
+    let args = SummaryArgs {
+        base,
+        head: head.unwrap_or_else(|| "HEAD".to_string()),
+        format: SummaryFormat::Json,
+        fail_on_missing_attribution: false,
+        by_package: false,
+        redact_level: None,
+        reconcile: false,
+        locale: None,
+        include_generated: false,
+    };
+    summary::range_json(&repo, &NotesStore::new(&repo)?, &args)
+}
+
+/// Decode `%XX` escapes; `None` if malformed or not UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'%' => {
+                let hex = std::str::from_utf8(rest.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &rest[2..];
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attribution::{AIAttribution, ModelInfo, SessionMetadata};
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn repo_with_attributed_commit() -> (TempDir, git2::Oid) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Dev", "dev@example.com").unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "Add a", &tree, &[])
+            .unwrap();
+        let attribution = AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 0,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files: vec![],
+        };
+        NotesStore::new(&repo)
+            .unwrap()
+            .store_attribution(oid, &attribution)
+            .unwrap();
+        (dir, oid)
+    }
+
+    #[test]
+    fn test_route_endpoints() {
+        let (dir, oid) = repo_with_attributed_commit();
+        let git_dir = &dir.path().join(".git");
+
+        let commit = route(git_dir, "/commit/HEAD");
+        assert_eq!(commit.status, 200);
+        assert_eq!(commit.body["schema"], "whogitit.show.v1");
+        assert_eq!(commit.body["has_attribution"], true);
+        assert_eq!(commit.body["commit"], oid.to_string());
+
+        let blame = route(git_dir, "/blame/lib.rs?rev=HEAD");
+        assert_eq!(blame.status, 200);
+        assert_eq!(blame.body["file"], "lib.rs");
+
+        let summary = route(git_dir, "/summary?head=HEAD");
+        assert_eq!(summary.status, 200);
+        assert_eq!(summary.body["schema"], "whogitit.summary.v1");
+        assert_eq!(summary.body["commits_with_ai"], 1);
+
+        assert_eq!(route(git_dir, "/").body["schema"], SERVE_MACHINE_SCHEMA);
+        assert_eq!(route(git_dir, "/blame/missing.rs").status, 400);
+        assert_eq!(route(git_dir, "/commit/nope").status, 400);
+        assert_eq!(route(git_dir, "/prompts").status, 404);
+    }
+
+    #[test]
+    fn test_oversized_header_line_without_newline() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_connection(stream, Path::new("."), None, &[]).unwrap();
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        let mut request = b"GET / HTTP/1.1\r\nX-Padding: ".to_vec();
+        request.resize(MAX_REQUEST_BYTES + 1, b'a');
+        client.write_all(&request).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        server.join().unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 431 "), "{}", response);
+    }
+
+    #[test]
+    fn test_read_head_reports_timeout() {
+        struct Stalled(ErrorKind);
+        impl Read for Stalled {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(self.0.into())
+            }
+        }
+
+        for kind in [ErrorKind::TimedOut, ErrorKind::WouldBlock] {
+            let mut reader = BufReader::new(Stalled(kind));
+            assert_eq!(read_head(&mut reader).unwrap_err().status, 408);
+        }
+
+        let mut reader = "GET /summary HTTP/1.1\r\nHost: localhost:7374\r\n\r\n".as_bytes();
+        let head = read_head(&mut reader).unwrap();
+        assert_eq!(head.method, "GET");
+        assert_eq!(head.target, "/summary");
+        assert_eq!(head.host.as_deref(), Some("localhost:7374"));
+    }
+
+    #[test]
+    fn test_deadline_reader_stops_at_deadline() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = DeadlineReader {
+            stream,
+            deadline: Instant::now() + Duration::from_millis(50),
+        };
+        let started = Instant::now();
+        let err = reader.read(&mut [0; 16]).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::TimedOut | ErrorKind::WouldBlock
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        drop(client);
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        let allowed = vec!["attribution.internal".to_string()];
+        assert!(host_allowed(Some("127.0.0.1:7374"), &[]));
+        assert!(host_allowed(Some("[::1]:7374"), &[]));
+        assert!(host_allowed(Some("LOCALHOST:7374"), &[]));
+        assert!(host_allowed(Some("attribution.internal"), &allowed));
+        // Rebinding: the browser sends the attacker's host name
+        assert!(!host_allowed(Some("evil.example.com:7374"), &allowed));
+        assert!(!host_allowed(Some("localhost.evil.example.com"), &[]));
+        assert!(!host_allowed(Some("[::1"), &[]));
+        assert!(!host_allowed(None, &[]));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("src/main.rs").unwrap(), "src/main.rs");
+        assert_eq!(percent_decode("a%20b+c%2Fd").unwrap(), "a b+c/d");
+        assert_eq!(percent_decode("caf%C3%A9").unwrap(), "café");
+        assert!(percent_decode("bad%2").is_none());
+        assert!(percent_decode("bad%zz").is_none());
+        assert!(percent_decode("%FF").is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Session command - explicit, time-boxed attribution sessions
+//!
+//! `whogitit session begin` writes `.git/whogitit/session.json`, which the capture
+//! hook reads on every edit; `whogitit session end` removes it. See
+//! [`crate::capture::session`].
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use git2::Repository;
+
+use crate::capture::pending::PendingStore;
+use crate::capture::session::ActiveSession;
+
+/// Session command arguments
+#[derive(Debug, Args)]
+pub struct SessionArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub action: SessionAction,
+}
+
+/// Session subcommands
+#[derive(Debug, Subcommand)]
+pub enum SessionAction {
+    /// Begin a session; edits are recorded under its ID, model and label until it ends
+    Begin {
+        /// Model ID to record for edits (default: the model reported by the hook)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Provider of --model
+        #[arg(long, requires = "model", default_value = "anthropic")]
+        provider: String,
+
+        /// Label shown in notes and summaries (e.g. a ticket number)
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// End the active session
+    End,
+    /// Show the active session
+    Status,
+}
+
+/// Run the session command
+pub fn run(args: SessionArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let git_dir = repo.path();
+
+    match args.action {
+        SessionAction::Begin {
+            model,
+            provider,
+            label,
+        } => {
+            if let Some(active) = ActiveSession::load(git_dir)? {
+                anyhow::bail!(
+                    "Session {} is already active. Run 'whogitit session end' first.",
+                    describe(&active)
+                );
+            }
+
+            let provider = model.is_some().then_some(provider);
+            let mut session = ActiveSession::begin(model, provider, label);
+
+            // Keep pending edits: continue their session instead of starting a new one,
+            // which would make the capture hook discard them
+            let workdir = repo
+                .workdir()
+                .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+            if let Some(pending) = PendingStore::new(workdir).load_quiet()? {
+                if pending.has_changes() {
+                    println!(
+                        "Continuing session {} with {} pending edit(s).",
+                        pending.session.session_id,
+                        pending.total_edits()
+                    );
+                    session.session_id = pending.session.session_id;
+                }
+            }
+
+            session.save(git_dir)?;
+            println!("{} session {}", "Began".green().bold(), describe(&session));
+            if let Some(model) = &session.model {
+                println!("  Model: {}", model);
+            }
+            println!("Run 'whogitit session end' when done.");
+        }
+        SessionAction::End => match ActiveSession::end(git_dir)? {
+            Some(session) => {
+                println!(
+                    "{} session {} ({})",
+                    "Ended".green().bold(),
+                    describe(&session),
+                    duration(&session)
+                );
+                println!("Edits still pending keep the session until the next commit.");
+            }
+            None => println!("No active session."),
+        },
+        SessionAction::Status => match ActiveSession::load(git_dir)? {
+            Some(session) => {
+                println!("Active session: {}", describe(&session));
+                if let Some(model) = &session.model {
+                    println!("  Model: {}", model);
+                }
+                println!("  Started: {} ({})", session.started_at, duration(&session));
+            }
+            None => println!("No active session."),
+        },
+    }
+
+    Ok(())
+}
+
+/// Session label and ID for display
+pub(crate) fn describe(session: &ActiveSession) -> String {
+    match &session.label {
+        Some(label) => format!("{} ({})", label.cyan(), session.session_id),
+        None => session.session_id.cyan().to_string(),
+    }
+}
+
+/// How long a session has been running
+fn duration(session: &ActiveSession) -> String {
+    let Ok(started) = DateTime::parse_from_rfc3339(&session.started_at) else {
+        return "unknown duration".to_string();
+    };
+    let minutes = (Utc::now() - started.with_timezone(&Utc)).num_minutes();
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
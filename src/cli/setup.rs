@@ -3,15 +3,26 @@
 //! The `setup` command handles one-time global configuration:
 //! - Installing the capture hook script to ~/.claude/hooks/
 //! - Configuring Claude Code settings.json with hook configuration
+//! - Printing integration instructions for other agents (`--provider generic`)
+//! - Registering the Cursor capture commands in `~/.cursor/hooks.json`
+//!   (`--agent cursor`)
 //!
 //! The `doctor` command verifies the configuration is correct.
+//!
+//! The Claude config directory is `$WHOGITIT_HOME/.claude` when `WHOGITIT_HOME`
+//! is set (see [`crate::cli::environment`]), otherwise `~/.claude`. With
+//! `setup --project` the hook script and settings go to `<repo>/.claude/` instead,
+//! so they can be committed and shared with the team.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
 use serde_json::{json, Value};
 
+use crate::cli::environment::{self, RuntimeEnvironment, ENV_WHOGITIT_HOME};
+
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
@@ -20,16 +31,18 @@ pub const CAPTURE_HOOK_SCRIPT: &str = include_str!("../../hooks/whogitit-capture
 
 /// Get the Claude configuration directory path
 ///
-/// Returns None if home directory cannot be determined (e.g., in containerized environments).
+/// Honors `WHOGITIT_HOME`. Returns None if no home directory can be determined
+/// (e.g., in containerized environments without `HOME`).
 pub fn claude_config_dir() -> Option<PathBuf> {
-    dirs::home_dir().map(|h| h.join(".claude"))
+    environment::home_dir().map(|h| h.join(".claude"))
 }
 
 /// Get the Claude configuration directory path, or error if unavailable
 pub fn claude_config_dir_required() -> Result<PathBuf> {
     claude_config_dir().ok_or_else(|| {
         anyhow::anyhow!(
-            "Could not determine home directory. Set HOME environment variable or run with --help for alternatives."
+            "Could not determine home directory. Set HOME or {} to the directory containing .claude/.",
+            ENV_WHOGITIT_HOME
         )
     })
 }
@@ -49,8 +62,85 @@ pub fn capture_hook_path() -> Option<PathBuf> {
     claude_hooks_dir().map(|h| h.join("whogitit-capture.sh"))
 }
 
-/// The hook configuration that needs to be in settings.json
-fn hook_configuration() -> Value {
+/// Capture hook path as written into settings.json commands
+///
+/// `~` keeps settings portable across machines. With `WHOGITIT_HOME` the AI tool's
+/// `~` may be a different directory, so the absolute path is used instead.
+fn hook_command_path() -> String {
+    match (environment::home_override(), capture_hook_path()) {
+        (Some(_), Some(path)) => format!("'{}'", path.display()),
+        _ => "~/.claude/hooks/whogitit-capture.sh".to_string(),
+    }
+}
+
+/// Capture hook path in project settings, resolved by Claude Code at run time
+const PROJECT_HOOK_COMMAND_PATH: &str = "\"$CLAUDE_PROJECT_DIR\"/.claude/hooks/whogitit-capture.sh";
+
+/// Where setup installs the capture hook and its settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetupScope {
+    /// User-level `~/.claude` (or `$WHOGITIT_HOME/.claude`)
+    Global,
+    /// Project-level `<repo>/.claude`, committed with the repository
+    Project(PathBuf),
+}
+
+impl SetupScope {
+    /// Project scope when the current repository has whogitit hooks in its own
+    /// `.claude/settings.json`, otherwise global
+    pub fn detect() -> Self {
+        git2::Repository::discover(".")
+            .ok()
+            .and_then(|repo| repo.workdir().map(Path::to_path_buf))
+            .map(SetupScope::Project)
+            .filter(|scope| scope.settings_configured())
+            .unwrap_or(SetupScope::Global)
+    }
+
+    /// The `.claude` directory for this scope
+    pub fn claude_dir(&self) -> Option<PathBuf> {
+        match self {
+            SetupScope::Global => claude_config_dir(),
+            SetupScope::Project(root) => Some(root.join(".claude")),
+        }
+    }
+
+    /// Capture hook script path for this scope
+    pub fn hook_path(&self) -> Option<PathBuf> {
+        self.claude_dir()
+            .map(|c| c.join("hooks").join("whogitit-capture.sh"))
+    }
+
+    /// settings.json path for this scope
+    pub fn settings_path(&self) -> Option<PathBuf> {
+        self.claude_dir().map(|c| c.join("settings.json"))
+    }
+
+    /// Command that (re)creates this scope's setup
+    pub fn setup_command(&self) -> &'static str {
+        match self {
+            SetupScope::Global => "whogitit setup",
+            SetupScope::Project(_) => "whogitit setup --project",
+        }
+    }
+
+    /// The hook configuration that needs to be in settings.json
+    fn hook_configuration(&self) -> Value {
+        match self {
+            SetupScope::Global => hook_configuration_for(&hook_command_path()),
+            SetupScope::Project(_) => hook_configuration_for(PROJECT_HOOK_COMMAND_PATH),
+        }
+    }
+
+    fn settings_configured(&self) -> bool {
+        self.settings_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .is_some_and(|settings| has_whogitit_hooks(&settings))
+    }
+}
+
+fn hook_configuration_for(hook_path: &str) -> Value {
     json!({
         "PreToolUse": [
             {
@@ -58,7 +148,7 @@ fn hook_configuration() -> Value {
                 "hooks": [
                     {
                         "type": "command",
-                        "command": "WHOGITIT_HOOK_PHASE=pre ~/.claude/hooks/whogitit-capture.sh"
+                        "command": format!("WHOGITIT_HOOK_PHASE=pre {}", hook_path)
                     }
                 ]
             }
@@ -69,7 +159,7 @@ fn hook_configuration() -> Value {
                 "hooks": [
                     {
                         "type": "command",
-                        "command": "WHOGITIT_HOOK_PHASE=post ~/.claude/hooks/whogitit-capture.sh"
+                        "command": format!("WHOGITIT_HOOK_PHASE=post {}", hook_path)
                     }
                 ]
             }
@@ -113,9 +203,7 @@ fn has_whogitit_phase_hook(settings: &Value, phase_key: &str, phase_value: &str)
 }
 
 /// Merge whogitit hooks into existing settings
-fn merge_hooks_into_settings(mut settings: Value) -> Value {
-    let hook_config = hook_configuration();
-
+fn merge_hooks_into_settings(mut settings: Value, hook_config: &Value) -> Value {
     // Ensure hooks object exists
     let hooks_is_object = settings
         .get("hooks")
@@ -185,9 +273,14 @@ impl SetupStatus {
     }
 }
 
-/// Check the current setup status
+/// Check the current setup status (project setup counts when present)
 pub fn check_setup_status() -> SetupStatus {
-    let claude_dir = match claude_config_dir() {
+    check_setup_status_for(&SetupScope::detect())
+}
+
+/// Check the setup status of one scope
+pub fn check_setup_status_for(scope: &SetupScope) -> SetupStatus {
+    let claude_dir = match scope.claude_dir() {
         Some(dir) => dir,
         None => {
             return SetupStatus {
@@ -200,7 +293,6 @@ pub fn check_setup_status() -> SetupStatus {
     };
 
     let hook_path = claude_dir.join("hooks").join("whogitit-capture.sh");
-    let settings_path = claude_dir.join("settings.json");
 
     let claude_dir_exists = claude_dir.exists();
     let hook_script_installed = hook_path.exists();
@@ -220,15 +312,7 @@ pub fn check_setup_status() -> SetupStatus {
         false
     };
 
-    let settings_configured = if settings_path.exists() {
-        fs::read_to_string(&settings_path)
-            .ok()
-            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
-            .map(|settings| has_whogitit_hooks(&settings))
-            .unwrap_or(false)
-    } else {
-        false
-    };
+    let settings_configured = scope.settings_configured();
 
     SetupStatus {
         hook_script_installed,
@@ -239,14 +323,16 @@ pub fn check_setup_status() -> SetupStatus {
 }
 
 /// Install the capture hook script
-fn install_hook_script() -> Result<bool> {
-    let hooks_dir =
-        claude_hooks_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
-    let hook_path = hooks_dir.join("whogitit-capture.sh");
+fn install_hook_script(scope: &SetupScope) -> Result<bool> {
+    let hook_path = scope
+        .hook_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+    let hooks_dir = hook_path.parent().expect("hook path has a parent");
 
     // Create hooks directory if needed
     if !hooks_dir.exists() {
-        fs::create_dir_all(&hooks_dir).context("Failed to create ~/.claude/hooks directory")?;
+        fs::create_dir_all(hooks_dir)
+            .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
     }
 
     // Check if already installed with same content
@@ -272,20 +358,23 @@ fn install_hook_script() -> Result<bool> {
 }
 
 /// Configure Claude Code settings.json
-fn configure_settings() -> Result<bool> {
-    let claude_dir =
-        claude_config_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+fn configure_settings(scope: &SetupScope) -> Result<bool> {
+    let claude_dir = scope
+        .claude_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
     let settings_path = claude_dir.join("settings.json");
 
     // Create .claude directory if needed
     if !claude_dir.exists() {
-        fs::create_dir_all(&claude_dir).context("Failed to create ~/.claude directory")?;
+        fs::create_dir_all(&claude_dir)
+            .with_context(|| format!("Failed to create {}", claude_dir.display()))?;
     }
 
     // Load existing settings or create new
     let settings: Value = if settings_path.exists() {
         let content = fs::read_to_string(&settings_path)?;
-        serde_json::from_str(&content).context("Failed to parse ~/.claude/settings.json")?
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", settings_path.display()))?
     } else {
         json!({})
     };
@@ -302,20 +391,257 @@ fn configure_settings() -> Result<bool> {
     }
 
     // Merge and write new settings
-    let new_settings = merge_hooks_into_settings(settings);
+    let new_settings = merge_hooks_into_settings(settings, &scope.hook_configuration());
     let formatted = serde_json::to_string_pretty(&new_settings)?;
     fs::write(&settings_path, formatted).context("Failed to write settings.json")?;
 
     Ok(true)
 }
 
+/// AI tool integration to set up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SetupProvider {
+    /// Install the Claude Code capture hook
+    #[default]
+    Claude,
+    /// Print integration instructions for the generic-v1 stdin schema
+    Generic,
+    /// Register the capture command in Cursor's hooks.json
+    Cursor,
+}
+
+/// Setup command arguments
+#[derive(Debug, Args)]
+pub struct SetupArgs {
+    /// Integration to configure
+    #[arg(long, alias = "agent", value_enum, default_value_t = SetupProvider::Claude)]
+    pub provider: SetupProvider,
+
+    /// Configure hooks in the current repository's .claude/ (or .cursor/) instead of the home directory
+    #[arg(long)]
+    pub project: bool,
+}
+
+/// Integration instructions for agents using the generic-v1 schema
+pub fn generic_provider_instructions() -> String {
+    let example = json!({
+        "version": crate::capture::generic::GENERIC_SCHEMA_VERSION,
+        "tool": "Edit",
+        "file": "src/lib.rs",
+        "before": "fn old() {}\n",
+        "after": "fn new() {}\n",
+        "prompt": "Rename old to new",
+        "model": "gpt-5-codex",
+        "provider": "openai",
+        "session": "3f2c9a1e-7b4d-4c1a-9e8f-2d6b5a0c1e7f"
+    });
+
+    format!(
+        "Generic provider integration (generic-v1)
+
+After each file change, have your agent pipe one JSON document to:
+
+  whogitit capture --stdin --provider generic-v1
+
+Run it from inside the repository (after 'whogitit init'). Example payload:
+
+{}
+
+Fields:
+  version   Schema version (optional, must be 1)
+  tool      Tool or action that made the change (required)
+  file      Path, absolute or relative to the repository root (required)
+  before    Content before the change (optional; omit to use HEAD)
+  after     Content after the change (required)
+  prompt    User prompt that triggered the change (optional)
+  model     Model identifier (optional; falls back to WHOGITIT_MODEL_ID)
+  provider  Model provider (optional; defaults to \"unknown\")
+  session   Session UUID (optional; falls back to WHOGITIT_SESSION_ID)
+
+Changes with the same session are grouped into one pending session until commit.",
+        serde_json::to_string_pretty(&example).unwrap_or_default()
+    )
+}
+
+/// Cursor hook events whogitit listens to
+const CURSOR_HOOK_EVENTS: [&str; 2] = ["beforeSubmitPrompt", "afterFileEdit"];
+
+/// Command Cursor runs for each hook event
+const CURSOR_CAPTURE_COMMAND: &str = "whogitit capture --stdin --provider cursor";
+
+/// Add the capture command to a Cursor hooks.json document
+///
+/// Returns `None` when every event already runs it.
+fn merge_cursor_hooks(mut hooks_json: Value) -> Option<Value> {
+    if !hooks_json.is_object() {
+        hooks_json = json!({});
+    }
+    let root = hooks_json.as_object_mut()?;
+    root.entry("version").or_insert(json!(1));
+    let hooks = root.entry("hooks").or_insert_with(|| json!({}));
+    if !hooks.is_object() {
+        *hooks = json!({});
+    }
+
+    let mut changed = false;
+    for event in CURSOR_HOOK_EVENTS {
+        let entries = hooks
+            .as_object_mut()?
+            .entry(event)
+            .or_insert_with(|| json!([]));
+        if !entries.is_array() {
+            *entries = json!([]);
+        }
+        let entries = entries.as_array_mut()?;
+        let present = entries.iter().any(|entry| {
+            entry["command"]
+                .as_str()
+                .is_some_and(|command| command.contains("--provider cursor"))
+        });
+        if !present {
+            entries.push(json!({ "command": CURSOR_CAPTURE_COMMAND }));
+            changed = true;
+        }
+    }
+    changed.then_some(hooks_json)
+}
+
+/// Whether Cursor's user or project hooks.json runs the capture command
+pub fn cursor_hooks_configured(repo_root: Option<&Path>) -> bool {
+    let user = environment::home_dir().map(|home| home.join(".cursor/hooks.json"));
+    let project = repo_root.map(|root| root.join(".cursor/hooks.json"));
+    [user, project].into_iter().flatten().any(|path| {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .is_some_and(|hooks_json| merge_cursor_hooks(hooks_json).is_none())
+    })
+}
+
+/// Register the capture command in `~/.cursor/hooks.json` (or `<repo>/.cursor/`)
+fn run_cursor_setup(project: bool) -> Result<()> {
+    let cursor_dir = if project {
+        let repo = git2::Repository::discover(".").context("Not in a git repository")?;
+        repo.workdir()
+            .ok_or_else(|| anyhow::anyhow!("Repository has no working directory"))?
+            .join(".cursor")
+    } else {
+        environment::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?
+            .join(".cursor")
+    };
+    let hooks_path = cursor_dir.join("hooks.json");
+
+    println!("Setting up whogitit for Cursor...\n");
+
+    let existing: Value = if hooks_path.exists() {
+        let content = fs::read_to_string(&hooks_path)?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", hooks_path.display()))?
+    } else {
+        json!({})
+    };
+
+    match merge_cursor_hooks(existing) {
+        Some(merged) => {
+            fs::create_dir_all(&cursor_dir)
+                .with_context(|| format!("Failed to create {}", cursor_dir.display()))?;
+            if hooks_path.exists() {
+                fs::copy(&hooks_path, cursor_dir.join("hooks.json.backup"))
+                    .context("Failed to backup hooks.json")?;
+                println!("  (Previous hooks backed up to hooks.json.backup)");
+            }
+            fs::write(&hooks_path, serde_json::to_string_pretty(&merged)?)
+                .context("Failed to write hooks.json")?;
+            println!("  Configured Cursor hooks in {}", hooks_path.display());
+        }
+        None => println!("  Cursor hooks already configured."),
+    }
+
+    println!("\nNext steps:");
+    println!("  1. Run 'whogitit init' in each repository you want to track");
+    println!("  2. Restart Cursor so it picks up the hooks");
+    println!("\nEdits made by Cursor's agent are attributed to model 'cursor-agent' unless");
+    println!("WHOGITIT_MODEL_ID is set in Cursor's environment.");
+
+    Ok(())
+}
+
 /// Run the setup command
-pub fn run_setup() -> Result<()> {
+pub fn run_setup(args: SetupArgs) -> Result<()> {
+    if args.provider == SetupProvider::Generic {
+        println!("{}", generic_provider_instructions());
+        return Ok(());
+    }
+    if args.provider == SetupProvider::Cursor {
+        return run_cursor_setup(args.project);
+    }
+
+    if args.project {
+        return run_project_setup();
+    }
+
     println!("Setting up whogitit for Claude Code...\n");
 
+    let runtime = RuntimeEnvironment::detect();
+    if runtime.is_remote() || environment::home_override().is_some() {
+        print_environment_note(runtime);
+    }
+
+    let scope = SetupScope::Global;
+    install_and_configure(&scope, "~/.claude/settings.json")?;
+
+    println!("\nGlobal setup complete!");
+    println!("\nNext steps:");
+    println!("  1. Run 'whogitit init' in each repository you want to track");
+    println!("  2. Use Claude Code normally - AI attribution will be captured automatically");
+    println!("\nRun 'whogitit doctor' to verify your configuration at any time.");
+
+    Ok(())
+}
+
+/// Install hooks into the current repository's `.claude/` directory
+fn run_project_setup() -> Result<()> {
+    let repo = git2::Repository::discover(".").context("Not in a git repository")?;
+    let root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("Repository has no working directory"))?
+        .to_path_buf();
+
+    println!("Setting up whogitit for Claude Code in this project...\n");
+
+    let scope = SetupScope::Project(root);
+    install_and_configure(&scope, ".claude/settings.json")?;
+
+    // Claude Code runs user and project hooks side by side, which would capture
+    // every edit twice
+    if SetupScope::Global.settings_configured() {
+        println!();
+        println!("  Warning: whogitit hooks are also configured in ~/.claude/settings.json.");
+        println!("    Both would run for this project; remove the user-level hooks to avoid");
+        println!("    capturing each edit twice.");
+    }
+
+    println!("\nProject setup complete!");
+    println!("\nNext steps:");
+    println!("  1. Commit .claude/settings.json and .claude/hooks/whogitit-capture.sh");
+    println!("  2. Run 'whogitit init' to install the git hooks (each clone needs this)");
+    println!("\nRun 'whogitit doctor' to verify your configuration at any time.");
+
+    Ok(())
+}
+
+/// Install the hook script and merge settings for a scope, reporting each step
+fn install_and_configure(scope: &SetupScope, settings_label: &str) -> Result<()> {
     // Install hook script
-    match install_hook_script() {
-        Ok(true) => println!("  Installed capture hook to ~/.claude/hooks/whogitit-capture.sh"),
+    match install_hook_script(scope) {
+        Ok(true) => println!(
+            "  Installed capture hook to {}",
+            scope
+                .hook_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        ),
         Ok(false) => println!("  Capture hook already installed and up to date."),
         Err(e) => {
             return Err(e.context("Failed to install capture hook"));
@@ -323,9 +649,9 @@ pub fn run_setup() -> Result<()> {
     }
 
     // Configure settings.json
-    match configure_settings() {
+    match configure_settings(scope) {
         Ok(true) => {
-            println!("  Configured Claude Code hooks in ~/.claude/settings.json");
+            println!("  Configured Claude Code hooks in {}", settings_label);
             println!("    (Previous settings backed up to settings.json.backup)");
         }
         Ok(false) => println!("  Claude Code hooks already configured."),
@@ -334,15 +660,29 @@ pub fn run_setup() -> Result<()> {
         }
     }
 
-    println!("\nGlobal setup complete!");
-    println!("\nNext steps:");
-    println!("  1. Run 'whogitit init' in each repository you want to track");
-    println!("  2. Use Claude Code normally - AI attribution will be captured automatically");
-    println!("\nRun 'whogitit doctor' to verify your configuration at any time.");
-
     Ok(())
 }
 
+/// Explain where setup installs to when not running on a plain local host
+fn print_environment_note(runtime: RuntimeEnvironment) {
+    let config_dir = claude_config_dir()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "(unknown)".to_string());
+    println!("  Environment: {}", runtime.label());
+    println!("  Claude config: {}", config_dir);
+    if environment::home_override().is_none() {
+        println!(
+            "  Note: capture runs wherever Claude Code runs. If Claude Code runs outside this {},",
+            runtime.label()
+        );
+        println!(
+            "        run setup there instead, or set {} to the directory containing its .claude/.",
+            ENV_WHOGITIT_HOME
+        );
+    }
+    println!();
+}
+
 /// Result of a single doctor check
 #[derive(Debug)]
 pub struct DoctorCheck {
@@ -352,8 +692,29 @@ pub struct DoctorCheck {
     pub fix_hint: Option<String>,
 }
 
+/// Doctor command arguments
+#[derive(Debug, Args)]
+pub struct DoctorArgs {
+    /// Check what CI needs instead of the local capture setup (exits non-zero on failure)
+    #[arg(long)]
+    pub ci: bool,
+
+    /// Base revision that must be reachable from HEAD
+    /// (default: origin/$GITHUB_BASE_REF or $CI_MERGE_REQUEST_DIFF_BASE_SHA)
+    #[arg(long, requires = "ci")]
+    pub base: Option<String>,
+
+    /// Skip the token check when the pipeline does not post comments
+    #[arg(long, requires = "ci")]
+    pub no_comment: bool,
+}
+
 /// Run the doctor command
-pub fn run_doctor() -> Result<()> {
+pub fn run_doctor(args: DoctorArgs) -> Result<()> {
+    if args.ci {
+        return crate::cli::ci_doctor::run(args.base, !args.no_comment);
+    }
+
     println!("Checking whogitit configuration...\n");
 
     let mut checks: Vec<DoctorCheck> = Vec::new();
@@ -362,6 +723,9 @@ pub fn run_doctor() -> Result<()> {
     // Check 1: whogitit binary
     checks.push(check_binary());
 
+    // Check 1b: Runtime environment and config location
+    checks.push(check_environment());
+
     // Check 2: Capture hook installed
     checks.push(check_hook_installed());
 
@@ -371,7 +735,7 @@ pub fn run_doctor() -> Result<()> {
     // Check 4: Claude settings configured
     checks.push(check_settings_configured());
 
-    // Check 5: Required tools (jq)
+    // Check 5: Required tools (whogitit binary for the hook)
     checks.push(check_required_tools());
 
     // Check 6: Git repo (if in one)
@@ -379,11 +743,29 @@ pub fn run_doctor() -> Result<()> {
         checks.push(repo_check);
     }
 
+    // Check 6b: Prompt visibility of the repo's remotes
+    if let Some(visibility_check) = check_prompt_visibility() {
+        checks.push(visibility_check);
+    }
+
     // Check 7: Orphaned notes (if in a git repo with notes)
     if let Some(notes_check) = check_orphaned_notes() {
         checks.push(notes_check);
     }
 
+    // Check 7b: Sparse checkout (informational)
+    if let Some(sparse_check) = check_sparse_checkout() {
+        checks.push(sparse_check);
+    }
+
+    // Check 8: Malformed hook payloads saved by the capture hooks
+    if let Some(bad_events_check) = check_bad_events() {
+        checks.push(bad_events_check);
+    }
+
+    // Check 9: Integration tokens (presence and scopes)
+    checks.extend(crate::cli::auth::doctor_checks());
+
     // Display results
     for check in &checks {
         let status = if check.passed { "[OK]" } else { "[FAIL]" };
@@ -417,8 +799,9 @@ fn check_binary() -> DoctorCheck {
     }
 }
 
-fn check_hook_installed() -> DoctorCheck {
-    let hook_path = match capture_hook_path() {
+pub(crate) fn check_hook_installed() -> DoctorCheck {
+    let scope = SetupScope::detect();
+    let hook_path = match scope.hook_path() {
         Some(p) => p,
         None => {
             return DoctorCheck {
@@ -448,7 +831,7 @@ fn check_hook_installed() -> DoctorCheck {
                 name: "Capture hook",
                 passed: false,
                 message: "Installed but outdated".to_string(),
-                fix_hint: Some("Run 'whogitit setup' to update".to_string()),
+                fix_hint: Some(format!("Run '{}' to update", scope.setup_command())),
             }
         }
     } else {
@@ -456,13 +839,14 @@ fn check_hook_installed() -> DoctorCheck {
             name: "Capture hook",
             passed: false,
             message: "Not installed".to_string(),
-            fix_hint: Some("Run 'whogitit setup' to install".to_string()),
+            fix_hint: Some(format!("Run '{}' to install", scope.setup_command())),
         }
     }
 }
 
-fn check_hook_executable() -> DoctorCheck {
-    let hook_path = match capture_hook_path() {
+pub(crate) fn check_hook_executable() -> DoctorCheck {
+    let scope = SetupScope::detect();
+    let hook_path = match scope.hook_path() {
         Some(p) => p,
         None => {
             return DoctorCheck {
@@ -479,7 +863,7 @@ fn check_hook_executable() -> DoctorCheck {
             name: "Hook permissions",
             passed: false,
             message: "Hook not installed".to_string(),
-            fix_hint: Some("Run 'whogitit setup'".to_string()),
+            fix_hint: Some(format!("Run '{}'", scope.setup_command())),
         };
     }
 
@@ -518,6 +902,16 @@ fn check_hook_executable() -> DoctorCheck {
 }
 
 fn check_settings_configured() -> DoctorCheck {
+    let scope = SetupScope::detect();
+    if let SetupScope::Project(_) = scope {
+        return DoctorCheck {
+            name: "Claude Code settings",
+            passed: true,
+            message: "Hooks configured in project .claude/settings.json".to_string(),
+            fix_hint: None,
+        };
+    }
+
     let settings_path = match claude_settings_path() {
         Some(p) => p,
         None => {
@@ -580,33 +974,149 @@ fn check_settings_configured() -> DoctorCheck {
     }
 }
 
-fn check_required_tools() -> DoctorCheck {
-    // Check for jq which is required by the hook script
-    let jq_available = std::process::Command::new("jq")
-        .arg("--version")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+pub(crate) fn check_environment() -> DoctorCheck {
+    environment_check(
+        RuntimeEnvironment::detect(),
+        environment::home_override().is_some(),
+        claude_config_dir().as_deref(),
+    )
+}
+
+fn environment_check(
+    runtime: RuntimeEnvironment,
+    home_overridden: bool,
+    config_dir: Option<&std::path::Path>,
+) -> DoctorCheck {
+    let Some(config_dir) = config_dir else {
+        return DoctorCheck {
+            name: "Environment",
+            passed: false,
+            message: format!("{}, no home directory", runtime.label()),
+            fix_hint: Some(format!(
+                "Set HOME or {} to the directory containing .claude/",
+                ENV_WHOGITIT_HOME
+            )),
+        };
+    };
 
-    if jq_available {
+    let source = if home_overridden {
+        format!(" via {}", ENV_WHOGITIT_HOME)
+    } else {
+        String::new()
+    };
+    let message = format!(
+        "{}, Claude config at {}{}",
+        runtime.label(),
+        config_dir.display(),
+        source
+    );
+
+    // In a container or remote shell a missing config usually means Claude Code
+    // runs elsewhere (e.g. on the host) and this home is the wrong one.
+    if runtime.is_remote() && !config_dir.exists() {
+        return DoctorCheck {
+            name: "Environment",
+            passed: false,
+            message: format!("{} (missing)", message),
+            fix_hint: Some(format!(
+                "Run 'whogitit setup' where Claude Code runs, or set {} to the directory containing its .claude/",
+                ENV_WHOGITIT_HOME
+            )),
+        };
+    }
+
+    DoctorCheck {
+        name: "Environment",
+        passed: true,
+        message,
+        fix_hint: None,
+    }
+}
+
+pub(crate) fn check_required_tools() -> DoctorCheck {
+    // The hook script needs nothing but the whogitit binary, found the same way
+    // the script looks for it: $WHOGITIT_BIN, ~/.cargo/bin, then PATH
+    let home = std::env::var(ENV_WHOGITIT_HOME)
+        .or_else(|_| std::env::var("HOME"))
+        .unwrap_or_default();
+    let candidate = std::env::var("WHOGITIT_BIN")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(home).join(".cargo/bin/whogitit"));
+    let on_path = || {
+        std::process::Command::new("whogitit")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    };
+
+    if candidate.is_file() || on_path() {
         DoctorCheck {
-            name: "Required tools (jq)",
+            name: "Required tools (whogitit)",
             passed: true,
-            message: "Available".to_string(),
+            message: "Available to the capture hook".to_string(),
             fix_hint: None,
         }
     } else {
         DoctorCheck {
-            name: "Required tools (jq)",
+            name: "Required tools (whogitit)",
             passed: false,
-            message: "jq not found".to_string(),
+            message: "whogitit binary not found by the capture hook".to_string(),
             fix_hint: Some(
-                "Install jq: brew install jq (macOS) or apt install jq (Linux)".to_string(),
+                "Install with `cargo install whogitit`, put it on PATH, or set WHOGITIT_BIN"
+                    .to_string(),
             ),
         }
     }
 }
 
+/// Remotes that pushed notes would disclose prompts to, per `privacy.prompt_visibility`
+fn check_prompt_visibility() -> Option<DoctorCheck> {
+    let repo = git2::Repository::discover(".").ok()?;
+    let config = crate::privacy::WhogititConfig::load(repo.workdir()?).ok()?;
+
+    let remotes = repo.remotes().ok()?;
+    if remotes.is_empty() {
+        return None;
+    }
+    let advisories: Vec<_> = remotes
+        .iter()
+        .flatten()
+        .filter_map(|name| {
+            let remote = repo.find_remote(name).ok()?;
+            let url = remote.pushurl().or(remote.url())?;
+            crate::privacy::visibility::check_remote(&config, name, url)
+        })
+        .collect();
+
+    if advisories.is_empty() {
+        Some(DoctorCheck {
+            name: "Prompt visibility",
+            passed: true,
+            message: format!(
+                "Remotes match prompt_visibility = \"{}\"",
+                config.privacy.prompt_visibility
+            ),
+            fix_hint: None,
+        })
+    } else {
+        Some(DoctorCheck {
+            name: "Prompt visibility",
+            passed: false,
+            message: advisories
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join("; "),
+            fix_hint: Some(
+                "Add private remotes to privacy.trusted_remotes, store prompts hash-only \
+                 ([privacy.tiers]), or stop pushing refs/notes/whogitit there"
+                    .to_string(),
+            ),
+        })
+    }
+}
+
 fn check_orphaned_notes() -> Option<DoctorCheck> {
     let repo = git2::Repository::discover(".").ok()?;
     let store = crate::storage::notes::NotesStore::new(&repo).ok()?;
@@ -643,6 +1153,60 @@ fn check_orphaned_notes() -> Option<DoctorCheck> {
     })
 }
 
+fn check_bad_events() -> Option<DoctorCheck> {
+    let repo = git2::Repository::discover(".").ok()?;
+    bad_events_check(repo.path())
+}
+
+/// Report hook payloads that failed to parse; `None` when there are none
+fn bad_events_check(git_dir: &Path) -> Option<DoctorCheck> {
+    use crate::capture::bad_events;
+
+    let events = bad_events::list(git_dir);
+    let latest = events.first()?;
+    let salvaged = events.iter().filter(|e| !e.salvaged.is_empty()).count();
+
+    Some(DoctorCheck {
+        name: "Hook payloads",
+        passed: false,
+        message: format!(
+            "{} malformed payload(s), {} salvaged; latest from {} at {}: {}",
+            events.len(),
+            salvaged,
+            latest.source,
+            latest.at,
+            latest.error
+        ),
+        fix_hint: Some(format!(
+            "Inspect the payloads in {}, then delete the directory once resolved",
+            bad_events::bad_events_dir(git_dir).display()
+        )),
+    })
+}
+
+/// Report sparse-checkout mode; `None` outside sparse checkouts
+fn check_sparse_checkout() -> Option<DoctorCheck> {
+    use crate::core::sparse::SparseCheckout;
+
+    let repo = git2::Repository::discover(".").ok()?;
+    let sparse = SparseCheckout::detect(&repo).ok()??;
+    Some(sparse_checkout_check(&sparse))
+}
+
+fn sparse_checkout_check(sparse: &crate::core::sparse::SparseCheckout) -> DoctorCheck {
+    DoctorCheck {
+        name: "Sparse checkout",
+        passed: true,
+        message: format!(
+            "{} mode, {} pattern(s), {} tracked file(s) outside the cone (read from the object database)",
+            if sparse.is_cone() { "Cone" } else { "Pattern" },
+            sparse.pattern_count(),
+            sparse.outside_count()
+        ),
+        fix_hint: None,
+    }
+}
+
 fn check_git_repo() -> Option<DoctorCheck> {
     // Only check if we're in a git repo
     let repo = git2::Repository::discover(".").ok()?;
@@ -668,7 +1232,25 @@ fn check_git_repo() -> Option<DoctorCheck> {
             .map(|c| c.contains("whogitit"))
             .unwrap_or(false);
 
-    if post_commit_ok && pre_push_ok && post_rewrite_ok {
+    // The commit-msg hook is only needed when attribution goes into trailers,
+    // the pre-commit hook only when the reminder is enabled
+    let config = crate::privacy::WhogititConfig::load(repo_root).ok();
+    let needs_commit_msg = config
+        .as_ref()
+        .is_some_and(|c| c.storage.backend.writes_trailers());
+    let commit_msg_ok = !needs_commit_msg
+        || fs::read_to_string(hooks_dir.join("commit-msg"))
+            .map(|c| c.contains("whogitit"))
+            .unwrap_or(false);
+    let needs_pre_commit = config
+        .as_ref()
+        .is_some_and(|c| c.analysis.pre_commit_reminder);
+    let pre_commit_ok = !needs_pre_commit
+        || fs::read_to_string(hooks_dir.join("pre-commit"))
+            .map(|c| c.contains("whogitit"))
+            .unwrap_or(false);
+
+    if post_commit_ok && pre_push_ok && post_rewrite_ok && commit_msg_ok && pre_commit_ok {
         Some(DoctorCheck {
             name: "Repository hooks",
             passed: true,
@@ -686,6 +1268,12 @@ fn check_git_repo() -> Option<DoctorCheck> {
         if !post_rewrite_ok {
             missing.push("post-rewrite");
         }
+        if !commit_msg_ok {
+            missing.push("commit-msg");
+        }
+        if !pre_commit_ok {
+            missing.push("pre-commit");
+        }
         Some(DoctorCheck {
             name: "Repository hooks",
             passed: false,
@@ -779,7 +1367,7 @@ mod tests {
     #[test]
     fn test_merge_hooks_empty_settings() {
         let settings = json!({});
-        let merged = merge_hooks_into_settings(settings);
+        let merged = merge_hooks_into_settings(settings, &SetupScope::Global.hook_configuration());
 
         assert!(merged.get("hooks").is_some());
         assert!(merged["hooks"].get("PreToolUse").is_some());
@@ -805,7 +1393,7 @@ mod tests {
             }
         });
 
-        let merged = merge_hooks_into_settings(settings);
+        let merged = merge_hooks_into_settings(settings, &SetupScope::Global.hook_configuration());
 
         // Original setting preserved
         assert_eq!(merged["other_setting"], "value");
@@ -835,7 +1423,7 @@ mod tests {
             "hooks": "not-an-object"
         });
 
-        let merged = merge_hooks_into_settings(settings);
+        let merged = merge_hooks_into_settings(settings, &SetupScope::Global.hook_configuration());
 
         assert!(merged["hooks"].is_object());
         assert!(merged["hooks"].get("PreToolUse").is_some());
@@ -844,7 +1432,7 @@ mod tests {
 
     #[test]
     fn test_hook_configuration_structure() {
-        let config = hook_configuration();
+        let config = SetupScope::Global.hook_configuration();
 
         // Check PreToolUse structure
         let pre = &config["PreToolUse"][0];
@@ -863,6 +1451,103 @@ mod tests {
             .contains("WHOGITIT_HOOK_PHASE=post"));
     }
 
+    #[test]
+    fn test_hook_configuration_with_absolute_path() {
+        let config = hook_configuration_for("'/workspaces/home/.claude/hooks/whogitit-capture.sh'");
+        let settings = json!({ "hooks": config });
+        assert!(has_whogitit_hooks(&settings));
+        assert_eq!(
+            settings["hooks"]["PostToolUse"][0]["hooks"][0]["command"],
+            "WHOGITIT_HOOK_PHASE=post '/workspaces/home/.claude/hooks/whogitit-capture.sh'"
+        );
+    }
+
+    #[test]
+    fn test_project_setup_is_idempotent_and_backs_up() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let scope = SetupScope::Project(dir.path().to_path_buf());
+        let claude_dir = dir.path().join(".claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+        fs::write(
+            claude_dir.join("settings.json"),
+            r#"{"permissions": {"allow": ["Bash(cargo test)"]}}"#,
+        )
+        .unwrap();
+
+        assert!(install_hook_script(&scope).unwrap());
+        assert!(!install_hook_script(&scope).unwrap());
+        assert!(configure_settings(&scope).unwrap());
+        assert!(!configure_settings(&scope).unwrap());
+
+        let settings: Value =
+            serde_json::from_str(&fs::read_to_string(claude_dir.join("settings.json")).unwrap())
+                .unwrap();
+        assert_eq!(settings["permissions"]["allow"][0], "Bash(cargo test)");
+        assert_eq!(
+            settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"],
+            "WHOGITIT_HOOK_PHASE=pre \"$CLAUDE_PROJECT_DIR\"/.claude/hooks/whogitit-capture.sh"
+        );
+        assert!(claude_dir.join("settings.json.backup").exists());
+        assert!(check_setup_status_for(&scope).is_complete());
+    }
+
+    #[test]
+    fn test_environment_check() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let missing = dir.path().join(".claude");
+
+        let host = environment_check(RuntimeEnvironment::Host, false, Some(&missing));
+        assert!(host.passed);
+
+        let container = environment_check(RuntimeEnvironment::Devcontainer, false, Some(&missing));
+        assert!(!container.passed);
+        assert!(container.fix_hint.unwrap().contains(ENV_WHOGITIT_HOME));
+
+        let overridden =
+            environment_check(RuntimeEnvironment::Devcontainer, true, Some(dir.path()));
+        assert!(overridden.passed);
+        assert!(overridden.message.contains(ENV_WHOGITIT_HOME));
+
+        assert!(!environment_check(RuntimeEnvironment::Host, false, None).passed);
+    }
+
+    #[test]
+    fn test_merge_cursor_hooks_keeps_existing_entries() {
+        let existing = json!({
+            "version": 1,
+            "hooks": {
+                "afterFileEdit": [{ "command": "./format.sh" }]
+            }
+        });
+        let merged = merge_cursor_hooks(existing).unwrap();
+        assert_eq!(merged["version"], 1);
+        let after_edit = merged["hooks"]["afterFileEdit"].as_array().unwrap();
+        assert_eq!(after_edit.len(), 2);
+        assert_eq!(after_edit[0]["command"], "./format.sh");
+        assert_eq!(after_edit[1]["command"], CURSOR_CAPTURE_COMMAND);
+        assert_eq!(
+            merged["hooks"]["beforeSubmitPrompt"][0]["command"],
+            CURSOR_CAPTURE_COMMAND
+        );
+
+        // Running setup again changes nothing
+        assert!(merge_cursor_hooks(merged).is_none());
+    }
+
+    #[test]
+    fn test_generic_provider_instructions() {
+        let text = generic_provider_instructions();
+        assert!(text.contains("whogitit capture --stdin --provider generic-v1"));
+
+        let start = text.find('{').unwrap();
+        let end = text.rfind('}').unwrap();
+        let example: Value = serde_json::from_str(&text[start..=end]).unwrap();
+        let payload = serde_json::to_string(&example).unwrap();
+        let capture =
+            crate::capture::generic::GenericCaptureV1::from_reader(payload.as_bytes()).unwrap();
+        assert_eq!(capture.tool, "Edit");
+    }
+
     #[test]
     fn test_doctor_check_structure() {
         let check = DoctorCheck {
@@ -877,6 +1562,28 @@ mod tests {
         assert!(check.fix_hint.is_none());
     }
 
+    #[test]
+    fn test_bad_events_check_reports_saved_payloads() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(bad_events_check(dir.path()).is_none());
+
+        crate::capture::bad_events::record(
+            dir.path(),
+            "capture",
+            b"{\"file_path\": \"a.rs\", \"new_content\": \"x\"",
+            "EOF while parsing an object",
+            &["file_path", "new_content"],
+        )
+        .unwrap();
+        let check = bad_events_check(dir.path()).unwrap();
+        assert!(!check.passed);
+        assert!(check
+            .message
+            .starts_with("1 malformed payload(s), 1 salvaged"));
+        assert!(check.message.contains("EOF while parsing"));
+        assert!(check.fix_hint.unwrap().contains("whogitit/badevents"));
+    }
+
     #[test]
     fn test_doctor_check_with_fix_hint() {
         let check = DoctorCheck {
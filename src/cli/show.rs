@@ -3,9 +3,21 @@ use clap::Args;
 use colored::Colorize;
 use git2::Repository;
 
-use crate::cli::output::{LineSourceOutput, OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::capture::encoding;
+use crate::capture::snapshot::{BinaryAttribution, FileAttributionResult};
+use crate::cli::i18n::{Locale, Msg};
+use crate::cli::output::{
+    source_marker, LineSourceOutput, OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION,
+};
+use crate::core::attribution::AIAttribution;
+use crate::core::calibration;
+use crate::core::integrity::{self, LineCountIssue};
+use crate::core::symbols::{attribute_symbols, SymbolAttribution};
+use crate::privacy::WhogititConfig;
+use crate::storage::audit::AuditLog;
 use crate::storage::notes::NotesStore;
-use crate::utils::{truncate, SHORT_COMMIT_LEN};
+use crate::storage::trailers::{ParsedTrailers, TrailerParser};
+use crate::utils::{repo_path, truncate, SHORT_COMMIT_LEN};
 
 /// Show command arguments
 #[derive(Debug, Args)]
@@ -17,10 +29,28 @@ pub struct ShowArgs {
     /// Output format
     #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
     pub format: OutputFormat,
+
+    /// Break down attribution by enclosing function/class
+    #[arg(long)]
+    pub symbols: bool,
+
+    /// Recompute per-file summaries from the stored line data and rewrite the note
+    #[arg(long)]
+    pub reconcile: bool,
+
+    /// Print AI line ranges with N lines of surrounding code from the commit (like grep -C)
+    #[arg(long, value_name = "N")]
+    pub context: Option<u32>,
+
+    /// Language and number format of pretty output: en, de, fr, es (default: $WHOGITIT_LOCALE)
+    #[arg(long, value_name = "LOCALE")]
+    pub locale: Option<String>,
 }
 
 /// Run the show command
 pub fn run(args: ShowArgs) -> Result<()> {
+    let locale = Locale::resolve(args.locale.as_deref())?;
+
     // Open repository
     let repo = Repository::discover(".").context(
         "Not in a git repository. \
@@ -52,68 +82,53 @@ pub fn run(args: ShowArgs) -> Result<()> {
     let attribution = notes_store.fetch_attribution(commit.id())?;
 
     match attribution {
-        Some(attr) => {
+        Some(mut attr) => {
+            if args.reconcile {
+                let changed = integrity::reconcile_summaries(&mut attr);
+                if !changed.is_empty() {
+                    notes_store.store_attribution(commit.id(), &attr)?;
+                }
+                if args.format == OutputFormat::Pretty {
+                    println!(
+                        "Reconciled {} file summar{} in {}",
+                        changed.len(),
+                        if changed.len() == 1 { "y" } else { "ies" },
+                        commit_short
+                    );
+                    println!();
+                }
+            }
+            let discrepancies = prepare_attribution(&repo, &commit, &mut attr, "show")?;
+
             if args.format == OutputFormat::Json {
-                let files_json: Vec<_> = attr
-                    .files
-                    .iter()
-                    .map(|file| {
-                        let lines_json: Vec<_> = file
-                            .lines
-                            .iter()
-                            .map(|line| {
-                                serde_json::json!({
-                                    "line_number": line.line_number,
-                                    "content": line.content,
-                                    "source": LineSourceOutput::from(&line.source),
-                                    "edit_id": line.edit_id,
-                                    "prompt_index": line.prompt_index,
-                                    "confidence": line.confidence,
-                                })
-                            })
-                            .collect();
-
-                        serde_json::json!({
-                            "path": file.path,
-                            "lines": lines_json,
-                            "summary": file.summary,
-                        })
-                    })
-                    .collect();
-
-                let output = serde_json::json!({
-                    "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
-                    "schema": "whogitit.show.v1",
-                    "has_attribution": true,
-                    "commit": commit_id,
-                    "commit_short": commit_short,
-                    "attribution_version": attr.version,
-                    "session": attr.session,
-                    "prompts": attr.prompts,
-                    "files": files_json,
-                    "summary": {
-                        "total_ai_lines": attr.total_ai_lines(),
-                        "total_ai_modified_lines": attr.total_ai_modified_lines(),
-                        "total_human_lines": attr.total_human_lines(),
-                        "total_original_lines": attr.total_original_lines(),
-                    }
-                });
+                let output = attribution_json(
+                    &repo,
+                    &commit,
+                    &attr,
+                    &discrepancies,
+                    args.symbols,
+                    args.context,
+                );
                 println!("{}", serde_json::to_string_pretty(&output)?);
             } else {
-                print_summary(commit_short, &attr);
+                print_summary(commit_short, &attr, args.symbols, locale);
+                if let Some(context) = args.context {
+                    print_snippets(&repo, &commit, &attr, context);
+                }
+                print_discrepancies(&discrepancies);
             }
         }
         None => {
+            // Repositories using the trailers storage backend only have a summary
+            let trailers = TrailerParser::parse(commit.message().unwrap_or(""));
+            if trailers.has_attribution() {
+                return print_trailer_summary(commit_short, &commit_id, &trailers, args.format);
+            }
+
             if args.format == OutputFormat::Json {
                 println!(
                     "{}",
-                    serde_json::to_string_pretty(&serde_json::json!({
-                        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
-                        "schema": "whogitit.show.v1",
-                        "has_attribution": false,
-                        "commit": commit_id,
-                        "commit_short": commit_short,
-                    }))?
+                    serde_json::to_string_pretty(&no_attribution_json(&commit_id))?
                 );
             } else {
                 println!("No AI attribution found for commit {}", commit_short);
@@ -125,16 +140,351 @@ pub fn run(args: ShowArgs) -> Result<()> {
     Ok(())
 }
 
-fn print_summary(commit_short: &str, attr: &crate::core::attribution::AIAttribution) {
+/// `show --format json` output for a commit, as served by `whogitit serve`
+///
+/// Falls back to the commit's trailers when it has no note, like `show`.
+pub(crate) fn commit_json(
+    repo: &Repository,
+    notes_store: &NotesStore,
+    commit: &git2::Commit,
+    command: &str,
+) -> Result<serde_json::Value> {
+    let commit_id = commit.id().to_string();
+    if let Some(mut attr) = notes_store.fetch_attribution(commit.id())? {
+        let discrepancies = prepare_attribution(repo, commit, &mut attr, command)?;
+        return Ok(attribution_json(
+            repo,
+            commit,
+            &attr,
+            &discrepancies,
+            false,
+            None,
+        ));
+    }
+    let trailers = TrailerParser::parse(commit.message().unwrap_or(""));
+    if trailers.has_attribution() {
+        return Ok(trailer_json(&commit_id, &trailers));
+    }
+    Ok(no_attribution_json(&commit_id))
+}
+
+/// Line count check, confidence calibration and the prompt access audit entry,
+/// done before an attribution is output
+fn prepare_attribution(
+    repo: &Repository,
+    commit: &git2::Commit,
+    attr: &mut AIAttribution,
+    command: &str,
+) -> Result<Vec<LineCountIssue>> {
+    // Checked before calibration, which rescales confidences but not counts
+    let discrepancies = integrity::check_line_counts(repo, commit, attr)?;
+
+    if let Some(root) = repo.workdir() {
+        let config = WhogititConfig::load_or_warn(root);
+        calibration::apply_to_attribution(&config.calibration, attr);
+
+        // Recorded before any prompt text is output
+        if let Some(audit_log) = AuditLog::for_prompt_access(root, &config.audit) {
+            let prompts: Vec<u32> = attr.prompts.iter().map(|p| p.index).collect();
+            if !prompts.is_empty() {
+                audit_log
+                    .log_prompt_access(command, &commit.id().to_string(), &prompts)
+                    .context("Failed to record prompt access in the audit log")?;
+            }
+        }
+    }
+    Ok(discrepancies)
+}
+
+/// JSON output for a commit with an attribution note
+fn attribution_json(
+    repo: &Repository,
+    commit: &git2::Commit,
+    attr: &AIAttribution,
+    discrepancies: &[LineCountIssue],
+    symbols: bool,
+    context: Option<u32>,
+) -> serde_json::Value {
+    let files_json: Vec<_> = attr
+        .files
+        .iter()
+        .map(|file| {
+            let lines_json: Vec<_> = file
+                .lines
+                .iter()
+                .map(|line| {
+                    serde_json::json!({
+                        "line_number": line.line_number,
+                        "content": line.content,
+                        "source": LineSourceOutput::from(&line.source),
+                        "edit_id": line.edit_id,
+                        "prompt_index": line.prompt_index,
+                        "confidence": line.confidence,
+                    })
+                })
+                .collect();
+
+            let mut file_json = serde_json::json!({
+                "path": file.path,
+                "lines": lines_json,
+                "summary": file.summary,
+            });
+            // Notes written with `analysis.granularity = "hunk"` only have ranges
+            if let Some(blocks) = file.blocks.as_ref().filter(|_| file.lines.is_empty()) {
+                file_json["blocks"] = serde_json::to_value(blocks).unwrap_or_default();
+            }
+            if let Some(binary) = &file.binary {
+                file_json["binary"] = serde_json::to_value(binary).unwrap_or_default();
+            }
+            if let Some(warning) = &file.analysis_warning {
+                file_json["analysis_warning"] = serde_json::json!(warning);
+            }
+            if symbols {
+                file_json["symbols"] =
+                    serde_json::to_value(attribute_symbols(file)).unwrap_or_default();
+            }
+            if let Some(context) = context {
+                file_json["snippets"] = snippets_json(repo, commit, file, context);
+            }
+            file_json
+        })
+        .collect();
+
+    serde_json::json!({
+        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+        "schema": "whogitit.show.v1",
+        "has_attribution": true,
+        "attribution_source": "notes",
+        "commit": commit.id().to_string(),
+        "commit_short": &commit.id().to_string()[..SHORT_COMMIT_LEN],
+        "attribution_version": attr.version,
+        "session": attr.session,
+        "prompts": attr.prompts,
+        "files": files_json,
+        "summary": {
+            "total_ai_lines": attr.total_ai_lines(),
+            "total_ai_modified_lines": attr.total_ai_modified_lines(),
+            "total_human_lines": attr.total_human_lines(),
+            "total_original_lines": attr.total_original_lines(),
+        },
+        "line_count_discrepancies": discrepancies,
+    })
+}
+
+/// JSON output for a commit whose attribution comes from its trailers
+fn trailer_json(commit_id: &str, trailers: &ParsedTrailers) -> serde_json::Value {
+    serde_json::json!({
+        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+        "schema": "whogitit.show.v1",
+        "has_attribution": true,
+        "attribution_source": "trailers",
+        "commit": commit_id,
+        "commit_short": &commit_id[..commit_id.len().min(SHORT_COMMIT_LEN)],
+        "session": {
+            "session_id": trailers.session,
+            "model": trailers.model,
+        },
+        "summary": {
+            "total_ai_lines": trailers.ai_lines.unwrap_or(0),
+            "total_ai_modified_lines": trailers.ai_modified_lines.unwrap_or(0),
+            "total_human_lines": trailers.human_lines.unwrap_or(0),
+        }
+    })
+}
+
+/// JSON output for a commit without attribution
+fn no_attribution_json(commit_id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+        "schema": "whogitit.show.v1",
+        "has_attribution": false,
+        "commit": commit_id,
+        "commit_short": &commit_id[..commit_id.len().min(SHORT_COMMIT_LEN)],
+    })
+}
+
+/// Show the attribution summary recorded in commit trailers (no note present)
+fn print_trailer_summary(
+    commit_short: &str,
+    commit_id: &str,
+    trailers: &ParsedTrailers,
+    format: OutputFormat,
+) -> Result<()> {
+    let ai_lines = trailers.ai_lines.unwrap_or(0);
+    let ai_modified_lines = trailers.ai_modified_lines.unwrap_or(0);
+    let human_lines = trailers.human_lines.unwrap_or(0);
+
+    if format == OutputFormat::Json {
+        let output = trailer_json(commit_id, trailers);
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
     println!("{}: {}", "Commit".bold(), commit_short.yellow());
-    println!("{}: {}", "Session".bold(), attr.session.session_id.cyan());
-    println!("{}: {}", "Model".bold(), attr.session.model.id);
-    println!("{}: {}", "Started".bold(), attr.session.started_at.dimmed());
+    if let Some(session) = &trailers.session {
+        println!("{}: {}", "Session".bold(), session.cyan());
+    }
+    if let Some(model) = &trailers.model {
+        println!("{}: {}", "Model".bold(), model);
+    }
+    println!();
+    println!(
+        "{}: {} AI, {} modified, {} human",
+        "Lines".bold(),
+        ai_lines.to_string().green(),
+        ai_modified_lines.to_string().yellow(),
+        human_lines.to_string().blue()
+    );
+    println!();
+    println!(
+        "{}",
+        "Summary from commit trailers; no attribution note is attached, so per-file and \
+         per-line details are unavailable."
+            .dimmed()
+    );
+    Ok(())
+}
+
+/// Line ranges (1-indexed, inclusive) covering a file's AI lines plus `context`
+/// lines on each side, clamped to `total_lines`; overlapping or touching ranges merge
+fn snippet_ranges(file: &FileAttributionResult, total_lines: u32, context: u32) -> Vec<(u32, u32)> {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for block in file.line_blocks().iter().filter(|b| b.source.is_ai()) {
+        let start = block.start.saturating_sub(context).max(1);
+        let end = (block.start + block.len - 1)
+            .saturating_add(context)
+            .min(total_lines);
+        if start > end {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+}
+
+/// Text of `path` in the commit's tree, if it is a text file
+fn committed_text(repo: &Repository, commit: &git2::Commit, path: &str) -> Option<String> {
+    let entry = commit
+        .tree()
+        .ok()?
+        .get_path(&repo_path::to_path(path))
+        .ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    encoding::decode(blob.content()).map(|decoded| decoded.text)
+}
+
+/// `--context` ranges of a file with their lines, for JSON output
+fn snippets_json(
+    repo: &Repository,
+    commit: &git2::Commit,
+    file: &FileAttributionResult,
+    context: u32,
+) -> serde_json::Value {
+    let Some(text) = committed_text(repo, commit, &file.path).filter(|_| !file.is_binary()) else {
+        return serde_json::json!([]);
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    snippet_ranges(file, lines.len() as u32, context)
+        .into_iter()
+        .map(|(start, end)| {
+            let lines_json: Vec<_> = (start..=end)
+                .map(|n| {
+                    serde_json::json!({
+                        "line_number": n,
+                        "content": lines[n as usize - 1],
+                        "source": file.line(n).map(|a| LineSourceOutput::from(a.source)),
+                    })
+                })
+                .collect();
+            serde_json::json!({ "start": start, "end": end, "lines": lines_json })
+        })
+        .collect()
+}
+
+/// Print each file's AI line ranges with surrounding code from the commit
+fn print_snippets(repo: &Repository, commit: &git2::Commit, attr: &AIAttribution, context: u32) {
+    for file in attr.files.iter().filter(|f| !f.is_binary()) {
+        let Some(text) = committed_text(repo, commit, &file.path) else {
+            continue;
+        };
+        let lines: Vec<&str> = text.lines().collect();
+        let ranges = snippet_ranges(file, lines.len() as u32, context);
+        if ranges.is_empty() {
+            continue;
+        }
+
+        println!();
+        println!("{}", file.path.bold());
+        let width = ranges.last().map_or(1, |r| r.1.to_string().len());
+        for (i, (start, end)) in ranges.iter().enumerate() {
+            if i > 0 {
+                println!("{}", "--".dimmed());
+            }
+            for n in *start..=*end {
+                let marker = file
+                    .line(n)
+                    .map_or_else(|| " ".to_string(), |a| source_marker(a.source));
+                println!(
+                    "{} {} {}",
+                    format!("{:>width$}", n, width = width).dimmed(),
+                    marker,
+                    lines[n as usize - 1]
+                );
+            }
+        }
+    }
+}
+
+fn print_discrepancies(issues: &[LineCountIssue]) {
+    if issues.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", "Line-count discrepancies:".yellow().bold());
+    for issue in issues {
+        let blob = issue
+            .blob_lines
+            .map_or_else(|| "?".to_string(), |n| n.to_string());
+        let stored = issue
+            .stored_lines
+            .map_or_else(|| "?".to_string(), |n| n.to_string());
+        println!(
+            "  {}: summary {} line(s), blob {}, stored lines {}",
+            issue.path, issue.recorded_lines, blob, stored
+        );
+    }
+    if issues.iter().any(LineCountIssue::reconcilable) {
+        println!(
+            "  {}",
+            "Run 'whogitit show --reconcile' to recompute summaries from the stored lines."
+                .dimmed()
+        );
+    }
+}
+
+pub(crate) fn print_summary(
+    commit_short: &str,
+    attr: &AIAttribution,
+    show_symbols: bool,
+    locale: Locale,
+) {
+    let t = |msg| locale.text(msg).bold();
+    let count = |n: usize| locale.number(n);
+    println!("{}: {}", t(Msg::Commit), commit_short.yellow());
+    println!("{}: {}", t(Msg::Session), attr.session.session_id.cyan());
+    if let Some(label) = &attr.session.label {
+        println!("{}: {}", t(Msg::Label), label);
+    }
+    println!("{}: {}", t(Msg::Model), attr.session.model.id);
+    println!("{}: {}", t(Msg::Started), attr.session.started_at.dimmed());
     println!();
 
     // Show prompts
     if !attr.prompts.is_empty() {
-        println!("{}", "Prompts used:".bold());
+        println!("{}", t(Msg::PromptsUsed));
         for prompt in &attr.prompts {
             let preview = truncate(&prompt.text, 60);
             println!("  #{}: \"{}\"", prompt.index, preview.dimmed());
@@ -143,7 +493,7 @@ fn print_summary(commit_short: &str, attr: &crate::core::attribution::AIAttribut
     }
 
     // Show files with detailed breakdown
-    println!("{}", "Files with AI changes:".bold());
+    println!("{}", t(Msg::FilesWithAiChanges));
 
     let mut total_ai = 0usize;
     let mut total_ai_modified = 0usize;
@@ -151,6 +501,11 @@ fn print_summary(commit_short: &str, attr: &crate::core::attribution::AIAttribut
     let mut total_original = 0usize;
 
     for file in &attr.files {
+        if let Some(binary) = &file.binary {
+            println!("  {} ({})", file.path, format_binary_status(binary));
+            continue;
+        }
+
         let s = &file.summary;
         total_ai += s.ai_lines;
         total_ai_modified += s.ai_modified_lines;
@@ -158,53 +513,124 @@ fn print_summary(commit_short: &str, attr: &crate::core::attribution::AIAttribut
         total_original += s.original_lines;
 
         // Color-coded breakdown
-        let ai_str = format!("{} AI", s.ai_lines).green();
+        let ai_str = locale.fill(Msg::FileAi, &[&count(s.ai_lines)]).green();
         let modified_str = if s.ai_modified_lines > 0 {
-            format!(", {} modified", s.ai_modified_lines)
-                .yellow()
-                .to_string()
+            format!(
+                ", {}",
+                locale.fill(Msg::FileModified, &[&count(s.ai_modified_lines)])
+            )
+            .yellow()
+            .to_string()
         } else {
             String::new()
         };
         let human_str = if s.human_lines > 0 {
-            format!(", {} human", s.human_lines).blue().to_string()
+            format!(
+                ", {}",
+                locale.fill(Msg::FileHuman, &[&count(s.human_lines)])
+            )
+            .blue()
+            .to_string()
         } else {
             String::new()
         };
         let original_str = if s.original_lines > 0 {
-            format!(", {} original", s.original_lines)
-                .dimmed()
-                .to_string()
+            format!(
+                ", {}",
+                locale.fill(Msg::FileOriginal, &[&count(s.original_lines)])
+            )
+            .dimmed()
+            .to_string()
         } else {
             String::new()
         };
 
         println!(
-            "  {} ({}{}{}{}) - {} total lines",
-            file.path, ai_str, modified_str, human_str, original_str, s.total_lines
+            "  {} ({}{}{}{}) - {}",
+            file.path,
+            ai_str,
+            modified_str,
+            human_str,
+            original_str,
+            locale.fill(Msg::TotalLines, &[&count(s.total_lines)])
         );
+        if let Some(warning) = &file.analysis_warning {
+            println!("    {}", format!("Note: {}", warning).dimmed());
+        }
+
+        if show_symbols {
+            for symbol in attribute_symbols(file)
+                .iter()
+                .filter(|sym| sym.ai_lines + sym.ai_modified_lines > 0)
+            {
+                println!("    {}", format_symbol_line(symbol));
+            }
+        }
     }
 
     println!();
-    println!("{}", "Summary:".bold());
-    println!("  {} AI-generated lines", total_ai.to_string().green());
+    println!("{}", t(Msg::Summary));
+    println!(
+        "  {}",
+        locale.fill(Msg::AiGeneratedLines, &[&count(total_ai).green()])
+    );
     if total_ai_modified > 0 {
         println!(
-            "  {} AI lines modified by human",
-            total_ai_modified.to_string().yellow()
+            "  {}",
+            locale.fill(Msg::AiModifiedLines, &[&count(total_ai_modified).yellow()])
         );
     }
     if total_human > 0 {
-        println!("  {} human-added lines", total_human.to_string().blue());
+        println!(
+            "  {}",
+            locale.fill(Msg::HumanAddedLines, &[&count(total_human).blue()])
+        );
     }
     if total_original > 0 {
         println!(
-            "  {} original/unchanged lines",
-            total_original.to_string().dimmed()
+            "  {}",
+            locale.fill(Msg::OriginalLines, &[&count(total_original).dimmed()])
         );
     }
 }
 
+/// Describe whole-file attribution, e.g. "binary, AI-generated via Write, prompt #2"
+pub(crate) fn format_binary_status(binary: &BinaryAttribution) -> String {
+    if binary.ai_generated {
+        format!(
+            "binary, {} via {}, prompt #{}",
+            "AI-generated".green(),
+            binary.tool,
+            binary.prompt_index
+        )
+    } else {
+        format!(
+            "binary, {} after AI {} (prompt #{})",
+            "changed".yellow(),
+            binary.tool,
+            binary.prompt_index
+        )
+    }
+}
+
+/// Format a symbol summary line, e.g. "function `parse_config` (L10-42): 80% AI"
+fn format_symbol_line(symbol: &SymbolAttribution) -> String {
+    let pct = format!("{:.0}% AI", symbol.ai_percent());
+    let pct = if symbol.ai_percent() >= 50.0 {
+        pct.green()
+    } else {
+        pct.yellow()
+    };
+    format!(
+        "{} `{}` (L{}-{}): {}",
+        symbol.kind.label(),
+        symbol.name,
+        symbol.start_line,
+        symbol.end_line,
+        pct
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,6 +642,10 @@ mod tests {
         let args = ShowArgs {
             commit: "HEAD".to_string(),
             format: OutputFormat::Pretty,
+            symbols: false,
+            reconcile: false,
+            context: None,
+            locale: None,
         };
         assert_eq!(args.commit, "HEAD");
         assert!(matches!(args.format, OutputFormat::Pretty));
@@ -226,6 +656,10 @@ mod tests {
         let args = ShowArgs {
             commit: "abc1234".to_string(),
             format: OutputFormat::Json,
+            symbols: false,
+            reconcile: false,
+            context: None,
+            locale: None,
         };
         assert_eq!(args.commit, "abc1234");
         assert!(matches!(args.format, OutputFormat::Json));
@@ -236,6 +670,10 @@ mod tests {
         let args = ShowArgs {
             commit: "main".to_string(),
             format: OutputFormat::Pretty,
+            symbols: false,
+            reconcile: false,
+            context: None,
+            locale: None,
         };
         assert_eq!(args.commit, "main");
     }
@@ -245,10 +683,32 @@ mod tests {
         let args = ShowArgs {
             commit: "HEAD~3".to_string(),
             format: OutputFormat::Pretty,
+            symbols: false,
+            reconcile: false,
+            context: None,
+            locale: None,
         };
         assert_eq!(args.commit, "HEAD~3");
     }
 
+    #[test]
+    fn test_format_symbol_line() {
+        let symbol = SymbolAttribution {
+            kind: crate::core::symbols::SymbolKind::Function,
+            name: "parse_config".to_string(),
+            start_line: 10,
+            end_line: 14,
+            total_lines: 5,
+            ai_lines: 3,
+            ai_modified_lines: 1,
+            human_lines: 1,
+            original_lines: 0,
+        };
+        let line = format_symbol_line(&symbol);
+        assert!(line.starts_with("function `parse_config` (L10-14): "));
+        assert!(line.contains("80% AI"));
+    }
+
     // Line counting aggregation test (simulated)
     #[test]
     fn test_line_count_aggregation() {
@@ -293,4 +753,63 @@ mod tests {
         let commit_short = &commit_id[..commit_id.len().min(SHORT_COMMIT_LEN)];
         assert_eq!(commit_short, "abc12");
     }
+
+    #[test]
+    fn test_format_binary_status() {
+        let mut binary = BinaryAttribution {
+            ai_generated: true,
+            tool: "Write".to_string(),
+            prompt_index: 2,
+        };
+        let status = format_binary_status(&binary);
+        assert!(status.starts_with("binary, "));
+        assert!(status.ends_with(" via Write, prompt #2"));
+
+        binary.ai_generated = false;
+        assert!(format_binary_status(&binary).ends_with(" after AI Write (prompt #2)"));
+    }
+
+    #[test]
+    fn test_snippet_ranges_expand_and_merge() {
+        use crate::capture::snapshot::{LineAttribution, LineSource};
+
+        let sources = "..AA.....A.A..";
+        let lines: Vec<LineAttribution> = sources
+            .chars()
+            .enumerate()
+            .map(|(i, c)| LineAttribution {
+                line_number: i as u32 + 1,
+                content: String::new(),
+                content_hash: None,
+                source: if c == 'A' {
+                    LineSource::AI {
+                        edit_id: "e".to_string(),
+                    }
+                } else {
+                    LineSource::Original
+                },
+                edit_id: None,
+                prompt_index: None,
+                confidence: 1.0,
+                timestamp: None,
+            })
+            .collect();
+        let file = FileAttributionResult {
+            path: "a.rs".to_string(),
+            summary: FileAttributionResult::compute_summary(&lines),
+            lines,
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        };
+
+        assert_eq!(
+            snippet_ranges(&file, 14, 0),
+            vec![(3, 4), (10, 10), (12, 12)]
+        );
+        // Ranges one line apart touch and merge; the end is clamped to the file
+        assert_eq!(snippet_ranges(&file, 14, 2), vec![(1, 6), (8, 14)]);
+        assert_eq!(snippet_ranges(&file, 14, 3), vec![(1, 14)]);
+    }
 }
@@ -0,0 +1,384 @@
+//! Split-plan command - propose commits that separate AI and human changes
+//!
+//! Classifies every file changed since HEAD by its uncommitted lines, attributed
+//! the way [`gutter`](super::gutter) does it: files whose changed lines all come
+//! from captured AI edits, files changed only by hand, and files mixing both.
+//! The plan is a list of `git add` / `git commit` commands, one commit per group,
+//! with mixed files left to `git add -p`. Nothing is staged or committed here;
+//! the post-commit hook keeps pending edits of files left out of a commit for the
+//! next one, so each commit in the plan gets its own attribution.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use git2::{Repository, Status, StatusOptions};
+use serde::Serialize;
+
+use crate::capture::pending::PendingStore;
+use crate::capture::watch::is_capturable;
+use crate::cli::gutter::{gutter_file, GutterLine};
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+
+const SPLIT_PLAN_MACHINE_SCHEMA: &str = "whogitit.split-plan.v1";
+
+const AI_COMMIT_MESSAGE: &str = "Add AI-generated changes";
+const HUMAN_COMMIT_MESSAGE: &str = "Human changes";
+
+/// Split-plan command arguments
+#[derive(Debug, Args)]
+pub struct SplitPlanArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+/// Commit a changed file belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitGroup {
+    /// Every changed line comes from an AI edit
+    Ai,
+    /// No changed line comes from an AI edit
+    Human,
+    /// AI lines alongside human lines or human edits of AI lines
+    Mixed,
+}
+
+impl SplitGroup {
+    fn title(self) -> &'static str {
+        match self {
+            Self::Ai => "AI-generated changes",
+            Self::Human => "Human changes",
+            Self::Mixed => "Mixed files",
+        }
+    }
+}
+
+/// A file changed since HEAD
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SplitFile {
+    pub path: String,
+    pub group: SplitGroup,
+    /// Uncommitted lines by source
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    pub human_lines: usize,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+}
+
+/// One proposed commit
+#[derive(Debug, Serialize)]
+pub struct SplitStep {
+    pub group: SplitGroup,
+    pub title: &'static str,
+    pub files: Vec<String>,
+    pub commands: Vec<String>,
+}
+
+/// Group of a file from its uncommitted line counts
+///
+/// A file with no uncommitted lines (only deletions, or binary) is AI when the
+/// pending buffer has edits for it.
+fn classify(ai: usize, ai_modified: usize, human: usize, has_pending: bool) -> SplitGroup {
+    if ai + ai_modified == 0 {
+        return if human == 0 && has_pending {
+            SplitGroup::Ai
+        } else {
+            SplitGroup::Human
+        };
+    }
+    if ai_modified == 0 && human == 0 {
+        SplitGroup::Ai
+    } else {
+        SplitGroup::Mixed
+    }
+}
+
+/// Uncommitted AI, AI-modified and human lines
+fn line_counts(lines: &[GutterLine]) -> (usize, usize, usize) {
+    let mut counts = (0, 0, 0);
+    for line in lines.iter().filter(|l| l.uncommitted) {
+        match line.source {
+            "ai" => counts.0 += 1,
+            "ai_modified" => counts.1 += 1,
+            _ => counts.2 += 1,
+        }
+    }
+    counts
+}
+
+/// Quote a path for a POSIX shell when it needs it
+fn shell_quote(path: &str) -> String {
+    let plain = !path.is_empty()
+        && path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | '+' | '@'));
+    if plain {
+        path.to_string()
+    } else {
+        format!("'{}'", path.replace('\'', r"'\''"))
+    }
+}
+
+/// Commits for the classified files: AI first, then human, then mixed
+fn build_steps(files: &[SplitFile]) -> Vec<SplitStep> {
+    let mut steps = Vec::new();
+    for group in [SplitGroup::Ai, SplitGroup::Human, SplitGroup::Mixed] {
+        let paths: Vec<String> = files
+            .iter()
+            .filter(|f| f.group == group)
+            .map(|f| f.path.clone())
+            .collect();
+        if paths.is_empty() {
+            continue;
+        }
+        let args: Vec<String> = paths.iter().map(|p| shell_quote(p)).collect();
+        let args = args.join(" ");
+        let commands = match group {
+            SplitGroup::Ai => vec![
+                format!("git add -- {}", args),
+                format!("git commit -m \"{}\"", AI_COMMIT_MESSAGE),
+            ],
+            SplitGroup::Human => vec![
+                format!("git add -- {}", args),
+                format!("git commit -m \"{}\"", HUMAN_COMMIT_MESSAGE),
+            ],
+            // Stage the AI hunks interactively, then commit the rest on top
+            SplitGroup::Mixed => vec![
+                format!("git add -p -- {}", args),
+                format!("git commit -m \"{}\"", AI_COMMIT_MESSAGE),
+                format!("git add -- {}", args),
+                format!("git commit -m \"{}\"", HUMAN_COMMIT_MESSAGE),
+            ],
+        };
+        steps.push(SplitStep {
+            group,
+            title: group.title(),
+            files: paths,
+            commands,
+        });
+    }
+    steps
+}
+
+/// Files changed since HEAD (staged, unstaged or untracked), and whether any are staged
+fn changed_files(repo: &Repository) -> Result<(Vec<(String, bool)>, bool)> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .context("Failed to read repository status")?;
+
+    let staged_mask = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED
+        | Status::INDEX_TYPECHANGE;
+    let mut staged = false;
+    let mut files = Vec::new();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.is_empty() || status.contains(Status::IGNORED) {
+            continue;
+        }
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        if !is_capturable(path) {
+            continue;
+        }
+        staged |= status.intersects(staged_mask);
+        let deleted = status.intersects(Status::WT_DELETED | Status::INDEX_DELETED);
+        files.push((path.to_string(), deleted));
+    }
+    files.sort();
+    Ok((files, staged))
+}
+
+/// Classify the files changed since HEAD
+fn classify_changes(repo: &Repository) -> Result<(Vec<SplitFile>, bool)> {
+    let repo_root = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+    let pending: HashSet<String> = PendingStore::new(repo_root)
+        .load_quiet()?
+        .map(|buffer| buffer.files().into_iter().map(str::to_string).collect())
+        .unwrap_or_default();
+
+    let (changed, staged) = changed_files(repo)?;
+    let mut files = Vec::with_capacity(changed.len());
+    for (path, deleted) in changed {
+        let (ai, ai_modified, human) = if deleted {
+            (0, 0, 0)
+        } else {
+            // Binary or unreadable files have no line attribution
+            gutter_file(repo, repo_root, &path)
+                .map(|file| line_counts(&file.lines))
+                .unwrap_or((0, 0, 0))
+        };
+        files.push(SplitFile {
+            group: classify(ai, ai_modified, human, pending.contains(&path)),
+            path,
+            ai_lines: ai,
+            ai_modified_lines: ai_modified,
+            human_lines: human,
+            deleted,
+        });
+    }
+    Ok((files, staged))
+}
+
+/// Run the split-plan command
+pub fn run(args: SplitPlanArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let (files, staged) = classify_changes(&repo)?;
+    let steps = build_steps(&files);
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&files, &steps, staged),
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": SPLIT_PLAN_MACHINE_SCHEMA,
+                "unstage_first": staged,
+                "files": files,
+                "steps": steps,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+    Ok(())
+}
+
+fn print_pretty(files: &[SplitFile], steps: &[SplitStep], staged: bool) {
+    if files.is_empty() {
+        println!("No changes since HEAD.");
+        return;
+    }
+    if steps.len() == 1 {
+        println!(
+            "All {} changed file(s) are {}; no split needed.",
+            files.len(),
+            steps[0].title.to_lowercase()
+        );
+        println!();
+    }
+
+    println!(
+        "{}",
+        format!("# Split plan for {} changed file(s)", files.len()).bold()
+    );
+    if staged {
+        println!("{}", "# Unstage everything first".dimmed());
+        println!("git reset -q");
+    }
+    for (i, step) in steps.iter().enumerate() {
+        println!();
+        let mut heading = format!("# {}. {} ({} file(s))", i + 1, step.title, step.files.len());
+        if step.group == SplitGroup::Mixed {
+            heading.push_str(": stage only the AI hunks at the prompt, then the rest");
+        }
+        println!("{}", heading.bold());
+        for path in &step.files {
+            let Some(file) = files.iter().find(|f| &f.path == path) else {
+                continue;
+            };
+            let detail = if file.deleted {
+                "deleted".to_string()
+            } else {
+                format!(
+                    "{} AI, {} AI-modified, {} human line(s)",
+                    file.ai_lines, file.ai_modified_lines, file.human_lines
+                )
+            };
+            println!("{}", format!("#   {} ({})", path, detail).dimmed());
+        }
+        for command in &step.commands {
+            println!("{}", command);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, group: SplitGroup) -> SplitFile {
+        SplitFile {
+            path: path.to_string(),
+            group,
+            ai_lines: 0,
+            ai_modified_lines: 0,
+            human_lines: 0,
+            deleted: false,
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(10, 0, 0, true), SplitGroup::Ai);
+        assert_eq!(classify(0, 0, 3, false), SplitGroup::Human);
+        assert_eq!(classify(10, 0, 3, true), SplitGroup::Mixed);
+        assert_eq!(classify(10, 1, 0, true), SplitGroup::Mixed);
+        // Deletions or binary content: the pending buffer decides
+        assert_eq!(classify(0, 0, 0, true), SplitGroup::Ai);
+        assert_eq!(classify(0, 0, 0, false), SplitGroup::Human);
+    }
+
+    #[test]
+    fn test_build_steps_orders_groups_and_quotes_paths() {
+        let files = vec![
+            file("src/lib.rs", SplitGroup::Mixed),
+            file("README.md", SplitGroup::Human),
+            file("src/gen.rs", SplitGroup::Ai),
+            file("docs/my notes.md", SplitGroup::Ai),
+        ];
+        let steps = build_steps(&files);
+        let groups: Vec<SplitGroup> = steps.iter().map(|s| s.group).collect();
+        assert_eq!(
+            groups,
+            [SplitGroup::Ai, SplitGroup::Human, SplitGroup::Mixed]
+        );
+        assert_eq!(
+            steps[0].commands[0],
+            "git add -- src/gen.rs 'docs/my notes.md'"
+        );
+        assert_eq!(steps[2].commands[0], "git add -p -- src/lib.rs");
+        assert_eq!(steps[2].commands.len(), 4);
+        assert_eq!(shell_quote("it's.rs"), r"'it'\''s.rs'");
+    }
+
+    #[test]
+    fn test_classify_changes_separates_ai_and_human_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let repo = Repository::init(root).unwrap();
+        std::fs::write(root.join("README.md"), "# Project\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial", &tree, &[])
+            .unwrap();
+
+        let generated = "pub fn generated() -> u32 {\n    42\n}\n";
+        let mut buffer = crate::capture::pending::PendingBuffer::new_session("model");
+        buffer.record_edit("src/gen.rs", None, generated, "Write", "Add a module", None);
+        PendingStore::new(root).save(&buffer).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/gen.rs"), generated).unwrap();
+        std::fs::write(root.join("README.md"), "# Project\n\nUsage notes.\n").unwrap();
+
+        let (files, staged) = classify_changes(&repo).unwrap();
+        assert!(!staged);
+        let group = |path: &str| files.iter().find(|f| f.path == path).unwrap().group;
+        assert_eq!(group("src/gen.rs"), SplitGroup::Ai);
+        assert_eq!(group("README.md"), SplitGroup::Human);
+    }
+}
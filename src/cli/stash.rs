@@ -0,0 +1,287 @@
+//! Stash command - `git stash` that keeps the pending buffer in sync
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use git2::{Oid, Repository, Signature, StashFlags};
+
+use crate::capture::pending::PendingStore;
+use crate::capture::stash::{suspend_stashed_files, SuspendedStore};
+use crate::storage::{LockHolderKind, RepoLock};
+
+/// Stash command arguments
+#[derive(Debug, clap::Args)]
+pub struct StashArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub action: StashAction,
+}
+
+/// Stash subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum StashAction {
+    /// Stash local changes and suspend pending AI edits for the stashed files
+    Push {
+        /// Stash message
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// Also stash untracked files
+        #[arg(short = 'u', long)]
+        include_untracked: bool,
+    },
+    /// Pop a stash and restore the AI edits suspended with it
+    Pop {
+        /// Stash index (as in stash@{N})
+        #[arg(default_value_t = 0)]
+        index: usize,
+    },
+    /// Restore suspended AI edits for a stash commit without touching the stash
+    /// (e.g. after resolving a conflicting `git stash pop` by hand)
+    Restore {
+        /// Stash commit SHA (see `whogitit stash list`)
+        commit: String,
+    },
+    /// List stash entries with suspended AI edits
+    List,
+}
+
+/// Run the stash command
+pub fn run(args: StashArgs) -> Result<()> {
+    let mut repo = Repository::discover(".").context("Not in a git repository")?;
+    let _lock = match args.action {
+        StashAction::List => None,
+        _ => Some(RepoLock::acquire_default(
+            repo.path(),
+            LockHolderKind::Stash,
+        )?),
+    };
+    match args.action {
+        StashAction::Push {
+            message,
+            include_untracked,
+        } => run_push(&mut repo, message.as_deref(), include_untracked),
+        StashAction::Pop { index } => run_pop(&mut repo, index),
+        StashAction::Restore { commit } => {
+            let oid = repo
+                .revparse_single(&commit)
+                .with_context(|| format!("Failed to resolve: {}", commit))?
+                .id();
+            if !restore_suspended(&repo, oid)? {
+                println!("No suspended AI edits for {}", commit);
+            }
+            Ok(())
+        }
+        StashAction::List => run_list(&mut repo),
+    }
+}
+
+fn repo_root(repo: &Repository) -> Result<std::path::PathBuf> {
+    repo.workdir()
+        .map(|p| p.to_path_buf())
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))
+}
+
+fn run_push(repo: &mut Repository, message: Option<&str>, include_untracked: bool) -> Result<()> {
+    let root = repo_root(repo)?;
+    let pending_store = PendingStore::new(&root);
+    // Read before stashing so the buffer reflects the pre-stash worktree
+    let pending = pending_store.load()?;
+
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("whogitit", "whogitit@localhost"))?;
+    let flags = if include_untracked {
+        StashFlags::INCLUDE_UNTRACKED
+    } else {
+        StashFlags::DEFAULT
+    };
+    let stash_oid = repo
+        .stash_save(&signature, message.unwrap_or(""), Some(flags))
+        .context("Failed to stash changes")?;
+    println!(
+        "Saved working directory to stash@{{0}} ({})",
+        short(stash_oid)
+    );
+
+    let Some(mut buffer) = pending else {
+        return Ok(());
+    };
+    let Some(suspended) = suspend_stashed_files(&mut buffer, &root) else {
+        return Ok(());
+    };
+
+    SuspendedStore::new(repo.path()).save(stash_oid, &suspended)?;
+    if buffer.has_changes() {
+        pending_store.save(&buffer)?;
+    } else {
+        pending_store.delete()?;
+    }
+    println!(
+        "{} Suspended pending AI edits for {} file(s) with the stash.",
+        "whogitit:".cyan(),
+        suspended.file_count()
+    );
+    Ok(())
+}
+
+fn run_pop(repo: &mut Repository, index: usize) -> Result<()> {
+    let stash_oid = stash_oid_at(repo, index)?
+        .ok_or_else(|| anyhow::anyhow!("No stash entry stash@{{{}}}", index))?;
+
+    repo.stash_pop(index, None).with_context(|| {
+        format!(
+            "Failed to pop stash@{{{}}}; suspended AI edits were kept. \
+             Resolve the stash manually, then run 'whogitit stash restore {}'",
+            index, stash_oid
+        )
+    })?;
+    println!("Dropped stash@{{{}}} ({})", index, short(stash_oid));
+
+    restore_suspended(repo, stash_oid)?;
+    Ok(())
+}
+
+/// Merge suspended histories back into the pending buffer; returns false if none existed
+fn restore_suspended(repo: &Repository, stash_oid: Oid) -> Result<bool> {
+    let suspended_store = SuspendedStore::new(repo.path());
+    let Some(suspended) = suspended_store.load(stash_oid)? else {
+        return Ok(false);
+    };
+
+    let pending_store = PendingStore::new(&repo_root(repo)?);
+    let file_count = suspended.file_count();
+    let buffer = match pending_store.load()? {
+        Some(mut current) => {
+            current.absorb(suspended);
+            current
+        }
+        None => suspended,
+    };
+    pending_store.save(&buffer)?;
+    suspended_store.remove(stash_oid)?;
+
+    println!(
+        "{} Restored pending AI edits for {} file(s).",
+        "whogitit:".cyan(),
+        file_count
+    );
+    Ok(true)
+}
+
+fn run_list(repo: &mut Repository) -> Result<()> {
+    let suspended = SuspendedStore::new(repo.path()).list();
+    if suspended.is_empty() {
+        println!("No stash entries with suspended AI edits.");
+        return Ok(());
+    }
+
+    let mut entries: Vec<(usize, Oid, String)> = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        entries.push((index, *oid, message.to_string()));
+        true
+    })?;
+
+    for oid in suspended {
+        match entries.iter().find(|(_, stash, _)| *stash == oid) {
+            Some((index, _, message)) => {
+                println!("stash@{{{}}} {} {}", index, short(oid).yellow(), message)
+            }
+            None => println!(
+                "{} {} (stash entry no longer exists; run 'whogitit stash restore {}')",
+                "orphaned".red(),
+                short(oid).yellow(),
+                oid
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the commit for stash@{index}
+fn stash_oid_at(repo: &mut Repository, index: usize) -> Result<Option<Oid>> {
+    let mut found = None;
+    repo.stash_foreach(|i, _, oid| {
+        if i == index {
+            found = Some(*oid);
+            false
+        } else {
+            true
+        }
+    })?;
+    Ok(found)
+}
+
+fn short(oid: Oid) -> String {
+    let id = oid.to_string();
+    id[..crate::utils::SHORT_COMMIT_LEN].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::pending::PendingBuffer;
+    use tempfile::TempDir;
+
+    const SESSION: &str = "3e1d2c4b-6a5f-4e7d-8c9b-0a1b2c3d4e5f";
+
+    fn create_test_repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@test.com").unwrap();
+
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("a.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = repo.signature().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+                .unwrap();
+        }
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_push_suspends_and_pop_restores() {
+        let (dir, mut repo) = create_test_repo();
+        let store = PendingStore::new(dir.path());
+
+        let mut buffer = PendingBuffer::new(SESSION, "test-model");
+        buffer.record_edit(
+            "a.rs",
+            Some("fn a() {}\n"),
+            "fn a() { ai(); }\n",
+            "Edit",
+            "Change a",
+            None,
+        );
+        store.save(&buffer).unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn a() { ai(); }\n").unwrap();
+
+        run_push(&mut repo, Some("wip"), false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+        assert!(!store.exists());
+        assert_eq!(SuspendedStore::new(repo.path()).list().len(), 1);
+
+        run_pop(&mut repo, 0).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.rs")).unwrap(),
+            "fn a() { ai(); }\n"
+        );
+        let restored = store.load().unwrap().unwrap();
+        assert!(restored.get_file_history("a.rs").is_some());
+        assert!(SuspendedStore::new(repo.path()).list().is_empty());
+    }
+
+    #[test]
+    fn test_pop_without_stash_errors() {
+        let (_dir, mut repo) = create_test_repo();
+        assert!(run_pop(&mut repo, 0).is_err());
+    }
+}
@@ -0,0 +1,731 @@
+//! Stats command - AI adoption metrics over a time window
+//!
+//! `--by-author` breaks the totals down per commit author, and `--adoption` adds
+//! the fraction of each author's committed lines that are AI-assisted (AI or
+//! AI-modified lines in the commit's attribution note). Authors listed in
+//! `[metrics] opt_out` are left out of per-author output, and `[metrics] anonymize`
+//! (or `--anonymize`) replaces identities with stable pseudonyms. `--by-language`
+//! breaks the totals down per language, detected from file extensions. With a
+//! decay half-life (`--half-life` or `[decay]`), the totals also report a
+//! decay-weighted AI line count (see [`crate::core::decay`]). `--query` restricts
+//! the counts to files matching a [`crate::core::query`] expression.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use colored::Colorize;
+use git2::{Repository, Sort};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::capture::snapshot::AttributionSummary;
+use crate::cli::export::{parse_date, DateBoundary};
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::attribution::AIAttribution;
+use crate::core::decay::{self, DecayModel, DecayedLines};
+use crate::core::generated::GeneratedPaths;
+use crate::core::languages::LanguageMap;
+use crate::core::query::{FileRecord, Query};
+use crate::privacy::{MetricsConfig, WhogititConfig};
+use crate::storage::notes::NotesStore;
+use crate::utils::{hex, repo_path, truncate_or_pad};
+
+const STATS_MACHINE_SCHEMA: &str = "whogitit.stats.v1";
+/// Window used when `--since` is not given
+const DEFAULT_WINDOW_DAYS: i64 = 90;
+/// Hash bytes used for anonymized author labels
+const PSEUDONYM_BYTES: usize = 4;
+
+/// Stats command arguments
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Only include commits on or after this date (YYYY-MM-DD). Default: 90 days ago
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only include commits on or before this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Break statistics down per commit author
+    #[arg(long)]
+    pub by_author: bool,
+
+    /// Include each author's AI adoption (share of committed lines that are AI-assisted)
+    #[arg(long, requires = "by_author")]
+    pub adoption: bool,
+
+    /// Replace author identities with stable pseudonyms
+    #[arg(long)]
+    pub anonymize: bool,
+
+    /// Break statistics down per language (by file extension)
+    #[arg(long)]
+    pub by_language: bool,
+
+    /// Also report AI lines weighted by age with this half-life in days (overrides `[decay]`)
+    #[arg(long, value_name = "DAYS")]
+    pub half_life: Option<f64>,
+
+    /// Only count files matching this query, e.g. 'path ~ "src/**" && model ~ "opus"'
+    #[arg(long)]
+    pub query: Option<String>,
+
+    /// Count vendored and generated files (lock files, `vendor/`, `[generated] paths`)
+    #[arg(long)]
+    pub include_generated: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+/// Line and commit counts for a set of commits
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct AdoptionStats {
+    pub commits: usize,
+    /// Commits with at least one AI-assisted line
+    pub ai_commits: usize,
+    /// Lines added by the commits (first-parent diff)
+    pub lines_added: usize,
+    /// AI and AI-modified lines recorded in attribution notes
+    pub ai_lines: usize,
+}
+
+impl AdoptionStats {
+    fn add(&mut self, lines_added: usize, ai_lines: usize) {
+        self.commits += 1;
+        if ai_lines > 0 {
+            self.ai_commits += 1;
+        }
+        self.lines_added += lines_added;
+        self.ai_lines += ai_lines;
+    }
+
+    /// Fraction of committed lines that are AI-assisted (0.0-1.0)
+    pub fn adoption(&self) -> f64 {
+        if self.lines_added == 0 {
+            0.0
+        } else {
+            (self.ai_lines as f64 / self.lines_added as f64).min(1.0)
+        }
+    }
+}
+
+/// Decay-weighted AI lines across the window
+#[derive(Debug, Clone, Serialize)]
+pub struct DecayStats {
+    pub half_life_days: f64,
+    /// AI and AI-modified lines weighted by age
+    pub ai_lines: f64,
+    /// Decay-weighted AI lines as a fraction of lines added (0.0-1.0)
+    pub adoption: f64,
+}
+
+impl DecayStats {
+    fn new(model: &DecayModel, lines: DecayedLines, totals: &AdoptionStats) -> Self {
+        let adoption = if totals.lines_added == 0 {
+            0.0
+        } else {
+            (lines.total() / totals.lines_added as f64).min(1.0)
+        };
+        Self {
+            half_life_days: model.half_life_days(),
+            ai_lines: (lines.total() * 10.0).round() / 10.0,
+            adoption,
+        }
+    }
+}
+
+/// Per-author row
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorStats {
+    pub author: String,
+    #[serde(flatten)]
+    pub stats: AdoptionStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adoption: Option<f64>,
+}
+
+/// Per-language row
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageStats {
+    pub language: String,
+    /// Commits touching files in this language
+    #[serde(flatten)]
+    pub stats: AdoptionStats,
+    pub adoption: f64,
+}
+
+/// Stable pseudonym for an author, derived from their email
+fn pseudonym(email: &str) -> String {
+    let digest = Sha256::digest(email.to_ascii_lowercase().as_bytes());
+    format!("dev-{}", hex::encode(&digest[..PSEUDONYM_BYTES]))
+}
+
+/// Accumulates commits per author, applying the metrics privacy controls
+#[derive(Debug)]
+struct AuthorTally<'a> {
+    config: &'a MetricsConfig,
+    anonymize: bool,
+    totals: AdoptionStats,
+    authors: HashMap<String, AdoptionStats>,
+    opted_out: usize,
+}
+
+impl<'a> AuthorTally<'a> {
+    fn new(config: &'a MetricsConfig, anonymize: bool) -> Self {
+        Self {
+            config,
+            anonymize: anonymize || config.anonymize,
+            totals: AdoptionStats::default(),
+            authors: HashMap::new(),
+            opted_out: 0,
+        }
+    }
+
+    fn add(&mut self, name: &str, email: &str, lines_added: usize, ai_lines: usize) {
+        self.totals.add(lines_added, ai_lines);
+        if self.config.is_opted_out(name, email) {
+            self.opted_out += 1;
+            return;
+        }
+        let author = if self.anonymize {
+            pseudonym(email)
+        } else {
+            format!("{} <{}>", name, email)
+        };
+        self.authors
+            .entry(author)
+            .or_default()
+            .add(lines_added, ai_lines);
+    }
+
+    /// Author rows, by adoption (with `adoption`) or lines added, descending
+    fn rows(&self, adoption: bool) -> Vec<AuthorStats> {
+        let mut rows: Vec<AuthorStats> = self
+            .authors
+            .iter()
+            .map(|(author, stats)| AuthorStats {
+                author: author.clone(),
+                stats: stats.clone(),
+                adoption: adoption.then(|| stats.adoption()),
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            let key = |row: &AuthorStats| {
+                if adoption {
+                    row.stats.adoption()
+                } else {
+                    row.stats.lines_added as f64
+                }
+            };
+            key(b)
+                .partial_cmp(&key(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.author.cmp(&b.author))
+        });
+        rows
+    }
+}
+
+/// Diff of a commit against its first parent
+pub(crate) fn first_parent_diff<'r>(
+    repo: &'r Repository,
+    commit: &git2::Commit,
+) -> Result<git2::Diff<'r>> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?)
+}
+
+/// Lines added per file by a commit's diff
+fn lines_added_by_file(diff: &git2::Diff) -> Result<Vec<(String, usize)>> {
+    let mut files = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path() else {
+            continue;
+        };
+        // Binary files have no patch
+        if let Some(patch) = git2::Patch::from_diff(diff, index)? {
+            let (_, additions, _) = patch.line_stats()?;
+            files.push((repo_path::from_path(path), additions));
+        }
+    }
+    Ok(files)
+}
+
+/// Paths of a commit's files that match `--query`
+///
+/// Changed files without attribution are matched as human-written lines added.
+fn query_paths(
+    query: &Query,
+    author: &str,
+    attribution: Option<&AIAttribution>,
+    lines_added: &[(String, usize)],
+    languages: &LanguageMap,
+) -> HashSet<String> {
+    let model = attribution.map_or("", |attr| attr.session.model.id.as_str());
+    let matches = |path: &str, summary: &AttributionSummary| {
+        query.matches(&FileRecord {
+            path,
+            model,
+            author,
+            language: languages.detect(path),
+            summary,
+        })
+    };
+    let attributed = attribution.map_or(&[][..], |attr| attr.files.as_slice());
+
+    let mut paths: HashSet<String> = attributed
+        .iter()
+        .filter(|f| matches(&f.path, &f.summary))
+        .map(|f| f.path.clone())
+        .collect();
+    for (path, added) in lines_added {
+        if attributed.iter().any(|f| &f.path == path) {
+            continue;
+        }
+        let summary = AttributionSummary {
+            total_lines: *added,
+            ai_lines: 0,
+            ai_modified_lines: 0,
+            human_lines: *added,
+            original_lines: 0,
+            unknown_lines: 0,
+        };
+        if matches(path, &summary) {
+            paths.insert(path.clone());
+        }
+    }
+    paths
+}
+
+/// Add one commit to the per-language totals
+fn tally_languages(
+    map: &LanguageMap,
+    lines_added: &[(String, usize)],
+    ai_lines: &[(String, usize)],
+    languages: &mut HashMap<String, AdoptionStats>,
+) {
+    let mut commit: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (path, added) in lines_added {
+        commit.entry(map.detect(path)).or_default().0 += added;
+    }
+    for (path, ai) in ai_lines {
+        commit.entry(map.detect(path)).or_default().1 += ai;
+    }
+    for (language, (added, ai)) in commit {
+        languages
+            .entry(language.to_string())
+            .or_default()
+            .add(added, ai);
+    }
+}
+
+/// Language rows by lines added, descending
+fn language_rows(languages: HashMap<String, AdoptionStats>) -> Vec<LanguageStats> {
+    let mut rows: Vec<LanguageStats> = languages
+        .into_iter()
+        .map(|(language, stats)| LanguageStats {
+            language,
+            adoption: stats.adoption(),
+            stats,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.stats
+            .lines_added
+            .cmp(&a.stats.lines_added)
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    rows
+}
+
+/// Run the stats command
+pub fn run(args: StatsArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let config = repo
+        .workdir()
+        .map(WhogititConfig::load_or_warn)
+        .unwrap_or_default();
+
+    let since = parse_date(&args.since, DateBoundary::StartOfDay)?
+        .unwrap_or_else(|| Utc::now() - Duration::days(DEFAULT_WINDOW_DAYS));
+    let until = parse_date(&args.until, DateBoundary::EndOfDay)?;
+    let decay = DecayModel::from_config(&config.decay, args.half_life)?;
+    let now = Utc::now();
+    let mut decayed = DecayedLines::default();
+    if until.is_some_and(|until| since > until) {
+        anyhow::bail!("Invalid date range: --since must be before --until");
+    }
+    let query = args.query.as_deref().map(Query::parse).transpose()?;
+
+    let notes_store = NotesStore::new(&repo)?;
+    let mut tally = AuthorTally::new(&config.metrics, args.anonymize);
+    let language_map = LanguageMap::new(&config.languages);
+    let generated = if args.include_generated {
+        GeneratedPaths::none()
+    } else {
+        GeneratedPaths::new(&config.generated)
+    };
+    let mut languages: HashMap<String, AdoptionStats> = HashMap::new();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    if revwalk.push_head().is_err() {
+        anyhow::bail!("Repository has no commits");
+    }
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let time =
+            DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or(DateTime::UNIX_EPOCH);
+        if time < since || until.is_some_and(|until| time > until) {
+            continue;
+        }
+        // Merges carry no authored lines of their own
+        if commit.parent_count() > 1 {
+            continue;
+        }
+
+        let author = commit.author();
+        let author_name = author.name().unwrap_or("Unknown");
+        let attribution = notes_store.fetch_attribution(commit.id())?;
+        let diff = first_parent_diff(&repo, &commit)?;
+        let per_file = args.by_language || query.is_some() || !args.include_generated;
+        let mut added_by_file = if per_file {
+            lines_added_by_file(&diff)?
+        } else {
+            Vec::new()
+        };
+        let mut files: Vec<_> = attribution.iter().flat_map(|attr| &attr.files).collect();
+        added_by_file.retain(|(path, _)| !generated.is_generated(path));
+        files.retain(|f| !generated.is_generated(&f.path));
+        let mut lines_added = if per_file {
+            added_by_file.iter().map(|(_, added)| added).sum()
+        } else {
+            diff.stats()?.insertions()
+        };
+        if let Some(query) = &query {
+            let selected = query_paths(
+                query,
+                author_name,
+                attribution.as_ref(),
+                &added_by_file,
+                &language_map,
+            );
+            if selected.is_empty() {
+                continue;
+            }
+            files.retain(|f| selected.contains(&f.path));
+            added_by_file.retain(|(path, _)| selected.contains(path));
+            lines_added = added_by_file.iter().map(|(_, added)| added).sum();
+        }
+
+        if let Some(model) = &decay {
+            let age_days = decay::age_days(commit.time().seconds(), now);
+            for file in &files {
+                decayed.add(model.weigh(&file.summary, age_days));
+            }
+        }
+        let file_ai_lines: Vec<(String, usize)> = files
+            .iter()
+            .map(|f| {
+                let summary = &f.summary;
+                (f.path.clone(), summary.ai_lines + summary.ai_modified_lines)
+            })
+            .collect();
+        if args.by_language {
+            tally_languages(
+                &language_map,
+                &added_by_file,
+                &file_ai_lines,
+                &mut languages,
+            );
+        }
+        tally.add(
+            author_name,
+            author.email().unwrap_or(""),
+            lines_added,
+            file_ai_lines.iter().map(|(_, ai)| ai).sum(),
+        );
+    }
+
+    let decay_stats = decay
+        .as_ref()
+        .map(|model| DecayStats::new(model, decayed, &tally.totals));
+    let rows = args.by_author.then(|| tally.rows(args.adoption));
+    let language_rows = args.by_language.then(|| language_rows(languages));
+    match args.format {
+        OutputFormat::Pretty => {
+            print_pretty(
+                &tally,
+                rows.as_deref(),
+                since,
+                args.adoption,
+                decay_stats.as_ref(),
+            );
+            if let Some(language_rows) = &language_rows {
+                print_languages(language_rows);
+            }
+        }
+        OutputFormat::Json => {
+            let mut output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": STATS_MACHINE_SCHEMA,
+                "since": since.to_rfc3339(),
+                "until": until.map(|u| u.to_rfc3339()),
+                "totals": tally.totals,
+                "adoption": tally.totals.adoption(),
+            });
+            if let Some(decay_stats) = decay_stats {
+                output["decay"] = serde_json::to_value(decay_stats)?;
+            }
+            if let Some(rows) = rows {
+                output["authors"] = serde_json::to_value(rows)?;
+                output["opted_out_commits"] = tally.opted_out.into();
+                output["anonymized"] = tally.anonymize.into();
+            }
+            if let Some(language_rows) = language_rows {
+                output["languages"] = serde_json::to_value(language_rows)?;
+            }
+            if let Some(query) = &args.query {
+                output["query"] = query.as_str().into();
+            }
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+    Ok(())
+}
+
+fn print_pretty(
+    tally: &AuthorTally,
+    rows: Option<&[AuthorStats]>,
+    since: DateTime<Utc>,
+    adoption: bool,
+    decay: Option<&DecayStats>,
+) {
+    let totals = &tally.totals;
+    println!(
+        "{} since {}",
+        "AI adoption".bold(),
+        since.format("%Y-%m-%d")
+    );
+    println!(
+        "  {} commits ({} AI-assisted), {} lines added, {} AI-assisted ({:.1}%)",
+        totals.commits,
+        totals.ai_commits,
+        totals.lines_added,
+        totals.ai_lines,
+        totals.adoption() * 100.0
+    );
+    if let Some(decay) = decay {
+        println!(
+            "  {}",
+            format!(
+                "Decay-weighted ({}-day half-life): {:.1} AI lines ({:.1}%)",
+                decay.half_life_days,
+                decay.ai_lines,
+                decay.adoption * 100.0
+            )
+            .dimmed()
+        );
+    }
+
+    let Some(rows) = rows else {
+        return;
+    };
+    println!();
+    if adoption {
+        println!(
+            "  {} {:>8} {:>10} {:>10} {:>9}",
+            truncate_or_pad("Author", 40),
+            "Commits",
+            "Lines",
+            "AI lines",
+            "Adoption"
+        );
+    } else {
+        println!(
+            "  {} {:>8} {:>10} {:>10}",
+            truncate_or_pad("Author", 40),
+            "Commits",
+            "Lines",
+            "AI lines"
+        );
+    }
+    for row in rows {
+        let author = truncate_or_pad(&row.author, 40);
+        match row.adoption {
+            Some(adoption) => println!(
+                "  {} {:>8} {:>10} {:>10} {:>8.1}%",
+                author,
+                row.stats.commits,
+                row.stats.lines_added,
+                row.stats.ai_lines,
+                adoption * 100.0
+            ),
+            None => println!(
+                "  {} {:>8} {:>10} {:>10}",
+                author, row.stats.commits, row.stats.lines_added, row.stats.ai_lines
+            ),
+        }
+    }
+    if tally.opted_out > 0 {
+        println!();
+        println!(
+            "  {}",
+            format!(
+                "{} commit(s) by opted-out authors are counted in totals only",
+                tally.opted_out
+            )
+            .dimmed()
+        );
+    }
+}
+
+fn print_languages(rows: &[LanguageStats]) {
+    println!();
+    println!(
+        "  {} {:>8} {:>10} {:>10} {:>9}",
+        truncate_or_pad("Language", 40),
+        "Commits",
+        "Lines",
+        "AI lines",
+        "AI share"
+    );
+    for row in rows {
+        println!(
+            "  {} {:>8} {:>10} {:>10} {:>8.1}%",
+            truncate_or_pad(&row.language, 40),
+            row.stats.commits,
+            row.stats.lines_added,
+            row.stats.ai_lines,
+            row.adoption * 100.0
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adoption_by_author_respects_opt_out() {
+        let config = MetricsConfig {
+            opt_out: vec!["carol@example.com".to_string()],
+            anonymize: false,
+        };
+        let mut tally = AuthorTally::new(&config, false);
+        tally.add("Alice", "alice@example.com", 100, 80);
+        tally.add("Alice", "alice@example.com", 100, 0);
+        tally.add("Bob", "bob@example.com", 50, 10);
+        tally.add("Carol", "carol@example.com", 10, 10);
+
+        assert_eq!(tally.totals.commits, 4);
+        assert_eq!(tally.totals.ai_lines, 100);
+        assert_eq!(tally.opted_out, 1);
+
+        let rows = tally.rows(true);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].author, "Alice <alice@example.com>");
+        assert_eq!(rows[0].stats.ai_commits, 1);
+        assert_eq!(rows[0].adoption, Some(0.4));
+        assert_eq!(rows[1].adoption, Some(0.2));
+        assert!(tally.rows(false)[0].adoption.is_none());
+    }
+
+    #[test]
+    fn test_anonymized_authors_use_stable_pseudonyms() {
+        let config = MetricsConfig {
+            opt_out: vec![],
+            anonymize: true,
+        };
+        let mut tally = AuthorTally::new(&config, false);
+        tally.add("Alice", "alice@example.com", 10, 5);
+        tally.add("Alice Smith", "Alice@Example.com", 10, 5);
+
+        let rows = tally.rows(true);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].author, pseudonym("alice@example.com"));
+        assert!(rows[0].author.starts_with("dev-"));
+        assert!(!rows[0].author.contains("alice"));
+        assert_eq!(rows[0].stats.commits, 2);
+    }
+
+    #[test]
+    fn test_query_paths_matches_unattributed_files_as_human() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let attribution = fixture.attribution(fixture.head()).unwrap().unwrap();
+        let generated = attribution.files[0].path.clone();
+        let lines_added = vec![(generated.clone(), 5), ("README.md".to_string(), 3)];
+        let map = LanguageMap::default();
+        let paths = |query: &str| {
+            let query = Query::parse(query).unwrap();
+            let mut paths: Vec<String> =
+                query_paths(&query, "Ada", Some(&attribution), &lines_added, &map)
+                    .into_iter()
+                    .collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(paths("ai_percent > 50"), vec![generated.clone()]);
+        assert_eq!(paths("human_lines == 3"), vec!["README.md".to_string()]);
+        assert_eq!(
+            paths(r#"author == "Ada""#),
+            vec!["README.md".to_string(), generated]
+        );
+        assert!(paths(r#"author == "Bob""#).is_empty());
+    }
+
+    #[test]
+    fn test_tally_languages_groups_files_by_language() {
+        let map = LanguageMap::default();
+        let mut languages = HashMap::new();
+        tally_languages(
+            &map,
+            &[
+                ("infra/main.tf".to_string(), 40),
+                ("infra/vars.tfvars".to_string(), 10),
+                ("src/lib.rs".to_string(), 50),
+            ],
+            &[
+                ("infra/main.tf".to_string(), 30),
+                ("infra/vars.tfvars".to_string(), 1),
+            ],
+            &mut languages,
+        );
+        tally_languages(
+            &map,
+            &[("src/main.rs".to_string(), 50)],
+            &[("src/main.rs".to_string(), 25)],
+            &mut languages,
+        );
+
+        let rows = language_rows(languages);
+        let summary: Vec<(&str, usize, usize, usize)> = rows
+            .iter()
+            .map(|r| {
+                (
+                    r.language.as_str(),
+                    r.stats.commits,
+                    r.stats.lines_added,
+                    r.stats.ai_lines,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("Rust", 2, 100, 25), ("Terraform", 1, 50, 31)]
+        );
+        assert_eq!(rows[1].adoption, 0.62);
+    }
+}
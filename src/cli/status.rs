@@ -0,0 +1,174 @@
+//! Machine-readable `status --porcelain` for shell prompts
+//!
+//! Prompts run this on every keystroke-return, so it does as little as possible:
+//! no lock on the pending buffer (saves replace it atomically), no index scan,
+//! no notes access, and nothing on stderr. The work runs on a worker thread and
+//! `--timeout` bounds how long the prompt waits for it.
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::capture::pause::CapturePause;
+use crate::capture::pending::{PendingBuffer, PendingStore};
+use crate::privacy::WhogititConfig;
+
+/// Status command arguments
+#[derive(Debug, Args)]
+pub struct StatusArgs {
+    /// Print a single stable line for shell prompts instead of the full report
+    #[arg(long)]
+    pub porcelain: bool,
+
+    /// Give up after this many milliseconds and report state `timeout` (with --porcelain)
+    #[arg(long, value_name = "MS", default_value_t = 100, requires = "porcelain")]
+    pub timeout: u64,
+
+    /// Also show the prompt count and writes skipped for leaving files unchanged
+    #[arg(short, long, conflicts_with = "porcelain")]
+    pub verbose: bool,
+}
+
+/// Print the porcelain status line, with state `timeout` if it takes longer than `timeout_ms`
+///
+/// Prints nothing outside a git repository.
+pub fn run_porcelain(timeout_ms: u64) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(porcelain_status());
+    });
+
+    match receiver.recv_timeout(Duration::from_millis(timeout_ms)) {
+        Ok(Some(line)) => println!("{}", line),
+        Ok(None) => {}
+        // The worker is abandoned; it ends with the process
+        Err(_) => println!("{}", format_unknown("timeout", false)),
+    }
+    Ok(())
+}
+
+fn porcelain_status() -> Option<String> {
+    let repo = git2::Repository::discover(".").ok()?;
+    let repo_root = repo.workdir()?;
+    Some(porcelain_line(repo_root))
+}
+
+/// Porcelain status line for the repository at `repo_root`
+pub fn porcelain_line(repo_root: &Path) -> String {
+    let paused = CapturePause::peek_for_workdir(repo_root).is_some();
+    let store = PendingStore::new(repo_root);
+    if !store.exists() {
+        return format_porcelain(None, false, paused);
+    }
+    match store.load_quiet() {
+        Ok(Some(buffer)) => {
+            let max_age_hours = WhogititConfig::load(repo_root)
+                .unwrap_or_default()
+                .analysis
+                .max_pending_age_hours as i64;
+            format_porcelain(Some(&buffer), buffer.is_stale_hours(max_age_hours), paused)
+        }
+        Ok(None) | Err(_) => format_unknown("corrupt", paused),
+    }
+}
+
+/// Porcelain line for a state without counts (`corrupt`, `timeout`), in the same format
+pub fn format_unknown(state: &str, paused: bool) -> String {
+    format_line(state, 0, 0, 0, false, 0, paused)
+}
+
+/// `<state> files=<n> edits=<n> ai_lines=<n> stale=<0|1> secrets=<n> paused=<0|1>`,
+/// state `clean` or `pending`
+pub fn format_porcelain(buffer: Option<&PendingBuffer>, stale: bool, paused: bool) -> String {
+    let (files, edits, ai_lines, secrets) = buffer
+        .map(|b| {
+            (
+                b.file_count(),
+                b.total_edits(),
+                b.total_lines(),
+                b.secret_findings().len(),
+            )
+        })
+        .unwrap_or_default();
+    let state = if files > 0 { "pending" } else { "clean" };
+    format_line(
+        state,
+        files,
+        edits,
+        ai_lines,
+        stale && files > 0,
+        secrets,
+        paused,
+    )
+}
+
+fn format_line(
+    state: &str,
+    files: usize,
+    edits: usize,
+    ai_lines: u32,
+    stale: bool,
+    secrets: usize,
+    paused: bool,
+) -> String {
+    format!(
+        "{} files={} edits={} ai_lines={} stale={} secrets={} paused={}",
+        state,
+        files,
+        edits,
+        ai_lines,
+        u8::from(stale),
+        secrets,
+        u8::from(paused)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_porcelain() {
+        assert_eq!(
+            format_porcelain(None, false, false),
+            "clean files=0 edits=0 ai_lines=0 stale=0 secrets=0 paused=0"
+        );
+        assert!(format_porcelain(None, false, true).ends_with(" paused=1"));
+
+        let mut buffer = PendingBuffer::new("session", "model");
+        buffer.record_edit("a.rs", None, "one\ntwo\n", "Write", "Add a", None);
+        buffer.record_edit(
+            "a.rs",
+            Some("one\ntwo\n"),
+            "one\ntwo\nthree\n",
+            "Edit",
+            "More",
+            None,
+        );
+        assert_eq!(
+            format_porcelain(Some(&buffer), true, false),
+            "pending files=1 edits=2 ai_lines=3 stale=1 secrets=0 paused=0"
+        );
+    }
+
+    #[test]
+    fn test_porcelain_line_reports_corrupt_buffer() {
+        let dir = TempDir::new().unwrap();
+        assert!(porcelain_line(dir.path()).starts_with("clean "));
+
+        let store = PendingStore::new(dir.path());
+        std::fs::write(store.path(), "{ not json").unwrap();
+        assert_eq!(
+            porcelain_line(dir.path()),
+            "corrupt files=0 edits=0 ai_lines=0 stale=0 secrets=0 paused=0"
+        );
+        assert_eq!(
+            format_unknown("timeout", false),
+            "timeout files=0 edits=0 ai_lines=0 stale=0 secrets=0 paused=0"
+        );
+    }
+}
@@ -3,8 +3,17 @@ use clap::{Args, ValueEnum};
 use colored::Colorize;
 use git2::Repository;
 
-use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::cli::i18n::{Locale, Msg};
+use crate::cli::output::{OutputSanitizer, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::core::generated::GeneratedPaths;
+use crate::core::integrity;
+use crate::core::packages::PackageMap;
+use crate::core::policy::changed_source_files;
+use crate::privacy::WhogititConfig;
+use crate::storage::index::{AttributionIndex, IndexedCommit, IndexedFile};
 use crate::storage::notes::NotesStore;
+use crate::storage::trailers::{ParsedTrailers, TrailerParser};
+use crate::utils::{truncate_or_pad, SHORT_COMMIT_LEN};
 
 /// Check if repository is a shallow clone
 fn is_shallow_clone(repo: &Repository) -> bool {
@@ -51,6 +60,86 @@ pub struct SummaryArgs {
     /// Output format
     #[arg(long, value_enum, default_value_t = SummaryFormat::Pretty)]
     pub format: SummaryFormat,
+
+    /// Exit with an error if any commit changing source files has no attribution note
+    #[arg(long)]
+    pub fail_on_missing_attribution: bool,
+
+    /// Roll up statistics per monorepo package (Cargo/npm workspaces, `[packages] roots`)
+    #[arg(long)]
+    pub by_package: bool,
+
+    /// Sanitization preset: full, internal, external or one from `[redact_levels]`
+    #[arg(long)]
+    pub redact_level: Option<String>,
+
+    /// Recompute per-file summaries from stored line data and rewrite notes that drifted
+    #[arg(long)]
+    pub reconcile: bool,
+
+    /// Language and number format of pretty/Markdown output: en, de, fr, es
+    /// (default: $WHOGITIT_LOCALE)
+    #[arg(long, value_name = "LOCALE")]
+    pub locale: Option<String>,
+
+    /// Count vendored and generated files (lock files, `vendor/`, `[generated] paths`)
+    #[arg(long)]
+    pub include_generated: bool,
+}
+
+/// Label for files outside every detected package
+const ROOT_PACKAGE: &str = "(root)";
+
+/// A commit that changes source files but has no whogitit note
+#[derive(Debug, Clone)]
+struct MissingAttribution {
+    commit: String,
+    subject: String,
+    source_files: usize,
+}
+
+impl MissingAttribution {
+    fn short_id(&self) -> &str {
+        &self.commit[..self.commit.len().min(SHORT_COMMIT_LEN)]
+    }
+}
+
+/// Build the error reported when commits are missing attribution
+fn missing_attribution_error(missing: &[MissingAttribution]) -> anyhow::Error {
+    let mut message = format!(
+        "{} commit(s) change source files but have no whogitit attribution:\n",
+        missing.len()
+    );
+    for entry in missing {
+        message.push_str(&format!(
+            "  {} {} ({} source file(s))\n",
+            entry.short_id(),
+            entry.subject,
+            entry.source_files
+        ));
+    }
+    message.push_str(
+        "Hint: the author's hooks were likely not installed. Run 'whogitit doctor' and \
+         'whogitit init' in their clone, and use 'whogitit copy-notes' to carry attribution \
+         over from rewritten commits.",
+    );
+    anyhow::anyhow!(message)
+}
+
+/// A possible secret in AI-written code, recorded in a commit's note
+#[derive(Debug, Clone)]
+struct SecretSummary {
+    commit: String,
+    path: String,
+    /// Line number in the file as of `commit`
+    line_number: u32,
+    pattern_name: String,
+}
+
+impl SecretSummary {
+    fn short_id(&self) -> &str {
+        &self.commit[..self.commit.len().min(SHORT_COMMIT_LEN)]
+    }
 }
 
 /// Per-file summary for diff-focused display
@@ -62,6 +151,8 @@ struct FileSummary {
     human_lines: usize,
     original_lines: usize,
     is_new_file: bool,
+    /// For binary assets/lockfiles: whether the latest commit kept the AI content as-is
+    binary_ai_generated: Option<bool>,
 }
 
 impl FileSummary {
@@ -86,11 +177,81 @@ impl FileSummary {
     }
 }
 
+/// Per-package rollup of file summaries
+#[derive(Debug, Clone, PartialEq)]
+struct PackageSummary {
+    name: String,
+    /// Package root (empty for files outside every package)
+    root: String,
+    files: usize,
+    ai_lines: usize,
+    ai_modified_lines: usize,
+    human_lines: usize,
+}
+
+impl PackageSummary {
+    fn additions(&self) -> usize {
+        self.ai_lines + self.ai_modified_lines + self.human_lines
+    }
+
+    fn ai_additions(&self) -> usize {
+        self.ai_lines + self.ai_modified_lines
+    }
+
+    fn ai_percent(&self) -> f64 {
+        let adds = self.additions();
+        if adds == 0 {
+            0.0
+        } else {
+            (self.ai_additions() as f64 / adds as f64) * 100.0
+        }
+    }
+}
+
+/// Group file summaries by package, most AI-authored first
+fn rollup_packages(files: &[FileSummary], packages: &PackageMap) -> Vec<PackageSummary> {
+    let mut rollup: Vec<PackageSummary> = Vec::new();
+    for file in files {
+        let (name, root) = match packages.package_for(&file.path) {
+            Some(package) => (package.name.as_str(), package.root.as_str()),
+            None => (ROOT_PACKAGE, ""),
+        };
+        let entry = match rollup.iter_mut().position(|p| p.root == root) {
+            Some(idx) => &mut rollup[idx],
+            None => {
+                rollup.push(PackageSummary {
+                    name: name.to_string(),
+                    root: root.to_string(),
+                    files: 0,
+                    ai_lines: 0,
+                    ai_modified_lines: 0,
+                    human_lines: 0,
+                });
+                rollup.last_mut().expect("just pushed")
+            }
+        };
+        entry.files += 1;
+        entry.ai_lines += file.ai_lines;
+        entry.ai_modified_lines += file.ai_modified_lines;
+        entry.human_lines += file.human_lines;
+    }
+
+    rollup.sort_by(|a, b| {
+        b.ai_percent()
+            .total_cmp(&a.ai_percent())
+            .then_with(|| b.ai_additions().cmp(&a.ai_additions()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rollup
+}
+
 /// Aggregated summary across multiple commits (diff-focused)
 #[derive(Debug, Default)]
 struct AggregateSummary {
     commits_analyzed: usize,
     commits_with_ai: usize,
+    /// Commits whose attribution came from commit trailers (no note, no per-file data)
+    commits_from_trailers: usize,
     /// AI-generated lines (additions)
     total_ai_lines: usize,
     /// AI lines modified by human (additions)
@@ -102,9 +263,58 @@ struct AggregateSummary {
     /// Per-file summaries for detailed breakdown
     file_summaries: Vec<FileSummary>,
     models_used: Vec<String>,
+    /// Labels of explicit sessions (`whogitit session begin --label`)
+    session_labels: Vec<String>,
+    /// Files left out of `file_summaries` by an aggregate-only redact level
+    hidden_files: usize,
+    /// Files whose note summary disagrees with the blob or the stored lines
+    line_count_discrepancies: usize,
+    /// Notes rewritten by `--reconcile`
+    reconciled_commits: usize,
+    /// Possible secrets in AI-written code (`privacy.scan_generated_code`)
+    secret_findings: Vec<SecretSummary>,
+    /// Secret findings left out of `secret_findings` by an aggregate-only redact level
+    hidden_secret_findings: usize,
+    /// Vendored or generated files left out of the totals
+    generated_files: Vec<String>,
+    /// Lines added to vendored or generated files, not in the totals
+    generated_lines: usize,
 }
 
 impl AggregateSummary {
+    /// Add the line counts recorded in a commit's attribution trailers
+    fn add_trailers(&mut self, trailers: &ParsedTrailers) {
+        self.commits_with_ai += 1;
+        self.commits_from_trailers += 1;
+        self.total_ai_lines += trailers.ai_lines.unwrap_or(0);
+        self.total_ai_modified_lines += trailers.ai_modified_lines.unwrap_or(0);
+        self.total_human_lines += trailers.human_lines.unwrap_or(0);
+        if let Some(model) = &trailers.model {
+            if !self.models_used.contains(model) {
+                self.models_used.push(model.clone());
+            }
+        }
+    }
+
+    /// Leave a vendored or generated file's lines out of the totals
+    fn add_generated(&mut self, file: &IndexedFile) {
+        let summary = &file.summary;
+        self.generated_lines += summary.ai_lines + summary.ai_modified_lines + summary.human_lines;
+        if !self.generated_files.contains(&file.path) {
+            self.generated_files.push(file.path.clone());
+        }
+    }
+
+    /// Number of secret findings, including hidden ones
+    fn secret_finding_count(&self) -> usize {
+        self.secret_findings.len() + self.hidden_secret_findings
+    }
+
+    /// Number of files with attribution, including hidden ones
+    fn files_changed(&self) -> usize {
+        self.file_summaries.len() + self.hidden_files
+    }
+
     /// Total additions (lines added in the diff)
     fn total_additions(&self) -> usize {
         self.total_ai_lines + self.total_ai_modified_lines + self.total_human_lines
@@ -136,7 +346,106 @@ pub fn run(args: SummaryArgs) -> Result<()> {
     }
 
     let notes_store = NotesStore::new(&repo)?;
+    run_with_store(&repo, &notes_store, args)
+}
+
+/// Summarize a commit range using attribution from `notes_store`
+pub(crate) fn run_with_store(
+    repo: &Repository,
+    notes_store: &NotesStore,
+    args: SummaryArgs,
+) -> Result<()> {
+    let locale = Locale::resolve(args.locale.as_deref())?;
+    let SummaryReport {
+        summary,
+        missing,
+        packages,
+        sanitizer,
+    } = build_report(repo, notes_store, &args)?;
+
+    if summary.reconciled_commits > 0 {
+        eprintln!(
+            "whogitit: Reconciled file summaries in {} note(s)",
+            summary.reconciled_commits
+        );
+    }
+    if summary.line_count_discrepancies > 0 && !matches!(args.format, SummaryFormat::Json) {
+        eprintln!(
+            "whogitit: Warning - {} file(s) have note summaries that disagree with their \
+             blobs; run 'whogitit show <commit>' for details",
+            summary.line_count_discrepancies
+        );
+    }
+
+    // Output based on format
+    match args.format {
+        SummaryFormat::Pretty => {
+            print_pretty(&summary, locale);
+            if let Some(packages) = &packages {
+                print_packages_pretty(packages, &summary);
+            }
+        }
+        SummaryFormat::Json => {
+            let output = summary_json(
+                &summary,
+                args.fail_on_missing_attribution
+                    .then_some(missing.as_slice()),
+                packages.as_deref(),
+                sanitizer.as_ref().map(OutputSanitizer::name),
+            );
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+            );
+        }
+        SummaryFormat::Markdown => {
+            print_markdown(&summary, locale);
+            if let Some(packages) = &packages {
+                print_packages_markdown(packages, &summary);
+            }
+            if !missing.is_empty() {
+                print_missing_markdown(&missing);
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(missing_attribution_error(&missing));
+    }
+
+    Ok(())
+}
+
+/// `summary --format json` output for a range, as served by `whogitit serve`
+pub(crate) fn range_json(
+    repo: &Repository,
+    notes_store: &NotesStore,
+    args: &SummaryArgs,
+) -> Result<serde_json::Value> {
+    let report = build_report(repo, notes_store, args)?;
+    Ok(summary_json(
+        &report.summary,
+        args.fail_on_missing_attribution
+            .then_some(report.missing.as_slice()),
+        report.packages.as_deref(),
+        report.sanitizer.as_ref().map(OutputSanitizer::name),
+    ))
+}
+
+/// A summarized range, ready for output
+struct SummaryReport {
+    summary: AggregateSummary,
+    missing: Vec<MissingAttribution>,
+    packages: Option<Vec<PackageSummary>>,
+    sanitizer: Option<OutputSanitizer>,
+}
 
+/// Aggregate the attribution of `args.base..args.head`
+fn build_report(
+    repo: &Repository,
+    notes_store: &NotesStore,
+    args: &SummaryArgs,
+) -> Result<SummaryReport> {
     // Resolve head commit
     let head_obj = repo
         .revparse_single(&args.head)
@@ -160,18 +469,79 @@ pub fn run(args: SummaryArgs) -> Result<()> {
         revwalk.hide(base_commit.id())?;
     }
 
-    // Analyze commits
+    let config = match repo.workdir() {
+        Some(workdir)
+            if args.by_package || args.redact_level.is_some() || !args.include_generated =>
+        {
+            WhogititConfig::load_or_warn(workdir)
+        }
+        _ => WhogititConfig::default(),
+    };
+    let generated = if args.include_generated {
+        GeneratedPaths::none()
+    } else {
+        GeneratedPaths::new(&config.generated)
+    };
+
+    // Analyze commits; --reconcile reads every note, as it may rewrite them
+    let mut index = if args.reconcile {
+        None
+    } else {
+        AttributionIndex::open_or_warn(repo)
+    };
     let mut summary = AggregateSummary::default();
+    let mut missing: Vec<MissingAttribution> = Vec::new();
 
     for oid_result in revwalk {
         let oid = oid_result?;
         summary.commits_analyzed += 1;
 
-        if let Ok(Some(attr)) = notes_store.fetch_attribution(oid) {
+        let indexed = match index
+            .as_mut()
+            .map(|index| index.lookup(repo, notes_store, oid))
+        {
+            Some(Ok(indexed)) => indexed,
+            _ => read_commit(repo, notes_store, oid, args.reconcile, &mut summary)?,
+        };
+        if indexed.is_none() {
+            // Fall back to the summary written by the trailers storage backend
+            let commit = repo.find_commit(oid)?;
+            let trailers = TrailerParser::parse(commit.message().unwrap_or(""));
+            if trailers.has_attribution() {
+                summary.add_trailers(&trailers);
+                continue;
+            }
+        }
+        if args.fail_on_missing_attribution && indexed.is_none() {
+            let commit = repo.find_commit(oid)?;
+            let source_files = changed_source_files(repo, &commit)?;
+            if source_files > 0 {
+                missing.push(MissingAttribution {
+                    commit: oid.to_string(),
+                    subject: commit.summary().unwrap_or("").to_string(),
+                    source_files,
+                });
+            }
+        }
+
+        if let Some(indexed) = indexed {
             summary.commits_with_ai += 1;
+            summary.line_count_discrepancies += indexed.line_count_issues;
 
             // Aggregate file statistics
-            for file in &attr.files {
+            for file in &indexed.files {
+                summary
+                    .secret_findings
+                    .extend(file.secret_findings.iter().map(|finding| SecretSummary {
+                        commit: oid.to_string(),
+                        path: file.path.clone(),
+                        line_number: finding.line_number,
+                        pattern_name: finding.pattern_name.clone(),
+                    }));
+                if generated.is_generated(&file.path) {
+                    summary.add_generated(file);
+                    continue;
+                }
                 summary.total_ai_lines += file.summary.ai_lines;
                 summary.total_ai_modified_lines += file.summary.ai_modified_lines;
                 summary.total_human_lines += file.summary.human_lines;
@@ -189,6 +559,10 @@ pub fn run(args: SummaryArgs) -> Result<()> {
                     existing.ai_modified_lines += file.summary.ai_modified_lines;
                     existing.human_lines += file.summary.human_lines;
                     existing.original_lines += file.summary.original_lines;
+                    // Commits are walked newest first, so keep the first binary status seen
+                    if existing.binary_ai_generated.is_none() {
+                        existing.binary_ai_generated = file.binary_ai_generated;
+                    }
                 } else {
                     // Add new file summary
                     let is_new = file.summary.original_lines == 0
@@ -203,115 +577,359 @@ pub fn run(args: SummaryArgs) -> Result<()> {
                         human_lines: file.summary.human_lines,
                         original_lines: file.summary.original_lines,
                         is_new_file: is_new,
+                        binary_ai_generated: file.binary_ai_generated,
                     });
                 }
             }
 
             // Track models used
-            if !summary.models_used.contains(&attr.session.model.id) {
-                summary.models_used.push(attr.session.model.id.clone());
+            if !summary.models_used.contains(&indexed.model) {
+                summary.models_used.push(indexed.model.clone());
+            }
+            if let Some(label) = &indexed.session_label {
+                if !summary.session_labels.contains(label) {
+                    summary.session_labels.push(label.clone());
+                }
             }
         }
     }
 
-    // Output based on format
-    match args.format {
-        SummaryFormat::Pretty => print_pretty(&summary),
-        SummaryFormat::Json => print_json(&summary),
-        SummaryFormat::Markdown => print_markdown(&summary),
+    let sanitizer = match &args.redact_level {
+        Some(name) => Some(OutputSanitizer::new(name, config.redact_level(name)?)),
+        None => None,
+    };
+
+    let mut packages = if args.by_package {
+        let workdir = repo
+            .workdir()
+            .context("--by-package requires a working tree to detect packages")?;
+        let map = PackageMap::detect(workdir, &config.packages)?;
+        if map.is_empty() {
+            eprintln!(
+                "whogitit: No packages detected (Cargo/npm workspaces or [packages] roots); \
+                 reporting everything under {}",
+                ROOT_PACKAGE
+            );
+        }
+        Some(rollup_packages(&summary.file_summaries, &map))
+    } else {
+        None
+    };
+
+    if let Some(sanitizer) = &sanitizer {
+        sanitize_summary(&mut summary, &mut packages, &mut missing, sanitizer);
     }
 
-    Ok(())
+    Ok(SummaryReport {
+        summary,
+        missing,
+        packages,
+        sanitizer,
+    })
 }
 
-fn print_pretty(summary: &AggregateSummary) {
+/// Read a commit's note directly, bypassing the attribution index
+///
+/// With `reconcile`, drifted per-file summaries are recomputed and the note rewritten.
+fn read_commit(
+    repo: &Repository,
+    notes_store: &NotesStore,
+    oid: git2::Oid,
+    reconcile: bool,
+    summary: &mut AggregateSummary,
+) -> Result<Option<IndexedCommit>> {
+    let Ok(Some(mut attr)) = notes_store.fetch_attribution(oid) else {
+        return Ok(None);
+    };
+    if reconcile && !integrity::reconcile_summaries(&mut attr).is_empty() {
+        notes_store.store_attribution(oid, &attr)?;
+        summary.reconciled_commits += 1;
+    }
+    let commit = repo.find_commit(oid)?;
+    IndexedCommit::from_attribution(repo, &commit, &attr).map(Some)
+}
+
+/// Apply a `--redact-level` preset to everything the summary prints
+///
+/// Paths, package names, session labels and commit subjects go through the
+/// sanitizer; aggregate-only presets drop the per-file and per-package breakdown.
+fn sanitize_summary(
+    summary: &mut AggregateSummary,
+    packages: &mut Option<Vec<PackageSummary>>,
+    missing: &mut [MissingAttribution],
+    sanitizer: &OutputSanitizer,
+) {
+    if sanitizer.aggregate_only() {
+        summary.hidden_files += summary.file_summaries.len();
+        summary.file_summaries.clear();
+        summary.hidden_secret_findings += summary.secret_findings.len();
+        summary.secret_findings.clear();
+        *packages = None;
+    }
+    for file in &mut summary.file_summaries {
+        file.path = sanitizer.content(&file.path);
+    }
+    for finding in &mut summary.secret_findings {
+        finding.path = sanitizer.content(&finding.path);
+    }
+    for package in packages.iter_mut().flatten() {
+        package.name = sanitizer.content(&package.name);
+        if !package.root.is_empty() {
+            package.root = sanitizer.content(&package.root);
+        }
+    }
+    for label in &mut summary.session_labels {
+        *label = sanitizer.content(label);
+    }
+    for entry in missing {
+        entry.subject = sanitizer.content(&entry.subject);
+    }
+}
+
+/// Share of `total` as a percentage (0 when `total` is 0)
+fn percent_of(part: usize, total: usize) -> f64 {
+    if total > 0 {
+        (part as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+fn print_pretty(summary: &AggregateSummary, locale: Locale) {
+    let added = |n: usize| format!("+{}", locale.number(n));
     println!();
     println!("{}", "═".repeat(60).dimmed());
-    println!("{}", "  AI Attribution Summary".bold());
+    println!("  {}", locale.text(Msg::SummaryTitle).bold());
     println!("{}", "═".repeat(60).dimmed());
     println!();
 
     println!(
-        "Commits analyzed: {} ({} with AI attribution)",
-        summary.commits_analyzed.to_string().cyan(),
-        summary.commits_with_ai.to_string().green()
+        "{}",
+        locale.fill(
+            Msg::CommitsAnalyzed,
+            &[
+                &locale.number(summary.commits_analyzed).cyan(),
+                &locale.number(summary.commits_with_ai).green()
+            ]
+        )
     );
+    if summary.commits_from_trailers > 0 {
+        println!(
+            "  {}",
+            locale
+                .fill(
+                    Msg::FromTrailers,
+                    &[&locale.number(summary.commits_from_trailers)]
+                )
+                .dimmed()
+        );
+    }
     println!();
 
     if summary.commits_with_ai == 0 {
-        println!("No AI attribution data found in the specified commit range.");
+        println!("{}", locale.text(Msg::NoAiData));
         return;
     }
 
     let total_additions = summary.total_additions();
 
-    println!("{}", "Lines Added:".bold());
+    println!("{}", locale.text(Msg::LinesAdded).bold());
     println!(
-        "  {} AI-generated ({:.1}%)",
-        format!("+{}", summary.total_ai_lines).green(),
-        if total_additions > 0 {
-            (summary.total_ai_lines as f64 / total_additions as f64) * 100.0
-        } else {
-            0.0
-        }
+        "  {} {} ({})",
+        added(summary.total_ai_lines).green(),
+        locale.text(Msg::AiGenerated),
+        locale.percent(percent_of(summary.total_ai_lines, total_additions), 1)
     );
     println!(
-        "  {} AI-modified by human ({:.1}%)",
-        format!("+{}", summary.total_ai_modified_lines).yellow(),
-        if total_additions > 0 {
-            (summary.total_ai_modified_lines as f64 / total_additions as f64) * 100.0
-        } else {
-            0.0
-        }
+        "  {} {} ({})",
+        added(summary.total_ai_modified_lines).yellow(),
+        locale.text(Msg::AiModifiedByHuman),
+        locale.percent(
+            percent_of(summary.total_ai_modified_lines, total_additions),
+            1
+        )
     );
     println!(
-        "  {} Human-written ({:.1}%)",
-        format!("+{}", summary.total_human_lines).blue(),
-        if total_additions > 0 {
-            (summary.total_human_lines as f64 / total_additions as f64) * 100.0
-        } else {
-            0.0
-        }
+        "  {} {} ({})",
+        added(summary.total_human_lines).blue(),
+        locale.text(Msg::HumanWritten),
+        locale.percent(percent_of(summary.total_human_lines, total_additions), 1)
     );
     println!(
-        "  {} Total additions",
-        format!("+{}", total_additions).bold()
+        "  {} {}",
+        added(total_additions).bold(),
+        locale.text(Msg::TotalAdditions)
     );
+    if !summary.generated_files.is_empty() {
+        println!(
+            "  {}",
+            locale
+                .fill(
+                    Msg::GeneratedExcluded,
+                    &[
+                        &locale.number(summary.generated_lines),
+                        &locale.number(summary.generated_files.len())
+                    ]
+                )
+                .dimmed()
+        );
+    }
     println!();
 
     println!(
-        "{}: {:.1}% of additions are AI-generated",
-        "AI involvement".bold(),
-        summary.ai_percentage()
+        "{}: {}",
+        locale.text(Msg::AiInvolvement).bold(),
+        locale.fill(
+            Msg::AdditionsAreAi,
+            &[&locale.percent(summary.ai_percentage(), 1)]
+        )
     );
     println!();
 
-    println!("{}", "Files Changed:".bold());
+    if summary.secret_finding_count() > 0 {
+        println!(
+            "{}",
+            format!(
+                "🚨 Possible secrets in AI-written code: {}",
+                summary.secret_finding_count()
+            )
+            .red()
+            .bold()
+        );
+        for finding in &summary.secret_findings {
+            println!(
+                "  {} {}:{} ({})",
+                finding.short_id().dimmed(),
+                finding.path,
+                finding.line_number,
+                finding.pattern_name
+            );
+        }
+        println!();
+    }
+
+    println!("{}:", locale.text(Msg::FilesChanged).bold());
+    if summary.hidden_files > 0 {
+        println!(
+            "  {}",
+            format!("{} file(s) hidden by --redact-level", summary.hidden_files).dimmed()
+        );
+    }
     for file in &summary.file_summaries {
-        let status = if file.is_new_file { " (new)" } else { "" };
-        let ai_pct = file.ai_percent();
+        if let Some(ai_generated) = file.binary_ai_generated {
+            println!("  {} (binary, {})", file.path, binary_label(ai_generated));
+            continue;
+        }
+        let status = if file.is_new_file {
+            format!(" ({})", locale.text(Msg::NewFile))
+        } else {
+            String::new()
+        };
         println!(
-            "  {} +{} ({:.0}% AI){}",
+            "  {} {} ({} {}){}",
             file.path,
-            file.additions(),
-            ai_pct,
+            added(file.additions()),
+            locale.percent(file.ai_percent(), 0),
+            locale.text(Msg::Ai),
             status
         );
     }
     println!();
 
     if !summary.models_used.is_empty() {
-        println!("{}", "Models used:".bold());
+        println!("{}", locale.text(Msg::ModelsUsed).bold());
         for model in &summary.models_used {
             println!("  - {}", model.cyan());
         }
     }
 
+    if !summary.session_labels.is_empty() {
+        println!("{}:", locale.text(Msg::Sessions).bold());
+        for label in &summary.session_labels {
+            println!("  - {}", label.cyan());
+        }
+    }
+
     println!();
     println!("{}", "═".repeat(60).dimmed());
 }
 
-fn print_json(summary: &AggregateSummary) {
+fn print_packages_pretty(packages: &[PackageSummary], summary: &AggregateSummary) {
+    if summary.commits_with_ai == 0 {
+        return;
+    }
+    println!();
+    println!("{}", "By Package:".bold());
+    println!(
+        "  {}",
+        format!(
+            "{:<32} {:>6} {:>9} {:>9} {:>7}",
+            "PACKAGE", "FILES", "+ADDED", "+AI", "AI %"
+        )
+        .dimmed()
+    );
+    for package in packages {
+        println!(
+            "  {} {:>6} {:>9} {} {:>6.1}%",
+            truncate_or_pad(&package.name, 32),
+            package.files,
+            package.additions(),
+            format!("{:>9}", package.ai_additions()).green(),
+            package.ai_percent()
+        );
+    }
+    println!(
+        "  {} {:>6} {:>9} {} {:>6.1}%",
+        format!("{:<32}", "Total").bold(),
+        summary.file_summaries.len(),
+        summary.total_additions(),
+        format!("{:>9}", summary.ai_additions()).green(),
+        summary.ai_percentage()
+    );
+    println!();
+}
+
+fn print_packages_markdown(packages: &[PackageSummary], summary: &AggregateSummary) {
+    if packages.is_empty() {
+        return;
+    }
+    println!();
+    println!("### By Package");
+    println!();
+    println!("| Package | Files | +Added | AI | Human | AI % |");
+    println!("|---------|------:|-------:|---:|------:|-----:|");
+    for package in packages {
+        let label = if package.root.is_empty() {
+            package.name.clone()
+        } else {
+            format!("`{}` ({})", package.name, package.root)
+        };
+        println!(
+            "| {} | {} | +{} | {} | {} | {:.0}% |",
+            label,
+            package.files,
+            package.additions(),
+            package.ai_additions(),
+            package.human_lines,
+            package.ai_percent()
+        );
+    }
+    println!(
+        "| **Total** | **{}** | **+{}** | **{}** | **{}** | **{:.0}%** |",
+        summary.file_summaries.len(),
+        summary.total_additions(),
+        summary.ai_additions(),
+        summary.total_human_lines,
+        summary.ai_percentage()
+    );
+}
+
+fn summary_json(
+    summary: &AggregateSummary,
+    missing: Option<&[MissingAttribution]>,
+    packages: Option<&[PackageSummary]>,
+    redact_level: Option<&str>,
+) -> serde_json::Value {
     let files_json: Vec<_> = summary
         .file_summaries
         .iter()
@@ -325,15 +943,17 @@ fn print_json(summary: &AggregateSummary) {
                 "human_lines": f.human_lines,
                 "ai_percent": f.ai_percent(),
                 "is_new_file": f.is_new_file,
+                "binary_ai_generated": f.binary_ai_generated,
             })
         })
         .collect();
 
-    let output = serde_json::json!({
+    let mut output = serde_json::json!({
         "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
         "schema": "whogitit.summary.v1",
         "commits_analyzed": summary.commits_analyzed,
         "commits_with_ai": summary.commits_with_ai,
+        "commits_from_trailers": summary.commits_from_trailers,
         "additions": {
             "total": summary.total_additions(),
             "ai": summary.total_ai_lines,
@@ -341,33 +961,72 @@ fn print_json(summary: &AggregateSummary) {
             "human": summary.total_human_lines,
         },
         "ai_percentage": summary.ai_percentage(),
+        "files_changed": summary.files_changed(),
+        "generated_excluded": {
+            "files": summary.generated_files.len(),
+            "lines": summary.generated_lines,
+        },
+        "line_count_discrepancies": summary.line_count_discrepancies,
+        "secret_finding_count": summary.secret_finding_count(),
+        "secret_findings": summary
+            .secret_findings
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "commit": f.commit,
+                    "path": f.path,
+                    "line": f.line_number,
+                    "pattern": f.pattern_name,
+                })
+            })
+            .collect::<Vec<_>>(),
         "files": files_json,
         "models": summary.models_used,
+        "session_labels": summary.session_labels,
     });
-
-    println!(
-        "{}",
-        serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
-    );
+    if let Some(name) = redact_level {
+        output["redact_level"] = name.into();
+    }
+    if let Some(packages) = packages {
+        output["packages"] = packages
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "name": p.name,
+                    "root": p.root,
+                    "files": p.files,
+                    "additions": p.additions(),
+                    "ai_additions": p.ai_additions(),
+                    "ai_lines": p.ai_lines,
+                    "ai_modified_lines": p.ai_modified_lines,
+                    "human_lines": p.human_lines,
+                    "ai_percent": p.ai_percent(),
+                })
+            })
+            .collect();
+    }
+    if let Some(missing) = missing {
+        output["missing_attribution"] = missing
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "commit": m.commit,
+                    "subject": m.subject,
+                    "source_files": m.source_files,
+                })
+            })
+            .collect();
+    }
+    output
 }
 
-fn print_markdown(summary: &AggregateSummary) {
+fn print_markdown(summary: &AggregateSummary, locale: Locale) {
+    let t = |msg| locale.text(msg);
+    let count = |n: usize| locale.number(n);
     let total_additions = summary.total_additions();
-    let ai_pct = if total_additions > 0 {
-        (summary.total_ai_lines as f64 / total_additions as f64) * 100.0
-    } else {
-        0.0
-    };
-    let mod_pct = if total_additions > 0 {
-        (summary.total_ai_modified_lines as f64 / total_additions as f64) * 100.0
-    } else {
-        0.0
-    };
-    let human_pct = if total_additions > 0 {
-        (summary.total_human_lines as f64 / total_additions as f64) * 100.0
-    } else {
-        0.0
-    };
+    let ai_pct = percent_of(summary.total_ai_lines, total_additions);
+    let mod_pct = percent_of(summary.total_ai_modified_lines, total_additions);
+    let human_pct = percent_of(summary.total_human_lines, total_additions);
 
     let emoji = if summary.ai_percentage() >= 80.0 {
         "🤖🤖🤖"
@@ -379,55 +1038,118 @@ fn print_markdown(summary: &AggregateSummary) {
         "👤"
     };
 
-    println!("## {} AI Attribution Summary", emoji);
+    println!("## {} {}", emoji, t(Msg::SummaryTitle));
     println!();
     println!(
-        "This PR adds **+{}** lines with AI attribution across **{}** files.",
-        total_additions,
-        summary.file_summaries.len()
+        "{}",
+        locale.fill(
+            Msg::PrAdds,
+            &[&count(total_additions), &count(summary.files_changed())]
+        )
     );
+    if summary.commits_from_trailers > 0 {
+        println!();
+        println!(
+            "{}",
+            locale.fill(Msg::TrailerOnly, &[&count(summary.commits_from_trailers)])
+        );
+    }
+    if !summary.generated_files.is_empty() {
+        println!();
+        println!(
+            "_{}_",
+            locale.fill(
+                Msg::GeneratedExcluded,
+                &[
+                    &count(summary.generated_lines),
+                    &count(summary.generated_files.len())
+                ]
+            )
+        );
+    }
     println!();
-    println!("### Additions Breakdown");
+    if summary.secret_finding_count() > 0 {
+        print_secrets_markdown(summary);
+    }
+    println!("### {}", t(Msg::AdditionsBreakdown));
     println!();
-    println!("| Metric | Lines | % of Additions |");
+    println!(
+        "| {} | {} | {} |",
+        t(Msg::Metric),
+        t(Msg::Lines),
+        t(Msg::PercentOfAdditions)
+    );
     println!("|--------|------:|--------------:|");
     println!(
-        "| 🟢 AI-generated | +{} | {:.1}% |",
-        summary.total_ai_lines, ai_pct
+        "| 🟢 {} | +{} | {} |",
+        t(Msg::AiGenerated),
+        count(summary.total_ai_lines),
+        locale.percent(ai_pct, 1)
     );
     println!(
-        "| 🟡 AI-modified by human | +{} | {:.1}% |",
-        summary.total_ai_modified_lines, mod_pct
+        "| 🟡 {} | +{} | {} |",
+        t(Msg::AiModifiedByHuman),
+        count(summary.total_ai_modified_lines),
+        locale.percent(mod_pct, 1)
     );
     println!(
-        "| 🔵 Human-written | +{} | {:.1}% |",
-        summary.total_human_lines, human_pct
+        "| 🔵 {} | +{} | {} |",
+        t(Msg::HumanWritten),
+        count(summary.total_human_lines),
+        locale.percent(human_pct, 1)
     );
     println!(
-        "| **Total additions** | **+{}** | **100%** |",
-        total_additions
+        "| **{}** | **+{}** | **{}** |",
+        t(Msg::TotalAdditions),
+        count(total_additions),
+        locale.percent(100.0, 0)
     );
     println!();
     println!(
-        "**AI involvement: {:.1}%** of additions are AI-generated",
-        summary.ai_percentage()
+        "{}",
+        locale.fill(
+            Msg::MarkdownAiInvolvement,
+            &[&locale.percent(summary.ai_percentage(), 1)]
+        )
     );
     println!();
 
     if !summary.file_summaries.is_empty() {
-        println!("### Files Changed");
+        println!("### {}", t(Msg::FilesChanged));
         println!();
-        println!("| File | +Added | AI | Human | AI % | Status |");
+        println!(
+            "| {} | {} | {} | {} | {} | {} |",
+            t(Msg::File),
+            t(Msg::Added),
+            t(Msg::Ai),
+            t(Msg::Human),
+            t(Msg::AiPercent),
+            t(Msg::Status)
+        );
         println!("|------|-------:|---:|------:|-----:|--------|");
         for file in &summary.file_summaries {
-            let status = if file.is_new_file { "New" } else { "Modified" };
+            if let Some(ai_generated) = file.binary_ai_generated {
+                println!(
+                    "| `{}` | {} | {} | | | {} |",
+                    file.path,
+                    t(Msg::BinaryCell),
+                    binary_label(ai_generated),
+                    t(Msg::Binary)
+                );
+                continue;
+            }
+            let status = if file.is_new_file {
+                t(Msg::New)
+            } else {
+                t(Msg::Modified)
+            };
             println!(
-                "| `{}` | +{} | {} | {} | {:.0}% | {} |",
+                "| `{}` | +{} | {} | {} | {} | {} |",
                 file.path,
-                file.additions(),
-                file.ai_additions(),
-                file.human_lines,
-                file.ai_percent(),
+                count(file.additions()),
+                count(file.ai_additions()),
+                count(file.human_lines),
+                locale.percent(file.ai_percent(), 0),
                 status
             );
         }
@@ -435,18 +1157,159 @@ fn print_markdown(summary: &AggregateSummary) {
     }
 
     if !summary.models_used.is_empty() {
-        println!("### Models Used");
+        println!("### {}", t(Msg::ModelsUsedHeading));
         println!();
         for model in &summary.models_used {
             println!("- {}", model);
         }
     }
+
+    if !summary.session_labels.is_empty() {
+        println!();
+        println!("### {}", t(Msg::Sessions));
+        println!();
+        for label in &summary.session_labels {
+            println!("- {}", label);
+        }
+    }
+}
+
+/// Markdown section listing possible secrets in AI-written code
+fn print_secrets_markdown(summary: &AggregateSummary) {
+    println!("### 🚨 Possible Secrets in AI-Written Code");
+    println!();
+    println!(
+        "**{}** line(s) written by AI matched a sensitive pattern. Review them before merging.",
+        summary.secret_finding_count()
+    );
+    println!();
+    if !summary.secret_findings.is_empty() {
+        println!("| Commit | File | Line | Pattern |");
+        println!("|--------|------|-----:|---------|");
+        for finding in &summary.secret_findings {
+            println!(
+                "| {} | `{}` | {} | {} |",
+                finding.short_id(),
+                finding.path,
+                finding.line_number,
+                finding.pattern_name
+            );
+        }
+        println!();
+    }
+}
+
+/// Label for whole-file (binary) attribution
+fn binary_label(ai_generated: bool) -> &'static str {
+    if ai_generated {
+        "AI-generated"
+    } else {
+        "changed after AI"
+    }
+}
+
+fn print_missing_markdown(missing: &[MissingAttribution]) {
+    println!();
+    println!("### ⚠️ Commits Missing Attribution");
+    println!();
+    println!("| Commit | Subject | Source files |");
+    println!("|--------|---------|-------------:|");
+    for entry in missing {
+        println!(
+            "| `{}` | {} | {} |",
+            entry.short_id(),
+            entry.subject,
+            entry.source_files
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn file(path: &str, ai: usize, human: usize) -> FileSummary {
+        FileSummary {
+            path: path.to_string(),
+            ai_lines: ai,
+            ai_modified_lines: 0,
+            human_lines: human,
+            original_lines: 0,
+            is_new_file: false,
+            binary_ai_generated: None,
+        }
+    }
+
+    #[test]
+    fn test_sanitize_summary_levels() {
+        use crate::privacy::RedactLevel;
+
+        let sanitizer = |name| OutputSanitizer::new(name, RedactLevel::builtin(name).unwrap());
+        let build = || AggregateSummary {
+            file_summaries: vec![file("src/auth.rs", 3, 1), file("src/db.rs", 2, 0)],
+            session_labels: vec!["incident-42".to_string()],
+            secret_findings: vec![SecretSummary {
+                commit: "abc1234def".to_string(),
+                path: "src/auth.rs".to_string(),
+                line_number: 3,
+                pattern_name: "AWS_KEY".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let mut summary = build();
+        let mut packages = None;
+        sanitize_summary(&mut summary, &mut packages, &mut [], &sanitizer("internal"));
+        assert_eq!(summary.files_changed(), 2);
+        assert_ne!(summary.file_summaries[0].path, "src/auth.rs");
+        assert_ne!(summary.session_labels[0], "incident-42");
+        assert_ne!(summary.secret_findings[0].path, "src/auth.rs");
+
+        let mut summary = build();
+        sanitize_summary(&mut summary, &mut packages, &mut [], &sanitizer("external"));
+        assert!(summary.file_summaries.is_empty());
+        assert_eq!(summary.files_changed(), 2);
+        assert!(summary.secret_findings.is_empty());
+        assert_eq!(summary.secret_finding_count(), 1);
+    }
+
+    #[test]
+    fn test_rollup_packages() {
+        use crate::core::packages::Package;
+
+        let map = PackageMap::from_packages(vec![
+            Package {
+                name: "core".to_string(),
+                root: "crates/core".to_string(),
+            },
+            Package {
+                name: "web".to_string(),
+                root: "web".to_string(),
+            },
+        ]);
+        let files = vec![
+            file("crates/core/src/lib.rs", 2, 8),
+            file("crates/core/src/util.rs", 3, 2),
+            file("web/index.ts", 9, 1),
+            file("README.md", 0, 4),
+        ];
+
+        let rollup = rollup_packages(&files, &map);
+        let rows: Vec<(&str, usize, usize, usize)> = rollup
+            .iter()
+            .map(|p| (p.name.as_str(), p.files, p.ai_additions(), p.additions()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("web", 1, 9, 10),
+                ("core", 2, 5, 15),
+                (ROOT_PACKAGE, 1, 0, 4),
+            ]
+        );
+        assert_eq!(rollup[2].root, "");
+    }
+
     // FileSummary tests
 
     #[test]
@@ -458,6 +1321,7 @@ mod tests {
             human_lines: 3,
             original_lines: 100,
             is_new_file: false,
+            binary_ai_generated: None,
         };
         assert_eq!(summary.additions(), 18); // 10 + 5 + 3
     }
@@ -471,6 +1335,7 @@ mod tests {
             human_lines: 3,
             original_lines: 100,
             is_new_file: false,
+            binary_ai_generated: None,
         };
         assert_eq!(summary.ai_additions(), 15); // 10 + 5
     }
@@ -484,6 +1349,7 @@ mod tests {
             human_lines: 0,
             original_lines: 100,
             is_new_file: false,
+            binary_ai_generated: None,
         };
         // 20 AI additions / 20 total additions = 100%
         assert!((summary.ai_percent() - 100.0).abs() < 0.001);
@@ -498,6 +1364,7 @@ mod tests {
             human_lines: 10,
             original_lines: 100,
             is_new_file: false,
+            binary_ai_generated: None,
         };
         // 10 AI additions / 20 total additions = 50%
         assert!((summary.ai_percent() - 50.0).abs() < 0.001);
@@ -512,6 +1379,7 @@ mod tests {
             human_lines: 0,
             original_lines: 100,
             is_new_file: false,
+            binary_ai_generated: None,
         };
         // Should return 0, not divide by zero
         assert!((summary.ai_percent() - 0.0).abs() < 0.001);
@@ -526,6 +1394,7 @@ mod tests {
             human_lines: 0,
             original_lines: 0,
             is_new_file: true,
+            binary_ai_generated: None,
         };
         assert!(new_file.is_new_file);
         assert_eq!(new_file.additions(), 100);
@@ -548,12 +1417,21 @@ mod tests {
         let summary = AggregateSummary {
             commits_analyzed: 2,
             commits_with_ai: 1,
+            commits_from_trailers: 0,
             total_ai_lines: 50,
             total_ai_modified_lines: 25,
             total_human_lines: 25,
             total_original_lines: 200,
             file_summaries: vec![],
             models_used: vec![],
+            session_labels: vec![],
+            hidden_files: 0,
+            line_count_discrepancies: 0,
+            reconciled_commits: 0,
+            secret_findings: vec![],
+            hidden_secret_findings: 0,
+            generated_files: vec![],
+            generated_lines: 0,
         };
         assert_eq!(summary.total_additions(), 100); // 50 + 25 + 25
     }
@@ -563,12 +1441,21 @@ mod tests {
         let summary = AggregateSummary {
             commits_analyzed: 2,
             commits_with_ai: 1,
+            commits_from_trailers: 0,
             total_ai_lines: 50,
             total_ai_modified_lines: 25,
             total_human_lines: 25,
             total_original_lines: 200,
             file_summaries: vec![],
             models_used: vec![],
+            session_labels: vec![],
+            hidden_files: 0,
+            line_count_discrepancies: 0,
+            reconciled_commits: 0,
+            secret_findings: vec![],
+            hidden_secret_findings: 0,
+            generated_files: vec![],
+            generated_lines: 0,
         };
         assert_eq!(summary.ai_additions(), 75); // 50 + 25
     }
@@ -578,12 +1465,21 @@ mod tests {
         let summary = AggregateSummary {
             commits_analyzed: 2,
             commits_with_ai: 1,
+            commits_from_trailers: 0,
             total_ai_lines: 50,
             total_ai_modified_lines: 25,
             total_human_lines: 25,
             total_original_lines: 200,
             file_summaries: vec![],
             models_used: vec![],
+            session_labels: vec![],
+            hidden_files: 0,
+            line_count_discrepancies: 0,
+            reconciled_commits: 0,
+            secret_findings: vec![],
+            hidden_secret_findings: 0,
+            generated_files: vec![],
+            generated_lines: 0,
         };
         // 75 AI / 100 total = 75%
         assert!((summary.ai_percentage() - 75.0).abs() < 0.001);
@@ -594,12 +1490,21 @@ mod tests {
         let summary = AggregateSummary {
             commits_analyzed: 2,
             commits_with_ai: 0,
+            commits_from_trailers: 0,
             total_ai_lines: 0,
             total_ai_modified_lines: 0,
             total_human_lines: 0,
             total_original_lines: 0,
             file_summaries: vec![],
             models_used: vec![],
+            session_labels: vec![],
+            hidden_files: 0,
+            line_count_discrepancies: 0,
+            reconciled_commits: 0,
+            secret_findings: vec![],
+            hidden_secret_findings: 0,
+            generated_files: vec![],
+            generated_lines: 0,
         };
         assert!((summary.ai_percentage() - 0.0).abs() < 0.001);
     }
@@ -609,12 +1514,21 @@ mod tests {
         let summary = AggregateSummary {
             commits_analyzed: 1,
             commits_with_ai: 1,
+            commits_from_trailers: 0,
             total_ai_lines: 100,
             total_ai_modified_lines: 0,
             total_human_lines: 0,
             total_original_lines: 0,
             file_summaries: vec![],
             models_used: vec!["claude-opus-4-5-20251101".to_string()],
+            session_labels: vec![],
+            hidden_files: 0,
+            line_count_discrepancies: 0,
+            reconciled_commits: 0,
+            secret_findings: vec![],
+            hidden_secret_findings: 0,
+            generated_files: vec![],
+            generated_lines: 0,
         };
         assert!((summary.ai_percentage() - 100.0).abs() < 0.001);
     }
@@ -624,6 +1538,7 @@ mod tests {
         let summary = AggregateSummary {
             commits_analyzed: 3,
             commits_with_ai: 2,
+            commits_from_trailers: 0,
             total_ai_lines: 80,
             total_ai_modified_lines: 20,
             total_human_lines: 50,
@@ -636,6 +1551,7 @@ mod tests {
                     human_lines: 20,
                     original_lines: 300,
                     is_new_file: false,
+                    binary_ai_generated: None,
                 },
                 FileSummary {
                     path: "src/lib.rs".to_string(),
@@ -644,9 +1560,18 @@ mod tests {
                     human_lines: 30,
                     original_lines: 200,
                     is_new_file: false,
+                    binary_ai_generated: None,
                 },
             ],
             models_used: vec!["claude-opus-4-5-20251101".to_string()],
+            session_labels: vec![],
+            hidden_files: 0,
+            line_count_discrepancies: 0,
+            reconciled_commits: 0,
+            secret_findings: vec![],
+            hidden_secret_findings: 0,
+            generated_files: vec![],
+            generated_lines: 0,
         };
 
         assert_eq!(summary.file_summaries.len(), 2);
@@ -670,4 +1595,57 @@ mod tests {
         let default = SummaryFormat::default();
         assert!(matches!(default, SummaryFormat::Pretty));
     }
+
+    #[test]
+    fn test_missing_attribution_error_lists_commits() {
+        let missing = vec![MissingAttribution {
+            commit: "abc1234def5678".to_string(),
+            subject: "Add parser".to_string(),
+            source_files: 2,
+        }];
+        let message = missing_attribution_error(&missing).to_string();
+        assert!(message.starts_with("1 commit(s) change source files"));
+        assert!(message.contains("abc1234 Add parser (2 source file(s))"));
+        assert!(message.contains("whogitit init"));
+    }
+
+    #[test]
+    fn test_add_trailers_counts_lines_and_models() {
+        let mut summary = AggregateSummary::default();
+        summary.add_trailers(&TrailerParser::parse(
+            "Add feature\n\nAI-Session: abc\nAI-Model: claude-opus-4-5-20251101\nAI-Lines: 40\nAI-Modified: 5\nHuman-Lines: 5",
+        ));
+
+        assert_eq!(summary.commits_with_ai, 1);
+        assert_eq!(summary.commits_from_trailers, 1);
+        assert_eq!(summary.total_additions(), 50);
+        assert_eq!(summary.ai_additions(), 45);
+        assert_eq!(summary.models_used, vec!["claude-opus-4-5-20251101"]);
+        assert!(summary.file_summaries.is_empty());
+    }
+
+    #[test]
+    fn test_add_generated_keeps_lines_out_of_totals() {
+        let lockfile = IndexedFile {
+            path: "Cargo.lock".to_string(),
+            summary: crate::capture::snapshot::AttributionSummary {
+                total_lines: 120,
+                ai_lines: 100,
+                ai_modified_lines: 0,
+                human_lines: 20,
+                original_lines: 0,
+                unknown_lines: 0,
+            },
+            binary_ai_generated: None,
+            secret_findings: vec![],
+        };
+        let mut summary = AggregateSummary::default();
+        summary.add_generated(&lockfile);
+        summary.add_generated(&lockfile);
+
+        assert_eq!(summary.generated_files, vec!["Cargo.lock"]);
+        assert_eq!(summary.generated_lines, 240);
+        assert_eq!(summary.total_additions(), 0);
+        assert_eq!(summary.ai_percentage(), 0.0);
+    }
 }
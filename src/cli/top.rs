@@ -0,0 +1,354 @@
+//! Top command - leaderboard of the most AI-dependent files
+//!
+//! Ranks the files in a revision's tree, and the directories holding them, by
+//! AI line count or AI share, using the same per-file blame as
+//! [`manifest`](super::manifest). With `--churn`, entries are ranked by AI lines
+//! times the number of commits that changed them in the `--days` before the
+//! revision, surfacing AI-heavy code that keeps being edited.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use git2::{Repository, Sort};
+use serde::Serialize;
+
+use crate::cli::manifest::{self, share, FileProvenance};
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+use crate::cli::stats::first_parent_diff;
+use crate::utils::repo_path;
+
+const TOP_MACHINE_SCHEMA: &str = "whogitit.top.v1";
+
+/// Top command arguments
+#[derive(Debug, Args)]
+pub struct TopArgs {
+    /// Revision whose tree is ranked
+    #[arg(long, default_value = "HEAD")]
+    pub rev: String,
+
+    /// Rank by AI line count or AI share
+    #[arg(long, value_enum, default_value_t = TopOrder::Lines)]
+    pub by: TopOrder,
+
+    /// Rank hotspots: AI lines times commits that changed the file in the last --days
+    #[arg(long)]
+    pub churn: bool,
+
+    /// Churn window in days before the revision's commit
+    #[arg(long, default_value_t = 90, value_name = "DAYS")]
+    pub days: u32,
+
+    /// Number of files and directories to list
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+/// Leaderboard ordering without `--churn`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TopOrder {
+    /// AI and AI-modified lines
+    Lines,
+    /// AI and AI-modified lines over all lines
+    Share,
+}
+
+/// A ranked file or directory
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TopEntry {
+    pub rank: usize,
+    pub path: String,
+    /// Files counted (directories only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<usize>,
+    pub total_lines: usize,
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    /// AI and AI-modified lines over all lines (0.0-1.0)
+    pub ai_share: f64,
+    /// Commits in the churn window that changed the entry (with `--churn`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<usize>,
+    /// AI and AI-modified lines times `changes` (with `--churn`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotspot_score: Option<usize>,
+}
+
+impl TopEntry {
+    fn ai_total(&self) -> usize {
+        self.ai_lines + self.ai_modified_lines
+    }
+}
+
+/// Commits that changed each path and each directory
+#[derive(Debug, Default)]
+struct Churn {
+    files: HashMap<String, usize>,
+    dirs: HashMap<String, usize>,
+}
+
+/// Directory of a tree path (`.` for the root)
+fn parent_dir(path: &str) -> String {
+    match Path::new(path).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy().to_string(),
+        _ => ".".to_string(),
+    }
+}
+
+/// Count commits changing each file in the `days` before `head`
+///
+/// Merge commits are skipped so their changes aren't counted twice.
+fn recent_changes(repo: &Repository, head: &git2::Commit, days: u32) -> Result<Churn> {
+    let cutoff = head.time().seconds() - i64::from(days) * 86_400;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    revwalk.push(head.id())?;
+
+    let mut churn = Churn::default();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < cutoff {
+            break;
+        }
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let diff = first_parent_diff(repo, &commit)?;
+        let mut dirs = HashSet::new();
+        for delta in diff.deltas() {
+            let Some(path) = delta.new_file().path() else {
+                continue;
+            };
+            let path = repo_path::from_path(path);
+            dirs.insert(parent_dir(&path));
+            *churn.files.entry(path).or_default() += 1;
+        }
+        for dir in dirs {
+            *churn.dirs.entry(dir).or_default() += 1;
+        }
+    }
+    Ok(churn)
+}
+
+/// File and directory entries for files with AI lines
+fn tally(files: &[FileProvenance], churn: Option<&Churn>) -> (Vec<TopEntry>, Vec<TopEntry>) {
+    let mut file_entries = Vec::new();
+    let mut dirs: HashMap<String, TopEntry> = HashMap::new();
+    for file in files {
+        if file.binary || file.ai_lines + file.ai_modified_lines == 0 {
+            continue;
+        }
+        let entry = TopEntry {
+            path: file.path.clone(),
+            total_lines: file.total_lines,
+            ai_lines: file.ai_lines,
+            ai_modified_lines: file.ai_modified_lines,
+            ai_share: file.ai_share,
+            changes: churn.map(|c| c.files.get(&file.path).copied().unwrap_or(0)),
+            ..Default::default()
+        };
+        let dir_path = parent_dir(&file.path);
+        let dir = dirs.entry(dir_path.clone()).or_insert_with(|| TopEntry {
+            files: Some(0),
+            changes: churn.map(|c| c.dirs.get(&dir_path).copied().unwrap_or(0)),
+            path: dir_path,
+            ..Default::default()
+        });
+        *dir.files.get_or_insert(0) += 1;
+        dir.total_lines += entry.total_lines;
+        dir.ai_lines += entry.ai_lines;
+        dir.ai_modified_lines += entry.ai_modified_lines;
+        file_entries.push(entry);
+    }
+
+    let mut dir_entries: Vec<TopEntry> = dirs.into_values().collect();
+    for dir in &mut dir_entries {
+        dir.ai_share = share(dir.ai_total(), dir.total_lines);
+    }
+    (file_entries, dir_entries)
+}
+
+/// Sort highest first, keep `limit` entries and number them
+fn rank(mut entries: Vec<TopEntry>, by: TopOrder, limit: usize) -> Vec<TopEntry> {
+    for entry in &mut entries {
+        entry.hotspot_score = entry.changes.map(|changes| entry.ai_total() * changes);
+    }
+    entries.sort_by(|a, b| {
+        let key = |e: &TopEntry| match (e.hotspot_score, by) {
+            (Some(score), _) => (score, e.ai_total()),
+            (None, TopOrder::Lines) => (e.ai_total(), (e.ai_share * 1000.0) as usize),
+            (None, TopOrder::Share) => ((e.ai_share * 1000.0) as usize, e.ai_total()),
+        };
+        Reverse(key(a))
+            .cmp(&Reverse(key(b)))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    entries.truncate(limit);
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.rank = i + 1;
+    }
+    entries
+}
+
+/// Run the top command
+pub fn run(args: TopArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let manifest = manifest::build_manifest(&repo, &args.rev)?;
+    let churn = if args.churn {
+        let head = repo.find_commit(git2::Oid::from_str(&manifest.commit)?)?;
+        Some(recent_changes(&repo, &head, args.days)?)
+    } else {
+        None
+    };
+
+    let (files, dirs) = tally(&manifest.files, churn.as_ref());
+    let files = rank(files, args.by, args.limit);
+    let dirs = rank(dirs, args.by, args.limit);
+
+    match args.format {
+        OutputFormat::Pretty => print_pretty(&args, &manifest.commit, &files, &dirs),
+        OutputFormat::Json => {
+            let mut output = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": TOP_MACHINE_SCHEMA,
+                "revision": args.rev,
+                "commit": manifest.commit,
+                "order": if args.churn { "churn" } else { match args.by {
+                    TopOrder::Lines => "lines",
+                    TopOrder::Share => "share",
+                } },
+                "files": files,
+                "directories": dirs,
+            });
+            if args.churn {
+                output["churn_days"] = args.days.into();
+            }
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+    Ok(())
+}
+
+fn print_pretty(args: &TopArgs, commit: &str, files: &[TopEntry], dirs: &[TopEntry]) {
+    if files.is_empty() {
+        println!("No AI-attributed lines at {}.", &commit[..7]);
+        return;
+    }
+
+    let order = if args.churn {
+        format!("AI lines x changes in the {} days before", args.days)
+    } else {
+        match args.by {
+            TopOrder::Lines => "by AI lines at".to_string(),
+            TopOrder::Share => "by AI share at".to_string(),
+        }
+    };
+    for (title, entries) in [("Top AI files", files), ("Top AI directories", dirs)] {
+        println!(
+            "{} {}",
+            title.bold(),
+            format!("({} {})", order, &commit[..7]).dimmed()
+        );
+        let width = entries.iter().map(|e| e.path.len()).max().unwrap_or(0);
+        for entry in entries {
+            let mut line = format!(
+                "  {:>2}. {:<width$}  {:>6} AI lines  {:>5.1}%",
+                entry.rank,
+                entry.path,
+                entry.ai_total(),
+                entry.ai_share * 100.0,
+                width = width
+            );
+            if let (Some(changes), Some(score)) = (entry.changes, entry.hotspot_score) {
+                line.push_str(&format!("  {:>3} changes  score {}", changes, score));
+            }
+            println!("{}", line);
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, ai: usize, total: usize) -> FileProvenance {
+        FileProvenance {
+            path: path.to_string(),
+            total_lines: total,
+            ai_lines: ai,
+            human_lines: total - ai,
+            ai_share: share(ai, total),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rank_by_lines_share_and_churn() {
+        let files = vec![
+            file("src/big.rs", 100, 1000),
+            file("src/gen.rs", 20, 20),
+            file("tests/util.rs", 50, 100),
+            file("README.md", 0, 10),
+        ];
+
+        let (entries, dirs) = tally(&files, None);
+        let paths = |ranked: &[TopEntry]| -> Vec<String> {
+            ranked.iter().map(|e| e.path.clone()).collect()
+        };
+        let by_lines = rank(entries.clone(), TopOrder::Lines, 10);
+        assert_eq!(
+            paths(&by_lines),
+            ["src/big.rs", "tests/util.rs", "src/gen.rs"]
+        );
+        assert_eq!(by_lines[0].rank, 1);
+        let by_share = rank(entries, TopOrder::Share, 2);
+        assert_eq!(paths(&by_share), ["src/gen.rs", "tests/util.rs"]);
+
+        let dirs = rank(dirs, TopOrder::Lines, 10);
+        assert_eq!(paths(&dirs), ["src", "tests"]);
+        assert_eq!(dirs[0].files, Some(2));
+        assert_eq!(dirs[0].ai_lines, 120);
+        assert_eq!(dirs[0].ai_share, 0.118);
+
+        let churn = Churn {
+            files: HashMap::from([
+                ("src/gen.rs".to_string(), 10),
+                ("src/big.rs".to_string(), 1),
+            ]),
+            dirs: HashMap::new(),
+        };
+        let (entries, _) = tally(&files, Some(&churn));
+        let hotspots = rank(entries, TopOrder::Lines, 10);
+        assert_eq!(
+            paths(&hotspots),
+            ["src/gen.rs", "src/big.rs", "tests/util.rs"]
+        );
+        assert_eq!(hotspots[0].hotspot_score, Some(200));
+        assert_eq!(hotspots[2].changes, Some(0));
+    }
+
+    #[test]
+    fn test_recent_changes_counts_commits_in_window() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let head = fixture.repo.find_commit(fixture.head()).unwrap();
+
+        let churn = recent_changes(&fixture.repo, &head, 1).unwrap();
+        assert_eq!(churn.files.get("src/generated.rs"), Some(&1));
+        assert_eq!(churn.files.get("README.md"), Some(&1));
+        assert_eq!(churn.dirs.get("."), Some(&1));
+
+        let churn = recent_changes(&fixture.repo, &head, 0).unwrap();
+        assert_eq!(churn.files.get("src/generated.rs"), Some(&1));
+        assert_eq!(churn.files.get("README.md"), None);
+    }
+}
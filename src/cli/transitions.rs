@@ -0,0 +1,270 @@
+//! Transitions command - how lines change source after they are committed
+//!
+//! Blames every file each non-merge commit in the range changed, in the commit
+//! and its first parent, and feeds the snapshots to
+//! [`crate::core::analytics::transitions`]. The JSON report summarizes the fates
+//! of AI lines; CSV lists one row per line transition for further analysis.
+//! As in `stats`, lines from commits without a note count as human.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::{Delta, DiffFindOptions, Oid, Repository, Sort};
+
+use crate::capture::snapshot::LineSource;
+use crate::cli::export::csv_escape;
+use crate::cli::output::MACHINE_OUTPUT_SCHEMA_VERSION;
+use crate::core::analytics::transitions::{
+    CommitSnapshot, FileSnapshot, TransitionAnalyzer, TransitionEvent, TransitionReport,
+};
+use crate::core::attribution::BlameLineResult;
+use crate::core::AIBlamer;
+use crate::utils::repo_path;
+
+const TRANSITIONS_MACHINE_SCHEMA: &str = "whogitit.transitions.v1";
+
+/// Transitions command arguments
+#[derive(Debug, Args)]
+pub struct TransitionsArgs {
+    /// Base commit (exclusive) - defaults to the first commit
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// Head commit (inclusive)
+    #[arg(long, default_value = "HEAD")]
+    pub head: String,
+
+    /// Output format: json (summary) or csv (one row per line transition)
+    #[arg(long, value_parser = ["json", "csv"], default_value = "json")]
+    pub format: String,
+
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+/// Blame of `path` at `commit`, or nothing for binary and missing files
+///
+/// Lines from commits without an attribution note count as human-written.
+fn blame_lines(blamer: &mut AIBlamer, commit: Oid, path: &str) -> Vec<BlameLineResult> {
+    let mut lines = blamer
+        .blame(path, Some(&commit.to_string()))
+        .map(|b| b.lines)
+        .unwrap_or_default();
+    mark_unattributed_human(blamer, &mut lines);
+    lines
+}
+
+fn mark_unattributed_human(blamer: &mut AIBlamer, lines: &mut [BlameLineResult]) {
+    for line in lines {
+        if line.source == LineSource::Unknown
+            && matches!(blamer.get_commit_attribution(&line.commit_id), Ok(None))
+        {
+            line.source = LineSource::Human;
+        }
+    }
+}
+
+/// Blame snapshots of the files `commit` changed relative to its first parent
+fn snapshot_commit(
+    repo: &Repository,
+    blamer: &mut AIBlamer,
+    commit: &git2::Commit,
+) -> Result<CommitSnapshot> {
+    let parent = commit.parents().next();
+    let parent_tree = parent.as_ref().map(|p| p.tree()).transpose()?;
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    let mut files = Vec::new();
+    for delta in diff.deltas() {
+        let old_path = delta.old_file().path().map(repo_path::from_path);
+        let new_path = delta.new_file().path().map(repo_path::from_path);
+        let (before, after) = match delta.status() {
+            Delta::Added => (None, new_path.as_deref()),
+            Delta::Deleted => (old_path.as_deref(), None),
+            Delta::Modified | Delta::Renamed => (old_path.as_deref(), new_path.as_deref()),
+            _ => continue,
+        };
+        let before = match (&parent, before) {
+            (Some(parent), Some(path)) => blame_lines(blamer, parent.id(), path),
+            _ => Vec::new(),
+        };
+        let after = after
+            .map(|path| blame_lines(blamer, commit.id(), path))
+            .unwrap_or_default();
+        if before.is_empty() && after.is_empty() {
+            continue;
+        }
+        files.push(FileSnapshot {
+            path: new_path.or(old_path).unwrap_or_default(),
+            before,
+            after,
+        });
+    }
+
+    Ok(CommitSnapshot {
+        commit: commit.id().to_string(),
+        time: commit.time().seconds(),
+        files,
+    })
+}
+
+/// Analyze the non-merge commits in `base..head`, oldest first
+pub fn analyze_range(repo: &Repository, base: Option<Oid>, head: Oid) -> Result<TransitionReport> {
+    let mut blamer = AIBlamer::new(repo)?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(head)?;
+    if let Some(base) = base {
+        revwalk.hide(base)?;
+    }
+
+    let mut analyzer = TransitionAnalyzer::new();
+    let mut paths = BTreeSet::new();
+    let mut times: HashMap<String, Option<i64>> = HashMap::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        // A merge repeats its branches' changes, which are analyzed commit by commit
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let snapshot = snapshot_commit(repo, &mut blamer, &commit)?;
+        paths.extend(snapshot.files.iter().map(|f| f.path.clone()));
+        analyzer.add_commit(&snapshot, |id| {
+            *times.entry(id.to_string()).or_insert_with(|| {
+                Oid::from_str(id)
+                    .and_then(|oid| repo.find_commit(oid))
+                    .map(|c| c.time().seconds())
+                    .ok()
+            })
+        });
+    }
+
+    let head_commit = repo.find_commit(head)?;
+    let mut final_blames = Vec::new();
+    for path in &paths {
+        if let Ok(mut blame) = blamer.blame(path, Some(&head.to_string())) {
+            mark_unattributed_human(&mut blamer, &mut blame.lines);
+            final_blames.push(blame);
+        }
+    }
+    Ok(analyzer.finish(
+        &final_blames,
+        &head.to_string(),
+        head_commit.time().seconds(),
+    ))
+}
+
+/// Run the transitions command
+pub fn run(args: TransitionsArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let resolve = |rev: &str| -> Result<Oid> {
+        Ok(repo
+            .revparse_single(rev)
+            .with_context(|| format!("Failed to resolve: {}", rev))?
+            .peel_to_commit()
+            .with_context(|| format!("Not a valid commit: {}", rev))?
+            .id())
+    };
+    let head = resolve(&args.head)?;
+    let base = args.base.as_deref().map(resolve).transpose()?;
+
+    let report = analyze_range(&repo, base, head)?;
+    let content = match args.format.as_str() {
+        "csv" => events_csv(&report.events),
+        _ => {
+            let json = serde_json::json!({
+                "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                "schema": TRANSITIONS_MACHINE_SCHEMA,
+                "base": base.map(|b| b.to_string()),
+                "head": head.to_string(),
+                "report": report,
+            });
+            format!("{}\n", serde_json::to_string_pretty(&json)?)
+        }
+    };
+
+    match &args.output {
+        Some(path) => {
+            let mut file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path))?;
+            file.write_all(content.as_bytes())?;
+            eprintln!(
+                "Wrote {} line transitions from {} commits to {}",
+                report.events.len(),
+                report.commits_analyzed,
+                path
+            );
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// One row per line transition
+fn events_csv(events: &[TransitionEvent]) -> String {
+    let mut csv = String::from("commit,path,from,from_commit,fate,to,age_days\n");
+    for event in events {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            event.commit,
+            csv_escape(&event.path),
+            event.from.as_str(),
+            event.from_commit,
+            event.fate.as_str(),
+            event.to.map(|k| k.as_str()).unwrap_or(""),
+            event
+                .age_days
+                .map(|d| format!("{:.3}", d))
+                .unwrap_or_default()
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::analytics::transitions::LineKind;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_human_edit_of_ai_line_is_a_transition() {
+        let dir = TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let repo = &fixture.repo;
+
+        // A later human-only commit rewrites one of the AI lines
+        let path = dir.path().join("src/generated.rs");
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace("-> u32", "-> u64")).unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_path(std::path::Path::new("src/generated.rs"))
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Dev", "dev@example.com").unwrap();
+        let parent = repo.find_commit(fixture.head()).unwrap();
+        let head = repo
+            .commit(Some("HEAD"), &sig, &sig, "Tweak", &tree, &[&parent])
+            .unwrap();
+
+        let report = analyze_range(repo, None, head).unwrap();
+        assert_eq!(report.ai.introduced, 3);
+        assert_eq!(report.ai.human_modified, 1);
+        assert_eq!(report.ai.surviving, 2);
+        assert!(report.events.iter().any(|e| e.from == LineKind::Ai
+            && e.to == Some(LineKind::Human)
+            && e.commit == head.to_string()));
+
+        let csv = events_csv(&report.events);
+        assert!(csv.starts_with("commit,path,from,from_commit,fate,to,age_days\n"));
+        assert!(csv.contains(",\"src/generated.rs\",ai,"));
+        assert_eq!(csv.lines().count(), 1 + report.events.len());
+    }
+}
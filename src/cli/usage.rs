@@ -0,0 +1,291 @@
+//! Usage command - opt-in, local-only command usage counts
+//!
+//! `whogitit usage enable` creates `~/.local/share/whogitit/usage.json` (the
+//! platform data directory; `$WHOGITIT_HOME/.local/share` when set). While the
+//! file exists, every user-facing command increments its counter there. Nothing
+//! is sent anywhere: teams collect the exported files themselves and aggregate
+//! them with `whogitit usage report`. Counts carry a random machine ID and no
+//! user, host or repository information.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::environment::home_override;
+use crate::cli::output::{OutputFormat, MACHINE_OUTPUT_SCHEMA_VERSION};
+
+const USAGE_SCHEMA: &str = "whogitit.usage.v1";
+const USAGE_REPORT_SCHEMA: &str = "whogitit.usage-report.v1";
+
+/// Usage command arguments
+#[derive(Debug, Args)]
+pub struct UsageArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub action: UsageAction,
+}
+
+/// Usage subcommands
+#[derive(Debug, Subcommand)]
+pub enum UsageAction {
+    /// Start counting command usage on this machine
+    Enable,
+    /// Stop counting and delete the local counts
+    Disable,
+    /// Show the local counts
+    Show,
+    /// Print the local usage file (to collect for `usage report`)
+    Export,
+    /// Aggregate exported usage files from several machines
+    Report {
+        /// Exported usage files
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+}
+
+/// Command counts for one machine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageFile {
+    pub schema_version: u8,
+    pub schema: String,
+    /// Random ID so reports can count machines without identifying them
+    pub machine_id: String,
+    /// When counting was enabled (RFC 3339)
+    pub since: String,
+    pub commands: BTreeMap<String, u64>,
+}
+
+impl UsageFile {
+    fn new() -> Self {
+        Self {
+            schema_version: MACHINE_OUTPUT_SCHEMA_VERSION,
+            schema: USAGE_SCHEMA.to_string(),
+            machine_id: uuid::Uuid::new_v4().to_string(),
+            since: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            commands: BTreeMap::new(),
+        }
+    }
+
+    /// Read a usage file; `None` when it doesn't exist (counting disabled)
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let file: Self = serde_json::from_slice(&content)
+            .with_context(|| format!("{} is not a whogitit usage file", path.display()))?;
+        Ok(Some(file))
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create usage directory")?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .context("Failed to write usage file")?;
+        fs::rename(&tmp, path).context("Failed to store usage file")?;
+        Ok(())
+    }
+}
+
+/// Location of the local usage file
+pub fn usage_path() -> Option<PathBuf> {
+    home_override()
+        .map(|home| home.join(".local").join("share"))
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("whogitit").join("usage.json"))
+}
+
+/// Count one run of `command` if usage counting is enabled
+///
+/// Never fails: usage counts must not get in the way of the command itself.
+pub fn record_command(command: &str) {
+    if let Some(path) = usage_path() {
+        let _ = increment(&path, command);
+    }
+}
+
+fn increment(path: &Path, command: &str) -> Result<()> {
+    let Some(mut file) = UsageFile::load(path)? else {
+        return Ok(());
+    };
+    *file.commands.entry(command.to_string()).or_insert(0) += 1;
+    file.save(path)
+}
+
+/// Usage of one command across machines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandUsage {
+    pub command: String,
+    pub count: u64,
+    /// Number of machines that ran the command at least once
+    pub machines: usize,
+}
+
+/// Aggregate usage files, most used commands first
+pub fn aggregate(files: &[UsageFile]) -> Vec<CommandUsage> {
+    let mut totals: BTreeMap<&str, (u64, BTreeSet<&str>)> = BTreeMap::new();
+    for file in files {
+        for (command, count) in &file.commands {
+            let entry = totals.entry(command).or_default();
+            entry.0 += count;
+            entry.1.insert(&file.machine_id);
+        }
+    }
+    let mut usage: Vec<CommandUsage> = totals
+        .into_iter()
+        .map(|(command, (count, machines))| CommandUsage {
+            command: command.to_string(),
+            count,
+            machines: machines.len(),
+        })
+        .collect();
+    usage.sort_by_key(|u| std::cmp::Reverse(u.count));
+    usage
+}
+
+/// Run the usage command
+pub fn run(args: UsageArgs) -> Result<()> {
+    let path = usage_path().context("Could not determine the user data directory")?;
+
+    match args.action {
+        UsageAction::Enable => {
+            if UsageFile::load(&path)?.is_some() {
+                println!("Usage counting is already enabled ({}).", path.display());
+                return Ok(());
+            }
+            UsageFile::new().save(&path)?;
+            println!(
+                "Usage counting enabled. Counts are stored only in {}.",
+                path.display()
+            );
+        }
+        UsageAction::Disable => match fs::remove_file(&path) {
+            Ok(()) => println!("Usage counting disabled; local counts deleted."),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!("Usage counting is not enabled.")
+            }
+            Err(e) => return Err(e).context("Failed to remove usage file"),
+        },
+        UsageAction::Show => {
+            let Some(file) = UsageFile::load(&path)? else {
+                println!("Usage counting is not enabled. Run 'whogitit usage enable' to opt in.");
+                return Ok(());
+            };
+            println!("Command usage since {} ({})", file.since, path.display());
+            println!();
+            print_counts(&aggregate(std::slice::from_ref(&file)), false);
+        }
+        UsageAction::Export => {
+            let file = UsageFile::load(&path)?
+                .context("Usage counting is not enabled; nothing to export")?;
+            println!("{}", serde_json::to_string_pretty(&file)?);
+        }
+        UsageAction::Report { files, format } => {
+            let mut loaded = Vec::new();
+            for file in &files {
+                loaded.push(
+                    UsageFile::load(file)?
+                        .with_context(|| format!("{} does not exist", file.display()))?,
+                );
+            }
+            let commands = aggregate(&loaded);
+            let machines: BTreeSet<&str> = loaded.iter().map(|f| f.machine_id.as_str()).collect();
+            match format {
+                OutputFormat::Json => {
+                    let output = serde_json::json!({
+                        "schema_version": MACHINE_OUTPUT_SCHEMA_VERSION,
+                        "schema": USAGE_REPORT_SCHEMA,
+                        "machines": machines.len(),
+                        "commands": commands,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                }
+                OutputFormat::Pretty => {
+                    println!("Command usage across {} machine(s)", machines.len());
+                    println!();
+                    print_counts(&commands, true);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_counts(commands: &[CommandUsage], with_machines: bool) {
+    if commands.is_empty() {
+        println!("No commands recorded yet.");
+        return;
+    }
+    for usage in commands {
+        if with_machines {
+            println!(
+                "  {:<20} {:>8}  {}",
+                usage.command.bold(),
+                usage.count,
+                format!("({} machine(s))", usage.machines).dimmed()
+            );
+        } else {
+            println!("  {:<20} {:>8}", usage.command.bold(), usage.count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_increment_only_when_enabled() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("whogitit/usage.json");
+
+        increment(&path, "blame").unwrap();
+        assert!(!path.exists());
+
+        UsageFile::new().save(&path).unwrap();
+        increment(&path, "blame").unwrap();
+        increment(&path, "blame").unwrap();
+        increment(&path, "show").unwrap();
+
+        let file = UsageFile::load(&path).unwrap().unwrap();
+        assert_eq!(file.commands["blame"], 2);
+        assert_eq!(file.commands["show"], 1);
+    }
+
+    #[test]
+    fn test_aggregate_across_machines() {
+        let machine = |counts: &[(&str, u64)]| UsageFile {
+            commands: counts.iter().map(|(c, n)| (c.to_string(), *n)).collect(),
+            ..UsageFile::new()
+        };
+        let files = [
+            machine(&[("blame", 3), ("show", 1)]),
+            machine(&[("blame", 2), ("summary", 5)]),
+        ];
+
+        let usage = aggregate(&files);
+        let rows: Vec<(&str, u64, usize)> = usage
+            .iter()
+            .map(|u| (u.command.as_str(), u.count, u.machines))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![("blame", 5, 2), ("summary", 5, 1), ("show", 1, 1)]
+        );
+    }
+}
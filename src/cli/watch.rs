@@ -0,0 +1,138 @@
+//! Watch-capture command - capture AI edits without editor hooks
+//!
+//! For AI tools with no hook system, watches the worktree and records each
+//! change into the pending buffer, tagged with the given session and model.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::capture::hook::{find_repo_root, is_repo_initialized};
+use crate::capture::watch::{PromptSource, WatchSession};
+use crate::core::attribution::ModelInfo;
+
+/// Watch-capture command arguments
+#[derive(Debug, Args)]
+pub struct WatchCaptureArgs {
+    /// Session UUID to record edits under (defaults to a new session)
+    #[arg(long)]
+    pub session_id: Option<String>,
+
+    /// Model identifier of the AI tool making the edits
+    #[arg(long)]
+    pub model: String,
+
+    /// Model provider (e.g. "openai")
+    #[arg(long, default_value = "unknown")]
+    pub provider: String,
+
+    /// Prompt text recorded for every captured edit
+    #[arg(long, conflicts_with = "prompt_file")]
+    pub prompt: Option<String>,
+
+    /// Sidecar file holding the current prompt (re-read on each change)
+    #[arg(long)]
+    pub prompt_file: Option<PathBuf>,
+
+    /// Milliseconds to wait for related filesystem events before capturing
+    #[arg(long, default_value = "300")]
+    pub debounce_ms: u64,
+}
+
+/// Run the watch-capture command
+pub fn run(args: WatchCaptureArgs) -> Result<()> {
+    let repo_root = find_repo_root()?;
+    if !is_repo_initialized(&repo_root) {
+        anyhow::bail!("Repository is not initialized. Run 'whogitit init' first.");
+    }
+
+    let session_id = match args.session_id {
+        Some(id) => {
+            uuid::Uuid::parse_str(&id)
+                .with_context(|| format!("--session-id must be a UUID, got '{}'", id))?;
+            id
+        }
+        None => uuid::Uuid::new_v4().to_string(),
+    };
+
+    let prompt = match (args.prompt, args.prompt_file) {
+        (_, Some(path)) => PromptSource::File(path),
+        (Some(text), None) => PromptSource::Text(text),
+        (None, None) => PromptSource::Text(read_session_prompt()?),
+    };
+
+    let model = ModelInfo::new(&args.model, &args.provider);
+    let mut session = WatchSession::new(&repo_root, &session_id, model, prompt)?;
+
+    println!(
+        "whogitit: Watching {} (session {})",
+        repo_root.display(),
+        session_id
+    );
+    println!("Press Ctrl-C to stop. Pending edits are saved as they are captured.");
+
+    session.run(Duration::from_millis(args.debounce_ms))
+}
+
+/// Ask for a session prompt when running interactively
+fn read_session_prompt() -> Result<String> {
+    if !atty::is(atty::Stream::Stdin) {
+        return Ok(String::new());
+    }
+
+    print!("Prompt for this session (optional): ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("Failed to read prompt")?;
+    Ok(line.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+
+    #[test]
+    fn test_watch_capture_args_parse() {
+        let cli = Cli::try_parse_from([
+            "whogitit",
+            "watch-capture",
+            "--session-id",
+            "6d1f0a2b-3c4d-4e5f-8a9b-0c1d2e3f4a5b",
+            "--model",
+            "gpt-5-codex",
+            "--prompt-file",
+            ".ai-prompt",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::WatchCapture(args) => {
+                assert_eq!(args.model, "gpt-5-codex");
+                assert_eq!(args.provider, "unknown");
+                assert_eq!(args.prompt_file, Some(PathBuf::from(".ai-prompt")));
+                assert_eq!(args.debounce_ms, 300);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_watch_capture_prompt_conflicts_with_prompt_file() {
+        let result = Cli::try_parse_from([
+            "whogitit",
+            "watch-capture",
+            "--model",
+            "m",
+            "--prompt",
+            "x",
+            "--prompt-file",
+            "p.txt",
+        ]);
+        assert!(result.is_err());
+    }
+}
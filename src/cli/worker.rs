@@ -0,0 +1,151 @@
+//! Worker command - retry attaching staged attribution notes
+//!
+//! When a note write fails (for example, lock contention outlasting the retry
+//! budget), the payload stays staged under `.git/whogitit/staged-notes/`.
+//! This command attaches those payloads. With `--resume` it also finishes
+//! post-commit analyses interrupted with Ctrl-C (see [`crate::capture::interrupt`]).
+
+use anyhow::{Context, Result};
+use clap::Args;
+use git2::Repository;
+
+use crate::capture::hook::CaptureHook;
+use crate::capture::interrupt::{InterruptedAnalysis, SigintGuard};
+use crate::storage::notes::NotesStore;
+use crate::storage::{LockHolderKind, RepoLock};
+use crate::utils::SHORT_COMMIT_LEN;
+
+/// Worker command arguments
+#[derive(Debug, Args)]
+pub struct WorkerArgs {
+    /// List staged payloads without attaching them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Also finish post-commit analyses that were interrupted
+    #[arg(long)]
+    pub resume: bool,
+}
+
+/// Run the worker command
+pub fn run(args: WorkerArgs) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let store = NotesStore::new(&repo)?;
+    let _lock = (!args.dry_run)
+        .then(|| RepoLock::acquire_default(repo.path(), LockHolderKind::Worker))
+        .transpose()?;
+
+    let interrupted = InterruptedAnalysis::list(repo.path());
+    let mut failed = 0;
+    if args.resume {
+        failed += resume_interrupted(&repo, &interrupted, args.dry_run)?;
+    } else if !interrupted.is_empty() {
+        println!(
+            "{} interrupted analysis(es) waiting; run 'whogitit worker --resume' to finish them.",
+            interrupted.len()
+        );
+    }
+
+    let staged = store.staged_payloads()?;
+    if staged.is_empty() {
+        println!("No staged attribution notes to attach.");
+        if failed > 0 {
+            anyhow::bail!("{} interrupted analysis(es) could not be finished", failed);
+        }
+        return Ok(());
+    }
+
+    for (oid, path) in &staged {
+        let oid_str = oid.to_string();
+        let short = &oid_str[..SHORT_COMMIT_LEN];
+        if args.dry_run {
+            println!("Would attach staged attribution to {}", short);
+            continue;
+        }
+        match store.retry_staged(*oid, path) {
+            Ok(_) => println!("Attached staged attribution to {}", short),
+            Err(e) => {
+                failed += 1;
+                eprintln!("whogitit: Warning - {}: {:#}", short, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} staged notes and interrupted analyses could not be finished",
+            failed,
+            staged.len() + if args.resume { interrupted.len() } else { 0 }
+        );
+    }
+    Ok(())
+}
+
+/// Analyze interrupted post-commit runs and attach their notes, returning the failure count
+fn resume_interrupted(
+    repo: &Repository,
+    markers: &[std::path::PathBuf],
+    dry_run: bool,
+) -> Result<usize> {
+    if markers.is_empty() {
+        return Ok(0);
+    }
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("No working directory"))?;
+    let hook = CaptureHook::new(workdir)?;
+    let _sigint = SigintGuard::install();
+
+    let mut failed = 0;
+    for path in markers {
+        let marker = match InterruptedAnalysis::load(path) {
+            Ok(marker) => marker,
+            Err(e) => {
+                failed += 1;
+                eprintln!("whogitit: Warning - {:#}", e);
+                continue;
+            }
+        };
+        let short = &marker.commit[..SHORT_COMMIT_LEN.min(marker.commit.len())];
+        if dry_run {
+            println!(
+                "Would finish analysis of {} (interrupted at {}/{} files)",
+                short, marker.files_done, marker.files_total
+            );
+            continue;
+        }
+        match hook.resume_interrupted(&marker) {
+            Ok(attribution) => {
+                std::fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                match attribution {
+                    Some(attribution) => println!(
+                        "Attached attribution to {} ({} files)",
+                        short,
+                        attribution.files.len()
+                    ),
+                    None => println!("Finished analysis of {}: no AI-edited files", short),
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("whogitit: Warning - {}: {:#}", short, e);
+            }
+        }
+    }
+    Ok(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_args_structure() {
+        let args = WorkerArgs {
+            dry_run: true,
+            resume: false,
+        };
+        assert!(args.dry_run);
+    }
+}
@@ -0,0 +1,7 @@
+//! Research analytics over attribution history
+//!
+//! Unlike `stats`, which sums what each commit's note says at commit time, these
+//! analyses follow lines after they are committed, using blame snapshots taken
+//! before and after each commit.
+
+pub mod transitions;
@@ -0,0 +1,573 @@
+//! Line source transitions
+//!
+//! Each commit is described by the blame of its changed files in the parent and
+//! in the commit ([`CommitSnapshot`]). Diffing the two tells what happened to
+//! every line the commit touched:
+//!
+//! - **modified**: a new line of the commit resembles it closely (the three-way
+//!   analysis similarity threshold), e.g. a human tweaking an AI line
+//! - **replaced**: its hunk was rewritten with dissimilar lines
+//! - **deleted**: its hunk only removed lines
+//!
+//! Lines whose blame origin survives elsewhere in the file were moved, not
+//! changed, and are not counted. AI lines written within the analyzed range
+//! that are still present in the final snapshot are reported as surviving, with
+//! their age, so lifetimes can be treated as right-censored.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+use crate::capture::snapshot::LineSource;
+use crate::capture::threeway::{compute_similarity, DEFAULT_SIMILARITY_THRESHOLD};
+use crate::core::attribution::{BlameLineResult, BlameResult};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Line source without its edit details
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineKind {
+    Ai,
+    AiModified,
+    Human,
+    Original,
+    Unknown,
+}
+
+impl LineKind {
+    pub fn is_ai(self) -> bool {
+        matches!(self, LineKind::Ai | LineKind::AiModified)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineKind::Ai => "ai",
+            LineKind::AiModified => "ai_modified",
+            LineKind::Human => "human",
+            LineKind::Original => "original",
+            LineKind::Unknown => "unknown",
+        }
+    }
+}
+
+impl From<&LineSource> for LineKind {
+    fn from(source: &LineSource) -> Self {
+        match source {
+            LineSource::AI { .. } => LineKind::Ai,
+            LineSource::AIModified { .. } => LineKind::AiModified,
+            LineSource::Human => LineKind::Human,
+            LineSource::Original => LineKind::Original,
+            LineSource::Unknown => LineKind::Unknown,
+        }
+    }
+}
+
+/// What happened to a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fate {
+    Modified,
+    Replaced,
+    Deleted,
+    /// Still present at the end of the range (AI lines written in the range only)
+    Survived,
+}
+
+impl Fate {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Fate::Modified => "modified",
+            Fate::Replaced => "replaced",
+            Fate::Deleted => "deleted",
+            Fate::Survived => "survived",
+        }
+    }
+}
+
+/// Blame of one file in a commit's parent and in the commit
+#[derive(Debug, Clone)]
+pub struct FileSnapshot {
+    /// Path in the commit (the parent's path may differ after a rename)
+    pub path: String,
+    /// Lines in the parent; empty for added files
+    pub before: Vec<BlameLineResult>,
+    /// Lines in the commit; empty for deleted files
+    pub after: Vec<BlameLineResult>,
+}
+
+/// Blame snapshots of the files a commit changed
+#[derive(Debug, Clone)]
+pub struct CommitSnapshot {
+    pub commit: String,
+    /// Commit time (seconds since the epoch)
+    pub time: i64,
+    pub files: Vec<FileSnapshot>,
+}
+
+/// One line's transition
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransitionEvent {
+    /// Commit that changed the line (the last analyzed commit for survivors)
+    pub commit: String,
+    pub path: String,
+    pub from: LineKind,
+    /// Commit that wrote the line
+    pub from_commit: String,
+    pub fate: Fate,
+    /// Source of the line that took its place (modified and replaced lines)
+    pub to: Option<LineKind>,
+    /// Days between the line being written and its transition (or the end of the range)
+    pub age_days: Option<f64>,
+}
+
+/// Number of transitions of one kind
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransitionCount {
+    pub from: LineKind,
+    pub fate: Fate,
+    pub to: Option<LineKind>,
+    pub count: usize,
+}
+
+/// What became of the AI lines written within the range
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AiLineFates {
+    /// AI and AI-modified lines committed in the range
+    pub introduced: usize,
+    /// Later modified by a human
+    pub human_modified: usize,
+    /// Later modified or rewritten by the AI
+    pub ai_revised: usize,
+    /// Hunk later rewritten by a human
+    pub human_replaced: usize,
+    pub deleted: usize,
+    pub surviving: usize,
+    pub human_modified_rate: f64,
+    pub human_replaced_rate: f64,
+    pub survival_rate: f64,
+    /// Median days from commit to first change, over changed lines
+    pub median_days_to_change: Option<f64>,
+    /// Median age in days of the surviving lines
+    pub median_surviving_age_days: Option<f64>,
+}
+
+/// Transition statistics over a range of commits
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransitionReport {
+    pub commits_analyzed: usize,
+    /// Lines written in the range, by source
+    pub introduced: BTreeMap<LineKind, usize>,
+    pub ai: AiLineFates,
+    /// Every (from, fate, to) combination that occurred, most frequent first
+    pub transitions: Vec<TransitionCount>,
+    #[serde(skip)]
+    pub events: Vec<TransitionEvent>,
+}
+
+/// Accumulates transitions commit by commit, oldest first
+#[derive(Debug, Default)]
+pub struct TransitionAnalyzer {
+    commits: HashSet<String>,
+    commit_times: HashMap<String, i64>,
+    introduced: BTreeMap<LineKind, usize>,
+    events: Vec<TransitionEvent>,
+}
+
+impl TransitionAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a commit's changes
+    ///
+    /// `origin_time` gives the commit time of lines written before the range;
+    /// lines without one get no age.
+    pub fn add_commit(
+        &mut self,
+        snapshot: &CommitSnapshot,
+        mut origin_time: impl FnMut(&str) -> Option<i64>,
+    ) {
+        self.commits.insert(snapshot.commit.clone());
+        self.commit_times
+            .insert(snapshot.commit.clone(), snapshot.time);
+
+        for file in &snapshot.files {
+            for line in file.after.iter().filter(|l| l.commit_id == snapshot.commit) {
+                *self
+                    .introduced
+                    .entry(LineKind::from(&line.source))
+                    .or_default() += 1;
+            }
+            for (line, fate, to) in file_transitions(&snapshot.commit, file) {
+                let age_days = self
+                    .time_of(&line.commit_id, &mut origin_time)
+                    .map(|t| days_between(t, snapshot.time));
+                self.events.push(TransitionEvent {
+                    commit: snapshot.commit.clone(),
+                    path: file.path.clone(),
+                    from: LineKind::from(&line.source),
+                    from_commit: line.commit_id.clone(),
+                    fate,
+                    to,
+                    age_days,
+                });
+            }
+        }
+    }
+
+    fn time_of(
+        &mut self,
+        commit: &str,
+        origin_time: &mut impl FnMut(&str) -> Option<i64>,
+    ) -> Option<i64> {
+        if let Some(time) = self.commit_times.get(commit) {
+            return Some(*time);
+        }
+        let time = origin_time(commit)?;
+        self.commit_times.insert(commit.to_string(), time);
+        Some(time)
+    }
+
+    /// Build the report, counting AI lines of the range still present in `final_blames`
+    pub fn finish(
+        mut self,
+        final_blames: &[BlameResult],
+        end_commit: &str,
+        end_time: i64,
+    ) -> TransitionReport {
+        for blame in final_blames {
+            for line in &blame.lines {
+                let kind = LineKind::from(&line.source);
+                if !kind.is_ai() || !self.commits.contains(&line.commit_id) {
+                    continue;
+                }
+                let age_days = self
+                    .commit_times
+                    .get(&line.commit_id)
+                    .map(|t| days_between(*t, end_time));
+                self.events.push(TransitionEvent {
+                    commit: end_commit.to_string(),
+                    path: blame.path.clone(),
+                    from: kind,
+                    from_commit: line.commit_id.clone(),
+                    fate: Fate::Survived,
+                    to: None,
+                    age_days,
+                });
+            }
+        }
+
+        let mut counts: BTreeMap<(LineKind, Fate, Option<LineKind>), usize> = BTreeMap::new();
+        for event in &self.events {
+            *counts
+                .entry((event.from, event.fate, event.to))
+                .or_default() += 1;
+        }
+        let mut transitions: Vec<TransitionCount> = counts
+            .into_iter()
+            .map(|((from, fate, to), count)| TransitionCount {
+                from,
+                fate,
+                to,
+                count,
+            })
+            .collect();
+        transitions.sort_by_key(|t| std::cmp::Reverse(t.count));
+
+        TransitionReport {
+            commits_analyzed: self.commits.len(),
+            ai: self.ai_fates(),
+            introduced: self.introduced,
+            transitions,
+            events: self.events,
+        }
+    }
+
+    fn ai_fates(&self) -> AiLineFates {
+        let mut fates = AiLineFates {
+            introduced: self
+                .introduced
+                .iter()
+                .filter(|(k, _)| k.is_ai())
+                .map(|(_, n)| n)
+                .sum(),
+            ..Default::default()
+        };
+        let mut change_ages = Vec::new();
+        let mut surviving_ages = Vec::new();
+        let tracked = self
+            .events
+            .iter()
+            .filter(|e| e.from.is_ai() && self.commits.contains(&e.from_commit));
+        for event in tracked {
+            let by_ai = event.to.is_some_and(LineKind::is_ai);
+            match event.fate {
+                Fate::Survived => fates.surviving += 1,
+                Fate::Deleted => fates.deleted += 1,
+                Fate::Modified if !by_ai => fates.human_modified += 1,
+                Fate::Replaced if !by_ai => fates.human_replaced += 1,
+                Fate::Modified | Fate::Replaced => fates.ai_revised += 1,
+            }
+            if let Some(age) = event.age_days {
+                if event.fate == Fate::Survived {
+                    surviving_ages.push(age);
+                } else {
+                    change_ages.push(age);
+                }
+            }
+        }
+
+        let rate = |n: usize| {
+            if fates.introduced == 0 {
+                0.0
+            } else {
+                n as f64 / fates.introduced as f64
+            }
+        };
+        fates.human_modified_rate = rate(fates.human_modified);
+        fates.human_replaced_rate = rate(fates.human_replaced);
+        fates.survival_rate = rate(fates.surviving);
+        fates.median_days_to_change = median(change_ages);
+        fates.median_surviving_age_days = median(surviving_ages);
+        fates
+    }
+}
+
+/// Lines of `file.before` the commit changed, with their fate and replacement source
+fn file_transitions<'a>(
+    commit: &str,
+    file: &'a FileSnapshot,
+) -> Vec<(&'a BlameLineResult, Fate, Option<LineKind>)> {
+    let old: Vec<&str> = file.before.iter().map(|l| l.content.as_str()).collect();
+    let new: Vec<&str> = file.after.iter().map(|l| l.content.as_str()).collect();
+
+    let ops = capture_diff_slices(Algorithm::Myers, &old, &new);
+
+    // Removed lines whose blame origin reappears among the inserted lines were moved
+    let mut kept: HashMap<(&str, &str), usize> = HashMap::new();
+    for op in &ops {
+        let inserted = match *op {
+            DiffOp::Insert {
+                new_index, new_len, ..
+            }
+            | DiffOp::Replace {
+                new_index, new_len, ..
+            } => &file.after[new_index..new_index + new_len],
+            _ => continue,
+        };
+        for line in inserted.iter().filter(|l| l.commit_id != commit) {
+            *kept
+                .entry((line.commit_id.as_str(), line.content.as_str()))
+                .or_default() += 1;
+        }
+    }
+    let mut moved = |line: &'a BlameLineResult| match kept
+        .get_mut(&(line.commit_id.as_str(), line.content.as_str()))
+    {
+        Some(n) if *n > 0 => {
+            *n -= 1;
+            true
+        }
+        _ => false,
+    };
+
+    let mut transitions = Vec::new();
+    for op in ops {
+        let (removed, added) = match op {
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => (old_index..old_index + old_len, 0..0),
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => (
+                old_index..old_index + old_len,
+                new_index..new_index + new_len,
+            ),
+            DiffOp::Equal { .. } | DiffOp::Insert { .. } => continue,
+        };
+        let removed: Vec<&BlameLineResult> =
+            file.before[removed].iter().filter(|l| !moved(l)).collect();
+        let added: Vec<&BlameLineResult> = file.after[added]
+            .iter()
+            .filter(|l| l.commit_id == commit)
+            .collect();
+        let mut unmatched: Vec<&BlameLineResult> = added.clone();
+        let replacement = majority_kind(&added);
+
+        for line in removed {
+            let best = unmatched
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| !a.content.trim().is_empty() && !line.content.trim().is_empty())
+                .map(|(i, a)| (i, compute_similarity(line.content.trim(), a.content.trim())))
+                .filter(|(_, s)| *s >= DEFAULT_SIMILARITY_THRESHOLD)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            match (best, replacement) {
+                (Some((i, _)), _) => {
+                    let to = LineKind::from(&unmatched.remove(i).source);
+                    transitions.push((line, Fate::Modified, Some(to)));
+                }
+                (None, Some(to)) => transitions.push((line, Fate::Replaced, Some(to))),
+                (None, None) => transitions.push((line, Fate::Deleted, None)),
+            }
+        }
+    }
+    transitions
+}
+
+/// Most common source among `lines` (ties go to the first seen)
+fn majority_kind(lines: &[&BlameLineResult]) -> Option<LineKind> {
+    let mut counts: Vec<(LineKind, usize)> = Vec::new();
+    for line in lines {
+        let kind = LineKind::from(&line.source);
+        match counts.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((kind, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, n)| *n)
+        .map(|(kind, _)| kind)
+}
+
+fn days_between(from: i64, to: i64) -> f64 {
+    (to - from).max(0) as f64 / SECONDS_PER_DAY
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(commit: &str, source: LineSource, content: &str) -> BlameLineResult {
+        BlameLineResult {
+            line_number: 0,
+            content: content.to_string(),
+            commit_id: commit.to_string(),
+            commit_short: commit.to_string(),
+            author: "dev".to_string(),
+            source,
+            prompt_index: None,
+            prompt_preview: None,
+            generated_at: None,
+            confidence: None,
+        }
+    }
+
+    fn ai(commit: &str, content: &str) -> BlameLineResult {
+        line(
+            commit,
+            LineSource::AI {
+                edit_id: "e".to_string(),
+            },
+            content,
+        )
+    }
+
+    fn human(commit: &str, content: &str) -> BlameLineResult {
+        line(commit, LineSource::Human, content)
+    }
+
+    #[test]
+    fn test_classifies_modified_replaced_deleted_and_moved_lines() {
+        let file = FileSnapshot {
+            path: "src/lib.rs".to_string(),
+            before: vec![
+                ai("c1", "let total = items.len();"),
+                ai("c1", "fn helper() {}"),
+                human("c0", "// moved comment"),
+                human("c0", "fn main() {}"),
+                ai("c1", "println!(\"debug\");"),
+            ],
+            after: vec![
+                human("c2", "let total = items.len() + 1;"),
+                human("c2", "struct Completely { different: u8 }"),
+                human("c0", "fn main() {}"),
+                human("c0", "// moved comment"),
+            ],
+        };
+        let transitions: Vec<(&str, Fate, Option<LineKind>)> = file_transitions("c2", &file)
+            .into_iter()
+            .map(|(l, fate, to)| (l.content.as_str(), fate, to))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (
+                    "let total = items.len();",
+                    Fate::Modified,
+                    Some(LineKind::Human)
+                ),
+                ("fn helper() {}", Fate::Replaced, Some(LineKind::Human)),
+                ("println!(\"debug\");", Fate::Deleted, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_tracks_ai_lines_of_the_range() {
+        let day = 86_400;
+        let mut analyzer = TransitionAnalyzer::new();
+        analyzer.add_commit(
+            &CommitSnapshot {
+                commit: "c1".to_string(),
+                time: 0,
+                files: vec![FileSnapshot {
+                    path: "a.rs".to_string(),
+                    before: vec![],
+                    after: vec![ai("c1", "let x = compute(1);"), ai("c1", "let y = 2;")],
+                }],
+            },
+            |_| None,
+        );
+        analyzer.add_commit(
+            &CommitSnapshot {
+                commit: "c2".to_string(),
+                time: 4 * day,
+                files: vec![FileSnapshot {
+                    path: "a.rs".to_string(),
+                    before: vec![ai("c1", "let x = compute(1);"), ai("c1", "let y = 2;")],
+                    after: vec![human("c2", "let x = compute(10);"), ai("c1", "let y = 2;")],
+                }],
+            },
+            |_| None,
+        );
+        let final_blame = BlameResult {
+            path: "a.rs".to_string(),
+            revision: "c2".to_string(),
+            lines: vec![human("c2", "let x = compute(10);"), ai("c1", "let y = 2;")],
+        };
+        let report = analyzer.finish(&[final_blame], "c2", 10 * day);
+
+        assert_eq!(report.commits_analyzed, 2);
+        assert_eq!(report.introduced[&LineKind::Ai], 2);
+        assert_eq!(report.introduced[&LineKind::Human], 1);
+        assert_eq!(report.ai.introduced, 2);
+        assert_eq!(report.ai.human_modified, 1);
+        assert_eq!(report.ai.surviving, 1);
+        assert_eq!(report.ai.human_modified_rate, 0.5);
+        assert_eq!(report.ai.median_days_to_change, Some(4.0));
+        assert_eq!(report.ai.median_surviving_age_days, Some(10.0));
+        assert_eq!(report.transitions.len(), 2);
+        assert_eq!(report.events.len(), 2);
+    }
+}
@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::capture::snapshot::{FileAttributionResult, LineSource};
+use crate::capture::snapshot::{compute_hash, FileAttributionResult, LineSource};
+use crate::privacy::tiers::PromptClassification;
 
-/// Schema version for the attribution format (3 = with edit context)
-pub const SCHEMA_VERSION: u8 = 3;
+/// Schema version for the attribution format (4 = line content stored as hashes)
+pub const SCHEMA_VERSION: u8 = 4;
+/// Oldest schema read without a compatibility warning (v3 notes store line content inline)
+pub const MIN_NATIVE_SCHEMA_VERSION: u8 = 3;
 
 /// Core attribution data attached to commits via git notes
 ///
@@ -59,6 +62,47 @@ pub struct PromptInfo {
     pub timestamp: String,
     /// Files affected by this prompt
     pub affected_files: Vec<String>,
+    /// Stable hash of the prompt text (see [`prompt_hash`]); absent in older notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Privacy classification from capture time; absent in older notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PromptClassification>,
+    /// Tool calls behind this prompt's edits, when the hook reported their ids
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Claude Code identifiers of the tool call behind an edit
+///
+/// Lines reference edits by `edit_id`; the ids find the tool call in the session
+/// transcript (`tool_use` block id) and the assistant message that issued it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub edit_id: String,
+    /// File the edit changed
+    pub file: String,
+    /// Tool that made the edit (Edit, Write, Bash)
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+}
+
+impl PromptInfo {
+    /// Stored prompt hash, computed from the text for notes written before hashing
+    pub fn hash(&self) -> String {
+        self.hash.clone().unwrap_or_else(|| prompt_hash(&self.text))
+    }
+}
+
+/// Stable hash of a prompt, used to find identical prompts across commits and repositories
+///
+/// Whitespace runs are collapsed first so re-wrapped or re-indented copies hash the same.
+pub fn prompt_hash(text: &str) -> String {
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    compute_hash(&normalized)
 }
 
 /// Metadata about the AI session that generated the code
@@ -78,6 +122,9 @@ pub struct SessionMetadata {
     /// Number of subagents spawned during this session
     #[serde(default)]
     pub subagent_count: u32,
+    /// Label of the explicit session (`whogitit session begin --label`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 /// Information about the AI model used
@@ -90,12 +137,16 @@ pub struct ModelInfo {
 }
 
 impl ModelInfo {
-    pub fn claude(model_id: &str) -> Self {
+    pub fn new(model_id: &str, provider: &str) -> Self {
         Self {
             id: model_id.to_string(),
-            provider: "anthropic".to_string(),
+            provider: provider.to_string(),
         }
     }
+
+    pub fn claude(model_id: &str) -> Self {
+        Self::new(model_id, "anthropic")
+    }
 }
 
 /// Result of blame operation for a single line
@@ -117,6 +168,10 @@ pub struct BlameLineResult {
     pub prompt_index: Option<u32>,
     /// Prompt text preview if available
     pub prompt_preview: Option<String>,
+    /// If AI-generated, when the line was generated (RFC 3339)
+    pub generated_at: Option<String>,
+    /// Confidence of the line's attribution, when the note records one per line
+    pub confidence: Option<f64>,
 }
 
 impl BlameLineResult {
@@ -206,6 +261,7 @@ mod tests {
                 prompt_count: 1,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![],
             files: vec![FileAttributionResult {
@@ -219,6 +275,10 @@ mod tests {
                     original_lines: 1,
                     unknown_lines: 0,
                 },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
             }],
         };
 
@@ -245,6 +305,8 @@ mod tests {
                     },
                     prompt_index: Some(0),
                     prompt_preview: None,
+                    generated_at: None,
+                    confidence: None,
                 },
                 BlameLineResult {
                     line_number: 2,
@@ -255,6 +317,8 @@ mod tests {
                     source: LineSource::Human,
                     prompt_index: None,
                     prompt_preview: None,
+                    generated_at: None,
+                    confidence: None,
                 },
                 BlameLineResult {
                     line_number: 3,
@@ -265,6 +329,8 @@ mod tests {
                     source: LineSource::Original,
                     prompt_index: None,
                     prompt_preview: None,
+                    generated_at: None,
+                    confidence: None,
                 },
             ],
         };
@@ -285,24 +351,30 @@ mod tests {
                 prompt_count: 1,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![PromptInfo {
                 index: 0,
                 text: "Add main function".to_string(),
                 timestamp: "2026-01-30T10:00:00Z".to_string(),
                 affected_files: vec!["test.rs".to_string()],
+                hash: None,
+                privacy: None,
+                tool_calls: Vec::new(),
             }],
             files: vec![FileAttributionResult {
                 path: "test.rs".to_string(),
                 lines: vec![LineAttribution {
                     line_number: 1,
                     content: "fn main() {}".to_string(),
+                    content_hash: None,
                     source: LineSource::AI {
                         edit_id: "e1".to_string(),
                     },
                     edit_id: Some("e1".to_string()),
                     prompt_index: Some(0),
                     confidence: 1.0,
+                    timestamp: None,
                 }],
                 summary: AttributionSummary {
                     total_lines: 1,
@@ -312,6 +384,10 @@ mod tests {
                     original_lines: 0,
                     unknown_lines: 0,
                 },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
             }],
         };
 
@@ -432,6 +508,7 @@ mod tests {
                 prompt_count: 2,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![
                 PromptInfo {
@@ -439,12 +516,18 @@ mod tests {
                     text: "First prompt".to_string(),
                     timestamp: "2026-01-30T10:00:00Z".to_string(),
                     affected_files: vec!["file1.rs".to_string()],
+                    hash: None,
+                    privacy: None,
+                    tool_calls: Vec::new(),
                 },
                 PromptInfo {
                     index: 1,
                     text: "Second prompt".to_string(),
                     timestamp: "2026-01-30T10:01:00Z".to_string(),
                     affected_files: vec!["file2.rs".to_string()],
+                    hash: None,
+                    privacy: None,
+                    tool_calls: Vec::new(),
                 },
             ],
             files: vec![],
@@ -460,6 +543,28 @@ mod tests {
         assert!(attribution.get_prompt(99).is_none());
     }
 
+    #[test]
+    fn test_prompt_hash_normalizes_whitespace() {
+        assert_eq!(
+            prompt_hash("Add  tests\n for auth"),
+            prompt_hash("Add tests for auth")
+        );
+        assert_ne!(prompt_hash("Add tests"), prompt_hash("Add test"));
+
+        let legacy = PromptInfo {
+            index: 0,
+            text: "Add tests".to_string(),
+            timestamp: "2026-01-30T10:00:00Z".to_string(),
+            affected_files: vec![],
+            hash: None,
+            privacy: None,
+            tool_calls: Vec::new(),
+        };
+        assert_eq!(legacy.hash(), prompt_hash("Add tests"));
+        let json = serde_json::to_string(&legacy).unwrap();
+        assert!(!json.contains("\"hash\""));
+    }
+
     #[test]
     fn test_model_info_claude() {
         let model = ModelInfo::claude("claude-opus-4-5-20251101");
@@ -478,6 +583,7 @@ mod tests {
                 prompt_count: 1,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![],
             files: vec![
@@ -492,6 +598,10 @@ mod tests {
                         original_lines: 1,
                         unknown_lines: 0,
                     },
+                    binary: None,
+                    blocks: None,
+                    analysis_warning: None,
+                    secret_findings: Vec::new(),
                 },
                 FileAttributionResult {
                     path: "file2.rs".to_string(),
@@ -504,6 +614,10 @@ mod tests {
                         original_lines: 2,
                         unknown_lines: 0,
                     },
+                    binary: None,
+                    blocks: None,
+                    analysis_warning: None,
+                    secret_findings: Vec::new(),
                 },
             ],
         };
@@ -526,6 +640,8 @@ mod tests {
             source,
             prompt_index: None,
             prompt_preview: None,
+            generated_at: None,
+            confidence: None,
         }
     }
 }
@@ -1,12 +1,14 @@
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 
 use anyhow::{Context, Result};
-use git2::{BlameOptions, Repository};
+use git2::{BlameOptions, Commit, Oid, Repository};
 
+use crate::capture::encoding;
 use crate::capture::snapshot::LineSource;
 use crate::core::attribution::{AIAttribution, BlameLineResult, BlameResult};
 use crate::storage::notes::NotesStore;
-use crate::utils::{truncate_prompt, PROMPT_PREVIEW_LEN};
+use crate::utils::{repo_path, truncate_prompt, PROMPT_PREVIEW_LEN};
 
 /// AI-aware git blame engine
 pub struct AIBlamer<'a> {
@@ -14,16 +16,23 @@ pub struct AIBlamer<'a> {
     notes_store: NotesStore<'a>,
     /// Cache of attributions by commit ID
     attribution_cache: HashMap<String, Option<AIAttribution>>,
+    /// Blame of merge parents, by parent and path
+    parent_blames: HashMap<(Oid, String), Vec<BlameLineResult>>,
 }
 
 impl<'a> AIBlamer<'a> {
     pub fn new(repo: &'a Repository) -> Result<Self> {
-        let notes_store = NotesStore::new(repo)?;
-        Ok(Self {
+        Ok(Self::with_notes_store(repo, NotesStore::new(repo)?))
+    }
+
+    /// Blame engine reading attribution from a specific notes store
+    pub fn with_notes_store(repo: &'a Repository, notes_store: NotesStore<'a>) -> Self {
+        Self {
             repo,
             notes_store,
             attribution_cache: HashMap::new(),
-        })
+            parent_blames: HashMap::new(),
+        }
     }
 
     /// Run blame on a file and correlate with AI attribution data
@@ -42,11 +51,12 @@ impl<'a> AIBlamer<'a> {
         // Get the file content at this revision
         let tree = commit.tree()?;
         let entry = tree
-            .get_path(std::path::Path::new(path))
+            .get_path(&repo_path::to_path(path))
             .with_context(|| format!("File not found: {}", path))?;
         let blob = self.repo.find_blob(entry.id())?;
-        let content = std::str::from_utf8(blob.content())
-            .with_context(|| format!("File is not valid UTF-8: {}", path))?;
+        let decoded = encoding::decode(blob.content())
+            .with_context(|| format!("File appears to be binary: {}", path))?;
+        let content = decoded.text.as_str();
 
         // Run git blame with move/copy detection
         let mut blame_opts = BlameOptions::new();
@@ -56,7 +66,7 @@ impl<'a> AIBlamer<'a> {
 
         let blame = self
             .repo
-            .blame_file(std::path::Path::new(path), Some(&mut blame_opts))
+            .blame_file(&repo_path::to_path(path), Some(&mut blame_opts))
             .with_context(|| format!("Failed to blame file: {}", path))?;
 
         // Collect unique commits from blame
@@ -75,6 +85,8 @@ impl<'a> AIBlamer<'a> {
         // Process each line
         let lines: Vec<&str> = content.lines().collect();
         let mut results = Vec::new();
+        // Merge hunks resolved against the parents, by hunk start line
+        let mut merge_hunks: HashMap<usize, Vec<Option<BlameLineResult>>> = HashMap::new();
 
         for (idx, line_content) in lines.iter().enumerate() {
             let line_number = (idx + 1) as u32;
@@ -101,9 +113,39 @@ impl<'a> AIBlamer<'a> {
                 let line_offset = line_number.saturating_sub(hunk.final_start_line() as u32);
                 let original_line = hunk.orig_start_line() as u32 + line_offset;
 
+                // Renames and copies are recorded in the note under the original path
+                let orig_path = hunk
+                    .path()
+                    .map(repo_path::from_path)
+                    .unwrap_or_else(|| path.to_string());
+
                 // Look up AI attribution
-                let (source, prompt_index, prompt_preview) =
-                    self.find_line_attribution(&commit_id, path, original_line);
+                let (source, prompt_index, prompt_preview, generated_at, confidence) =
+                    self.find_line_attribution(&commit_id, &orig_path, original_line);
+
+                // A merge's own note only covers lines its resolution wrote
+                if matches!(source, LineSource::Unknown | LineSource::Original) {
+                    let start = hunk.final_start_line();
+                    let end = (start - 1 + hunk.lines_in_hunk()).min(lines.len());
+                    let hunk_lines = &lines[start - 1..end];
+                    let resolved = match merge_hunks.entry(start) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => entry.insert(self.blame_merge_hunk(
+                            hunk.final_commit_id(),
+                            &orig_path,
+                            hunk_lines,
+                        )?),
+                    };
+                    let inherited = resolved.get(line_offset as usize).cloned().flatten();
+                    if let Some(inherited) = inherited {
+                        results.push(BlameLineResult {
+                            line_number,
+                            content: line_content.to_string(),
+                            ..inherited
+                        });
+                        continue;
+                    }
+                }
 
                 results.push(BlameLineResult {
                     line_number,
@@ -114,6 +156,8 @@ impl<'a> AIBlamer<'a> {
                     source,
                     prompt_index,
                     prompt_preview,
+                    generated_at,
+                    confidence,
                 });
             }
         }
@@ -125,6 +169,57 @@ impl<'a> AIBlamer<'a> {
         })
     }
 
+    /// Blame lines of a merge hunk that came from one of the merge's parents
+    ///
+    /// git blame charges a merge with every line that no parent has at the same
+    /// position, including lines the resolution moved or reordered. Each parent is
+    /// searched in order (first parent first) for the hunk's lines, and matched
+    /// lines take that parent's blame. Returns one entry per hunk line; `None` for
+    /// lines the merge wrote itself, and all `None` when `commit_id` is not a merge.
+    fn blame_merge_hunk(
+        &mut self,
+        commit_id: Oid,
+        path: &str,
+        hunk_lines: &[&str],
+    ) -> Result<Vec<Option<BlameLineResult>>> {
+        let mut resolved = vec![None; hunk_lines.len()];
+        let commit = self.repo.find_commit(commit_id)?;
+        if commit.parent_count() < 2 {
+            return Ok(resolved);
+        }
+
+        for parent in commit.parents() {
+            let Some(parent_content) = file_at(self.repo, &parent, path)? else {
+                continue;
+            };
+            let parent_lines: Vec<&str> = parent_content.lines().collect();
+            let positions = match_lines(hunk_lines, &parent_lines);
+            if positions
+                .iter()
+                .zip(&resolved)
+                .all(|(pos, done)| pos.is_none() || done.is_some())
+            {
+                continue;
+            }
+
+            let key = (parent.id(), path.to_string());
+            if !self.parent_blames.contains_key(&key) {
+                let blame = self.blame(path, Some(&parent.id().to_string()))?;
+                self.parent_blames.insert(key.clone(), blame.lines);
+            }
+            let parent_blame = &self.parent_blames[&key];
+            for (slot, pos) in resolved.iter_mut().zip(positions) {
+                if slot.is_none() {
+                    *slot = pos.and_then(|pos| parent_blame.get(pos)).cloned();
+                }
+            }
+            if resolved.iter().all(Option::is_some) {
+                break;
+            }
+        }
+        Ok(resolved)
+    }
+
     /// Pre-fetch attributions for a batch of commits
     fn prefetch_attributions(&mut self, commit_ids: &[String]) -> Result<()> {
         for commit_id in commit_ids {
@@ -144,29 +239,47 @@ impl<'a> AIBlamer<'a> {
         commit_id: &str,
         path: &str,
         line: u32,
-    ) -> (LineSource, Option<u32>, Option<String>) {
+    ) -> (
+        LineSource,
+        Option<u32>,
+        Option<String>,
+        Option<String>,
+        Option<f64>,
+    ) {
         if let Some(Some(attribution)) = self.attribution_cache.get(commit_id) {
             // Find file attribution
             if let Some(file_attr) = attribution.files.iter().find(|f| f.path == path) {
-                // Find line attribution by line number
-                if let Some(line_attr) = file_attr.lines.iter().find(|l| l.line_number == line) {
+                // Precomputed blocks answer large files without scanning lines
+                if let Some(line_attr) = file_attr.line(line) {
                     // Get prompt preview if available
-                    let prompt_preview = line_attr.prompt_index.and_then(|idx| {
-                        attribution
-                            .get_prompt(idx)
-                            .map(|p| truncate_prompt(&p.text, PROMPT_PREVIEW_LEN))
+                    let prompt = line_attr
+                        .prompt_index
+                        .and_then(|idx| attribution.get_prompt(idx));
+                    let prompt_preview =
+                        prompt.map(|p| truncate_prompt(&p.text, PROMPT_PREVIEW_LEN));
+
+                    // Notes written before per-line timestamps fall back to the
+                    // prompt time, then the session start
+                    let generated_at = line_attr.source.is_ai().then(|| {
+                        line_attr
+                            .timestamp
+                            .map(str::to_string)
+                            .or_else(|| prompt.map(|p| p.timestamp.clone()))
+                            .unwrap_or_else(|| attribution.session.started_at.clone())
                     });
 
                     return (
                         line_attr.source.clone(),
                         line_attr.prompt_index,
                         prompt_preview,
+                        generated_at,
+                        line_attr.confidence,
                     );
                 }
             }
         }
         // Default to Unknown if no attribution found
-        (LineSource::Unknown, None, None)
+        (LineSource::Unknown, None, None, None, None)
     }
 
     /// Get attribution for a specific commit
@@ -183,13 +296,48 @@ impl<'a> AIBlamer<'a> {
     }
 }
 
+/// Text content of `path` in a commit, if present and not binary
+fn file_at(repo: &Repository, commit: &Commit, path: &str) -> Result<Option<String>> {
+    let Ok(entry) = commit.tree()?.get_path(&repo_path::to_path(path)) else {
+        return Ok(None);
+    };
+    let blob = repo.find_blob(entry.id())?;
+    Ok(encoding::decode(blob.content()).map(|decoded| decoded.text))
+}
+
+/// Position of each hunk line in a parent's file
+///
+/// A hunk found intact in the parent maps line by line. Otherwise lines are
+/// matched individually when they occur exactly once in the parent; blank and
+/// repeated lines (a lone `}`) stay unmatched rather than guessed.
+fn match_lines(hunk: &[&str], parent: &[&str]) -> Vec<Option<usize>> {
+    if hunk.iter().any(|line| !line.trim().is_empty()) {
+        if let Some(start) = parent.windows(hunk.len()).position(|w| w == hunk) {
+            return (start..start + hunk.len()).map(Some).collect();
+        }
+    }
+
+    let mut occurrences: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, line) in parent.iter().enumerate() {
+        occurrences.entry(line).or_default().push(idx);
+    }
+    hunk.iter()
+        .map(|line| match occurrences.get(line).map(Vec::as_slice) {
+            Some([idx]) if !line.trim().is_empty() => Some(*idx),
+            _ => None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::capture::snapshot::{AttributionSummary, FileAttributionResult, LineAttribution};
     use crate::core::attribution::{ModelInfo, PromptInfo, SessionMetadata, SCHEMA_VERSION};
+    use crate::testing::{generate, Scenario};
     use git2::Signature;
     use std::fs;
+    use std::path::Path;
     use tempfile::TempDir;
 
     /// Create a test repository with a single commit
@@ -294,12 +442,16 @@ mod tests {
                 prompt_count: 1,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![PromptInfo {
                 index: 0,
                 text: "Create hello function with greeting".to_string(),
                 timestamp: "2026-01-30T10:00:00Z".to_string(),
                 affected_files: vec!["test.rs".to_string()],
+                hash: None,
+                privacy: None,
+                tool_calls: Vec::new(),
             }],
             files: vec![FileAttributionResult {
                 path: "test.rs".to_string(),
@@ -307,32 +459,38 @@ mod tests {
                     LineAttribution {
                         line_number: 1,
                         content: "fn hello() {".to_string(),
+                        content_hash: None,
                         source: LineSource::AI {
                             edit_id: "e1".to_string(),
                         },
                         edit_id: Some("e1".to_string()),
                         prompt_index: Some(0),
                         confidence: 1.0,
+                        timestamp: None,
                     },
                     LineAttribution {
                         line_number: 2,
                         content: "    println!(\"hi\");".to_string(),
+                        content_hash: None,
                         source: LineSource::AI {
                             edit_id: "e1".to_string(),
                         },
                         edit_id: Some("e1".to_string()),
                         prompt_index: Some(0),
                         confidence: 1.0,
+                        timestamp: None,
                     },
                     LineAttribution {
                         line_number: 3,
                         content: "}".to_string(),
+                        content_hash: None,
                         source: LineSource::AI {
                             edit_id: "e1".to_string(),
                         },
                         edit_id: Some("e1".to_string()),
                         prompt_index: Some(0),
                         confidence: 1.0,
+                        timestamp: None,
                     },
                 ],
                 summary: AttributionSummary {
@@ -343,6 +501,10 @@ mod tests {
                     original_lines: 0,
                     unknown_lines: 0,
                 },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
             }],
         };
 
@@ -384,6 +546,7 @@ mod tests {
                 prompt_count: 1,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![],
             files: vec![],
@@ -410,4 +573,85 @@ mod tests {
         // Verify it was cached
         assert!(blamer.attribution_cache.contains_key(&commit_str));
     }
+
+    #[test]
+    fn test_blame_octopus_merge_keeps_secondary_parent_lines() {
+        let dir = TempDir::new().unwrap();
+        let fixture = generate(Scenario::OctopusMerge, dir.path()).unwrap();
+        let (beta, gamma, delta) = (fixture.commits[2], fixture.commits[3], fixture.commits[4]);
+        let mut blamer = AIBlamer::new(&fixture.repo).unwrap();
+
+        let lib = blamer.blame("src/lib.rs", None).unwrap();
+        let line = |n: usize| &lib.lines[n - 1];
+        assert_eq!(line(8).commit_id, beta.to_string());
+        assert_eq!(line(13).commit_id, gamma.to_string());
+        assert!(line(8).source.is_ai() && line(13).source.is_ai());
+
+        // Introduced under a merge that is itself the third parent
+        let delta_rs = blamer.blame("src/delta.rs", None).unwrap();
+        assert!(delta_rs
+            .lines
+            .iter()
+            .all(|l| l.commit_id == delta.to_string() && l.source.is_ai()));
+    }
+
+    #[test]
+    fn test_blame_merge_resolution_moving_parent_lines() {
+        let dir = TempDir::new().unwrap();
+        let fixture = generate(Scenario::OctopusMerge, dir.path()).unwrap();
+        let (beta, gamma) = (fixture.commits[2], fixture.commits[3]);
+        let repo = &fixture.repo;
+
+        // Re-resolve the octopus merge with the function bodies swapped
+        let head = repo.find_commit(fixture.head()).unwrap();
+        let parents: Vec<git2::Commit> = head.parents().collect();
+        let content = "// alpha\npub fn alpha() -> u8 {\n    1\n}\n\n// beta\npub fn beta() -> u8 {\n    3\n}\n\n// gamma\npub fn gamma() -> u8 {\n    2\n}\n";
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let mut builder = repo.treebuilder(Some(&head.tree().unwrap())).unwrap();
+        let src = head.tree().unwrap().get_path(Path::new("src")).unwrap();
+        let mut src_builder = repo
+            .treebuilder(Some(&repo.find_tree(src.id()).unwrap()))
+            .unwrap();
+        src_builder.insert("lib.rs", blob, 0o100644).unwrap();
+        builder
+            .insert("src", src_builder.write().unwrap(), 0o040000)
+            .unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let merge = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "Merge with swapped bodies",
+                &tree,
+                &parents.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+
+        let mut blamer = AIBlamer::new(repo).unwrap();
+        let result = blamer
+            .blame("src/lib.rs", Some(&merge.to_string()))
+            .unwrap();
+        let (moved_gamma, moved_beta) = (&result.lines[7], &result.lines[12]);
+        assert_eq!(moved_gamma.content, "    3");
+        assert_eq!(moved_gamma.commit_id, gamma.to_string());
+        assert_eq!(moved_beta.commit_id, beta.to_string());
+        assert!(moved_gamma.source.is_ai() && moved_beta.source.is_ai());
+    }
+
+    #[test]
+    fn test_match_lines() {
+        let parent = ["fn a() {", "    1", "}", "", "fn b() {", "    2", "}"];
+        assert_eq!(
+            match_lines(&["fn b() {", "    2"], &parent),
+            vec![Some(4), Some(5)]
+        );
+        // Not intact: unique lines still match, blank and repeated ones do not
+        assert_eq!(
+            match_lines(&["    2", "}", "", "    1"], &parent),
+            vec![Some(5), None, None, Some(1)]
+        );
+        assert_eq!(match_lines(&[""], &parent), vec![None]);
+    }
 }
@@ -0,0 +1,304 @@
+//! Confidence calibration
+//!
+//! Raw confidence comes from fixed constants in the three-way analyzer (1.0 for
+//! exact matches, 0.85 for context matches, similarity for modified lines), so a
+//! threshold like "confidence >= 0.8" says little about how often attribution is
+//! right. `whogitit annotate-manual` records the true source of individual lines in
+//! `.git/whogitit/calibration.jsonl`; `whogitit calibrate` bins those outcomes by raw
+//! confidence and writes the observed accuracy per bin to `[calibration]` in
+//! `.whogitit.toml`. Commands that emit confidence map it through those bins.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::capture::snapshot::LineSource;
+use crate::core::attribution::AIAttribution;
+use crate::privacy::{CalibrationBin, CalibrationConfig};
+
+/// Labeled outcomes file (inside the git dir)
+const OUTCOMES_FILE: &str = "whogitit/calibration.jsonl";
+
+/// Upper bounds of the calibration bins
+///
+/// Confidence below 0.5 is never emitted, so everything up to 0.5 shares a bin.
+const BIN_EDGES: &[f64] = &[0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+
+/// Weight (in samples) of the raw confidence when smoothing a bin's accuracy,
+/// so a bin fitted on a handful of labels does not jump to 0 or 1
+const PRIOR_WEIGHT: f64 = 2.0;
+
+/// Source kind of a line, as recorded in labeled outcomes
+pub fn source_kind(source: &LineSource) -> &'static str {
+    match source {
+        LineSource::Original => "original",
+        LineSource::AI { .. } => "ai",
+        LineSource::AIModified { .. } => "ai_modified",
+        LineSource::Human => "human",
+        LineSource::Unknown => "unknown",
+    }
+}
+
+/// A line whose true source was labeled by a person
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LabeledOutcome {
+    pub commit: String,
+    pub path: String,
+    pub line: u32,
+    /// Source kind attribution predicted (see [`source_kind`])
+    pub predicted: String,
+    /// Raw confidence of the prediction
+    pub confidence: f64,
+    /// Source kind the line actually has
+    pub actual: String,
+    /// When the label was recorded (ISO 8601)
+    pub labeled_at: String,
+}
+
+impl LabeledOutcome {
+    /// Whether attribution got this line right
+    pub fn is_correct(&self) -> bool {
+        self.predicted == self.actual
+    }
+}
+
+/// Path of the labeled outcomes file for a git dir
+pub fn outcomes_path(git_dir: &Path) -> PathBuf {
+    git_dir.join(OUTCOMES_FILE)
+}
+
+/// Append a labeled outcome
+pub fn record_outcome(git_dir: &Path, outcome: &LabeledOutcome) -> Result<()> {
+    let path = outcomes_path(git_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create whogitit directory")?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(outcome)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Load labeled outcomes, keeping only the latest label per commit line
+pub fn load_outcomes(git_dir: &Path) -> Result<Vec<LabeledOutcome>> {
+    let path = outcomes_path(git_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut outcomes: Vec<LabeledOutcome> = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let outcome: LabeledOutcome = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse {} line {}", path.display(), idx + 1))?;
+        outcomes.retain(|o| {
+            (o.commit.as_str(), o.path.as_str(), o.line)
+                != (outcome.commit.as_str(), outcome.path.as_str(), outcome.line)
+        });
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+/// Fit calibration bins to labeled outcomes (bins without outcomes are omitted)
+pub fn fit(outcomes: &[LabeledOutcome]) -> Vec<CalibrationBin> {
+    let mut bins = Vec::new();
+    let mut min = 0.0;
+    for &max in BIN_EDGES {
+        let in_bin: Vec<&LabeledOutcome> = outcomes
+            .iter()
+            .filter(|o| (o.confidence > min || min == 0.0) && o.confidence <= max)
+            .collect();
+        let bin_min = min;
+        min = max;
+        if in_bin.is_empty() {
+            continue;
+        }
+
+        let samples = in_bin.len() as f64;
+        let correct = in_bin.iter().filter(|o| o.is_correct()).count() as f64;
+        let mean_raw = in_bin.iter().map(|o| o.confidence).sum::<f64>() / samples;
+        let confidence = (correct + PRIOR_WEIGHT * mean_raw) / (samples + PRIOR_WEIGHT);
+        bins.push(CalibrationBin {
+            min: bin_min,
+            max,
+            confidence: (confidence * 1000.0).round() / 1000.0,
+            samples: in_bin.len() as u32,
+        });
+    }
+    bins
+}
+
+/// Calibrated confidence for a raw value (unchanged if no bin covers it)
+///
+/// Bins are checked in order, so a value on the edge of two bins uses the lower one.
+pub fn apply(config: &CalibrationConfig, raw: f64) -> f64 {
+    config
+        .bins
+        .iter()
+        .find(|bin| raw >= bin.min && raw <= bin.max)
+        .map_or(raw, |bin| bin.confidence)
+}
+
+/// Calibrate the confidence of every line in an attribution
+pub fn apply_to_attribution(config: &CalibrationConfig, attribution: &mut AIAttribution) {
+    if config.bins.is_empty() {
+        return;
+    }
+    for line in attribution
+        .files
+        .iter_mut()
+        .flat_map(|f| f.lines.iter_mut())
+    {
+        line.confidence = apply(config, line.confidence);
+    }
+}
+
+/// Write `[calibration]` to a config file, replacing any previous section
+///
+/// The rest of the file is kept as-is, comments included.
+pub fn write_config_section(config_path: &Path, bins: &[CalibrationBin]) -> Result<()> {
+    let existing = if config_path.exists() {
+        fs::read_to_string(config_path)
+            .with_context(|| format!("Failed to read {}", config_path.display()))?
+    } else {
+        String::new()
+    };
+
+    let mut content = strip_section(&existing);
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content.push_str(&render_section(bins));
+
+    fs::write(config_path, content)
+        .with_context(|| format!("Failed to write {}", config_path.display()))
+}
+
+/// Remove `[calibration]` (and its sub-tables) from TOML text
+fn strip_section(content: &str) -> String {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut in_section = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        // Table headers, not rows of a multi-line array
+        if trimmed.starts_with('[') && !trimmed.contains('=') && !trimmed.contains(',') {
+            let name = trimmed.trim_start_matches('[').trim_start();
+            in_section = name.starts_with("calibration]") || name.starts_with("calibration.");
+        }
+        if !in_section {
+            kept.push(line);
+        }
+    }
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+
+    let mut stripped = kept.join("\n");
+    if !stripped.is_empty() {
+        stripped.push('\n');
+    }
+    stripped
+}
+
+fn render_section(bins: &[CalibrationBin]) -> String {
+    let samples: u32 = bins.iter().map(|b| b.samples).sum();
+    let mut section = format!(
+        "[calibration]\n# Fitted by `whogitit calibrate` from {} labeled lines\nbins = [\n",
+        samples
+    );
+    for bin in bins {
+        section.push_str(&format!(
+            "    {{ min = {:?}, max = {:?}, confidence = {:?}, samples = {} }},\n",
+            bin.min, bin.max, bin.confidence, bin.samples
+        ));
+    }
+    section.push_str("]\n");
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::privacy::WhogititConfig;
+    use tempfile::TempDir;
+
+    fn outcome(confidence: f64, correct: bool) -> LabeledOutcome {
+        LabeledOutcome {
+            commit: "abc".to_string(),
+            path: "a.rs".to_string(),
+            line: (confidence * 1000.0) as u32 + u32::from(correct),
+            predicted: "ai".to_string(),
+            confidence,
+            actual: if correct { "ai" } else { "human" }.to_string(),
+            labeled_at: "2026-01-30T10:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fit_and_apply() {
+        // Context matches (0.85) are right half the time, exact matches always
+        let mut outcomes = vec![outcome(0.85, true), outcome(0.85, false)];
+        outcomes.extend((0..8).map(|i| LabeledOutcome {
+            line: 100 + i,
+            ..outcome(1.0, true)
+        }));
+
+        let bins = fit(&outcomes);
+        assert_eq!(bins.len(), 2);
+        assert_eq!((bins[0].max, bins[0].samples), (0.9, 2));
+        assert!((bins[0].confidence - 0.675).abs() < 1e-9);
+        assert_eq!((bins[1].max, bins[1].samples), (1.0, 8));
+        assert_eq!(bins[1].confidence, 1.0);
+
+        let config = CalibrationConfig { bins };
+        assert_eq!(apply(&config, 0.85), 0.675);
+        // Not covered by any bin
+        assert_eq!(apply(&config, 0.6), 0.6);
+        assert_eq!(apply(&CalibrationConfig::default(), 0.85), 0.85);
+    }
+
+    #[test]
+    fn test_write_config_section_replaces_previous() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".whogitit.toml");
+        fs::write(
+            &path,
+            "# team config\n[privacy]\nenabled = true\n\n[calibration]\nbins = []\n\n[review]\ncritical_paths = [\"src/**\"]\n",
+        )
+        .unwrap();
+
+        let bins = fit(&[outcome(0.85, true), outcome(0.85, false)]);
+        write_config_section(&path, &bins).unwrap();
+        write_config_section(&path, &bins).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# team config\n[privacy]"));
+        assert_eq!(content.matches("[calibration]").count(), 1);
+        let config = WhogititConfig::load_from_file(&path).unwrap();
+        assert_eq!(config.calibration.bins, bins);
+        assert_eq!(config.review.critical_paths, vec!["src/**".to_string()]);
+    }
+
+    #[test]
+    fn test_latest_label_wins() {
+        let dir = TempDir::new().unwrap();
+        record_outcome(dir.path(), &outcome(0.85, false)).unwrap();
+        let mut relabeled = outcome(0.85, false);
+        relabeled.actual = "ai".to_string();
+        record_outcome(dir.path(), &relabeled).unwrap();
+
+        assert_eq!(load_outcomes(dir.path()).unwrap(), vec![relabeled]);
+    }
+}
@@ -0,0 +1,151 @@
+//! Attribution decay
+//!
+//! Raw counts treat an AI line committed two years ago like one committed
+//! yesterday, although the older line has usually been read, tested and built
+//! upon since. The decay model gives each AI line a "current relevance" weight of
+//! `0.5^(age / half_life)`, and AI lines a human has since modified a further
+//! `modified_weight`. `stats` and `review-plan` report the weighted score next to
+//! the raw counts, never instead of them.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::capture::snapshot::AttributionSummary;
+use crate::privacy::DecayConfig;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Decay-weighted AI line counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct DecayedLines {
+    pub ai_lines: f64,
+    pub ai_modified_lines: f64,
+}
+
+impl DecayedLines {
+    pub fn add(&mut self, other: DecayedLines) {
+        self.ai_lines += other.ai_lines;
+        self.ai_modified_lines += other.ai_modified_lines;
+    }
+
+    /// AI and AI-modified lines combined
+    pub fn total(&self) -> f64 {
+        self.ai_lines + self.ai_modified_lines
+    }
+}
+
+/// Exponential decay of AI attribution with age
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayModel {
+    half_life_days: f64,
+    modified_weight: f64,
+}
+
+impl DecayModel {
+    pub fn new(half_life_days: f64, modified_weight: f64) -> Result<Self> {
+        if !(half_life_days.is_finite() && half_life_days > 0.0) {
+            bail!(
+                "Decay half-life must be a positive number of days, got {}",
+                half_life_days
+            );
+        }
+        if !(0.0..=1.0).contains(&modified_weight) {
+            bail!(
+                "decay.modified_weight must be between 0.0 and 1.0, got {}",
+                modified_weight
+            );
+        }
+        Ok(Self {
+            half_life_days,
+            modified_weight,
+        })
+    }
+
+    /// Model from `[decay]`, with `half_life_days` overridden by `--half-life`;
+    /// `None` when no half-life is set (decay off)
+    pub fn from_config(config: &DecayConfig, half_life_days: Option<f64>) -> Result<Option<Self>> {
+        match half_life_days.or(config.half_life_days) {
+            Some(days) => Self::new(days, config.modified_weight).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn half_life_days(&self) -> f64 {
+        self.half_life_days
+    }
+
+    /// Weight of a line of the given age: 1.0 when new, 0.5 after one half-life
+    pub fn weight(&self, age_days: f64) -> f64 {
+        0.5f64.powf(age_days.max(0.0) / self.half_life_days)
+    }
+
+    /// Decay-weighted AI lines of a file summary committed `age_days` ago
+    pub fn weigh(&self, summary: &AttributionSummary, age_days: f64) -> DecayedLines {
+        let weight = self.weight(age_days);
+        DecayedLines {
+            ai_lines: summary.ai_lines as f64 * weight,
+            ai_modified_lines: summary.ai_modified_lines as f64 * weight * self.modified_weight,
+        }
+    }
+}
+
+/// Age in days of a commit made at `commit_time` (git seconds since the epoch)
+pub fn age_days(commit_time: i64, now: DateTime<Utc>) -> f64 {
+    (now.timestamp() - commit_time) as f64 / SECONDS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(ai_lines: usize, ai_modified_lines: usize) -> AttributionSummary {
+        AttributionSummary {
+            total_lines: ai_lines + ai_modified_lines,
+            ai_lines,
+            ai_modified_lines,
+            human_lines: 0,
+            original_lines: 0,
+            unknown_lines: 0,
+        }
+    }
+
+    #[test]
+    fn test_weight_halves_every_half_life() {
+        let model = DecayModel::new(30.0, 0.5).unwrap();
+        assert_eq!(model.weight(0.0), 1.0);
+        assert_eq!(model.weight(-5.0), 1.0);
+        assert!((model.weight(30.0) - 0.5).abs() < 1e-9);
+        assert!((model.weight(90.0) - 0.125).abs() < 1e-9);
+
+        let lines = model.weigh(&summary(8, 4), 30.0);
+        assert!((lines.ai_lines - 4.0).abs() < 1e-9);
+        assert!((lines.ai_modified_lines - 1.0).abs() < 1e-9);
+        assert!((lines.total() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = DecayConfig::default();
+        assert!(DecayModel::from_config(&config, None).unwrap().is_none());
+
+        let model = DecayModel::from_config(&config, Some(90.0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(model.half_life_days(), 90.0);
+
+        let configured = DecayConfig {
+            half_life_days: Some(180.0),
+            ..DecayConfig::default()
+        };
+        let model = DecayModel::from_config(&configured, None).unwrap().unwrap();
+        assert_eq!(model.half_life_days(), 180.0);
+
+        assert!(DecayModel::from_config(&config, Some(0.0)).is_err());
+        let bad_weight = DecayConfig {
+            half_life_days: Some(30.0),
+            modified_weight: 1.5,
+        };
+        assert!(DecayModel::from_config(&bad_weight, None).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Vendored and generated path detection for line accounting
+//!
+//! Lock files, vendored dependencies, minified bundles and code generator output
+//! are committed but not written by anyone, so they would skew AI-share
+//! percentages either way. Paths are matched against built-in globs in the spirit
+//! of GitHub Linguist's vendor and generated lists, plus `[generated] paths` from
+//! the whogitit config.
+
+use crate::privacy::GeneratedConfig;
+use crate::utils::GlobSet;
+
+/// Built-in globs for vendored dependencies and generated files
+const BUILTIN_PATTERNS: &[&str] = &[
+    // Vendored dependencies
+    "**/vendor/**",
+    "**/node_modules/**",
+    "**/bower_components/**",
+    "**/third_party/**",
+    "**/third-party/**",
+    "**/Pods/**",
+    "**/Carthage/**",
+    "**/dist/**",
+    // Lock files
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+    // Minified and bundled assets
+    "*.min.js",
+    "*.min.css",
+    "*.js.map",
+    "*.css.map",
+    // Code generator output
+    "**/__generated__/**",
+    "*.pb.go",
+    "*.pb.cc",
+    "*.pb.h",
+    "*_pb2.py",
+    "*_pb2_grpc.py",
+    "*.g.dart",
+    "*.freezed.dart",
+    "*.Designer.cs",
+    "*.designer.cs",
+    "*.generated.*",
+];
+
+/// Detection of vendored and generated paths
+#[derive(Debug, Clone)]
+pub struct GeneratedPaths {
+    patterns: GlobSet,
+}
+
+impl GeneratedPaths {
+    /// Built-in patterns (unless `[generated] builtin = false`) and the config's paths
+    pub fn new(config: &GeneratedConfig) -> Self {
+        let builtin = BUILTIN_PATTERNS
+            .iter()
+            .filter(|_| config.builtin)
+            .map(|pattern| pattern.to_string());
+        Self {
+            patterns: GlobSet::new(
+                &builtin
+                    .chain(config.paths.iter().cloned())
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+
+    /// Detection that matches nothing, for `--include-generated`
+    pub fn none() -> Self {
+        Self {
+            patterns: GlobSet::default(),
+        }
+    }
+
+    /// Whether a repository-relative path is vendored or generated
+    pub fn is_generated(&self, path: &str) -> bool {
+        self.patterns.is_match(path)
+    }
+}
+
+impl Default for GeneratedPaths {
+    fn default() -> Self {
+        Self::new(&GeneratedConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_patterns() {
+        let paths = GeneratedPaths::default();
+        assert!(paths.is_generated("Cargo.lock"));
+        assert!(paths.is_generated("web/package-lock.json"));
+        assert!(paths.is_generated("vendor/github.com/pkg/errors/errors.go"));
+        assert!(paths.is_generated("web/node_modules/react/index.js"));
+        assert!(paths.is_generated("static/app.min.js"));
+        assert!(paths.is_generated("api/v1/service.pb.go"));
+        assert!(paths.is_generated("src/__generated__/schema.ts"));
+        assert!(!paths.is_generated("src/main.rs"));
+        assert!(!paths.is_generated("src/vendored.rs"));
+        assert!(!GeneratedPaths::none().is_generated("Cargo.lock"));
+    }
+
+    #[test]
+    fn test_config_paths_and_builtin_toggle() {
+        let config = GeneratedConfig {
+            builtin: false,
+            paths: vec!["src/schema/**".to_string()],
+        };
+        let paths = GeneratedPaths::new(&config);
+        assert!(paths.is_generated("src/schema/types.rs"));
+        assert!(!paths.is_generated("Cargo.lock"));
+    }
+}
@@ -0,0 +1,203 @@
+//! Line-count integrity checks for attribution notes
+//!
+//! A note's per-file `summary` is computed once, at post-commit time. Format drift
+//! (notes rewritten by older versions, hand edits) and partial staging can leave it
+//! disagreeing with the committed blob or with the note's own per-line data. `show`
+//! and `summary` report such files; `--reconcile` recomputes the summaries from the
+//! stored lines (or blocks) and rewrites the note.
+
+use anyhow::Result;
+use git2::{Commit, Repository};
+use serde::Serialize;
+
+use crate::capture::encoding;
+use crate::capture::snapshot::{AttributionSummary, FileAttributionResult};
+use crate::core::attribution::AIAttribution;
+
+/// A file whose recorded summary disagrees with the blob or the stored lines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineCountIssue {
+    pub path: String,
+    /// `summary.total_lines` in the note
+    pub recorded_lines: usize,
+    /// Lines in the committed blob (`None` if the file is missing or not text)
+    pub blob_lines: Option<usize>,
+    /// Lines covered by the note's per-line data or blocks (`None` if it has neither)
+    pub stored_lines: Option<usize>,
+}
+
+impl LineCountIssue {
+    /// Whether `--reconcile` can change this file's summary
+    pub fn reconcilable(&self) -> bool {
+        self.stored_lines
+            .is_some_and(|stored| stored != self.recorded_lines)
+    }
+}
+
+/// Summary recomputed from a file's stored lines or blocks
+pub fn stored_summary(file: &FileAttributionResult) -> Option<AttributionSummary> {
+    if !file.lines.is_empty() {
+        return Some(FileAttributionResult::compute_summary(&file.lines));
+    }
+    let blocks = file.blocks.as_ref()?;
+    Some(FileAttributionResult::compute_block_summary(blocks))
+}
+
+/// Compare each text file's recorded summary against the commit's blob and the
+/// note's per-line data
+pub fn check_line_counts(
+    repo: &Repository,
+    commit: &Commit,
+    attribution: &AIAttribution,
+) -> Result<Vec<LineCountIssue>> {
+    let tree = commit.tree()?;
+    let mut issues = Vec::new();
+
+    for file in attribution.files.iter().filter(|f| !f.is_binary()) {
+        let blob_lines = tree
+            .get_path(std::path::Path::new(&file.path))
+            .ok()
+            .and_then(|entry| repo.find_blob(entry.id()).ok())
+            .and_then(|blob| encoding::decode(blob.content()))
+            .map(|text| text.text.lines().count());
+        let stored = stored_summary(file);
+
+        let blob_mismatch = blob_lines.is_some_and(|n| n != file.summary.total_lines);
+        let stored_mismatch = stored.as_ref().is_some_and(|s| *s != file.summary);
+        if blob_mismatch || stored_mismatch {
+            issues.push(LineCountIssue {
+                path: file.path.clone(),
+                recorded_lines: file.summary.total_lines,
+                blob_lines,
+                stored_lines: stored.map(|s| s.total_lines),
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Recompute file summaries from stored lines or blocks; returns the paths changed
+pub fn reconcile_summaries(attribution: &mut AIAttribution) -> Vec<String> {
+    let mut changed = Vec::new();
+    for file in attribution.files.iter_mut().filter(|f| !f.is_binary()) {
+        if let Some(summary) = stored_summary(file) {
+            if summary != file.summary {
+                file.summary = summary;
+                changed.push(file.path.clone());
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::{LineAttribution, LineSource};
+    use crate::core::attribution::{ModelInfo, SessionMetadata};
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn line(line_number: u32, source: LineSource) -> LineAttribution {
+        LineAttribution {
+            line_number,
+            content: String::new(),
+            content_hash: None,
+            source,
+            edit_id: None,
+            prompt_index: None,
+            confidence: 1.0,
+            timestamp: None,
+        }
+    }
+
+    fn attribution(lines: Vec<LineAttribution>, recorded_total: usize) -> AIAttribution {
+        let mut summary = FileAttributionResult::compute_summary(&lines);
+        summary.total_lines = recorded_total;
+        AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 0,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files: vec![FileAttributionResult {
+                path: "a.rs".to_string(),
+                lines,
+                summary,
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_check_and_reconcile_line_counts() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a.rs"), "one\ntwo\n").unwrap();
+        let oid = {
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new("a.rs")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "add", &tree, &[])
+                .unwrap()
+        };
+        let commit = repo.find_commit(oid).unwrap();
+        let edit = || LineSource::AI {
+            edit_id: "e".to_string(),
+        };
+        let lines = || vec![line(1, edit()), line(2, LineSource::Original)];
+
+        let consistent = attribution(lines(), 2);
+        assert!(check_line_counts(&repo, &commit, &consistent)
+            .unwrap()
+            .is_empty());
+
+        let mut drifted = attribution(lines(), 5);
+        let issues = check_line_counts(&repo, &commit, &drifted).unwrap();
+        assert_eq!(
+            issues,
+            vec![LineCountIssue {
+                path: "a.rs".to_string(),
+                recorded_lines: 5,
+                blob_lines: Some(2),
+                stored_lines: Some(2),
+            }]
+        );
+        assert!(issues[0].reconcilable());
+
+        assert_eq!(reconcile_summaries(&mut drifted), vec!["a.rs".to_string()]);
+        assert_eq!(drifted.files[0].summary.total_lines, 2);
+        assert!(check_line_counts(&repo, &commit, &drifted)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stored_summary_from_blocks() {
+        let mut file = attribution(
+            vec![
+                line(1, LineSource::Human),
+                line(2, LineSource::Human),
+                line(3, LineSource::Original),
+            ],
+            3,
+        )
+        .files
+        .remove(0);
+        let expected = file.summary.clone();
+        file.collapse_to_blocks();
+
+        assert_eq!(stored_summary(&file), Some(expected));
+    }
+}
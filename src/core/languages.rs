@@ -0,0 +1,170 @@
+//! Language detection for per-language AI share
+//!
+//! Languages are detected from the file extension (or a few well-known file names)
+//! using a built-in map in the spirit of GitHub Linguist. `[languages] extensions`
+//! in the whogitit config adds extensions or overrides built-in ones.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::privacy::LanguagesConfig;
+
+/// Language reported for files no mapping matches
+pub const OTHER_LANGUAGE: &str = "Other";
+
+/// Built-in extension to language map (extensions are lowercase, without the dot)
+const BUILTIN_EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("go", "Go"),
+    ("py", "Python"),
+    ("pyi", "Python"),
+    ("rb", "Ruby"),
+    ("js", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("mts", "TypeScript"),
+    ("cts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("scala", "Scala"),
+    ("swift", "Swift"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("cxx", "C++"),
+    ("hh", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("fs", "F#"),
+    ("php", "PHP"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("erl", "Erlang"),
+    ("hs", "Haskell"),
+    ("ml", "OCaml"),
+    ("clj", "Clojure"),
+    ("lua", "Lua"),
+    ("dart", "Dart"),
+    ("zig", "Zig"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("ps1", "PowerShell"),
+    ("sql", "SQL"),
+    ("tf", "Terraform"),
+    ("tfvars", "Terraform"),
+    ("hcl", "HCL"),
+    ("proto", "Protocol Buffers"),
+    ("graphql", "GraphQL"),
+    ("gql", "GraphQL"),
+    ("html", "HTML"),
+    ("htm", "HTML"),
+    ("css", "CSS"),
+    ("scss", "SCSS"),
+    ("sass", "Sass"),
+    ("less", "Less"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("md", "Markdown"),
+    ("mdx", "MDX"),
+    ("rst", "reStructuredText"),
+    ("json", "JSON"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+    ("toml", "TOML"),
+    ("xml", "XML"),
+    ("nix", "Nix"),
+];
+
+/// Built-in file name to language map for files without a telling extension
+const BUILTIN_FILE_NAMES: &[(&str, &str)] = &[
+    ("Dockerfile", "Dockerfile"),
+    ("Containerfile", "Dockerfile"),
+    ("Makefile", "Makefile"),
+    ("GNUmakefile", "Makefile"),
+    ("CMakeLists.txt", "CMake"),
+    ("Jenkinsfile", "Groovy"),
+    ("Gemfile", "Ruby"),
+    ("Rakefile", "Ruby"),
+];
+
+/// Extension-based language detection
+#[derive(Debug, Clone)]
+pub struct LanguageMap {
+    extensions: HashMap<String, String>,
+}
+
+impl LanguageMap {
+    /// Built-in map with the config's extensions added on top
+    pub fn new(config: &LanguagesConfig) -> Self {
+        let mut extensions: HashMap<String, String> = BUILTIN_EXTENSIONS
+            .iter()
+            .map(|(ext, lang)| (ext.to_string(), lang.to_string()))
+            .collect();
+        for (ext, lang) in &config.extensions {
+            let ext = ext.trim_start_matches('.').to_ascii_lowercase();
+            extensions.insert(ext, lang.clone());
+        }
+        Self { extensions }
+    }
+
+    /// Language of a repository-relative path ([`OTHER_LANGUAGE`] if unknown)
+    pub fn detect(&self, path: &str) -> &str {
+        let path = Path::new(path);
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        if let Some(lang) = extension.and_then(|ext| self.extensions.get(&ext)) {
+            return lang;
+        }
+
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        BUILTIN_FILE_NAMES
+            .iter()
+            .find(|(name, _)| *name == file_name)
+            .map_or(OTHER_LANGUAGE, |(_, lang)| lang)
+    }
+}
+
+impl Default for LanguageMap {
+    fn default() -> Self {
+        Self::new(&LanguagesConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_detect_builtin_languages() {
+        let map = LanguageMap::default();
+        assert_eq!(map.detect("infra/main.tf"), "Terraform");
+        assert_eq!(map.detect("src/lib.RS"), "Rust");
+        assert_eq!(map.detect("web/App.tsx"), "TypeScript");
+        assert_eq!(map.detect("docker/Dockerfile"), "Dockerfile");
+        assert_eq!(map.detect("LICENSE"), OTHER_LANGUAGE);
+        assert_eq!(map.detect("data.unknownext"), OTHER_LANGUAGE);
+    }
+
+    #[test]
+    fn test_config_extensions_add_and_override() {
+        let config = LanguagesConfig {
+            extensions: BTreeMap::from([
+                (".tpl".to_string(), "Terraform".to_string()),
+                ("h".to_string(), "C++".to_string()),
+            ]),
+        };
+        let map = LanguageMap::new(&config);
+        assert_eq!(map.detect("modules/vpc.tpl"), "Terraform");
+        assert_eq!(map.detect("include/widget.h"), "C++");
+        assert_eq!(map.detect("src/main.c"), "C");
+    }
+}
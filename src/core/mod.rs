@@ -1,5 +1,21 @@
+pub mod analytics;
 pub mod attribution;
+#[cfg(feature = "git")]
 pub mod blame;
+pub mod calibration;
+pub mod decay;
+pub mod generated;
+#[cfg(feature = "git")]
+pub mod integrity;
+pub mod languages;
+pub mod packages;
+#[cfg(feature = "git")]
+pub mod policy;
+pub mod query;
+#[cfg(feature = "git")]
+pub mod sparse;
+pub mod symbols;
 
 pub use attribution::*;
+#[cfg(feature = "git")]
 pub use blame::AIBlamer;
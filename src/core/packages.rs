@@ -0,0 +1,342 @@
+//! Package boundary detection for monorepos
+//!
+//! Packages come from Cargo workspace members, npm/yarn `workspaces` in the root
+//! `package.json`, and `[packages] roots` globs in the whogitit config. Files are
+//! assigned to the package with the deepest root containing them.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::privacy::PackagesConfig;
+use crate::utils::GlobSet;
+
+/// Directories never scanned for packages
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor"];
+/// Directory depth scanned for patterns containing `**`
+const MAX_SCAN_DEPTH: usize = 8;
+
+/// A package in the repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// Package name from its manifest, or its root path
+    pub name: String,
+    /// Root directory relative to the repository root (no trailing slash)
+    pub root: String,
+}
+
+/// Packages detected in a working tree
+#[derive(Debug, Default)]
+pub struct PackageMap {
+    /// Sorted by root
+    packages: Vec<Package>,
+}
+
+impl PackageMap {
+    /// Detect packages in a working tree
+    pub fn detect(workdir: &Path, config: &PackagesConfig) -> Result<Self> {
+        let mut patterns = Vec::new();
+        let mut excludes = Vec::new();
+
+        if let Some((members, exclude)) = cargo_workspace_members(workdir)? {
+            patterns.extend(members);
+            excludes.extend(exclude);
+        }
+        if let Some(workspaces) = npm_workspaces(workdir)? {
+            for pattern in workspaces {
+                match pattern.strip_prefix('!') {
+                    Some(negated) => excludes.push(negated.to_string()),
+                    None => patterns.push(pattern),
+                }
+            }
+        }
+        patterns.extend(config.roots.iter().cloned());
+
+        let mut roots = expand_dir_patterns(workdir, &patterns);
+        let excluded = expand_dir_patterns(workdir, &excludes);
+        roots.retain(|root| !excluded.contains(root));
+
+        let packages = roots
+            .into_iter()
+            .map(|root| Package {
+                name: package_name(&workdir.join(&root)).unwrap_or_else(|| root.clone()),
+                root,
+            })
+            .collect();
+        Ok(Self::from_packages(packages))
+    }
+
+    /// Build a map from known packages
+    pub fn from_packages(mut packages: Vec<Package>) -> Self {
+        packages.sort_by(|a, b| a.root.cmp(&b.root));
+        packages.dedup_by(|a, b| a.root == b.root);
+        Self { packages }
+    }
+
+    /// Detected packages, sorted by root
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Package containing a repository-relative path (deepest root wins)
+    pub fn package_for(&self, path: &str) -> Option<&Package> {
+        self.packages
+            .iter()
+            .filter(|p| {
+                path.strip_prefix(p.root.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|p| p.root.len())
+    }
+}
+
+/// `[workspace] members` and `exclude` from the root Cargo.toml
+fn cargo_workspace_members(workdir: &Path) -> Result<Option<(Vec<String>, Vec<String>)>> {
+    let path = workdir.join("Cargo.toml");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let manifest: toml::Value =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    let Some(workspace) = manifest.get("workspace") else {
+        return Ok(None);
+    };
+    let strings = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Ok(Some((strings("members"), strings("exclude"))))
+}
+
+/// `workspaces` from the root package.json (array or `{ "packages": [...] }`)
+fn npm_workspaces(workdir: &Path) -> Result<Option<Vec<String>>> {
+    let path = workdir.join("package.json");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let manifest: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let workspaces = match manifest.get("workspaces") {
+        Some(serde_json::Value::Array(items)) => items,
+        Some(serde_json::Value::Object(obj)) => match obj.get("packages") {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(
+        workspaces
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+    ))
+}
+
+/// Name from a package's Cargo.toml or package.json
+fn package_name(dir: &Path) -> Option<String> {
+    if let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) {
+        let name = toml::from_str::<toml::Value>(&content)
+            .ok()
+            .and_then(|m| m.get("package")?.get("name")?.as_str().map(str::to_string));
+        if name.is_some() {
+            return name;
+        }
+    }
+    let content = fs::read_to_string(dir.join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    manifest.get("name")?.as_str().map(str::to_string)
+}
+
+/// Normalize a directory pattern so it is matched against the full relative path
+fn normalize_pattern(pattern: &str) -> String {
+    let pattern = pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/');
+    format!("/{}", pattern.trim_start_matches('/'))
+}
+
+fn has_wildcard(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Resolve directory patterns to existing directories relative to `workdir`
+fn expand_dir_patterns(workdir: &Path, patterns: &[String]) -> Vec<String> {
+    let mut literal = Vec::new();
+    let mut globs = Vec::new();
+    for pattern in patterns {
+        let normalized = normalize_pattern(pattern);
+        if normalized == "/" {
+            continue;
+        }
+        if has_wildcard(&normalized) {
+            globs.push(normalized);
+        } else {
+            literal.push(normalized[1..].to_string());
+        }
+    }
+
+    let mut dirs: Vec<String> = literal
+        .into_iter()
+        .filter(|dir| workdir.join(dir).is_dir())
+        .collect();
+
+    if !globs.is_empty() {
+        let depth = if globs.iter().any(|g| g.contains("**")) {
+            MAX_SCAN_DEPTH
+        } else {
+            globs
+                .iter()
+                .map(|g| g.matches('/').count())
+                .max()
+                .unwrap_or(1)
+        };
+        let matcher = GlobSet::new(&globs);
+        let mut candidates = Vec::new();
+        collect_dirs(workdir, "", depth, &mut candidates);
+        dirs.extend(candidates.into_iter().filter(|dir| matcher.is_match(dir)));
+    }
+
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Collect directories below `base/prefix` up to `depth` levels
+fn collect_dirs(base: &Path, prefix: &str, depth: usize, out: &mut Vec<String>) {
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(base.join(prefix)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+        let rel = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        collect_dirs(base, &rel, depth - 1, out);
+        out.push(rel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_detect_cargo_and_npm_workspaces() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\nexclude = [\"crates/scratch\"]\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"acme-core\"\n",
+        );
+        write(
+            root,
+            "crates/scratch/Cargo.toml",
+            "[package]\nname = \"scratch\"\n",
+        );
+        write(
+            root,
+            "tools/cli/Cargo.toml",
+            "[package]\nname = \"acme-cli\"\n",
+        );
+        write(
+            root,
+            "package.json",
+            r#"{"workspaces": {"packages": ["web/*"]}}"#,
+        );
+        write(root, "web/app/package.json", r#"{"name": "@acme/app"}"#);
+        write(root, "web/node_modules/x/package.json", "{}");
+
+        let map = PackageMap::detect(root, &PackagesConfig::default()).unwrap();
+        let names: Vec<(&str, &str)> = map
+            .packages()
+            .iter()
+            .map(|p| (p.root.as_str(), p.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("crates/core", "acme-core"),
+                ("tools/cli", "acme-cli"),
+                ("web/app", "@acme/app"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_config_roots() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "services/billing/main.go", "package main\n");
+        write(root, "services/auth/main.go", "package main\n");
+        write(root, "libs/shared/util/README.md", "");
+
+        let config = PackagesConfig {
+            roots: vec!["services/*".to_string(), "libs/**/util".to_string()],
+        };
+        let map = PackageMap::detect(root, &config).unwrap();
+        let roots: Vec<&str> = map.packages().iter().map(|p| p.root.as_str()).collect();
+        assert_eq!(
+            roots,
+            vec!["libs/shared/util", "services/auth", "services/billing"]
+        );
+        // No manifest: the root path doubles as the name
+        assert_eq!(map.packages()[1].name, "services/auth");
+    }
+
+    #[test]
+    fn test_package_for_prefers_deepest_root() {
+        let map = PackageMap::from_packages(vec![
+            Package {
+                name: "app".to_string(),
+                root: "apps/web".to_string(),
+            },
+            Package {
+                name: "apps".to_string(),
+                root: "apps".to_string(),
+            },
+        ]);
+
+        assert_eq!(map.package_for("apps/web/src/main.ts").unwrap().name, "app");
+        assert_eq!(map.package_for("apps/api/main.ts").unwrap().name, "apps");
+        assert!(map.package_for("apps-old/main.ts").is_none());
+        assert!(map.package_for("README.md").is_none());
+    }
+}
@@ -0,0 +1,686 @@
+//! Policy evaluation - attribution checks as structured violations
+//!
+//! Evaluates a commit range against the checks whogitit already enforces from the
+//! CLI: commits without attribution (`summary --fail-on-missing-attribution`), AI
+//! code in `[annotations.sensitive_paths]` and AI code in `[review] critical_paths`,
+//! plus the gates of the `[policy]` section (AI share per file, forbidden paths,
+//! human review).
+//! Each finding is a [`Violation`] with a stable rule id, so bots and merge-queue
+//! services can act on the [`PolicyReport`] (schema [`POLICY_SCHEMA`]) directly
+//! instead of parsing terminal output.
+
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::{Context, Result};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::core::blame::AIBlamer;
+use crate::privacy::{SensitivePathLevel, WhogititConfig};
+use crate::storage::notes::NotesStore;
+use crate::storage::trailers::TrailerParser;
+use crate::utils::GlobSet;
+
+/// Schema identifier of serialized policy reports
+pub const POLICY_SCHEMA: &str = "whogitit.policy.v1";
+
+/// Version of the policy report schema; bumped on incompatible changes
+pub const POLICY_SCHEMA_VERSION: u8 = 1;
+
+/// Rule: a commit changes source files but has no attribution
+pub const RULE_MISSING_ATTRIBUTION: &str = "missing-attribution";
+
+/// Rule: AI lines in a path listed in `[annotations.sensitive_paths]`
+pub const RULE_SENSITIVE_PATH: &str = "sensitive-path";
+
+/// Rule: AI lines in a path listed in `[review] critical_paths`
+pub const RULE_CRITICAL_PATH: &str = "critical-path";
+
+/// Rule: a file's AI share at head exceeds `[policy] max_ai_percent`
+pub const RULE_MAX_AI_PERCENT: &str = "max-ai-percent";
+
+/// Rule: AI lines in a path listed in `[policy] forbidden_paths`
+pub const RULE_FORBIDDEN_PATH: &str = "forbidden-path";
+
+/// Rule: a commit with AI lines has no `Reviewed-by:` trailer (`[policy] require_human_review`)
+pub const RULE_HUMAN_REVIEW: &str = "human-review";
+
+/// File extensions treated as source code by the missing-attribution rule
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "pyi", "js", "jsx", "mjs", "cjs", "ts", "tsx", "go", "java", "kt", "kts", "scala",
+    "swift", "c", "h", "cc", "cpp", "cxx", "hpp", "cs", "rb", "php", "sh", "bash", "zsh", "lua",
+    "ex", "exs", "erl", "hs", "ml", "clj", "dart", "vue", "svelte", "sql",
+];
+
+/// Severity of a violation, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl From<SensitivePathLevel> for Severity {
+    fn from(level: SensitivePathLevel) -> Self {
+        match level {
+            SensitivePathLevel::Notice => Severity::Info,
+            SensitivePathLevel::Warning => Severity::Warning,
+            SensitivePathLevel::Failure => Severity::Error,
+        }
+    }
+}
+
+/// Inclusive range of line numbers (1-indexed) at the head revision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// One policy finding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    /// Stable rule identifier (e.g. "sensitive-path")
+    pub rule_id: String,
+    pub severity: Severity,
+    /// File the violation is about, if any
+    pub path: Option<String>,
+    /// Commit the violation is about, if any
+    pub commit: Option<String>,
+    /// Lines at the head revision the violation covers (empty for commit-level rules)
+    #[serde(default)]
+    pub lines: Vec<LineRange>,
+    /// Why the rule fired
+    pub evidence: String,
+}
+
+/// Result of evaluating a commit range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyReport {
+    pub schema_version: u8,
+    pub schema: String,
+    /// Base revision (exclusive), as given
+    pub base: Option<String>,
+    /// Head commit (inclusive), resolved
+    pub head: String,
+    pub commits_evaluated: usize,
+    /// Whether no violation has error severity
+    pub passed: bool,
+    /// Sorted by severity (most severe first), then path and commit
+    pub violations: Vec<Violation>,
+}
+
+impl PolicyReport {
+    /// Number of violations at a severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.violations
+            .iter()
+            .filter(|v| v.severity == severity)
+            .count()
+    }
+}
+
+/// Rules that are not driven by configuration
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PolicyOptions {
+    /// Report commits that change source files without attribution as errors
+    pub require_attribution: bool,
+}
+
+/// Evaluate the commits in `base..head` (all of `head`'s history without a base)
+pub fn evaluate(
+    repo: &Repository,
+    config: &WhogititConfig,
+    base: Option<&str>,
+    head: &str,
+    options: PolicyOptions,
+) -> Result<PolicyReport> {
+    let notes_store = NotesStore::new(repo)?;
+    let head_commit = repo
+        .revparse_single(head)
+        .with_context(|| format!("Failed to resolve: {}", head))?
+        .peel_to_commit()
+        .with_context(|| format!("Not a valid commit: {}", head))?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_commit.id())?;
+    if let Some(base) = base {
+        let base_commit = repo
+            .revparse_single(base)
+            .with_context(|| format!("Failed to resolve base: {}", base))?
+            .peel_to_commit()
+            .with_context(|| format!("Not a valid commit: {}", base))?;
+        revwalk.hide(base_commit.id())?;
+    }
+
+    let mut violations = Vec::new();
+    let mut range: HashSet<Oid> = HashSet::new();
+    let mut ai_paths: BTreeSet<String> = BTreeSet::new();
+
+    let policy = &config.policy;
+    for oid in revwalk {
+        let oid = oid?;
+        range.insert(oid);
+
+        let attribution = notes_store.fetch_attribution(oid).ok().flatten();
+        if let Some(attribution) = &attribution {
+            ai_paths.extend(
+                attribution
+                    .files
+                    .iter()
+                    .filter(|f| f.summary.ai_lines + f.summary.ai_modified_lines > 0)
+                    .map(|f| f.path.clone()),
+            );
+        }
+        let check_attribution = attribution.is_none() && options.require_attribution;
+        if !check_attribution && !policy.require_human_review {
+            continue;
+        }
+        let commit = repo.find_commit(oid)?;
+        let trailers = TrailerParser::parse(commit.message().unwrap_or(""));
+
+        if policy.require_human_review && trailers.reviewed_by.is_empty() {
+            let ai_lines = match &attribution {
+                Some(attribution) => attribution
+                    .files
+                    .iter()
+                    .map(|f| f.summary.ai_lines + f.summary.ai_modified_lines)
+                    .sum(),
+                None => trailers.ai_lines.unwrap_or(0) + trailers.ai_modified_lines.unwrap_or(0),
+            };
+            if ai_lines > 0 {
+                violations.push(Violation {
+                    rule_id: RULE_HUMAN_REVIEW.to_string(),
+                    severity: Severity::Error,
+                    path: None,
+                    commit: Some(oid.to_string()),
+                    lines: Vec::new(),
+                    evidence: format!(
+                        "\"{}\" has {} AI line(s) but no Reviewed-by trailer",
+                        commit.summary().unwrap_or(""),
+                        ai_lines
+                    ),
+                });
+            }
+        }
+
+        if !check_attribution || trailers.has_attribution() {
+            continue;
+        }
+        let source_files = changed_source_files(repo, &commit)?;
+        if source_files > 0 {
+            violations.push(Violation {
+                rule_id: RULE_MISSING_ATTRIBUTION.to_string(),
+                severity: Severity::Error,
+                path: None,
+                commit: Some(oid.to_string()),
+                lines: Vec::new(),
+                evidence: format!(
+                    "\"{}\" changes {} source file(s) but has no whogitit attribution",
+                    commit.summary().unwrap_or(""),
+                    source_files
+                ),
+            });
+        }
+    }
+
+    let head_rev = head_commit.id().to_string();
+    let mut blamer = AIBlamer::new(repo)?;
+    let sensitive_paths = config.annotations.matcher();
+    let critical_paths = GlobSet::new(&config.review.critical_paths);
+    let forbidden_paths = GlobSet::new(&policy.forbidden_paths);
+    for path in &ai_paths {
+        let sensitive = sensitive_paths.level_for(path);
+        let critical = critical_paths.is_match(path);
+        let forbidden = forbidden_paths.is_match(path);
+        if sensitive.is_none() && !critical && !policy.has_file_gates() {
+            continue;
+        }
+        // AI lines added in the range that are still there at head
+        let Ok(blame) = blamer.blame(path, Some(&head_rev)) else {
+            continue;
+        };
+        let ai_lines: Vec<u32> = blame
+            .lines
+            .iter()
+            .filter(|line| line.source.is_ai())
+            .filter(|line| Oid::from_str(&line.commit_id).is_ok_and(|oid| range.contains(&oid)))
+            .map(|line| line.line_number)
+            .collect();
+        if ai_lines.is_empty() {
+            continue;
+        }
+        let lines = line_ranges(&ai_lines);
+
+        if forbidden {
+            violations.push(Violation {
+                rule_id: RULE_FORBIDDEN_PATH.to_string(),
+                severity: Severity::Error,
+                path: Some(path.clone()),
+                commit: None,
+                lines: lines.clone(),
+                evidence: format!(
+                    "{} AI line(s) in a path listed in [policy] forbidden_paths",
+                    ai_lines.len()
+                ),
+            });
+        }
+        let total = blame.lines.len();
+        if let Some(limit) = policy.max_ai_percent {
+            let ai_total = blame.lines.iter().filter(|l| l.source.is_ai()).count();
+            let percent = ai_total as f64 * 100.0 / total as f64;
+            if total >= policy.min_file_lines && percent > limit {
+                violations.push(Violation {
+                    rule_id: RULE_MAX_AI_PERCENT.to_string(),
+                    severity: Severity::Error,
+                    path: Some(path.clone()),
+                    commit: None,
+                    lines: lines.clone(),
+                    evidence: format!(
+                        "{:.1}% of {} line(s) are AI-written at head ([policy] max_ai_percent = {})",
+                        percent, total, limit
+                    ),
+                });
+            }
+        }
+        if let Some(level) = sensitive {
+            violations.push(Violation {
+                rule_id: RULE_SENSITIVE_PATH.to_string(),
+                severity: level.into(),
+                path: Some(path.clone()),
+                commit: None,
+                lines: lines.clone(),
+                evidence: format!(
+                    "{} AI line(s) in a path marked {} by [annotations.sensitive_paths]",
+                    ai_lines.len(),
+                    level_name(level)
+                ),
+            });
+        }
+        if critical {
+            violations.push(Violation {
+                rule_id: RULE_CRITICAL_PATH.to_string(),
+                severity: Severity::Warning,
+                path: Some(path.clone()),
+                commit: None,
+                lines,
+                evidence: format!(
+                    "{} AI line(s) in a path listed in [review] critical_paths",
+                    ai_lines.len()
+                ),
+            });
+        }
+    }
+
+    violations.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.commit.cmp(&b.commit))
+    });
+
+    Ok(PolicyReport {
+        schema_version: POLICY_SCHEMA_VERSION,
+        schema: POLICY_SCHEMA.to_string(),
+        base: base.map(str::to_string),
+        head: head_rev,
+        commits_evaluated: range.len(),
+        passed: !violations.iter().any(|v| v.severity == Severity::Error),
+        violations,
+    })
+}
+
+fn level_name(level: SensitivePathLevel) -> &'static str {
+    match level {
+        SensitivePathLevel::Notice => "notice",
+        SensitivePathLevel::Warning => "warning",
+        SensitivePathLevel::Failure => "failure",
+    }
+}
+
+/// Collapse sorted line numbers into contiguous ranges
+fn line_ranges(lines: &[u32]) -> Vec<LineRange> {
+    let mut ranges: Vec<LineRange> = Vec::new();
+    for &line in lines {
+        match ranges.last_mut() {
+            Some(range) if range.end + 1 == line => range.end = line,
+            _ => ranges.push(LineRange {
+                start: line,
+                end: line,
+            }),
+        }
+    }
+    ranges
+}
+
+/// Whether a path looks like source code (by extension)
+pub fn is_source_path(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SOURCE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Count source files changed by a commit relative to its first parent.
+/// Merge commits are skipped since they carry no authored changes of their own.
+pub fn changed_source_files(repo: &Repository, commit: &git2::Commit) -> Result<usize> {
+    if commit.parent_count() > 1 {
+        return Ok(0);
+    }
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    Ok(diff
+        .deltas()
+        .filter(|delta| delta.status() != git2::Delta::Deleted)
+        .filter_map(|delta| delta.new_file().path())
+        .filter(|path| is_source_path(&path.to_string_lossy()))
+        .count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::{
+        AttributionSummary, FileAttributionResult, LineAttribution, LineSource,
+    };
+    use crate::core::attribution::{AIAttribution, ModelInfo, SessionMetadata, SCHEMA_VERSION};
+
+    #[test]
+    fn test_is_source_path() {
+        assert!(is_source_path("src/main.rs"));
+        assert!(is_source_path("web/App.TSX"));
+        assert!(!is_source_path("README.md"));
+        assert!(!is_source_path("Makefile"));
+    }
+
+    #[test]
+    fn test_changed_source_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+
+        let commit_files = |files: &[(&str, &str)], parents: &[&git2::Commit]| {
+            let mut index = repo.index().unwrap();
+            for (path, content) in files {
+                std::fs::write(dir.path().join(path), content).unwrap();
+                index.add_path(std::path::Path::new(path)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let oid = repo
+                .commit(Some("HEAD"), &sig, &sig, "msg", &tree, parents)
+                .unwrap();
+            repo.find_commit(oid).unwrap()
+        };
+
+        let root = commit_files(&[("lib.rs", "fn a() {}\n")], &[]);
+        let docs = commit_files(&[("README.md", "docs\n")], &[&root]);
+        let code = commit_files(&[("main.py", "print(1)\n")], &[&docs]);
+
+        assert_eq!(changed_source_files(&repo, &root).unwrap(), 1);
+        assert_eq!(changed_source_files(&repo, &docs).unwrap(), 0);
+        assert_eq!(changed_source_files(&repo, &code).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_evaluate_reports_structured_violations() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        let commit_file = |path: &str, content: &str, message: &str| {
+            let full = dir.path().join(path);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(&full, content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new(path)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = repo
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .into_iter()
+                .collect();
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+
+        let base = commit_file("README.md", "# Demo\n", "Initial");
+        let ai_commit = commit_file(
+            "src/auth/login.rs",
+            "fn login() {}\nfn check() {}\nfn human() {}\n",
+            "Add login",
+        );
+        let ai = || LineSource::AI {
+            edit_id: "e1".to_string(),
+        };
+        let line = |n: u32, source: LineSource| LineAttribution {
+            line_number: n,
+            content: String::new(),
+            content_hash: None,
+            source,
+            edit_id: None,
+            prompt_index: Some(0),
+            confidence: 1.0,
+            timestamp: None,
+        };
+        let attribution = AIAttribution {
+            version: SCHEMA_VERSION,
+            session: SessionMetadata {
+                session_id: "session-1".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 1,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files: vec![FileAttributionResult {
+                path: "src/auth/login.rs".to_string(),
+                lines: vec![line(1, ai()), line(2, ai()), line(3, LineSource::Human)],
+                summary: AttributionSummary {
+                    total_lines: 3,
+                    ai_lines: 2,
+                    ai_modified_lines: 0,
+                    human_lines: 1,
+                    original_lines: 0,
+                    unknown_lines: 0,
+                },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
+            }],
+        };
+        NotesStore::new(&repo)
+            .unwrap()
+            .store_attribution(ai_commit, &attribution)
+            .unwrap();
+        let unattributed = commit_file("tools/gen.py", "print(1)\n", "Add generator");
+
+        let config: WhogititConfig = toml::from_str(
+            "[annotations.sensitive_paths]\n\"src/auth/**\" = \"failure\"\n\n[review]\ncritical_paths = [\"src/**\"]\n",
+        )
+        .unwrap();
+        let options = PolicyOptions {
+            require_attribution: true,
+        };
+        let report = evaluate(&repo, &config, Some(&base.to_string()), "HEAD", options).unwrap();
+
+        assert_eq!(report.commits_evaluated, 2);
+        assert!(!report.passed);
+        let rules: Vec<(&str, Severity)> = report
+            .violations
+            .iter()
+            .map(|v| (v.rule_id.as_str(), v.severity))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (RULE_MISSING_ATTRIBUTION, Severity::Error),
+                (RULE_SENSITIVE_PATH, Severity::Error),
+                (RULE_CRITICAL_PATH, Severity::Warning),
+            ]
+        );
+        assert_eq!(report.violations[0].commit, Some(unattributed.to_string()));
+        assert_eq!(
+            report.violations[1].lines,
+            vec![LineRange { start: 1, end: 2 }]
+        );
+
+        // Round-trips through the published schema
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["schema"], POLICY_SCHEMA);
+        assert_eq!(json["violations"][1]["rule_id"], "sensitive-path");
+        assert_eq!(json["violations"][1]["severity"], "error");
+        let parsed: PolicyReport = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.violations, report.violations);
+    }
+
+    #[test]
+    fn test_evaluate_policy_gates() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        let commit_files = |files: &[(&str, &str)], message: &str| {
+            let mut index = repo.index().unwrap();
+            for (path, content) in files {
+                let full = dir.path().join(path);
+                std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+                std::fs::write(&full, content).unwrap();
+                index.add_path(std::path::Path::new(path)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = repo
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .into_iter()
+                .collect();
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+        let file = |path: &str, sources: Vec<LineSource>| {
+            let lines: Vec<LineAttribution> = sources
+                .into_iter()
+                .enumerate()
+                .map(|(i, source)| LineAttribution {
+                    line_number: i as u32 + 1,
+                    content: String::new(),
+                    content_hash: None,
+                    source,
+                    edit_id: None,
+                    prompt_index: Some(0),
+                    confidence: 1.0,
+                    timestamp: None,
+                })
+                .collect();
+            let ai_lines = lines.iter().filter(|l| l.source.is_ai()).count();
+            FileAttributionResult {
+                path: path.to_string(),
+                summary: AttributionSummary {
+                    total_lines: lines.len(),
+                    ai_lines,
+                    ai_modified_lines: 0,
+                    human_lines: lines.len() - ai_lines,
+                    original_lines: 0,
+                    unknown_lines: 0,
+                },
+                lines,
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
+            }
+        };
+        let ai = || LineSource::AI {
+            edit_id: "e1".to_string(),
+        };
+
+        let base = commit_files(&[("README.md", "# Demo\n")], "Initial");
+        let unreviewed = commit_files(
+            &[
+                ("security/token.rs", "fn token() {}\n"),
+                ("src/lib.rs", "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n"),
+            ],
+            "Add token handling",
+        );
+        let reviewed = commit_files(
+            &[("src/util.rs", "fn u() {}\nfn v() {}\n")],
+            "Add util\n\nReviewed-by: Ann <ann@example.com>",
+        );
+        let attribution = |files: Vec<FileAttributionResult>| AIAttribution {
+            version: SCHEMA_VERSION,
+            session: SessionMetadata {
+                session_id: "session-1".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 1,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files,
+        };
+        let notes_store = NotesStore::new(&repo).unwrap();
+        notes_store
+            .store_attribution(
+                unreviewed,
+                &attribution(vec![
+                    file("security/token.rs", vec![ai()]),
+                    file("src/lib.rs", vec![ai(), ai(), ai(), LineSource::Human]),
+                ]),
+            )
+            .unwrap();
+        notes_store
+            .store_attribution(
+                reviewed,
+                &attribution(vec![file("src/util.rs", vec![ai(), LineSource::Human])]),
+            )
+            .unwrap();
+
+        let config: WhogititConfig = toml::from_str(
+            "[policy]\nmax_ai_percent = 60\nmin_file_lines = 2\nforbidden_paths = [\"security/**\"]\nrequire_human_review = true\n",
+        )
+        .unwrap();
+        let report = evaluate(
+            &repo,
+            &config,
+            Some(&base.to_string()),
+            "HEAD",
+            PolicyOptions::default(),
+        )
+        .unwrap();
+
+        assert!(!report.passed);
+        let rules: Vec<(&str, Option<&str>)> = report
+            .violations
+            .iter()
+            .map(|v| (v.rule_id.as_str(), v.path.as_deref()))
+            .collect();
+        // token.rs is 100% AI but shorter than min_file_lines; util.rs is at 50%
+        assert_eq!(
+            rules,
+            vec![
+                (RULE_HUMAN_REVIEW, None),
+                (RULE_FORBIDDEN_PATH, Some("security/token.rs")),
+                (RULE_MAX_AI_PERCENT, Some("src/lib.rs")),
+            ]
+        );
+        assert_eq!(report.violations[0].commit, Some(unreviewed.to_string()));
+        assert!(report.violations[2]
+            .evidence
+            .starts_with("75.0% of 4 line(s)"));
+    }
+}
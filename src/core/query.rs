@@ -0,0 +1,505 @@
+//! Attribution query language
+//!
+//! A small filter language over per-file attribution records, used by
+//! `export --query` and `stats --query` so common filters don't need a jq
+//! pipeline:
+//!
+//! ```text
+//! ai_percent > 50 && path ~ "src/**" && !(model ~ "haiku")
+//! ```
+//!
+//! A query is comparisons joined with `&&`, `||` and `!`, grouped with
+//! parentheses. Numeric fields take `== != > >= < <=` and a number; text fields
+//! take `==` and `!=` (exact) or `~`, which is a glob for `path` and a
+//! case-insensitive substring for the other fields.
+
+use anyhow::{bail, Result};
+
+use crate::capture::snapshot::AttributionSummary;
+use crate::utils::GlobSet;
+
+/// One file of one commit, as seen by a query
+#[derive(Debug, Clone, Copy)]
+pub struct FileRecord<'a> {
+    pub path: &'a str,
+    pub model: &'a str,
+    pub author: &'a str,
+    pub language: &'a str,
+    pub summary: &'a AttributionSummary,
+}
+
+impl FileRecord<'_> {
+    /// Share of the written lines that are AI or AI-modified (0-100)
+    pub fn ai_percent(&self) -> f64 {
+        let ai = self.summary.ai_lines + self.summary.ai_modified_lines;
+        let written = ai + self.summary.human_lines;
+        if written == 0 {
+            0.0
+        } else {
+            ai as f64 / written as f64 * 100.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Path,
+    Model,
+    Author,
+    Language,
+    AiPercent,
+    AiLines,
+    AiModifiedLines,
+    HumanLines,
+    OriginalLines,
+    TotalLines,
+}
+
+impl Field {
+    const ALL: [(&'static str, Field); 10] = [
+        ("path", Field::Path),
+        ("model", Field::Model),
+        ("author", Field::Author),
+        ("language", Field::Language),
+        ("ai_percent", Field::AiPercent),
+        ("ai_lines", Field::AiLines),
+        ("ai_modified_lines", Field::AiModifiedLines),
+        ("human_lines", Field::HumanLines),
+        ("original_lines", Field::OriginalLines),
+        ("total_lines", Field::TotalLines),
+    ];
+
+    fn from_name(name: &str) -> Option<Field> {
+        Self::ALL
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, field)| *field)
+    }
+
+    fn is_numeric(self) -> bool {
+        !matches!(
+            self,
+            Field::Path | Field::Model | Field::Author | Field::Language
+        )
+    }
+
+    fn text<'a>(self, record: &FileRecord<'a>) -> &'a str {
+        match self {
+            Field::Path => record.path,
+            Field::Model => record.model,
+            Field::Author => record.author,
+            _ => record.language,
+        }
+    }
+
+    fn number(self, record: &FileRecord) -> f64 {
+        let summary = record.summary;
+        match self {
+            Field::AiPercent => record.ai_percent(),
+            Field::AiLines => summary.ai_lines as f64,
+            Field::AiModifiedLines => summary.ai_modified_lines as f64,
+            Field::HumanLines => summary.human_lines as f64,
+            Field::OriginalLines => summary.original_lines as f64,
+            _ => summary.total_lines as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Match,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+    /// `path ~ "<glob>"`, compiled when the query is parsed
+    Glob(GlobSet),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Compare { field: Field, op: Op, value: Value },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn matches(&self, record: &FileRecord) -> bool {
+        match self {
+            Expr::Compare { field, op, value } => compare(*field, *op, value, record),
+            Expr::Not(inner) => !inner.matches(record),
+            Expr::And(left, right) => left.matches(record) && right.matches(record),
+            Expr::Or(left, right) => left.matches(record) || right.matches(record),
+        }
+    }
+}
+
+fn compare(field: Field, op: Op, value: &Value, record: &FileRecord) -> bool {
+    match value {
+        Value::Number(expected) => {
+            let actual = field.number(record);
+            match op {
+                Op::Eq => actual == *expected,
+                Op::Ne => actual != *expected,
+                Op::Gt => actual > *expected,
+                Op::Ge => actual >= *expected,
+                Op::Lt => actual < *expected,
+                Op::Le => actual <= *expected,
+                Op::Match => false,
+            }
+        }
+        Value::Text(expected) => {
+            let actual = field.text(record);
+            match op {
+                Op::Eq => actual == expected,
+                Op::Ne => actual != expected,
+                Op::Match => actual.to_lowercase().contains(&expected.to_lowercase()),
+                _ => false,
+            }
+        }
+        Value::Glob(glob) => glob.is_match(field.text(record)),
+    }
+}
+
+/// A parsed query
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    expr: Expr,
+}
+
+impl Query {
+    /// Parse a query, reporting the column of the first error
+    pub fn parse(source: &str) -> Result<Query> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: source.chars().count() + 1,
+        };
+        let expr = parser.or()?;
+        if let Some((column, token)) = parser.tokens.get(parser.pos) {
+            bail!(
+                "Invalid query at column {}: unexpected {}",
+                column,
+                token.describe()
+            );
+        }
+        Ok(Query { expr })
+    }
+
+    /// Whether a file record satisfies the query
+    pub fn matches(&self, record: &FileRecord) -> bool {
+        self.expr.matches(record)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Text(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(name) => format!("'{}'", name),
+            Token::Number(n) => format!("number {}", n),
+            Token::Text(text) => format!("string \"{}\"", text),
+            Token::Op(_) => "operator".to_string(),
+            Token::And => "'&&'".to_string(),
+            Token::Or => "'||'".to_string(),
+            Token::Not => "'!'".to_string(),
+            Token::Open => "'('".to_string(),
+            Token::Close => "')'".to_string(),
+        }
+    }
+}
+
+/// Split a query into tokens tagged with their 1-based column
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Op(Op::Eq), 2),
+            ('!', Some('=')) => (Token::Op(Op::Ne), 2),
+            ('>', Some('=')) => (Token::Op(Op::Ge), 2),
+            ('<', Some('=')) => (Token::Op(Op::Le), 2),
+            ('>', _) => (Token::Op(Op::Gt), 1),
+            ('<', _) => (Token::Op(Op::Lt), 1),
+            ('~', _) => (Token::Op(Op::Match), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            ('"', _) => {
+                let mut text = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => bail!("Invalid query at column {}: unterminated string", column),
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.get(j + 1), Some('"' | '\\')) => {
+                            text.push(chars[j + 1]);
+                            j += 2;
+                        }
+                        Some(&c) => {
+                            text.push(c);
+                            j += 1;
+                        }
+                    }
+                }
+                (Token::Text(text), j + 1 - i)
+            }
+            (c, _) if c.is_ascii_digit() || c == '.' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit() || **c == '.')
+                    .count();
+                let literal: String = chars[i..i + len].iter().collect();
+                let number = literal.parse().map_err(|_| {
+                    anyhow::anyhow!(
+                        "Invalid query at column {}: invalid number '{}'",
+                        column,
+                        literal
+                    )
+                })?;
+                (Token::Number(number), len)
+            }
+            (c, _) if c.is_ascii_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+                    .count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => bail!("Invalid query at column {}: unexpected '{}'", column, c),
+        };
+        tokens.push((column, token));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser; `||` binds looser than `&&`, which binds looser than `!`
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Column reported for errors at the end of the query
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<(usize, Token)> {
+        match self.tokens.get(self.pos) {
+            Some(token) => {
+                self.pos += 1;
+                Ok(token.clone())
+            }
+            None => bail!(
+                "Invalid query at column {}: expected {}, found end of query",
+                self.end,
+                expected
+            ),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.next("a field name, '!' or '('")? {
+            (_, Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            (_, Token::Open) => {
+                let expr = self.or()?;
+                match self.next("')'")? {
+                    (_, Token::Close) => Ok(expr),
+                    (column, token) => bail!(
+                        "Invalid query at column {}: expected ')', found {}",
+                        column,
+                        token.describe()
+                    ),
+                }
+            }
+            (column, Token::Ident(name)) => self.comparison(column, &name),
+            (column, token) => bail!(
+                "Invalid query at column {}: expected a field name, found {}",
+                column,
+                token.describe()
+            ),
+        }
+    }
+
+    fn comparison(&mut self, column: usize, name: &str) -> Result<Expr> {
+        let Some(field) = Field::from_name(name) else {
+            let known: Vec<&str> = Field::ALL.iter().map(|(n, _)| *n).collect();
+            bail!(
+                "Invalid query at column {}: unknown field '{}' (fields: {})",
+                column,
+                name,
+                known.join(", ")
+            );
+        };
+        let op = match self.next("an operator")? {
+            (_, Token::Op(op)) => op,
+            (column, token) => bail!(
+                "Invalid query at column {}: expected an operator after '{}', found {}",
+                column,
+                name,
+                token.describe()
+            ),
+        };
+        let (value_column, value) = match self.next("a value")? {
+            (column, Token::Number(n)) => (column, Value::Number(n)),
+            (column, Token::Text(text)) => (column, Value::Text(text)),
+            (column, token) => bail!(
+                "Invalid query at column {}: expected a number or string, found {}",
+                column,
+                token.describe()
+            ),
+        };
+
+        match (&value, field.is_numeric()) {
+            (Value::Number(_), false) => bail!(
+                "Invalid query at column {}: '{}' is compared with a quoted string",
+                value_column,
+                name
+            ),
+            (Value::Text(_), true) => bail!(
+                "Invalid query at column {}: '{}' is compared with a number",
+                value_column,
+                name
+            ),
+            (Value::Number(_), true) if op == Op::Match => bail!(
+                "Invalid query at column {}: '~' only applies to text fields",
+                column
+            ),
+            (Value::Text(_), false) if !matches!(op, Op::Eq | Op::Ne | Op::Match) => bail!(
+                "Invalid query at column {}: '{}' only supports ==, != and ~",
+                column,
+                name
+            ),
+            _ => {}
+        }
+        let value = match value {
+            Value::Text(pattern) if field == Field::Path && op == Op::Match => {
+                Value::Glob(GlobSet::new(&[pattern]))
+            }
+            value => value,
+        };
+        Ok(Expr::Compare { field, op, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(ai: usize, modified: usize, human: usize, original: usize) -> AttributionSummary {
+        AttributionSummary {
+            total_lines: ai + modified + human + original,
+            ai_lines: ai,
+            ai_modified_lines: modified,
+            human_lines: human,
+            original_lines: original,
+            unknown_lines: 0,
+        }
+    }
+
+    fn record<'a>(
+        path: &'a str,
+        model: &'a str,
+        summary: &'a AttributionSummary,
+    ) -> FileRecord<'a> {
+        FileRecord {
+            path,
+            model,
+            author: "Ada <ada@example.com>",
+            language: "Rust",
+            summary,
+        }
+    }
+
+    #[test]
+    fn test_query_matches_records() {
+        let mostly_ai = summary(8, 1, 1, 20);
+        let mostly_human = summary(1, 0, 9, 0);
+        let query =
+            Query::parse(r#"ai_percent > 50 && path ~ "src/**" && model ~ "OPUS""#).unwrap();
+
+        assert!(query.matches(&record("src/core/a.rs", "claude-opus-4-5", &mostly_ai)));
+        assert!(!query.matches(&record("src/core/a.rs", "claude-opus-4-5", &mostly_human)));
+        assert!(!query.matches(&record("tests/a.rs", "claude-opus-4-5", &mostly_ai)));
+        assert!(!query.matches(&record("src/a.rs", "claude-sonnet-4-5", &mostly_ai)));
+
+        // `&&` binds tighter than `||`; `!` and parentheses group
+        let query = Query::parse(
+            r#"language == "Go" || human_lines >= 9 && !(path ~ "*.md" || author ~ "bob")"#,
+        )
+        .unwrap();
+        assert!(query.matches(&record("src/a.rs", "m", &mostly_human)));
+        assert!(!query.matches(&record("README.md", "m", &mostly_human)));
+        assert!(!query.matches(&record("src/a.rs", "m", &mostly_ai)));
+
+        let query = Query::parse(r#"path == "say \"hi\".txt""#).unwrap();
+        assert!(query.matches(&record(r#"say "hi".txt"#, "m", &mostly_ai)));
+    }
+
+    #[test]
+    fn test_query_errors_name_the_column() {
+        let error = |source: &str| Query::parse(source).unwrap_err().to_string();
+
+        assert!(error("ai_pct > 5").contains("column 1: unknown field 'ai_pct'"));
+        assert!(error("ai_percent > \"5\"").contains("column 14"));
+        assert!(error("path > \"src\"").contains("only supports ==, != and ~"));
+        assert!(error("ai_lines ~ 3").contains("'~' only applies to text fields"));
+        assert!(error("path ~ \"src").contains("unterminated string"));
+        assert!(error("(ai_lines > 1").contains("column 14: expected ')'"));
+        assert!(error("ai_lines > 1 ai_lines").contains("column 14: unexpected 'ai_lines'"));
+        assert!(error("").contains("expected a field name"));
+    }
+}
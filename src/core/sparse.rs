@@ -0,0 +1,149 @@
+//! Sparse checkout support
+//!
+//! In a sparse checkout, index entries outside the cone carry the skip-worktree
+//! bit and are usually absent from disk (or stale, if they were left behind).
+//! Blame and attribution already read committed content from the object
+//! database; anything that needs a file's *current* content goes through
+//! [`read_current`], which takes the index blob for those paths instead of the
+//! worktree.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use git2::{IndexEntryExtendedFlag, Repository};
+
+use crate::capture::encoding::{self, DecodedText};
+use crate::utils::repo_path;
+
+/// Sparse-checkout state of a worktree
+#[derive(Debug)]
+pub struct SparseCheckout {
+    cone: bool,
+    patterns: usize,
+    skipped: HashSet<String>,
+}
+
+impl SparseCheckout {
+    /// Sparse-checkout state, or `None` when `core.sparseCheckout` is off
+    pub fn detect(repo: &Repository) -> Result<Option<Self>> {
+        let config = repo.config().context("Failed to read git config")?;
+        if !config.get_bool("core.sparseCheckout").unwrap_or(false) {
+            return Ok(None);
+        }
+        let cone = config.get_bool("core.sparseCheckoutCone").unwrap_or(false);
+
+        // Per-worktree file: linked worktrees keep their own patterns
+        let patterns = std::fs::read_to_string(repo.path().join("info/sparse-checkout"))
+            .map(|content| {
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        let index = repo.index().context("Failed to read index")?;
+        let skipped = index
+            .iter()
+            .filter(|entry| is_skip_worktree(entry.flags_extended))
+            .map(|entry| repo_path::encode(&entry.path))
+            .collect();
+
+        Ok(Some(Self {
+            cone,
+            patterns,
+            skipped,
+        }))
+    }
+
+    /// Whether the checkout uses cone mode (`core.sparseCheckoutCone`)
+    pub fn is_cone(&self) -> bool {
+        self.cone
+    }
+
+    /// Patterns in `info/sparse-checkout`
+    pub fn pattern_count(&self) -> usize {
+        self.patterns
+    }
+
+    /// Tracked files outside the sparse cone
+    pub fn outside_count(&self) -> usize {
+        self.skipped.len()
+    }
+
+    /// Whether a tracked path is outside the sparse cone
+    pub fn is_outside(&self, path: &str) -> bool {
+        self.skipped.contains(path)
+    }
+}
+
+fn is_skip_worktree(flags_extended: u16) -> bool {
+    flags_extended & IndexEntryExtendedFlag::SKIP_WORKTREE.bits() != 0
+}
+
+/// Current content of a repository-relative path
+///
+/// The worktree copy, except for paths outside the sparse cone, which are read
+/// from their index blob. `None` if the file is missing, unreadable, or binary.
+pub fn read_current(repo: &Repository, path: &str) -> Option<DecodedText> {
+    let index = repo.index().ok()?;
+    if let Some(entry) = index
+        .get_path(&repo_path::to_path(path), 0)
+        .filter(|entry| is_skip_worktree(entry.flags_extended))
+    {
+        let blob = repo.find_blob(entry.id).ok()?;
+        return encoding::decode(blob.content());
+    }
+    encoding::read_text_file(&repo.workdir()?.join(repo_path::to_path(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::AIBlamer;
+    use tempfile::TempDir;
+
+    /// Mark `path` skip-worktree and remove it from disk, as `git sparse-checkout` does
+    fn sparsify(repo: &Repository, path: &str) {
+        let mut config = repo.config().unwrap();
+        config.set_bool("core.sparseCheckout", true).unwrap();
+        config.set_bool("core.sparseCheckoutCone", true).unwrap();
+        std::fs::create_dir_all(repo.path().join("info")).unwrap();
+        std::fs::write(repo.path().join("info/sparse-checkout"), "/*\n!/*/\n").unwrap();
+
+        let mut index = repo.index().unwrap();
+        let mut entry = index.get_path(std::path::Path::new(path), 0).unwrap();
+        entry.flags |= 0x4000; // extended flags present
+        entry.flags_extended |= IndexEntryExtendedFlag::SKIP_WORKTREE.bits();
+        index.add(&entry).unwrap();
+        index.write().unwrap();
+        std::fs::remove_file(repo.workdir().unwrap().join(path)).unwrap();
+    }
+
+    #[test]
+    fn test_paths_outside_cone_read_from_object_database() {
+        let dir = TempDir::new().unwrap();
+        let fixture =
+            crate::testing::generate(crate::testing::Scenario::AiNewFile, dir.path()).unwrap();
+        let repo = &fixture.repo;
+        assert!(SparseCheckout::detect(repo).unwrap().is_none());
+
+        sparsify(repo, "src/generated.rs");
+        let sparse = SparseCheckout::detect(repo).unwrap().unwrap();
+        assert!(sparse.is_cone());
+        assert_eq!(sparse.pattern_count(), 2);
+        assert_eq!(sparse.outside_count(), 1);
+        assert!(sparse.is_outside("src/generated.rs"));
+
+        let content = read_current(repo, "src/generated.rs").unwrap();
+        assert!(content.text.contains("pub fn generated()"));
+
+        // Blame never touches the missing worktree file
+        let blame = AIBlamer::new(repo)
+            .unwrap()
+            .blame("src/generated.rs", None)
+            .unwrap();
+        assert!(blame.lines.iter().all(|line| line.source.is_ai()));
+    }
+}
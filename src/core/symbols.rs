@@ -0,0 +1,401 @@
+//! Block-level attribution for functions and classes
+//!
+//! Maps attributed line ranges to enclosing symbols using lightweight
+//! definition patterns plus brace or indentation tracking, so reviewers can
+//! see "function `parse_config`: 80% AI" instead of raw line ranges.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::capture::snapshot::{FileAttributionResult, LineSource};
+
+/// Kind of code block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Function,
+    Class,
+}
+
+impl SymbolKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Class => "class",
+        }
+    }
+}
+
+/// A detected symbol and its line range (1-indexed, inclusive)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSpan {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// Attribution counts for a single symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolAttribution {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub total_lines: usize,
+    pub ai_lines: usize,
+    pub ai_modified_lines: usize,
+    pub human_lines: usize,
+    pub original_lines: usize,
+}
+
+impl SymbolAttribution {
+    /// Percentage of lines that are AI or AI-modified
+    pub fn ai_percent(&self) -> f64 {
+        if self.total_lines == 0 {
+            0.0
+        } else {
+            (self.ai_lines + self.ai_modified_lines) as f64 / self.total_lines as f64 * 100.0
+        }
+    }
+}
+
+/// Block style used to find where a symbol ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockStyle {
+    Braces,
+    Indentation,
+}
+
+fn block_style(path: &str) -> BlockStyle {
+    let ext = path.rsplit('.').next().unwrap_or("").to_ascii_lowercase();
+    match ext.as_str() {
+        "py" | "pyi" => BlockStyle::Indentation,
+        _ => BlockStyle::Braces,
+    }
+}
+
+fn definition_patterns() -> &'static [(Regex, SymbolKind)] {
+    static PATTERNS: OnceLock<Vec<(Regex, SymbolKind)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let specs: [(&str, SymbolKind); 7] = [
+            // Rust
+            (
+                r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:default\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+"[^"]*"\s+)?fn\s+([A-Za-z_]\w*)"#,
+                SymbolKind::Function,
+            ),
+            (
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:struct|enum|trait|union|mod)\s+([A-Za-z_]\w*)",
+                SymbolKind::Class,
+            ),
+            (
+                r"^\s*(?:unsafe\s+)?impl(?:\s*<[^>]*>)?\s+(?:[\w:<>, ]+\s+for\s+)?([A-Za-z_][\w:]*)",
+                SymbolKind::Class,
+            ),
+            // Python / JavaScript / TypeScript
+            (
+                r"^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?(?:def|function\*?)\s+([A-Za-z_$][\w$]*)",
+                SymbolKind::Function,
+            ),
+            (
+                r"^\s*(?:export\s+)?(?:default\s+)?(?:abstract\s+)?class\s+([A-Za-z_$][\w$]*)",
+                SymbolKind::Class,
+            ),
+            (
+                r"^\s*(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*=>|[A-Za-z_$][\w$]*\s*=>)",
+                SymbolKind::Function,
+            ),
+            // Go
+            (
+                r"^func\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)",
+                SymbolKind::Function,
+            ),
+        ];
+        specs
+            .iter()
+            .map(|(pattern, kind)| (Regex::new(pattern).expect("valid symbol pattern"), *kind))
+            .collect()
+    })
+}
+
+fn match_definition(line: &str) -> Option<(SymbolKind, String)> {
+    definition_patterns().iter().find_map(|(re, kind)| {
+        re.captures(line)
+            .and_then(|c| c.get(1))
+            .map(|m| (*kind, m.as_str().to_string()))
+    })
+}
+
+/// Net brace change on a line, ignoring string literals and `//` comments
+fn brace_delta(line: &str) -> (i32, bool) {
+    let mut delta = 0;
+    let mut opened = false;
+    let mut in_string: Option<char> = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if let Some(quote) = in_string {
+            if c == '\\' {
+                chars.next();
+            } else if c == quote {
+                in_string = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '`' => in_string = Some(c),
+            '/' if chars.peek() == Some(&'/') => break,
+            '{' => {
+                delta += 1;
+                opened = true;
+            }
+            '}' => delta -= 1,
+            _ => {}
+        }
+    }
+    (delta, opened)
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Find the end line index (0-based) of a brace-delimited block starting at `start`
+fn brace_block_end(lines: &[&str], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut opened = false;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        // A declaration without a body (e.g. `fn f();`) ends before any brace opens
+        if !opened && i > start + 3 {
+            return None;
+        }
+        let (delta, has_open) = brace_delta(line);
+        if !opened && !has_open && line.trim_end().ends_with(';') {
+            return None;
+        }
+        opened |= has_open;
+        depth += delta;
+        if opened && depth <= 0 {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Find the end line index (0-based) of an indentation-delimited block
+fn indent_block_end(lines: &[&str], start: usize) -> usize {
+    let base = indentation(lines[start]);
+    let mut end = start;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indentation(line) <= base {
+            break;
+        }
+        end = i;
+    }
+    end
+}
+
+/// Detect function and class blocks in a file
+pub fn detect_symbols(path: &str, lines: &[&str]) -> Vec<SymbolSpan> {
+    let style = block_style(path);
+    let mut symbols = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some((kind, name)) = match_definition(line) else {
+            continue;
+        };
+        let end = match style {
+            BlockStyle::Braces => match brace_block_end(lines, i) {
+                Some(end) => end,
+                None => continue,
+            },
+            BlockStyle::Indentation => indent_block_end(lines, i),
+        };
+        symbols.push(SymbolSpan {
+            kind,
+            name,
+            start_line: i as u32 + 1,
+            end_line: end as u32 + 1,
+        });
+    }
+
+    symbols
+}
+
+/// Compute per-symbol attribution for a file's line-level results
+pub fn attribute_symbols(file: &FileAttributionResult) -> Vec<SymbolAttribution> {
+    let contents: Vec<&str> = file.lines.iter().map(|l| l.content.as_str()).collect();
+
+    detect_symbols(&file.path, &contents)
+        .into_iter()
+        .map(|span| {
+            let mut attr = SymbolAttribution {
+                kind: span.kind,
+                name: span.name,
+                start_line: span.start_line,
+                end_line: span.end_line,
+                total_lines: 0,
+                ai_lines: 0,
+                ai_modified_lines: 0,
+                human_lines: 0,
+                original_lines: 0,
+            };
+            for line in file
+                .lines
+                .iter()
+                .filter(|l| l.line_number >= span.start_line && l.line_number <= span.end_line)
+            {
+                attr.total_lines += 1;
+                match line.source {
+                    LineSource::AI { .. } => attr.ai_lines += 1,
+                    LineSource::AIModified { .. } => attr.ai_modified_lines += 1,
+                    LineSource::Human => attr.human_lines += 1,
+                    LineSource::Original => attr.original_lines += 1,
+                    LineSource::Unknown => {}
+                }
+            }
+            attr
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::{AttributionSummary, LineAttribution};
+
+    fn spans(path: &str, src: &str) -> Vec<(SymbolKind, String, u32, u32)> {
+        let lines: Vec<&str> = src.lines().collect();
+        detect_symbols(path, &lines)
+            .into_iter()
+            .map(|s| (s.kind, s.name, s.start_line, s.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_rust_functions_and_impl() {
+        let src = "pub struct Config {\n    a: u32,\n}\n\nimpl Config {\n    pub fn parse_config(s: &str) -> Self {\n        let x = \"{\";\n        Self { a: 1 }\n    }\n}\n";
+        let found = spans("src/config.rs", src);
+        assert_eq!(
+            found,
+            vec![
+                (SymbolKind::Class, "Config".to_string(), 1, 3),
+                (SymbolKind::Class, "Config".to_string(), 5, 10),
+                (SymbolKind::Function, "parse_config".to_string(), 6, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trait_declaration_without_body_skipped() {
+        let src = "trait Parse {\n    fn parse(&self) -> u32;\n}\n";
+        let found = spans("a.rs", src);
+        assert_eq!(found, vec![(SymbolKind::Class, "Parse".to_string(), 1, 3)]);
+    }
+
+    #[test]
+    fn test_detect_python_indentation() {
+        let src = "class Parser:\n    def parse(self):\n        return 1\n\n    def other(self):\n        pass\n\ndef main():\n    Parser()\n";
+        let found = spans("app.py", src);
+        assert_eq!(
+            found,
+            vec![
+                (SymbolKind::Class, "Parser".to_string(), 1, 6),
+                (SymbolKind::Function, "parse".to_string(), 2, 3),
+                (SymbolKind::Function, "other".to_string(), 5, 6),
+                (SymbolKind::Function, "main".to_string(), 8, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_js_and_go() {
+        let js = "export const load = async (x) => {\n  return x;\n};\nfunction save() {\n}\n";
+        let found = spans("a.ts", js);
+        assert_eq!(found[0].1, "load");
+        assert_eq!(found[1], (SymbolKind::Function, "save".to_string(), 4, 5));
+
+        let go = "func (s *Server) Start() error {\n\treturn nil\n}\n";
+        assert_eq!(
+            spans("main.go", go),
+            vec![(SymbolKind::Function, "Start".to_string(), 1, 3)]
+        );
+    }
+
+    #[test]
+    fn test_attribute_symbols_counts() {
+        let src = [
+            "fn keep() {",
+            "}",
+            "fn parse_config() {",
+            "    a();",
+            "    b();",
+            "}",
+        ];
+        let sources = [
+            LineSource::Original,
+            LineSource::Original,
+            LineSource::AI {
+                edit_id: "e1".into(),
+            },
+            LineSource::AI {
+                edit_id: "e1".into(),
+            },
+            LineSource::Human,
+            LineSource::AIModified {
+                edit_id: "e1".into(),
+                similarity: 0.8,
+            },
+        ];
+        let lines = src
+            .iter()
+            .zip(sources)
+            .enumerate()
+            .map(|(i, (content, source))| LineAttribution {
+                line_number: i as u32 + 1,
+                content: content.to_string(),
+                content_hash: None,
+                source,
+                edit_id: None,
+                prompt_index: None,
+                confidence: 1.0,
+                timestamp: None,
+            })
+            .collect();
+        let file = FileAttributionResult {
+            path: "lib.rs".to_string(),
+            lines,
+            summary: AttributionSummary {
+                total_lines: 6,
+                ai_lines: 2,
+                ai_modified_lines: 1,
+                human_lines: 1,
+                original_lines: 2,
+                unknown_lines: 0,
+            },
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        };
+
+        let symbols = attribute_symbols(&file);
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].ai_percent(), 0.0);
+        let parse = &symbols[1];
+        assert_eq!(parse.name, "parse_config");
+        assert_eq!(parse.total_lines, 4);
+        assert_eq!(parse.ai_lines, 2);
+        assert_eq!(parse.ai_modified_lines, 1);
+        assert_eq!(parse.human_lines, 1);
+        assert_eq!(parse.ai_percent(), 75.0);
+    }
+}
@@ -1,12 +1,20 @@
 pub mod capture;
+#[cfg(feature = "git")]
 pub mod cli;
 pub mod core;
 pub mod privacy;
+#[cfg(feature = "git")]
 pub mod retention;
 pub mod storage;
+#[cfg(all(feature = "git", any(test, feature = "dev-tools")))]
+pub mod testing;
 pub mod utils;
 
 pub use core::attribution::*;
+#[cfg(feature = "git")]
 pub use core::blame::AIBlamer;
+#[cfg(feature = "git")]
+pub use core::policy::{PolicyOptions, PolicyReport, Violation};
+#[cfg(feature = "git")]
 pub use storage::notes::NotesStore;
 pub use storage::trailers::{TrailerGenerator, TrailerParser};
@@ -0,0 +1,233 @@
+//! `whogitit.*` git config keys as a configuration layer
+//!
+//! Settings can be given as git config, on top of `.whogitit.toml`:
+//!
+//! ```text
+//! git -c whogitit.analysis.similarity-threshold=0.7 whogitit blame src/main.rs
+//! git config whogitit.privacy.audit-log true
+//! ```
+//!
+//! The key after `whogitit.` is the TOML path; git variable names cannot contain
+//! `_`, so dashes in the last segment stand for underscores. Values are parsed as
+//! TOML values (`true`, `0.7`, `["a", "b"]`) and fall back to plain strings.
+//! Keys from the repository's git config (and the global/system files) apply
+//! first, then `git -c` overrides, which git passes to subcommands through
+//! `GIT_CONFIG_PARAMETERS`.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+/// Section prefix of whogitit keys in git config
+const SECTION: &str = "whogitit.";
+
+/// `whogitit.*` keys and values, lowest precedence first
+pub fn overrides(repo_root: &Path) -> Vec<(String, String)> {
+    let mut overrides = repository_entries(repo_root);
+
+    let mut from_env = Vec::new();
+    if let Ok(parameters) = std::env::var("GIT_CONFIG_PARAMETERS") {
+        from_env.extend(parse_config_parameters(&parameters));
+    }
+    // GIT_CONFIG_COUNT with GIT_CONFIG_KEY_<n>/GIT_CONFIG_VALUE_<n> (git 2.31+)
+    let count = std::env::var("GIT_CONFIG_COUNT")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(0);
+    for i in 0..count {
+        if let Ok(key) = std::env::var(format!("GIT_CONFIG_KEY_{}", i)) {
+            let value = std::env::var(format!("GIT_CONFIG_VALUE_{}", i)).unwrap_or_default();
+            from_env.push((key, value));
+        }
+    }
+
+    overrides.extend(
+        from_env
+            .into_iter()
+            .filter(|(key, _)| key.to_ascii_lowercase().starts_with(SECTION)),
+    );
+    overrides
+}
+
+/// `whogitit.*` entries of the repository's git config and the global/system files
+#[cfg(feature = "git")]
+fn repository_entries(repo_root: &Path) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let config = git2::Repository::open(repo_root)
+        .and_then(|repo| repo.config())
+        .or_else(|_| git2::Config::open_default());
+    if let Ok(config) = config {
+        if let Ok(found) = config.entries(Some("whogitit\\..*")) {
+            let _ = found.for_each(|entry| {
+                if let Some(name) = entry.name() {
+                    entries.push((name.to_string(), entry.value().unwrap_or("").to_string()));
+                }
+            });
+        }
+    }
+    entries
+}
+
+/// Without git2 only the environment's `git -c` overrides apply
+#[cfg(not(feature = "git"))]
+fn repository_entries(_repo_root: &Path) -> Vec<(String, String)> {
+    Vec::new()
+}
+
+/// Parse git's `GIT_CONFIG_PARAMETERS` (`'key'='value' 'key2=value2'`, shell-quoted)
+pub fn parse_config_parameters(parameters: &str) -> Vec<(String, String)> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = parameters.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    word.push(c);
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    words
+        .into_iter()
+        .map(|word| match word.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            // `git -c key` (no value) means true
+            None => (word, "true".to_string()),
+        })
+        .collect()
+}
+
+/// Set `key` (a `whogitit.*` git config name) to `raw` in a TOML document
+pub fn apply(document: &mut toml::Table, key: &str, raw: &str) -> Result<()> {
+    let Some(path) = key
+        .get(..SECTION.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(SECTION))
+        .map(|_| &key[SECTION.len()..])
+    else {
+        bail!("'{}' is not a whogitit key", key);
+    };
+    let mut segments: Vec<String> = path.split('.').map(str::to_string).collect();
+    if segments.iter().any(String::is_empty) {
+        bail!("Invalid git config key '{}'", key);
+    }
+    if let Some(last) = segments.last_mut() {
+        *last = last.replace('-', "_");
+    }
+
+    let Some((last, parents)) = segments.split_last() else {
+        bail!("Invalid git config key '{}'", key);
+    };
+    let mut table = document;
+    for segment in parents {
+        let child = table
+            .entry(segment.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        let Some(child) = child.as_table_mut() else {
+            bail!("Git config key '{}': '{}' is not a table", key, segment);
+        };
+        table = child;
+    }
+    table.insert(last.clone(), parse_value(raw));
+    Ok(())
+}
+
+fn parse_value(raw: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_parameters() {
+        let parsed = parse_config_parameters(
+            "'whogitit.privacy.audit-log'='true' 'core.pager=less' 'x.y' \
+             'whogitit.storage.namespace'='it'\\''s'",
+        );
+        assert_eq!(
+            parsed,
+            vec![
+                ("whogitit.privacy.audit-log".to_string(), "true".to_string()),
+                ("core.pager".to_string(), "less".to_string()),
+                ("x.y".to_string(), "true".to_string()),
+                ("whogitit.storage.namespace".to_string(), "it's".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_sets_typed_values_by_path() {
+        let mut document = toml::Table::new();
+        apply(&mut document, "whogitit.privacy.audit-log", "true").unwrap();
+        apply(
+            &mut document,
+            "whogitit.analysis.similarity-threshold",
+            "0.7",
+        )
+        .unwrap();
+        apply(
+            &mut document,
+            "whogitit.redact_levels.partner.prompts",
+            "omit",
+        )
+        .unwrap();
+        apply(
+            &mut document,
+            "whogitit.review.critical-paths",
+            "[\"src/**\"]",
+        )
+        .unwrap();
+
+        assert_eq!(document["privacy"]["audit_log"].as_bool(), Some(true));
+        assert_eq!(
+            document["analysis"]["similarity_threshold"].as_float(),
+            Some(0.7)
+        );
+        assert_eq!(
+            document["redact_levels"]["partner"]["prompts"].as_str(),
+            Some("omit")
+        );
+        assert_eq!(
+            document["review"]["critical_paths"][0].as_str(),
+            Some("src/**")
+        );
+
+        assert!(apply(
+            &mut document,
+            "whogitit.analysis.similarity_threshold.x",
+            "1"
+        )
+        .is_err());
+        assert!(apply(&mut document, "core.pager", "less").is_err());
+    }
+}
@@ -1,5 +1,17 @@
 pub mod config;
+pub mod git_config;
 pub mod redaction;
+pub mod tiers;
+pub mod visibility;
 
-pub use config::{AnalysisConfig, PatternConfig, PrivacyConfig, RetentionConfig, WhogititConfig};
+pub use config::{
+    AnalysisConfig, AnalysisGranularity, AnnotationsConfig, AuditConfig, AuditForwardFormat,
+    AuditForwardingConfig, CalibrationBin, CalibrationConfig, ContentExposure, DecayConfig,
+    GeneratedConfig, LanguagesConfig, MetricsConfig, NotifyConfig, PackagesConfig, PatternConfig,
+    PolicyConfig, PrivacyConfig, PromptExposure, PromptGuardConfig, RedactLevel, RemotePolicy,
+    RetentionConfig, ReviewConfig, SensitivePathLevel, SensitivePathMatcher, StaleAction,
+    StorageBackend, StorageConfig, WhogititConfig, STARTER_CONFIG,
+};
 pub use redaction::{RedactionEvent, RedactionResult, Redactor};
+pub use tiers::{PromptClassification, PromptStorage, PromptTier, TierStorageConfig};
+pub use visibility::{PromptVisibility, VisibilityAdvisory};
@@ -1,4 +1,4 @@
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use serde::{Deserialize, Serialize};
 
 /// Redaction placeholder
@@ -266,12 +266,23 @@ struct CompiledPattern {
 }
 
 /// Privacy redactor for sensitive data in prompts
+///
+/// All patterns are also compiled into one [`RegexSet`], so a single pass over the
+/// text finds which patterns match; only those are run again to extract matches.
+/// Most prompts hit none or one of the 20+ patterns.
 #[derive(Clone)]
 pub struct Redactor {
     patterns: Vec<CompiledPattern>,
+    /// Match detection for `patterns`; `None` if the set fails to compile (size limit)
+    set: Option<RegexSet>,
 }
 
 impl Redactor {
+    fn from_patterns(patterns: Vec<CompiledPattern>) -> Self {
+        let set = RegexSet::new(patterns.iter().map(|cp| cp.regex.as_str())).ok();
+        Self { patterns, set }
+    }
+
     /// Create a redactor with custom patterns (unnamed)
     pub fn new(pattern_strings: &[&str]) -> Self {
         let patterns = pattern_strings
@@ -285,7 +296,7 @@ impl Redactor {
             })
             .collect();
 
-        Self { patterns }
+        Self::from_patterns(patterns)
     }
 
     /// Create a redactor with named patterns
@@ -300,7 +311,7 @@ impl Redactor {
             })
             .collect();
 
-        Self { patterns }
+        Self::from_patterns(patterns)
     }
 
     /// Create a redactor with all default security patterns
@@ -337,9 +348,7 @@ impl Redactor {
 
     /// Create a redactor with no patterns (no redaction)
     pub fn none() -> Self {
-        Self {
-            patterns: Vec::new(),
-        }
+        Self::from_patterns(Vec::new())
     }
 
     /// Add a custom pattern with a name
@@ -349,9 +358,26 @@ impl Redactor {
             name: name.to_string(),
             regex,
         });
+        self.set = RegexSet::new(self.patterns.iter().map(|cp| cp.regex.as_str())).ok();
         Ok(())
     }
 
+    /// Patterns that match somewhere in `text`, in pattern order
+    fn matching_patterns<'a>(&'a self, text: &str) -> Vec<&'a CompiledPattern> {
+        match &self.set {
+            Some(set) => set
+                .matches(text)
+                .into_iter()
+                .map(|i| &self.patterns[i])
+                .collect(),
+            None => self
+                .patterns
+                .iter()
+                .filter(|cp| cp.regex.is_match(text))
+                .collect(),
+        }
+    }
+
     /// Add a custom pattern (backward compatible)
     pub fn add_pattern(&mut self, pattern: &str) -> Result<(), regex::Error> {
         let name = format!("CUSTOM_{}", self.patterns.len());
@@ -364,14 +390,28 @@ impl Redactor {
     /// preventing double-redaction and corrupted output.
     pub fn redact(&self, text: &str) -> String {
         // Collect all match intervals
-        let mut intervals: Vec<(usize, usize)> = Vec::new();
+        let intervals: Vec<(usize, usize)> = self
+            .matching_patterns(text)
+            .into_iter()
+            .flat_map(|cp| cp.regex.find_iter(text).map(|m| (m.start(), m.end())))
+            .collect();
+        Self::replace_intervals(text, intervals)
+    }
 
-        for cp in &self.patterns {
-            for m in cp.regex.find_iter(text) {
-                intervals.push((m.start(), m.end()));
-            }
-        }
+    /// [`Self::redact`] running every pattern over the whole text, without the
+    /// pattern-set pass; kept as the reference for `whogitit bench`
+    #[cfg_attr(not(feature = "git"), allow(dead_code))]
+    pub(crate) fn redact_sequential(&self, text: &str) -> String {
+        let intervals: Vec<(usize, usize)> = self
+            .patterns
+            .iter()
+            .flat_map(|cp| cp.regex.find_iter(text).map(|m| (m.start(), m.end())))
+            .collect();
+        Self::replace_intervals(text, intervals)
+    }
 
+    /// Replace the (possibly overlapping) byte ranges with the placeholder
+    fn replace_intervals(text: &str, mut intervals: Vec<(usize, usize)>) -> String {
         if intervals.is_empty() {
             return text.to_string();
         }
@@ -410,7 +450,7 @@ impl Redactor {
         let mut all_intervals: Vec<(usize, usize)> = Vec::new();
 
         // Collect all matches first with their pattern info
-        for cp in &self.patterns {
+        for cp in self.matching_patterns(text) {
             for m in cp.regex.find_iter(text) {
                 let matched = m.as_str();
                 let preview = if matched.len() > 10 {
@@ -438,7 +478,7 @@ impl Redactor {
         let redaction_count = events.len();
 
         // Perform the actual redaction (with interval merging)
-        let text = self.redact(text);
+        let text = Self::replace_intervals(text, all_intervals);
 
         RedactionResult {
             text,
@@ -449,21 +489,24 @@ impl Redactor {
 
     /// Check if text contains sensitive data
     pub fn contains_sensitive(&self, text: &str) -> bool {
-        self.patterns.iter().any(|cp| cp.regex.is_match(text))
+        match &self.set {
+            Some(set) => set.is_match(text),
+            None => self.patterns.iter().any(|cp| cp.regex.is_match(text)),
+        }
     }
 
     /// Get list of matches in text (for debugging/preview)
     pub fn find_sensitive(&self, text: &str) -> Vec<String> {
-        self.patterns
-            .iter()
+        self.matching_patterns(text)
+            .into_iter()
             .flat_map(|cp| cp.regex.find_iter(text).map(|m| m.as_str().to_string()))
             .collect()
     }
 
     /// Get list of matches with pattern names
     pub fn find_sensitive_named(&self, text: &str) -> Vec<(String, String)> {
-        self.patterns
-            .iter()
+        self.matching_patterns(text)
+            .into_iter()
             .flat_map(|cp| {
                 cp.regex
                     .find_iter(text)
@@ -472,6 +515,14 @@ impl Redactor {
             .collect()
     }
 
+    /// Names of the patterns that match somewhere in `text`
+    pub fn matching_pattern_names(&self, text: &str) -> Vec<&str> {
+        self.matching_patterns(text)
+            .into_iter()
+            .map(|cp| cp.name.as_str())
+            .collect()
+    }
+
     /// Get names of all loaded patterns
     pub fn pattern_names(&self) -> Vec<&str> {
         self.patterns.iter().map(|cp| cp.name.as_str()).collect()
@@ -958,4 +1009,21 @@ mod tests {
         assert_eq!(merged[0], (0, 8));
         assert_eq!(merged[1], (10, 20));
     }
+
+    #[test]
+    fn test_pattern_set_matches_sequential_scan() {
+        let mut redactor = Redactor::default_patterns();
+        assert!(redactor.set.is_some());
+        redactor.add_named_pattern("TICKET", r"TICKET-\d+").unwrap();
+
+        let input = "api_key=sk-1234567890abcdefghij and TICKET-42 to user@example.com\n\
+                     password: hunter22 then nothing else";
+        assert_eq!(redactor.redact(input), redactor.redact_sequential(input));
+        assert!(redactor.redact(input).contains("[REDACTED] to [REDACTED]"));
+        assert!(redactor.matching_pattern_names(input).contains(&"TICKET"));
+        assert_eq!(
+            redactor.pattern_count(),
+            redactor.set.as_ref().unwrap().len()
+        );
+    }
 }
@@ -0,0 +1,209 @@
+//! Prompt privacy classification and tiered storage
+//!
+//! Redaction removes known secret shapes, but says nothing about how sensitive
+//! what remains is. Each prompt is classified once at capture time, after
+//! redaction, into one of three tiers:
+//!
+//! - tier 1: plain instructions
+//! - tier 2: contains code or file paths
+//! - tier 3: contains possible PII that survived redaction
+//!
+//! `[privacy.tiers]` in `.whogitit.toml` decides per tier whether the prompt text
+//! is stored verbatim or only as its hash.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::privacy::redaction::patterns;
+
+/// Privacy tier of a prompt (1 = least sensitive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum PromptTier {
+    Tier1,
+    Tier2,
+    Tier3,
+}
+
+impl From<PromptTier> for u8 {
+    fn from(tier: PromptTier) -> u8 {
+        match tier {
+            PromptTier::Tier1 => 1,
+            PromptTier::Tier2 => 2,
+            PromptTier::Tier3 => 3,
+        }
+    }
+}
+
+impl TryFrom<u8> for PromptTier {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(PromptTier::Tier1),
+            2 => Ok(PromptTier::Tier2),
+            3 => Ok(PromptTier::Tier3),
+            other => Err(format!("Invalid prompt tier {} (expected 1-3)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for PromptTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "tier {}", u8::from(*self))
+    }
+}
+
+/// Classification of a (redacted) prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptClassification {
+    pub tier: PromptTier,
+    pub contains_code: bool,
+    pub contains_paths: bool,
+    pub possible_pii: bool,
+}
+
+/// How prompt text of a tier is stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptStorage {
+    /// Store the (redacted) text
+    #[default]
+    Verbatim,
+    /// Store only the prompt hash
+    Hash,
+}
+
+/// Storage mode per prompt tier (`[privacy.tiers]`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TierStorageConfig {
+    pub tier1: PromptStorage,
+    pub tier2: PromptStorage,
+    pub tier3: PromptStorage,
+}
+
+impl TierStorageConfig {
+    /// Storage mode for a tier
+    pub fn storage_for(&self, tier: PromptTier) -> PromptStorage {
+        match tier {
+            PromptTier::Tier1 => self.tier1,
+            PromptTier::Tier2 => self.tier2,
+            PromptTier::Tier3 => self.tier3,
+        }
+    }
+}
+
+/// Text stored in place of a hash-only prompt
+pub fn hash_only_placeholder(tier: PromptTier) -> String {
+    format!("[hash-only: {}]", tier)
+}
+
+/// Classify a prompt (after redaction)
+pub fn classify(text: &str) -> PromptClassification {
+    let contains_code = looks_like_code(text);
+    let contains_paths = path_pattern().is_match(text);
+    let possible_pii = pii_patterns().iter().any(|re| re.is_match(text));
+
+    let tier = if possible_pii {
+        PromptTier::Tier3
+    } else if contains_code || contains_paths {
+        PromptTier::Tier2
+    } else {
+        PromptTier::Tier1
+    };
+
+    PromptClassification {
+        tier,
+        contains_code,
+        contains_paths,
+        possible_pii,
+    }
+}
+
+fn looks_like_code(text: &str) -> bool {
+    if text.contains("```") {
+        return true;
+    }
+    static CODE_LINE: OnceLock<Regex> = OnceLock::new();
+    let code_line = CODE_LINE.get_or_init(|| {
+        Regex::new(
+            r"(?m)(?:[;{}]\s*$|^\s*(?:fn|def|class|impl|import|from|return|const|let|var|func|pub|if|for|while)\b.*[(:{=]|\w+\([^()]*\)\s*[;{]|=>|::\w)",
+        )
+        .expect("valid code pattern")
+    });
+    code_line.is_match(text)
+}
+
+fn path_pattern() -> &'static Regex {
+    static PATH: OnceLock<Regex> = OnceLock::new();
+    PATH.get_or_init(|| {
+        Regex::new(
+            r"(?:^|[\s`'(])(?:~|\.{1,2})?/?(?:[\w.-]+/)+[\w.-]+|\b[\w-]+\.(?:rs|py|ts|tsx|js|jsx|go|java|kt|rb|c|h|cpp|hpp|cs|swift|php|toml|ya?ml|json|md|sql|sh)\b|[A-Za-z]:\\[\w\\.-]+",
+        )
+        .expect("valid path pattern")
+    })
+}
+
+/// PII shapes checked after redaction (they survive when their patterns are disabled)
+fn pii_patterns() -> &'static [Regex] {
+    static PII: OnceLock<Vec<Regex>> = OnceLock::new();
+    PII.get_or_init(|| {
+        [
+            patterns::EMAIL,
+            patterns::SSN,
+            patterns::CREDIT_CARD,
+            patterns::PHONE,
+            // International phone numbers
+            r"\+\d{1,3}[\s-]?\d[\d\s-]{6,}\d",
+            // IPv4 addresses
+            r"\b(?:\d{1,3}\.){3}\d{1,3}\b",
+        ]
+        .iter()
+        .map(|p| Regex::new(p).expect("valid PII pattern"))
+        .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_tiers() {
+        let plain = classify("Make the error messages friendlier");
+        assert_eq!(plain.tier, PromptTier::Tier1);
+        assert!(!plain.contains_code && !plain.contains_paths && !plain.possible_pii);
+
+        let path = classify("Refactor src/cli/export.rs to stream output");
+        assert_eq!(path.tier, PromptTier::Tier2);
+        assert!(path.contains_paths);
+
+        let code = classify("Replace `let x = foo();` with a match");
+        assert_eq!(code.tier, PromptTier::Tier2);
+        assert!(code.contains_code);
+
+        // Survived redaction (e.g. EMAIL pattern disabled)
+        let pii = classify("Send the report to jane.doe@example.com from main.rs");
+        assert_eq!(pii.tier, PromptTier::Tier3);
+        assert!(pii.possible_pii && pii.contains_paths);
+    }
+
+    #[test]
+    fn test_tier_config_parses_and_serializes_tier_as_number() {
+        let config: TierStorageConfig = toml::from_str("tier3 = \"hash\"").unwrap();
+        assert_eq!(
+            config.storage_for(PromptTier::Tier1),
+            PromptStorage::Verbatim
+        );
+        assert_eq!(config.storage_for(PromptTier::Tier3), PromptStorage::Hash);
+
+        let json = serde_json::to_string(&classify("Call 555-123-4567")).unwrap();
+        assert!(json.contains("\"tier\":3"));
+        let parsed: PromptClassification = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.tier, PromptTier::Tier3);
+        assert!(serde_json::from_str::<PromptTier>("4").is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Prompt visibility advisories
+//!
+//! Attribution notes carry prompt text, and the pre-push hook sends them along
+//! with every push. `privacy.prompt_visibility` states who may read prompts:
+//!
+//! - `team` (default): anyone with access to the repository's own remotes, but
+//!   not the public at large
+//! - `private`: nobody else; prompts should not leave this machine
+//!
+//! Git can't tell whether a remote is public, so remotes on the big public forges
+//! are treated as public unless they match `privacy.trusted_remotes`. Advisories
+//! only warn; they never block a push.
+
+use serde::{Deserialize, Serialize};
+
+use crate::privacy::{PromptStorage, WhogititConfig};
+use crate::utils::GlobSet;
+
+/// Hosts whose repositories are assumed to be publicly readable
+const PUBLIC_HOSTS: &[&str] = &[
+    "github.com",
+    "gitlab.com",
+    "bitbucket.org",
+    "codeberg.org",
+    "git.sr.ht",
+];
+
+/// Who may read stored prompts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptVisibility {
+    /// Collaborators on the repository's (non-public) remotes
+    #[default]
+    Team,
+    /// Only the local machine
+    Private,
+}
+
+impl std::fmt::Display for PromptVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PromptVisibility::Team => "team",
+            PromptVisibility::Private => "private",
+        })
+    }
+}
+
+/// A remote that prompts would be pushed to against the configured visibility
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibilityAdvisory {
+    pub remote: String,
+    pub url: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for VisibilityAdvisory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.remote, self.url, self.reason)
+    }
+}
+
+/// Check pushing attribution notes to a remote against `privacy.prompt_visibility`
+pub fn check_remote(
+    config: &WhogititConfig,
+    remote: &str,
+    url: &str,
+) -> Option<VisibilityAdvisory> {
+    if !prompts_in_notes(config) || !config.remote_policy(remote, url).push_prompts {
+        return None;
+    }
+    let advisory = |reason: String| VisibilityAdvisory {
+        remote: remote.to_string(),
+        url: url.to_string(),
+        reason,
+    };
+
+    match config.privacy.prompt_visibility {
+        PromptVisibility::Private => Some(advisory(
+            "prompt_visibility is \"private\" but notes with prompt text would be pushed"
+                .to_string(),
+        )),
+        PromptVisibility::Team => {
+            let location = normalize_remote_url(url);
+            let host = location.split('/').next().unwrap_or_default();
+            let public = PUBLIC_HOSTS.contains(&host);
+            (public && !GlobSet::new(&config.privacy.trusted_remotes).is_match(&location)).then(
+                || {
+                    advisory(format!(
+                        "{} may be public; prompt_visibility is \"team\" \
+                     (add it to privacy.trusted_remotes if the repository is private)",
+                        host
+                    ))
+                },
+            )
+        }
+    }
+}
+
+/// Whether pushed notes contain prompt text at all
+fn prompts_in_notes(config: &WhogititConfig) -> bool {
+    let tiers = &config.privacy.tiers;
+    config.storage.backend.writes_notes()
+        && [tiers.tier1, tiers.tier2, tiers.tier3].contains(&PromptStorage::Verbatim)
+}
+
+/// Remote URL as `host/path`, without scheme, credentials, port or `.git`
+///
+/// `git@github.com:acme/app.git` and `https://github.com/acme/app` both become
+/// `github.com/acme/app`; local paths are returned trimmed of `.git`.
+pub fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim();
+    let location = if let Some((_, rest)) = url.split_once("://") {
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+        match rest.split_once('/') {
+            Some((host, path)) => {
+                let host = host.split(':').next().unwrap_or(host);
+                format!("{}/{}", host, path)
+            }
+            None => rest.to_string(),
+        }
+    } else if let Some((host, path)) = url.split_once(':').filter(|(host, _)| {
+        // scp-like syntax; a colon after a slash is part of a local path
+        !host.contains('/') && host.len() > 1
+    }) {
+        let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+        format!("{}/{}", host, path.trim_start_matches('/'))
+    } else {
+        url.to_string()
+    };
+    let location = location.trim_end_matches('/');
+    location
+        .strip_suffix(".git")
+        .unwrap_or(location)
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_remote_url() {
+        for url in [
+            "git@github.com:Acme/app.git",
+            "https://github.com/acme/app",
+            "ssh://git@github.com:22/acme/app.git",
+            "https://token@github.com/acme/app.git/",
+        ] {
+            assert_eq!(normalize_remote_url(url), "github.com/acme/app", "{}", url);
+        }
+        assert_eq!(normalize_remote_url("/srv/git/app.git"), "/srv/git/app");
+    }
+
+    #[test]
+    fn test_check_remote_by_visibility() {
+        let mut config = WhogititConfig::default();
+        let public = "git@github.com:acme/app.git";
+        let internal = "https://git.corp.example/acme/app.git";
+
+        assert!(check_remote(&config, "origin", public).is_some());
+        assert!(check_remote(&config, "origin", internal).is_none());
+
+        config.privacy.trusted_remotes = vec!["github.com/acme/*".to_string()];
+        assert!(check_remote(&config, "origin", public).is_none());
+
+        config.privacy.prompt_visibility = PromptVisibility::Private;
+        assert!(check_remote(&config, "origin", internal).is_some());
+
+        // Remotes that receive prompt-stripped notes disclose nothing
+        config.remotes.insert(
+            "git.corp.example/acme".to_string(),
+            crate::privacy::RemotePolicy {
+                push_prompts: false,
+            },
+        );
+        assert!(check_remote(&config, "origin", internal).is_none());
+        config.remotes.clear();
+
+        // Nothing to disclose when every tier is stored hash-only
+        config.privacy.tiers.tier1 = PromptStorage::Hash;
+        config.privacy.tiers.tier2 = PromptStorage::Hash;
+        config.privacy.tiers.tier3 = PromptStorage::Hash;
+        assert!(check_remote(&config, "origin", internal).is_none());
+    }
+}
@@ -74,10 +74,10 @@ pub fn apply_retention_policy(
     retention: &RetentionConfig,
     execute: bool,
     reason: &str,
-    audit_log_enabled: bool,
+    audit_log: Option<&AuditLog>,
 ) -> Result<RetentionResult> {
     let sets = compute_retention_sets(repo, retention)?;
-    apply_retention_policy_with_sets(repo, sets, execute, reason, audit_log_enabled)
+    apply_retention_policy_with_sets(repo, sets, execute, reason, audit_log)
 }
 
 /// Apply a retention policy using precomputed retention sets.
@@ -89,7 +89,7 @@ pub fn apply_retention_policy_with_sets(
     sets: RetentionSets,
     execute: bool,
     reason: &str,
-    audit_log_enabled: bool,
+    audit_log: Option<&AuditLog>,
 ) -> Result<RetentionResult> {
     if execute {
         let notes_store = NotesStore::new(repo)?;
@@ -97,11 +97,8 @@ pub fn apply_retention_policy_with_sets(
             notes_store.remove_attribution(*commit_oid)?;
         }
 
-        if audit_log_enabled {
-            if let Some(repo_root) = repo.workdir() {
-                let audit_log = AuditLog::new(repo_root);
-                audit_log.log_retention(sets.to_delete.len() as u32, reason)?;
-            }
+        if let Some(audit_log) = audit_log {
+            audit_log.log_retention(sets.to_delete.len() as u32, reason)?;
         }
     }
 
@@ -2,9 +2,13 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File, OpenOptions};
+use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::privacy::{AuditConfig, AuditForwardFormat, AuditForwardingConfig};
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -18,6 +22,15 @@ const AUDIT_DIR: &str = ".whogitit";
 const AUDIT_FILE: &str = "audit.jsonl";
 /// Number of hex chars retained from SHA-256 for event hash chaining (128 bits).
 const EVENT_HASH_HEX_LEN: usize = 32;
+/// CEF header vendor and product
+const CEF_VENDOR: &str = "dotsetlabs";
+const CEF_PRODUCT: &str = "whogitit";
+/// Syslog APP-NAME
+const SYSLOG_APP_NAME: &str = "whogitit";
+/// RFC 5424 structured data ID (32473 is the documentation enterprise number)
+const SYSLOG_SD_ID: &str = "whogitit@32473";
+/// Timeout for connecting and writing to a TCP collector
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// An audit log event
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +58,20 @@ pub enum AuditEventType {
     ConfigChange,
     /// Redaction occurred (when audit logging enabled)
     Redaction,
+    /// Outgoing prompt matched a prohibited pattern (`check-prompt`)
+    PolicyHit,
+    /// Pushing attribution notes to a remote failed (`push-notes`)
+    NotesPushFailed,
+    /// Prompt text was shown or exported (`audit.log_prompt_access`)
+    PromptAccess,
+    /// AI-written code matched a sensitive pattern (`privacy.scan_generated_code`)
+    SecretDetected,
+    /// A stale pending buffer was archived or discarded (`analysis.stale_action`)
+    PendingExpired,
+    /// Capture was paused (`whogitit pause`)
+    CapturePaused,
+    /// Capture resumed after a pause (`whogitit resume` or the pause window ending)
+    CaptureResumed,
 }
 
 impl std::fmt::Display for AuditEventType {
@@ -55,6 +82,60 @@ impl std::fmt::Display for AuditEventType {
             Self::RetentionApply => write!(f, "retention_apply"),
             Self::ConfigChange => write!(f, "config_change"),
             Self::Redaction => write!(f, "redaction"),
+            Self::PolicyHit => write!(f, "policy_hit"),
+            Self::NotesPushFailed => write!(f, "notes_push_failed"),
+            Self::PromptAccess => write!(f, "prompt_access"),
+            Self::SecretDetected => write!(f, "secret_detected"),
+            Self::PendingExpired => write!(f, "pending_expired"),
+            Self::CapturePaused => write!(f, "capture_paused"),
+            Self::CaptureResumed => write!(f, "capture_resumed"),
+        }
+    }
+}
+
+impl AuditEventType {
+    /// Human-readable event name (CEF `Name`, syslog message)
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Delete => "Attribution deleted",
+            Self::Export => "Attribution exported",
+            Self::RetentionApply => "Retention policy applied",
+            Self::ConfigChange => "Configuration changed",
+            Self::Redaction => "Sensitive data redacted",
+            Self::PolicyHit => "Prohibited content in prompt",
+            Self::NotesPushFailed => "Attribution notes push failed",
+            Self::PromptAccess => "Prompt content accessed",
+            Self::SecretDetected => "Possible secret in AI-written code",
+            Self::PendingExpired => "Stale pending buffer expired",
+            Self::CapturePaused => "Capture paused",
+            Self::CaptureResumed => "Capture resumed",
+        }
+    }
+
+    /// CEF severity (0-10, higher is more severe)
+    pub fn cef_severity(&self) -> u8 {
+        match self {
+            Self::PolicyHit | Self::SecretDetected => 8,
+            Self::Delete => 7,
+            Self::RetentionApply | Self::PendingExpired => 6,
+            Self::Export | Self::NotesPushFailed | Self::PromptAccess | Self::CapturePaused => 5,
+            Self::ConfigChange | Self::CaptureResumed => 4,
+            Self::Redaction => 3,
+        }
+    }
+
+    /// Syslog severity (RFC 5424: 4 warning, 5 notice, 6 informational)
+    pub fn syslog_severity(&self) -> u8 {
+        match self {
+            Self::Delete | Self::PolicyHit | Self::NotesPushFailed | Self::SecretDetected => 4,
+            Self::Export
+            | Self::RetentionApply
+            | Self::ConfigChange
+            | Self::PromptAccess
+            | Self::PendingExpired
+            | Self::CapturePaused
+            | Self::CaptureResumed => 5,
+            Self::Redaction => 6,
         }
     }
 }
@@ -92,18 +173,50 @@ pub struct AuditDetails {
     /// Configuration field that changed (for config_change events)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub field: Option<String>,
+    /// Command that revealed prompt text (for prompt_access events)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Indices of the prompts revealed (for prompt_access events)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<Vec<u32>>,
 }
 
 /// Append-only audit log store
 pub struct AuditLog {
     path: PathBuf,
+    forwarder: Option<AuditForwarder>,
 }
 
 impl AuditLog {
     /// Create a new audit log for the given repo root
     pub fn new(repo_root: &Path) -> Self {
         let path = repo_root.join(AUDIT_DIR).join(AUDIT_FILE);
-        Self { path }
+        Self {
+            path,
+            forwarder: None,
+        }
+    }
+
+    /// Log for prompt access events, or `None` unless `audit.log_prompt_access` is on
+    ///
+    /// Prompt access logging is switched on by itself, independently of
+    /// `privacy.audit_log`.
+    pub fn for_prompt_access(repo_root: &Path, config: &AuditConfig) -> Option<Self> {
+        config
+            .log_prompt_access
+            .then(|| Self::new(repo_root).with_forwarding(&config.forwarding))
+    }
+
+    /// Forward new events to syslog as configured under `[audit.forwarding]`
+    ///
+    /// An invalid forwarding configuration is reported and ignored; events are still
+    /// written to the local log.
+    pub fn with_forwarding(mut self, config: &AuditForwardingConfig) -> Self {
+        match AuditForwarder::from_config(config) {
+            Ok(forwarder) => self.forwarder = forwarder,
+            Err(e) => eprintln!("whogitit: Warning - audit forwarding disabled: {:#}", e),
+        }
+        self
     }
 
     /// Ensure the audit log directory exists
@@ -117,7 +230,15 @@ impl AuditLog {
     /// Append an event to the audit log
     pub fn log(&self, event: AuditEvent) -> Result<()> {
         let event = self.with_chain(event)?;
-        self.write_event(&event)
+        self.write_event(&event)?;
+
+        // The local log is authoritative; a collector outage must not fail the action
+        if let Some(forwarder) = &self.forwarder {
+            if let Err(e) = forwarder.send(&event) {
+                eprintln!("whogitit: Warning - failed to forward audit event: {:#}", e);
+            }
+        }
+        Ok(())
     }
 
     /// Log a delete event
@@ -175,6 +296,117 @@ impl AuditLog {
         })
     }
 
+    /// Log a stale pending buffer being archived or discarded
+    pub fn log_pending_expired(&self, reason: &str) -> Result<()> {
+        self.log(AuditEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: AuditEventType::PendingExpired,
+            details: AuditDetails {
+                reason: Some(reason.to_string()),
+                user: get_current_user(),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Log capture being paused from `paused_at` until `until` (None: until resumed)
+    pub fn log_capture_paused(
+        &self,
+        paused_at: &str,
+        until: Option<&str>,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        let window = format!(
+            "paused from {} until {}",
+            paused_at,
+            until.unwrap_or("resumed")
+        );
+        let reason = match reason {
+            Some(reason) => format!("{}: {}", window, reason),
+            None => window,
+        };
+        self.log(AuditEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: AuditEventType::CapturePaused,
+            details: AuditDetails {
+                reason: Some(reason),
+                user: get_current_user(),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Log capture resuming after the pause that started at `paused_at`
+    pub fn log_capture_resumed(&self, paused_at: &str, reason: &str) -> Result<()> {
+        self.log(AuditEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: AuditEventType::CaptureResumed,
+            details: AuditDetails {
+                reason: Some(format!("paused since {}; {}", paused_at, reason)),
+                user: get_current_user(),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Log a sensitive pattern found in code written by an AI edit
+    ///
+    /// Only the pattern name and location are recorded, never the matched text.
+    pub fn log_secret_detected(&self, pattern_name: &str, path: &str, line: u32) -> Result<()> {
+        self.log(AuditEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: AuditEventType::SecretDetected,
+            details: AuditDetails {
+                pattern_name: Some(pattern_name.to_string()),
+                reason: Some(format!("{}:{}", path, line)),
+                user: get_current_user(),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Log a prohibited pattern hit in an outgoing prompt
+    pub fn log_policy_hit(&self, pattern_name: &str, reason: &str) -> Result<()> {
+        self.log(AuditEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: AuditEventType::PolicyHit,
+            details: AuditDetails {
+                pattern_name: Some(pattern_name.to_string()),
+                reason: Some(reason.to_string()),
+                user: get_current_user(),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Log a failed notes push
+    pub fn log_notes_push_failure(&self, remote: &str, reason: &str) -> Result<()> {
+        self.log(AuditEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: AuditEventType::NotesPushFailed,
+            details: AuditDetails {
+                reason: Some(format!("{}: {}", remote, reason)),
+                user: get_current_user(),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Log that `command` revealed the text of `prompts` from `commit`'s note
+    pub fn log_prompt_access(&self, command: &str, commit: &str, prompts: &[u32]) -> Result<()> {
+        self.log(AuditEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: AuditEventType::PromptAccess,
+            details: AuditDetails {
+                commit: Some(commit.to_string()),
+                command: Some(command.to_string()),
+                prompts: Some(prompts.to_vec()),
+                user: get_current_user(),
+                ..Default::default()
+            },
+        })
+    }
+
     /// Log a configuration change event
     pub fn log_config_change(&self, field: &str, reason: &str) -> Result<()> {
         self.log(AuditEvent {
@@ -269,6 +501,7 @@ impl AuditLog {
     fn write_event(&self, event: &AuditEvent) -> Result<()> {
         self.ensure_dir()?;
 
+        #[cfg(unix)]
         let is_new_file = !self.path.exists();
 
         let mut file = OpenOptions::new()
@@ -280,9 +513,9 @@ impl AuditLog {
         // Set restrictive permissions (0600) on new audit log - contains sensitive data
         #[cfg(unix)]
         if is_new_file {
-            let mut perms = fs::metadata(&self.path)?.permissions();
+            let mut perms = std::fs::metadata(&self.path)?.permissions();
             perms.set_mode(0o600);
-            fs::set_permissions(&self.path, perms)
+            std::fs::set_permissions(&self.path, perms)
                 .context("Failed to set permissions on audit log")?;
         }
 
@@ -357,6 +590,309 @@ impl AuditLog {
     }
 }
 
+/// Sends audit events to a syslog collector
+pub struct AuditForwarder {
+    target: ForwardTarget,
+    format: AuditForwardFormat,
+    facility: u8,
+    hostname: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum ForwardTarget {
+    Udp(String),
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl ForwardTarget {
+    fn parse(address: &str) -> Result<Self> {
+        if let Some(addr) = address.strip_prefix("udp://") {
+            return Ok(Self::Udp(addr.to_string()));
+        }
+        if let Some(addr) = address.strip_prefix("tcp://") {
+            return Ok(Self::Tcp(addr.to_string()));
+        }
+        #[cfg(unix)]
+        if let Some(path) = address.strip_prefix("unix://") {
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        anyhow::bail!(
+            "Unsupported audit forwarding address '{}'. Use udp://host:port, tcp://host:port or unix:///path",
+            address
+        )
+    }
+}
+
+impl AuditForwarder {
+    /// Build a forwarder from config (`None` when forwarding is disabled)
+    pub fn from_config(config: &AuditForwardingConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let facility = syslog_facility(&config.facility)
+            .with_context(|| format!("Unknown syslog facility '{}'", config.facility))?;
+        Ok(Some(Self {
+            target: ForwardTarget::parse(&config.address)?,
+            format: config.format,
+            facility,
+            hostname: hostname(),
+        }))
+    }
+
+    /// Send one event
+    pub fn send(&self, event: &AuditEvent) -> Result<()> {
+        let message = format_syslog(event, self.facility, &self.hostname, self.format);
+        match &self.target {
+            ForwardTarget::Udp(addr) => {
+                let target = resolve(addr)?;
+                let bind = if target.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                let socket = UdpSocket::bind(bind).context("Failed to open UDP socket")?;
+                socket
+                    .send_to(message.as_bytes(), target)
+                    .with_context(|| format!("Failed to send to udp://{}", addr))?;
+            }
+            ForwardTarget::Tcp(addr) => {
+                let mut stream = TcpStream::connect_timeout(&resolve(addr)?, FORWARD_TIMEOUT)
+                    .with_context(|| format!("Failed to connect to tcp://{}", addr))?;
+                stream.set_write_timeout(Some(FORWARD_TIMEOUT))?;
+                // RFC 6587 octet-counting framing
+                write!(stream, "{} {}", message.len(), message)
+                    .with_context(|| format!("Failed to send to tcp://{}", addr))?;
+            }
+            #[cfg(unix)]
+            ForwardTarget::Unix(path) => {
+                let socket = std::os::unix::net::UnixDatagram::unbound()?;
+                socket
+                    .send_to(message.as_bytes(), path)
+                    .with_context(|| format!("Failed to send to {}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn resolve(addr: &str) -> Result<std::net::SocketAddr> {
+    addr.to_socket_addrs()
+        .with_context(|| format!("Invalid collector address '{}'", addr))?
+        .next()
+        .with_context(|| format!("Could not resolve '{}'", addr))
+}
+
+/// Syslog facility code for a facility name
+pub fn syslog_facility(name: &str) -> Option<u8> {
+    const FACILITIES: &[&str] = &[
+        "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron",
+        "authpriv", "ftp",
+    ];
+    let name = name.trim().to_ascii_lowercase();
+    if let Some(idx) = FACILITIES.iter().position(|f| *f == name) {
+        return Some(idx as u8);
+    }
+    let local: u8 = name.strip_prefix("local")?.parse().ok()?;
+    (local <= 7).then_some(16 + local)
+}
+
+/// An event detail with its CEF and syslog names
+struct DetailField {
+    /// CEF extension key
+    cef_key: &'static str,
+    /// Label for CEF custom fields (`cs1Label=commit`)
+    cef_label: Option<&'static str>,
+    /// RFC 5424 structured data parameter name
+    sd_name: &'static str,
+    value: String,
+}
+
+/// Present detail fields (`reason` is carried as the message instead)
+fn detail_fields(details: &AuditDetails) -> Vec<DetailField> {
+    let fields = [
+        ("suser", None, "user", details.user.clone()),
+        ("cs1", Some("commit"), "commit", details.commit.clone()),
+        ("cs2", Some("format"), "format", details.format.clone()),
+        (
+            "cn1",
+            Some("commitCount"),
+            "commit_count",
+            details.commit_count.map(|c| c.to_string()),
+        ),
+        (
+            "cs3",
+            Some("pattern"),
+            "pattern_name",
+            details.pattern_name.clone(),
+        ),
+        (
+            "cn2",
+            Some("redactionCount"),
+            "redaction_count",
+            details.redaction_count.map(|c| c.to_string()),
+        ),
+        ("cs4", Some("configField"), "field", details.field.clone()),
+        (
+            "flexString1",
+            Some("command"),
+            "command",
+            details.command.clone(),
+        ),
+        (
+            "flexString2",
+            Some("prompts"),
+            "prompts",
+            details.prompts.as_ref().map(|prompts| {
+                prompts
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+        ),
+        (
+            "cs5",
+            Some("eventHash"),
+            "event_hash",
+            details.event_hash.clone(),
+        ),
+        (
+            "cs6",
+            Some("prevHash"),
+            "prev_hash",
+            details.prev_hash.clone(),
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter_map(|(cef_key, cef_label, sd_name, value)| {
+            value.map(|value| DetailField {
+                cef_key,
+                cef_label,
+                sd_name,
+                value,
+            })
+        })
+        .collect()
+}
+
+fn escape_cef_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn escape_cef_extension(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
+
+/// Format an event as an ArcSight Common Event Format (CEF) record
+pub fn format_cef(event: &AuditEvent) -> String {
+    let mut extensions = Vec::new();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(&event.timestamp) {
+        extensions.push(format!("rt={}", time.timestamp_millis()));
+    }
+    extensions.push(format!("act={}", event.event));
+    for field in detail_fields(&event.details) {
+        if let Some(label) = field.cef_label {
+            extensions.push(format!("{}Label={}", field.cef_key, label));
+        }
+        extensions.push(format!(
+            "{}={}",
+            field.cef_key,
+            escape_cef_extension(&field.value)
+        ));
+    }
+    if let Some(reason) = &event.details.reason {
+        extensions.push(format!("msg={}", escape_cef_extension(reason)));
+    }
+
+    format!(
+        "CEF:0|{}|{}|{}|{}|{}|{}|{}",
+        CEF_VENDOR,
+        CEF_PRODUCT,
+        escape_cef_header(env!("CARGO_PKG_VERSION")),
+        event.event,
+        escape_cef_header(event.event.description()),
+        event.event.cef_severity(),
+        extensions.join(" ")
+    )
+}
+
+fn escape_sd_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+/// Format an event as an RFC 5424 syslog message
+///
+/// With [`AuditForwardFormat::Cef`] the message body is the CEF record; otherwise event
+/// details are carried as structured data.
+pub fn format_syslog(
+    event: &AuditEvent,
+    facility: u8,
+    hostname: &str,
+    format: AuditForwardFormat,
+) -> String {
+    let priority = u16::from(facility) * 8 + u16::from(event.event.syslog_severity());
+    let (structured_data, message) = match format {
+        AuditForwardFormat::Cef => ("-".to_string(), format_cef(event)),
+        AuditForwardFormat::Syslog => {
+            let mut params = vec![format!("event=\"{}\"", event.event)];
+            for field in detail_fields(&event.details) {
+                params.push(format!(
+                    "{}=\"{}\"",
+                    field.sd_name,
+                    escape_sd_value(&field.value)
+                ));
+            }
+            let message = match &event.details.reason {
+                Some(reason) => format!("{}: {}", event.event.description(), reason),
+                None => event.event.description().to_string(),
+            };
+            (format!("[{} {}]", SYSLOG_SD_ID, params.join(" ")), message)
+        }
+    };
+
+    format!(
+        "<{}>1 {} {} {} {} {} {} {}",
+        priority,
+        event.timestamp,
+        hostname,
+        SYSLOG_APP_NAME,
+        std::process::id(),
+        event.event,
+        structured_data,
+        message.replace(['\r', '\n'], " ")
+    )
+}
+
+/// Host name for syslog headers ("-" when unknown, per RFC 5424)
+pub fn hostname() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        let ret = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+        if ret == 0 {
+            let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            if end > 0 {
+                return String::from_utf8_lossy(&buf[..end]).into_owned();
+            }
+        }
+    }
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|h| !h.is_empty() && !h.contains(' '))
+        .unwrap_or_else(|| "-".to_string())
+}
+
 fn truncate_event_hash(full_hash_hex: &str) -> String {
     full_hash_hex[..EVENT_HASH_HEX_LEN.min(full_hash_hex.len())].to_string()
 }
@@ -555,6 +1091,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_log_capture_pause_window() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path());
+
+        log.log_capture_paused(
+            "2026-10-17T10:00:00+00:00",
+            Some("2026-10-17T12:00:00+00:00"),
+            Some("rotating keys"),
+        )
+        .unwrap();
+        log.log_capture_resumed("2026-10-17T10:00:00+00:00", "whogitit resume")
+            .unwrap();
+
+        let events = log.read_all().unwrap();
+        assert_eq!(events[0].event, AuditEventType::CapturePaused);
+        assert_eq!(
+            events[0].details.reason.as_deref(),
+            Some("paused from 2026-10-17T10:00:00+00:00 until 2026-10-17T12:00:00+00:00: rotating keys")
+        );
+        assert_eq!(events[1].event, AuditEventType::CaptureResumed);
+        assert_eq!(events[1].event.to_string(), "capture_resumed");
+        assert!(log.verify_chain().unwrap());
+    }
+
+    #[test]
+    fn test_log_prompt_access() {
+        let dir = TempDir::new().unwrap();
+        let mut config = AuditConfig::default();
+        assert!(AuditLog::for_prompt_access(dir.path(), &config).is_none());
+
+        config.log_prompt_access = true;
+        let log = AuditLog::for_prompt_access(dir.path(), &config).unwrap();
+        log.log_prompt_access("show", "abc123", &[0, 2]).unwrap();
+
+        let events = log.read_all().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, AuditEventType::PromptAccess);
+        assert_eq!(events[0].details.command.as_deref(), Some("show"));
+        assert_eq!(events[0].details.prompts, Some(vec![0, 2]));
+        assert!(format_cef(&events[0]).contains("flexString2Label=prompts flexString2=0,2"));
+        assert!(log.verify_chain().unwrap());
+    }
+
     #[test]
     fn test_hashable_event_content_excludes_event_hash() {
         let dir = TempDir::new().unwrap();
@@ -577,4 +1157,114 @@ mod tests {
 
         assert_eq!(hashable_a, hashable_b);
     }
+
+    fn sample_event() -> AuditEvent {
+        AuditEvent {
+            timestamp: "2026-01-30T10:00:00Z".to_string(),
+            event: AuditEventType::Delete,
+            details: AuditDetails {
+                commit: Some("abc123".to_string()),
+                reason: Some("GDPR a=b\nline".to_string()),
+                user: Some("alice".to_string()),
+                event_hash: Some("ff00".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_format_cef() {
+        let cef = format_cef(&sample_event());
+
+        assert!(cef.starts_with(&format!(
+            "CEF:0|dotsetlabs|whogitit|{}|delete|Attribution deleted|7|",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(cef.contains("rt=1769767200000 act=delete suser=alice"));
+        assert!(cef.contains("cs1Label=commit cs1=abc123"));
+        assert!(cef.contains("cs5Label=eventHash cs5=ff00"));
+        // Extension values escape '=' and newlines
+        assert!(cef.ends_with("msg=GDPR a\\=b\\nline"));
+    }
+
+    #[test]
+    fn test_format_syslog() {
+        let event = sample_event();
+        let line = format_syslog(&event, 16, "host1", AuditForwardFormat::Syslog);
+
+        // local0 (16) * 8 + warning (4)
+        assert!(line.starts_with("<132>1 2026-01-30T10:00:00Z host1 whogitit "));
+        assert!(line.contains(
+            " delete [whogitit@32473 event=\"delete\" user=\"alice\" commit=\"abc123\" event_hash=\"ff00\"] "
+        ));
+        assert!(line.ends_with("Attribution deleted: GDPR a=b line"));
+
+        let cef_line = format_syslog(&event, 4, "host1", AuditForwardFormat::Cef);
+        assert!(cef_line.starts_with("<36>1 "));
+        assert!(cef_line.contains(" delete - CEF:0|"));
+    }
+
+    #[test]
+    fn test_syslog_facility() {
+        assert_eq!(syslog_facility("auth"), Some(4));
+        assert_eq!(syslog_facility("LOCAL3"), Some(19));
+        assert_eq!(syslog_facility("local8"), None);
+        assert_eq!(syslog_facility("bogus"), None);
+    }
+
+    #[test]
+    fn test_forward_target_parse() {
+        assert_eq!(
+            ForwardTarget::parse("udp://127.0.0.1:514").unwrap(),
+            ForwardTarget::Udp("127.0.0.1:514".to_string())
+        );
+        assert_eq!(
+            ForwardTarget::parse("tcp://siem:6514").unwrap(),
+            ForwardTarget::Tcp("siem:6514".to_string())
+        );
+        assert!(ForwardTarget::parse("http://siem").is_err());
+    }
+
+    #[test]
+    fn test_log_forwards_to_udp_collector() {
+        let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+        collector
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let config = AuditForwardingConfig {
+            enabled: true,
+            address: format!("udp://{}", collector.local_addr().unwrap()),
+            format: AuditForwardFormat::Cef,
+            facility: "auth".to_string(),
+        };
+
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path()).with_forwarding(&config);
+        log.log_export("json", 3).unwrap();
+
+        let mut buf = [0u8; 2048];
+        let (len, _) = collector.recv_from(&mut buf).unwrap();
+        let message = String::from_utf8_lossy(&buf[..len]);
+        assert!(message.starts_with("<37>1 "));
+        assert!(message.contains("CEF:0|dotsetlabs|whogitit|"));
+        assert!(message.contains("cn1=3"));
+        // Still written locally
+        assert_eq!(log.read_all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_forwarding_disabled_or_invalid() {
+        assert!(
+            AuditForwarder::from_config(&AuditForwardingConfig::default())
+                .unwrap()
+                .is_none()
+        );
+
+        let config = AuditForwardingConfig {
+            enabled: true,
+            facility: "nope".to_string(),
+            ..Default::default()
+        };
+        assert!(AuditForwarder::from_config(&config).is_err());
+    }
 }
@@ -0,0 +1,629 @@
+//! SQLite index of per-file attribution across commits
+//!
+//! Cached at `.git/whogitit/index.db` so repo-wide queries read a table instead of
+//! parsing every note and re-reading blobs. Each indexed commit remembers the note
+//! blob it was built from; a rewritten or removed note is picked up on the next
+//! lookup or refresh, so the index never needs to be trusted over the notes.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use git2::{Commit, Oid, Repository};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::capture::snapshot::AttributionSummary;
+use crate::core::attribution::AIAttribution;
+use crate::core::integrity;
+use crate::core::languages::LanguageMap;
+use crate::core::query::{FileRecord, Query};
+use crate::storage::notes::NotesStore;
+
+/// Database file (inside the git dir)
+const INDEX_FILE: &str = "whogitit/index.db";
+/// Stored as `PRAGMA user_version`; databases with another version are rebuilt
+const SCHEMA_VERSION: i32 = 1;
+/// How long to wait for another process (e.g. the post-commit hook) to finish writing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+CREATE TABLE commits (
+    commit_id TEXT PRIMARY KEY,
+    note_id TEXT NOT NULL,
+    author TEXT NOT NULL,
+    committed_at INTEGER NOT NULL,
+    model TEXT NOT NULL,
+    session_label TEXT,
+    line_count_issues INTEGER NOT NULL
+);
+CREATE TABLE files (
+    commit_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    total_lines INTEGER NOT NULL,
+    ai_lines INTEGER NOT NULL,
+    ai_modified_lines INTEGER NOT NULL,
+    human_lines INTEGER NOT NULL,
+    original_lines INTEGER NOT NULL,
+    unknown_lines INTEGER NOT NULL,
+    binary_ai_generated INTEGER,
+    PRIMARY KEY (commit_id, position)
+);
+CREATE INDEX files_path ON files (path);
+CREATE TABLE secret_findings (
+    commit_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    line_number INTEGER NOT NULL,
+    pattern_name TEXT NOT NULL
+);
+CREATE INDEX secret_findings_commit ON secret_findings (commit_id);
+";
+
+/// A possible secret recorded in a file's note (`privacy.scan_generated_code`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedSecret {
+    pub line_number: u32,
+    pub pattern_name: String,
+}
+
+/// One file of an indexed commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedFile {
+    pub path: String,
+    pub summary: AttributionSummary,
+    /// For binary assets/lockfiles: whether the commit kept the AI content as-is
+    pub binary_ai_generated: Option<bool>,
+    pub secret_findings: Vec<IndexedSecret>,
+}
+
+/// What repo-wide queries need from one commit's note, without line data
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedCommit {
+    pub commit: String,
+    pub author: String,
+    /// Commit time (seconds since the epoch)
+    pub committed_at: i64,
+    pub model: String,
+    pub session_label: Option<String>,
+    /// Files whose note summary disagrees with the blob or the stored lines
+    pub line_count_issues: usize,
+    pub files: Vec<IndexedFile>,
+}
+
+impl IndexedCommit {
+    /// Build the index entry for `commit` from its attribution
+    ///
+    /// Checks the recorded line counts against the commit's blobs, which is the
+    /// expensive part the index saves on later reads.
+    pub fn from_attribution(
+        repo: &Repository,
+        commit: &Commit,
+        attribution: &AIAttribution,
+    ) -> Result<Self> {
+        let line_count_issues = integrity::check_line_counts(repo, commit, attribution)?.len();
+        Ok(Self {
+            commit: commit.id().to_string(),
+            author: commit.author().name().unwrap_or("Unknown").to_string(),
+            committed_at: commit.time().seconds(),
+            model: attribution.session.model.id.clone(),
+            session_label: attribution.session.label.clone(),
+            line_count_issues,
+            files: attribution
+                .files
+                .iter()
+                .map(|file| IndexedFile {
+                    path: file.path.clone(),
+                    summary: file.summary.clone(),
+                    binary_ai_generated: file.binary.as_ref().map(|b| b.ai_generated),
+                    secret_findings: file
+                        .secret_findings
+                        .iter()
+                        .map(|finding| IndexedSecret {
+                            line_number: finding.line_number,
+                            pattern_name: finding.pattern_name.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Outcome of [`AttributionIndex::refresh`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshStats {
+    /// Commits read from new or rewritten notes
+    pub indexed: usize,
+    /// Commits whose entry was still current
+    pub reused: usize,
+    /// Entries dropped because their note is gone
+    pub removed: usize,
+    /// Notes that could not be read (left out of the index)
+    pub failed: usize,
+}
+
+/// Attribution index for a repository
+pub struct AttributionIndex {
+    conn: Connection,
+}
+
+impl AttributionIndex {
+    /// Path of the index database for a git dir
+    pub fn path(git_dir: &Path) -> PathBuf {
+        git_dir.join(INDEX_FILE)
+    }
+
+    /// Whether the repository has an index yet
+    pub fn exists(repo: &Repository) -> bool {
+        Self::path(repo.path()).exists()
+    }
+
+    /// Open the repository's index, creating it (empty) if needed
+    pub fn open(repo: &Repository) -> Result<Self> {
+        let path = Self::path(repo.path());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create whogitit directory")?;
+        }
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open attribution index {}", path.display()))?;
+        let mut index = Self { conn };
+        index.prepare_schema()?;
+        Ok(index)
+    }
+
+    /// Open the index, or print a warning and return `None` so callers read the
+    /// notes directly
+    pub fn open_or_warn(repo: &Repository) -> Option<Self> {
+        Self::open(repo)
+            .map_err(|err| {
+                eprintln!(
+                    "whogitit: Warning - attribution index unavailable, reading notes: {:#}",
+                    err
+                );
+            })
+            .ok()
+    }
+
+    /// Create the tables, dropping those of an older schema version
+    fn prepare_schema(&mut self) -> Result<()> {
+        self.conn.busy_timeout(BUSY_TIMEOUT)?;
+        // A cache: losing the last writes on power failure only means re-reading notes
+        self.conn.pragma_update(None, "journal_mode", "WAL")?;
+        self.conn.pragma_update(None, "synchronous", "NORMAL")?;
+        let version: i32 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version == SCHEMA_VERSION {
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        tx.execute_batch(
+            "DROP TABLE IF EXISTS secret_findings;
+             DROP TABLE IF EXISTS files;
+             DROP TABLE IF EXISTS commits;",
+        )?;
+        tx.execute_batch(SCHEMA)?;
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        tx.commit().context("Failed to create attribution index")?;
+        Ok(())
+    }
+
+    /// Number of indexed commits
+    pub fn commit_count(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM commits", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Number of indexed file entries
+    pub fn file_count(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Index entry for a commit, indexing its note first if the entry is missing or stale
+    ///
+    /// Returns `None` for commits without a note (or with an unreadable one).
+    pub fn lookup(
+        &mut self,
+        repo: &Repository,
+        notes_store: &NotesStore,
+        commit_oid: Oid,
+    ) -> Result<Option<IndexedCommit>> {
+        let commit_id = commit_oid.to_string();
+        let stored = stored_note_id(&self.conn, &commit_id)?;
+        let Some(note_id) = notes_store.note_id(commit_oid)? else {
+            if stored.is_some() {
+                remove_commit(&self.conn, &commit_id)?;
+            }
+            return Ok(None);
+        };
+        let note_id = note_id.to_string();
+        if stored.as_deref() == Some(note_id.as_str()) {
+            return load_commit(&self.conn, &commit_id);
+        }
+
+        let Some(indexed) = read_note(repo, notes_store, commit_oid)? else {
+            remove_commit(&self.conn, &commit_id)?;
+            return Ok(None);
+        };
+        let tx = self.conn.transaction()?;
+        insert_commit(&tx, &note_id, &indexed)?;
+        tx.commit().context("Failed to update attribution index")?;
+        Ok(Some(indexed))
+    }
+
+    /// Bring the index up to date with the notes: index new and rewritten notes and
+    /// drop entries whose note was removed
+    pub fn refresh(&mut self, repo: &Repository, notes_store: &NotesStore) -> Result<RefreshStats> {
+        let entries = notes_store.list_note_entries()?;
+        let mut stored: HashMap<String, String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT commit_id, note_id FROM commits")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut stats = RefreshStats::default();
+        let tx = self.conn.transaction()?;
+        for (commit_oid, note_oid) in entries {
+            let commit_id = commit_oid.to_string();
+            let note_id = note_oid.to_string();
+            if stored.remove(&commit_id).as_deref() == Some(note_id.as_str()) {
+                stats.reused += 1;
+                continue;
+            }
+            match read_note(repo, notes_store, commit_oid) {
+                Ok(Some(indexed)) => {
+                    insert_commit(&tx, &note_id, &indexed)?;
+                    stats.indexed += 1;
+                }
+                Ok(None) | Err(_) => {
+                    remove_commit(&tx, &commit_id)?;
+                    stats.failed += 1;
+                }
+            }
+        }
+        for commit_id in stored.keys() {
+            remove_commit(&tx, commit_id)?;
+            stats.removed += 1;
+        }
+        tx.commit().context("Failed to update attribution index")?;
+        Ok(stats)
+    }
+
+    /// Drop every entry, so the next refresh reads all notes again
+    pub fn clear(&mut self) -> Result<()> {
+        self.conn
+            .execute_batch(
+                "DELETE FROM secret_findings;
+                 DELETE FROM files;
+                 DELETE FROM commits;",
+            )
+            .context("Failed to clear attribution index")?;
+        Ok(())
+    }
+
+    /// Indexed commits without a file matching `query`
+    ///
+    /// Call [`Self::refresh`] first; commits missing from the index are not returned.
+    pub fn commits_without_match(
+        &self,
+        query: &Query,
+        languages: &LanguageMap,
+    ) -> Result<HashSet<String>> {
+        let mut unmatched: HashSet<String> = {
+            let mut stmt = self.conn.prepare("SELECT commit_id FROM commits")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let mut stmt = self.conn.prepare(
+            "SELECT f.commit_id, f.path, c.model, c.author, f.total_lines, f.ai_lines,
+                    f.ai_modified_lines, f.human_lines, f.original_lines, f.unknown_lines
+             FROM files f JOIN commits c ON c.commit_id = f.commit_id",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let commit_id: String = row.get(0)?;
+            if !unmatched.contains(&commit_id) {
+                continue;
+            }
+            let path: String = row.get(1)?;
+            let model: String = row.get(2)?;
+            let author: String = row.get(3)?;
+            let summary = summary_from_row(row, 4)?;
+            let record = FileRecord {
+                path: &path,
+                model: &model,
+                author: &author,
+                language: languages.detect(&path),
+                summary: &summary,
+            };
+            if query.matches(&record) {
+                unmatched.remove(&commit_id);
+            }
+        }
+        Ok(unmatched)
+    }
+}
+
+/// Read a commit's note into an index entry
+fn read_note(
+    repo: &Repository,
+    notes_store: &NotesStore,
+    commit_oid: Oid,
+) -> Result<Option<IndexedCommit>> {
+    let Some(attribution) = notes_store.fetch_attribution(commit_oid)? else {
+        return Ok(None);
+    };
+    let commit = repo.find_commit(commit_oid)?;
+    IndexedCommit::from_attribution(repo, &commit, &attribution).map(Some)
+}
+
+fn stored_note_id(conn: &Connection, commit_id: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT note_id FROM commits WHERE commit_id = ?1",
+            [commit_id],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn remove_commit(conn: &Connection, commit_id: &str) -> Result<()> {
+    for table in ["secret_findings", "files", "commits"] {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE commit_id = ?1", table),
+            [commit_id],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_commit(conn: &Connection, note_id: &str, indexed: &IndexedCommit) -> Result<()> {
+    remove_commit(conn, &indexed.commit)?;
+    conn.execute(
+        "INSERT INTO commits (commit_id, note_id, author, committed_at, model, session_label,
+                              line_count_issues)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            indexed.commit,
+            note_id,
+            indexed.author,
+            indexed.committed_at,
+            indexed.model,
+            indexed.session_label,
+            indexed.line_count_issues as i64,
+        ],
+    )?;
+    for (position, file) in indexed.files.iter().enumerate() {
+        let summary = &file.summary;
+        conn.execute(
+            "INSERT INTO files (commit_id, position, path, total_lines, ai_lines,
+                                ai_modified_lines, human_lines, original_lines, unknown_lines,
+                                binary_ai_generated)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                indexed.commit,
+                position as i64,
+                file.path,
+                summary.total_lines as i64,
+                summary.ai_lines as i64,
+                summary.ai_modified_lines as i64,
+                summary.human_lines as i64,
+                summary.original_lines as i64,
+                summary.unknown_lines as i64,
+                file.binary_ai_generated,
+            ],
+        )?;
+        for finding in &file.secret_findings {
+            conn.execute(
+                "INSERT INTO secret_findings (commit_id, path, line_number, pattern_name)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    indexed.commit,
+                    file.path,
+                    finding.line_number,
+                    finding.pattern_name
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn load_commit(conn: &Connection, commit_id: &str) -> Result<Option<IndexedCommit>> {
+    let Some(mut indexed) = conn
+        .query_row(
+            "SELECT author, committed_at, model, session_label, line_count_issues
+             FROM commits WHERE commit_id = ?1",
+            [commit_id],
+            |row| {
+                Ok(IndexedCommit {
+                    commit: commit_id.to_string(),
+                    author: row.get(0)?,
+                    committed_at: row.get(1)?,
+                    model: row.get(2)?,
+                    session_label: row.get(3)?,
+                    line_count_issues: row.get::<_, i64>(4)? as usize,
+                    files: Vec::new(),
+                })
+            },
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+
+    let mut findings: HashMap<String, Vec<IndexedSecret>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT path, line_number, pattern_name FROM secret_findings
+         WHERE commit_id = ?1 ORDER BY rowid",
+    )?;
+    let mut rows = stmt.query([commit_id])?;
+    while let Some(row) = rows.next()? {
+        findings
+            .entry(row.get(0)?)
+            .or_default()
+            .push(IndexedSecret {
+                line_number: row.get(1)?,
+                pattern_name: row.get(2)?,
+            });
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT path, total_lines, ai_lines, ai_modified_lines, human_lines, original_lines,
+                unknown_lines, binary_ai_generated
+         FROM files WHERE commit_id = ?1 ORDER BY position",
+    )?;
+    let mut rows = stmt.query([commit_id])?;
+    while let Some(row) = rows.next()? {
+        let path: String = row.get(0)?;
+        indexed.files.push(IndexedFile {
+            secret_findings: findings.remove(&path).unwrap_or_default(),
+            summary: summary_from_row(row, 1)?,
+            binary_ai_generated: row.get(7)?,
+            path,
+        });
+    }
+    Ok(Some(indexed))
+}
+
+/// Read the six line counts starting at column `first`
+fn summary_from_row(row: &rusqlite::Row, first: usize) -> rusqlite::Result<AttributionSummary> {
+    let count = |offset: usize| row.get::<_, i64>(first + offset).map(|n| n as usize);
+    Ok(AttributionSummary {
+        total_lines: count(0)?,
+        ai_lines: count(1)?,
+        ai_modified_lines: count(2)?,
+        human_lines: count(3)?,
+        original_lines: count(4)?,
+        unknown_lines: count(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::FileAttributionResult;
+    use crate::core::attribution::{ModelInfo, SessionMetadata};
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, message: &str) -> Oid {
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap()
+    }
+
+    fn attribution(files: &[(&str, usize, usize)]) -> AIAttribution {
+        AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 0,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: Some("refactor".to_string()),
+            },
+            prompts: vec![],
+            files: files
+                .iter()
+                .map(|(path, ai_lines, human_lines)| FileAttributionResult {
+                    path: path.to_string(),
+                    lines: vec![],
+                    summary: AttributionSummary {
+                        total_lines: ai_lines + human_lines,
+                        ai_lines: *ai_lines,
+                        ai_modified_lines: 0,
+                        human_lines: *human_lines,
+                        original_lines: 0,
+                        unknown_lines: 0,
+                    },
+                    binary: None,
+                    blocks: None,
+                    analysis_warning: None,
+                    secret_findings: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_lookup_follows_rewritten_and_removed_notes() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let store = NotesStore::new(&repo).unwrap();
+        let oid = commit(&repo, "first");
+        store
+            .store_attribution(oid, &attribution(&[("src/a.rs", 8, 2)]))
+            .unwrap();
+
+        let mut index = AttributionIndex::open(&repo).unwrap();
+        let indexed = index.lookup(&repo, &store, oid).unwrap().unwrap();
+        assert_eq!(indexed.session_label.as_deref(), Some("refactor"));
+        assert_eq!(indexed.files[0].summary.ai_lines, 8);
+        // A reopened index serves the stored entry
+        let mut index = AttributionIndex::open(&repo).unwrap();
+        assert_eq!(index.lookup(&repo, &store, oid).unwrap(), Some(indexed));
+
+        store
+            .store_attribution(oid, &attribution(&[("src/a.rs", 3, 7), ("src/b.rs", 1, 0)]))
+            .unwrap();
+        let indexed = index.lookup(&repo, &store, oid).unwrap().unwrap();
+        assert_eq!(indexed.files.len(), 2);
+        assert_eq!(indexed.files[0].summary.ai_lines, 3);
+        assert_eq!(index.file_count().unwrap(), 2);
+
+        store.remove_attribution(oid).unwrap();
+        assert_eq!(index.lookup(&repo, &store, oid).unwrap(), None);
+        assert_eq!(index.commit_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_refresh_and_commits_without_match() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let store = NotesStore::new(&repo).unwrap();
+        let mostly_ai = commit(&repo, "first");
+        store
+            .store_attribution(mostly_ai, &attribution(&[("src/a.rs", 9, 1)]))
+            .unwrap();
+        let mostly_human = commit(&repo, "second");
+        store
+            .store_attribution(mostly_human, &attribution(&[("src/b.rs", 1, 9)]))
+            .unwrap();
+
+        let mut index = AttributionIndex::open(&repo).unwrap();
+        let stats = index.refresh(&repo, &store).unwrap();
+        assert_eq!((stats.indexed, stats.reused, stats.removed), (2, 0, 0));
+        let stats = index.refresh(&repo, &store).unwrap();
+        assert_eq!((stats.indexed, stats.reused, stats.removed), (0, 2, 0));
+
+        let query = Query::parse("ai_percent > 50").unwrap();
+        let languages = LanguageMap::new(&Default::default());
+        let skip = index.commits_without_match(&query, &languages).unwrap();
+        assert_eq!(skip, HashSet::from([mostly_human.to_string()]));
+
+        store.remove_attribution(mostly_human).unwrap();
+        let stats = index.refresh(&repo, &store).unwrap();
+        assert_eq!((stats.indexed, stats.reused, stats.removed), (0, 1, 1));
+    }
+}
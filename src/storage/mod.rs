@@ -1,7 +1,20 @@
 pub mod audit;
+#[cfg(feature = "git")]
+pub mod index;
+pub mod note_format;
+#[cfg(feature = "git")]
 pub mod notes;
+#[cfg(feature = "git")]
+pub mod notes_push;
+#[cfg(feature = "git")]
+pub mod notes_sync;
+#[cfg(feature = "git")]
+pub mod prompt_index;
+pub mod repo_lock;
 pub mod trailers;
 
 pub use audit::{AuditEvent, AuditEventType, AuditLog};
+#[cfg(feature = "git")]
 pub use notes::NotesStore;
+pub use repo_lock::{LockHolderKind, RepoLock};
 pub use trailers::{TrailerGenerator, TrailerParser};
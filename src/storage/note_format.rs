@@ -0,0 +1,313 @@
+//! Attribution note payloads, independent of git
+//!
+//! Notes hold an [`AIAttribution`] as compact JSON with line content replaced by
+//! hashes. Parsing, hashing and restoring content work on plain strings, so
+//! anything holding note or export JSON (a browser viewer built without the
+//! `git` feature, for one) can read them; [`super::notes`] adds the git side.
+//!
+//! Notes are written in canonical form (see [`canonical_json`]) so the same
+//! attribution has the same bytes on every machine, which is what replication
+//! and signing compare.
+
+use std::cmp::Ordering;
+
+use anyhow::{Context, Result};
+use serde_json::{Number, Value};
+
+use crate::capture::snapshot::line_hash;
+use crate::core::attribution::{AIAttribution, MIN_NATIVE_SCHEMA_VERSION, SCHEMA_VERSION};
+
+/// Decimal places kept for floats (confidence, similarity) in canonical payloads
+const CANONICAL_FLOAT_DECIMALS: i32 = 6;
+
+/// Parse a note payload
+pub fn parse_note(payload: &str) -> Result<AIAttribution> {
+    serde_json::from_str(payload).context("Failed to parse attribution JSON")
+}
+
+/// Serialize an attribution as a canonical note payload
+///
+/// Compact JSON with object keys sorted, files ordered by path, lines by line
+/// number, and floats rounded to six decimal places (negative zero written as
+/// zero). Prompts keep their order; lines refer to them by index.
+pub fn canonical_json(attribution: &AIAttribution) -> Result<String> {
+    let value = serde_json::to_value(attribution).context("Failed to serialize attribution")?;
+    canonical_string(value)
+}
+
+/// Canonical form of an existing note payload, whichever version wrote it
+///
+/// Unknown fields are kept, so notes from newer builds compare and sign the same
+/// way. Two payloads describe the same attribution exactly when their canonical
+/// forms are equal.
+pub fn canonicalize_note(payload: &str) -> Result<String> {
+    let value = serde_json::from_str(payload).context("Failed to parse attribution JSON")?;
+    canonical_string(value)
+}
+
+fn canonical_string(mut value: Value) -> Result<String> {
+    canonicalize_value(&mut value);
+    if let Some(files) = value.get_mut("files").and_then(Value::as_array_mut) {
+        files.sort_by(|a, b| compare_field(a, b, "path"));
+        for file in files {
+            if let Some(lines) = file.get_mut("lines").and_then(Value::as_array_mut) {
+                lines.sort_by(|a, b| compare_field(a, b, "line_number"));
+            }
+        }
+    }
+    serde_json::to_string(&value).context("Failed to serialize attribution to JSON")
+}
+
+/// Sort object keys and round floats, recursively
+fn canonicalize_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (_, entry) in &mut entries {
+                canonicalize_value(entry);
+            }
+            *map = entries.into_iter().collect();
+        }
+        Value::Array(items) => items.iter_mut().for_each(canonicalize_value),
+        Value::Number(number) if number.is_f64() => {
+            if let Some(rounded) = number.as_f64().map(round_float).and_then(Number::from_f64) {
+                *number = rounded;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn round_float(value: f64) -> f64 {
+    let scale = 10f64.powi(CANONICAL_FLOAT_DECIMALS);
+    let rounded = (value * scale).round() / scale;
+    if rounded == 0.0 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
+/// Order two objects by a string or integer field
+fn compare_field(a: &Value, b: &Value, field: &str) -> Ordering {
+    match (a.get(field), b.get(field)) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_u64()
+            .cmp(&b.as_u64())
+            .then_with(|| a.to_string().cmp(&b.to_string())),
+        (a, b) => a.and_then(Value::as_str).cmp(&b.and_then(Value::as_str)),
+    }
+}
+
+/// Replace line content with its hash and mark the payload with the current schema
+pub fn strip_line_content(attribution: &mut AIAttribution) {
+    attribution.version = SCHEMA_VERSION;
+    for line in attribution
+        .files
+        .iter_mut()
+        .flat_map(|f| f.lines.iter_mut())
+    {
+        if !line.content.is_empty() || line.content_hash.is_none() {
+            line.content_hash = Some(line_hash(&line.content));
+        }
+        line.content.clear();
+    }
+}
+
+/// Whether any line is stored as a hash only
+pub fn needs_line_content(attribution: &AIAttribution) -> bool {
+    attribution
+        .files
+        .iter()
+        .flat_map(|f| &f.lines)
+        .any(|l| l.content.is_empty() && l.content_hash.is_some())
+}
+
+/// Fill in line content stored as hashes from the files' text
+///
+/// `file_text` returns the text of a path at the attributed commit. Lines whose
+/// text doesn't match the hash (e.g. a note copied to a commit with different
+/// content) are left empty.
+pub fn restore_line_content(
+    attribution: &mut AIAttribution,
+    mut file_text: impl FnMut(&str) -> Option<String>,
+) {
+    for file in &mut attribution.files {
+        if !file
+            .lines
+            .iter()
+            .any(|l| l.content.is_empty() && l.content_hash.is_some())
+        {
+            continue;
+        }
+        let Some(text) = file_text(&file.path) else {
+            continue;
+        };
+        let text_lines: Vec<&str> = text.lines().collect();
+        for line in &mut file.lines {
+            let Some(hash) = &line.content_hash else {
+                continue;
+            };
+            let content = (line.line_number as usize)
+                .checked_sub(1)
+                .and_then(|i| text_lines.get(i));
+            if let Some(content) = content {
+                if line_hash(content) == *hash {
+                    line.content = content.to_string();
+                }
+            }
+        }
+    }
+}
+
+/// Warning for a note written with a schema this build doesn't read natively
+pub fn schema_version_warning(commit: &str, note_version: u8) -> Option<String> {
+    if (MIN_NATIVE_SCHEMA_VERSION..=SCHEMA_VERSION).contains(&note_version) {
+        return None;
+    }
+
+    Some(if note_version < SCHEMA_VERSION {
+        format!(
+            "commit {} uses attribution schema v{} (current is v{}); continuing in compatibility mode.",
+            commit, note_version, SCHEMA_VERSION
+        )
+    } else {
+        format!(
+            "commit {} uses newer attribution schema v{} (this build supports v{}); some fields may be ignored.",
+            commit, note_version, SCHEMA_VERSION
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::{
+        AttributionSummary, FileAttributionResult, LineAttribution, LineSource,
+    };
+    use crate::core::attribution::{ModelInfo, SessionMetadata};
+
+    #[test]
+    fn test_stripped_note_round_trips_through_file_text() {
+        let line = |n: u32, content: &str| LineAttribution {
+            line_number: n,
+            content: content.to_string(),
+            source: LineSource::Human,
+            edit_id: None,
+            prompt_index: None,
+            confidence: 1.0,
+            content_hash: None,
+            timestamp: None,
+        };
+        let mut attribution = AIAttribution {
+            version: 1,
+            session: SessionMetadata {
+                session_id: "s".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-01T00:00:00Z".to_string(),
+                prompt_count: 0,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files: vec![FileAttributionResult {
+                path: "src/a.rs".to_string(),
+                lines: vec![line(1, "fn a() {}"), line(2, "fn b() {}")],
+                summary: AttributionSummary {
+                    total_lines: 2,
+                    ai_lines: 0,
+                    ai_modified_lines: 0,
+                    human_lines: 2,
+                    original_lines: 0,
+                    unknown_lines: 0,
+                },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: vec![],
+            }],
+        };
+
+        strip_line_content(&mut attribution);
+        assert_eq!(attribution.version, SCHEMA_VERSION);
+        let mut parsed = parse_note(&serde_json::to_string(&attribution).unwrap()).unwrap();
+        assert!(needs_line_content(&parsed));
+
+        restore_line_content(&mut parsed, |path| {
+            (path == "src/a.rs").then(|| "fn a() {}\nfn changed() {}\n".to_string())
+        });
+        assert_eq!(parsed.files[0].lines[0].content, "fn a() {}");
+        assert_eq!(parsed.files[0].lines[1].content, "");
+
+        assert!(parse_note("{not json").is_err());
+        assert!(schema_version_warning("abc", SCHEMA_VERSION).is_none());
+        assert!(schema_version_warning("abc", SCHEMA_VERSION + 1)
+            .unwrap()
+            .contains("newer attribution schema"));
+    }
+
+    #[test]
+    fn test_canonical_json_is_order_and_float_stable() {
+        let file = |path: &str, confidence: f64| FileAttributionResult {
+            path: path.to_string(),
+            lines: vec![LineAttribution {
+                line_number: 1,
+                content: String::new(),
+                source: LineSource::AIModified {
+                    edit_id: "e1".to_string(),
+                    similarity: 0.1 + 0.2,
+                },
+                edit_id: None,
+                prompt_index: Some(0),
+                confidence,
+                content_hash: Some("h".to_string()),
+                timestamp: None,
+            }],
+            summary: AttributionSummary {
+                total_lines: 1,
+                ai_lines: 0,
+                ai_modified_lines: 1,
+                human_lines: 0,
+                original_lines: 0,
+                unknown_lines: 0,
+            },
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: vec![],
+        };
+        let attribution = |files: Vec<FileAttributionResult>| AIAttribution {
+            version: SCHEMA_VERSION,
+            session: SessionMetadata {
+                session_id: "s".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-01T00:00:00Z".to_string(),
+                prompt_count: 1,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files,
+        };
+
+        let a = attribution(vec![file("src/b.rs", 0.9), file("src/a.rs", -0.0)]);
+        let b = attribution(vec![
+            file("src/a.rs", 0.0),
+            file("src/b.rs", 0.900_000_000_1),
+        ]);
+        let canonical = canonical_json(&a).unwrap();
+        assert_eq!(canonical, canonical_json(&b).unwrap());
+        assert!(canonical.find("src/a.rs").unwrap() < canonical.find("src/b.rs").unwrap());
+        assert!(canonical.contains("\"similarity\":0.3,"));
+        assert!(canonical.contains("\"confidence\":0.0,"));
+        assert!(canonical.starts_with("{\"files\":"));
+
+        // Plain serialization (field order, pretty-printed) canonicalizes to the same bytes
+        let pretty = serde_json::to_string_pretty(&a).unwrap();
+        assert_eq!(canonicalize_note(&pretty).unwrap(), canonical);
+        assert_eq!(parse_note(&canonical).unwrap().files[0].path, "src/a.rs");
+    }
+}
@@ -1,72 +1,394 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
 use anyhow::{Context, Result};
-use git2::{Oid, Repository, Signature};
+use git2::{ErrorCode, Oid, Repository, Signature};
 
-use crate::core::attribution::{AIAttribution, SCHEMA_VERSION};
+use crate::capture::encoding;
+use crate::core::attribution::AIAttribution;
+use crate::privacy::{StorageConfig, WhogititConfig};
+use crate::storage::note_format::{self, strip_line_content};
 
 /// Notes reference used for AI attribution storage
 pub const NOTES_REF: &str = "refs/notes/whogitit";
+/// Prefix of namespaced notes refs. They are siblings of `NOTES_REF` rather than
+/// children because git cannot store refs below an existing ref.
+pub const NOTES_NAMESPACE_PREFIX: &str = "refs/notes/whogitit-ns/";
+/// Namespace name for `NOTES_REF` in `storage.read_order`
+pub const DEFAULT_NAMESPACE: &str = "default";
 /// Warn when a single attribution note grows beyond this size.
 const NOTE_SIZE_WARN_BYTES: usize = 512 * 1024;
 /// Reject note payloads above this size to avoid pathological note objects.
 const NOTE_SIZE_HARD_LIMIT_BYTES: usize = 4 * 1024 * 1024;
+/// Attempts made when a concurrent writer holds the notes ref lock.
+const NOTE_WRITE_MAX_ATTEMPTS: u32 = 5;
+/// Initial backoff between note write attempts (doubles each retry).
+const NOTE_WRITE_BASE_DELAY_MS: u64 = 50;
+/// Directory (inside the git dir) holding payloads whose note write failed.
+const STAGED_NOTES_DIR: &str = "whogitit/staged-notes";
+/// Prefix of the short-lived refs a note is written on before the notes ref moves.
+const NOTE_WRITE_TMP_PREFIX: &str = "refs/whogitit/note-write/";
+
+/// Notes ref for a namespace (`default` is the main ref)
+pub fn namespace_ref(namespace: &str) -> Result<String> {
+    if namespace == DEFAULT_NAMESPACE {
+        return Ok(NOTES_REF.to_string());
+    }
+    let name = format!("{}{}", NOTES_NAMESPACE_PREFIX, namespace);
+    if namespace.is_empty() || namespace.contains('/') || !git2::Reference::is_valid_name(&name) {
+        anyhow::bail!("Invalid notes namespace '{}'", namespace);
+    }
+    Ok(name)
+}
 
 /// Git notes storage for AI attribution data
+///
+/// Writes go to one notes ref; reads consult several in precedence order, and for
+/// each commit the note from the first ref that has one is used.
 pub struct NotesStore<'a> {
     repo: &'a Repository,
+    /// Ref written by this store
+    write_ref: String,
+    /// Refs read, highest precedence first
+    read_refs: Vec<String>,
 }
 
 impl<'a> NotesStore<'a> {
+    /// Open the store using the repository's `[storage]` namespace settings
+    ///
+    /// Fails on an invalid config rather than silently using the default ref,
+    /// which would put notes where the configured namespace never sees them.
     pub fn new(repo: &'a Repository) -> Result<Self> {
-        Ok(Self { repo })
+        let storage = repo
+            .workdir()
+            .map(WhogititConfig::load)
+            .transpose()
+            .context("Failed to load configuration for notes storage")?
+            .map(|config| config.storage)
+            .unwrap_or_default();
+        Self::with_storage(repo, &storage)
+    }
+
+    /// Open the store with explicit namespace settings
+    pub fn with_storage(repo: &'a Repository, storage: &StorageConfig) -> Result<Self> {
+        let write_ref = match &storage.namespace {
+            Some(namespace) => namespace_ref(namespace)?,
+            None => NOTES_REF.to_string(),
+        };
+        let mut read_refs = Vec::new();
+        if storage.read_order.is_empty() {
+            read_refs.push(write_ref.clone());
+            read_refs.push(NOTES_REF.to_string());
+        } else {
+            for namespace in &storage.read_order {
+                read_refs.push(namespace_ref(namespace)?);
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        read_refs.retain(|r| seen.insert(r.clone()));
+
+        Ok(Self {
+            repo,
+            write_ref,
+            read_refs,
+        })
+    }
+
+    /// Notes ref this store writes to
+    pub fn write_ref(&self) -> &str {
+        &self.write_ref
+    }
+
+    /// Notes refs this store reads, highest precedence first
+    pub fn read_refs(&self) -> &[String] {
+        &self.read_refs
+    }
+
+    /// First note for a commit in read precedence order
+    fn find_note(&self, commit_oid: Oid) -> Result<Option<git2::Note<'a>>> {
+        for notes_ref in &self.read_refs {
+            match self.repo.find_note(Some(notes_ref), commit_oid) {
+                Ok(note) => return Ok(Some(note)),
+                Err(e) if e.code() == ErrorCode::NotFound => {}
+                Err(e) => return Err(e).context("Failed to read git note"),
+            }
+        }
+        Ok(None)
     }
 
     /// Store attribution data as a git note on a commit
+    ///
+    /// The payload is staged under `.git/whogitit/staged-notes/` first so a failed
+    /// attach can be retried later by `whogitit worker`. Lock contention with other
+    /// note writers is retried with exponential backoff. Line content is replaced by
+    /// hashes, so the note never carries code.
     pub fn store_attribution(&self, commit_oid: Oid, attribution: &AIAttribution) -> Result<Oid> {
-        // Store compact JSON to keep note payloads smaller in large sessions.
-        let json = serde_json::to_string(attribution)
-            .context("Failed to serialize attribution to JSON")?;
-        if let Some(warning) = evaluate_note_payload_size(json.len())? {
+        let existing_before = self.read_note_message(commit_oid);
+        let mut attribution = attribution.clone();
+        strip_line_content(&mut attribution);
+
+        let (json, warning) = serialize_payload(&attribution)?;
+        if let Some(warning) = warning {
             eprintln!("whogitit: Warning - {warning}");
         }
 
+        let staged_path = self.stage_payload(commit_oid, &json);
+        let note_oid =
+            self.write_attribution_with_retry(commit_oid, &attribution, json, existing_before)?;
+        if let Some(path) = staged_path {
+            let _ = fs::remove_file(path);
+        }
+
+        Ok(note_oid)
+    }
+
+    /// Write a note, merging with any note a concurrent writer attached meanwhile
+    ///
+    /// `existing_before` is the note as the caller first read it. The note is read
+    /// again before every write attempt; when another writer changed it in between,
+    /// our attribution is merged into theirs instead of replacing it. The notes ref
+    /// only moves if it still points where it did when the note was read, so a
+    /// note written between the read and the write is merged on the next attempt
+    /// rather than overwritten.
+    fn write_attribution_with_retry(
+        &self,
+        commit_oid: Oid,
+        attribution: &AIAttribution,
+        json: String,
+        existing_before: Option<String>,
+    ) -> Result<Oid> {
         let sig = self.get_signature()?;
+        let mut attempt = 0;
+
+        loop {
+            // Read-merge-write: keep a note another writer attached in the meantime
+            let tip = self.repo.refname_to_id(&self.write_ref).ok();
+            let current = self.read_note_message(commit_oid);
+            let payload = match current
+                .as_deref()
+                .filter(|_| current != existing_before)
+                .and_then(|m| serde_json::from_str::<AIAttribution>(m).ok())
+            {
+                Some(existing) => {
+                    note_format::canonical_json(&merge_attributions(existing, attribution.clone()))
+                        .context("Failed to serialize merged attribution")?
+                }
+                None => json.clone(),
+            };
+
+            match self.write_note_if_unchanged(&sig, commit_oid, &payload, tip) {
+                Ok(Some(oid)) => return Ok(oid),
+                // Another writer moved the notes ref: read and merge again
+                Ok(None) if attempt + 1 < NOTE_WRITE_MAX_ATTEMPTS => attempt += 1,
+                Ok(None) => anyhow::bail!(
+                    "{} kept changing while writing a note (payload staged; run 'whogitit worker' to retry)",
+                    self.write_ref
+                ),
+                Err(e) if is_retryable_note_error(&e) && attempt + 1 < NOTE_WRITE_MAX_ATTEMPTS => {
+                    std::thread::sleep(note_write_backoff(attempt));
+                    attempt += 1;
+                }
+                Err(e) => return Err(e).context(
+                    "Failed to create git note (payload staged; run 'whogitit worker' to retry)",
+                ),
+            }
+        }
+    }
 
-        let note_oid = self
-            .repo
-            .note(&sig, &sig, Some(NOTES_REF), commit_oid, &json, true)
-            .context("Failed to create git note")?;
+    /// Attach a note, moving the notes ref only if it still points at `tip`
+    ///
+    /// The note is written on a temporary ref started at `tip`, so libgit2 lays out
+    /// the notes tree as usual, and the notes ref is then compare-and-swapped to the
+    /// result. Returns `None` when another writer moved the ref first.
+    fn write_note_if_unchanged(
+        &self,
+        sig: &Signature,
+        commit_oid: Oid,
+        payload: &str,
+        tip: Option<Oid>,
+    ) -> std::result::Result<Option<Oid>, git2::Error> {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let tmp_ref = format!(
+            "{}{}-{}",
+            NOTE_WRITE_TMP_PREFIX,
+            std::process::id(),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+        let message = "notes: Notes added by whogitit";
 
+        let result = (|| {
+            if let Some(tip) = tip {
+                self.repo.reference(&tmp_ref, tip, true, message)?;
+            }
+            let note_oid = self
+                .repo
+                .note(sig, sig, Some(&tmp_ref), commit_oid, payload, true)?;
+            let written = self.repo.refname_to_id(&tmp_ref)?;
+            let swapped = match tip {
+                Some(tip) => {
+                    self.repo
+                        .reference_matching(&self.write_ref, written, true, tip, message)
+                }
+                None => self
+                    .repo
+                    .reference(&self.write_ref, written, false, message),
+            };
+            match swapped {
+                Ok(_) => Ok(Some(note_oid)),
+                Err(e) if matches!(e.code(), ErrorCode::Modified | ErrorCode::Exists) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })();
+
+        if let Ok(mut reference) = self.repo.find_reference(&tmp_ref) {
+            let _ = reference.delete();
+        }
+        result
+    }
+
+    fn read_note_message(&self, commit_oid: Oid) -> Option<String> {
+        self.repo
+            .find_note(Some(&self.write_ref), commit_oid)
+            .ok()
+            .and_then(|note| note.message().map(str::to_string))
+    }
+
+    /// Directory holding staged note payloads
+    pub fn staged_dir(&self) -> PathBuf {
+        self.repo.path().join(STAGED_NOTES_DIR)
+    }
+
+    /// Persist a payload before attaching it (best effort, atomic rename)
+    fn stage_payload(&self, commit_oid: Oid, json: &str) -> Option<PathBuf> {
+        let dir = self.staged_dir();
+        let path = dir.join(format!("{}.json", commit_oid));
+        let tmp = dir.join(format!("{}.json.tmp", commit_oid));
+
+        let result = fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(&tmp, json))
+            .and_then(|_| fs::rename(&tmp, &path));
+        match result {
+            Ok(()) => Some(path),
+            Err(e) => {
+                eprintln!("whogitit: Warning - failed to stage note payload: {}", e);
+                let _ = fs::remove_file(&tmp);
+                None
+            }
+        }
+    }
+
+    /// List staged payloads awaiting a retry, as (commit, payload path)
+    pub fn staged_payloads(&self) -> Result<Vec<(Oid, PathBuf)>> {
+        let dir = self.staged_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut staged = Vec::new();
+        for entry in fs::read_dir(&dir).context("Failed to read staged notes directory")? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let oid = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| Oid::from_str(s).ok());
+            if let Some(oid) = oid {
+                staged.push((oid, path));
+            }
+        }
+        staged.sort();
+        Ok(staged)
+    }
+
+    /// Attach a staged payload, removing it on success
+    pub fn retry_staged(&self, commit_oid: Oid, path: &std::path::Path) -> Result<Oid> {
+        let existing_before = self.read_note_message(commit_oid);
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read staged payload {}", path.display()))?;
+        let mut attribution: AIAttribution =
+            serde_json::from_str(&json).context("Failed to parse staged attribution JSON")?;
+        self.repo
+            .find_commit(commit_oid)
+            .with_context(|| format!("Commit {} no longer exists", commit_oid))?;
+        // Payloads staged by older versions may still carry line content
+        strip_line_content(&mut attribution);
+        let json = note_format::canonical_json(&attribution)?;
+
+        let note_oid =
+            self.write_attribution_with_retry(commit_oid, &attribution, json, existing_before)?;
+        fs::remove_file(path).context("Failed to remove staged payload")?;
         Ok(note_oid)
     }
 
     /// Fetch attribution data from a git note
+    ///
+    /// Line content stored as hashes is restored from the commit's blobs; older
+    /// notes carry it inline and are returned as stored.
     pub fn fetch_attribution(&self, commit_oid: Oid) -> Result<Option<AIAttribution>> {
-        match self.repo.find_note(Some(NOTES_REF), commit_oid) {
-            Ok(note) => {
-                if let Some(message) = note.message() {
-                    let attribution: AIAttribution = serde_json::from_str(message)
-                        .context("Failed to parse attribution JSON")?;
-                    warn_on_schema_version_mismatch(commit_oid, attribution.version);
-                    Ok(Some(attribution))
-                } else {
-                    Ok(None)
+        let Some(note) = self.find_note(commit_oid)? else {
+            return Ok(None);
+        };
+        match note.message() {
+            Some(message) => {
+                let mut attribution = note_format::parse_note(message)?;
+                if let Some(warning) = note_format::schema_version_warning(
+                    &commit_oid.to_string(),
+                    attribution.version,
+                ) {
+                    eprintln!("whogitit: Warning - {}", warning);
                 }
+                self.restore_line_content(commit_oid, &mut attribution);
+                Ok(Some(attribution))
             }
-            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
-            Err(e) => Err(e).context("Failed to read git note"),
+            None => Ok(None),
         }
     }
 
+    /// Note text exactly as stored, i.e. what a push sends
+    pub fn fetch_note_payload(&self, commit_oid: Oid) -> Result<Option<String>> {
+        Ok(self
+            .find_note(commit_oid)?
+            .and_then(|note| note.message().map(str::to_string)))
+    }
+
+    /// Fill in line content from the commit's blobs for lines stored as hashes
+    fn restore_line_content(&self, commit_oid: Oid, attribution: &mut AIAttribution) {
+        if !note_format::needs_line_content(attribution) {
+            return;
+        }
+        let Ok(tree) = self.repo.find_commit(commit_oid).and_then(|c| c.tree()) else {
+            return;
+        };
+        note_format::restore_line_content(attribution, |path| {
+            tree.get_path(&crate::utils::repo_path::to_path(path))
+                .and_then(|entry| self.repo.find_blob(entry.id()))
+                .ok()
+                .and_then(|blob| encoding::decode(blob.content()))
+                .map(|text| text.text)
+        });
+    }
+
+    /// Id of the note blob attributing a commit (the one taking precedence)
+    ///
+    /// Changes whenever the note is rewritten; see [`Self::list_note_entries`].
+    pub fn note_id(&self, commit_oid: Oid) -> Result<Option<Oid>> {
+        Ok(self.find_note(commit_oid)?.map(|note| note.id()))
+    }
+
     /// Check if a commit has AI attribution
     pub fn has_attribution(&self, commit_oid: Oid) -> bool {
-        self.repo.find_note(Some(NOTES_REF), commit_oid).is_ok()
+        self.find_note(commit_oid).ok().flatten().is_some()
     }
 
     /// Remove attribution from a commit
     pub fn remove_attribution(&self, commit_oid: Oid) -> Result<()> {
         let sig = self.get_signature()?;
         self.repo
-            .note_delete(commit_oid, Some(NOTES_REF), &sig, &sig)
+            .note_delete(commit_oid, Some(&self.write_ref), &sig, &sig)
             .context("Failed to delete git note")?;
         Ok(())
     }
@@ -74,8 +396,7 @@ impl<'a> NotesStore<'a> {
     /// Copy attribution from one commit to another
     pub fn copy_attribution(&self, from_oid: Oid, to_oid: Oid) -> Result<()> {
         let note = self
-            .repo
-            .find_note(Some(NOTES_REF), from_oid)
+            .find_note(from_oid)?
             .context("Source commit has no attribution note")?;
 
         let message = note
@@ -84,37 +405,244 @@ impl<'a> NotesStore<'a> {
 
         let sig = self.get_signature()?;
 
-        self.repo
-            .note(&sig, &sig, Some(NOTES_REF), to_oid, message, false)
-            .context("Failed to copy note to target commit")?;
-
-        Ok(())
+        let mut attempt = 0;
+        loop {
+            match self
+                .repo
+                .note(&sig, &sig, Some(&self.write_ref), to_oid, message, false)
+            {
+                Ok(_) => return Ok(()),
+                Err(e) if is_retryable_note_error(&e) && attempt + 1 < NOTE_WRITE_MAX_ATTEMPTS => {
+                    std::thread::sleep(note_write_backoff(attempt));
+                    attempt += 1;
+                }
+                Err(e) => return Err(e).context("Failed to copy note to target commit"),
+            }
+        }
     }
 
     /// Get default signature from git config
     fn get_signature(&self) -> Result<Signature<'static>> {
-        if let Ok(sig) = self.repo.signature() {
-            return Ok(Signature::now(
-                sig.name().unwrap_or("whogitit"),
-                sig.email().unwrap_or("whogitit@local"),
-            )?);
-        }
-
-        Ok(Signature::now("whogitit", "whogitit@local")?)
+        note_signature(self.repo)
     }
 
     /// List all commits with AI attribution
     pub fn list_attributed_commits(&self) -> Result<Vec<Oid>> {
-        let mut commits = Vec::new();
+        Ok(self
+            .list_note_entries()?
+            .into_iter()
+            .map(|(commit_oid, _)| commit_oid)
+            .collect())
+    }
 
-        if let Ok(notes) = self.repo.notes(Some(NOTES_REF)) {
-            for (_, commit_oid) in notes.flatten() {
-                commits.push(commit_oid);
+    /// List `(commit, note blob)` pairs for all attributed commits
+    ///
+    /// The note blob id changes whenever a note is rewritten, so it can be used
+    /// to invalidate data derived from a note. With several read refs, the blob
+    /// is the one that takes precedence for the commit.
+    pub fn list_note_entries(&self) -> Result<Vec<(Oid, Oid)>> {
+        let mut entries = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for notes_ref in &self.read_refs {
+            if let Ok(notes) = self.repo.notes(Some(notes_ref)) {
+                for (note_oid, commit_oid) in notes.flatten() {
+                    if seen.insert(commit_oid) {
+                        entries.push((commit_oid, note_oid));
+                    }
+                }
             }
         }
 
-        Ok(commits)
+        Ok(entries)
+    }
+}
+
+/// Author of note commits: the repository's user, or a generic whogitit identity
+fn note_signature(repo: &Repository) -> Result<Signature<'static>> {
+    if let Ok(sig) = repo.signature() {
+        return Ok(Signature::now(
+            sig.name().unwrap_or("whogitit"),
+            sig.email().unwrap_or("whogitit@local"),
+        )?);
+    }
+
+    Ok(Signature::now("whogitit", "whogitit@local")?)
+}
+
+/// Text stored in place of a prompt withheld by [`NoteTransform::StripPrompts`]
+pub const WITHHELD_PROMPT_TEXT: &str = "[withheld]";
+
+/// A rewrite applied to notes copied into a derived notes ref
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteTransform {
+    /// Replace prompt text with [`WITHHELD_PROMPT_TEXT`]; index, time, hash,
+    /// privacy classification and affected files are kept
+    StripPrompts,
+}
+
+impl NoteTransform {
+    pub fn apply(self, attribution: &mut AIAttribution) {
+        match self {
+            NoteTransform::StripPrompts => {
+                for prompt in &mut attribution.prompts {
+                    prompt.hash = Some(prompt.hash());
+                    prompt.text = WITHHELD_PROMPT_TEXT.to_string();
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of [`mirror_notes_ref`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MirrorStats {
+    /// Notes written or rewritten in the target ref
+    pub written: usize,
+    /// Notes removed from the target because the source no longer has them
+    pub removed: usize,
+    /// Source notes that are not attribution JSON and were not copied
+    pub skipped: usize,
+}
+
+/// Bring `target_ref` in line with `source_ref`, passing every note through `transforms`
+///
+/// Only notes whose transformed payload differs are written, so the target ref
+/// grows by fast-forward commits and can be pushed like a regular notes ref.
+/// Notes that don't parse are skipped rather than copied unfiltered.
+pub fn mirror_notes_ref(
+    repo: &Repository,
+    source_ref: &str,
+    target_ref: &str,
+    transforms: &[NoteTransform],
+) -> Result<MirrorStats> {
+    let sig = note_signature(repo)?;
+    let mut stats = MirrorStats::default();
+    let mut source_commits = std::collections::HashSet::new();
+
+    let source_notes = match repo.notes(Some(source_ref)) {
+        Ok(notes) => notes.flatten().collect::<Vec<_>>(),
+        Err(e) if e.code() == ErrorCode::NotFound => Vec::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", source_ref)),
+    };
+    for (note_oid, commit_oid) in source_notes {
+        source_commits.insert(commit_oid);
+        let blob = repo.find_blob(note_oid)?;
+        let Ok(mut attribution) = serde_json::from_slice::<AIAttribution>(blob.content()) else {
+            stats.skipped += 1;
+            continue;
+        };
+        for transform in transforms {
+            transform.apply(&mut attribution);
+        }
+        let payload = note_format::canonical_json(&attribution)?;
+
+        let current = repo
+            .find_note(Some(target_ref), commit_oid)
+            .ok()
+            .and_then(|note| note.message().map(str::to_string));
+        if current.as_deref() != Some(payload.as_str()) {
+            repo.note(&sig, &sig, Some(target_ref), commit_oid, &payload, true)
+                .with_context(|| format!("Failed to write note to {}", target_ref))?;
+            stats.written += 1;
+        }
+    }
+
+    let stale: Vec<Oid> = match repo.notes(Some(target_ref)) {
+        Ok(notes) => notes
+            .flatten()
+            .map(|(_, commit_oid)| commit_oid)
+            .filter(|commit_oid| !source_commits.contains(commit_oid))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    for commit_oid in stale {
+        repo.note_delete(commit_oid, Some(target_ref), &sig, &sig)
+            .with_context(|| format!("Failed to remove note from {}", target_ref))?;
+        stats.removed += 1;
     }
+    Ok(stats)
+}
+
+/// Whether a note write failed because another writer holds the notes ref
+fn is_retryable_note_error(err: &git2::Error) -> bool {
+    matches!(err.code(), ErrorCode::Locked | ErrorCode::Modified)
+        || err.message().to_ascii_lowercase().contains("lock")
+}
+
+fn note_write_backoff(attempt: u32) -> Duration {
+    Duration::from_millis(NOTE_WRITE_BASE_DELAY_MS << attempt.min(6))
+}
+
+/// Merge a concurrently written note with ours: files by path (ours win)
+///
+/// Our session, prompts and files are kept as they are. The other note's files
+/// we don't have are added, and the prompts they refer to are appended after ours
+/// under new indices, with the prompt references of their lines, blocks, binary
+/// attribution and secret findings rewritten to match, so every line still points
+/// at the prompt that wrote it. Their prompts used only by files we replaced are
+/// dropped.
+fn merge_attributions(existing: AIAttribution, ours: AIAttribution) -> AIAttribution {
+    let mut merged = ours;
+
+    let offset = merged
+        .prompts
+        .iter()
+        .map(|p| p.index + 1)
+        .max()
+        .unwrap_or(0);
+    let remap = |index: u32| index + offset;
+
+    let mut referenced = std::collections::HashSet::new();
+    for mut file in existing.files {
+        if merged.files.iter().any(|f| f.path == file.path) {
+            continue;
+        }
+        for line in &mut file.lines {
+            line.prompt_index = line.prompt_index.map(remap);
+            referenced.extend(line.prompt_index);
+        }
+        for block in file.blocks.iter_mut().flatten() {
+            block.prompt_index = block.prompt_index.map(remap);
+            referenced.extend(block.prompt_index);
+        }
+        if let Some(binary) = &mut file.binary {
+            binary.prompt_index = remap(binary.prompt_index);
+            referenced.insert(binary.prompt_index);
+        }
+        for finding in &mut file.secret_findings {
+            finding.prompt_index = remap(finding.prompt_index);
+            referenced.insert(finding.prompt_index);
+        }
+        merged.files.push(file);
+    }
+    for mut prompt in existing.prompts {
+        prompt.index = remap(prompt.index);
+        if referenced.contains(&prompt.index) {
+            merged.prompts.push(prompt);
+        }
+    }
+    merged.prompts.sort_by_key(|p| p.index);
+    merged.session.prompt_count = merged.prompts.len() as u32;
+    merged
+}
+
+/// Note text [`NotesStore::store_attribution`] writes for an attribution, and the
+/// large-payload warning it prints, if any
+///
+/// Fails like `store_attribution` when the payload exceeds the note size limit.
+pub fn note_payload(attribution: &AIAttribution) -> Result<(String, Option<String>)> {
+    let mut attribution = attribution.clone();
+    strip_line_content(&mut attribution);
+    serialize_payload(&attribution)
+}
+
+/// Serialize an attribution whose line content is already stripped
+fn serialize_payload(attribution: &AIAttribution) -> Result<(String, Option<String>)> {
+    // Compact canonical JSON: small for large sessions, identical bytes on every machine
+    let json = note_format::canonical_json(attribution)?;
+    let warning = evaluate_note_payload_size(json.len())?;
+    Ok((json, warning))
 }
 
 fn evaluate_note_payload_size(payload_bytes: usize) -> Result<Option<String>> {
@@ -153,31 +681,13 @@ fn format_bytes(bytes: usize) -> String {
     }
 }
 
-fn warn_on_schema_version_mismatch(commit_oid: Oid, note_version: u8) {
-    if note_version == SCHEMA_VERSION {
-        return;
-    }
-
-    if note_version < SCHEMA_VERSION {
-        eprintln!(
-            "whogitit: Warning - commit {} uses attribution schema v{} (current is v{}); continuing in compatibility mode.",
-            commit_oid, note_version, SCHEMA_VERSION
-        );
-    } else {
-        eprintln!(
-            "whogitit: Warning - commit {} uses newer attribution schema v{} (this build supports v{}); some fields may be ignored.",
-            commit_oid, note_version, SCHEMA_VERSION
-        );
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::capture::snapshot::{
-        AttributionSummary, FileAttributionResult, LineAttribution, LineSource,
+        line_hash, AttributionSummary, FileAttributionResult, LineAttribution, LineSource,
     };
-    use crate::core::attribution::{ModelInfo, PromptInfo, SessionMetadata};
+    use crate::core::attribution::{ModelInfo, PromptInfo, SessionMetadata, SCHEMA_VERSION};
     use tempfile::TempDir;
 
     fn create_test_repo() -> (TempDir, Repository) {
@@ -214,24 +724,30 @@ mod tests {
                 prompt_count: 1,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![PromptInfo {
                 index: 0,
                 text: "Test prompt".to_string(),
                 timestamp: "2026-01-30T10:00:00Z".to_string(),
                 affected_files: vec!["test.rs".to_string()],
+                hash: None,
+                privacy: None,
+                tool_calls: Vec::new(),
             }],
             files: vec![FileAttributionResult {
                 path: "test.rs".to_string(),
                 lines: vec![LineAttribution {
                     line_number: 1,
                     content: "fn test() {}".to_string(),
+                    content_hash: None,
                     source: LineSource::AI {
                         edit_id: "e1".to_string(),
                     },
                     edit_id: Some("e1".to_string()),
                     prompt_index: Some(0),
                     confidence: 1.0,
+                    timestamp: None,
                 }],
                 summary: AttributionSummary {
                     total_lines: 1,
@@ -241,6 +757,10 @@ mod tests {
                     original_lines: 0,
                     unknown_lines: 0,
                 },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
             }],
         };
 
@@ -463,7 +983,427 @@ mod tests {
             .contains("no attribution note"));
     }
 
+    #[test]
+    fn test_store_attribution_clears_staged_payload() {
+        let (_dir, repo) = create_test_repo();
+        let store = NotesStore::new(&repo).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        store
+            .store_attribution(head, &create_minimal_attribution("s1"))
+            .unwrap();
+
+        assert!(store.staged_payloads().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_retry_staged_payload() {
+        let (_dir, repo) = create_test_repo();
+        let store = NotesStore::new(&repo).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        // Simulate a crash between staging and attaching the note
+        let json = serde_json::to_string(&create_minimal_attribution("staged")).unwrap();
+        let path = store.stage_payload(head, &json).unwrap();
+        assert!(!store.has_attribution(head));
+
+        let staged = store.staged_payloads().unwrap();
+        assert_eq!(staged, vec![(head, path.clone())]);
+
+        store.retry_staged(head, &path).unwrap();
+        assert!(!path.exists());
+        let fetched = store.fetch_attribution(head).unwrap().unwrap();
+        assert_eq!(fetched.session.session_id, "staged");
+    }
+
+    #[test]
+    fn test_write_note_if_unchanged_refuses_moved_ref() {
+        let (_dir, repo) = create_test_repo();
+        let store = NotesStore::new(&repo).unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = head.tree().unwrap();
+        let other = repo
+            .commit(None, &sig, &sig, "Other commit", &tree, &[&head])
+            .unwrap();
+
+        let first = store
+            .write_note_if_unchanged(&sig, head.id(), "first", None)
+            .unwrap();
+        assert!(first.is_some());
+        let tip = repo.refname_to_id(&store.write_ref).ok();
+
+        // Another writer moves the ref between our read and our write
+        repo.note(&sig, &sig, Some(&store.write_ref), other, "theirs", false)
+            .unwrap();
+
+        let lost = store
+            .write_note_if_unchanged(&sig, head.id(), "ours", tip)
+            .unwrap();
+        assert!(lost.is_none());
+        assert_eq!(store.read_note_message(head.id()).as_deref(), Some("first"));
+        let theirs = repo.find_note(Some(&store.write_ref), other).unwrap();
+        assert_eq!(theirs.message(), Some("theirs"));
+
+        let leftover = repo
+            .references_glob(&format!("{}*", NOTE_WRITE_TMP_PREFIX))
+            .unwrap()
+            .count();
+        assert_eq!(leftover, 0);
+    }
+
+    #[test]
+    fn test_merge_attributions_keeps_concurrent_files_and_prompts() {
+        let prompt = |index: u32, text: &str| PromptInfo {
+            index,
+            text: text.to_string(),
+            timestamp: "2026-01-30T10:00:00Z".to_string(),
+            affected_files: vec![],
+            hash: None,
+            privacy: None,
+            tool_calls: Vec::new(),
+        };
+        let file = |path: &str, prompts: &[u32]| FileAttributionResult {
+            path: path.to_string(),
+            lines: prompts
+                .iter()
+                .enumerate()
+                .map(|(i, &p)| LineAttribution {
+                    line_number: i as u32 + 1,
+                    content: String::new(),
+                    content_hash: None,
+                    source: LineSource::AI {
+                        edit_id: "e".to_string(),
+                    },
+                    edit_id: None,
+                    prompt_index: Some(p),
+                    confidence: 1.0,
+                    timestamp: None,
+                })
+                .collect(),
+            summary: AttributionSummary {
+                total_lines: prompts.len(),
+                ai_lines: prompts.len(),
+                ai_modified_lines: 0,
+                human_lines: 0,
+                original_lines: 0,
+                unknown_lines: 0,
+            },
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        };
+        // Prompt text each line was written by, per file
+        let line_prompts = |attribution: &AIAttribution, path: &str| -> Vec<String> {
+            let file = attribution.files.iter().find(|f| f.path == path).unwrap();
+            file.lines
+                .iter()
+                .map(|l| {
+                    let index = l.prompt_index.unwrap();
+                    attribution
+                        .prompts
+                        .iter()
+                        .find(|p| p.index == index)
+                        .unwrap()
+                        .text
+                        .clone()
+                })
+                .collect()
+        };
+
+        let mut existing = create_minimal_attribution("theirs");
+        existing.prompts = vec![
+            prompt(0, "theirs 0"),
+            prompt(1, "theirs 1, only in a.rs"),
+            prompt(2, "theirs 2"),
+        ];
+        existing.files = vec![file("a.rs", &[1]), file("b.rs", &[0, 2])];
+
+        let mut ours = create_minimal_attribution("ours");
+        ours.prompts = vec![prompt(0, "ours 0"), prompt(1, "ours 1")];
+        ours.files = vec![file("a.rs", &[1, 0])];
+
+        let merged = merge_attributions(existing.clone(), ours.clone());
+        assert_eq!(merged.session.session_id, "ours");
+        assert_eq!(merged.session.prompt_count, 4);
+        // Their prompt 1 only wrote a.rs, which ours replaced
+        let indices: Vec<u32> = merged.prompts.iter().map(|p| p.index).collect();
+        assert_eq!(indices, vec![0, 1, 2, 4]);
+        assert!(merged
+            .prompts
+            .iter()
+            .all(|p| p.text != "theirs 1, only in a.rs"));
+        assert_eq!(merged.files.len(), 2);
+        assert_eq!(line_prompts(&merged, "a.rs"), line_prompts(&ours, "a.rs"));
+        assert_eq!(
+            line_prompts(&merged, "b.rs"),
+            line_prompts(&existing, "b.rs")
+        );
+        assert_eq!(line_prompts(&merged, "b.rs"), vec!["theirs 0", "theirs 2"]);
+    }
+
+    #[test]
+    fn test_note_written_since_first_read_is_merged() {
+        let (_dir, repo) = create_test_repo();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let store = NotesStore::new(&repo).unwrap();
+        let with_file = |session: &str, path: &str| {
+            let mut attribution = create_minimal_attribution(session);
+            attribution.files.push(FileAttributionResult {
+                path: path.to_string(),
+                lines: vec![],
+                summary: AttributionSummary {
+                    total_lines: 0,
+                    ai_lines: 0,
+                    ai_modified_lines: 0,
+                    human_lines: 0,
+                    original_lines: 0,
+                    unknown_lines: 0,
+                },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
+            });
+            attribution
+        };
+        let theirs = with_file("theirs", "theirs.rs");
+        let ours = with_file("ours", "ours.rs");
+        let paths = || -> Vec<String> {
+            let stored = store.fetch_attribution(head).unwrap().unwrap();
+            let mut paths: Vec<String> = stored.files.into_iter().map(|f| f.path).collect();
+            paths.sort();
+            paths
+        };
+
+        // Another writer attaches a note after our first read (no note then)
+        store.store_attribution(head, &theirs).unwrap();
+        let json = note_format::canonical_json(&ours).unwrap();
+        store
+            .write_attribution_with_retry(head, &ours, json.clone(), None)
+            .unwrap();
+        assert_eq!(paths(), vec!["ours.rs", "theirs.rs"]);
+
+        // Unchanged since the first read: our note replaces it
+        let first_read = store.read_note_message(head);
+        store
+            .write_attribution_with_retry(head, &ours, json, first_read)
+            .unwrap();
+        assert_eq!(paths(), vec!["ours.rs"]);
+    }
+
+    #[test]
+    fn test_note_write_backoff_grows() {
+        assert_eq!(note_write_backoff(0), Duration::from_millis(50));
+        assert_eq!(note_write_backoff(1), Duration::from_millis(100));
+        assert_eq!(note_write_backoff(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_config() {
+        let (dir, repo) = create_test_repo();
+        std::fs::write(
+            dir.path().join(".whogitit.toml"),
+            "[storage]\nnamespace = \"ci-bot\"\n[storage\n",
+        )
+        .unwrap();
+        assert!(NotesStore::new(&repo).is_err());
+
+        std::fs::write(
+            dir.path().join(".whogitit.toml"),
+            "[storage]\nnamespace = \"ci-bot\"\n",
+        )
+        .unwrap();
+        let store = NotesStore::new(&repo).unwrap();
+        assert_eq!(store.write_ref(), "refs/notes/whogitit-ns/ci-bot");
+    }
+
+    #[test]
+    fn test_namespaced_writers_and_merged_reads() {
+        let (_dir, repo) = create_test_repo();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let second = repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Second",
+                &tree,
+                &[&repo.find_commit(head).unwrap()],
+            )
+            .unwrap();
+
+        let bot_config = StorageConfig {
+            namespace: Some("ci-bot".to_string()),
+            ..Default::default()
+        };
+        let bot = NotesStore::with_storage(&repo, &bot_config).unwrap();
+        assert_eq!(bot.write_ref(), "refs/notes/whogitit-ns/ci-bot");
+        bot.store_attribution(head, &create_minimal_attribution("bot"))
+            .unwrap();
+        bot.store_attribution(second, &create_minimal_attribution("bot"))
+            .unwrap();
+
+        let capture = NotesStore::with_storage(&repo, &StorageConfig::default()).unwrap();
+        capture
+            .store_attribution(head, &create_minimal_attribution("capture"))
+            .unwrap();
+
+        // Default reads only see the main ref
+        assert!(!capture.has_attribution(second));
+
+        // Captured notes take precedence; bot notes fill the gaps
+        let merged = NotesStore::with_storage(
+            &repo,
+            &StorageConfig {
+                read_order: vec!["default".to_string(), "ci-bot".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let session = |oid| {
+            merged
+                .fetch_attribution(oid)
+                .unwrap()
+                .unwrap()
+                .session
+                .session_id
+        };
+        assert_eq!(session(head), "capture");
+        assert_eq!(session(second), "bot");
+        assert_eq!(merged.list_attributed_commits().unwrap().len(), 2);
+
+        // The bot's own store reads its namespace first
+        assert_eq!(
+            bot.fetch_attribution(head)
+                .unwrap()
+                .unwrap()
+                .session
+                .session_id,
+            "bot"
+        );
+    }
+
+    #[test]
+    fn test_namespace_ref_validation() {
+        assert_eq!(namespace_ref("default").unwrap(), NOTES_REF);
+        assert!(namespace_ref("overrides").is_ok());
+        assert!(namespace_ref("").is_err());
+        assert!(namespace_ref("a/b").is_err());
+        assert!(namespace_ref("bad..name").is_err());
+    }
+
+    #[test]
+    fn test_line_content_stored_as_hashes() {
+        let (dir, repo) = create_test_repo();
+        std::fs::write(dir.path().join("a.rs"), "one\ntwo\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("a.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "Add a.rs", &tree, &[&parent])
+            .unwrap();
+
+        let line = |line_number: u32, content: &str| LineAttribution {
+            line_number,
+            content: content.to_string(),
+            content_hash: None,
+            source: LineSource::Original,
+            edit_id: None,
+            prompt_index: None,
+            confidence: 1.0,
+            timestamp: None,
+        };
+        let mut attribution = create_minimal_attribution("hashes");
+        attribution.files.push(FileAttributionResult {
+            path: "a.rs".to_string(),
+            // Line 2 no longer matches the committed blob
+            lines: vec![line(1, "one"), line(2, "stale")],
+            summary: AttributionSummary {
+                total_lines: 2,
+                ai_lines: 0,
+                ai_modified_lines: 0,
+                human_lines: 0,
+                original_lines: 2,
+                unknown_lines: 0,
+            },
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
+        });
+
+        let store = NotesStore::new(&repo).unwrap();
+        store.store_attribution(oid, &attribution).unwrap();
+
+        let raw = repo.find_note(Some(NOTES_REF), oid).unwrap();
+        let raw = raw.message().unwrap();
+        assert!(raw.contains(&line_hash("one")));
+        assert!(!raw.contains("\"content\""));
+
+        let fetched = store.fetch_attribution(oid).unwrap().unwrap();
+        assert_eq!(fetched.files[0].lines[0].content, "one");
+        assert_eq!(fetched.files[0].lines[1].content, "");
+
+        // Notes written before hashing keep their inline content
+        attribution.version = 3;
+        let legacy = serde_json::to_string(&attribution).unwrap();
+        repo.note(&sig, &sig, Some(NOTES_REF), oid, &legacy, true)
+            .unwrap();
+        let fetched = store.fetch_attribution(oid).unwrap().unwrap();
+        assert_eq!(fetched.files[0].lines[1].content, "stale");
+    }
+
     // Helper function to create minimal attribution for tests
+    #[test]
+    fn test_mirror_notes_ref_strips_prompts_and_drops_stale_notes() {
+        let (_dir, repo) = create_test_repo();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let store = NotesStore::new(&repo).unwrap();
+        let mut attribution = create_minimal_attribution("mirror");
+        attribution.prompts.push(PromptInfo {
+            index: 0,
+            text: "use the staging API key".to_string(),
+            timestamp: "2026-01-30T10:00:00Z".to_string(),
+            affected_files: vec!["a.rs".to_string()],
+            hash: None,
+            privacy: None,
+            tool_calls: Vec::new(),
+        });
+        store.store_attribution(head, &attribution).unwrap();
+
+        let mirror = "refs/notes/whogitit-remote/test/default";
+        let transforms = [NoteTransform::StripPrompts];
+        let stats = mirror_notes_ref(&repo, NOTES_REF, mirror, &transforms).unwrap();
+        assert_eq!(stats.written, 1);
+
+        let raw = repo.find_note(Some(mirror), head).unwrap();
+        let mirrored: AIAttribution = serde_json::from_str(raw.message().unwrap()).unwrap();
+        assert_eq!(mirrored.prompts[0].text, WITHHELD_PROMPT_TEXT);
+        assert_eq!(
+            mirrored.prompts[0].hash,
+            Some(attribution.prompts[0].hash())
+        );
+        // The source note is untouched
+        let source = store.fetch_attribution(head).unwrap().unwrap();
+        assert_eq!(source.prompts[0].text, "use the staging API key");
+
+        // Unchanged notes are not rewritten
+        let again = mirror_notes_ref(&repo, NOTES_REF, mirror, &transforms).unwrap();
+        assert_eq!(again, MirrorStats::default());
+
+        store.remove_attribution(head).unwrap();
+        let stats = mirror_notes_ref(&repo, NOTES_REF, mirror, &transforms).unwrap();
+        assert_eq!(stats.removed, 1);
+        assert!(repo.find_note(Some(mirror), head).is_err());
+    }
+
     fn create_minimal_attribution(session_id: &str) -> AIAttribution {
         AIAttribution {
             version: SCHEMA_VERSION,
@@ -474,6 +1414,7 @@ mod tests {
                 prompt_count: 0,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![],
             files: vec![],
@@ -0,0 +1,308 @@
+//! Push attribution notes to a remote in-process
+//!
+//! The pre-push hook used to run `git push <remote> refs/notes/whogitit` with its
+//! errors discarded. Pushing through git2 lets us pick credentials explicitly (SSH
+//! agent, then the configured credential helper) and report why a push failed.
+//! The last failure is kept in the git dir so `whogitit status` can show it until
+//! a later push succeeds.
+//!
+//! Remotes that must not receive prompts (`[remotes."..."] push_prompts = false`)
+//! get transformed copies: each notes ref is mirrored into
+//! `refs/notes/whogitit-remote/<remote>/...` with the transforms applied, and the
+//! mirror is pushed under the original ref name.
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use git2::{Cred, CredentialType, PushOptions, RemoteCallbacks, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::notes::{mirror_notes_ref, NoteTransform, NOTES_NAMESPACE_PREFIX, NOTES_REF};
+
+/// Record of the last failed push (inside the git dir)
+const PUSH_FAILURE_FILE: &str = "whogitit/notes-push-failure.json";
+
+/// Prefix of the local refs holding transformed copies of the notes, per remote
+pub const REMOTE_MIRROR_PREFIX: &str = "refs/notes/whogitit-remote/";
+
+/// A notes push that did not complete
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushFailure {
+    pub remote: String,
+    pub error: String,
+    /// RFC 3339 time of the attempt
+    pub at: String,
+}
+
+/// Local notes refs to push: the main ref and any namespaced refs
+pub fn local_notes_refs(repo: &Repository) -> Result<Vec<String>> {
+    let mut refs = Vec::new();
+    if repo.find_reference(NOTES_REF).is_ok() {
+        refs.push(NOTES_REF.to_string());
+    }
+    for reference in repo.references_glob(&format!("{}*", NOTES_NAMESPACE_PREFIX))? {
+        if let Some(name) = reference?.name() {
+            refs.push(name.to_string());
+        }
+    }
+    Ok(refs)
+}
+
+/// Local ref holding the transformed copy of `notes_ref` for `remote`
+pub fn mirror_ref(remote: &str, notes_ref: &str) -> String {
+    // Remotes may be given as URLs; keep the ref name valid
+    let slug: String = remote
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let suffix = match notes_ref.strip_prefix(NOTES_NAMESPACE_PREFIX) {
+        Some(namespace) => format!("ns/{}", namespace),
+        None => "default".to_string(),
+    };
+    format!("{}{}/{}", REMOTE_MIRROR_PREFIX, slug, suffix)
+}
+
+/// Push all local notes refs to `remote` (a remote name or URL)
+///
+/// With `transforms`, each ref is first mirrored into [`mirror_ref`] with the
+/// transforms applied, and the mirror is pushed in its place. Returns the remote
+/// ref names pushed. Rejected refs (e.g. the remote has notes that are not in the
+/// local ref) fail the push.
+pub fn push_notes(
+    repo: &Repository,
+    remote: &str,
+    transforms: &[NoteTransform],
+) -> Result<Vec<String>> {
+    let refs = local_notes_refs(repo)?;
+    if refs.is_empty() {
+        return Ok(refs);
+    }
+
+    let mut refspecs = Vec::with_capacity(refs.len());
+    for notes_ref in &refs {
+        if transforms.is_empty() {
+            refspecs.push(format!("{0}:{0}", notes_ref));
+            continue;
+        }
+        let mirror = mirror_ref(remote, notes_ref);
+        let stats = mirror_notes_ref(repo, notes_ref, &mirror, transforms)?;
+        if stats.skipped > 0 {
+            eprintln!(
+                "whogitit: Warning - {} note(s) in {} are not attribution data and were not pushed",
+                stats.skipped, notes_ref
+            );
+        }
+        // An empty mirror has no ref to push
+        if repo.find_reference(&mirror).is_ok() {
+            refspecs.push(format!("{}:{}", mirror, notes_ref));
+        }
+    }
+
+    let mut git_remote = repo
+        .find_remote(remote)
+        .or_else(|_| repo.remote_anonymous(remote))
+        .with_context(|| format!("Unknown remote '{}'", remote))?;
+    let config = repo.config()?;
+    let rejected = RefCell::new(Vec::new());
+
+    let mut callbacks = RemoteCallbacks::new();
+    let mut tried = CredentialType::empty();
+    callbacks.credentials(|url, username, allowed| {
+        let username = username.unwrap_or("git");
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username);
+        }
+        // Each method is tried once; libgit2 keeps asking while authentication fails
+        if allowed.contains(CredentialType::SSH_KEY) && !tried.contains(CredentialType::SSH_KEY) {
+            tried |= CredentialType::SSH_KEY;
+            return Cred::ssh_key_from_agent(username);
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT)
+            && !tried.contains(CredentialType::USER_PASS_PLAINTEXT)
+        {
+            tried |= CredentialType::USER_PASS_PLAINTEXT;
+            return Cred::credential_helper(&config, url, Some(username));
+        }
+        if allowed.contains(CredentialType::DEFAULT) && !tried.contains(CredentialType::DEFAULT) {
+            tried |= CredentialType::DEFAULT;
+            return Cred::default();
+        }
+        Err(git2::Error::from_str(
+            "authentication failed (tried SSH agent and credential helper)",
+        ))
+    });
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(message) = status {
+            rejected
+                .borrow_mut()
+                .push(format!("{} ({})", refname, message));
+        }
+        Ok(())
+    });
+
+    let mut options = PushOptions::new();
+    options.remote_callbacks(callbacks);
+    git_remote
+        .push(&refspecs, Some(&mut options))
+        .with_context(|| format!("Failed to push notes to {}", remote))?;
+    drop(options);
+
+    let rejected = rejected.into_inner();
+    if !rejected.is_empty() {
+        anyhow::bail!(
+            "{} rejected {}; fetch and merge the remote notes first",
+            remote,
+            rejected.join(", ")
+        );
+    }
+    Ok(refs)
+}
+
+fn failure_path(repo: &Repository) -> PathBuf {
+    repo.path().join(PUSH_FAILURE_FILE)
+}
+
+/// Remember a failed push for `whogitit status`
+pub fn record_failure(repo: &Repository, remote: &str, error: &str) -> Result<()> {
+    let failure = PushFailure {
+        remote: remote.to_string(),
+        error: error.to_string(),
+        at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+    let path = failure_path(repo);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(&failure)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Forget the last failure after a successful push
+pub fn clear_failure(repo: &Repository) {
+    let _ = fs::remove_file(failure_path(repo));
+}
+
+/// The last failed push, if no push has succeeded since
+pub fn last_failure(repo: &Repository) -> Option<PushFailure> {
+    let content = fs::read(failure_path(repo)).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attribution::{AIAttribution, ModelInfo, PromptInfo, SessionMetadata};
+    use crate::storage::notes::{NotesStore, WITHHELD_PROMPT_TEXT};
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn repo_with_note(dir: &std::path::Path) -> Repository {
+        let repo = Repository::init(dir).unwrap();
+        let oid = {
+            let sig = Signature::now("Test", "test@test.com").unwrap();
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+                .unwrap()
+        };
+        let attribution = AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 1,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![PromptInfo {
+                index: 0,
+                text: "Add the initial commit".to_string(),
+                timestamp: "2026-01-30T10:00:00Z".to_string(),
+                affected_files: vec![],
+                hash: None,
+                privacy: None,
+                tool_calls: Vec::new(),
+            }],
+            files: vec![],
+        };
+        NotesStore::new(&repo)
+            .unwrap()
+            .store_attribution(oid, &attribution)
+            .unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_push_notes_to_bare_remote() {
+        let dir = TempDir::new().unwrap();
+        let repo = repo_with_note(dir.path());
+        let remote_dir = TempDir::new().unwrap();
+        let remote = Repository::init_bare(remote_dir.path()).unwrap();
+        repo.remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+
+        let pushed = push_notes(&repo, "origin", &[]).unwrap();
+        assert_eq!(pushed, vec![NOTES_REF.to_string()]);
+        assert_eq!(
+            remote.refname_to_id(NOTES_REF).unwrap(),
+            repo.refname_to_id(NOTES_REF).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_push_notes_with_prompts_withheld() {
+        let dir = TempDir::new().unwrap();
+        let repo = repo_with_note(dir.path());
+        let remote_dir = TempDir::new().unwrap();
+        let remote = Repository::init_bare(remote_dir.path()).unwrap();
+        repo.remote("public", remote_dir.path().to_str().unwrap())
+            .unwrap();
+
+        let pushed = push_notes(&repo, "public", &[NoteTransform::StripPrompts]).unwrap();
+        assert_eq!(pushed, vec![NOTES_REF.to_string()]);
+
+        let head = repo.head().unwrap().target().unwrap();
+        let remote_note = remote.find_note(Some(NOTES_REF), head).unwrap();
+        let remote_note: AIAttribution =
+            serde_json::from_str(remote_note.message().unwrap()).unwrap();
+        assert_eq!(remote_note.prompts[0].text, WITHHELD_PROMPT_TEXT);
+
+        // The local ref keeps the prompt; the stripped copy lives in the mirror ref
+        let local = NotesStore::new(&repo)
+            .unwrap()
+            .fetch_attribution(head)
+            .unwrap()
+            .unwrap();
+        assert_eq!(local.prompts[0].text, "Add the initial commit");
+        assert!(repo.refname_to_id(&mirror_ref("public", NOTES_REF)).is_ok());
+    }
+
+    #[test]
+    fn test_failure_is_recorded_until_cleared() {
+        let dir = TempDir::new().unwrap();
+        let repo = repo_with_note(dir.path());
+
+        let missing = dir.path().join("missing.git");
+        let err = push_notes(&repo, missing.to_str().unwrap(), &[]).unwrap_err();
+        record_failure(&repo, "missing", &format!("{:#}", err)).unwrap();
+
+        let failure = last_failure(&repo).unwrap();
+        assert_eq!(failure.remote, "missing");
+        assert!(failure.error.contains("Failed to push notes"));
+
+        clear_failure(&repo);
+        assert!(last_failure(&repo).is_none());
+    }
+}
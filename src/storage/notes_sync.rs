@@ -0,0 +1,274 @@
+//! Detect and fetch attribution notes that exist on a remote but not locally
+//!
+//! A fresh clone does not fetch `refs/notes/*`, so read commands would report
+//! every line as human. Before reading, [`ensure_local_notes`] checks for the
+//! local notes ref and, when it is missing, probes the remote with
+//! `git ls-remote` and fetches the notes (unless fetching is disabled).
+//! The git CLI is used so the user's credential helpers and SSH config apply.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use git2::Repository;
+
+use crate::storage::notes::{NOTES_NAMESPACE_PREFIX, NOTES_REF};
+
+/// Set to `1` to never probe or fetch notes from a remote
+pub const ENV_NO_FETCH: &str = "WHOGITIT_NO_FETCH";
+/// Marker recording the last remote probe (inside the git dir)
+const PROBE_MARKER: &str = "whogitit/remote-notes-probe";
+/// How long a probe that found no remote notes is trusted
+const PROBE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Outcome of [`ensure_local_notes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotesAvailability {
+    /// Notes ref already exists locally
+    Local,
+    /// Notes were missing locally and were fetched from the remote
+    Fetched { remote: String },
+    /// Notes were missing locally and fetching is disabled
+    FetchDisabled { remote: String },
+    /// Neither the repository nor its remote has notes
+    Missing,
+}
+
+/// Whether the local notes ref exists
+pub fn local_notes_exist(repo: &Repository) -> bool {
+    repo.find_reference(NOTES_REF).is_ok()
+}
+
+/// Remote to probe: `origin` if configured, otherwise the only remote
+pub fn default_remote(repo: &Repository) -> Option<String> {
+    let remotes = repo.remotes().ok()?;
+    let names: Vec<&str> = remotes.iter().flatten().collect();
+    if names.contains(&"origin") {
+        Some("origin".to_string())
+    } else if names.len() == 1 {
+        Some(names[0].to_string())
+    } else {
+        None
+    }
+}
+
+/// Refspec that fetches notes into the local notes ref
+pub fn fetch_refspec() -> String {
+    format!("+{}:{}", NOTES_REF, NOTES_REF)
+}
+
+/// Refspec that fetches all namespaced notes refs
+pub fn namespace_fetch_refspec() -> String {
+    format!("+{0}*:{0}*", NOTES_NAMESPACE_PREFIX)
+}
+
+/// Git CLI command for a repository that never prompts for credentials
+pub(crate) fn git(git_dir: &Path) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("--git-dir")
+        .arg(git_dir)
+        // Never block a read command on an interactive credential prompt
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null());
+    cmd
+}
+
+/// Whether the remote advertises the notes ref
+pub fn remote_has_notes(repo: &Repository, remote: &str) -> Result<bool> {
+    let output = git(repo.path())
+        .args(["ls-remote", "--quiet", remote, NOTES_REF])
+        .stderr(Stdio::null())
+        .output()
+        .context("Failed to run git ls-remote")?;
+    if !output.status.success() {
+        anyhow::bail!("git ls-remote {} failed", remote);
+    }
+    Ok(!output.stdout.is_empty())
+}
+
+/// Fetch the notes ref from a remote
+pub fn fetch_notes(repo: &Repository, remote: &str) -> Result<()> {
+    let output = git(repo.path())
+        .args([
+            "fetch",
+            "--quiet",
+            remote,
+            &fetch_refspec(),
+            &namespace_fetch_refspec(),
+        ])
+        .output()
+        .context("Failed to run git fetch")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git fetch {} failed: {}",
+            remote,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn probe_marker(repo: &Repository) -> PathBuf {
+    repo.path().join(PROBE_MARKER)
+}
+
+/// Whether the remote was probed recently (so repositories without notes anywhere
+/// don't pay for a network round trip on every command)
+fn probed_recently(repo: &Repository) -> bool {
+    fs::metadata(probe_marker(repo))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < PROBE_INTERVAL)
+}
+
+fn record_probe(repo: &Repository) {
+    let marker = probe_marker(repo);
+    if let Some(parent) = marker.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(marker, b"");
+}
+
+/// Make sure attribution notes are available locally before reading them
+///
+/// With `fetch` false (or `WHOGITIT_NO_FETCH=1`), the remote is not contacted and
+/// [`NotesAvailability::FetchDisabled`] is returned whenever local notes are missing
+/// and a remote exists. The remote is probed at most once an hour while it has no notes.
+pub fn ensure_local_notes(repo: &Repository, fetch: bool) -> Result<NotesAvailability> {
+    if local_notes_exist(repo) {
+        return Ok(NotesAvailability::Local);
+    }
+    let Some(remote) = default_remote(repo) else {
+        return Ok(NotesAvailability::Missing);
+    };
+    let fetch = fetch && std::env::var(ENV_NO_FETCH).map_or(true, |v| v != "1");
+    if !fetch {
+        return Ok(NotesAvailability::FetchDisabled { remote });
+    }
+    if probed_recently(repo) {
+        return Ok(NotesAvailability::Missing);
+    }
+    record_probe(repo);
+    if !remote_has_notes(repo, &remote)? {
+        return Ok(NotesAvailability::Missing);
+    }
+    fetch_notes(repo, &remote)?;
+    Ok(NotesAvailability::Fetched { remote })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attribution::{AIAttribution, ModelInfo, SessionMetadata};
+    use crate::storage::notes::NotesStore;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository) -> git2::Oid {
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap()
+    }
+
+    fn attribution() -> AIAttribution {
+        AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: 0,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: vec![],
+            files: vec![],
+        }
+    }
+
+    #[test]
+    fn test_no_remote_means_missing() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        assert_eq!(
+            ensure_local_notes(&repo, true).unwrap(),
+            NotesAvailability::Missing
+        );
+    }
+
+    #[test]
+    fn test_fetches_notes_missing_locally() {
+        let upstream_dir = TempDir::new().unwrap();
+        let upstream = Repository::init(upstream_dir.path()).unwrap();
+        let oid = commit(&upstream);
+        NotesStore::new(&upstream)
+            .unwrap()
+            .store_attribution(oid, &attribution())
+            .unwrap();
+
+        // Like `git clone`, only branches are fetched
+        let clone_dir = TempDir::new().unwrap();
+        let clone =
+            Repository::clone(upstream_dir.path().to_str().unwrap(), clone_dir.path()).unwrap();
+        assert!(!local_notes_exist(&clone));
+
+        assert_eq!(
+            ensure_local_notes(&clone, false).unwrap(),
+            NotesAvailability::FetchDisabled {
+                remote: "origin".to_string()
+            }
+        );
+        assert!(!local_notes_exist(&clone));
+
+        assert_eq!(
+            ensure_local_notes(&clone, true).unwrap(),
+            NotesAvailability::Fetched {
+                remote: "origin".to_string()
+            }
+        );
+        assert!(NotesStore::new(&clone).unwrap().has_attribution(oid));
+        assert_eq!(
+            ensure_local_notes(&clone, true).unwrap(),
+            NotesAvailability::Local
+        );
+    }
+
+    #[test]
+    fn test_remote_without_notes() {
+        let upstream_dir = TempDir::new().unwrap();
+        let upstream = Repository::init(upstream_dir.path()).unwrap();
+        commit(&upstream);
+
+        let clone_dir = TempDir::new().unwrap();
+        let clone =
+            Repository::clone(upstream_dir.path().to_str().unwrap(), clone_dir.path()).unwrap();
+        assert_eq!(
+            ensure_local_notes(&clone, true).unwrap(),
+            NotesAvailability::Missing
+        );
+        assert!(probed_recently(&clone));
+
+        // Notes pushed later are picked up once the probe interval has passed
+        let oid = upstream.head().unwrap().target().unwrap();
+        NotesStore::new(&upstream)
+            .unwrap()
+            .store_attribution(oid, &attribution())
+            .unwrap();
+        assert_eq!(
+            ensure_local_notes(&clone, true).unwrap(),
+            NotesAvailability::Missing
+        );
+        fs::remove_file(probe_marker(&clone)).unwrap();
+        assert!(matches!(
+            ensure_local_notes(&clone, true).unwrap(),
+            NotesAvailability::Fetched { .. }
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+//! Index of prompt hashes across attributed commits
+//!
+//! Built from the attribution notes and cached at `.git/whogitit/prompt-index.json`.
+//! Each cache entry is keyed by commit and remembers the note blob it was read
+//! from, so only new or rewritten notes are parsed on the next refresh.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::notes::NotesStore;
+use crate::utils::truncate_prompt;
+
+/// Cache file (inside the git dir)
+const INDEX_FILE: &str = "whogitit/prompt-index.json";
+/// Bump when the cache layout changes; older caches are rebuilt
+const INDEX_VERSION: u8 = 1;
+/// Characters of prompt text kept for display
+const PREVIEW_LEN: usize = 80;
+
+/// A prompt as recorded in one commit's note
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexedPrompt {
+    pub hash: String,
+    pub index: u32,
+    pub timestamp: String,
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedCommit {
+    /// Note blob the prompts were read from
+    note: String,
+    prompts: Vec<IndexedPrompt>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexCache {
+    version: u8,
+    commits: BTreeMap<String, IndexedCommit>,
+}
+
+/// Where a prompt hash occurs
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptOccurrence {
+    pub commit: String,
+    pub prompt_index: u32,
+    pub timestamp: String,
+    /// Repository path, when the index spans several repositories
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+}
+
+/// All occurrences of one prompt hash
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptGroup {
+    pub hash: String,
+    pub preview: String,
+    pub count: usize,
+    /// Number of distinct commits using the prompt
+    pub commits: usize,
+    pub occurrences: Vec<PromptOccurrence>,
+}
+
+/// Prompt hash index for a repository
+pub struct PromptIndex {
+    commits: BTreeMap<String, IndexedCommit>,
+}
+
+impl PromptIndex {
+    fn cache_path(git_dir: &Path) -> PathBuf {
+        git_dir.join(INDEX_FILE)
+    }
+
+    /// Load the cached index and bring it up to date with the notes ref
+    pub fn refresh(repo: &Repository) -> Result<Self> {
+        let cache_path = Self::cache_path(repo.path());
+        let mut commits = fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<IndexCache>(&content).ok())
+            .filter(|cache| cache.version == INDEX_VERSION)
+            .map(|cache| cache.commits)
+            .unwrap_or_default();
+
+        let store = NotesStore::new(repo)?;
+        let entries = store.list_note_entries()?;
+        let mut changed = commits.len() != entries.len();
+        let mut fresh = BTreeMap::new();
+
+        for (commit_oid, note_oid) in entries {
+            let commit = commit_oid.to_string();
+            let note = note_oid.to_string();
+            if let Some(cached) = commits.remove(&commit).filter(|c| c.note == note) {
+                fresh.insert(commit, cached);
+                continue;
+            }
+
+            changed = true;
+            let prompts = match store.fetch_attribution(commit_oid) {
+                Ok(Some(attribution)) => attribution
+                    .prompts
+                    .iter()
+                    .map(|p| IndexedPrompt {
+                        hash: p.hash(),
+                        index: p.index,
+                        timestamp: p.timestamp.clone(),
+                        preview: truncate_prompt(&p.text, PREVIEW_LEN),
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            fresh.insert(commit, IndexedCommit { note, prompts });
+        }
+
+        let index = Self { commits: fresh };
+        if changed {
+            if let Err(err) = index.save(&cache_path) {
+                eprintln!(
+                    "whogitit: Warning - failed to write prompt index cache: {:#}",
+                    err
+                );
+            }
+        }
+        Ok(index)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create whogitit directory")?;
+        }
+        let cache = IndexCache {
+            version: INDEX_VERSION,
+            commits: self.commits.clone(),
+        };
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string(&cache)?).context("Failed to write prompt index")?;
+        fs::rename(&tmp, path).context("Failed to store prompt index")?;
+        Ok(())
+    }
+
+    /// Number of indexed commits
+    pub fn commit_count(&self) -> usize {
+        self.commits.len()
+    }
+
+    /// Group prompts by hash, most frequent first (ties broken by hash)
+    ///
+    /// `repo_label` is attached to every occurrence when several indexes are merged.
+    pub fn groups(indexes: &[(Option<String>, PromptIndex)]) -> Vec<PromptGroup> {
+        let mut groups: HashMap<String, PromptGroup> = HashMap::new();
+        for (repo_label, index) in indexes {
+            for (commit, indexed) in &index.commits {
+                for prompt in &indexed.prompts {
+                    let group = groups
+                        .entry(prompt.hash.clone())
+                        .or_insert_with(|| PromptGroup {
+                            hash: prompt.hash.clone(),
+                            preview: prompt.preview.clone(),
+                            count: 0,
+                            commits: 0,
+                            occurrences: Vec::new(),
+                        });
+                    group.count += 1;
+                    group.occurrences.push(PromptOccurrence {
+                        commit: commit.clone(),
+                        prompt_index: prompt.index,
+                        timestamp: prompt.timestamp.clone(),
+                        repo: repo_label.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut groups: Vec<PromptGroup> = groups
+            .into_values()
+            .map(|mut group| {
+                group.occurrences.sort_by(|a, b| {
+                    a.timestamp
+                        .cmp(&b.timestamp)
+                        .then_with(|| a.commit.cmp(&b.commit))
+                });
+                let commits: HashSet<(&Option<String>, &str)> = group
+                    .occurrences
+                    .iter()
+                    .map(|o| (&o.repo, o.commit.as_str()))
+                    .collect();
+                group.commits = commits.len();
+                group
+            })
+            .collect();
+        groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.hash.cmp(&b.hash)));
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::attribution::{AIAttribution, ModelInfo, PromptInfo, SessionMetadata};
+    use git2::{Oid, Signature};
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, message: &str) -> Oid {
+        let sig = Signature::now("Test", "test@test.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap()
+    }
+
+    fn attribution(prompts: &[&str]) -> AIAttribution {
+        AIAttribution {
+            version: 3,
+            session: SessionMetadata {
+                session_id: "session".to_string(),
+                model: ModelInfo::claude("claude-opus-4-5-20251101"),
+                started_at: "2026-01-30T10:00:00Z".to_string(),
+                prompt_count: prompts.len() as u32,
+                used_plan_mode: false,
+                subagent_count: 0,
+                label: None,
+            },
+            prompts: prompts
+                .iter()
+                .enumerate()
+                .map(|(i, text)| PromptInfo {
+                    index: i as u32,
+                    text: text.to_string(),
+                    timestamp: "2026-01-30T10:00:00Z".to_string(),
+                    affected_files: vec![],
+                    hash: None,
+                    privacy: None,
+                    tool_calls: Vec::new(),
+                })
+                .collect(),
+            files: vec![],
+        }
+    }
+
+    #[test]
+    fn test_groups_find_duplicates_across_commits() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let store = NotesStore::new(&repo).unwrap();
+
+        let first = commit(&repo, "first");
+        store
+            .store_attribution(first, &attribution(&["Ignore all rules", "Add tests"]))
+            .unwrap();
+        let second = commit(&repo, "second");
+        store
+            .store_attribution(second, &attribution(&["Ignore   all\nrules"]))
+            .unwrap();
+
+        let index = PromptIndex::refresh(&repo).unwrap();
+        assert_eq!(index.commit_count(), 2);
+
+        let groups = PromptIndex::groups(&[(None, index)]);
+        assert_eq!(groups.len(), 2);
+        // Whitespace differences still hash the same
+        assert_eq!(groups[0].count, 2);
+        assert_eq!(groups[0].commits, 2);
+        assert_eq!(groups[1].count, 1);
+    }
+
+    #[test]
+    fn test_refresh_reuses_cache_and_drops_removed_notes() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let store = NotesStore::new(&repo).unwrap();
+
+        let first = commit(&repo, "first");
+        store
+            .store_attribution(first, &attribution(&["Prompt A"]))
+            .unwrap();
+        PromptIndex::refresh(&repo).unwrap();
+        assert!(repo.path().join(INDEX_FILE).exists());
+
+        // Rewritten note is re-read; removed note is dropped
+        store
+            .store_attribution(first, &attribution(&["Prompt B"]))
+            .unwrap();
+        let index = PromptIndex::refresh(&repo).unwrap();
+        let groups = PromptIndex::groups(&[(None, index)]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].preview, "Prompt B");
+
+        store.remove_attribution(first).unwrap();
+        let index = PromptIndex::refresh(&repo).unwrap();
+        assert_eq!(index.commit_count(), 0);
+    }
+}
@@ -0,0 +1,262 @@
+//! Repository-level operation lock
+//!
+//! The pending buffer has its own lock, but commands that rewrite attribution
+//! notes (`retention apply`, `copy-notes`, `import`, ...) can still interleave
+//! with the post-commit hook writing a note for the commit just made. Mutating
+//! operations therefore take a coarse advisory lock on `.git/whogitit/lock`.
+//! The lock file records who holds it so waiters and `whogitit status` can say
+//! what they are waiting for.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Lock file (inside the git dir)
+const REPO_LOCK_FILE: &str = "whogitit/lock";
+
+/// How long mutating operations wait for another holder before giving up
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between attempts while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Operation holding the repository lock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LockHolderKind {
+    PostCommit,
+    Retention,
+    CopyNotes,
+    Import,
+    Recover,
+    Worker,
+    Stash,
+    Clear,
+}
+
+impl fmt::Display for LockHolderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::PostCommit => "post-commit hook",
+            Self::Retention => "retention apply",
+            Self::CopyNotes => "copy-notes",
+            Self::Import => "import",
+            Self::Recover => "recover",
+            Self::Worker => "worker",
+            Self::Stash => "stash",
+            Self::Clear => "clear",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Who holds the lock, as recorded in the lock file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub kind: LockHolderKind,
+    pub pid: u32,
+    /// RFC 3339 time the lock was taken
+    pub acquired_at: String,
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (pid {}, since {})",
+            self.kind, self.pid, self.acquired_at
+        )
+    }
+}
+
+/// Held repository lock; released when dropped
+#[derive(Debug)]
+pub struct RepoLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl RepoLock {
+    /// Take the lock for `kind`, waiting up to `timeout` for the current holder
+    pub fn acquire(git_dir: &Path, kind: LockHolderKind, timeout: Duration) -> Result<Self> {
+        let path = git_dir.join(REPO_LOCK_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // Not truncated on open: the current holder's record must survive until we own the lock
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+
+        let deadline = Instant::now() + timeout;
+        let mut announced = false;
+        while !try_lock(&file)? {
+            let holder = read_holder(&mut file);
+            if Instant::now() >= deadline {
+                let holder = holder
+                    .map(|h| h.to_string())
+                    .unwrap_or_else(|| "another whogitit process".to_string());
+                anyhow::bail!(
+                    "Timed out after {}s waiting for the repository lock held by {}",
+                    timeout.as_secs(),
+                    holder
+                );
+            }
+            if !announced {
+                if let Some(holder) = &holder {
+                    eprintln!(
+                        "whogitit: Waiting for {} to release the repository lock...",
+                        holder
+                    );
+                    announced = true;
+                }
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+
+        let holder = LockHolder {
+            kind,
+            pid: std::process::id(),
+            acquired_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        };
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(serde_json::to_string(&holder)?.as_bytes())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        file.flush()?;
+
+        Ok(Self { file, path })
+    }
+
+    /// Take the lock with [`DEFAULT_LOCK_TIMEOUT`]
+    pub fn acquire_default(git_dir: &Path, kind: LockHolderKind) -> Result<Self> {
+        Self::acquire(git_dir, kind, DEFAULT_LOCK_TIMEOUT)
+    }
+
+    /// Current holder of the lock, if it is held
+    pub fn holder(git_dir: &Path) -> Option<LockHolder> {
+        let mut file = File::open(git_dir.join(REPO_LOCK_FILE)).ok()?;
+        if is_free(&file) {
+            return None;
+        }
+        read_holder(&mut file)
+    }
+
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RepoLock {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(0);
+        unlock(&self.file);
+    }
+}
+
+fn read_holder(file: &mut File) -> Option<LockHolder> {
+    let mut content = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Try to take the exclusive lock without blocking
+#[cfg(unix)]
+fn try_lock(file: &File) -> Result<bool> {
+    let result = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    if result == 0 {
+        return Ok(true);
+    }
+    let err = std::io::Error::last_os_error();
+    if err.kind() == std::io::ErrorKind::WouldBlock {
+        Ok(false)
+    } else {
+        Err(err).context("Failed to acquire the repository lock")
+    }
+}
+
+/// No locking on non-Unix platforms; the holder is still recorded
+#[cfg(not(unix))]
+fn try_lock(_file: &File) -> Result<bool> {
+    Ok(true)
+}
+
+/// Whether nobody holds the lock (probed with a shared lock)
+#[cfg(unix)]
+fn is_free(file: &File) -> bool {
+    let fd = file.as_raw_fd();
+    if unsafe { libc::flock(fd, libc::LOCK_SH | libc::LOCK_NB) } != 0 {
+        return false;
+    }
+    unsafe {
+        libc::flock(fd, libc::LOCK_UN);
+    }
+    true
+}
+
+/// Without locking, a non-empty lock file means a holder that has not finished
+#[cfg(not(unix))]
+fn is_free(file: &File) -> bool {
+    file.metadata().map(|m| m.len() == 0).unwrap_or(true)
+}
+
+#[cfg(unix)]
+fn unlock(file: &File) {
+    unsafe {
+        libc::flock(file.as_raw_fd(), libc::LOCK_UN);
+    }
+}
+
+#[cfg(not(unix))]
+fn unlock(_file: &File) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_holder_is_reported_while_held() {
+        let dir = TempDir::new().unwrap();
+        assert!(RepoLock::holder(dir.path()).is_none());
+
+        let lock = RepoLock::acquire_default(dir.path(), LockHolderKind::Retention).unwrap();
+        let holder = RepoLock::holder(dir.path()).unwrap();
+        assert_eq!(holder.kind, LockHolderKind::Retention);
+        assert_eq!(holder.pid, std::process::id());
+        assert!(lock.path().ends_with("whogitit/lock"));
+
+        drop(lock);
+        assert!(RepoLock::holder(dir.path()).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_second_holder_times_out() {
+        let dir = TempDir::new().unwrap();
+        let _lock = RepoLock::acquire_default(dir.path(), LockHolderKind::PostCommit).unwrap();
+
+        let err = RepoLock::acquire(
+            dir.path(),
+            LockHolderKind::CopyNotes,
+            Duration::from_millis(100),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("post-commit hook (pid"));
+    }
+}
@@ -8,6 +8,7 @@ pub mod keys {
     pub const AI_MODIFIED: &str = "AI-Modified";
     pub const HUMAN_LINES: &str = "Human-Lines";
     pub const CO_AUTHORED_BY: &str = "Co-Authored-By";
+    pub const REVIEWED_BY: &str = "Reviewed-by";
 }
 
 /// Generates git trailers from attribution data
@@ -76,8 +77,47 @@ impl TrailerGenerator {
             format!("{}\n\n{}", trimmed, trailer_block)
         }
     }
+
+    /// Add trailers to a commit message file as seen by the `commit-msg` hook
+    ///
+    /// Trailers go before git's trailing `#` comment block (and any `--verbose`
+    /// diff below the scissors line). AI trailers from an earlier run, e.g. before
+    /// `git commit --amend`, are replaced rather than duplicated.
+    pub fn apply_to_commit_message(message: &str, attribution: &AIAttribution) -> String {
+        let lines: Vec<&str> = message.lines().collect();
+        let mut split = lines
+            .iter()
+            .position(|line| line.starts_with('#') && line.contains(SCISSORS))
+            .unwrap_or(lines.len());
+        while split > 0 && (lines[split - 1].starts_with('#') || lines[split - 1].trim().is_empty())
+        {
+            split -= 1;
+        }
+        let (body, comments) = lines.split_at(split);
+
+        let body = TrailerParser::strip_ai_trailers(&body.join("\n"));
+        let mut result = Self::append_to_message(&body, attribution);
+        result.push('\n');
+        if comments.iter().any(|line| !line.trim().is_empty()) {
+            result.push_str(&comments.join("\n"));
+            result.push('\n');
+        }
+        result
+    }
 }
 
+/// Marker git puts above the diff in `git commit --verbose` messages
+const SCISSORS: &str = "------------------------ >8 ------------------------";
+
+/// Trailer keys written by [`TrailerGenerator`]
+const AI_KEYS: &[&str] = &[
+    keys::AI_SESSION,
+    keys::AI_MODEL,
+    keys::AI_LINES,
+    keys::AI_MODIFIED,
+    keys::HUMAN_LINES,
+];
+
 /// Parse trailers from a commit message
 pub struct TrailerParser;
 
@@ -109,6 +149,9 @@ impl TrailerParser {
                     k if k == keys::HUMAN_LINES => {
                         result.human_lines = value.parse().ok();
                     }
+                    k if k.eq_ignore_ascii_case(keys::REVIEWED_BY) => {
+                        result.reviewed_by.push(value.to_string());
+                    }
                     _ => {}
                 }
             }
@@ -119,8 +162,32 @@ impl TrailerParser {
 
     /// Check if a commit message has AI trailers
     pub fn has_ai_trailers(message: &str) -> bool {
-        let parsed = Self::parse(message);
-        parsed.session.is_some() || parsed.model.is_some()
+        Self::parse(message).has_attribution()
+    }
+
+    /// Remove trailers written by [`TrailerGenerator`] from the end of a message
+    pub fn strip_ai_trailers(message: &str) -> String {
+        let mut lines: Vec<&str> = message.trim_end().lines().collect();
+        let mut end = lines.len();
+        let mut kept = Vec::new();
+        while end > 0 {
+            let line = lines[end - 1];
+            let Some((key, value)) = line.split_once(": ") else {
+                break;
+            };
+            let generated_co_author =
+                key == keys::CO_AUTHORED_BY && value.ends_with(CO_AUTHOR_EMAIL);
+            if !AI_KEYS.contains(&key) && !generated_co_author {
+                kept.push(line);
+            }
+            end -= 1;
+        }
+        lines.truncate(end);
+        if !kept.is_empty() {
+            kept.reverse();
+            lines.extend(kept);
+        }
+        lines.join("\n").trim_end().to_string()
     }
 }
 
@@ -132,8 +199,20 @@ pub struct ParsedTrailers {
     pub ai_lines: Option<usize>,
     pub ai_modified_lines: Option<usize>,
     pub human_lines: Option<usize>,
+    /// `Reviewed-by:` trailers (not written by whogitit; checked by the policy gate)
+    pub reviewed_by: Vec<String>,
 }
 
+impl ParsedTrailers {
+    /// Whether the message carries whogitit attribution trailers
+    pub fn has_attribution(&self) -> bool {
+        self.session.is_some() || self.model.is_some()
+    }
+}
+
+/// Address used in the generated Co-Authored-By trailer
+const CO_AUTHOR_EMAIL: &str = "<noreply@anthropic.com>";
+
 /// Format co-author string based on model
 fn format_co_author(model_id: &str) -> String {
     let model_name = if model_id.contains("opus") {
@@ -146,7 +225,7 @@ fn format_co_author(model_id: &str) -> String {
         "Claude"
     };
 
-    format!("{} <noreply@anthropic.com>", model_name)
+    format!("{} {}", model_name, CO_AUTHOR_EMAIL)
 }
 
 /// Check if message has existing trailers at the end
@@ -188,6 +267,7 @@ mod tests {
                 prompt_count: 3,
                 used_plan_mode: false,
                 subagent_count: 0,
+                label: None,
             },
             prompts: vec![],
             files: vec![FileAttributionResult {
@@ -201,6 +281,10 @@ mod tests {
                     original_lines: 2,
                     unknown_lines: 0,
                 },
+                binary: None,
+                blocks: None,
+                analysis_warning: None,
+                secret_findings: Vec::new(),
             }],
         }
     }
@@ -253,6 +337,11 @@ mod tests {
         assert_eq!(parsed.model, Some("claude-opus-4-5-20251101".to_string()));
         assert_eq!(parsed.ai_lines, Some(42));
         assert_eq!(parsed.ai_modified_lines, Some(5));
+        assert!(parsed.reviewed_by.is_empty());
+
+        let reviewed =
+            TrailerParser::parse("Fix\n\nAI-Session: abc\nReviewed-By: Ann <ann@example.com>");
+        assert_eq!(reviewed.reviewed_by, vec!["Ann <ann@example.com>"]);
     }
 
     #[test]
@@ -263,4 +352,36 @@ mod tests {
         assert!(TrailerParser::has_ai_trailers(with_trailers));
         assert!(!TrailerParser::has_ai_trailers(without_trailers));
     }
+
+    #[test]
+    fn test_apply_to_commit_message_keeps_comments_and_replaces_old_trailers() {
+        let attribution = test_attribution();
+        let message = "Add feature\n\nSigned-off-by: Dev <dev@example.com>\n\
+                       # Please enter the commit message\n\
+                       # ------------------------ >8 ------------------------\n\
+                       diff --git a/test.rs b/test.rs\n";
+
+        let first = TrailerGenerator::apply_to_commit_message(message, &attribution);
+        assert!(first.starts_with(
+            "Add feature\n\nSigned-off-by: Dev <dev@example.com>\nAI-Session: abc123-def45\n"
+        ));
+        assert!(first.ends_with("# Please enter the commit message\n# ------------------------ >8 ------------------------\ndiff --git a/test.rs b/test.rs\n"));
+
+        // Amending re-runs the hook: trailers are replaced, not duplicated
+        let second = TrailerGenerator::apply_to_commit_message(&first, &attribution);
+        assert_eq!(first, second);
+        assert_eq!(second.matches("AI-Lines:").count(), 1);
+        assert!(second.contains("Signed-off-by: Dev <dev@example.com>"));
+    }
+
+    #[test]
+    fn test_strip_ai_trailers() {
+        let message = "Fix bug\n\nAI-Session: abc\nReviewed-by: Ann <ann@example.com>\n\
+                       AI-Lines: 3\nCo-Authored-By: Claude <noreply@anthropic.com>\n";
+        assert_eq!(
+            TrailerParser::strip_ai_trailers(message),
+            "Fix bug\n\nReviewed-by: Ann <ann@example.com>"
+        );
+        assert_eq!(TrailerParser::strip_ai_trailers("Fix bug\n"), "Fix bug");
+    }
 }
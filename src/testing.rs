@@ -0,0 +1,480 @@
+//! Scratch repositories for attribution scenarios
+//!
+//! Each [`Scenario`] builds a small repository by feeding edits through the real
+//! capture hook and post-commit analysis, so the resulting notes are exactly what
+//! whogitit would record in the field. Commits use fixed authors and timestamps and
+//! are reproducible; notes carry capture times and differ between runs.
+//!
+//! Available to downstream crates with the `dev-tools` feature; `whogitit fixtures
+//! generate` wraps [`generate`].
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use git2::{Oid, Repository, Signature, Time};
+
+use crate::capture::hook::{CaptureHook, HookInput};
+use crate::core::attribution::{AIAttribution, ModelInfo};
+use crate::storage::notes::NotesStore;
+
+/// Commit time of the first fixture commit (2025-01-01T00:00:00Z)
+const BASE_COMMIT_TIME: i64 = 1_735_689_600;
+const FIXTURE_AUTHOR: &str = "whogitit fixtures";
+const FIXTURE_EMAIL: &str = "fixtures@whogitit.invalid";
+const FIXTURE_MODEL: &str = "fixture-model";
+
+/// An attribution scenario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Scenario {
+    /// The AI writes a new file that is committed as-is
+    AiNewFile,
+    /// The AI adds a function; a human edits one of its lines and adds another
+    HumanModifiedAi,
+    /// A file is renamed, then edited by the AI under its new name
+    Rename,
+    /// Two AI-assisted commits squashed into one commit without a note
+    Squash,
+    /// A formatter reindents and rewraps AI-written code before the commit
+    FormatterReflow,
+    /// AI commits on three branches, one itself a merge, joined by an octopus merge
+    OctopusMerge,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 6] = [
+        Scenario::AiNewFile,
+        Scenario::HumanModifiedAi,
+        Scenario::Rename,
+        Scenario::Squash,
+        Scenario::FormatterReflow,
+        Scenario::OctopusMerge,
+    ];
+
+    /// Name used on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Scenario::AiNewFile => "ai-new-file",
+            Scenario::HumanModifiedAi => "human-modified-ai",
+            Scenario::Rename => "rename",
+            Scenario::Squash => "squash",
+            Scenario::FormatterReflow => "formatter-reflow",
+            Scenario::OctopusMerge => "octopus-merge",
+        }
+    }
+
+    /// One-line description of the repository it produces
+    pub fn description(self) -> &'static str {
+        match self {
+            Scenario::AiNewFile => "AI writes src/generated.rs, committed unchanged",
+            Scenario::HumanModifiedAi => {
+                "AI adds a function to src/lib.rs; a human edits one AI line and adds another"
+            }
+            Scenario::Rename => "src/old_name.rs renamed to src/new_name.rs, then extended by AI",
+            Scenario::Squash => {
+                "two AI commits on branch `feature`, squashed onto the default branch without a note"
+            }
+            Scenario::FormatterReflow => "AI writes src/format.rs; a formatter reflows it",
+            Scenario::OctopusMerge => {
+                "AI commits on three branches (one a merge of two more) joined by a 3-parent merge"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Scenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A generated scenario repository
+pub struct Fixture {
+    pub scenario: Scenario,
+    pub dir: PathBuf,
+    pub repo: Repository,
+    /// Commits in creation order; the last one is HEAD
+    pub commits: Vec<Oid>,
+}
+
+impl Fixture {
+    /// HEAD commit
+    pub fn head(&self) -> Oid {
+        *self
+            .commits
+            .last()
+            .expect("fixtures have at least one commit")
+    }
+
+    /// Attribution note on a commit
+    pub fn attribution(&self, commit: Oid) -> Result<Option<AIAttribution>> {
+        NotesStore::new(&self.repo)?.fetch_attribution(commit)
+    }
+}
+
+/// Create the repository for a scenario in `dir`, which must be missing or empty
+pub fn generate(scenario: Scenario, dir: &Path) -> Result<Fixture> {
+    if dir.exists() && dir.read_dir()?.next().is_some() {
+        anyhow::bail!("{} is not empty", dir.display());
+    }
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let mut builder = FixtureBuilder::new(scenario, dir)?;
+    match scenario {
+        Scenario::AiNewFile => ai_new_file(&mut builder)?,
+        Scenario::HumanModifiedAi => human_modified_ai(&mut builder)?,
+        Scenario::Rename => rename(&mut builder)?,
+        Scenario::Squash => squash(&mut builder)?,
+        Scenario::FormatterReflow => formatter_reflow(&mut builder)?,
+        Scenario::OctopusMerge => octopus_merge(&mut builder)?,
+    }
+
+    Ok(Fixture {
+        scenario,
+        dir: dir.to_path_buf(),
+        repo: builder.repo,
+        commits: builder.commits,
+    })
+}
+
+fn ai_new_file(b: &mut FixtureBuilder) -> Result<()> {
+    b.write("README.md", "# Fixture\n")?;
+    b.commit("Initial commit")?;
+    b.ai_edit(
+        "Write",
+        "src/generated.rs",
+        "pub fn generated() -> u32 {\n    42\n}\n",
+        "Add a generated module",
+    )?;
+    b.commit("Add generated module")
+}
+
+fn human_modified_ai(b: &mut FixtureBuilder) -> Result<()> {
+    b.write("src/lib.rs", "pub fn original() {}\n")?;
+    b.commit("Initial commit")?;
+    b.ai_edit(
+        "Edit",
+        "src/lib.rs",
+        "pub fn original() {}\n\npub fn total(items: &[u32]) -> u32 {\n    items.iter().sum()\n}\n",
+        "Add a total function",
+    )?;
+    b.write(
+        "src/lib.rs",
+        "pub fn original() {}\n\npub fn total(items: &[u32]) -> u32 {\n    items.iter().copied().sum()\n}\n// Reviewed: overflow is not a concern here\n",
+    )?;
+    b.commit("Add total")
+}
+
+fn rename(b: &mut FixtureBuilder) -> Result<()> {
+    let original = "pub fn parse(input: &str) -> Vec<&str> {\n    input.split(',').collect()\n}\n\npub fn join(parts: &[&str]) -> String {\n    parts.join(\",\")\n}\n";
+    b.write("src/old_name.rs", original)?;
+    b.commit("Initial commit")?;
+    b.remove("src/old_name.rs")?;
+    b.write("src/new_name.rs", original)?;
+    b.ai_edit(
+        "Edit",
+        "src/new_name.rs",
+        &format!(
+            "{}\npub fn count(input: &str) -> usize {{\n    parse(input).len()\n}}\n",
+            original
+        ),
+        "Add a count helper",
+    )?;
+    b.commit("Rename module and add count")
+}
+
+fn squash(b: &mut FixtureBuilder) -> Result<()> {
+    b.write("README.md", "# Fixture\n")?;
+    let base = b.commit_oid("Initial commit")?;
+    b.ai_edit(
+        "Write",
+        "src/feature.rs",
+        "pub fn feature() -> bool {\n    true\n}\n",
+        "Add the feature flag",
+    )?;
+    b.commit("Add feature")?;
+    b.ai_edit(
+        "Edit",
+        "src/feature.rs",
+        "pub fn feature() -> bool {\n    true\n}\n\npub fn disabled() -> bool {\n    !feature()\n}\n",
+        "Add the inverse flag",
+    )?;
+    let tip = b.commit_oid("Add disabled")?;
+
+    // Keep the branch commits on `feature` and squash them onto the default branch
+    let repo = &b.repo;
+    repo.branch("feature", &repo.find_commit(tip)?, false)?;
+    let head_ref = repo.head()?.name().unwrap_or("refs/heads/main").to_string();
+    let tree = repo.find_commit(tip)?.tree()?;
+    let sig = b.signature()?;
+    let squashed = repo.commit(
+        None,
+        &sig,
+        &sig,
+        "Add feature (squashed)",
+        &tree,
+        &[&repo.find_commit(base)?],
+    )?;
+    repo.reference(&head_ref, squashed, true, "fixtures: squash")?;
+    b.commits.push(squashed);
+    Ok(())
+}
+
+fn formatter_reflow(b: &mut FixtureBuilder) -> Result<()> {
+    b.write("README.md", "# Fixture\n")?;
+    b.commit("Initial commit")?;
+    b.ai_edit(
+        "Write",
+        "src/format.rs",
+        "pub fn describe(name: &str, count: usize) -> String {\n  format!(\"{} has {} items\", name, count)\n}\n\npub fn sum(values: &[i64]) -> i64 { values.iter().sum() }\n",
+        "Add formatting helpers",
+    )?;
+    b.write(
+        "src/format.rs",
+        "pub fn describe(name: &str, count: usize) -> String {\n    format!(\"{} has {} items\", name, count)\n}\n\npub fn sum(values: &[i64]) -> i64 {\n    values.iter().sum()\n}\n",
+    )?;
+    b.commit("Add formatting helpers")
+}
+
+fn octopus_merge(b: &mut FixtureBuilder) -> Result<()> {
+    // Each branch fills one section, so the merged file matches no single parent
+    let sections = |alpha: &str, beta: &str, gamma: &str| {
+        format!(
+            "// alpha\n{}\n// beta\n{}\n// gamma\n{}",
+            alpha, beta, gamma
+        )
+    };
+    let alpha = "pub fn alpha() -> u8 {\n    1\n}\n";
+    let beta = "pub fn beta() -> u8 {\n    2\n}\n";
+    let gamma = "pub fn gamma() -> u8 {\n    3\n}\n";
+    let delta = "pub fn delta() -> u8 {\n    4\n}\n";
+
+    b.write("src/lib.rs", &sections("", "", ""))?;
+    let base = b.commit_oid("Initial commit")?;
+    let head_ref = b
+        .repo
+        .head()?
+        .name()
+        .unwrap_or("refs/heads/main")
+        .to_string();
+
+    b.ai_edit("Edit", "src/lib.rs", &sections(alpha, "", ""), "Add alpha")?;
+    let alpha_commit = b.commit_oid("Add alpha")?;
+
+    b.checkout(base)?;
+    b.ai_edit("Edit", "src/lib.rs", &sections("", beta, ""), "Add beta")?;
+    let beta_commit = b.commit_with_parents(&[base], "Add beta")?;
+
+    b.checkout(base)?;
+    b.ai_edit("Edit", "src/lib.rs", &sections("", "", gamma), "Add gamma")?;
+    let gamma_commit = b.commit_with_parents(&[base], "Add gamma")?;
+
+    b.checkout(base)?;
+    b.ai_edit("Write", "src/delta.rs", delta, "Add delta")?;
+    let delta_commit = b.commit_with_parents(&[base], "Add delta")?;
+
+    // The third octopus parent is itself a merge
+    b.checkout(gamma_commit)?;
+    b.write("src/delta.rs", delta)?;
+    let gamma_delta =
+        b.commit_with_parents(&[gamma_commit, delta_commit], "Merge delta into gamma")?;
+
+    b.checkout(alpha_commit)?;
+    b.write("src/lib.rs", &sections(alpha, beta, gamma))?;
+    b.write("src/delta.rs", delta)?;
+    let merge = b.commit_with_parents(
+        &[alpha_commit, beta_commit, gamma_delta],
+        "Merge beta and gamma",
+    )?;
+
+    b.repo
+        .reference(&head_ref, merge, true, "fixtures: octopus merge")?;
+    b.repo.set_head(&head_ref)?;
+    Ok(())
+}
+
+/// Drives the capture hook and commits for one scenario
+struct FixtureBuilder {
+    dir: PathBuf,
+    repo: Repository,
+    hook: CaptureHook,
+    session: String,
+    commits: Vec<Oid>,
+}
+
+impl FixtureBuilder {
+    fn new(scenario: Scenario, dir: &Path) -> Result<Self> {
+        let repo = Repository::init(dir).context("git init failed")?;
+        std::fs::write(
+            repo.path().join("info/exclude"),
+            ".whogitit-pending.json\n.whogitit-pending.lock\n.whogitit-pending.tmp\n",
+        )?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            hook: CaptureHook::new(dir)?,
+            repo,
+            // Sessions must be UUIDs; one fixed ID per scenario keeps notes comparable
+            session: format!(
+                "00000000-0000-4000-8000-{:012}",
+                Scenario::ALL
+                    .iter()
+                    .position(|s| *s == scenario)
+                    .unwrap_or(0)
+                    + 1
+            ),
+            commits: Vec::new(),
+        })
+    }
+
+    /// Write a file as a human would
+    fn write(&self, path: &str, content: &str) -> Result<()> {
+        let full = self.dir.join(path);
+        if let Some(parent) = full.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(full, content)?;
+        Ok(())
+    }
+
+    fn remove(&self, path: &str) -> Result<()> {
+        std::fs::remove_file(self.dir.join(path))?;
+        Ok(())
+    }
+
+    /// Record an AI edit through the capture hook, then write the file
+    fn ai_edit(&self, tool: &str, path: &str, content: &str, prompt: &str) -> Result<()> {
+        let before = std::fs::read_to_string(self.dir.join(path)).ok();
+        self.hook.on_file_change_for_session(
+            HookInput {
+                tool: tool.to_string(),
+                file_path: path.to_string(),
+                prompt: prompt.to_string(),
+                old_content_present: before.is_some(),
+                old_content: before,
+                new_content: content.to_string(),
+                context: None,
+            },
+            Some(&self.session),
+            Some(ModelInfo::new(FIXTURE_MODEL, "whogitit")),
+        )?;
+        self.write(path, content)
+    }
+
+    /// Author and committer for the next commit, one minute after the previous one
+    fn signature(&self) -> Result<Signature<'static>> {
+        let time = Time::new(BASE_COMMIT_TIME + 60 * self.commits.len() as i64, 0);
+        Ok(Signature::new(FIXTURE_AUTHOR, FIXTURE_EMAIL, &time)?)
+    }
+
+    fn commit(&mut self, message: &str) -> Result<()> {
+        self.commit_oid(message).map(|_| ())
+    }
+
+    /// Commit the whole worktree on top of the previous commit
+    fn commit_oid(&mut self, message: &str) -> Result<Oid> {
+        let parents: Vec<Oid> = self.commits.last().copied().into_iter().collect();
+        self.commit_with_parents(&parents, message)
+    }
+
+    /// Detach HEAD at `commit` and reset the worktree to it
+    fn checkout(&self, commit: Oid) -> Result<()> {
+        self.repo.set_head_detached(commit)?;
+        self.repo.checkout_head(Some(
+            git2::build::CheckoutBuilder::new()
+                .force()
+                .remove_untracked(true),
+        ))?;
+        Ok(())
+    }
+
+    /// Commit the whole worktree with explicit parents and run the post-commit analysis
+    fn commit_with_parents(&mut self, parents: &[Oid], message: &str) -> Result<Oid> {
+        let mut index = self.repo.index()?;
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"].iter(), None)?;
+        index.write()?;
+        let tree = self.repo.find_tree(index.write_tree()?)?;
+        let sig = self.signature()?;
+        let parents = parents
+            .iter()
+            .map(|oid| self.repo.find_commit(*oid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        let oid = self
+            .repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)?;
+        self.commits.push(oid);
+        self.hook.on_post_commit()?;
+        Ok(oid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::snapshot::LineSource;
+    use tempfile::TempDir;
+
+    fn sources(fixture: &Fixture, commit: Oid, path: &str) -> Vec<LineSource> {
+        let attribution = fixture.attribution(commit).unwrap().unwrap();
+        let file = attribution.files.iter().find(|f| f.path == path).unwrap();
+        file.lines.iter().map(|l| l.source.clone()).collect()
+    }
+
+    #[test]
+    fn test_generate_scenarios() {
+        let dir = TempDir::new().unwrap();
+        let fixture =
+            |scenario: Scenario| generate(scenario, &dir.path().join(scenario.name())).unwrap();
+
+        let new_file = fixture(Scenario::AiNewFile);
+        let lines = sources(&new_file, new_file.head(), "src/generated.rs");
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(LineSource::is_ai));
+
+        let modified = fixture(Scenario::HumanModifiedAi);
+        let lines = sources(&modified, modified.head(), "src/lib.rs");
+        assert!(lines.contains(&LineSource::Original));
+        assert!(lines.contains(&LineSource::Human));
+        assert!(lines
+            .iter()
+            .any(|s| matches!(s, LineSource::AIModified { .. })));
+
+        let renamed = fixture(Scenario::Rename);
+        let lines = sources(&renamed, renamed.head(), "src/new_name.rs");
+        assert!(lines.contains(&LineSource::Original));
+        assert!(lines.iter().any(LineSource::is_ai));
+
+        let squashed = fixture(Scenario::Squash);
+        assert_eq!(squashed.commits.len(), 4);
+        assert!(squashed.attribution(squashed.commits[2]).unwrap().is_some());
+        assert!(squashed.attribution(squashed.head()).unwrap().is_none());
+
+        let reflowed = fixture(Scenario::FormatterReflow);
+        let lines = sources(&reflowed, reflowed.head(), "src/format.rs");
+        // Whitespace-only reflows keep AI attribution
+        assert!(lines.iter().all(LineSource::is_ai));
+        assert!(lines
+            .iter()
+            .any(|s| matches!(s, LineSource::AIModified { .. })));
+
+        let octopus = fixture(Scenario::OctopusMerge);
+        let merge = octopus.repo.find_commit(octopus.head()).unwrap();
+        assert_eq!(merge.parent_count(), 3);
+        assert_eq!(merge.parent(2).unwrap().parent_count(), 2);
+        assert_eq!(octopus.repo.head().unwrap().target(), Some(octopus.head()));
+        assert!(octopus.attribution(octopus.head()).unwrap().is_none());
+        for branch in &octopus.commits[1..5] {
+            assert!(octopus.attribution(*branch).unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn test_generate_is_reproducible_and_refuses_non_empty_dir() {
+        let dir = TempDir::new().unwrap();
+        let first = generate(Scenario::HumanModifiedAi, &dir.path().join("a")).unwrap();
+        let second = generate(Scenario::HumanModifiedAi, &dir.path().join("b")).unwrap();
+        assert_eq!(first.commits, second.commits);
+
+        assert!(generate(Scenario::AiNewFile, &dir.path().join("a")).is_err());
+    }
+}
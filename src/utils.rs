@@ -1,5 +1,8 @@
 //! Shared utility functions and constants
 
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
 /// Length of prompt preview in summaries
 pub const PROMPT_PREVIEW_LEN: usize = 60;
 
@@ -9,6 +12,9 @@ pub const CONTENT_HASH_BYTES: usize = 16;
 /// Number of bytes to use from SHA256 hash for diff hashing
 pub const DIFF_HASH_BYTES: usize = 8;
 
+/// Number of bytes to use from SHA256 hash for per-line content hashes in notes
+pub const LINE_HASH_BYTES: usize = 4;
+
 /// Short commit ID length
 pub const SHORT_COMMIT_LEN: usize = 7;
 
@@ -85,6 +91,248 @@ pub mod hex {
     pub fn encode(bytes: &[u8]) -> String {
         bytes.iter().map(|b| format!("{:02x}", b)).collect()
     }
+
+    /// Decode a hex string, or `None` if it is not valid hex
+    pub fn decode(text: &str) -> Option<Vec<u8>> {
+        if text.len() % 2 != 0 {
+            return None;
+        }
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
+}
+
+/// Repository paths as lossless strings
+///
+/// Attribution records paths as strings. A path that is valid UTF-8 is kept as
+/// it is; any other path is written the way git prints it with `core.quotePath`:
+/// in double quotes, with `\"`, `\\` and octal escapes for bytes outside
+/// printable ASCII. Paths with control characters (such as newlines) or a
+/// leading `"` are quoted as well, so a quoted string always decodes back to
+/// the original bytes.
+pub mod repo_path {
+    use std::borrow::Cow;
+    use std::path::{Path, PathBuf};
+
+    /// String form of raw path bytes (as stored in the git index and trees)
+    pub fn encode(bytes: &[u8]) -> String {
+        match std::str::from_utf8(bytes) {
+            Ok(text) if !text.starts_with('"') && !text.contains(char::is_control) => {
+                text.to_string()
+            }
+            _ => quote(bytes),
+        }
+    }
+
+    fn quote(bytes: &[u8]) -> String {
+        let mut quoted = String::with_capacity(bytes.len() + 2);
+        quoted.push('"');
+        for &byte in bytes {
+            match byte {
+                b'"' => quoted.push_str("\\\""),
+                b'\\' => quoted.push_str("\\\\"),
+                0x20..=0x7e => quoted.push(byte as char),
+                _ => quoted.push_str(&format!("\\{:03o}", byte)),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    /// Raw bytes of an encoded path
+    ///
+    /// Strings that are not a well-formed quoted path are taken literally.
+    pub fn decode(path: &str) -> Cow<'_, [u8]> {
+        let Some(inner) = path
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+        else {
+            return Cow::Borrowed(path.as_bytes());
+        };
+        match unquote(inner.as_bytes()) {
+            Some(bytes) => Cow::Owned(bytes),
+            None => Cow::Borrowed(path.as_bytes()),
+        }
+    }
+
+    fn unquote(inner: &[u8]) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(inner.len());
+        let mut rest = inner;
+        while let Some((&byte, tail)) = rest.split_first() {
+            rest = tail;
+            match byte {
+                b'"' => return None,
+                b'\\' => match rest {
+                    [b'"' | b'\\', tail @ ..] => {
+                        bytes.push(rest[0]);
+                        rest = tail;
+                    }
+                    [a @ b'0'..=b'3', b @ b'0'..=b'7', c @ b'0'..=b'7', tail @ ..] => {
+                        bytes.push((a - b'0') << 6 | (b - b'0') << 3 | (c - b'0'));
+                        rest = tail;
+                    }
+                    _ => return None,
+                },
+                _ => bytes.push(byte),
+            }
+        }
+        Some(bytes)
+    }
+
+    /// String form of a filesystem or git path
+    pub fn from_path(path: &Path) -> String {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            encode(path.as_os_str().as_bytes())
+        }
+        #[cfg(not(unix))]
+        {
+            encode(path.to_string_lossy().as_bytes())
+        }
+    }
+
+    /// Filesystem path of an encoded path
+    pub fn to_path(path: &str) -> PathBuf {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            PathBuf::from(std::ffi::OsStr::from_bytes(&decode(path)))
+        }
+        #[cfg(not(unix))]
+        {
+            PathBuf::from(String::from_utf8_lossy(&decode(path)).into_owned())
+        }
+    }
+}
+
+/// A compiled glob pattern for repository-relative paths
+///
+/// Supports `*` (within a path segment), `**` (across segments) and `?`.
+/// Patterns without a `/` match the file name at any depth, like `.gitignore`.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: String,
+    regex: regex::Regex,
+    file_name_only: bool,
+}
+
+impl Glob {
+    /// Compile a pattern; `None` for an empty or uncompilable pattern
+    pub fn new(pattern: &str) -> Option<Self> {
+        let trimmed = pattern.trim().trim_start_matches("./");
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        let mut regex = String::from("^");
+        let mut chars = trimmed.trim_start_matches('/').chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+
+        Some(Self {
+            pattern: pattern.to_string(),
+            regex: regex::Regex::new(&regex).ok()?,
+            file_name_only: !trimmed.contains('/'),
+        })
+    }
+
+    /// Compile a configured pattern, warning once if it has to be ignored
+    pub fn new_or_warn(pattern: &str) -> Option<Self> {
+        let glob = Self::new(pattern);
+        if glob.is_none() {
+            warn_ignored_glob(pattern);
+        }
+        glob
+    }
+
+    /// Whether a path matches
+    pub fn is_match(&self, path: &str) -> bool {
+        let target = if self.file_name_only {
+            path.rsplit('/').next().unwrap_or(path)
+        } else {
+            path
+        };
+        self.regex.is_match(target)
+    }
+
+    /// The pattern as given
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl PartialEq for Glob {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+/// Glob patterns compiled once, for matching many paths
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlobSet {
+    globs: Vec<Glob>,
+}
+
+impl GlobSet {
+    /// Compile patterns; invalid or empty ones match nothing and are dropped,
+    /// with a warning the first time each one is seen
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self {
+            globs: patterns
+                .iter()
+                .filter_map(|p| Glob::new_or_warn(p.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Whether a path matches any pattern
+    pub fn is_match(&self, path: &str) -> bool {
+        self.globs.iter().any(|glob| glob.is_match(path))
+    }
+
+    /// Whether no usable pattern was given, so nothing matches
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_empty()
+    }
+}
+
+/// Warn once per process about a glob pattern that was dropped
+fn warn_ignored_glob(pattern: &str) {
+    static WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    let mut warned = WARNED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if warned.insert(pattern.to_string()) {
+        eprintln!(
+            "whogitit: Warning - ignoring glob pattern {:?}: it is empty or invalid",
+            pattern
+        );
+    }
+}
+
+/// Match a path against a single glob pattern (compiles it; use [`Glob`] or
+/// [`GlobSet`] when matching many paths)
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    Glob::new(pattern).is_some_and(|glob| glob.is_match(path))
 }
 
 #[cfg(test)]
@@ -149,5 +397,61 @@ mod tests {
     fn test_hex_encode() {
         assert_eq!(hex::encode(&[0x00, 0xff, 0x10]), "00ff10");
         assert_eq!(hex::encode(&[]), "");
+        assert_eq!(hex::decode("00ff10"), Some(vec![0x00, 0xff, 0x10]));
+        assert_eq!(hex::decode("0f1"), None);
+        assert_eq!(hex::decode("zz"), None);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("src/auth/**", "src/auth/login.rs"));
+        assert!(glob_match("src/auth/**", "src/auth/oauth/token.rs"));
+        assert!(!glob_match("src/auth/**", "src/authz.rs"));
+        assert!(glob_match("**/migrations/*.sql", "db/migrations/001.sql"));
+        assert!(glob_match("**/migrations/*.sql", "migrations/001.sql"));
+        assert!(!glob_match("src/*.rs", "src/cli/mod.rs"));
+        assert!(glob_match("src/*.rs", "src/lib.rs"));
+        assert!(glob_match("*.lock", "vendor/Cargo.lock"));
+        assert!(glob_match("Cargo.t?ml", "Cargo.toml"));
+        assert!(!glob_match("", "anything"));
+    }
+
+    #[test]
+    fn test_glob_set() {
+        let patterns = vec![
+            "*.md".to_string(),
+            "src/crypto/**".to_string(),
+            " ".to_string(),
+        ];
+        let set = GlobSet::new(&patterns);
+        assert!(set.is_match("docs/README.md"));
+        assert!(set.is_match("src/crypto/aes.rs"));
+        assert!(!set.is_match("src/main.rs"));
+        assert!(GlobSet::new::<String>(&[]).is_empty());
+        assert_eq!(Glob::new("src/**").unwrap().pattern(), "src/**");
+    }
+
+    #[test]
+    fn test_repo_path_round_trip() {
+        assert_eq!(repo_path::encode(b"src/lib.rs"), "src/lib.rs");
+        assert_eq!(
+            repo_path::encode("docs/caf\u{e9}.md".as_bytes()),
+            "docs/caf\u{e9}.md"
+        );
+
+        let latin1 = b"data/caf\xe9 \"1\".txt";
+        let encoded = repo_path::encode(latin1);
+        assert_eq!(encoded, r#""data/caf\351 \"1\".txt""#);
+        assert_eq!(repo_path::decode(&encoded).as_ref(), latin1);
+
+        // A literal leading quote is escaped too, so decoding stays unambiguous
+        let quoted = repo_path::encode(b"\"odd\".rs");
+        assert_eq!(quoted, r#""\"odd\".rs""#);
+        assert_eq!(repo_path::decode(&quoted).as_ref(), b"\"odd\".rs");
+
+        assert_eq!(repo_path::encode(b"two\nlines"), r#""two\012lines""#);
+
+        // Anything that is not a well-formed quoted path is taken literally
+        assert_eq!(repo_path::decode(r#""bad\9""#).as_ref(), br#""bad\9""#);
     }
 }
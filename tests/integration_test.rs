@@ -1,3 +1,6 @@
+//! Needs the `git` feature (on by default)
+#![cfg(feature = "git")]
+
 use std::fs;
 
 use git2::{Repository, Signature};
@@ -127,6 +130,7 @@ fn test_trailers() {
             prompt_count: 5,
             used_plan_mode: false,
             subagent_count: 0,
+            label: None,
         },
         prompts: vec![],
         files: vec![FileAttributionResult {
@@ -140,6 +144,10 @@ fn test_trailers() {
                 original_lines: 0,
                 unknown_lines: 0,
             },
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
         }],
     };
 
@@ -323,24 +331,30 @@ fn test_copy_attribution() {
             prompt_count: 1,
             used_plan_mode: false,
             subagent_count: 0,
+            label: None,
         },
         prompts: vec![PromptInfo {
             index: 0,
             text: "Test copy functionality".to_string(),
             timestamp: "2026-01-30T10:00:00Z".to_string(),
             affected_files: vec!["test.rs".to_string()],
+            hash: None,
+            privacy: None,
+            tool_calls: Vec::new(),
         }],
         files: vec![FileAttributionResult {
             path: "test.rs".to_string(),
             lines: vec![LineAttribution {
                 line_number: 1,
                 content: "fn test() {}".to_string(),
+                content_hash: None,
                 source: LineSource::AI {
                     edit_id: "e1".to_string(),
                 },
                 edit_id: Some("e1".to_string()),
                 prompt_index: Some(0),
                 confidence: 1.0,
+                timestamp: None,
             }],
             summary: AttributionSummary {
                 total_lines: 1,
@@ -350,6 +364,10 @@ fn test_copy_attribution() {
                 original_lines: 0,
                 unknown_lines: 0,
             },
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
         }],
     };
 
@@ -385,10 +403,14 @@ fn test_notes_roundtrip() {
     let dir = TempDir::new().unwrap();
     let repo = Repository::init(dir.path()).unwrap();
 
-    // Create initial commit
+    // Create initial commit with the attributed file
     {
+        fs::write(dir.path().join("test.rs"), "fn test() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("test.rs")).unwrap();
+        index.write().unwrap();
         let sig = Signature::now("Test", "test@test.com").unwrap();
-        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree_id = index.write_tree().unwrap();
         let tree = repo.find_tree(tree_id).unwrap();
         repo.commit(Some("HEAD"), &sig, &sig, "Initial", &tree, &[])
             .unwrap();
@@ -406,24 +428,30 @@ fn test_notes_roundtrip() {
             prompt_count: 1,
             used_plan_mode: false,
             subagent_count: 0,
+            label: None,
         },
         prompts: vec![PromptInfo {
             index: 0,
             text: "Create test function".to_string(),
             timestamp: "2026-01-30T10:00:00Z".to_string(),
             affected_files: vec!["test.rs".to_string()],
+            hash: None,
+            privacy: None,
+            tool_calls: Vec::new(),
         }],
         files: vec![FileAttributionResult {
             path: "test.rs".to_string(),
             lines: vec![LineAttribution {
                 line_number: 1,
                 content: "fn test() {}".to_string(),
+                content_hash: None,
                 source: LineSource::AI {
                     edit_id: "e1".to_string(),
                 },
                 edit_id: Some("e1".to_string()),
                 prompt_index: Some(0),
                 confidence: 1.0,
+                timestamp: None,
             }],
             summary: AttributionSummary {
                 total_lines: 1,
@@ -433,6 +461,10 @@ fn test_notes_roundtrip() {
                 original_lines: 0,
                 unknown_lines: 0,
             },
+            binary: None,
+            blocks: None,
+            analysis_warning: None,
+            secret_findings: Vec::new(),
         }],
     };
 
@@ -441,9 +473,16 @@ fn test_notes_roundtrip() {
 
     // Fetch and verify
     let fetched = store.fetch_attribution(head.id()).unwrap().unwrap();
-    assert_eq!(fetched.version, 2);
+    assert_eq!(fetched.version, whogitit::core::attribution::SCHEMA_VERSION);
     assert_eq!(fetched.session.session_id, "test-session");
     assert_eq!(fetched.files.len(), 1);
     assert_eq!(fetched.prompts.len(), 1);
     assert_eq!(fetched.prompts[0].text, "Create test function");
+
+    // Line content is stored as a hash and restored from the commit's blob
+    let note = repo
+        .find_note(Some(whogitit::storage::notes::NOTES_REF), head.id())
+        .unwrap();
+    assert!(!note.message().unwrap().contains("fn test()"));
+    assert_eq!(fetched.files[0].lines[0].content, "fn test() {}");
 }